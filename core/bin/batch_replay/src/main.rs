@@ -0,0 +1,129 @@
+use std::process;
+
+use anyhow::Context as _;
+use clap::{Parser, ValueEnum};
+use zksync_config::configs::ObservabilityConfig;
+use zksync_dal::{ConnectionPool, Core};
+use zksync_env_config::FromEnv;
+use zksync_types::{
+    url::SensitiveUrl, vm::FastVmMode, L1BatchNumber, L2ChainId, ProtocolVersionId,
+};
+
+use crate::replay::{replay_batch, ReplayParams};
+
+mod replay;
+mod report;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum VmMode {
+    /// Run only the legacy VM.
+    Old,
+    /// Run only the fast VM.
+    New,
+    /// Run both VMs and compare their outputs for each transaction. The first divergence between VMs
+    /// is recorded in the report.
+    Shadow,
+}
+
+impl From<VmMode> for FastVmMode {
+    fn from(mode: VmMode) -> Self {
+        match mode {
+            VmMode::Old => Self::Old,
+            VmMode::New => Self::New,
+            VmMode::Shadow => Self::Shadow,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Replays a sealed L1 batch with the chosen VM and reports divergences from the sealed data",
+    long_about = None
+)]
+struct Cli {
+    /// Number of the L1 batch to replay.
+    #[arg(long = "l1-batch")]
+    l1_batch: u32,
+    /// L2 chain ID.
+    #[arg(long)]
+    chain_id: u64,
+    /// VM mode to replay the batch with.
+    #[arg(long, value_enum, default_value_t = VmMode::Old)]
+    vm_mode: VmMode,
+    /// Overrides the protocol version of the batch, which determines the `multivm` VM version used for the replay.
+    /// Base system contracts are not changed.
+    #[arg(long)]
+    protocol_version: Option<u16>,
+    /// PostgreSQL connection string. If not specified, the `DATABASE_URL` env variable is used.
+    #[arg(long)]
+    database_url: Option<String>,
+    /// Outputs the report as JSON, so that it is machine-readable.
+    #[arg(long)]
+    json: bool,
+}
+
+impl Cli {
+    async fn run(self) -> anyhow::Result<bool> {
+        let database_url = match self.database_url {
+            Some(url) => url,
+            None => std::env::var("DATABASE_URL")
+                .context("Postgres URL must be specified via `--database-url` or `DATABASE_URL` env variable")?,
+        };
+        let database_url: SensitiveUrl = database_url.parse().context("invalid Postgres URL")?;
+        let chain_id = L2ChainId::new(self.chain_id).map_err(|err| anyhow::anyhow!(err))?;
+        let protocol_version = self
+            .protocol_version
+            .map(ProtocolVersionId::try_from)
+            .transpose()
+            .context("invalid protocol version")?;
+        let params = ReplayParams {
+            vm_mode: self.vm_mode.into(),
+            protocol_version,
+        };
+
+        // We need at most 2 connections: one for loading batch data, and one for VM storage
+        let pool = ConnectionPool::<Core>::builder(database_url, 2)
+            .build()
+            .await
+            .context("failed connecting to Postgres")?;
+        let report = replay_batch(&pool, chain_id, L1BatchNumber(self.l1_batch), params).await?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else if report.is_consistent() {
+            println!(
+                "L1 batch #{} ({} transactions) replayed with {:?} and VM mode {:?}; no divergences found",
+                report.l1_batch_number, report.tx_count, report.protocol_version, report.vm_mode
+            );
+        } else {
+            println!(
+                "L1 batch #{} ({} transactions) replayed with {:?} and VM mode {:?}; {} divergence(s) found:",
+                report.l1_batch_number,
+                report.tx_count,
+                report.protocol_version,
+                report.vm_mode,
+                report.divergences.len()
+            );
+            for divergence in &report.divergences {
+                println!("{}", serde_json::to_string(divergence)?);
+            }
+        }
+        Ok(report.is_consistent())
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let observability_guard = observability_config.install()?;
+
+    let is_consistent = Cli::parse().run().await?;
+    if !is_consistent {
+        drop(observability_guard);
+        process::exit(1);
+    }
+    Ok(())
+}
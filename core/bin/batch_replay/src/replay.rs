@@ -0,0 +1,219 @@
+//! Replaying a sealed L1 batch from Postgres.
+
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context as _;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_multivm::interface::{utils::DivergenceHandler, ExecutionResult, L2BlockEnv};
+use zksync_state::OwnedStorage;
+use zksync_types::{
+    vm::FastVmMode, L1BatchNumber, L2ChainId, ProtocolVersionId, Transaction, U256, U64,
+};
+use zksync_vm_executor::{
+    batch::MainBatchExecutorFactory, interface::BatchExecutorFactory,
+    storage::L1BatchParamsProvider,
+};
+
+use crate::report::{
+    diff_events, diff_pubdata, diff_storage_writes, diff_tx_outcomes, normalize_storage_writes,
+    Divergence, ReplayReport, TxOutcome,
+};
+
+/// Parameters of the VM used to replay a batch.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReplayParams {
+    pub vm_mode: FastVmMode,
+    /// If set, overrides the protocol version of the replayed batch. This selects the VM version
+    /// in `multivm`, but doesn't change the base system contracts.
+    pub protocol_version: Option<ProtocolVersionId>,
+}
+
+/// Replays the specified L1 batch and compares the results with the sealed batch data in Postgres.
+pub(crate) async fn replay_batch(
+    pool: &ConnectionPool<Core>,
+    chain_id: L2ChainId,
+    l1_batch_number: L1BatchNumber,
+    params: ReplayParams,
+) -> anyhow::Result<ReplayReport> {
+    anyhow::ensure!(
+        l1_batch_number > L1BatchNumber(0),
+        "genesis L1 batch cannot be replayed"
+    );
+
+    let mut conn = pool.connection_tagged("batch_replay").await?;
+    let l1_batch_params_provider = L1BatchParamsProvider::new(&mut conn)
+        .await
+        .context("failed initializing L1 batch params provider")?;
+    let (mut system_env, l1_batch_env, pubdata_params) = l1_batch_params_provider
+        // `validation_computational_gas_limit` is only relevant when rejecting txs, but we
+        // are re-executing so none of them should be rejected
+        .load_l1_batch_env(&mut conn, l1_batch_number, u32::MAX, chain_id)
+        .await?
+        .with_context(|| {
+            format!("L1 batch #{l1_batch_number} is not sealed or its data is pruned")
+        })?;
+    if let Some(protocol_version) = params.protocol_version {
+        tracing::info!(
+            "Overriding protocol version for L1 batch #{l1_batch_number}: {:?} -> {protocol_version:?}",
+            system_env.version
+        );
+        system_env.version = protocol_version;
+    }
+    let protocol_version = system_env.version;
+
+    let l2_blocks = conn
+        .transactions_dal()
+        .get_l2_blocks_to_execute_for_l1_batch(l1_batch_number)
+        .await?;
+    let header = conn
+        .blocks_dal()
+        .get_l1_batch_header(l1_batch_number)
+        .await?
+        .with_context(|| format!("L1 batch #{l1_batch_number} header is missing"))?;
+    let sealed_writes = conn
+        .storage_logs_dal()
+        .get_touched_slots_for_l1_batch(l1_batch_number)
+        .await?;
+    let sealed_events = conn
+        .events_dal()
+        .get_vm_events_for_l1_batch(l1_batch_number)
+        .await?
+        .with_context(|| format!("events for L1 batch #{l1_batch_number} are missing"))?;
+    let tx_hashes: Vec<_> = l2_blocks
+        .iter()
+        .flat_map(|block| block.txs.iter().map(Transaction::hash))
+        .collect();
+    let receipts = conn
+        .transactions_web3_dal()
+        .get_transaction_receipts(&tx_hashes)
+        .await?;
+    drop(conn);
+
+    let sealed_outcomes: Vec<_> = receipts
+        .into_iter()
+        .filter_map(|receipt| {
+            let receipt = receipt.inner;
+            Some(TxOutcome {
+                hash: receipt.transaction_hash,
+                success: receipt.status == U64::one(),
+                gas_used: receipt.gas_used?,
+            })
+        })
+        .collect();
+
+    tracing::info!(
+        "Replaying L1 batch #{l1_batch_number} ({} transactions in {} L2 blocks) with protocol version {protocol_version:?}, VM mode {:?}",
+        tx_hashes.len(),
+        l2_blocks.len(),
+        params.vm_mode
+    );
+    let storage_conn = pool.connection_tagged("batch_replay").await?;
+    let storage = OwnedStorage::postgres(storage_conn, l1_batch_number - 1).await?;
+    let mut executor_factory = MainBatchExecutorFactory::<()>::new(true);
+    executor_factory.set_fast_vm_mode(params.vm_mode);
+    // By default, VM divergences panic; we record them in the report instead.
+    let vm_divergences = Arc::<Mutex<Vec<Divergence>>>::default();
+    let vm_divergences_for_handler = vm_divergences.clone();
+    executor_factory.set_divergence_handler(DivergenceHandler::new(move |errors, _| {
+        let divergence = Divergence::Vm {
+            message: errors.to_string(),
+        };
+        vm_divergences_for_handler
+            .lock()
+            .expect("VM divergences are poisoned")
+            .push(divergence);
+    }));
+    let mut executor =
+        executor_factory.init_batch(storage.into(), l1_batch_env, system_env, pubdata_params);
+
+    let mut replayed_outcomes = Vec::with_capacity(tx_hashes.len());
+    let mut replayed_events = vec![];
+    let mut halted_txs = vec![];
+    for (i, l2_block) in l2_blocks.into_iter().enumerate() {
+        if i > 0 {
+            // First L2 block in every batch is already preloaded
+            let block_env = L2BlockEnv::from_l2_block_data(&l2_block);
+            executor.start_next_l2_block(block_env).await?;
+        }
+
+        for tx in l2_block.txs {
+            let tx_hash = tx.hash();
+            let gas_limit = tx.gas_limit();
+            let result = executor
+                .execute_tx(tx)
+                .await
+                .with_context(|| format!("failed executing transaction {tx_hash:?}"))?;
+            if let ExecutionResult::Halt { reason } = &result.tx_result.result {
+                // Sealed transactions are never halted, so this is a divergence on its own. We roll back
+                // the transaction (as the state keeper would do) and continue to get a full report.
+                tracing::warn!("Transaction {tx_hash:?} was halted during replay: {reason}");
+                halted_txs.push(Divergence::TxHalted {
+                    tx_hash,
+                    reason: reason.to_string(),
+                });
+                executor.rollback_last_tx().await?;
+                continue;
+            }
+            replayed_events.extend(result.tx_result.logs.events.iter().cloned());
+            replayed_outcomes.push(TxOutcome {
+                hash: tx_hash,
+                success: !result.tx_result.result.is_failed(),
+                gas_used: gas_limit - U256::from(result.tx_result.refunds.gas_refunded),
+            });
+        }
+    }
+
+    let (finished_batch, _) = executor
+        .finish_batch()
+        .await
+        .context("failed executing batch tip")?;
+    replayed_events.extend(finished_batch.block_tip_execution_result.logs.events);
+    let replayed_writes: Vec<_> = finished_batch
+        .final_execution_state
+        .deduplicated_storage_logs
+        .iter()
+        .filter(|log| log.is_write())
+        .map(|log| (log.key.hashed_key(), log.value))
+        .collect();
+
+    // Sealed and replayed writes are normalized in the same way, so that no-op writes and writes overwritten
+    // within the batch don't lead to false divergences.
+    let touched_keys: Vec<_> = sealed_writes
+        .keys()
+        .copied()
+        .chain(replayed_writes.iter().map(|(key, _)| *key))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let previous_values = pool
+        .connection_tagged("batch_replay")
+        .await?
+        .storage_logs_dal()
+        .get_previous_storage_values(&touched_keys, l1_batch_number)
+        .await?;
+    let sealed_writes = normalize_storage_writes(sealed_writes, &previous_values);
+    let replayed_writes = normalize_storage_writes(replayed_writes, &previous_values);
+
+    let mut divergences = halted_txs;
+    divergences.extend(std::mem::take(
+        &mut *vm_divergences.lock().expect("VM divergences are poisoned"),
+    ));
+    divergences.extend(diff_storage_writes(&sealed_writes, &replayed_writes));
+    divergences.extend(diff_events(&sealed_events, &replayed_events));
+    divergences.extend(diff_tx_outcomes(&sealed_outcomes, &replayed_outcomes));
+    divergences.extend(diff_pubdata(
+        header.pubdata_input.as_deref(),
+        finished_batch.pubdata_input.as_deref(),
+    ));
+
+    Ok(ReplayReport {
+        l1_batch_number,
+        protocol_version,
+        vm_mode: params.vm_mode,
+        tx_count: tx_hashes.len(),
+        divergences,
+    })
+}
@@ -0,0 +1,367 @@
+//! Divergence report comparing replayed batch execution with the sealed batch data.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+use zksync_multivm::interface::VmEvent;
+use zksync_types::{vm::FastVmMode, web3, Address, L1BatchNumber, ProtocolVersionId, H256, U256};
+
+/// Event data included into a divergence report.
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct EventSummary {
+    address: Address,
+    topics: Vec<H256>,
+    data: web3::Bytes,
+}
+
+impl From<&VmEvent> for EventSummary {
+    fn from(event: &VmEvent) -> Self {
+        Self {
+            address: event.address,
+            topics: event.indexed_topics.clone(),
+            data: web3::Bytes(event.value.clone()),
+        }
+    }
+}
+
+/// Single divergence between sealed and replayed data.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum Divergence {
+    /// Final value of a storage slot written in the batch differs.
+    StorageWrite {
+        hashed_key: H256,
+        sealed: Option<H256>,
+        replayed: Option<H256>,
+    },
+    /// Event emitted in the batch differs.
+    Event {
+        index: usize,
+        sealed: Option<EventSummary>,
+        replayed: Option<EventSummary>,
+    },
+    /// Transaction was halted during replay (i.e., it would be rejected from the batch).
+    TxHalted { tx_hash: H256, reason: String },
+    /// Transaction status (success / failure) differs.
+    TxStatus {
+        tx_hash: H256,
+        sealed_success: bool,
+        replayed_success: bool,
+    },
+    /// Gas used by a transaction differs.
+    TxGasUsed {
+        tx_hash: H256,
+        sealed: U256,
+        replayed: U256,
+    },
+    /// Main and shadow VMs diverged (only reported in the shadow VM mode). The shadow VM is dropped
+    /// after the first divergence, so at most one such divergence is reported per batch.
+    Vm { message: String },
+    /// Pubdata published for the batch differs.
+    Pubdata {
+        sealed_len: Option<usize>,
+        replayed_len: Option<usize>,
+        first_mismatch_offset: Option<usize>,
+    },
+}
+
+/// Outcome of executing a single transaction, either sealed or replayed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TxOutcome {
+    pub hash: H256,
+    pub success: bool,
+    pub gas_used: U256,
+}
+
+/// Structured report produced by replaying an L1 batch.
+#[derive(Debug, Serialize)]
+pub(crate) struct ReplayReport {
+    pub l1_batch_number: L1BatchNumber,
+    pub protocol_version: ProtocolVersionId,
+    pub vm_mode: FastVmMode,
+    pub tx_count: usize,
+    pub divergences: Vec<Divergence>,
+}
+
+impl ReplayReport {
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// Normalizes storage writes so that sealed and replayed writes can be compared: only the final value
+/// for each slot is retained, and writes not changing the slot value compared to the previous L1 batch are dropped.
+/// Writes are expected to be provided in their execution order.
+pub(crate) fn normalize_storage_writes(
+    writes: impl IntoIterator<Item = (H256, H256)>,
+    previous_values: &HashMap<H256, Option<H256>>,
+) -> HashMap<H256, H256> {
+    let mut final_values: HashMap<_, _> = writes.into_iter().collect();
+    final_values.retain(|hashed_key, value| {
+        let previous_value = previous_values
+            .get(hashed_key)
+            .copied()
+            .flatten()
+            .unwrap_or_default();
+        *value != previous_value
+    });
+    final_values
+}
+
+/// Compares final values of written storage slots. Divergences are ordered by the hashed key.
+pub(crate) fn diff_storage_writes(
+    sealed: &HashMap<H256, H256>,
+    replayed: &HashMap<H256, H256>,
+) -> Vec<Divergence> {
+    let all_keys: BTreeSet<_> = sealed.keys().chain(replayed.keys()).copied().collect();
+    all_keys
+        .into_iter()
+        .filter_map(|hashed_key| {
+            let sealed = sealed.get(&hashed_key).copied();
+            let replayed = replayed.get(&hashed_key).copied();
+            (sealed != replayed).then_some(Divergence::StorageWrite {
+                hashed_key,
+                sealed,
+                replayed,
+            })
+        })
+        .collect()
+}
+
+/// Compares events emitted in the batch in their emission order. Event locations are not compared
+/// since they are not reliably restored from Postgres.
+pub(crate) fn diff_events(sealed: &[VmEvent], replayed: &[VmEvent]) -> Vec<Divergence> {
+    let len = sealed.len().max(replayed.len());
+    (0..len)
+        .filter_map(|index| {
+            let sealed = sealed.get(index).map(EventSummary::from);
+            let replayed = replayed.get(index).map(EventSummary::from);
+            (sealed != replayed).then_some(Divergence::Event {
+                index,
+                sealed,
+                replayed,
+            })
+        })
+        .collect()
+}
+
+/// Compares transaction outcomes. Transactions are matched by hash; replayed transactions are always
+/// the same as sealed ones, so missing sealed outcomes (e.g., because of pruned receipts) are skipped.
+pub(crate) fn diff_tx_outcomes(sealed: &[TxOutcome], replayed: &[TxOutcome]) -> Vec<Divergence> {
+    let sealed: HashMap<_, _> = sealed
+        .iter()
+        .map(|outcome| (outcome.hash, outcome))
+        .collect();
+    let mut divergences = vec![];
+    for replayed in replayed {
+        let Some(sealed) = sealed.get(&replayed.hash) else {
+            continue;
+        };
+        if sealed.success != replayed.success {
+            divergences.push(Divergence::TxStatus {
+                tx_hash: replayed.hash,
+                sealed_success: sealed.success,
+                replayed_success: replayed.success,
+            });
+        }
+        if sealed.gas_used != replayed.gas_used {
+            divergences.push(Divergence::TxGasUsed {
+                tx_hash: replayed.hash,
+                sealed: sealed.gas_used,
+                replayed: replayed.gas_used,
+            });
+        }
+    }
+    divergences
+}
+
+/// Compares pubdata published for the batch.
+pub(crate) fn diff_pubdata(sealed: Option<&[u8]>, replayed: Option<&[u8]>) -> Option<Divergence> {
+    if sealed == replayed {
+        return None;
+    }
+    let first_mismatch_offset = match (sealed, replayed) {
+        (Some(sealed), Some(replayed)) => Some(
+            sealed
+                .iter()
+                .zip(replayed)
+                .position(|(sealed, replayed)| sealed != replayed)
+                .unwrap_or_else(|| sealed.len().min(replayed.len())),
+        ),
+        _ => None,
+    };
+    Some(Divergence::Pubdata {
+        sealed_len: sealed.map(<[u8]>::len),
+        replayed_len: replayed.map(<[u8]>::len),
+        first_mismatch_offset,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(address: u8, value: &[u8]) -> VmEvent {
+        VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(address),
+            indexed_topics: vec![H256::repeat_byte(1)],
+            value: value.to_vec(),
+        }
+    }
+
+    #[test]
+    fn diffing_storage_writes() {
+        let sealed = HashMap::from([
+            (H256::repeat_byte(1), H256::repeat_byte(0xaa)),
+            (H256::repeat_byte(2), H256::repeat_byte(0xbb)),
+        ]);
+        let replayed = HashMap::from([
+            (H256::repeat_byte(1), H256::repeat_byte(0xaa)),
+            (H256::repeat_byte(2), H256::repeat_byte(0xcc)),
+            (H256::repeat_byte(3), H256::repeat_byte(0xdd)),
+        ]);
+
+        let divergences = diff_storage_writes(&sealed, &replayed);
+        assert_eq!(
+            divergences,
+            [
+                Divergence::StorageWrite {
+                    hashed_key: H256::repeat_byte(2),
+                    sealed: Some(H256::repeat_byte(0xbb)),
+                    replayed: Some(H256::repeat_byte(0xcc)),
+                },
+                Divergence::StorageWrite {
+                    hashed_key: H256::repeat_byte(3),
+                    sealed: None,
+                    replayed: Some(H256::repeat_byte(0xdd)),
+                },
+            ]
+        );
+        assert!(diff_storage_writes(&sealed, &sealed).is_empty());
+    }
+
+    #[test]
+    fn normalizing_storage_writes() {
+        let previous_values = HashMap::from([
+            (H256::repeat_byte(1), Some(H256::repeat_byte(0xaa))),
+            (H256::repeat_byte(2), None),
+        ]);
+        let writes = [
+            // Overwritten within the batch
+            (H256::repeat_byte(1), H256::repeat_byte(0xbb)),
+            (H256::repeat_byte(1), H256::repeat_byte(0xaa)),
+            // Zero write to a slot that didn't exist before
+            (H256::repeat_byte(2), H256::zero()),
+            (H256::repeat_byte(3), H256::repeat_byte(0xcc)),
+        ];
+        let normalized = normalize_storage_writes(writes, &previous_values);
+        assert_eq!(
+            normalized,
+            HashMap::from([(H256::repeat_byte(3), H256::repeat_byte(0xcc))])
+        );
+    }
+
+    #[test]
+    fn diffing_events() {
+        let sealed = [event(1, b"test"), event(2, b"other")];
+        let mut replayed = [event(1, b"test"), event(2, b"other"), event(3, &[])];
+        // Locations must be ignored.
+        replayed[0].location = (L1BatchNumber(1), 5);
+
+        let divergences = diff_events(&sealed, &replayed);
+        assert_eq!(divergences.len(), 1);
+        assert_matches_event(&divergences[0], 2, false, true);
+
+        replayed[1].value = b"changed".to_vec();
+        let divergences = diff_events(&sealed, &replayed[..2]);
+        assert_eq!(divergences.len(), 1);
+        assert_matches_event(&divergences[0], 1, true, true);
+    }
+
+    fn assert_matches_event(
+        divergence: &Divergence,
+        idx: usize,
+        has_sealed: bool,
+        has_replayed: bool,
+    ) {
+        let Divergence::Event {
+            index,
+            sealed,
+            replayed,
+        } = divergence
+        else {
+            panic!("unexpected divergence: {divergence:?}");
+        };
+        assert_eq!(*index, idx);
+        assert_eq!(sealed.is_some(), has_sealed);
+        assert_eq!(replayed.is_some(), has_replayed);
+    }
+
+    #[test]
+    fn diffing_tx_outcomes() {
+        let sealed = [
+            TxOutcome {
+                hash: H256::repeat_byte(1),
+                success: true,
+                gas_used: 100_000.into(),
+            },
+            TxOutcome {
+                hash: H256::repeat_byte(2),
+                success: true,
+                gas_used: 200_000.into(),
+            },
+        ];
+        let mut replayed = sealed;
+        assert!(diff_tx_outcomes(&sealed, &replayed).is_empty());
+
+        replayed[1].success = false;
+        replayed[1].gas_used = 150_000.into();
+        let divergences = diff_tx_outcomes(&sealed, &replayed);
+        assert_eq!(
+            divergences,
+            [
+                Divergence::TxStatus {
+                    tx_hash: H256::repeat_byte(2),
+                    sealed_success: true,
+                    replayed_success: false,
+                },
+                Divergence::TxGasUsed {
+                    tx_hash: H256::repeat_byte(2),
+                    sealed: 200_000.into(),
+                    replayed: 150_000.into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diffing_pubdata() {
+        assert_eq!(diff_pubdata(Some(&b"test"[..]), Some(&b"test"[..])), None);
+        assert_eq!(diff_pubdata(None, None), None);
+        assert_eq!(
+            diff_pubdata(Some(&b"test"[..]), Some(&b"text"[..])),
+            Some(Divergence::Pubdata {
+                sealed_len: Some(4),
+                replayed_len: Some(4),
+                first_mismatch_offset: Some(2),
+            })
+        );
+        assert_eq!(
+            diff_pubdata(Some(&b"test"[..]), Some(&b"tes"[..])),
+            Some(Divergence::Pubdata {
+                sealed_len: Some(4),
+                replayed_len: Some(3),
+                first_mismatch_offset: Some(3),
+            })
+        );
+        assert_eq!(
+            diff_pubdata(None, Some(&b"test"[..])),
+            Some(Divergence::Pubdata {
+                sealed_len: None,
+                replayed_len: Some(4),
+                first_mismatch_offset: None,
+            })
+        );
+    }
+}
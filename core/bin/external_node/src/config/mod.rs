@@ -370,6 +370,11 @@ pub(crate) struct OptionalENConfig {
     /// Time limit in milliseconds to abort a health check and return "not ready" status for the corresponding component.
     /// If not specified, the default value in the health check crate will be used.
     healthcheck_hard_time_limit_ms: Option<u64>,
+    /// Maximum number of health transitions retained for each component.
+    /// If not specified, the default value in the health check crate will be used.
+    healthcheck_history_capacity: Option<usize>,
+    /// URL to which notifications about component health transitioning to `affected` or `shut_down` are POSTed.
+    healthcheck_webhook_url: Option<String>,
 
     // Gas estimation config
     /// The factor by which to scale the gas limit.
@@ -474,6 +479,10 @@ pub(crate) struct OptionalENConfig {
     /// if I/O capacity of your infra is high, you may increase concurrency to speed up Postgres recovery.
     #[serde(default = "OptionalENConfig::default_snapshots_recovery_postgres_max_concurrency")]
     pub snapshots_recovery_postgres_max_concurrency: NonZeroUsize,
+    /// Whether to import transaction history (L2 block headers, transactions, receipts and events) if it's included
+    /// into the snapshot. Allows the node to serve `eth_getLogs` and receipts for the trailing L1 batches before the snapshot.
+    #[serde(default)]
+    pub snapshots_recovery_postgres_import_history: bool,
 
     #[serde(default)]
     pub snapshots_recovery_object_store: Option<ObjectStoreConfig>,
@@ -496,6 +505,12 @@ pub(crate) struct OptionalENConfig {
     /// If set to 0, L1 batches will not be retained based on their timestamp. The default value is 7 days.
     #[serde(default = "OptionalENConfig::default_pruning_data_retention_sec")]
     pruning_data_retention_sec: u64,
+    /// Number of L1 batches already pruned in Postgres that the Merkle tree will still retain. Storage values
+    /// for these batches can be read together with proofs via `zks_getProof`; other methods (e.g., `eth_getStorageAt`
+    /// or `eth_call`) are not served for pruned blocks regardless of this value. The default value is 0, i.e.,
+    /// the tree is pruned in lockstep with Postgres.
+    #[serde(default)]
+    pruning_tree_retained_l1_batches: u32,
     /// Gateway RPC URL, needed for operating during migration.
     pub gateway_url: Option<SensitiveUrl>,
     /// Interval for bridge addresses refreshing in seconds.
@@ -614,6 +629,14 @@ impl OptionalENConfig {
                 general_config.api_config,
                 healthcheck.hard_time_limit_ms
             ),
+            healthcheck_history_capacity: load_config!(
+                general_config.api_config,
+                healthcheck.history_capacity
+            ),
+            healthcheck_webhook_url: load_config!(
+                general_config.api_config,
+                healthcheck.webhook_url
+            ),
             estimate_gas_scale_factor: load_config_or_default!(
                 general_config.api_config,
                 web3_json_rpc.estimate_gas_scale_factor,
@@ -693,6 +716,10 @@ impl OptionalENConfig {
                 postgres.max_concurrency,
                 default_snapshots_recovery_postgres_max_concurrency
             ),
+            snapshots_recovery_postgres_import_history: general_config
+                .snapshot_recovery
+                .as_ref()
+                .map_or(false, |config| config.postgres.import_history),
             pruning_enabled: general_config
                 .pruning
                 .as_ref()
@@ -717,6 +744,11 @@ impl OptionalENConfig {
                 data_retention_sec,
                 default_pruning_data_retention_sec
             ),
+            pruning_tree_retained_l1_batches: general_config
+                .pruning
+                .as_ref()
+                .and_then(|a| a.tree_retained_l1_batches)
+                .unwrap_or_default(),
             protective_reads_persistence_enabled: general_config
                 .db_config
                 .as_ref()
@@ -970,6 +1002,14 @@ impl OptionalENConfig {
             .map(Duration::from_millis)
     }
 
+    pub fn healthcheck_history_capacity(&self) -> Option<usize> {
+        self.healthcheck_history_capacity
+    }
+
+    pub fn healthcheck_webhook_url(&self) -> Option<&str> {
+        self.healthcheck_webhook_url.as_deref()
+    }
+
     pub fn mempool_cache_update_interval(&self) -> Duration {
         Duration::from_millis(self.mempool_cache_update_interval_ms)
     }
@@ -978,6 +1018,10 @@ impl OptionalENConfig {
         Duration::from_secs(self.pruning_removal_delay_sec.get())
     }
 
+    pub fn pruning_tree_retained_l1_batches(&self) -> u32 {
+        self.pruning_tree_retained_l1_batches
+    }
+
     pub fn pruning_data_retention(&self) -> Duration {
         Duration::from_secs(self.pruning_data_retention_sec)
     }
@@ -3,6 +3,7 @@ use std::{collections::HashSet, str::FromStr};
 use anyhow::Context as _;
 use clap::Parser;
 use node_builder::ExternalNodeBuilder;
+use zksync_node_framework::service::WiringGraphFormat;
 use zksync_web3_decl::client::{Client, DynClient, L2};
 
 use crate::config::{generate_consensus_secrets, ExternalNodeConfig};
@@ -58,6 +59,10 @@ struct Cli {
         requires = "enable_consensus"
     )]
     consensus_path: Option<std::path::PathBuf>,
+    /// Wire the node with the provided list of the components without running any tasks, print the dependency graph
+    /// among wiring layers, resources and tasks in the specified format (`dot` or `json`) and exit.
+    #[arg(long, value_name = "FORMAT")]
+    print_wiring: Option<WiringGraphFormat>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Hash, Eq)]
@@ -175,6 +180,12 @@ fn main() -> anyhow::Result<()> {
 
     let node = ExternalNodeBuilder::on_runtime(runtime, config)
         .build(opt.components.0.into_iter().collect())?;
+    if let Some(format) = opt.print_wiring {
+        let graph = node.dry_run();
+        println!("{}", graph.render(format));
+        anyhow::ensure!(!graph.has_errors(), "Some wiring layers have failed");
+        return Ok(());
+    }
     node.run(guard)?;
     anyhow::Ok(())
 }
@@ -194,6 +194,12 @@ impl ExternalNodeBuilder {
                 .optional
                 .healthcheck_hard_time_limit()
                 .map(|d| d.as_millis() as u64),
+            history_capacity: self.config.optional.healthcheck_history_capacity(),
+            webhook_url: self
+                .config
+                .optional
+                .healthcheck_webhook_url()
+                .map(str::to_owned),
         };
         self.node.add_layer(HealthCheckLayer(healthcheck_config));
         Ok(self)
@@ -439,7 +445,11 @@ impl ExternalNodeBuilder {
 
         // Add tree pruning if needed.
         if self.config.optional.pruning_enabled {
-            layer = layer.with_pruning_config(self.config.optional.pruning_removal_delay());
+            layer = layer
+                .with_pruning_config(self.config.optional.pruning_removal_delay())
+                .with_tree_retained_l1_batches(
+                    self.config.optional.pruning_tree_retained_l1_batches(),
+                );
         }
 
         self.node.add_layer(layer);
@@ -611,6 +621,7 @@ impl ExternalNodeBuilder {
                     drop_storage_key_preimages: config
                         .experimental
                         .snapshots_recovery_drop_storage_key_preimages,
+                    import_history: config.optional.snapshots_recovery_postgres_import_history,
                     object_store_config: config.optional.snapshots_recovery_object_store.clone(),
                 });
         self.node.add_layer(ExternalNodeInitStrategyLayer {
@@ -1,4 +1,7 @@
-use std::{path::Path, time::Instant};
+use std::{
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use anyhow::Context as _;
 use clap::Parser;
@@ -20,15 +23,35 @@ struct Cli {
     /// applied to it last. If not specified, the latest tree version is checked.
     #[arg(long = "l1-batch")]
     l1_batch: Option<u32>,
+    /// Checks the ZK OS tree instead of the Era tree.
+    #[arg(long)]
+    zk_os: bool,
+    /// Path to the tree RocksDB directory. If not specified, the path from the DB config is used.
+    #[arg(long)]
+    db_path: Option<PathBuf>,
+    /// Repairs damaged internal nodes of the checked tree version by recomputing them from leaves
+    /// if the tree is inconsistent. Only supported for the ZK OS tree.
+    #[arg(long, requires = "zk_os")]
+    repair: bool,
 }
 
 impl Cli {
     fn run(self, config: &DBConfig) -> anyhow::Result<()> {
-        let db_path = &config.merkle_tree.path;
-        tracing::info!("Verifying consistency of Merkle tree at {db_path}");
+        let db_path = self
+            .db_path
+            .clone()
+            .unwrap_or_else(|| config.merkle_tree.path.clone().into());
+        if self.zk_os {
+            self.run_for_zk_os_tree(&db_path)
+        } else {
+            self.run_for_era_tree(&db_path)
+        }
+    }
+
+    fn run_for_era_tree(self, db_path: &Path) -> anyhow::Result<()> {
+        tracing::info!("Verifying consistency of Merkle tree at {db_path:?}");
         let start = Instant::now();
-        let db =
-            RocksDB::new(Path::new(db_path)).context("failed initializing Merkle tree RocksDB")?;
+        let db = RocksDB::new(db_path).context("failed initializing Merkle tree RocksDB")?;
         let tree =
             ZkSyncTree::new_lightweight(db.into()).context("cannot initialize Merkle tree")?;
 
@@ -49,6 +72,43 @@ impl Cli {
         tracing::info!("Merkle tree verified in {:?}", start.elapsed());
         Ok(())
     }
+
+    fn run_for_zk_os_tree(self, db_path: &Path) -> anyhow::Result<()> {
+        tracing::info!("Verifying consistency of ZK OS Merkle tree at {db_path:?}");
+        let start = Instant::now();
+        let db = zk_os_merkle_tree::RocksDBWrapper::new(db_path)
+            .context("failed initializing Merkle tree RocksDB")?;
+        let mut tree =
+            zk_os_merkle_tree::MerkleTree::new(db).context("cannot initialize Merkle tree")?;
+
+        let version = if let Some(number) = self.l1_batch {
+            number.into()
+        } else if let Some(version) = tree.latest_version()? {
+            version
+        } else {
+            tracing::info!("Merkle tree is empty, skipping");
+            return Ok(());
+        };
+
+        tracing::info!("Tree version to check: {version}");
+        let Err(err) = tree.verify_consistency(version) else {
+            tracing::info!("Merkle tree verified in {:?}", start.elapsed());
+            return Ok(());
+        };
+        if !self.repair {
+            return Err(anyhow::Error::from(err).context("Merkle tree is inconsistent"));
+        }
+
+        tracing::warn!("Merkle tree is inconsistent: {err}; repairing");
+        let stats = tree
+            .repair(version)
+            .context("failed repairing Merkle tree")?;
+        tracing::info!(?stats, "Repaired Merkle tree");
+        tree.verify_consistency(version)
+            .context("Merkle tree is inconsistent after repair")?;
+        tracing::info!("Merkle tree repaired and verified in {:?}", start.elapsed());
+        Ok(())
+    }
 }
 
 fn main() -> anyhow::Result<()> {
@@ -0,0 +1,137 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+    time::Instant,
+};
+
+use anyhow::Context as _;
+use clap::{Parser, Subcommand, ValueEnum};
+use zksync_config::configs::ObservabilityConfig;
+use zksync_env_config::FromEnv;
+use zksync_merkle_tree_export::{
+    export_era_tree, export_zk_os_tree, import_era_tree, import_zk_os_tree, ExportReader, TreeKind,
+};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum TreeKindArg {
+    /// Era tree (`zksync_merkle_tree`).
+    Era,
+    /// ZK OS tree (`zk_os_merkle_tree`).
+    ZkOs,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Exports a version of the Merkle tree into a file.
+    Export {
+        /// Kind of the exported tree.
+        #[arg(long, value_enum)]
+        tree: TreeKindArg,
+        /// Path to the tree RocksDB directory.
+        #[arg(long)]
+        db_path: PathBuf,
+        /// Version of the tree to export, expressed as a 0-based L1 batch number applied to it last.
+        /// If not specified, the latest tree version is exported.
+        #[arg(long = "l1-batch")]
+        l1_batch: Option<u32>,
+        /// Path to the output file.
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Imports the Merkle tree from a file into an empty RocksDB directory. The tree kind and version
+    /// are read from the file; the root hash of the imported tree is checked against the file header.
+    Import {
+        /// Path to the tree RocksDB directory. Must not contain a tree.
+        #[arg(long)]
+        db_path: PathBuf,
+        /// Path to the input file.
+        #[arg(long)]
+        input: PathBuf,
+    },
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Merkle tree export / import tool",
+    long_about = None
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+impl Cli {
+    fn run(self) -> anyhow::Result<()> {
+        let start = Instant::now();
+        match self.command {
+            Command::Export {
+                tree,
+                db_path,
+                l1_batch,
+                output,
+            } => {
+                tracing::info!("Exporting {tree:?} Merkle tree at {db_path:?} to {output:?}");
+                let file = File::create(&output)
+                    .with_context(|| format!("failed creating output file {output:?}"))?;
+                let writer = BufWriter::new(file);
+                let header = match tree {
+                    TreeKindArg::Era => {
+                        let mut db = zksync_merkle_tree::RocksDBWrapper::new(&db_path)
+                            .context("failed initializing Merkle tree RocksDB")?;
+                        let version = match l1_batch {
+                            Some(number) => number.into(),
+                            None => zksync_merkle_tree::MerkleTree::new(&mut db)?
+                                .latest_version()
+                                .context("Merkle tree is empty")?,
+                        };
+                        export_era_tree(db, version, writer)?
+                    }
+                    TreeKindArg::ZkOs => {
+                        let mut db = zk_os_merkle_tree::RocksDBWrapper::new(&db_path)
+                            .context("failed initializing Merkle tree RocksDB")?;
+                        let version = match l1_batch {
+                            Some(number) => number.into(),
+                            None => zk_os_merkle_tree::MerkleTree::new(&mut db)?
+                                .latest_version()?
+                                .context("Merkle tree is empty")?,
+                        };
+                        export_zk_os_tree(db, version, writer)?
+                    }
+                };
+                tracing::info!(?header, "Merkle tree exported in {:?}", start.elapsed());
+            }
+
+            Command::Import { db_path, input } => {
+                tracing::info!("Importing Merkle tree from {input:?} to {db_path:?}");
+                let file = File::open(&input)
+                    .with_context(|| format!("failed opening input file {input:?}"))?;
+                let reader = ExportReader::new(BufReader::new(file))?;
+                let header = *reader.header();
+                match header.tree_kind {
+                    TreeKind::Era => {
+                        let db = zksync_merkle_tree::RocksDBWrapper::new(&db_path)
+                            .context("failed initializing Merkle tree RocksDB")?;
+                        import_era_tree(reader, db)?;
+                    }
+                    TreeKind::ZkOs => {
+                        let db = zk_os_merkle_tree::RocksDBWrapper::new(&db_path)
+                            .context("failed initializing Merkle tree RocksDB")?;
+                        import_zk_os_tree(reader, db)?;
+                    }
+                }
+                tracing::info!(?header, "Merkle tree imported in {:?}", start.elapsed());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let _observability_guard = observability_config.install()?;
+    Cli::parse().run()
+}
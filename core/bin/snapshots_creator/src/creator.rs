@@ -1,6 +1,6 @@
 //! [`SnapshotCreator`] and tightly related types.
 
-use std::{sync::Arc, time::Duration};
+use std::{collections::BTreeMap, ops, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use tokio::sync::Semaphore;
@@ -10,13 +10,14 @@ use zksync_object_store::{ObjectStore, StoredObject};
 use zksync_types::{
     snapshots::{
         uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotFactoryDependency,
+        SnapshotHistoryStorageKey, SnapshotL1BatchHistory, SnapshotL2BlockHistory,
         SnapshotMetadata, SnapshotStorageLog, SnapshotStorageLogsChunk,
         SnapshotStorageLogsStorageKey, SnapshotVersion,
     },
     L1BatchNumber, L2BlockNumber,
 };
 
-use crate::metrics::{FactoryDepsStage, StorageChunkStage, METRICS};
+use crate::metrics::{FactoryDepsStage, HistoryStage, StorageChunkStage, METRICS};
 #[cfg(test)]
 use crate::tests::HandleEvent;
 
@@ -25,20 +26,35 @@ use crate::tests::HandleEvent;
 struct SnapshotProgress {
     version: SnapshotVersion,
     l1_batch_number: L1BatchNumber,
+    /// L1 batch of the base snapshot if the snapshot is incremental.
+    base_l1_batch_number: Option<L1BatchNumber>,
     /// `true` if the snapshot is new (i.e., its progress is not recovered from Postgres).
     is_new_snapshot: bool,
     chunk_count: u64,
     remaining_chunk_ids: Vec<u64>,
+    /// Number of trailing L1 batches (ending with `l1_batch_number`) with exported transaction history.
+    history_l1_batch_count: u32,
+    /// Indices of L1 batches in the history window that are not exported yet.
+    remaining_history_indices: Vec<usize>,
 }
 
 impl SnapshotProgress {
-    fn new(version: SnapshotVersion, l1_batch_number: L1BatchNumber, chunk_count: u64) -> Self {
+    fn new(
+        version: SnapshotVersion,
+        l1_batch_number: L1BatchNumber,
+        base_l1_batch_number: Option<L1BatchNumber>,
+        chunk_count: u64,
+        history_l1_batch_count: u32,
+    ) -> Self {
         Self {
             version,
             l1_batch_number,
+            base_l1_batch_number,
             is_new_snapshot: true,
             chunk_count,
             remaining_chunk_ids: (0..chunk_count).collect(),
+            history_l1_batch_count,
+            remaining_history_indices: (0..history_l1_batch_count as usize).collect(),
         }
     }
 
@@ -49,15 +65,28 @@ impl SnapshotProgress {
             .enumerate()
             .filter_map(|(chunk_id, path)| path.is_none().then_some(chunk_id as u64))
             .collect();
+        let remaining_history_indices = snapshot
+            .history_filepaths
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, path)| path.is_none().then_some(idx))
+            .collect();
 
         Self {
             version: snapshot.version,
             l1_batch_number: snapshot.l1_batch_number,
+            base_l1_batch_number: snapshot.base_l1_batch_number,
             is_new_snapshot: false,
             chunk_count: snapshot.storage_logs_filepaths.len() as u64,
             remaining_chunk_ids,
+            history_l1_batch_count: snapshot.history_filepaths.len() as u32,
+            remaining_history_indices,
         }
     }
+
+    fn history_l1_batch(&self, history_index: usize) -> L1BatchNumber {
+        self.l1_batch_number + history_index as u32 + 1 - self.history_l1_batch_count
+    }
 }
 
 /// Creator of a single storage snapshot.
@@ -77,11 +106,36 @@ impl SnapshotCreator {
             .await
     }
 
+    /// Returns the range of L2 blocks covered by the snapshot, i.e. from the genesis (for full snapshots) or from
+    /// the L2 block after the base snapshot (for incremental ones) to the last L2 block in the snapshot L1 batch.
+    async fn get_l2_block_range(
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+        base_l1_batch_number: Option<L1BatchNumber>,
+    ) -> anyhow::Result<ops::RangeInclusive<L2BlockNumber>> {
+        let (_, last_l2_block_number) = conn
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(l1_batch_number)
+            .await?
+            .context("No L2 blocks for L1 batch")?;
+        let first_l2_block_number = if let Some(base_l1_batch_number) = base_l1_batch_number {
+            let (_, last_base_l2_block_number) = conn
+                .blocks_dal()
+                .get_l2_block_range_of_l1_batch(base_l1_batch_number)
+                .await?
+                .context("No L2 blocks for base L1 batch")?;
+            last_base_l2_block_number + 1
+        } else {
+            L2BlockNumber(0)
+        };
+        Ok(first_l2_block_number..=last_l2_block_number)
+    }
+
     async fn process_storage_logs_single_chunk(
         &self,
         semaphore: &Semaphore,
         progress: &SnapshotProgress,
-        l2_block_number: L2BlockNumber,
+        l2_block_range: &ops::RangeInclusive<L2BlockNumber>,
         chunk_id: u64,
     ) -> anyhow::Result<()> {
         let chunk_count = progress.chunk_count;
@@ -104,7 +158,7 @@ impl SnapshotCreator {
                 let logs = conn
                     .snapshots_creator_dal()
                     .get_storage_logs_chunk_with_key_preimages(
-                        l2_block_number,
+                        *l2_block_range.end(),
                         l1_batch_number,
                         hashed_keys_range,
                     )
@@ -121,11 +175,24 @@ impl SnapshotCreator {
                     .await?
             }
             SnapshotVersion::Version1 => {
-                let logs = conn
-                    .snapshots_creator_dal()
-                    .get_storage_logs_chunk(l2_block_number, l1_batch_number, hashed_keys_range)
-                    .await
-                    .context("error fetching storage logs")?;
+                let logs = if progress.base_l1_batch_number.is_some() {
+                    conn.snapshots_creator_dal()
+                        .get_changed_storage_logs_chunk(
+                            l2_block_range.clone(),
+                            l1_batch_number,
+                            hashed_keys_range,
+                        )
+                        .await
+                } else {
+                    conn.snapshots_creator_dal()
+                        .get_storage_logs_chunk(
+                            *l2_block_range.end(),
+                            l1_batch_number,
+                            hashed_keys_range,
+                        )
+                        .await
+                };
+                let logs = logs.context("error fetching storage logs")?;
                 drop(conn);
 
                 let latency = latency.observe();
@@ -186,20 +253,178 @@ impl SnapshotCreator {
         Ok((output_filepath, latency))
     }
 
-    async fn process_factory_deps(
+    async fn process_history_single_l1_batch(
         &self,
-        l2_block_number: L2BlockNumber,
+        semaphore: &Semaphore,
+        progress: &SnapshotProgress,
+        history_index: usize,
+    ) -> anyhow::Result<()> {
+        let l1_batch_number = progress.history_l1_batch(history_index);
+        let _permit = semaphore.acquire().await?;
+        let mut conn = self.connect_to_replica().await?;
+
+        let latency = METRICS.history_processing_duration[&HistoryStage::LoadFromPostgres].start();
+        let history = Self::load_l1_batch_history(&mut conn, l1_batch_number).await?;
+        drop(conn);
+        let latency = latency.observe();
+        tracing::info!(
+            "Loaded history for L1 batch #{l1_batch_number} ({} L2 blocks) from Postgres in {latency:?}",
+            history.l2_blocks.len()
+        );
+
+        let latency = METRICS.history_processing_duration[&HistoryStage::SaveToGcs].start();
+        let key = SnapshotHistoryStorageKey {
+            snapshot_l1_batch_number: progress.l1_batch_number,
+            l1_batch_number,
+        };
+        let filename = self
+            .blob_store
+            .put(key, &history)
+            .await
+            .context("Error storing L1 batch history in blob store")?;
+        let output_filepath_prefix = self
+            .blob_store
+            .get_storage_prefix::<SnapshotL1BatchHistory>();
+        let output_filepath = format!("{output_filepath_prefix}/{filename}");
+        let latency = latency.observe();
+
+        let mut master_conn = self
+            .master_pool
+            .connection_tagged("snapshots_creator")
+            .await?;
+        master_conn
+            .snapshots_dal()
+            .add_history_filepath_for_snapshot(
+                progress.l1_batch_number,
+                history_index,
+                &output_filepath,
+            )
+            .await?;
+
+        METRICS.history_l1_batches_left_to_process.dec_by(1);
+        tracing::info!(
+            "Saved history for L1 batch #{l1_batch_number} in {latency:?} to location: {output_filepath}"
+        );
+        Ok(())
+    }
+
+    async fn load_l1_batch_history(
+        conn: &mut Connection<'_, Core>,
         l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<SnapshotL1BatchHistory> {
+        let header = conn
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} disappeared from Postgres"))?;
+        let (first_l2_block, last_l2_block) = conn
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(l1_batch_number)
+            .await?
+            .with_context(|| format!("No L2 blocks for L1 batch #{l1_batch_number}"))?;
+
+        let mut l2_blocks = BTreeMap::new();
+        for number in first_l2_block.0..=last_l2_block.0 {
+            let number = L2BlockNumber(number);
+            let header = conn
+                .blocks_dal()
+                .get_l2_block_header(number)
+                .await?
+                .with_context(|| format!("L2 block #{number} disappeared from Postgres"))?;
+            let block = SnapshotL2BlockHistory {
+                header,
+                transactions: vec![],
+                events: vec![],
+                user_l2_to_l1_logs: vec![],
+            };
+            l2_blocks.insert(number, block);
+        }
+
+        let transactions = conn
+            .snapshots_creator_dal()
+            .get_l1_batch_transactions(l1_batch_number)
+            .await?;
+        for (number, tx) in transactions {
+            let block = l2_blocks.get_mut(&number).with_context(|| {
+                format!(
+                    "transaction {:?} references unexpected L2 block #{number}",
+                    tx.transaction.hash()
+                )
+            })?;
+            block.transactions.push(tx);
+        }
+
+        for (&number, block) in &mut l2_blocks {
+            if block.transactions.is_empty() {
+                continue;
+            }
+            let traces = conn
+                .blocks_web3_dal()
+                .get_traces_for_l2_block(number)
+                .await?;
+            for (call, meta) in traces {
+                let tx = block
+                    .transactions
+                    .iter_mut()
+                    .find(|tx| tx.transaction.hash() == meta.tx_hash)
+                    .with_context(|| {
+                        format!(
+                            "call trace references unexpected transaction {:?} in L2 block #{number}",
+                            meta.tx_hash
+                        )
+                    })?;
+                let call = serde_json::to_value(call).context("failed serializing call trace")?;
+                tx.call_trace = Some(call);
+            }
+        }
+
+        let l2_block_range = first_l2_block..=last_l2_block;
+        let events = conn
+            .snapshots_creator_dal()
+            .get_events(l2_block_range.clone())
+            .await?;
+        for (number, event) in events {
+            // `unwrap()` is safe since all L2 blocks in the range are inserted above
+            l2_blocks.get_mut(&number).unwrap().events.push(event);
+        }
+        let logs = conn
+            .snapshots_creator_dal()
+            .get_user_l2_to_l1_logs(l2_block_range)
+            .await?;
+        for (number, log) in logs {
+            l2_blocks
+                .get_mut(&number)
+                .unwrap()
+                .user_l2_to_l1_logs
+                .push(log);
+        }
+
+        Ok(SnapshotL1BatchHistory {
+            header,
+            l2_blocks: l2_blocks.into_values().collect(),
+        })
+    }
+
+    async fn process_factory_deps(
+        &self,
+        progress: &SnapshotProgress,
+        l2_block_range: &ops::RangeInclusive<L2BlockNumber>,
     ) -> anyhow::Result<String> {
+        let l1_batch_number = progress.l1_batch_number;
         let mut conn = self.connect_to_replica().await?;
 
         tracing::info!("Loading factory deps from Postgres...");
         let latency =
             METRICS.factory_deps_processing_duration[&FactoryDepsStage::LoadFromPostgres].start();
-        let factory_deps = conn
-            .snapshots_creator_dal()
-            .get_all_factory_deps(l2_block_number)
-            .await?;
+        let factory_deps = if progress.base_l1_batch_number.is_some() {
+            conn.snapshots_creator_dal()
+                .get_factory_deps_in_range(l2_block_range.clone())
+                .await?
+        } else {
+            conn.snapshots_creator_dal()
+                .get_all_factory_deps(*l2_block_range.end())
+                .await?
+        };
         drop(conn);
         let latency = latency.observe();
         tracing::info!("Loaded {} factory deps in {latency:?}", factory_deps.len());
@@ -233,10 +458,76 @@ impl SnapshotCreator {
         Ok(output_filepath)
     }
 
+    /// Selects the base snapshot for a new snapshot. Returns `Ok(None)` if a full snapshot should be created.
+    async fn select_base_snapshot(
+        config: &SnapshotsCreatorConfig,
+        l1_batch_number: L1BatchNumber,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        if config.max_incremental_snapshots == 0 {
+            return Ok(None);
+        }
+        let snapshot_version = SnapshotVersion::try_from(config.version)
+            .context("invalid snapshot version specified in config")?;
+        anyhow::ensure!(
+            snapshot_version == SnapshotVersion::Version1,
+            "incremental snapshots are only supported for snapshot version 1"
+        );
+
+        let complete_snapshots = conn.snapshots_dal().get_all_complete_snapshots().await?;
+        let Some(base_l1_batch_number) = complete_snapshots
+            .snapshots_l1_batch_numbers
+            .into_iter()
+            .find(|&number| number < l1_batch_number)
+        else {
+            tracing::info!(
+                "No complete snapshots before L1 batch {l1_batch_number}; creating a full snapshot"
+            );
+            return Ok(None);
+        };
+
+        // Walk the chain of snapshots ending in the base snapshot to check its length and versions.
+        let mut increment_count = 0;
+        let mut current_l1_batch_number = base_l1_batch_number;
+        loop {
+            let snapshot = conn
+                .snapshots_dal()
+                .get_snapshot_metadata(current_l1_batch_number)
+                .await?
+                .with_context(|| {
+                    format!("snapshot for L1 batch #{current_l1_batch_number} referenced in the snapshot chain is missing")
+                })?;
+            if snapshot.version != SnapshotVersion::Version1 {
+                tracing::info!(
+                    "Snapshot for L1 batch {current_l1_batch_number} has version {:?}; creating a full snapshot",
+                    snapshot.version
+                );
+                return Ok(None);
+            }
+            match snapshot.base_l1_batch_number {
+                Some(number) => {
+                    increment_count += 1;
+                    current_l1_batch_number = number;
+                }
+                None => break,
+            }
+        }
+
+        if increment_count >= config.max_incremental_snapshots {
+            tracing::info!(
+                "Snapshot for L1 batch {base_l1_batch_number} already has {increment_count} incremental snapshots \
+                 in its chain; creating a full snapshot"
+            );
+            return Ok(None);
+        }
+        Ok(Some(base_l1_batch_number))
+    }
+
     /// Returns `Ok(None)` if the created snapshot would coincide with `latest_snapshot`.
     async fn initialize_snapshot_progress(
         config: &SnapshotsCreatorConfig,
         l1_batch_number: L1BatchNumber,
+        base_l1_batch_number: Option<L1BatchNumber>,
         min_chunk_count: u64,
         conn: &mut Connection<'_, Core>,
     ) -> anyhow::Result<Option<SnapshotProgress>> {
@@ -255,10 +546,23 @@ impl SnapshotCreator {
                 )
             })?;
 
-        let distinct_storage_logs_keys_count = conn
-            .snapshots_creator_dal()
-            .get_distinct_storage_logs_keys_count(l1_batch_number)
-            .await?;
+        let distinct_storage_logs_keys_count = if let Some(base_l1_batch_number) =
+            base_l1_batch_number
+        {
+            let l2_block_range =
+                Self::get_l2_block_range(conn, l1_batch_number, Some(base_l1_batch_number)).await?;
+            tracing::info!(
+                "Creating incremental snapshot for L1 batch {l1_batch_number} based on the snapshot \
+                 for L1 batch {base_l1_batch_number} (changes in L2 blocks {l2_block_range:?})"
+            );
+            conn.snapshots_creator_dal()
+                .get_changed_storage_logs_count(l2_block_range)
+                .await?
+        } else {
+            conn.snapshots_creator_dal()
+                .get_distinct_storage_logs_keys_count(l1_batch_number)
+                .await?
+        };
         let chunk_size = config.storage_logs_chunk_size;
         // We force the minimum number of chunks to avoid situations where only one chunk is created in tests.
         let chunk_count = distinct_storage_logs_keys_count
@@ -269,10 +573,20 @@ impl SnapshotCreator {
             "Selected storage logs chunking for L1 batch {l1_batch_number}: \
             {chunk_count} chunks of expected size {chunk_size}"
         );
+
+        // The genesis L1 batch doesn't contain any transactions, so it's never included into history.
+        let history_l1_batch_count = config.history_l1_batches.min(l1_batch_number.0);
+        if history_l1_batch_count > 0 {
+            tracing::info!(
+                "Transaction history will be exported for {history_l1_batch_count} L1 batches ending with L1 batch {l1_batch_number}"
+            );
+        }
         Ok(Some(SnapshotProgress::new(
             snapshot_version,
             l1_batch_number,
+            base_l1_batch_number,
             chunk_count,
+            history_l1_batch_count,
         )))
     }
 
@@ -324,6 +638,11 @@ impl SnapshotCreator {
                 });
             (requested_l1_batch_number, existing_snapshot)
         };
+        let base_l1_batch_number = if existing_snapshot.is_none() {
+            Self::select_base_snapshot(config, requested_l1_batch_number, &mut master_conn).await?
+        } else {
+            None
+        };
         drop(master_conn);
 
         match existing_snapshot {
@@ -336,6 +655,7 @@ impl SnapshotCreator {
                 Self::initialize_snapshot_progress(
                     config,
                     requested_l1_batch_number,
+                    base_l1_batch_number,
                     min_chunk_count,
                     &mut self.connect_to_replica().await?,
                 )
@@ -364,23 +684,24 @@ impl SnapshotCreator {
         };
 
         let mut conn = self.connect_to_replica().await?;
-        let (_, last_l2_block_number_in_batch) = conn
-            .blocks_dal()
-            .get_l2_block_range_of_l1_batch(progress.l1_batch_number)
-            .await?
-            .context("No L2 blocks for L1 batch")?;
+        let l2_block_range = Self::get_l2_block_range(
+            &mut conn,
+            progress.l1_batch_number,
+            progress.base_l1_batch_number,
+        )
+        .await?;
         drop(conn);
 
         METRICS.storage_logs_chunks_count.set(progress.chunk_count);
         tracing::info!(
-            "Creating snapshot for storage logs up to L2 block {last_l2_block_number_in_batch}, \
-            L1 batch {}",
-            progress.l1_batch_number
+            "Creating snapshot for storage logs in L2 blocks {l2_block_range:?}, L1 batch {} (base snapshot: {:?})",
+            progress.l1_batch_number,
+            progress.base_l1_batch_number
         );
 
         if progress.is_new_snapshot {
             let factory_deps_output_file = self
-                .process_factory_deps(last_l2_block_number_in_batch, progress.l1_batch_number)
+                .process_factory_deps(&progress, &l2_block_range)
                 .await?;
 
             let mut master_conn = self
@@ -392,7 +713,9 @@ impl SnapshotCreator {
                 .add_snapshot(
                     progress.version,
                     progress.l1_batch_number,
+                    progress.base_l1_batch_number,
                     progress.chunk_count,
+                    progress.history_l1_batch_count,
                     &factory_deps_output_file,
                 )
                 .await?;
@@ -410,12 +733,21 @@ impl SnapshotCreator {
                 self.process_storage_logs_single_chunk(
                     &semaphore,
                     &progress,
-                    last_l2_block_number_in_batch,
+                    &l2_block_range,
                     chunk_id,
                 )
             });
         futures::future::try_join_all(tasks).await?;
 
+        METRICS
+            .history_l1_batches_left_to_process
+            .set(progress.remaining_history_indices.len());
+        let tasks = progress
+            .remaining_history_indices
+            .iter()
+            .map(|&idx| self.process_history_single_l1_batch(&semaphore, &progress, idx));
+        futures::future::try_join_all(tasks).await?;
+
         METRICS
             .snapshot_l1_batch
             .set(progress.l1_batch_number.0.into());
@@ -18,6 +18,13 @@ pub(crate) enum StorageChunkStage {
     SaveToGcs,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(crate) enum HistoryStage {
+    LoadFromPostgres,
+    SaveToGcs,
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "snapshots_creator")]
 pub(crate) struct SnapshotsCreatorMetrics {
@@ -37,6 +44,11 @@ pub(crate) struct SnapshotsCreatorMetrics {
     /// Latency of factory deps processing split by stage.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub factory_deps_processing_duration: Family<FactoryDepsStage, Histogram<Duration>>,
+    /// Number of L1 batches with exported transaction history left to process for the snapshot being currently generated.
+    pub history_l1_batches_left_to_process: Gauge<usize>,
+    /// Latency of processing transaction history for a single L1 batch split by stage.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub history_processing_duration: Family<HistoryStage, Histogram<Duration>>,
 }
 
 #[vise::register]
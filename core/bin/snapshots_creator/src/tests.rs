@@ -2,7 +2,7 @@
 
 use std::{
     collections::{HashMap, HashSet},
-    fmt,
+    fmt, slice,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
@@ -13,16 +13,20 @@ use rand::{thread_rng, Rng};
 use test_casing::test_casing;
 use zksync_config::SnapshotsCreatorConfig;
 use zksync_dal::{Connection, CoreDal};
+use zksync_node_test_utils::{create_l2_transaction, execute_l2_transaction};
 use zksync_object_store::{MockObjectStore, ObjectStore};
 use zksync_types::{
     block::{L1BatchHeader, L1BatchTreeData, L2BlockHeader},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotStorageLog,
+        SnapshotEvent, SnapshotFactoryDependencies, SnapshotFactoryDependency,
+        SnapshotHistoryStorageKey, SnapshotL1BatchHistory, SnapshotStorageLog,
         SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
     },
-    AccountTreeId, Address, L1BatchNumber, L2BlockNumber, ProtocolVersion, StorageKey, StorageLog,
-    H256,
+    tx::IncludedTxLocation,
+    AccountTreeId, Address, L1BatchNumber, L2BlockNumber, ProtocolVersion, ProtocolVersionId,
+    StorageKey, StorageLog, H256,
 };
+use zksync_vm_interface::{Call, VmEvent, VmExecutionMetrics};
 
 use super::*;
 
@@ -31,6 +35,8 @@ const TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     l1_batch_number: None,
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 10,
+    history_l1_batches: 0,
+    max_incremental_snapshots: 0,
     object_store: None,
 };
 const SEQUENTIAL_TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
@@ -384,6 +390,113 @@ async fn persisting_snapshot_logs_with_specified_l1_batch() {
     }
 }
 
+#[tokio::test]
+async fn persisting_snapshot_history() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store = MockObjectStore::arc();
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    // Add a transaction with an event to L1 batch #7.
+    let mut tx_result = execute_l2_transaction(create_l2_transaction(10, 100));
+    tx_result.execution_info.gas_used = 10_000;
+    tx_result.call_traces = vec![Call {
+        to: Address::repeat_byte(0x42),
+        ..Call::default()
+    }];
+    tx_result.revert_reason = Some("oops".to_owned());
+    conn.transactions_dal()
+        .mark_txs_as_executed_in_l2_block(
+            L2BlockNumber(7),
+            slice::from_ref(&tx_result),
+            1.into(),
+            ProtocolVersionId::latest(),
+            true,
+        )
+        .await
+        .unwrap();
+    conn.transactions_dal()
+        .mark_txs_as_executed_in_l1_batch(L1BatchNumber(7), slice::from_ref(&tx_result))
+        .await
+        .unwrap();
+    let event = VmEvent {
+        location: (L1BatchNumber(7), 0),
+        address: Address::repeat_byte(0x23),
+        indexed_topics: vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+        value: vec![1, 2, 3],
+    };
+    let tx_location = IncludedTxLocation {
+        tx_hash: tx_result.hash,
+        tx_index_in_l2_block: 0,
+    };
+    conn.events_dal()
+        .save_events(L2BlockNumber(7), &[(tx_location, vec![&event])])
+        .await
+        .unwrap();
+
+    let config = SnapshotsCreatorConfig {
+        history_l1_batches: 3,
+        ..TEST_CONFIG
+    };
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(config, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+
+    let snapshot_l1_batch_number = L1BatchNumber(8);
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(snapshot_l1_batch_number)
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    assert!(snapshot_metadata.is_complete());
+    assert_eq!(snapshot_metadata.history_filepaths.len(), 3);
+
+    for (idx, l1_batch_number) in [6, 7, 8].into_iter().enumerate() {
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        assert_eq!(snapshot_metadata.history_l1_batch(idx), l1_batch_number);
+        let key = SnapshotHistoryStorageKey {
+            snapshot_l1_batch_number,
+            l1_batch_number,
+        };
+        let history: SnapshotL1BatchHistory = object_store.get(key).await.unwrap();
+        assert_eq!(history.header.number, l1_batch_number);
+        assert_eq!(history.l2_blocks.len(), 1);
+        let block = &history.l2_blocks[0];
+        assert_eq!(block.header.number, L2BlockNumber(l1_batch_number.0));
+
+        if l1_batch_number == L1BatchNumber(7) {
+            assert_eq!(block.transactions.len(), 1);
+            let tx = &block.transactions[0];
+            assert_eq!(tx.transaction.hash(), tx_result.hash);
+            assert_eq!(tx.index_in_l1_batch, 0);
+            assert!(tx.success);
+            let execution_info: VmExecutionMetrics =
+                serde_json::from_value(tx.execution_info.clone()).unwrap();
+            assert_eq!(execution_info.gas_used, 10_000);
+            let call_trace: Call =
+                serde_json::from_value(tx.call_trace.clone().expect("no call trace")).unwrap();
+            assert_eq!(call_trace.revert_reason.as_deref(), Some("oops"));
+            assert_eq!(call_trace.calls.len(), 1);
+            assert_eq!(call_trace.calls[0].to, Address::repeat_byte(0x42));
+            assert_eq!(
+                block.events,
+                [SnapshotEvent {
+                    tx_index_in_l2_block: 0,
+                    address: event.address,
+                    indexed_topics: event.indexed_topics.clone(),
+                    value: event.value.clone().into(),
+                }]
+            );
+        } else {
+            assert!(block.transactions.is_empty());
+            assert!(block.events.is_empty());
+        }
+    }
+}
+
 async fn assert_storage_logs(
     object_store: &dyn ObjectStore,
     snapshot_l1_batch_number: L1BatchNumber,
@@ -407,6 +520,74 @@ async fn assert_storage_logs(
     assert_eq!(actual_logs, expected_logs);
 }
 
+#[tokio::test]
+async fn creating_incremental_snapshots() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store = MockObjectStore::arc();
+    let mut conn = pool.connection().await.unwrap();
+    let expected_outputs = prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    // Pairs of the snapshot L1 batch and the expected base snapshot L1 batch. The snapshot for L1 batch #7
+    // must be full since the chain of incremental snapshots ending in L1 batch #5 has the max allowed length.
+    let expected_snapshots = [
+        (2, None),
+        (4, Some(2)),
+        (5, Some(4)),
+        (7, None),
+        (8, Some(7)),
+    ];
+    for (l1_batch_number, expected_base) in expected_snapshots {
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        let expected_base = expected_base.map(L1BatchNumber);
+        let config = SnapshotsCreatorConfig {
+            l1_batch_number: Some(l1_batch_number),
+            max_incremental_snapshots: 2,
+            ..TEST_CONFIG
+        };
+        SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+            .run(config, MIN_CHUNK_COUNT)
+            .await
+            .unwrap();
+
+        let snapshot_metadata = conn
+            .snapshots_dal()
+            .get_snapshot_metadata(l1_batch_number)
+            .await
+            .unwrap()
+            .expect("No snapshot metadata");
+        assert!(snapshot_metadata.is_complete());
+        assert_eq!(snapshot_metadata.base_l1_batch_number, expected_base);
+
+        // An incremental snapshot must only contain storage logs and factory deps changed after the base snapshot.
+        let first_l1_batch = expected_base.map_or(L1BatchNumber(0), |number| number + 1);
+        let mut actual_logs = HashSet::new();
+        for chunk_id in 0..MIN_CHUNK_COUNT {
+            let key = SnapshotStorageLogsStorageKey {
+                l1_batch_number,
+                chunk_id,
+            };
+            let chunk: SnapshotStorageLogsChunk = object_store.get(key).await.unwrap();
+            actual_logs.extend(chunk.storage_logs);
+        }
+        let expected_logs: HashSet<_> = expected_outputs
+            .storage_logs
+            .iter()
+            .filter(|log| {
+                (first_l1_batch..=l1_batch_number).contains(&log.l1_batch_number_of_initial_write)
+            })
+            .cloned()
+            .collect();
+        assert_eq!(actual_logs, expected_logs);
+
+        let SnapshotFactoryDependencies { factory_deps } =
+            object_store.get(l1_batch_number).await.unwrap();
+        // Each L1 batch contains a single L2 block with 10 factory deps.
+        let expected_deps_count = (l1_batch_number.0 - first_l1_batch.0 + 1) * 10;
+        assert_eq!(factory_deps.len(), expected_deps_count as usize);
+    }
+}
+
 #[tokio::test]
 async fn persisting_snapshot_logs_for_v0_snapshot() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
 use anyhow::Context as _;
 use clap::Parser;
@@ -10,11 +10,12 @@ use zksync_config::{
             TimestampAsserterConfig,
         },
         house_keeper::HouseKeeperConfig,
-        BasicWitnessInputProducerConfig, ContractVerifierSecrets, DataAvailabilitySecrets,
-        DatabaseSecrets, ExperimentalVmConfig, ExternalPriceApiClientConfig,
-        FriProofCompressorConfig, FriProverConfig, FriProverGatewayConfig,
-        FriWitnessGeneratorConfig, L1Secrets, ObservabilityConfig, PrometheusConfig,
-        ProofDataHandlerConfig, ProtectiveReadsWriterConfig, Secrets,
+        ApiSecrets, BasicWitnessInputProducerConfig, ContractVerifierSecrets,
+        DataAvailabilitySecrets, DatabaseSecrets, ExperimentalVmConfig,
+        ExternalPriceApiClientConfig, FriProofCompressorConfig, FriProverConfig,
+        FriProverGatewayConfig, FriWitnessGeneratorConfig, GeneralConfig, L1Secrets,
+        ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig, ProtectiveReadsWriterConfig,
+        Secrets,
     },
     ApiConfig, BaseTokenAdjusterConfig, ContractVerifierConfig, ContractsConfig, DAClientConfig,
     DADispatcherConfig, DBConfig, EthConfig, EthWatchConfig, ExternalProofIntegrationApiConfig,
@@ -25,6 +26,9 @@ use zksync_core_leftovers::{
     Component, Components,
 };
 use zksync_env_config::FromEnv;
+use zksync_node_framework::{
+    implementations::resources::config_reload::ConfigSource, service::WiringGraphFormat,
+};
 
 use crate::node_builder::MainNodeBuilder;
 
@@ -47,6 +51,7 @@ struct Cli {
     )]
     components: ComponentsToRun,
     /// Path to the yaml config. If set, it will be used instead of env vars.
+    /// Required for config reloading (on SIGHUP or via the `admin_reloadConfig` method).
     #[arg(long)]
     config_path: Option<std::path::PathBuf>,
     /// Path to the yaml with secrets. If set, it will be used instead of env vars.
@@ -70,6 +75,10 @@ struct Cli {
     /// Can be used to catch issues with configuration.
     #[arg(long, conflicts_with = "genesis")]
     no_run: bool,
+    /// Wire the node with the provided list of the components without running any tasks, print the dependency graph
+    /// among wiring layers, resources and tasks in the specified format (`dot` or `json`) and exit.
+    #[arg(long, value_name = "FORMAT", conflicts_with_all = ["genesis", "no_run"])]
+    print_wiring: Option<WiringGraphFormat>,
 }
 
 #[derive(Debug, Clone)]
@@ -94,18 +103,10 @@ fn main() -> anyhow::Result<()> {
     // Load env config and use it if file config is not provided
     let tmp_config = load_env_config()?;
 
-    let configs = match opt.config_path {
-        None => {
-            let mut configs = tmp_config.general();
-            configs.consensus_config =
-                config::read_consensus_config().context("read_consensus_config()")?;
-            configs
-        }
-        Some(path) => {
-            read_yaml_repr::<zksync_protobuf_config::proto::general::GeneralConfig>(&path)
-                .context("failed decoding general YAML config")?
-        }
+    let config_source = GeneralConfigSource {
+        config_path: opt.config_path,
     };
+    let configs = config_source.load_with(&tmp_config)?;
 
     let wallets = match opt.wallets_path {
         None => tmp_config.wallets(),
@@ -122,6 +123,7 @@ fn main() -> anyhow::Result<()> {
             l1: L1Secrets::from_env().ok(),
             data_availability: DataAvailabilitySecrets::from_env().ok(),
             contract_verifier: ContractVerifierSecrets::from_env().ok(),
+            api: ApiSecrets::from_env().ok(),
         },
     };
 
@@ -142,7 +144,7 @@ fn main() -> anyhow::Result<()> {
         .context("observability config")?;
 
     let node = MainNodeBuilder::new(
-        configs,
+        configs.clone(),
         wallets,
         genesis,
         secrets,
@@ -154,12 +156,20 @@ fn main() -> anyhow::Result<()> {
         Some(contracts_config.settlement_layer_specific_contracts()),
         Some(contracts_config.l1_multicall3_addr),
     )?;
+    // Without a config file, the general config is read from env vars, which cannot change while the node is running,
+    // so config reloading is not enabled.
+    let node = if config_source.config_path.is_some() {
+        node.with_config_reload(config_source, configs)
+    } else {
+        node
+    };
 
     let observability_guard = {
         // Observability initialization should be performed within tokio context.
         let _context_guard = node.runtime_handle().enter();
         observability_config.install()?
     };
+    let node = node.with_logs_reload(observability_guard.logs_reload_handle());
 
     if opt.genesis {
         // If genesis is requested, we don't need to run the node.
@@ -169,6 +179,13 @@ fn main() -> anyhow::Result<()> {
 
     let node = node.build(opt.components.0)?;
 
+    if let Some(format) = opt.print_wiring {
+        let graph = node.dry_run();
+        println!("{}", graph.render(format));
+        anyhow::ensure!(!graph.has_errors(), "Some wiring layers have failed");
+        return Ok(());
+    }
+
     if opt.no_run {
         tracing::info!("Node composed successfully; exiting due to --no-run flag");
         return Ok(());
@@ -178,6 +195,35 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Source of the general config; used both on startup and on config reloads.
+#[derive(Debug)]
+struct GeneralConfigSource {
+    config_path: Option<PathBuf>,
+}
+
+impl GeneralConfigSource {
+    fn load_with(&self, env_config: &TempConfigStore) -> anyhow::Result<GeneralConfig> {
+        Ok(match &self.config_path {
+            None => {
+                let mut configs = env_config.general();
+                configs.consensus_config =
+                    config::read_consensus_config().context("read_consensus_config()")?;
+                configs
+            }
+            Some(path) => {
+                read_yaml_repr::<zksync_protobuf_config::proto::general::GeneralConfig>(path)
+                    .context("failed decoding general YAML config")?
+            }
+        })
+    }
+}
+
+impl ConfigSource for GeneralConfigSource {
+    fn load(&self) -> anyhow::Result<GeneralConfig> {
+        self.load_with(&load_env_config()?)
+    }
+}
+
 fn load_env_config() -> anyhow::Result<TempConfigStore> {
     Ok(TempConfigStore {
         postgres_config: PostgresConfig::from_env().ok(),
@@ -21,60 +21,67 @@ use zksync_node_api_server::{
     web3::{state::InternalApiConfigBase, Namespace},
 };
 use zksync_node_framework::{
-    implementations::layers::{
-        base_token::{
-            base_token_ratio_persister::BaseTokenRatioPersisterLayer,
-            base_token_ratio_provider::BaseTokenRatioProviderLayer, ExternalPriceApiLayer,
-        },
-        circuit_breaker_checker::CircuitBreakerCheckerLayer,
-        commitment_generator::CommitmentGeneratorLayer,
-        consensus::MainNodeConsensusLayer,
-        contract_verification_api::ContractVerificationApiLayer,
-        da_clients::{
-            avail::AvailWiringLayer, celestia::CelestiaWiringLayer, eigen::EigenWiringLayer,
-            no_da::NoDAClientWiringLayer, nomos::NomosWiringLayer,
-            object_store::ObjectStorageClientWiringLayer,
-        },
-        da_dispatcher::DataAvailabilityDispatcherLayer,
-        eth_sender::{EthTxAggregatorLayer, EthTxManagerLayer},
-        eth_watch::EthWatchLayer,
-        external_proof_integration_api::ExternalProofIntegrationApiLayer,
-        gas_adjuster::GasAdjusterLayer,
-        gateway_migrator_layer::GatewayMigratorLayer,
-        healtcheck_server::HealthCheckLayer,
-        house_keeper::HouseKeeperLayer,
-        l1_batch_commitment_mode_validation::L1BatchCommitmentModeValidationLayer,
-        l1_gas::L1GasLayer,
-        logs_bloom_backfill::LogsBloomBackfillLayer,
-        metadata_calculator::MetadataCalculatorLayer,
-        node_storage_init::{
-            main_node_strategy::MainNodeInitStrategyLayer, NodeStorageInitializerLayer,
-        },
-        object_store::ObjectStoreLayer,
-        pk_signing_eth_client::PKSigningEthClientLayer,
-        pools_layer::PoolsLayerBuilder,
-        postgres::PostgresLayer,
-        prometheus_exporter::PrometheusExporterLayer,
-        proof_data_handler::ProofDataHandlerLayer,
-        query_eth_client::QueryEthClientLayer,
-        settlement_layer_client::SettlementLayerClientLayer,
-        settlement_layer_data::{MainNodeConfig, SettlementLayerData},
-        sigint::SigintHandlerLayer,
-        state_keeper::{
-            main_batch_executor::MainBatchExecutorLayer, mempool_io::MempoolIOLayer,
-            output_handler::OutputHandlerLayer, RocksdbStorageOptions, StateKeeperLayer,
-        },
-        vm_runner::{
-            bwip::BasicWitnessInputProducerLayer, playground::VmPlaygroundLayer,
-            protective_reads::ProtectiveReadsWriterLayer,
-        },
-        web3_api::{
-            caches::MempoolCacheLayer,
-            server::{Web3ServerLayer, Web3ServerOptionalConfig},
-            tree_api_client::TreeApiClientLayer,
-            tx_sender::{PostgresStorageCachesConfig, TxSenderLayer},
-            tx_sink::{whitelist::WhitelistedMasterPoolSinkLayer, MasterPoolSinkLayer},
+    implementations::{
+        layers::{
+            base_token::{
+                base_token_ratio_persister::BaseTokenRatioPersisterLayer,
+                base_token_ratio_provider::BaseTokenRatioProviderLayer, ExternalPriceApiLayer,
+            },
+            circuit_breaker_checker::CircuitBreakerCheckerLayer,
+            circuit_breakers::CircuitBreakersLayer,
+            commitment_generator::CommitmentGeneratorLayer,
+            config_reload::ConfigReloadLayer,
+            consensus::MainNodeConsensusLayer,
+            contract_verification_api::ContractVerificationApiLayer,
+            da_clients::{
+                avail::AvailWiringLayer, celestia::CelestiaWiringLayer, eigen::EigenWiringLayer,
+                no_da::NoDAClientWiringLayer, nomos::NomosWiringLayer,
+                object_store::ObjectStorageClientWiringLayer,
+            },
+            da_dispatcher::DataAvailabilityDispatcherLayer,
+            eth_sender::{EthTxAggregatorLayer, EthTxManagerLayer},
+            eth_watch::EthWatchLayer,
+            external_proof_integration_api::ExternalProofIntegrationApiLayer,
+            gas_adjuster::GasAdjusterLayer,
+            gateway_migrator_layer::GatewayMigratorLayer,
+            healtcheck_server::HealthCheckLayer,
+            house_keeper::HouseKeeperLayer,
+            l1_batch_commitment_mode_validation::L1BatchCommitmentModeValidationLayer,
+            l1_gas::L1GasLayer,
+            logs_bloom_backfill::LogsBloomBackfillLayer,
+            logs_reload::LogsReloadLayer,
+            metadata_calculator::MetadataCalculatorLayer,
+            node_storage_init::{
+                main_node_strategy::MainNodeInitStrategyLayer, NodeStorageInitializerLayer,
+            },
+            object_store::ObjectStoreLayer,
+            pk_signing_eth_client::PKSigningEthClientLayer,
+            pools_layer::PoolsLayerBuilder,
+            postgres::PostgresLayer,
+            prometheus_exporter::PrometheusExporterLayer,
+            proof_data_handler::ProofDataHandlerLayer,
+            query_eth_client::QueryEthClientLayer,
+            settlement_layer_client::SettlementLayerClientLayer,
+            settlement_layer_data::{MainNodeConfig, SettlementLayerData},
+            sigint::SigintHandlerLayer,
+            state_keeper::{
+                main_batch_executor::MainBatchExecutorLayer, mempool_io::MempoolIOLayer,
+                output_handler::OutputHandlerLayer, RocksdbStorageOptions, StateKeeperLayer,
+            },
+            vm_runner::{
+                bwip::BasicWitnessInputProducerLayer, playground::VmPlaygroundLayer,
+                protective_reads::ProtectiveReadsWriterLayer,
+            },
+            web3_api::{
+                admin::AdminApiLayer,
+                caches::MempoolCacheLayer,
+                server::{Web3ServerLayer, Web3ServerOptionalConfig},
+                tree_api_client::TreeApiClientLayer,
+                tx_sender::{PostgresStorageCachesConfig, TxSenderLayer},
+                tx_sink::{whitelist::WhitelistedMasterPoolSinkLayer, MasterPoolSinkLayer},
+            },
         },
+        resources::config_reload::ConfigSource,
     },
     service::{ZkStackService, ZkStackServiceBuilder},
 };
@@ -83,7 +90,7 @@ use zksync_types::{
     pubdata_da::PubdataSendingMode,
     Address, SHARED_BRIDGE_ETHER_TOKEN_ADDRESS,
 };
-use zksync_vlog::prometheus::PrometheusExporterConfig;
+use zksync_vlog::{prometheus::PrometheusExporterConfig, LogsReloadHandle};
 
 /// Macro that looks into a path to fetch an optional config,
 /// and clones it into a variable.
@@ -105,6 +112,8 @@ pub struct MainNodeBuilder {
     l1_sl_contracts: Option<SettlementLayerSpecificContracts>,
     l2_contracts: L2Contracts,
     multicall3: Option<Address>,
+    config_reload: Option<ConfigReloadLayer>,
+    logs_reload: Option<LogsReloadLayer>,
 }
 
 impl MainNodeBuilder {
@@ -129,9 +138,23 @@ impl MainNodeBuilder {
             l1_sl_contracts,
             l2_contracts,
             multicall3,
+            config_reload: None,
+            logs_reload: None,
         })
     }
 
+    /// Enables runtime reloading of the reloadable part of the `initial` config from the provided `source`.
+    pub fn with_config_reload(mut self, source: impl ConfigSource, initial: GeneralConfig) -> Self {
+        self.config_reload = Some(ConfigReloadLayer::new(source, initial));
+        self
+    }
+
+    /// Allows changing log directives at runtime, e.g. via the admin API or config reloading.
+    pub fn with_logs_reload(mut self, handle: LogsReloadHandle) -> Self {
+        self.logs_reload = Some(LogsReloadLayer::new(handle));
+        self
+    }
+
     pub fn runtime_handle(&self) -> tokio::runtime::Handle {
         self.node.runtime_handle()
     }
@@ -160,6 +183,20 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    fn add_config_reload_layer(mut self) -> anyhow::Result<Self> {
+        if let Some(layer) = self.config_reload.take() {
+            self.node.add_layer(layer);
+        }
+        Ok(self)
+    }
+
+    fn add_logs_reload_layer(mut self) -> anyhow::Result<Self> {
+        if let Some(layer) = self.logs_reload.take() {
+            self.node.add_layer(layer);
+        }
+        Ok(self)
+    }
+
     fn add_pools_layer(mut self) -> anyhow::Result<Self> {
         let config = try_load_config!(self.configs.postgres_config);
         let secrets = try_load_config!(self.secrets.database);
@@ -363,6 +400,26 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    fn add_admin_api_layer(mut self) -> anyhow::Result<Self> {
+        let admin_addr = self
+            .configs
+            .api_config
+            .as_ref()
+            .and_then(|config| config.web3_json_rpc.admin_bind_addr());
+        let Some(admin_addr) = admin_addr else {
+            return Ok(self);
+        };
+        let admin_token = self
+            .secrets
+            .api
+            .as_ref()
+            .and_then(|secrets| secrets.admin_token.clone())
+            .context("admin API port is set, but admin token is not provided in secrets")?;
+        self.node
+            .add_layer(AdminApiLayer::new(admin_addr, admin_token));
+        Ok(self)
+    }
+
     fn add_tx_sender_layer(mut self) -> anyhow::Result<Self> {
         let sk_config = try_load_config!(self.configs.state_keeper_config);
         let rpc_config = try_load_config!(self.configs.api_config).web3_json_rpc;
@@ -527,6 +584,28 @@ impl MainNodeBuilder {
     }
 
     fn add_eth_tx_aggregator_layer(mut self) -> anyhow::Result<Self> {
+        let eth_sender_config = self
+            .configs
+            .eth
+            .as_ref()
+            .and_then(|eth| eth.sender.as_ref());
+        if let Some(first_gated_batch) =
+            eth_sender_config.and_then(|sender| sender.tee_quorum_required_from_batch)
+        {
+            let quorum_enabled =
+                self.configs
+                    .proof_data_handler_config
+                    .as_ref()
+                    .is_some_and(|config| {
+                        config.tee_config.tee_support
+                            && !config.tee_config.quorum_tee_types.is_empty()
+                    });
+            anyhow::ensure!(
+                quorum_enabled,
+                "TEE quorum is required from batch #{first_gated_batch}, but TEE support is disabled \
+                 or no quorum TEE types are configured"
+            );
+        }
         self.node.add_layer(EthTxAggregatorLayer::new(
             self.genesis_config.l2_chain_id,
             self.genesis_config.l1_batch_commit_data_generator_mode,
@@ -560,6 +639,31 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    fn add_circuit_breakers_layer(mut self) -> anyhow::Result<Self> {
+        let circuit_breaker_config = try_load_config!(self.configs.circuit_breaker_config);
+        let operator_addresses = self
+            .wallets
+            .eth_sender
+            .as_ref()
+            .map(|wallets| {
+                let blob_operator = wallets
+                    .blob_operator
+                    .as_ref()
+                    .map(|wallet| wallet.address());
+                [wallets.operator.address()]
+                    .into_iter()
+                    .chain(blob_operator)
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.node.add_layer(CircuitBreakersLayer::new(
+            circuit_breaker_config,
+            operator_addresses,
+        ));
+
+        Ok(self)
+    }
+
     fn add_contract_verification_api_layer(mut self) -> anyhow::Result<Self> {
         let config = try_load_config!(self.configs.contract_verifier);
         self.node.add_layer(ContractVerificationApiLayer(config));
@@ -773,12 +877,15 @@ impl MainNodeBuilder {
         // Add "base" layers (resources and helper tasks).
         self = self
             .add_sigint_handler_layer()?
+            .add_config_reload_layer()?
+            .add_logs_reload_layer()?
             .add_pools_layer()?
             .add_object_store_layer()?
             .add_circuit_breaker_checker_layer()?
             .add_healthcheck_layer()?
             .add_prometheus_exporter_layer()?
             .add_query_eth_client_layer()?
+            .add_circuit_breakers_layer()?
             .add_settlement_mode_data()?
             .add_settlement_layer_client_layer()?
             .add_gateway_migrator_layer()?
@@ -886,6 +993,9 @@ impl MainNodeBuilder {
                 }
             }
         }
+
+        // The admin API uses optional resources provided by component layers, so it's added last.
+        self = self.add_admin_api_layer()?;
         Ok(self.node.build())
     }
 }
@@ -170,6 +170,41 @@ pub enum ProverJobStatus {
     InGPUProof,
 }
 
+/// Priority class of an L1 batch. Witness generator, prover and compressor jobs for batches with a higher class
+/// are picked before jobs for batches with a lower class; within the same class, older batches go first.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, strum::Display, strum::EnumString,
+)]
+pub enum BatchPriorityClass {
+    #[strum(serialize = "low")]
+    Low,
+    #[default]
+    #[strum(serialize = "normal")]
+    Normal,
+    #[strum(serialize = "urgent")]
+    Urgent,
+}
+
+impl BatchPriorityClass {
+    /// Converts this class to the representation stored in the prover DB.
+    pub fn to_db(self) -> i32 {
+        match self {
+            Self::Low => -1,
+            Self::Normal => 0,
+            Self::Urgent => 1,
+        }
+    }
+
+    /// Converts the class from its DB representation. Out-of-range values are clamped.
+    pub fn from_db(value: i32) -> Self {
+        match value {
+            ..=-1 => Self::Low,
+            0 => Self::Normal,
+            1.. => Self::Urgent,
+        }
+    }
+}
+
 #[derive(Debug, Clone, strum::Display, strum::EnumString, strum::AsRefStr)]
 pub enum WitnessJobStatus {
     #[strum(serialize = "failed")]
@@ -416,3 +451,44 @@ pub struct ProofGenerationTime {
     pub time_taken: NaiveTime,
     pub created_at: NaiveDateTime,
 }
+
+/// DTO describing how far an L1 batch has progressed through the proving pipeline (currently used by prover_cli watch).
+/// All durations are measured from the moment the batch witness inputs were saved.
+#[derive(Debug, Clone)]
+pub struct BatchStageTimings {
+    pub l1_batch_number: L1BatchNumber,
+    /// Time elapsed since the batch witness inputs were saved.
+    pub age: std::time::Duration,
+    /// Durations after which witness generation completed, indexed by [`AggregationRound`].
+    /// `None` if witness generation for the round hasn't completed yet.
+    pub witness_generation: [Option<std::time::Duration>; 5],
+    /// Duration after which proof compression completed, or `None` if it hasn't completed yet.
+    pub compression: Option<std::time::Duration>,
+}
+
+/// Kind of a proving artifact stored in the prover object store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum ProverArtifactKind {
+    /// Inputs of the basic witness generator.
+    WitnessInputs,
+    /// Closed form inputs of the leaf aggregation witness generator.
+    ClosedFormInputs,
+    /// Aggregations passed to the node aggregation witness generator.
+    Aggregations,
+    /// Partial input of the scheduler witness generator.
+    SchedulerPartialInput,
+    /// Circuit proven by a prover job.
+    Circuit,
+    /// FRI proof produced by a prover job.
+    Proof,
+    /// Final (compressed) proof of the batch.
+    FinalProof,
+}
+
+/// Artifact referenced by prover jobs of an L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProverArtifact {
+    pub kind: ProverArtifactKind,
+    pub object_key: String,
+}
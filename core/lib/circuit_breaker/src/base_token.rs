@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+use crate::{metrics::METRICS, CircuitBreaker, CircuitBreakerError};
+
+/// Checks that the latest base token ratio persisted to the database is not too old.
+/// If no ratio is persisted yet, the check passes.
+#[derive(Debug)]
+pub struct BaseTokenRatioStalenessChecker {
+    pub pool: ConnectionPool<Core>,
+    pub staleness_limit: Duration,
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for BaseTokenRatioStalenessChecker {
+    fn name(&self) -> &'static str {
+        "base_token_ratio_staleness"
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let latest_ratio = self
+            .pool
+            .connection_tagged("circuit_breaker")
+            .await?
+            .base_token_dal()
+            .get_latest_ratio()
+            .await?;
+        let Some(latest_ratio) = latest_ratio else {
+            return Ok(());
+        };
+
+        // The ratio timestamp may be slightly in the future because of clock skew; treat it as fresh.
+        let age = (chrono::Utc::now() - latest_ratio.ratio_timestamp)
+            .to_std()
+            .unwrap_or_default();
+        METRICS.base_token_ratio_age.set(age);
+        if age > self.staleness_limit {
+            return Err(CircuitBreakerError::StaleBaseTokenRatio {
+                age,
+                threshold: self.staleness_limit,
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+use crate::{metrics::METRICS, CircuitBreaker, CircuitBreakerError};
+
+/// Checks the number of sealed L1 batches not dispatched to the DA layer yet.
+#[derive(Debug)]
+pub struct DaDispatchBacklogChecker {
+    pub pool: ConnectionPool<Core>,
+    pub backlog_limit: u32,
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for DaDispatchBacklogChecker {
+    fn name(&self) -> &'static str {
+        "da_dispatch_backlog"
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let mut connection = self.pool.connection_tagged("circuit_breaker").await?;
+        let Some(sealed_batch) = connection.blocks_dal().get_sealed_l1_batch_number().await? else {
+            return Ok(());
+        };
+        let oldest_ready_batch = connection
+            .data_availability_dal()
+            .get_ready_for_da_dispatch_l1_batches(1)
+            .await?
+            .first()
+            .map(|batch| batch.l1_batch_number);
+        drop(connection);
+
+        let backlog =
+            oldest_ready_batch.map_or(0, |oldest| (sealed_batch.0 + 1).saturating_sub(oldest.0));
+        METRICS.da_dispatch_backlog.set(backlog.into());
+        if backlog > self.backlog_limit {
+            return Err(CircuitBreakerError::DaDispatchBacklog {
+                backlog,
+                threshold: self.backlog_limit,
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Context as _;
+use zksync_eth_client::EthInterface;
+use zksync_types::{Address, U256};
+
+use crate::{CircuitBreaker, CircuitBreakerError};
+
+/// Checks that L1 balances of the specified accounts (e.g., eth sender operators) are above the threshold.
+#[derive(Debug)]
+pub struct L1BalanceChecker {
+    pub eth_client: Box<dyn EthInterface>,
+    pub addresses: Vec<Address>,
+    pub min_balance: U256,
+}
+
+impl L1BalanceChecker {
+    pub fn new(
+        eth_client: Box<dyn EthInterface>,
+        addresses: Vec<Address>,
+        min_balance_gwei: u64,
+    ) -> Self {
+        Self {
+            eth_client,
+            addresses,
+            min_balance: U256::from(min_balance_gwei) * U256::exp10(9),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for L1BalanceChecker {
+    fn name(&self) -> &'static str {
+        "l1_balance"
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        for &address in &self.addresses {
+            let balance = self
+                .eth_client
+                .eth_balance(address)
+                .await
+                .with_context(|| format!("cannot get L1 balance of {address:?}"))?;
+            if balance < self.min_balance {
+                return Err(CircuitBreakerError::L1BalanceTooLow {
+                    address,
+                    balance,
+                    threshold: self.min_balance,
+                });
+            }
+        }
+        Ok(())
+    }
+}
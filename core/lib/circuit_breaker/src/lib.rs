@@ -1,18 +1,69 @@
-use std::{fmt, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+    time::Duration,
+};
 
+use serde::Serialize;
 use thiserror::Error;
 use tokio::sync::{watch, Mutex};
+use zksync_config::configs::chain::{CircuitBreakerAction, CircuitBreakerActions};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{Address, U256};
 
+use crate::metrics::METRICS;
+
+pub mod base_token;
+pub mod da_dispatch;
+pub mod l1_balance;
 pub mod l1_txs;
 mod metrics;
+pub mod proof_generation;
 pub mod replication_lag;
+#[cfg(test)]
+mod tests;
+
+/// Name of the state keeper component that can be paused by circuit breakers.
+pub const STATE_KEEPER_COMPONENT: &str = "state_keeper";
+/// Names of all components that can be paused by circuit breakers. Components are registered
+/// with [`CircuitBreakers::insert_pausable_component()`] by the nodes running them.
+pub const PAUSABLE_COMPONENTS: &[&str] = &[STATE_KEEPER_COMPONENT];
+
+/// Checks that circuit breaker actions only refer to known pausable components.
+pub fn validate_actions(actions: &CircuitBreakerActions) -> anyhow::Result<()> {
+    for (breaker, action) in actions.iter() {
+        let CircuitBreakerAction::Pause(components) = action else {
+            continue;
+        };
+        for component in components {
+            anyhow::ensure!(
+                PAUSABLE_COMPONENTS.contains(&component.as_str()),
+                "circuit breaker `{breaker}` is configured to pause unknown component `{component}`; \
+                 known pausable components: {PAUSABLE_COMPONENTS:?}"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Component that can be paused by a tripped circuit breaker and resumed once the breaker recovers.
+pub trait PausableComponent: fmt::Debug + Send + Sync {
+    /// Pauses the component. Returns `false` if the component was already paused.
+    fn pause(&self) -> bool;
+    /// Resumes the component. Returns `false` if the component wasn't paused.
+    fn resume(&self) -> bool;
+}
 
 #[derive(Default, Debug)]
-pub struct CircuitBreakers(Mutex<Vec<Box<dyn CircuitBreaker>>>);
+pub struct CircuitBreakers {
+    breakers: Mutex<Vec<Box<dyn CircuitBreaker>>>,
+    pausable_components: Mutex<HashMap<&'static str, Arc<dyn PausableComponent>>>,
+}
 
 impl CircuitBreakers {
     pub async fn insert(&self, circuit_breaker: Box<dyn CircuitBreaker>) {
-        let mut guard = self.0.lock().await;
+        let mut guard = self.breakers.lock().await;
         if !guard
             .iter()
             .any(|existing_breaker| existing_breaker.name() == circuit_breaker.name())
@@ -21,12 +72,46 @@ impl CircuitBreakers {
         }
     }
 
+    /// Registers a component that can be paused by circuit breakers with the [`CircuitBreakerAction::Pause`] action.
+    pub async fn insert_pausable_component(
+        &self,
+        name: &'static str,
+        component: Arc<dyn PausableComponent>,
+    ) {
+        self.pausable_components
+            .lock()
+            .await
+            .insert(name, component);
+    }
+
     pub async fn check(&self) -> Result<(), CircuitBreakerError> {
-        for circuit_breaker in self.0.lock().await.iter() {
+        for circuit_breaker in self.breakers.lock().await.iter() {
             circuit_breaker.check().await?;
         }
         Ok(())
     }
+
+    /// Checks all circuit breakers, returning the result for each of them.
+    pub async fn check_each(&self) -> Vec<(&'static str, Result<(), CircuitBreakerError>)> {
+        let breakers = self.breakers.lock().await;
+        let mut results = Vec::with_capacity(breakers.len());
+        for circuit_breaker in breakers.iter() {
+            results.push((circuit_breaker.name(), circuit_breaker.check().await));
+        }
+        results
+    }
+
+    /// Returns `None` if the component is not registered.
+    async fn pause_component(&self, name: &str) -> Option<bool> {
+        let components = self.pausable_components.lock().await;
+        Some(components.get(name)?.pause())
+    }
+
+    /// Returns `None` if the component is not registered.
+    async fn resume_component(&self, name: &str) -> Option<bool> {
+        let components = self.pausable_components.lock().await;
+        Some(components.get(name)?.resume())
+    }
 }
 
 #[derive(Debug, Error)]
@@ -35,6 +120,20 @@ pub enum CircuitBreakerError {
     FailedL1Transaction,
     #[error("Replication lag ({lag:?}) is above the threshold ({threshold:?})")]
     ReplicationLag { lag: Duration, threshold: Duration },
+    #[error("L1 balance of {address:?} ({balance} wei) is below the threshold ({threshold} wei)")]
+    L1BalanceTooLow {
+        address: Address,
+        balance: U256,
+        threshold: U256,
+    },
+    #[error("DA dispatch backlog ({backlog} L1 batches) is above the threshold ({threshold})")]
+    DaDispatchBacklog { backlog: u32, threshold: u32 },
+    #[error("Proof generation lag ({lag} L1 batches) is above the threshold ({threshold})")]
+    ProofGenerationLag { lag: u32, threshold: u32 },
+    #[error("Latest base token ratio is stale ({age:?}); the threshold is {threshold:?}")]
+    StaleBaseTokenRatio { age: Duration, threshold: Duration },
+    /// Error running the check (e.g., a failed DB query or L1 request). Doesn't trip the circuit breaker;
+    /// the check is retried on the next iteration.
     #[error("Internal error running circuit breaker checks")]
     Internal(#[from] anyhow::Error),
 }
@@ -45,11 +144,31 @@ impl From<zksync_dal::DalError> for CircuitBreakerError {
     }
 }
 
-/// Checks circuit breakers
+#[derive(Debug, Serialize)]
+struct TrippedCircuitBreaker {
+    action: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    paused_components: Vec<String>,
+    error: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CircuitBreakersHealthDetails {
+    tripped: HashMap<&'static str, TrippedCircuitBreaker>,
+}
+
+/// Checks circuit breakers and takes actions configured for the tripped ones.
 #[derive(Debug)]
 pub struct CircuitBreakerChecker {
     circuit_breakers: Arc<CircuitBreakers>,
     sync_interval: Duration,
+    actions: CircuitBreakerActions,
+    health_updater: HealthUpdater,
+    /// Components requested to be paused by each tripped circuit breaker with the `pause` action.
+    pause_requests: HashMap<&'static str, Vec<String>>,
+    /// Components actually paused by the checker. Components paused by other means are not resumed
+    /// when circuit breakers recover.
+    paused_components: HashSet<String>,
 }
 
 #[async_trait::async_trait]
@@ -64,23 +183,147 @@ impl CircuitBreakerChecker {
         Self {
             circuit_breakers,
             sync_interval,
+            actions: CircuitBreakerActions::default(),
+            health_updater: ReactiveHealthCheck::new("circuit_breakers").1,
+            pause_requests: HashMap::new(),
+            paused_components: HashSet::new(),
         }
     }
 
+    /// Sets actions taken when circuit breakers trip. By default, any tripped circuit breaker stops the node.
+    /// Internal errors when running checks never trip circuit breakers; they are logged, and the checks are retried.
+    #[must_use]
+    pub fn with_actions(mut self, actions: CircuitBreakerActions) -> Self {
+        self.actions = actions;
+        self
+    }
+
+    /// Returns the health check reporting tripped circuit breakers that didn't stop the node.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
     pub async fn check(&self) -> Result<(), CircuitBreakerError> {
         self.circuit_breakers.check().await?;
 
         Ok(())
     }
 
-    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+    async fn check_and_apply_actions(&mut self) -> anyhow::Result<()> {
+        let mut tripped = HashMap::new();
+        let mut failed_checks = HashSet::new();
+        for (name, result) in self.circuit_breakers.check_each().await {
+            let error = match result {
+                Ok(()) => {
+                    METRICS.tripped[&name].set(0);
+                    continue;
+                }
+                Err(CircuitBreakerError::Internal(err)) => {
+                    // The state of the circuit breaker is unknown, so we keep the actions taken for it previously
+                    // (e.g., paused components remain paused).
+                    tracing::warn!("Failed checking circuit breaker `{name}`, will retry: {err:#}");
+                    METRICS.check_errors[&name].inc();
+                    failed_checks.insert(name);
+                    continue;
+                }
+                Err(error) => error,
+            };
+            METRICS.tripped[&name].set(1);
+
+            let (action, paused_components) = match self.actions.get(name).clone() {
+                CircuitBreakerAction::Stop => {
+                    return Err(anyhow::format_err!(
+                        "Circuit breaker error. Reason: {error}"
+                    ));
+                }
+                CircuitBreakerAction::Alert => {
+                    tracing::warn!("Circuit breaker `{name}` is tripped: {error}");
+                    ("alert", vec![])
+                }
+                CircuitBreakerAction::Pause(components) => {
+                    tracing::warn!(
+                        "Circuit breaker `{name}` is tripped: {error}; pausing components {components:?}"
+                    );
+                    for component in &components {
+                        self.pause_component(component).await;
+                    }
+                    self.pause_requests.insert(name, components.clone());
+                    ("pause", components)
+                }
+            };
+            tripped.insert(
+                name,
+                TrippedCircuitBreaker {
+                    action,
+                    paused_components,
+                    error: error.to_string(),
+                },
+            );
+        }
+
+        let recovered_breakers: Vec<_> = self
+            .pause_requests
+            .keys()
+            .copied()
+            .filter(|name| !tripped.contains_key(name) && !failed_checks.contains(name))
+            .collect();
+        for name in recovered_breakers {
+            let components = self.pause_requests.remove(name).unwrap_or_default();
+            tracing::info!("Circuit breaker `{name}` has recovered");
+            for component in components {
+                // Other tripped circuit breakers may still require the component to be paused.
+                let still_requested = self
+                    .pause_requests
+                    .values()
+                    .any(|requested| requested.contains(&component));
+                if !still_requested && self.paused_components.remove(&component) {
+                    self.resume_component(&component).await;
+                }
+            }
+        }
+
+        let health = if tripped.is_empty() {
+            Health::from(HealthStatus::Ready)
+        } else {
+            Health::from(HealthStatus::Affected)
+                .with_details(CircuitBreakersHealthDetails { tripped })
+        };
+        self.health_updater.update(health);
+        Ok(())
+    }
+
+    async fn pause_component(&mut self, component: &str) {
+        if self.paused_components.contains(component) {
+            return;
+        }
+        match self.circuit_breakers.pause_component(component).await {
+            Some(true) => {
+                tracing::info!("Paused component `{component}`");
+                self.paused_components.insert(component.to_owned());
+            }
+            Some(false) => {
+                tracing::debug!("Component `{component}` is already paused");
+            }
+            None => {
+                tracing::warn!(
+                    "Cannot pause component `{component}`: it is not running on this node or doesn't support pausing"
+                );
+            }
+        }
+    }
+
+    async fn resume_component(&self, component: &str) {
+        match self.circuit_breakers.resume_component(component).await {
+            Some(true) => tracing::info!("Resumed component `{component}`"),
+            Some(false) => tracing::info!("Component `{component}` was resumed by other means"),
+            None => { /* Cannot happen: only registered components are paused */ }
+        }
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         tracing::info!("running circuit breaker checker...");
         while !*stop_receiver.borrow_and_update() {
-            if let Err(error) = self.check().await {
-                return Err(anyhow::format_err!(
-                    "Circuit breaker error. Reason: {error}"
-                ));
-            }
+            self.check_and_apply_actions().await?;
             // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
             tokio::time::timeout(self.sync_interval, stop_receiver.changed())
                 .await
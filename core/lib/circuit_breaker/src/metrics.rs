@@ -2,13 +2,25 @@
 
 use std::time::Duration;
 
-use vise::{Gauge, Global, Metrics};
+use vise::{Counter, Gauge, Global, LabeledFamily, Metrics};
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "circuit_breaker")]
 pub(crate) struct CircuitBreakerMetrics {
     /// Replication lag for Postgres in seconds.
     pub replication_lag: Gauge<Duration>,
+    /// Number of L1 batches awaiting dispatch to the DA layer.
+    pub da_dispatch_backlog: Gauge<u64>,
+    /// Number of sealed L1 batches without a generated proof.
+    pub proof_generation_lag: Gauge<u64>,
+    /// Age of the latest base token ratio.
+    pub base_token_ratio_age: Gauge<Duration>,
+    /// Whether a circuit breaker is currently tripped (1) or not (0).
+    #[metrics(labels = ["breaker"])]
+    pub tripped: LabeledFamily<&'static str, Gauge<u64>>,
+    /// Number of internal errors running circuit breaker checks.
+    #[metrics(labels = ["breaker"])]
+    pub check_errors: LabeledFamily<&'static str, Counter>,
 }
 
 #[vise::register]
@@ -0,0 +1,40 @@
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+use crate::{metrics::METRICS, CircuitBreaker, CircuitBreakerError};
+
+/// Checks the number of sealed L1 batches without a generated (or skipped) proof.
+#[derive(Debug)]
+pub struct ProofGenerationLagChecker {
+    pub pool: ConnectionPool<Core>,
+    pub lag_limit: u32,
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for ProofGenerationLagChecker {
+    fn name(&self) -> &'static str {
+        "proof_generation_lag"
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let mut connection = self.pool.connection_tagged("circuit_breaker").await?;
+        let Some(sealed_batch) = connection.blocks_dal().get_sealed_l1_batch_number().await? else {
+            return Ok(());
+        };
+        let oldest_not_generated_batch = connection
+            .proof_generation_dal()
+            .get_oldest_not_generated_batch()
+            .await?;
+        drop(connection);
+
+        let lag = oldest_not_generated_batch
+            .map_or(0, |oldest| (sealed_batch.0 + 1).saturating_sub(oldest.0));
+        METRICS.proof_generation_lag.set(lag.into());
+        if lag > self.lag_limit {
+            return Err(CircuitBreakerError::ProofGenerationLag {
+                lag,
+                threshold: self.lag_limit,
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use zksync_health_check::CheckHealth;
+
+use super::*;
+
+#[derive(Debug)]
+struct MockBreaker {
+    name: &'static str,
+    tripped: Arc<AtomicBool>,
+    failing: Arc<AtomicBool>,
+}
+
+impl MockBreaker {
+    fn new(name: &'static str) -> (Self, Arc<AtomicBool>, Arc<AtomicBool>) {
+        let tripped = Arc::<AtomicBool>::default();
+        let failing = Arc::<AtomicBool>::default();
+        let this = Self {
+            name,
+            tripped: tripped.clone(),
+            failing: failing.clone(),
+        };
+        (this, tripped, failing)
+    }
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for MockBreaker {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        if self.failing.load(Ordering::SeqCst) {
+            Err(CircuitBreakerError::Internal(anyhow::anyhow!("failed")))
+        } else if self.tripped.load(Ordering::SeqCst) {
+            Err(CircuitBreakerError::FailedL1Transaction)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockComponent {
+    paused: AtomicBool,
+}
+
+impl PausableComponent for MockComponent {
+    fn pause(&self) -> bool {
+        !self.paused.swap(true, Ordering::SeqCst)
+    }
+
+    fn resume(&self) -> bool {
+        self.paused.swap(false, Ordering::SeqCst)
+    }
+}
+
+async fn create_checker(
+    actions: &str,
+) -> (
+    CircuitBreakerChecker,
+    [Arc<AtomicBool>; 2],
+    Arc<MockComponent>,
+) {
+    let (checker, tripped, _, component) = create_checker_with_failures(actions).await;
+    (checker, tripped, component)
+}
+
+async fn create_checker_with_failures(
+    actions: &str,
+) -> (
+    CircuitBreakerChecker,
+    [Arc<AtomicBool>; 2],
+    [Arc<AtomicBool>; 2],
+    Arc<MockComponent>,
+) {
+    let breakers = Arc::<CircuitBreakers>::default();
+    let (first_breaker, first_tripped, first_failing) = MockBreaker::new("first");
+    breakers.insert(Box::new(first_breaker)).await;
+    let (second_breaker, second_tripped, second_failing) = MockBreaker::new("second");
+    breakers.insert(Box::new(second_breaker)).await;
+    let component = Arc::<MockComponent>::default();
+    breakers
+        .insert_pausable_component("component", component.clone())
+        .await;
+
+    let checker = CircuitBreakerChecker::new(breakers, Duration::from_secs(1))
+        .with_actions(actions.parse().unwrap());
+    (
+        checker,
+        [first_tripped, second_tripped],
+        [first_failing, second_failing],
+        component,
+    )
+}
+
+#[tokio::test]
+async fn stop_action() {
+    let (mut checker, [first_tripped, _], _) = create_checker("second=alert").await;
+    checker.check_and_apply_actions().await.unwrap();
+    first_tripped.store(true, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap_err();
+}
+
+#[tokio::test]
+async fn alert_action() {
+    let (mut checker, [first_tripped, _], component) =
+        create_checker("first=alert,second=alert").await;
+    let health_check = checker.health_check();
+    first_tripped.store(true, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap();
+
+    let health = health_check.check_health().await;
+    assert_eq!(health.status(), HealthStatus::Affected);
+    let details = health.details().unwrap();
+    assert_eq!(details["tripped"]["first"]["action"], "alert");
+    assert!(!component.paused.load(Ordering::SeqCst));
+
+    first_tripped.store(false, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap();
+    let health = health_check.check_health().await;
+    assert_eq!(health.status(), HealthStatus::Ready);
+}
+
+#[tokio::test]
+async fn pause_action() {
+    let (mut checker, [first_tripped, second_tripped], component) =
+        create_checker("first=pause:component+unknown,second=pause:component").await;
+    first_tripped.store(true, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap();
+    assert!(component.paused.load(Ordering::SeqCst));
+
+    second_tripped.store(true, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap();
+    assert!(component.paused.load(Ordering::SeqCst));
+
+    // The component must remain paused while the second circuit breaker is tripped.
+    first_tripped.store(false, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap();
+    assert!(component.paused.load(Ordering::SeqCst));
+
+    second_tripped.store(false, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap();
+    assert!(!component.paused.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn pause_action_does_not_resume_externally_paused_component() {
+    let (mut checker, [first_tripped, _], component) =
+        create_checker("first=pause:component").await;
+    assert!(component.pause());
+    first_tripped.store(true, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap();
+    first_tripped.store(false, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap();
+    assert!(component.paused.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn internal_errors_do_not_trip_circuit_breakers() {
+    let (mut checker, [first_tripped, _], [first_failing, second_failing], component) =
+        create_checker_with_failures("second=pause:component").await;
+    let health_check = checker.health_check();
+    first_failing.store(true, Ordering::SeqCst);
+    second_failing.store(true, Ordering::SeqCst);
+    // The first circuit breaker has the default `stop` action, but the node must not be stopped.
+    checker.check_and_apply_actions().await.unwrap();
+    assert_eq!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+    assert!(!component.paused.load(Ordering::SeqCst));
+
+    first_failing.store(false, Ordering::SeqCst);
+    first_tripped.store(true, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap_err();
+}
+
+#[tokio::test]
+async fn paused_components_are_not_resumed_on_internal_errors() {
+    let (mut checker, [_, second_tripped], [_, second_failing], component) =
+        create_checker_with_failures("second=pause:component").await;
+    second_tripped.store(true, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap();
+    assert!(component.paused.load(Ordering::SeqCst));
+
+    second_failing.store(true, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap();
+    assert!(component.paused.load(Ordering::SeqCst));
+
+    second_failing.store(false, Ordering::SeqCst);
+    second_tripped.store(false, Ordering::SeqCst);
+    checker.check_and_apply_actions().await.unwrap();
+    assert!(!component.paused.load(Ordering::SeqCst));
+}
+
+#[test]
+fn validating_actions() {
+    validate_actions(&"first=alert,second=pause:state_keeper".parse().unwrap()).unwrap();
+    let err = validate_actions(&"first=pause:state_keeper+eth_tx_manager".parse().unwrap())
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("eth_tx_manager"), "{err}");
+}
@@ -1,7 +1,7 @@
 use std::{
     collections::HashMap,
     fmt,
-    net::SocketAddr,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     num::{NonZeroU32, NonZeroUsize},
     str::FromStr,
     time::Duration,
@@ -223,6 +223,12 @@ pub struct Web3JsonRpcConfig {
     /// Configuration options for the deployment allow list
     #[serde(default)]
     pub deployment_allowlist: DeploymentAllowlist,
+    /// Port for the admin JSON-RPC server serving the `admin_` namespace. The server is only started
+    /// if both this port and the admin token in [secrets](crate::configs::secrets::ApiSecrets) are set.
+    pub admin_port: Option<u16>,
+    /// IP address the admin JSON-RPC server is bound to. By default, the server is only accessible
+    /// from the local host (`127.0.0.1`).
+    pub admin_host: Option<IpAddr>,
 }
 
 impl Web3JsonRpcConfig {
@@ -262,6 +268,8 @@ impl Web3JsonRpcConfig {
             api_namespaces: None,
             extended_api_tracing: false,
             deployment_allowlist: DeploymentAllowlist::default(),
+            admin_port: None,
+            admin_host: None,
         }
     }
 
@@ -350,6 +358,12 @@ impl Web3JsonRpcConfig {
     pub fn mempool_cache_size(&self) -> usize {
         self.mempool_cache_size.unwrap_or(10_000)
     }
+
+    /// Returns the bind address for the admin JSON-RPC server, or `None` if the server is disabled.
+    pub fn admin_bind_addr(&self) -> Option<SocketAddr> {
+        let host = self.admin_host.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        Some(SocketAddr::new(host, self.admin_port?))
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -362,6 +376,11 @@ pub struct HealthCheckConfig {
     /// Time limit in milliseconds to abort a health check and return "not ready" status for the corresponding component.
     /// If not specified, the default value in the health check crate will be used.
     pub hard_time_limit_ms: Option<u64>,
+    /// Maximum number of health transitions retained for each component and exposed on the `/health/history` endpoint.
+    /// If not specified, the default value in the health check crate will be used.
+    pub history_capacity: Option<usize>,
+    /// URL to which JSON notifications about component health transitioning to `affected` or `shut_down` are POSTed.
+    pub webhook_url: Option<String>,
 }
 
 impl HealthCheckConfig {
@@ -1,6 +1,7 @@
-use std::{str::FromStr, time::Duration};
+use std::{collections::HashMap, fmt, str::FromStr, time::Duration};
 
-use serde::{Deserialize, Serialize};
+use anyhow::Context as _;
+use serde::{de, Deserialize, Deserializer, Serialize};
 use zksync_basic_types::{commitment::L1BatchCommitmentMode, Address, H256};
 
 /// An enum that represents the version of the fee model to use.
@@ -179,6 +180,17 @@ pub struct CircuitBreakerConfig {
     pub http_req_max_retry_number: usize,
     pub http_req_retry_interval_sec: u8,
     pub replication_lag_limit_sec: Option<u32>,
+    /// Minimum L1 balance of operator accounts used by the eth sender, in gwei.
+    pub min_operator_l1_balance_gwei: Option<u64>,
+    /// Maximum number of L1 batches awaiting dispatch to the DA layer.
+    pub da_dispatch_backlog_limit: Option<u32>,
+    /// Maximum number of sealed L1 batches without a generated proof.
+    pub proof_generation_lag_limit: Option<u32>,
+    /// Maximum age of the latest base token ratio, in seconds.
+    pub base_token_ratio_staleness_limit_sec: Option<u32>,
+    /// Actions taken when circuit breakers trip. By default, a tripped circuit breaker stops the node.
+    #[serde(default)]
+    pub actions: CircuitBreakerActions,
 }
 
 impl CircuitBreakerConfig {
@@ -194,6 +206,117 @@ impl CircuitBreakerConfig {
         self.replication_lag_limit_sec
             .map(|limit| Duration::from_secs(limit.into()))
     }
+
+    pub fn base_token_ratio_staleness_limit(&self) -> Option<Duration> {
+        self.base_token_ratio_staleness_limit_sec
+            .map(|limit| Duration::from_secs(limit.into()))
+    }
+}
+
+/// Action taken when a circuit breaker trips.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CircuitBreakerAction {
+    /// Stop the node.
+    #[default]
+    Stop,
+    /// Pause the specified components (e.g., `state_keeper`) until the circuit breaker recovers.
+    Pause(Vec<String>),
+    /// Only report the tripped circuit breaker via the health check.
+    Alert,
+}
+
+impl FromStr for CircuitBreakerAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            "stop" => Self::Stop,
+            "alert" => Self::Alert,
+            s => {
+                let components = s
+                    .strip_prefix("pause:")
+                    .with_context(|| format!("unknown circuit breaker action: `{s}`"))?;
+                let components: Vec<_> = components
+                    .split('+')
+                    .map(|component| component.trim().to_owned())
+                    .filter(|component| !component.is_empty())
+                    .collect();
+                anyhow::ensure!(!components.is_empty(), "no components to pause in `{s}`");
+                Self::Pause(components)
+            }
+        })
+    }
+}
+
+/// Per-circuit breaker actions. Circuit breakers not mentioned in the mapping use [`CircuitBreakerAction::Stop`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CircuitBreakerActions(HashMap<String, CircuitBreakerAction>);
+
+impl FromIterator<(String, CircuitBreakerAction)> for CircuitBreakerActions {
+    fn from_iter<I: IntoIterator<Item = (String, CircuitBreakerAction)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl FromStr for CircuitBreakerActions {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut actions = HashMap::new();
+        for part in s.split(',').filter(|part| !part.trim().is_empty()) {
+            let (breaker, action) = part.split_once('=').with_context(|| {
+                format!("Incorrect circuit breaker action specified: expected `<breaker>=<action>`, got `{part}`")
+            })?;
+            let breaker = breaker.trim();
+            let action = action
+                .parse()
+                .with_context(|| format!("invalid action for circuit breaker `{breaker}`"))?;
+            if actions.insert(breaker.to_owned(), action).is_some() {
+                anyhow::bail!("Circuit breaker `{breaker}` has multiple actions specified");
+            }
+        }
+        Ok(Self(actions))
+    }
+}
+
+impl CircuitBreakerActions {
+    /// Gets the action for the specified circuit breaker.
+    pub fn get(&self, breaker: &str) -> &CircuitBreakerAction {
+        static DEFAULT_ACTION: CircuitBreakerAction = CircuitBreakerAction::Stop;
+        self.0.get(breaker).unwrap_or(&DEFAULT_ACTION)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &CircuitBreakerAction)> + '_ {
+        self.0
+            .iter()
+            .map(|(breaker, action)| (breaker.as_str(), action))
+    }
+}
+
+impl<'de> Deserialize<'de> for CircuitBreakerActions {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ParseVisitor;
+
+        impl<'v> de::Visitor<'v> for ParseVisitor {
+            type Value = CircuitBreakerActions;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str(
+                    "comma-separated list of <breaker>=stop|alert|pause:<component>+... tuples, \
+                     such as: l1_balance=alert,da_dispatch_backlog=pause:state_keeper",
+                )
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(ParseVisitor)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -229,3 +352,38 @@ pub struct TimestampAsserterConfig {
     /// Minimum time between current block.timestamp and the end of the asserted range
     pub min_time_till_end_sec: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_circuit_breaker_actions() {
+        let actions: CircuitBreakerActions =
+            "l1_balance=alert, da_dispatch_backlog = pause:state_keeper+eth_tx_aggregator,replication_lag=stop"
+                .parse()
+                .unwrap();
+        assert_eq!(*actions.get("l1_balance"), CircuitBreakerAction::Alert);
+        assert_eq!(
+            *actions.get("da_dispatch_backlog"),
+            CircuitBreakerAction::Pause(vec![
+                "state_keeper".to_owned(),
+                "eth_tx_aggregator".to_owned()
+            ])
+        );
+        assert_eq!(*actions.get("replication_lag"), CircuitBreakerAction::Stop);
+        assert_eq!(
+            *actions.get("failed_l1_transaction"),
+            CircuitBreakerAction::Stop
+        );
+
+        for invalid in [
+            "l1_balance",
+            "l1_balance=halt",
+            "l1_balance=pause:",
+            "a=stop,a=alert",
+        ] {
+            invalid.parse::<CircuitBreakerActions>().unwrap_err();
+        }
+    }
+}
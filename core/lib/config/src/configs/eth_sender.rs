@@ -55,6 +55,7 @@ impl EthConfig {
                 time_in_mempool_in_l1_blocks_cap: 1800,
                 is_verifier_pre_fflonk: true,
                 gas_limit_mode: GasLimitMode::Maximum,
+                tee_quorum_required_from_batch: None,
             }),
             gas_adjuster: Some(GasAdjusterConfig {
                 default_priority_fee_per_gas: 1000000000,
@@ -148,6 +149,11 @@ pub struct SenderConfig {
     pub is_verifier_pre_fflonk: bool,
     #[serde(default = "SenderConfig::default_gas_limit_mode")]
     pub gas_limit_mode: GasLimitMode,
+    /// If set, L1 batches starting from this number are executed only after reaching the TEE proof quorum
+    /// configured for the proof data handler. Batches before it are executed regardless of TEE proofs.
+    /// Requires TEE support with non-empty `quorum_tee_types` in the proof data handler config.
+    #[serde(default)]
+    pub tee_quorum_required_from_batch: Option<u32>,
 }
 
 impl SenderConfig {
@@ -80,6 +80,10 @@ pub struct ExperimentalVmPlaygroundConfig {
     /// require to drop the RocksDB cache.
     #[serde(default)]
     pub reset: bool,
+    /// If set to true, VM divergences detected by the playground will be recorded in the Postgres divergence registry.
+    /// Otherwise, divergences are only dumped to the object store (if it's configured) or panic the playground.
+    #[serde(default)]
+    pub record_divergences: bool,
 }
 
 impl Default for ExperimentalVmPlaygroundConfig {
@@ -90,6 +94,7 @@ impl Default for ExperimentalVmPlaygroundConfig {
             first_processed_batch: L1BatchNumber(0),
             window_size: Self::default_window_size(),
             reset: false,
+            record_divergences: false,
         }
     }
 }
@@ -1,6 +1,63 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ExternalProofIntegrationApiConfig {
     pub http_port: u16,
+    /// Time after which a batch leased by an external prover can be leased again if no proof was submitted for it.
+    #[serde(default = "ExternalProofIntegrationApiConfig::default_lease_timeout_in_secs")]
+    pub lease_timeout_in_secs: u64,
+    /// API keys of external provers accepted in the `X-API-Key` header by the leasing, proof submission and batch status
+    /// endpoints. If empty, these endpoints reject all requests.
+    #[serde(default)]
+    pub api_keys: Vec<ExternalProverApiKey>,
+    /// Maximum number of unexpired leases held by a single external prover.
+    #[serde(default = "ExternalProofIntegrationApiConfig::default_max_leases_per_prover")]
+    pub max_leases_per_prover: usize,
+    /// Directory with the SNARK wrapper verification keys (`verification_snark_key.json` and, optionally,
+    /// `fflonk_verification_snark_key.json`) used to verify submitted proofs. If not set, batches cannot be leased
+    /// and proofs cannot be submitted.
+    #[serde(default)]
+    pub verification_keys_path: Option<String>,
+}
+
+/// API key of an external prover. In env variables, keys are specified as `<prover_id>:<api_key>`.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(try_from = "String")]
+pub struct ExternalProverApiKey {
+    /// ID of the prover authenticated by the key. Used as the owner of batch leases.
+    pub prover_id: String,
+    pub api_key: String,
+}
+
+impl TryFrom<String> for ExternalProverApiKey {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let (prover_id, api_key) = s
+            .split_once(':')
+            .context("API key must be specified as `<prover_id>:<api_key>`")?;
+        anyhow::ensure!(!prover_id.is_empty(), "prover ID is empty");
+        anyhow::ensure!(!api_key.is_empty(), "API key is empty");
+        Ok(Self {
+            prover_id: prover_id.to_owned(),
+            api_key: api_key.to_owned(),
+        })
+    }
+}
+
+impl ExternalProofIntegrationApiConfig {
+    pub const fn default_lease_timeout_in_secs() -> u64 {
+        3_600
+    }
+
+    pub const fn default_max_leases_per_prover() -> usize {
+        1
+    }
+
+    pub fn lease_timeout(&self) -> Duration {
+        Duration::from_secs(self.lease_timeout_in_secs)
+    }
 }
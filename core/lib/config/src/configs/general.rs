@@ -21,7 +21,7 @@ use crate::{
     ObjectStoreConfig, PostgresConfig, SnapshotsCreatorConfig,
 };
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct GeneralConfig {
     pub postgres_config: Option<PostgresConfig>,
     pub api_config: Option<ApiConfig>,
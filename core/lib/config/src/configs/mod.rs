@@ -24,8 +24,10 @@ pub use self::{
     proof_data_handler::{ProofDataHandlerConfig, TeeConfig},
     prover_job_monitor::ProverJobMonitorConfig,
     pruning::PruningConfig,
+    reloadable::{GasPricingMultipliers, MempoolFilters, ReloadableConfig},
     secrets::{
-        ContractVerifierSecrets, DataAvailabilitySecrets, DatabaseSecrets, L1Secrets, Secrets,
+        ApiSecrets, ContractVerifierSecrets, DataAvailabilitySecrets, DatabaseSecrets, L1Secrets,
+        Secrets,
     },
     snapshot_recovery::SnapshotRecoveryConfig,
     snapshots_creator::SnapshotsCreatorConfig,
@@ -61,6 +63,7 @@ pub mod observability;
 pub mod proof_data_handler;
 pub mod prover_job_monitor;
 pub mod pruning;
+mod reloadable;
 pub mod secrets;
 pub mod snapshot_recovery;
 pub mod snapshots_creator;
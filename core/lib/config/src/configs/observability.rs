@@ -11,10 +11,13 @@ pub struct ObservabilityConfig {
     /// Opentelemetry configuration.
     pub opentelemetry: Option<OpentelemetryConfig>,
     /// Format of the logs as expected by the `vlog` crate.
-    /// Currently must be either `plain` or `json`.
+    /// Currently must be one of `plain`, `json` or `logfmt`.
     pub log_format: String,
     /// Log directives in format that is used in `RUST_LOG`
     pub log_directives: Option<String>,
+    /// Rate limits for noisy log targets in the `target=max_events_per_second` format, separated by commas
+    /// (e.g., `zksync_state_keeper=100,zksync_eth_sender=50`). Warnings and errors are never rate-limited.
+    pub log_sampling: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use serde::Deserialize;
-use zksync_basic_types::L1BatchNumber;
+use zksync_basic_types::{tee_types::TeeType, L1BatchNumber};
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct TeeConfig {
@@ -15,6 +15,29 @@ pub struct TeeConfig {
     pub tee_proof_generation_timeout_in_secs: u16,
     /// Timeout in hours after which a batch will be permanently ignored if repeated retries failed.
     pub tee_batch_permanently_ignored_timeout_in_hours: u16,
+    /// If true, attestation quotes of TEE provers are verified on registration, and proofs are
+    /// only accepted if signed by a key with a verified attestation.
+    #[serde(default = "TeeConfig::default_verify_attestations")]
+    pub verify_attestations: bool,
+    /// Hex-encoded MRENCLAVE values of SGX enclaves allowed to register.
+    #[serde(default)]
+    pub allowed_mrenclaves: Vec<String>,
+    /// Hex-encoded MRTD values of TDX trust domains allowed to register.
+    #[serde(default)]
+    pub allowed_mrtds: Vec<String>,
+    /// TCB statuses of verified quotes accepted on registration, e.g. `UpToDate` or `SWHardeningNeeded`.
+    #[serde(default = "TeeConfig::default_allowed_tcb_statuses")]
+    pub allowed_tcb_statuses: Vec<String>,
+    /// Directory with quote verification collateral fetched from a PCCS: `sgx.json` for SGX quotes
+    /// and `tdx.json` for TDX quotes. Files are re-read on each registration, so they can be refreshed
+    /// without restarting the server.
+    #[serde(default)]
+    pub attestation_collateral_path: Option<String>,
+    /// TEE types required for a batch to reach the TEE proof quorum. The quorum is reached once the batch has
+    /// a proof of each listed type, all signed by distinct keys with a registered (and, if `verify_attestations`
+    /// is enabled, verified) attestation. If empty, the quorum is not evaluated.
+    #[serde(default)]
+    pub quorum_tee_types: Vec<TeeType>,
 }
 
 impl Default for TeeConfig {
@@ -26,6 +49,12 @@ impl Default for TeeConfig {
                 Self::default_tee_proof_generation_timeout_in_secs(),
             tee_batch_permanently_ignored_timeout_in_hours:
                 Self::default_tee_batch_permanently_ignored_timeout_in_hours(),
+            verify_attestations: Self::default_verify_attestations(),
+            allowed_mrenclaves: Vec::new(),
+            allowed_mrtds: Vec::new(),
+            allowed_tcb_statuses: Self::default_allowed_tcb_statuses(),
+            attestation_collateral_path: None,
+            quorum_tee_types: Vec::new(),
         }
     }
 }
@@ -47,6 +76,14 @@ impl TeeConfig {
         10 * 24
     }
 
+    pub fn default_verify_attestations() -> bool {
+        false
+    }
+
+    pub fn default_allowed_tcb_statuses() -> Vec<String> {
+        vec!["UpToDate".to_owned()]
+    }
+
     pub fn tee_proof_generation_timeout(&self) -> Duration {
         Duration::from_secs(self.tee_proof_generation_timeout_in_secs.into())
     }
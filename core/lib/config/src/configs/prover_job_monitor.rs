@@ -61,6 +61,9 @@ pub struct ProverJobMonitorConfig {
     /// The interval between runs for Witness Job Queuer.
     #[serde(default = "ProverJobMonitorConfig::default_witness_job_queuer_run_interval_ms")]
     pub witness_job_queuer_run_interval_ms: u64,
+    /// The interval between runs for Batch Priority Ager, which raises priority classes of jobs for aged batches.
+    #[serde(default = "ProverJobMonitorConfig::default_batch_priority_ager_run_interval_ms")]
+    pub batch_priority_ager_run_interval_ms: u64,
     /// HTTP port of the ProverJobMonitor to send requests to.
     pub http_port: u16,
 }
@@ -185,6 +188,16 @@ impl ProverJobMonitorConfig {
         10_000
     }
 
+    /// The interval between runs for Batch Priority Ager.
+    pub fn batch_priority_ager_run_interval(&self) -> Duration {
+        Duration::from_millis(self.batch_priority_ager_run_interval_ms)
+    }
+
+    /// Default batch_priority_ager_run_interval_ms -- 1 minute
+    pub fn default_batch_priority_ager_run_interval_ms() -> u64 {
+        60_000
+    }
+
     /// Default attempts reporter run interval -- 10 seconds
     pub fn default_attempts_reporter_run_interval_ms() -> u64 {
         10_000
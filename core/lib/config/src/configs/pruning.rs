@@ -16,4 +16,8 @@ pub struct PruningConfig {
     /// the retention period greater than that implicitly imposed by other criteria (e.g., 7 or 30 days).
     /// If set to 0, L1 batches will not be retained based on their timestamp. The default value is 1 hour.
     pub data_retention_sec: Option<u64>,
+    /// Number of L1 batches already pruned in Postgres that the Merkle tree will still retain. Storage values
+    /// (with proofs) for these batches can be read from the tree via `zks_getProof` only; other methods are not served
+    /// for pruned blocks. The default value is 0, i.e., the tree is pruned in lockstep with Postgres.
+    pub tree_retained_l1_batches: Option<u32>,
 }
@@ -0,0 +1,223 @@
+//! Part of the node configuration that can be changed at runtime without restarting the node.
+
+use std::num::NonZeroU32;
+
+use crate::configs::GeneralConfig;
+
+/// Multipliers applied by the gas adjuster to the settlement layer gas and pubdata prices.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GasPricingMultipliers {
+    pub internal_l1_pricing_multiplier: f64,
+    pub internal_pubdata_pricing_multiplier: f64,
+}
+
+/// Filters applied when syncing the state keeper mempool with the database.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MempoolFilters {
+    pub l1_to_l2_txs_paused: bool,
+    pub skip_unsafe_deposit_checks: bool,
+}
+
+/// Reloadable part of [`GeneralConfig`]. Fields are `None` if the corresponding config section is missing.
+///
+/// Components opt into reloading explicitly; all other config fields are only read on node startup.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReloadableConfig {
+    /// Rate limit for WebSocket API sessions; applies to already open sessions as well. This is the only rate limit
+    /// enforced by the API server itself, since HTTP rate limiting is expected to be configured on the infra level.
+    pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    pub gas_pricing_multipliers: Option<GasPricingMultipliers>,
+    pub mempool_filters: Option<MempoolFilters>,
+    /// Log directives. `None` if the observability section is missing or doesn't specify directives;
+    /// in this case, directives used on node startup are applied.
+    pub log_directives: Option<String>,
+}
+
+impl GeneralConfig {
+    /// Splits off the reloadable part of the config. In the returned `GeneralConfig`, reloadable fields
+    /// are reset to fixed values, so that it can be compared to check whether any non-reloadable field has changed.
+    pub fn split_reloadable(mut self) -> (Self, ReloadableConfig) {
+        let mut reloadable = ReloadableConfig::default();
+        if let Some(api) = &mut self.api_config {
+            let web3_config = &mut api.web3_json_rpc;
+            reloadable.websocket_requests_per_minute_limit =
+                Some(web3_config.websocket_requests_per_minute_limit());
+            web3_config.websocket_requests_per_minute_limit = None;
+        }
+        if let Some(gas_adjuster) = self.eth.as_mut().and_then(|eth| eth.gas_adjuster.as_mut()) {
+            reloadable.gas_pricing_multipliers = Some(GasPricingMultipliers {
+                internal_l1_pricing_multiplier: gas_adjuster.internal_l1_pricing_multiplier,
+                internal_pubdata_pricing_multiplier: gas_adjuster
+                    .internal_pubdata_pricing_multiplier,
+            });
+            gas_adjuster.internal_l1_pricing_multiplier = 1.0;
+            gas_adjuster.internal_pubdata_pricing_multiplier = 1.0;
+        }
+        if let Some(mempool) = &mut self.mempool_config {
+            reloadable.mempool_filters = Some(MempoolFilters {
+                l1_to_l2_txs_paused: mempool.l1_to_l2_txs_paused,
+                skip_unsafe_deposit_checks: mempool.skip_unsafe_deposit_checks,
+            });
+            mempool.l1_to_l2_txs_paused = false;
+            mempool.skip_unsafe_deposit_checks = false;
+        }
+        if let Some(observability) = &mut self.observability {
+            reloadable.log_directives = observability.log_directives.take();
+        }
+        (self, reloadable)
+    }
+
+    /// Returns names of the config sections that differ between `self` and `other`.
+    pub fn changed_sections(&self, other: &Self) -> Vec<&'static str> {
+        macro_rules! changed_sections {
+            ($this:expr; $($section:ident),+) => {{
+                // Exhaustive destructuring ensures that new sections are not forgotten.
+                let Self { $($section),+ } = $this;
+                let mut changed = vec![];
+                $(
+                if *$section != other.$section {
+                    changed.push(stringify!($section));
+                }
+                )+
+                changed
+            }};
+        }
+
+        changed_sections!(
+            self;
+            postgres_config,
+            api_config,
+            contract_verifier,
+            circuit_breaker_config,
+            mempool_config,
+            operations_manager_config,
+            state_keeper_config,
+            house_keeper_config,
+            proof_compressor_config,
+            prover_config,
+            prover_gateway,
+            witness_generator_config,
+            prometheus_config,
+            proof_data_handler_config,
+            db_config,
+            eth,
+            snapshot_creator,
+            observability,
+            da_client_config,
+            da_dispatcher_config,
+            protective_reads_writer_config,
+            basic_witness_input_producer_config,
+            commitment_generator,
+            snapshot_recovery,
+            pruning,
+            core_object_store,
+            base_token_adjuster,
+            external_price_api_client_config,
+            consensus_config,
+            external_proof_integration_api_config,
+            experimental_vm_config,
+            prover_job_monitor_config,
+            timestamp_asserter_config
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::{
+        chain::{MempoolConfig, StateKeeperConfig},
+        EthConfig, ObservabilityConfig,
+    };
+
+    fn mempool_config() -> MempoolConfig {
+        MempoolConfig {
+            sync_interval_ms: 10,
+            sync_batch_size: 1_000,
+            capacity: 10_000_000,
+            stuck_tx_timeout: 10,
+            remove_stuck_txs: true,
+            delay_interval: 100,
+            l1_to_l2_txs_paused: false,
+            skip_unsafe_deposit_checks: false,
+        }
+    }
+
+    fn general_config() -> GeneralConfig {
+        GeneralConfig {
+            mempool_config: Some(mempool_config()),
+            eth: Some(EthConfig::for_tests()),
+            observability: Some(ObservabilityConfig {
+                sentry_url: None,
+                sentry_environment: None,
+                opentelemetry: None,
+                log_format: "plain".to_owned(),
+                log_directives: Some("zksync=info".to_owned()),
+                log_sampling: None,
+            }),
+            ..GeneralConfig::default()
+        }
+    }
+
+    #[test]
+    fn splitting_reloadable_config() {
+        let config = general_config();
+        let (static_config, reloadable) = config.clone().split_reloadable();
+        assert_eq!(reloadable.websocket_requests_per_minute_limit, None);
+        assert_eq!(reloadable.mempool_filters, Some(MempoolFilters::default()));
+        assert!(reloadable.gas_pricing_multipliers.is_some());
+        assert_eq!(reloadable.log_directives.as_deref(), Some("zksync=info"));
+
+        let mut updated_config = config.clone();
+        let mempool = updated_config.mempool_config.as_mut().unwrap();
+        mempool.l1_to_l2_txs_paused = true;
+        let gas_adjuster = updated_config
+            .eth
+            .as_mut()
+            .unwrap()
+            .gas_adjuster
+            .as_mut()
+            .unwrap();
+        gas_adjuster.internal_l1_pricing_multiplier = 2.0;
+        updated_config
+            .observability
+            .as_mut()
+            .unwrap()
+            .log_directives = None;
+
+        let (updated_static_config, updated_reloadable) = updated_config.split_reloadable();
+        assert_eq!(updated_static_config, static_config);
+        assert!(updated_static_config
+            .changed_sections(&static_config)
+            .is_empty());
+        assert!(
+            updated_reloadable
+                .mempool_filters
+                .unwrap()
+                .l1_to_l2_txs_paused
+        );
+        assert_eq!(
+            updated_reloadable
+                .gas_pricing_multipliers
+                .unwrap()
+                .internal_l1_pricing_multiplier,
+            2.0
+        );
+        assert_eq!(updated_reloadable.log_directives, None);
+    }
+
+    #[test]
+    fn detecting_changes_in_non_reloadable_sections() {
+        let config = general_config();
+        let (static_config, _) = config.clone().split_reloadable();
+        let mut updated_config = config;
+        updated_config.mempool_config.as_mut().unwrap().capacity = 1;
+        updated_config.state_keeper_config = Some(StateKeeperConfig::for_tests());
+
+        let (updated_static_config, _) = updated_config.split_reloadable();
+        assert_eq!(
+            updated_static_config.changed_sections(&static_config),
+            ["mempool_config", "state_keeper_config"]
+        );
+    }
+}
@@ -36,6 +36,12 @@ pub struct ContractVerifierSecrets {
     pub etherscan_api_key: Option<APIKey>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiSecrets {
+    /// Bearer token required to access the admin JSON-RPC server. If not set, the admin server is disabled.
+    pub admin_token: Option<APIKey>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Secrets {
     pub consensus: Option<ConsensusSecrets>,
@@ -43,6 +49,7 @@ pub struct Secrets {
     pub l1: Option<L1Secrets>,
     pub data_availability: Option<DataAvailabilitySecrets>,
     pub contract_verifier: Option<ContractVerifierSecrets>,
+    pub api: Option<ApiSecrets>,
 }
 
 impl DatabaseSecrets {
@@ -26,6 +26,11 @@ pub struct PostgresRecoveryConfig {
     /// reduce this factor to about 5 if snapshot recovery overloads I/O capacity of the node. Conversely,
     /// if I/O capacity of your infra is high, you may increase concurrency to speed up Postgres recovery.
     pub max_concurrency: Option<NonZeroUsize>,
+    /// Whether to import transaction history (L2 block headers, transactions, receipts and events) included into the snapshot.
+    /// Has no effect if the snapshot doesn't contain history. If pruning is enabled, imported history is removed
+    /// by the first pruning iteration after recovery.
+    #[serde(default)]
+    pub import_history: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -20,6 +20,17 @@ pub struct SnapshotsCreatorConfig {
     pub storage_logs_chunk_size: u64,
     #[serde(default = "SnapshotsCreatorConfig::concurrent_queries_count")]
     pub concurrent_queries_count: u32,
+    /// Number of trailing L1 batches (ending with the snapshot L1 batch) for which to export L2 block headers,
+    /// transactions, receipts and events. This allows nodes recovered from the snapshot to serve `eth_getLogs`
+    /// and receipts for these batches. If set to 0 (the default), history is not exported.
+    #[serde(default)]
+    pub history_l1_batches: u32,
+    /// Maximum number of incremental snapshots built on top of a single full snapshot. If positive, a new snapshot
+    /// only contains storage logs and factory deps changed since the newest complete snapshot, unless the chain
+    /// of incremental snapshots ending in it is already this long; in the latter case, a full snapshot is created.
+    /// Incremental snapshots are only supported for snapshot version 1. If set to 0 (the default), all snapshots are full.
+    #[serde(default)]
+    pub max_incremental_snapshots: u32,
     pub object_store: Option<ObjectStoreConfig>,
 }
 
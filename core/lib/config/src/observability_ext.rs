@@ -1,5 +1,7 @@
 //! Extensions for the `ObservabilityConfig` to install the observability stack.
 
+use anyhow::Context as _;
+
 use crate::configs::ObservabilityConfig;
 
 impl ObservabilityConfig {
@@ -27,7 +29,15 @@ impl TryFrom<ObservabilityConfig> for zksync_vlog::Logs {
     type Error = anyhow::Error;
 
     fn try_from(config: ObservabilityConfig) -> Result<Self, Self::Error> {
-        Ok(zksync_vlog::Logs::new(&config.log_format)?.with_log_directives(config.log_directives))
+        let sampling = config
+            .log_sampling
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .context("invalid log sampling rules")?;
+        Ok(zksync_vlog::Logs::new(&config.log_format)?
+            .with_log_directives(config.log_directives)
+            .with_sampling(sampling))
     }
 }
 
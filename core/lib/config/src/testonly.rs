@@ -6,6 +6,7 @@ use zksync_basic_types::{
     protocol_version::{ProtocolSemanticVersion, ProtocolVersionId, VersionPatch},
     pubdata_da::PubdataSendingMode,
     secrets::{APIKey, SeedPhrase},
+    tee_types::TeeType,
     vm::FastVmMode,
     L1BatchNumber, L1ChainId, L2ChainId, SLChainId,
 };
@@ -93,6 +94,8 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
                 .sample_opt(|| self.sample_range(rng).map(|_| self.sample(rng)).collect()),
             extended_api_tracing: self.sample(rng),
             deployment_allowlist: DeploymentAllowlist::new(None, Some(300)),
+            admin_port: self.sample(rng),
+            admin_host: self.sample_opt(|| std::net::Ipv4Addr::from(rng.gen::<[u8; 4]>()).into()),
         }
     }
 }
@@ -103,6 +106,8 @@ impl Distribution<configs::api::HealthCheckConfig> for EncodeDist {
             port: self.sample(rng),
             slow_time_limit_ms: self.sample(rng),
             hard_time_limit_ms: self.sample(rng),
+            history_capacity: self.sample(rng),
+            webhook_url: self.sample(rng),
         }
     }
 }
@@ -187,6 +192,27 @@ impl Distribution<configs::chain::CircuitBreakerConfig> for EncodeDist {
             http_req_max_retry_number: self.sample(rng),
             http_req_retry_interval_sec: self.sample(rng),
             replication_lag_limit_sec: self.sample(rng),
+            min_operator_l1_balance_gwei: self.sample(rng),
+            da_dispatch_backlog_limit: self.sample(rng),
+            proof_generation_lag_limit: self.sample(rng),
+            base_token_ratio_staleness_limit_sec: self.sample(rng),
+            actions: [
+                ("l1_balance".to_owned(), self.sample(rng)),
+                ("da_dispatch_backlog".to_owned(), self.sample(rng)),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+}
+
+impl Distribution<configs::chain::CircuitBreakerAction> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::chain::CircuitBreakerAction {
+        type T = configs::chain::CircuitBreakerAction;
+        match rng.gen_range(0..3) {
+            0 => T::Stop,
+            1 => T::Pause(vec![self.sample(rng)]),
+            _ => T::Alert,
         }
     }
 }
@@ -296,6 +322,7 @@ impl Distribution<configs::ExperimentalVmPlaygroundConfig> for EncodeDist {
             first_processed_batch: L1BatchNumber(rng.gen()),
             window_size: rng.gen(),
             reset: self.sample(rng),
+            record_divergences: self.sample(rng),
         }
     }
 }
@@ -402,6 +429,7 @@ impl Distribution<configs::eth_sender::SenderConfig> for EncodeDist {
             time_in_mempool_in_l1_blocks_cap: self.sample(rng),
             is_verifier_pre_fflonk: self.sample(rng),
             gas_limit_mode: self.sample(rng),
+            tee_quorum_required_from_batch: self.sample(rng),
         }
     }
 }
@@ -540,6 +568,22 @@ impl Distribution<configs::ProofDataHandlerConfig> for EncodeDist {
                 first_tee_processed_batch: L1BatchNumber(rng.gen()),
                 tee_proof_generation_timeout_in_secs: self.sample(rng),
                 tee_batch_permanently_ignored_timeout_in_hours: self.sample(rng),
+                verify_attestations: self.sample(rng),
+                allowed_mrenclaves: self.sample_collect(rng),
+                allowed_mrtds: self.sample_collect(rng),
+                // Empty lists are read back as the default one.
+                allowed_tcb_statuses: vec![self.sample(rng)],
+                attestation_collateral_path: self.sample(rng),
+                quorum_tee_types: self
+                    .sample_range(rng)
+                    .map(|_| {
+                        if rng.gen() {
+                            TeeType::Sgx
+                        } else {
+                            TeeType::Tdx
+                        }
+                    })
+                    .collect(),
             },
         }
     }
@@ -552,6 +596,8 @@ impl Distribution<configs::SnapshotsCreatorConfig> for EncodeDist {
             version: if rng.gen() { 0 } else { 1 },
             storage_logs_chunk_size: self.sample(rng),
             concurrent_queries_count: self.sample(rng),
+            history_l1_batches: self.sample(rng),
+            max_incremental_snapshots: self.sample(rng),
             object_store: self.sample(rng),
         }
     }
@@ -565,6 +611,7 @@ impl Distribution<configs::ObservabilityConfig> for EncodeDist {
             log_format: self.sample(rng),
             opentelemetry: self.sample(rng),
             log_directives: self.sample(rng),
+            log_sampling: self.sample(rng),
         }
     }
 }
@@ -727,6 +774,7 @@ impl Distribution<configs::secrets::Secrets> for EncodeDist {
             l1: self.sample_opt(|| self.sample(rng)),
             data_availability: self.sample_opt(|| self.sample(rng)),
             contract_verifier: self.sample_opt(|| self.sample(rng)),
+            api: self.sample_opt(|| self.sample(rng)),
         }
     }
 }
@@ -884,6 +932,7 @@ impl Distribution<configs::snapshot_recovery::PostgresRecoveryConfig> for Encode
     ) -> configs::snapshot_recovery::PostgresRecoveryConfig {
         configs::snapshot_recovery::PostgresRecoveryConfig {
             max_concurrency: self.sample_opt(|| rng.gen()),
+            import_history: self.sample(rng),
         }
     }
 }
@@ -913,6 +962,7 @@ impl Distribution<configs::pruning::PruningConfig> for EncodeDist {
             chunk_size: self.sample(rng),
             removal_delay_sec: self.sample_opt(|| rng.gen()),
             data_retention_sec: self.sample(rng),
+            tree_retained_l1_batches: self.sample(rng),
         }
     }
 }
@@ -949,6 +999,22 @@ impl Distribution<configs::external_proof_integration_api::ExternalProofIntegrat
     ) -> configs::external_proof_integration_api::ExternalProofIntegrationApiConfig {
         configs::external_proof_integration_api::ExternalProofIntegrationApiConfig {
             http_port: self.sample(rng),
+            lease_timeout_in_secs: self.sample(rng),
+            api_keys: self.sample_collect(rng),
+            max_leases_per_prover: self.sample(rng),
+            verification_keys_path: self.sample(rng),
+        }
+    }
+}
+
+impl Distribution<configs::external_proof_integration_api::ExternalProverApiKey> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> configs::external_proof_integration_api::ExternalProverApiKey {
+        configs::external_proof_integration_api::ExternalProverApiKey {
+            prover_id: self.sample(rng),
+            api_key: self.sample(rng),
         }
     }
 }
@@ -993,6 +1059,7 @@ impl Distribution<configs::prover_job_monitor::ProverJobMonitorConfig> for Encod
             prover_queue_reporter_run_interval_ms: self.sample(rng),
             witness_generator_queue_reporter_run_interval_ms: self.sample(rng),
             witness_job_queuer_run_interval_ms: self.sample(rng),
+            batch_priority_ager_run_interval_ms: self.sample(rng),
             http_port: self.sample(rng),
         }
     }
@@ -1053,3 +1120,11 @@ impl Distribution<configs::secrets::ContractVerifierSecrets> for EncodeDist {
         }
     }
 }
+
+impl Distribution<configs::secrets::ApiSecrets> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::secrets::ApiSecrets {
+        configs::secrets::ApiSecrets {
+            admin_token: Some(<APIKey as From<String>>::from(self.sample(rng))),
+        }
+    }
+}
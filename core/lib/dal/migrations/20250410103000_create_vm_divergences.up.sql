@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS vm_divergences
+(
+    id                   BIGSERIAL PRIMARY KEY,
+    l1_batch_number      BIGINT    NOT NULL,
+    tx_index_in_l1_batch INT,
+    kind                 TEXT      NOT NULL,
+    dump_key             TEXT,
+    occurrences          INT       NOT NULL DEFAULT 1,
+    first_seen_at        TIMESTAMP NOT NULL,
+    last_seen_at         TIMESTAMP NOT NULL
+);
+
+-- Repeated divergences (e.g., after the VM playground is restarted) are deduplicated by this index.
+CREATE UNIQUE INDEX IF NOT EXISTS vm_divergences_batch_tx_kind
+    ON vm_divergences (l1_batch_number, (COALESCE(tx_index_in_l1_batch, -1)), kind);
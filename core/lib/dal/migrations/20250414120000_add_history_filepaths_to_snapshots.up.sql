@@ -0,0 +1,2 @@
+ALTER TABLE snapshots
+    ADD COLUMN IF NOT EXISTS history_filepaths TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,3 @@
+-- Incremental snapshots reference their base snapshot; the base cannot be removed while it has dependent snapshots.
+ALTER TABLE snapshots ADD COLUMN IF NOT EXISTS base_l1_batch_number BIGINT
+    REFERENCES snapshots (l1_batch_number);
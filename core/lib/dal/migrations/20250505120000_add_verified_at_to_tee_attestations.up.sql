@@ -0,0 +1 @@
+ALTER TABLE tee_attestations ADD COLUMN IF NOT EXISTS verified_at TIMESTAMP;
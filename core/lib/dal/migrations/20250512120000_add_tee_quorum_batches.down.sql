@@ -0,0 +1 @@
+DROP TABLE IF EXISTS tee_quorum_batches;
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS tee_quorum_batches
+(
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    reached_at      TIMESTAMP NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE proof_generation_details DROP COLUMN IF EXISTS lease_owner;
+ALTER TABLE proof_generation_details DROP COLUMN IF EXISTS lease_expires_at;
@@ -0,0 +1,2 @@
+ALTER TABLE proof_generation_details ADD COLUMN IF NOT EXISTS lease_owner TEXT;
+ALTER TABLE proof_generation_details ADD COLUMN IF NOT EXISTS lease_expires_at TIMESTAMP;
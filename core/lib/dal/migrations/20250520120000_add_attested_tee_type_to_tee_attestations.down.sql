@@ -0,0 +1 @@
+ALTER TABLE tee_attestations DROP COLUMN IF EXISTS attested_tee_type;
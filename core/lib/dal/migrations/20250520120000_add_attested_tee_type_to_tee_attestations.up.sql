@@ -0,0 +1 @@
+ALTER TABLE tee_attestations ADD COLUMN IF NOT EXISTS attested_tee_type TEXT;
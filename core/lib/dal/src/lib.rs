@@ -26,7 +26,8 @@ use crate::{
     storage_web3_dal::StorageWeb3Dal, sync_dal::SyncDal, system_dal::SystemDal,
     tee_proof_generation_dal::TeeProofGenerationDal, tokens_dal::TokensDal,
     tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
-    transactions_web3_dal::TransactionsWeb3Dal, vm_runner_dal::VmRunnerDal,
+    transactions_web3_dal::TransactionsWeb3Dal, vm_divergences_dal::VmDivergencesDal,
+    vm_runner_dal::VmRunnerDal,
 };
 
 pub mod base_token_dal;
@@ -64,6 +65,7 @@ pub mod tokens_dal;
 pub mod tokens_web3_dal;
 pub mod transactions_dal;
 pub mod transactions_web3_dal;
+pub mod vm_divergences_dal;
 pub mod vm_runner_dal;
 
 #[cfg(test)]
@@ -136,6 +138,8 @@ where
 
     fn vm_runner_dal(&mut self) -> VmRunnerDal<'_, 'a>;
 
+    fn vm_divergences_dal(&mut self) -> VmDivergencesDal<'_, 'a>;
+
     fn base_token_dal(&mut self) -> BaseTokenDal<'_, 'a>;
 
     fn eth_watcher_dal(&mut self) -> EthWatcherDal<'_, 'a>;
@@ -270,6 +274,10 @@ impl<'a> CoreDal<'a> for Connection<'a, Core> {
         VmRunnerDal { storage: self }
     }
 
+    fn vm_divergences_dal(&mut self) -> VmDivergencesDal<'_, 'a> {
+        VmDivergencesDal { storage: self }
+    }
+
     fn base_token_dal(&mut self) -> BaseTokenDal<'_, 'a> {
         BaseTokenDal { storage: self }
     }
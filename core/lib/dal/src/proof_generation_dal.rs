@@ -1,6 +1,7 @@
 #![doc = include_str!("../doc/ProofGenerationDal.md")]
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use strum::{Display, EnumString};
 use zksync_db_connection::{
     connection::Connection,
@@ -17,8 +18,8 @@ pub struct ProofGenerationDal<'a, 'c> {
     pub(crate) storage: &'a mut Connection<'c, Core>,
 }
 
-#[derive(Debug, EnumString, Display)]
-enum ProofGenerationJobStatus {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+pub enum ProofGenerationJobStatus {
     #[strum(serialize = "unpicked")]
     Unpicked,
     #[strum(serialize = "picked_by_prover")]
@@ -29,6 +30,24 @@ enum ProofGenerationJobStatus {
     Skipped,
 }
 
+/// Lease of a batch by an external prover.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofGenerationLease {
+    pub l1_batch_number: L1BatchNumber,
+    pub owner: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Proof generation status of a batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofGenerationDetails {
+    pub l1_batch_number: L1BatchNumber,
+    pub status: ProofGenerationJobStatus,
+    /// The last lease of the batch by an external prover, if any. May be expired.
+    pub lease: Option<ProofGenerationLease>,
+    pub updated_at: DateTime<Utc>,
+}
+
 impl ProofGenerationDal<'_, '_> {
     /// Chooses the batch number so that it has all the necessary data to generate the proof
     /// and is not already picked.
@@ -48,7 +67,9 @@ impl ProofGenerationDal<'_, '_> {
             SET
                 status = 'picked_by_prover',
                 updated_at = NOW(),
-                prover_taken_at = NOW()
+                prover_taken_at = NOW(),
+                lease_owner = NULL,
+                lease_expires_at = NULL
             WHERE
                 l1_batch_number = (
                     SELECT
@@ -68,6 +89,7 @@ impl ProofGenerationDal<'_, '_> {
                         OR (
                             status = 'picked_by_prover'
                             AND prover_taken_at < NOW() - $1::INTERVAL
+                            AND (lease_expires_at IS NULL OR lease_expires_at < NOW())
                         )
                     ORDER BY
                         l1_batch_number ASC
@@ -88,6 +110,162 @@ impl ProofGenerationDal<'_, '_> {
         Ok(result)
     }
 
+    /// Leases the oldest batch ready for proving to an external prover for `lease_timeout`. Besides unpicked
+    /// batches, batches with an expired lease can be leased.
+    ///
+    /// Batches locked via [`Self::lock_batch_for_proving()`] are never leased; conversely, a leased batch
+    /// can be locked for internal proving only after both its lease and the processing timeout expire.
+    ///
+    /// Returns `None` if `owner` already holds `max_leases` unexpired leases.
+    pub async fn lease_batch_for_external_proving(
+        &mut self,
+        owner: &str,
+        lease_timeout: Duration,
+        max_leases: usize,
+    ) -> DalResult<Option<ProofGenerationLease>> {
+        let lease_timeout = pg_interval_from_duration(lease_timeout);
+        let max_leases = i64::try_from(max_leases).unwrap_or(i64::MAX);
+        let mut transaction = self.storage.start_transaction().await?;
+
+        // Serialize leasing for the same owner until the end of the transaction, so that concurrent requests
+        // cannot exceed `max_leases`.
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(owner)
+            .instrument("lease_batch_for_external_proving#lock_owner")
+            .with_arg("owner", &owner)
+            .execute(&mut transaction)
+            .await?;
+
+        let active_leases = sqlx::query_scalar!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                proof_generation_details
+            WHERE
+                status = 'picked_by_prover'
+                AND lease_owner = $1
+                AND lease_expires_at > NOW()
+            "#,
+            owner,
+        )
+        .instrument("lease_batch_for_external_proving#count_leases")
+        .with_arg("owner", &owner)
+        .fetch_one(&mut transaction)
+        .await?;
+        if active_leases >= max_leases {
+            return Ok(None);
+        }
+
+        // The leasing predicate is repeated in the outer `WHERE` clause, since the selected row may be modified
+        // by a concurrent transaction before it's locked by the update.
+        let lease = sqlx::query!(
+            r#"
+            UPDATE proof_generation_details
+            SET
+                status = 'picked_by_prover',
+                updated_at = NOW(),
+                prover_taken_at = NOW(),
+                lease_owner = $2,
+                lease_expires_at = NOW() + $1::INTERVAL
+            WHERE
+                l1_batch_number = (
+                    SELECT
+                        l1_batch_number
+                    FROM
+                        proof_generation_details
+                    LEFT JOIN l1_batches ON l1_batch_number = l1_batches.number
+                    WHERE
+                        (
+                            vm_run_data_blob_url IS NOT NULL
+                            AND proof_gen_data_blob_url IS NOT NULL
+                            AND l1_batches.hash IS NOT NULL
+                            AND l1_batches.aux_data_hash IS NOT NULL
+                            AND l1_batches.meta_parameters_hash IS NOT NULL
+                            AND status = 'unpicked'
+                        )
+                        OR (
+                            status = 'picked_by_prover'
+                            AND lease_expires_at < NOW()
+                        )
+                    ORDER BY
+                        l1_batch_number ASC
+                    LIMIT
+                        1
+                    FOR UPDATE
+                    OF proof_generation_details
+                    SKIP LOCKED
+                )
+                AND (
+                    status = 'unpicked'
+                    OR (
+                        status = 'picked_by_prover'
+                        AND lease_expires_at < NOW()
+                    )
+                )
+            RETURNING
+            proof_generation_details.l1_batch_number,
+            proof_generation_details.lease_expires_at AS "lease_expires_at!"
+            "#,
+            &lease_timeout,
+            owner,
+        )
+        .instrument("lease_batch_for_external_proving")
+        .with_arg("owner", &owner)
+        .with_arg("lease_timeout", &lease_timeout)
+        .fetch_optional(&mut transaction)
+        .await?
+        .map(|row| ProofGenerationLease {
+            l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            owner: owner.to_owned(),
+            expires_at: DateTime::<Utc>::from_naive_utc_and_offset(row.lease_expires_at, Utc),
+        });
+
+        transaction.commit().await?;
+        Ok(lease)
+    }
+
+    pub async fn get_proof_generation_details(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<Option<ProofGenerationDetails>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                status,
+                lease_owner,
+                lease_expires_at,
+                updated_at
+            FROM
+                proof_generation_details
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0),
+        )
+        .instrument("get_proof_generation_details")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| ProofGenerationDetails {
+            l1_batch_number,
+            status: row
+                .status
+                .parse()
+                .expect("invalid proof generation job status"),
+            lease: row
+                .lease_owner
+                .zip(row.lease_expires_at)
+                .map(|(owner, expires_at)| ProofGenerationLease {
+                    l1_batch_number,
+                    owner,
+                    expires_at: DateTime::<Utc>::from_naive_utc_and_offset(expires_at, Utc),
+                }),
+            updated_at: DateTime::<Utc>::from_naive_utc_and_offset(row.updated_at, Utc),
+        }))
+    }
+
     pub async fn get_latest_proven_batch(&mut self) -> DalResult<L1BatchNumber> {
         let result = sqlx::query!(
             r#"
@@ -172,6 +350,43 @@ impl ProofGenerationDal<'_, '_> {
         Ok(())
     }
 
+    /// Saves the proof submitted by an external prover for a leased batch. Unlike [`Self::save_proof_artifacts_metadata()`],
+    /// the proof is only saved if the batch is still leased by `lease_owner` and the lease hasn't expired.
+    ///
+    /// Returns `false` if the proof wasn't saved.
+    pub async fn save_leased_proof_artifacts_metadata(
+        &mut self,
+        batch_number: L1BatchNumber,
+        lease_owner: &str,
+        proof_blob_url: &str,
+    ) -> DalResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE proof_generation_details
+            SET
+                status = 'generated',
+                proof_blob_url = $1,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $2
+                AND status = 'picked_by_prover'
+                AND lease_owner = $3
+                AND lease_expires_at > NOW()
+            "#,
+            proof_blob_url,
+            i64::from(batch_number.0),
+            lease_owner,
+        )
+        .instrument("save_leased_proof_artifacts_metadata")
+        .with_arg("l1_batch_number", &batch_number)
+        .with_arg("lease_owner", &lease_owner)
+        .with_arg("proof_blob_url", &proof_blob_url)
+        .execute(self.storage)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
     pub async fn save_vm_runner_artifacts_metadata(
         &mut self,
         batch_number: L1BatchNumber,
@@ -369,6 +584,132 @@ mod tests {
     use super::*;
     use crate::{tests::create_l1_batch_header, ConnectionPool, CoreDal};
 
+    async fn prepare_batch_for_proving(conn: &mut Connection<'_, Core>, number: L1BatchNumber) {
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch_header(number.0))
+            .await
+            .unwrap();
+        let mut dal = conn.proof_generation_dal();
+        dal.insert_proof_generation_details(number).await.unwrap();
+        dal.save_vm_runner_artifacts_metadata(number, "vm_run")
+            .await
+            .unwrap();
+        dal.save_merkle_paths_artifacts_metadata(number, "data")
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .save_l1_batch_tree_data(
+                number,
+                &L1BatchTreeData {
+                    hash: H256::zero(),
+                    rollup_last_leaf_index: 123,
+                },
+            )
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .save_l1_batch_commitment_artifacts(number, &L1BatchCommitmentArtifacts::default())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn leasing_batches_to_external_provers() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        for number in 1..=3 {
+            prepare_batch_for_proving(&mut conn, L1BatchNumber(number)).await;
+        }
+
+        let lease = conn
+            .proof_generation_dal()
+            .lease_batch_for_external_proving("prover", Duration::from_secs(3_600), 2)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(lease.l1_batch_number, L1BatchNumber(1));
+        assert_eq!(lease.owner, "prover");
+        let details = conn
+            .proof_generation_dal()
+            .get_proof_generation_details(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(details.status, ProofGenerationJobStatus::PickedByProver);
+        assert_eq!(details.lease.as_ref(), Some(&lease));
+
+        // The prover cannot hold more leases than allowed.
+        let extra_lease = conn
+            .proof_generation_dal()
+            .lease_batch_for_external_proving("prover", Duration::from_secs(3_600), 1)
+            .await
+            .unwrap();
+        assert_eq!(extra_lease, None);
+
+        // A leased batch cannot be locked by an internal prover even after the processing timeout.
+        let picked_l1_batch = conn
+            .proof_generation_dal()
+            .lock_batch_for_proving(Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(picked_l1_batch, Some(L1BatchNumber(2)));
+        let lease = conn
+            .proof_generation_dal()
+            .lease_batch_for_external_proving("other_prover", Duration::ZERO, 1)
+            .await
+            .unwrap();
+        // Batches locked for internal proving are not leased either.
+        assert_eq!(lease.unwrap().l1_batch_number, L1BatchNumber(3));
+
+        // Batches with an expired lease are leased again.
+        let lease = conn
+            .proof_generation_dal()
+            .lease_batch_for_external_proving("prover", Duration::ZERO, 2)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(lease.l1_batch_number, L1BatchNumber(3));
+
+        // Proofs are saved only by the owner of an unexpired lease.
+        for (number, owner) in [(1, "other_prover"), (3, "prover")] {
+            let saved = conn
+                .proof_generation_dal()
+                .save_leased_proof_artifacts_metadata(L1BatchNumber(number), owner, "proof")
+                .await
+                .unwrap();
+            assert!(!saved, "{number} {owner}");
+        }
+        let saved = conn
+            .proof_generation_dal()
+            .save_leased_proof_artifacts_metadata(L1BatchNumber(1), "prover", "proof")
+            .await
+            .unwrap();
+        assert!(saved);
+
+        conn.proof_generation_dal()
+            .save_proof_artifacts_metadata(L1BatchNumber(3), "proof")
+            .await
+            .unwrap();
+        let lease = conn
+            .proof_generation_dal()
+            .lease_batch_for_external_proving("other_prover", Duration::ZERO, 1)
+            .await
+            .unwrap();
+        assert_eq!(lease, None);
+        let details = conn
+            .proof_generation_dal()
+            .get_proof_generation_details(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(details.status, ProofGenerationJobStatus::Generated);
+        assert_eq!(details.lease.unwrap().owner, "prover");
+    }
+
     #[tokio::test]
     async fn proof_generation_workflow() {
         let pool = ConnectionPool::<Core>::test_pool().await;
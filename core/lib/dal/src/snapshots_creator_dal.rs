@@ -1,10 +1,14 @@
+use std::ops;
+
 use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
 use zksync_types::{
-    snapshots::SnapshotStorageLog, AccountTreeId, Address, L1BatchNumber, L2BlockNumber,
-    StorageKey, H256,
+    l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
+    snapshots::{SnapshotEvent, SnapshotStorageLog, SnapshotTransaction, SnapshotUserL2ToL1Log},
+    web3::Bytes,
+    AccountTreeId, Address, L1BatchNumber, L2BlockNumber, StorageKey, H256,
 };
 
-use crate::Core;
+use crate::{models::storage_transaction::StorageTransaction, Core};
 
 #[derive(Debug)]
 pub struct SnapshotsCreatorDal<'a, 'c> {
@@ -180,6 +184,100 @@ impl SnapshotsCreatorDal<'_, '_> {
         Ok(storage_logs)
     }
 
+    /// Returns the number of distinct storage keys changed in the specified range of L2 blocks.
+    /// Used to chunk incremental snapshots.
+    pub async fn get_changed_storage_logs_count(
+        &mut self,
+        l2_block_range: ops::RangeInclusive<L2BlockNumber>,
+    ) -> DalResult<u64> {
+        let count = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(DISTINCT hashed_key) AS "count!"
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            i64::from(l2_block_range.start().0),
+            i64::from(l2_block_range.end().0)
+        )
+        .instrument("get_changed_storage_logs_count")
+        .with_arg("l2_block_range", &l2_block_range)
+        .report_latency()
+        .expect_slow_query()
+        .fetch_one(self.storage)
+        .await?
+        .count;
+        Ok(count as u64)
+    }
+
+    /// Constructs an incremental `storage_logs` chunk: latest values of all keys changed in `l2_block_range`.
+    /// The end of `l2_block_range` MUST be the last L2 block of the `l1_batch_number` batch.
+    pub async fn get_changed_storage_logs_chunk(
+        &mut self,
+        l2_block_range: ops::RangeInclusive<L2BlockNumber>,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys_range: ops::RangeInclusive<H256>,
+    ) -> DalResult<Vec<SnapshotStorageLog>> {
+        // Phantom writes are filtered out in the same way as in `Self::get_storage_logs_chunk()`.
+        let storage_logs = sqlx::query!(
+            r#"
+            SELECT
+                storage_logs.hashed_key AS "hashed_key!",
+                storage_logs.value AS "value!",
+                initial_writes.l1_batch_number AS "l1_batch_number!",
+                initial_writes.index
+            FROM
+                (
+                    SELECT
+                        hashed_key,
+                        MAX(ARRAY[miniblock_number, operation_number]::INT []) AS op
+                    FROM
+                        storage_logs
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                        AND hashed_key >= $4
+                        AND hashed_key <= $5
+                    GROUP BY
+                        hashed_key
+                    ORDER BY
+                        hashed_key
+                ) AS keys
+            INNER JOIN storage_logs
+                ON
+                    keys.hashed_key = storage_logs.hashed_key
+                    AND storage_logs.miniblock_number = keys.op[1]
+                    AND storage_logs.operation_number = keys.op[2]
+            INNER JOIN initial_writes ON keys.hashed_key = initial_writes.hashed_key
+            WHERE
+                initial_writes.l1_batch_number <= $3
+            "#,
+            i64::from(l2_block_range.start().0),
+            i64::from(l2_block_range.end().0),
+            i64::from(l1_batch_number.0),
+            hashed_keys_range.start().as_bytes(),
+            hashed_keys_range.end().as_bytes()
+        )
+        .instrument("get_changed_storage_logs_chunk")
+        .with_arg("l2_block_range", &l2_block_range)
+        .with_arg("min_hashed_key", &hashed_keys_range.start())
+        .with_arg("max_hashed_key", &hashed_keys_range.end())
+        .report_latency()
+        .expect_slow_query()
+        .fetch_all(self.storage)
+        .await?
+        .iter()
+        .map(|row| SnapshotStorageLog {
+            key: H256::from_slice(&row.hashed_key),
+            value: H256::from_slice(&row.value),
+            l1_batch_number_of_initial_write: L1BatchNumber(row.l1_batch_number as u32),
+            enumeration_index: row.index as u64,
+        })
+        .collect();
+        Ok(storage_logs)
+    }
+
     /// Returns all factory dependencies up to and including the specified `l2_block_number`.
     pub async fn get_all_factory_deps(
         &mut self,
@@ -208,10 +306,194 @@ impl SnapshotsCreatorDal<'_, '_> {
             .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
             .collect())
     }
+
+    /// Returns factory dependencies deployed in the specified range of L2 blocks.
+    pub async fn get_factory_deps_in_range(
+        &mut self,
+        l2_block_range: ops::RangeInclusive<L2BlockNumber>,
+    ) -> DalResult<Vec<(H256, Vec<u8>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                bytecode_hash,
+                bytecode
+            FROM
+                factory_deps
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            i64::from(l2_block_range.start().0),
+            i64::from(l2_block_range.end().0)
+        )
+        .instrument("get_factory_deps_in_range")
+        .with_arg("l2_block_range", &l2_block_range)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
+            .collect())
+    }
+
+    /// Returns all transactions executed in the specified L1 batch together with the L2 block they belong to,
+    /// ordered by the L2 block number and the index in the block. Call traces are not loaded.
+    pub async fn get_l1_batch_transactions(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<Vec<(L2BlockNumber, SnapshotTransaction)>> {
+        let rows = sqlx::query_as!(
+            StorageTransaction,
+            r#"
+            SELECT
+                *
+            FROM
+                transactions
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                miniblock_number,
+                index_in_block
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_l1_batch_transactions")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let l2_block_number =
+                    L2BlockNumber(row.miniblock_number.unwrap_or_default() as u32);
+                let tx = SnapshotTransaction {
+                    index_in_l1_batch: row.l1_batch_tx_index.unwrap_or_default() as u32,
+                    success: row.error.is_none(),
+                    refunded_gas: row.refunded_gas as u64,
+                    execution_info: row.execution_info.clone(),
+                    call_trace: None,
+                    transaction: row.into(),
+                };
+                (l2_block_number, tx)
+            })
+            .collect())
+    }
+
+    /// Returns all events emitted in the specified range of L2 blocks, ordered by the L2 block number
+    /// and the index in the block.
+    pub async fn get_events(
+        &mut self,
+        l2_block_range: ops::RangeInclusive<L2BlockNumber>,
+    ) -> DalResult<Vec<(L2BlockNumber, SnapshotEvent)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number,
+                tx_index_in_block,
+                address,
+                topic1,
+                topic2,
+                topic3,
+                topic4,
+                value
+            FROM
+                events
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                miniblock_number,
+                event_index_in_block
+            "#,
+            i64::from(l2_block_range.start().0),
+            i64::from(l2_block_range.end().0)
+        )
+        .instrument("get_events")
+        .with_arg("l2_block_range", &l2_block_range)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let indexed_topics = [row.topic1, row.topic2, row.topic3, row.topic4]
+                    .into_iter()
+                    .filter(|topic| !topic.is_empty())
+                    .map(|topic| H256::from_slice(&topic))
+                    .collect();
+                let event = SnapshotEvent {
+                    tx_index_in_l2_block: row.tx_index_in_block as u32,
+                    address: Address::from_slice(&row.address),
+                    indexed_topics,
+                    value: Bytes(row.value),
+                };
+                (L2BlockNumber(row.miniblock_number as u32), event)
+            })
+            .collect())
+    }
+
+    /// Returns all user L2-to-L1 logs emitted in the specified range of L2 blocks, ordered by the L2 block number
+    /// and the index in the block.
+    pub async fn get_user_l2_to_l1_logs(
+        &mut self,
+        l2_block_range: ops::RangeInclusive<L2BlockNumber>,
+    ) -> DalResult<Vec<(L2BlockNumber, SnapshotUserL2ToL1Log)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number,
+                tx_index_in_miniblock,
+                tx_index_in_l1_batch,
+                shard_id,
+                is_service,
+                sender,
+                key,
+                value
+            FROM
+                l2_to_l1_logs
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                miniblock_number,
+                log_index_in_miniblock
+            "#,
+            i64::from(l2_block_range.start().0),
+            i64::from(l2_block_range.end().0)
+        )
+        .instrument("get_user_l2_to_l1_logs")
+        .with_arg("l2_block_range", &l2_block_range)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let log = L2ToL1Log {
+                    shard_id: row.shard_id as u8,
+                    is_service: row.is_service,
+                    tx_number_in_block: row.tx_index_in_l1_batch as u16,
+                    sender: Address::from_slice(&row.sender),
+                    key: H256::from_slice(&row.key),
+                    value: H256::from_slice(&row.value),
+                };
+                let log = SnapshotUserL2ToL1Log {
+                    tx_index_in_l2_block: row.tx_index_in_miniblock as u32,
+                    log: UserL2ToL1Log(log),
+                };
+                (L2BlockNumber(row.miniblock_number as u32), log)
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use zksync_types::StorageLog;
 
     use super::*;
@@ -289,6 +571,37 @@ mod tests {
             .unwrap();
         assert_eq!(log_row_count, (logs.len() + all_new_logs_len) as u64);
         assert_logs_for_snapshot(&mut conn, L2BlockNumber(1), L1BatchNumber(1), &logs).await;
+
+        // Logs for an incremental snapshot should only contain inserts / updates in the L2 block #2.
+        let changed_logs_count = conn
+            .snapshots_creator_dal()
+            .get_changed_storage_logs_count(L2BlockNumber(2)..=L2BlockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(changed_logs_count, all_new_logs_len as u64);
+        let changed_logs = conn
+            .snapshots_creator_dal()
+            .get_changed_storage_logs_chunk(
+                L2BlockNumber(2)..=L2BlockNumber(2),
+                L1BatchNumber(2),
+                H256::zero()..=H256::repeat_byte(0xff),
+            )
+            .await
+            .unwrap();
+        assert_eq!(changed_logs.len(), all_new_logs_len);
+        let expected_values: HashMap<_, _> = all_new_logs
+            .iter()
+            .map(|log| (log.key.hashed_key(), log.value))
+            .collect();
+        for log in &changed_logs {
+            assert_eq!(log.value, expected_values[&log.key]);
+            let expected_l1_batch = if new_written_keys.contains(&log.key) {
+                L1BatchNumber(2)
+            } else {
+                L1BatchNumber(1)
+            };
+            assert_eq!(log.l1_batch_number_of_initial_write, expected_l1_batch);
+        }
     }
 
     async fn assert_logs_for_snapshot(
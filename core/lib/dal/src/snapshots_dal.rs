@@ -14,8 +14,10 @@ use crate::Core;
 struct StorageSnapshotMetadata {
     version: i32,
     l1_batch_number: i64,
+    base_l1_batch_number: Option<i64>,
     storage_logs_filepaths: Vec<String>,
     factory_deps_filepath: String,
+    history_filepaths: Vec<String>,
 }
 
 impl TryFrom<StorageSnapshotMetadata> for SnapshotMetadata {
@@ -28,12 +30,20 @@ impl TryFrom<StorageSnapshotMetadata> for SnapshotMetadata {
         Ok(Self {
             version,
             l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            base_l1_batch_number: row
+                .base_l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
             storage_logs_filepaths: row
                 .storage_logs_filepaths
                 .into_iter()
                 .map(|path| (!path.is_empty()).then_some(path))
                 .collect(),
             factory_deps_filepath: row.factory_deps_filepath,
+            history_filepaths: row
+                .history_filepaths
+                .into_iter()
+                .map(|path| (!path.is_empty()).then_some(path))
+                .collect(),
         })
     }
 }
@@ -44,11 +54,15 @@ pub struct SnapshotsDal<'a, 'c> {
 }
 
 impl SnapshotsDal<'_, '_> {
+    /// Adds a new snapshot. If `base_l1_batch_number` is specified, the snapshot is incremental, i.e. only contains
+    /// storage logs and factory deps changed after the snapshot with this L1 batch number.
     pub async fn add_snapshot(
         &mut self,
         version: SnapshotVersion,
         l1_batch_number: L1BatchNumber,
+        base_l1_batch_number: Option<L1BatchNumber>,
         storage_logs_chunk_count: u64,
+        history_l1_batch_count: u32,
         factory_deps_filepaths: &str,
     ) -> DalResult<()> {
         sqlx::query!(
@@ -57,22 +71,37 @@ impl SnapshotsDal<'_, '_> {
             snapshots (
                 version,
                 l1_batch_number,
+                base_l1_batch_number,
                 storage_logs_filepaths,
+                history_filepaths,
                 factory_deps_filepath,
                 created_at,
                 updated_at
             )
             VALUES
-            ($1, $2, ARRAY_FILL(''::TEXT, ARRAY[$3::INTEGER]), $4, NOW(), NOW())
+            (
+                $1,
+                $2,
+                $3,
+                ARRAY_FILL(''::TEXT, ARRAY[$4::INTEGER]),
+                ARRAY_FILL(''::TEXT, ARRAY[$5::INTEGER]),
+                $6,
+                NOW(),
+                NOW()
+            )
             "#,
             version as i32,
             l1_batch_number.0 as i32,
+            base_l1_batch_number.map(|number| i64::from(number.0)),
             storage_logs_chunk_count as i32,
+            history_l1_batch_count as i32,
             factory_deps_filepaths,
         )
         .instrument("add_snapshot")
         .with_arg("version", &version)
         .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("base_l1_batch_number", &base_l1_batch_number)
+        .with_arg("history_l1_batch_count", &history_l1_batch_count)
         .report_latency()
         .execute(self.storage)
         .await?;
@@ -107,6 +136,36 @@ impl SnapshotsDal<'_, '_> {
         Ok(())
     }
 
+    /// Sets the path to the transaction history blob with the specified index (i.e., offset from the first
+    /// L1 batch in the history window).
+    pub async fn add_history_filepath_for_snapshot(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        history_index: usize,
+        history_filepath: &str,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE snapshots
+            SET
+                history_filepaths[$2] = $3,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i32,
+            history_index as i32 + 1,
+            history_filepath,
+        )
+        .instrument("add_history_filepath_for_snapshot")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("history_index", &history_index)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn get_all_complete_snapshots(&mut self) -> DalResult<AllSnapshots> {
         let rows = sqlx::query!(
             r#"
@@ -116,6 +175,7 @@ impl SnapshotsDal<'_, '_> {
                 snapshots
             WHERE
                 NOT (''::TEXT = ANY(storage_logs_filepaths))
+                AND NOT (''::TEXT = ANY(history_filepaths))
             ORDER BY
                 l1_batch_number DESC
             "#
@@ -142,8 +202,10 @@ impl SnapshotsDal<'_, '_> {
             SELECT
                 VERSION,
                 L1_BATCH_NUMBER,
+                BASE_L1_BATCH_NUMBER,
                 FACTORY_DEPS_FILEPATH,
-                STORAGE_LOGS_FILEPATHS
+                STORAGE_LOGS_FILEPATHS,
+                HISTORY_FILEPATHS
             FROM
                 SNAPSHOTS
             ORDER BY
@@ -169,8 +231,10 @@ impl SnapshotsDal<'_, '_> {
             SELECT
                 VERSION,
                 L1_BATCH_NUMBER,
+                BASE_L1_BATCH_NUMBER,
                 FACTORY_DEPS_FILEPATH,
-                STORAGE_LOGS_FILEPATHS
+                STORAGE_LOGS_FILEPATHS,
+                HISTORY_FILEPATHS
             FROM
                 SNAPSHOTS
             WHERE
@@ -187,6 +251,9 @@ impl SnapshotsDal<'_, '_> {
     }
 
     /// Deletes all snapshots after the specified L1 batch number and returns their metadata.
+    ///
+    /// Since incremental snapshots always follow their base snapshot, this never leaves dangling incremental snapshots.
+    /// Deleting a base snapshot while retaining snapshots that depend on it is prohibited by a foreign key constraint.
     pub async fn delete_snapshots_after(
         &mut self,
         last_retained_l1_batch_number: L1BatchNumber,
@@ -200,8 +267,10 @@ impl SnapshotsDal<'_, '_> {
             RETURNING
             version,
             l1_batch_number,
+            base_l1_batch_number,
             factory_deps_filepath,
-            storage_logs_filepaths
+            storage_logs_filepaths,
+            history_filepaths
             "#,
             last_retained_l1_batch_number.0 as i32
         )
@@ -231,7 +300,9 @@ mod tests {
         dal.add_snapshot(
             SnapshotVersion::Version0,
             l1_batch_number,
+            None,
             2,
+            0,
             "gs:///bucket/factory_deps.bin",
         )
         .await
@@ -276,7 +347,9 @@ mod tests {
         dal.add_snapshot(
             SnapshotVersion::Version0,
             l1_batch_number,
+            None,
             2,
+            0,
             "gs:///bucket/factory_deps.bin",
         )
         .await
@@ -339,7 +412,9 @@ mod tests {
         dal.add_snapshot(
             SnapshotVersion::Version0,
             l1_batch_number,
+            None,
             2,
+            0,
             "gs:///bucket/factory_deps.bin",
         )
         .await
@@ -379,4 +454,152 @@ mod tests {
             ]
         );
     }
+
+    #[tokio::test]
+    async fn adding_history_files() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        let l1_batch_number = L1BatchNumber(100);
+        dal.add_snapshot(
+            SnapshotVersion::Version1,
+            l1_batch_number,
+            None,
+            1,
+            2,
+            "gs:///bucket/factory_deps.bin",
+        )
+        .await
+        .unwrap();
+        dal.add_storage_logs_filepath_for_snapshot(l1_batch_number, 0, "gs:///bucket/chunk.bin")
+            .await
+            .unwrap();
+        dal.add_history_filepath_for_snapshot(l1_batch_number, 1, "gs:///bucket/history100.bin")
+            .await
+            .unwrap();
+
+        let snapshot_metadata = dal
+            .get_snapshot_metadata(l1_batch_number)
+            .await
+            .unwrap()
+            .expect("snapshot is not persisted");
+        assert!(!snapshot_metadata.is_complete());
+        assert_eq!(
+            snapshot_metadata.history_filepaths,
+            [None, Some("gs:///bucket/history100.bin".to_owned())]
+        );
+        assert_eq!(snapshot_metadata.history_l1_batch(0), L1BatchNumber(99));
+        assert_eq!(snapshot_metadata.history_l1_batch(1), l1_batch_number);
+        let snapshots = dal.get_all_complete_snapshots().await.unwrap();
+        assert_eq!(snapshots.snapshots_l1_batch_numbers, []);
+
+        dal.add_history_filepath_for_snapshot(l1_batch_number, 0, "gs:///bucket/history99.bin")
+            .await
+            .unwrap();
+        let snapshot_metadata = dal
+            .get_snapshot_metadata(l1_batch_number)
+            .await
+            .unwrap()
+            .expect("snapshot is not persisted");
+        assert!(snapshot_metadata.is_complete());
+        let snapshots = dal.get_all_complete_snapshots().await.unwrap();
+        assert_eq!(snapshots.snapshots_l1_batch_numbers, [l1_batch_number]);
+    }
+
+    #[tokio::test]
+    async fn adding_incremental_snapshot() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        let base_l1_batch_number = L1BatchNumber(100);
+        let l1_batch_number = L1BatchNumber(110);
+        for (number, base) in [
+            (base_l1_batch_number, None),
+            (l1_batch_number, Some(base_l1_batch_number)),
+        ] {
+            dal.add_snapshot(
+                SnapshotVersion::Version1,
+                number,
+                base,
+                1,
+                0,
+                "gs:///bucket/factory_deps.bin",
+            )
+            .await
+            .unwrap();
+            dal.add_storage_logs_filepath_for_snapshot(number, 0, "gs:///bucket/chunk.bin")
+                .await
+                .unwrap();
+        }
+
+        let snapshot_metadata = dal
+            .get_newest_snapshot_metadata()
+            .await
+            .unwrap()
+            .expect("snapshot is not persisted");
+        assert_eq!(snapshot_metadata.l1_batch_number, l1_batch_number);
+        assert_eq!(
+            snapshot_metadata.base_l1_batch_number,
+            Some(base_l1_batch_number)
+        );
+        let snapshot_metadata = dal
+            .get_snapshot_metadata(base_l1_batch_number)
+            .await
+            .unwrap()
+            .expect("snapshot is not persisted");
+        assert_eq!(snapshot_metadata.base_l1_batch_number, None);
+
+        // The base snapshot cannot be deleted while the incremental snapshot depends on it.
+        let err = sqlx::query("DELETE FROM snapshots WHERE l1_batch_number = $1")
+            .bind(i64::from(base_l1_batch_number.0))
+            .execute(conn.conn())
+            .await
+            .unwrap_err();
+        assert!(
+            err.as_database_error()
+                .is_some_and(|err| err.is_foreign_key_violation()),
+            "{err:?}"
+        );
+
+        let mut dal = conn.snapshots_dal();
+        let deleted_snapshots = dal
+            .delete_snapshots_after(base_l1_batch_number - 1)
+            .await
+            .unwrap();
+        assert_eq!(deleted_snapshots.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn deleting_incremental_snapshot() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        let base_l1_batch_number = L1BatchNumber(100);
+        let l1_batch_number = L1BatchNumber(110);
+        for (number, base) in [
+            (base_l1_batch_number, None),
+            (l1_batch_number, Some(base_l1_batch_number)),
+        ] {
+            dal.add_snapshot(
+                SnapshotVersion::Version1,
+                number,
+                base,
+                1,
+                0,
+                "gs:///bucket/factory_deps.bin",
+            )
+            .await
+            .unwrap();
+        }
+
+        let deleted_snapshots = dal
+            .delete_snapshots_after(base_l1_batch_number)
+            .await
+            .unwrap();
+        assert_eq!(deleted_snapshots.len(), 1);
+        assert_eq!(
+            deleted_snapshots[0].base_l1_batch_number,
+            Some(base_l1_batch_number)
+        );
+    }
 }
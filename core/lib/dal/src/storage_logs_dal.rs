@@ -151,6 +151,70 @@ impl StorageLogsDal<'_, '_> {
         copy.send(buffer.as_bytes()).await
     }
 
+    /// Same as [`Self::insert_storage_logs_from_snapshot()`], but overwrites values of the logs already inserted
+    /// for the same L2 block. Used to apply incremental snapshots on top of their base snapshot.
+    pub async fn upsert_storage_logs_from_snapshot(
+        &mut self,
+        l2_block_number: L2BlockNumber,
+        snapshot_storage_logs: &[SnapshotStorageLog],
+    ) -> DalResult<()> {
+        let hashed_keys: Vec<_> = snapshot_storage_logs
+            .iter()
+            .map(|log| log.key.as_bytes())
+            .collect();
+        let values: Vec<_> = snapshot_storage_logs
+            .iter()
+            .map(|log| log.value.as_bytes())
+            .collect();
+        let operation_numbers: Vec<_> = snapshot_storage_logs
+            .iter()
+            .map(|log| log.enumeration_index as i32)
+            .collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+            storage_logs (
+                hashed_key,
+                value,
+                operation_number,
+                tx_hash,
+                miniblock_number,
+                created_at,
+                updated_at
+            )
+            SELECT
+                u.hashed_key,
+                u.value,
+                u.operation_number,
+                $4,
+                $5,
+                NOW(),
+                NOW()
+            FROM
+                UNNEST($1::BYTEA [], $2::BYTEA [], $3::INT []) AS u (
+                    hashed_key, value, operation_number
+                )
+            ON CONFLICT (hashed_key, miniblock_number, operation_number) DO
+            UPDATE
+            SET
+            value = excluded.value,
+            updated_at = NOW()
+            "#,
+            &hashed_keys as &[&[u8]],
+            &values as &[&[u8]],
+            &operation_numbers,
+            H256::zero().as_bytes(),
+            i64::from(l2_block_number.0)
+        )
+        .instrument("upsert_storage_logs_from_snapshot")
+        .with_arg("l2_block_number", &l2_block_number)
+        .with_arg("storage_logs.len", &snapshot_storage_logs.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn append_storage_logs(
         &mut self,
         block_number: L2BlockNumber,
@@ -1301,4 +1365,55 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn upserting_storage_logs_from_snapshot() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let l2_block_number = L2BlockNumber(10);
+        let logs: Vec<_> = (1..=10)
+            .map(|i| SnapshotStorageLog {
+                key: H256::from_low_u64_be(i),
+                value: H256::repeat_byte(1),
+                l1_batch_number_of_initial_write: L1BatchNumber(1),
+                enumeration_index: i,
+            })
+            .collect();
+        conn.storage_logs_dal()
+            .insert_storage_logs_from_snapshot(l2_block_number, &logs)
+            .await
+            .unwrap();
+
+        // Update some existing logs and add new ones.
+        let updated_logs: Vec<_> = (5..=15)
+            .map(|i| SnapshotStorageLog {
+                key: H256::from_low_u64_be(i),
+                value: H256::repeat_byte(2),
+                l1_batch_number_of_initial_write: L1BatchNumber(1),
+                enumeration_index: i,
+            })
+            .collect();
+        conn.storage_logs_dal()
+            .upsert_storage_logs_from_snapshot(l2_block_number, &updated_logs)
+            .await
+            .unwrap();
+
+        let mut all_logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        all_logs.sort_unstable_by_key(|log| log.operation_number);
+        assert_eq!(all_logs.len(), 15);
+        for (i, log) in (1..=15).zip(&all_logs) {
+            assert_eq!(log.hashed_key, H256::from_low_u64_be(i));
+            assert_eq!(log.operation_number, i);
+            assert_eq!(log.l2_block_number, l2_block_number);
+            let expected_value = if i < 5 {
+                H256::repeat_byte(1)
+            } else {
+                H256::repeat_byte(2)
+            };
+            assert_eq!(log.value, expected_value);
+        }
+    }
 }
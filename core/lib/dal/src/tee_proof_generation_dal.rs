@@ -55,6 +55,16 @@ pub struct LockedBatch {
     pub created_at: DateTime<Utc>,
 }
 
+/// Signer of a generated TEE proof for a batch, used to evaluate the TEE proof quorum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeeProofSigner {
+    pub tee_type: TeeType,
+    pub pubkey: Vec<u8>,
+    /// TEE type of the attestation quote verified on the key registration, or `None` if the attestation
+    /// wasn't verified.
+    pub attested_tee_type: Option<TeeType>,
+}
+
 impl TeeProofGenerationDal<'_, '_> {
     pub async fn lock_batch_for_proving(
         &mut self,
@@ -268,6 +278,247 @@ impl TeeProofGenerationDal<'_, '_> {
         Ok(())
     }
 
+    /// Saves an attestation whose quote was verified by the caller, marking the key as verified for the TEE type
+    /// of the quote. Unlike [`Self::save_attestation()`], overwrites the attestation previously registered for the key.
+    pub async fn save_verified_attestation(
+        &mut self,
+        pubkey: &[u8],
+        attestation: &[u8],
+        attested_tee_type: TeeType,
+    ) -> DalResult<()> {
+        let query = sqlx::query!(
+            r#"
+            INSERT INTO
+            tee_attestations (pubkey, attestation, attested_tee_type, verified_at)
+            VALUES
+            ($1, $2, $3, NOW())
+            ON CONFLICT (pubkey) DO
+            UPDATE
+            SET
+            attestation = excluded.attestation,
+            attested_tee_type = excluded.attested_tee_type,
+            verified_at = excluded.verified_at
+            "#,
+            pubkey,
+            attestation,
+            attested_tee_type.to_string()
+        );
+        Instrumented::new("save_verified_attestation")
+            .with_arg("pubkey", &pubkey)
+            .with_arg("attested_tee_type", &attested_tee_type)
+            .with(query)
+            .execute(self.storage)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Checks whether the key has an attestation saved via [`Self::save_verified_attestation()`] with a quote
+    /// of the specified TEE type.
+    pub async fn is_attestation_verified(
+        &mut self,
+        pubkey: &[u8],
+        tee_type: TeeType,
+    ) -> DalResult<bool> {
+        let query = sqlx::query!(
+            r#"
+            SELECT
+                verified_at IS NOT NULL
+                AND attested_tee_type IS NOT DISTINCT FROM $2 AS "verified!"
+            FROM
+                tee_attestations
+            WHERE
+                pubkey = $1
+            "#,
+            pubkey,
+            tee_type.to_string()
+        );
+        let verified = Instrumented::new("is_attestation_verified")
+            .with_arg("pubkey", &pubkey)
+            .with_arg("tee_type", &tee_type)
+            .with(query)
+            .fetch_optional(self.storage)
+            .await?
+            .is_some_and(|row| row.verified);
+
+        Ok(verified)
+    }
+
+    /// Returns signers of generated proofs for the batch that have a registered attestation.
+    pub async fn get_tee_proof_signers(
+        &mut self,
+        batch_number: L1BatchNumber,
+    ) -> DalResult<Vec<TeeProofSigner>> {
+        let query = sqlx::query!(
+            r#"
+            SELECT
+                tp.tee_type AS "tee_type!",
+                tp.pubkey AS "pubkey!",
+                CASE
+                    WHEN ta.verified_at IS NOT NULL THEN ta.attested_tee_type
+                END AS attested_tee_type
+            FROM
+                tee_proof_generation_details tp
+            JOIN tee_attestations ta ON tp.pubkey = ta.pubkey
+            WHERE
+                tp.l1_batch_number = $1
+                AND tp.status = $2
+            "#,
+            i64::from(batch_number.0),
+            TeeProofGenerationJobStatus::Generated.to_string(),
+        );
+        let rows = Instrumented::new("get_tee_proof_signers")
+            .with_arg("l1_batch_number", &batch_number)
+            .with(query)
+            .fetch_all(self.storage)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let tee_type = row.tee_type.parse().ok()?;
+                Some(TeeProofSigner {
+                    tee_type,
+                    pubkey: row.pubkey,
+                    attested_tee_type: row
+                        .attested_tee_type
+                        .and_then(|tee_type| tee_type.parse().ok()),
+                })
+            })
+            .collect())
+    }
+
+    /// Locks TEE proofs of the batch until the end of the current transaction, so that the quorum for the batch
+    /// is evaluated by a single request at a time. Must be called in a transaction.
+    pub async fn lock_tee_proofs_for_quorum(
+        &mut self,
+        batch_number: L1BatchNumber,
+    ) -> DalResult<()> {
+        let query = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number
+            FROM
+                tee_proof_generation_details
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                tee_type
+            FOR UPDATE
+            "#,
+            i64::from(batch_number.0),
+        );
+        Instrumented::new("lock_tee_proofs_for_quorum")
+            .with_arg("l1_batch_number", &batch_number)
+            .with(query)
+            .fetch_all(self.storage)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns numbers of batches with proofs signed by the specified key that haven't reached
+    /// the TEE proof quorum yet.
+    pub async fn get_batches_without_quorum_signed_by(
+        &mut self,
+        pubkey: &[u8],
+    ) -> DalResult<Vec<L1BatchNumber>> {
+        let query = sqlx::query!(
+            r#"
+            SELECT DISTINCT
+                tp.l1_batch_number
+            FROM
+                tee_proof_generation_details tp
+            LEFT JOIN tee_quorum_batches q ON tp.l1_batch_number = q.l1_batch_number
+            WHERE
+                tp.pubkey = $1
+                AND tp.status = $2
+                AND q.l1_batch_number IS NULL
+            ORDER BY
+                tp.l1_batch_number
+            "#,
+            pubkey,
+            TeeProofGenerationJobStatus::Generated.to_string(),
+        );
+        let rows = Instrumented::new("get_batches_without_quorum_signed_by")
+            .with_arg("pubkey", &pubkey)
+            .with(query)
+            .fetch_all(self.storage)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchNumber(row.l1_batch_number as u32))
+            .collect())
+    }
+
+    /// Records that the batch has reached the TEE proof quorum. Does nothing if it was already recorded.
+    pub async fn mark_tee_quorum_reached(&mut self, batch_number: L1BatchNumber) -> DalResult<()> {
+        let query = sqlx::query!(
+            r#"
+            INSERT INTO
+            tee_quorum_batches (l1_batch_number, reached_at)
+            VALUES
+            ($1, NOW())
+            ON CONFLICT (l1_batch_number) DO NOTHING
+            "#,
+            i64::from(batch_number.0),
+        );
+        Instrumented::new("mark_tee_quorum_reached")
+            .with_arg("l1_batch_number", &batch_number)
+            .with(query)
+            .execute(self.storage)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes the record that the batch has reached the TEE proof quorum, e.g. if one of the proofs counted
+    /// for the quorum was replaced by a proof from another signer.
+    pub async fn clear_tee_quorum_reached(&mut self, batch_number: L1BatchNumber) -> DalResult<()> {
+        let query = sqlx::query!(
+            r#"
+            DELETE FROM tee_quorum_batches
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(batch_number.0),
+        );
+        Instrumented::new("clear_tee_quorum_reached")
+            .with_arg("l1_batch_number", &batch_number)
+            .with(query)
+            .execute(self.storage)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the time the batch has reached the TEE proof quorum, or `None` if it hasn't.
+    pub async fn get_tee_quorum_reached_at(
+        &mut self,
+        batch_number: L1BatchNumber,
+    ) -> DalResult<Option<DateTime<Utc>>> {
+        let query = sqlx::query!(
+            r#"
+            SELECT
+                reached_at
+            FROM
+                tee_quorum_batches
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(batch_number.0),
+        );
+        let reached_at = Instrumented::new("get_tee_quorum_reached_at")
+            .with_arg("l1_batch_number", &batch_number)
+            .with(query)
+            .fetch_optional(self.storage)
+            .await?
+            .map(|row| DateTime::<Utc>::from_naive_utc_and_offset(row.reached_at, Utc));
+
+        Ok(reached_at)
+    }
+
     pub async fn get_tee_proofs(
         &mut self,
         batch_number: L1BatchNumber,
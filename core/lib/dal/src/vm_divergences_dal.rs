@@ -0,0 +1,220 @@
+use chrono::{DateTime, Utc};
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::{api::VmDivergence, L1BatchNumber};
+
+use crate::Core;
+
+/// Registry of divergences between the main and shadow VM (e.g., detected by the VM playground).
+#[derive(Debug)]
+pub struct VmDivergencesDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl VmDivergencesDal<'_, '_> {
+    /// Records a divergence. If a divergence with the same L1 batch, transaction index and kind is already recorded,
+    /// increments its occurrence counter instead; the dump key is updated only if it's provided.
+    ///
+    /// Returns `true` if a new divergence was recorded, and `false` if it was already present in the registry.
+    pub async fn insert_divergence(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        tx_index_in_l1_batch: Option<u32>,
+        kind: &str,
+        dump_key: Option<&str>,
+    ) -> DalResult<bool> {
+        let mut transaction = self.storage.start_transaction().await?;
+        let inserted_rows = sqlx::query!(
+            r#"
+            INSERT INTO
+            vm_divergences (
+                l1_batch_number,
+                tx_index_in_l1_batch,
+                kind,
+                dump_key,
+                occurrences,
+                first_seen_at,
+                last_seen_at
+            )
+            VALUES
+            ($1, $2, $3, $4, 1, NOW(), NOW())
+            ON CONFLICT (l1_batch_number, (COALESCE(tx_index_in_l1_batch, -1)), kind) DO NOTHING
+            "#,
+            i64::from(l1_batch_number.0),
+            tx_index_in_l1_batch.map(|idx| idx as i32),
+            kind,
+            dump_key
+        )
+        .instrument("insert_divergence")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("tx_index_in_l1_batch", &tx_index_in_l1_batch)
+        .with_arg("kind", &kind)
+        .execute(&mut transaction)
+        .await?
+        .rows_affected();
+
+        let is_new = inserted_rows > 0;
+        if !is_new {
+            sqlx::query!(
+                r#"
+                UPDATE vm_divergences
+                SET
+                    dump_key = COALESCE($4, dump_key),
+                    occurrences = occurrences + 1,
+                    last_seen_at = NOW()
+                WHERE
+                    l1_batch_number = $1
+                    AND COALESCE(tx_index_in_l1_batch, -1) = COALESCE($2, -1)
+                    AND kind = $3
+                "#,
+                i64::from(l1_batch_number.0),
+                tx_index_in_l1_batch.map(|idx| idx as i32),
+                kind,
+                dump_key
+            )
+            .instrument("insert_divergence#update")
+            .with_arg("l1_batch_number", &l1_batch_number)
+            .with_arg("tx_index_in_l1_batch", &tx_index_in_l1_batch)
+            .with_arg("kind", &kind)
+            .execute(&mut transaction)
+            .await?;
+        }
+        transaction.commit().await?;
+        Ok(is_new)
+    }
+
+    /// Returns recorded divergences for L1 batches starting from `from_l1_batch` (inclusive),
+    /// ordered by the L1 batch number and transaction index.
+    pub async fn get_divergences(
+        &mut self,
+        from_l1_batch: L1BatchNumber,
+        limit: usize,
+    ) -> DalResult<Vec<VmDivergence>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                tx_index_in_l1_batch,
+                kind,
+                dump_key,
+                occurrences,
+                first_seen_at,
+                last_seen_at
+            FROM
+                vm_divergences
+            WHERE
+                l1_batch_number >= $1
+            ORDER BY
+                l1_batch_number,
+                tx_index_in_l1_batch NULLS LAST,
+                kind
+            LIMIT
+                $2
+            "#,
+            i64::from(from_l1_batch.0),
+            limit as i64
+        )
+        .instrument("get_divergences")
+        .with_arg("from_l1_batch", &from_l1_batch)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| VmDivergence {
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                tx_index_in_l1_batch: row.tx_index_in_l1_batch.map(|idx| idx as u32),
+                kind: row.kind,
+                dump_key: row.dump_key,
+                occurrences: row.occurrences as u32,
+                first_seen_at: DateTime::<Utc>::from_naive_utc_and_offset(row.first_seen_at, Utc),
+                last_seen_at: DateTime::<Utc>::from_naive_utc_and_offset(row.last_seen_at, Utc),
+            })
+            .collect())
+    }
+
+    /// Returns the total number of recorded (deduplicated) divergences.
+    pub async fn get_divergences_count(&mut self) -> DalResult<u64> {
+        let count = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                vm_divergences
+            "#
+        )
+        .instrument("get_divergences_count")
+        .fetch_one(self.storage)
+        .await?
+        .count;
+        Ok(count as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn recording_divergences() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.vm_divergences_dal();
+
+        let is_new = dal
+            .insert_divergence(L1BatchNumber(2), Some(3), "result", None)
+            .await
+            .unwrap();
+        assert!(is_new);
+        let is_new = dal
+            .insert_divergence(L1BatchNumber(2), None, "pubdata_input", Some("dump.json"))
+            .await
+            .unwrap();
+        assert!(is_new);
+        dal.insert_divergence(L1BatchNumber(1), Some(0), "logs.events", Some("dump1.json"))
+            .await
+            .unwrap();
+        assert_eq!(dal.get_divergences_count().await.unwrap(), 3);
+
+        // Repeated divergences must be deduplicated, including ones without a transaction index.
+        let is_new = dal
+            .insert_divergence(L1BatchNumber(2), Some(3), "result", Some("dump2.json"))
+            .await
+            .unwrap();
+        assert!(!is_new);
+        let is_new = dal
+            .insert_divergence(L1BatchNumber(2), None, "pubdata_input", None)
+            .await
+            .unwrap();
+        assert!(!is_new);
+        assert_eq!(dal.get_divergences_count().await.unwrap(), 3);
+
+        let divergences = dal.get_divergences(L1BatchNumber(0), 10).await.unwrap();
+        let summary: Vec<_> = divergences
+            .iter()
+            .map(|div| {
+                (
+                    div.l1_batch_number.0,
+                    div.tx_index_in_l1_batch,
+                    div.kind.as_str(),
+                    div.dump_key.as_deref(),
+                    div.occurrences,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (1, Some(0), "logs.events", Some("dump1.json"), 1),
+                (2, Some(3), "result", Some("dump2.json"), 2),
+                (2, None, "pubdata_input", Some("dump.json"), 2),
+            ]
+        );
+        assert!(divergences[1].first_seen_at <= divergences[1].last_seen_at);
+
+        let divergences = dal.get_divergences(L1BatchNumber(2), 1).await.unwrap();
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].kind, "result");
+    }
+}
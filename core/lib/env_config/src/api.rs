@@ -3,7 +3,7 @@ use zksync_config::configs::{
     api::{
         ContractVerificationApiConfig, HealthCheckConfig, MerkleTreeApiConfig, Web3JsonRpcConfig,
     },
-    ApiConfig, PrometheusConfig,
+    ApiConfig, ApiSecrets, PrometheusConfig,
 };
 
 use crate::{envy_load, FromEnv};
@@ -25,6 +25,16 @@ impl FromEnv for Web3JsonRpcConfig {
     }
 }
 
+impl FromEnv for ApiSecrets {
+    fn from_env() -> anyhow::Result<Self> {
+        Ok(Self {
+            admin_token: std::env::var("API_WEB3_JSON_RPC_ADMIN_TOKEN")
+                .ok()
+                .map(Into::into),
+        })
+    }
+}
+
 impl FromEnv for HealthCheckConfig {
     fn from_env() -> anyhow::Result<Self> {
         envy_load("healthcheck", "API_HEALTHCHECK_")
@@ -46,8 +56,12 @@ impl FromEnv for MerkleTreeApiConfig {
 
 #[cfg(test)]
 mod tests {
-    use std::num::{NonZeroU32, NonZeroUsize};
+    use std::{
+        net::Ipv4Addr,
+        num::{NonZeroU32, NonZeroUsize},
+    };
 
+    use zksync_basic_types::secrets::APIKey;
     use zksync_config::configs::api::DeploymentAllowlist;
 
     use super::*;
@@ -98,6 +112,8 @@ mod tests {
                 api_namespaces: Some(vec!["debug".to_string()]),
                 extended_api_tracing: true,
                 deployment_allowlist: DeploymentAllowlist::default(),
+                admin_port: Some(3052),
+                admin_host: Some(Ipv4Addr::UNSPECIFIED.into()),
             },
             prometheus: PrometheusConfig {
                 listener_port: 3312,
@@ -108,6 +124,8 @@ mod tests {
                 port: 8081,
                 slow_time_limit_ms: Some(250),
                 hard_time_limit_ms: Some(2_000),
+                history_capacity: Some(50),
+                webhook_url: Some("http://127.0.0.1:8090/alerts".into()),
             },
             merkle_tree: MerkleTreeApiConfig { port: 8082 },
         }
@@ -130,6 +148,8 @@ mod tests {
             API_WEB3_JSON_RPC_GAS_PRICE_SCALE_FACTOR=1.2
             API_WEB3_JSON_RPC_API_NAMESPACES=debug
             API_WEB3_JSON_RPC_EXTENDED_API_TRACING=true
+            API_WEB3_JSON_RPC_ADMIN_PORT=3052
+            API_WEB3_JSON_RPC_ADMIN_HOST=0.0.0.0
             API_WEB3_JSON_RPC_WHITELISTED_TOKENS_FOR_AA="0x0000000000000000000000000000000000000001,0x0000000000000000000000000000000000000002"
             API_WEB3_JSON_RPC_ESTIMATE_GAS_SCALE_FACTOR=1.0
             API_WEB3_JSON_RPC_ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION=1000
@@ -154,6 +174,8 @@ mod tests {
             API_HEALTHCHECK_PORT=8081
             API_HEALTHCHECK_SLOW_TIME_LIMIT_MS=250
             API_HEALTHCHECK_HARD_TIME_LIMIT_MS=2000
+            API_HEALTHCHECK_HISTORY_CAPACITY=50
+            API_HEALTHCHECK_WEBHOOK_URL="http://127.0.0.1:8090/alerts"
             API_MERKLE_TREE_PORT=8082
         "#;
         lock.set_env(config);
@@ -161,4 +183,16 @@ mod tests {
         let actual = ApiConfig::from_env().unwrap();
         assert_eq!(actual, expected_config());
     }
+
+    #[test]
+    fn secrets_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            API_WEB3_JSON_RPC_ADMIN_TOKEN=admin-token
+        "#;
+        lock.set_env(config);
+
+        let actual = ApiSecrets::from_env().unwrap();
+        assert_eq!(actual.admin_token, Some(APIKey::from("admin-token")));
+    }
 }
@@ -31,7 +31,7 @@ impl FromEnv for MempoolConfig {
 #[cfg(test)]
 mod tests {
     use zksync_basic_types::commitment::L1BatchCommitmentMode;
-    use zksync_config::configs::chain::FeeModelVersion;
+    use zksync_config::configs::chain::{CircuitBreakerAction, FeeModelVersion};
 
     use super::*;
     use crate::test_utils::{addr, hash, EnvMutex};
@@ -173,6 +173,19 @@ mod tests {
             http_req_max_retry_number: 5,
             http_req_retry_interval_sec: 2,
             replication_lag_limit_sec: Some(10),
+            min_operator_l1_balance_gwei: Some(100_000_000),
+            da_dispatch_backlog_limit: None,
+            proof_generation_lag_limit: Some(500),
+            base_token_ratio_staleness_limit_sec: None,
+            actions: [
+                ("l1_balance".to_owned(), CircuitBreakerAction::Alert),
+                (
+                    "proof_generation_lag".to_owned(),
+                    CircuitBreakerAction::Pause(vec!["state_keeper".to_owned()]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
         }
     }
 
@@ -184,6 +197,9 @@ mod tests {
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_MAX_RETRY_NUMBER="5"
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_RETRY_INTERVAL_SEC="2"
             CHAIN_CIRCUIT_BREAKER_REPLICATION_LAG_LIMIT_SEC="10"
+            CHAIN_CIRCUIT_BREAKER_MIN_OPERATOR_L1_BALANCE_GWEI="100000000"
+            CHAIN_CIRCUIT_BREAKER_PROOF_GENERATION_LAG_LIMIT="500"
+            CHAIN_CIRCUIT_BREAKER_ACTIONS="l1_balance=alert,proof_generation_lag=pause:state_keeper"
         "#;
         lock.set_env(config);
 
@@ -77,6 +77,7 @@ mod tests {
                     time_in_mempool_in_l1_blocks_cap: 2000,
                     is_verifier_pre_fflonk: true,
                     gas_limit_mode: Default::default(),
+                    tee_quorum_required_from_batch: Some(100),
                 }),
                 Some(GasAdjusterConfig {
                     default_priority_fee_per_gas: 20000000000,
@@ -136,6 +137,7 @@ mod tests {
             ETH_SENDER_SENDER_MAX_AGGREGATED_TX_GAS="4000000"
             ETH_SENDER_SENDER_MAX_ETH_TX_DATA_SIZE="120000"
             ETH_SENDER_SENDER_TIME_IN_MEMPOOL_IN_L1_BLOCKS_CAP="2000"
+            ETH_SENDER_SENDER_TEE_QUORUM_REQUIRED_FROM_BATCH="100"
             ETH_SENDER_SENDER_L1_BATCH_MIN_AGE_BEFORE_EXECUTE_SECONDS="1000"
             ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
             ETH_SENDER_SENDER_PUBDATA_SENDING_MODE="Calldata"
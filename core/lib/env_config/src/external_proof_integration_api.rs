@@ -13,19 +13,40 @@ impl FromEnv for ExternalProofIntegrationApiConfig {
 
 #[cfg(test)]
 mod tests {
+    use zksync_config::configs::external_proof_integration_api::ExternalProverApiKey;
+
     use super::*;
     use crate::test_utils::EnvMutex;
 
     static MUTEX: EnvMutex = EnvMutex::new();
 
     fn expected_config() -> ExternalProofIntegrationApiConfig {
-        ExternalProofIntegrationApiConfig { http_port: 3320 }
+        ExternalProofIntegrationApiConfig {
+            http_port: 3320,
+            lease_timeout_in_secs: 600,
+            api_keys: vec![
+                ExternalProverApiKey {
+                    prover_id: "prover1".to_owned(),
+                    api_key: "key1".to_owned(),
+                },
+                ExternalProverApiKey {
+                    prover_id: "prover2".to_owned(),
+                    api_key: "key2".to_owned(),
+                },
+            ],
+            max_leases_per_prover: 2,
+            verification_keys_path: Some("prover/data/keys".to_owned()),
+        }
     }
 
     #[test]
     fn from_env() {
         let config = r#"
             EXTERNAL_PROOF_INTEGRATION_API_HTTP_PORT="3320"
+            EXTERNAL_PROOF_INTEGRATION_API_LEASE_TIMEOUT_IN_SECS="600"
+            EXTERNAL_PROOF_INTEGRATION_API_API_KEYS="prover1:key1,prover2:key2"
+            EXTERNAL_PROOF_INTEGRATION_API_MAX_LEASES_PER_PROVER="2"
+            EXTERNAL_PROOF_INTEGRATION_API_VERIFICATION_KEYS_PATH="prover/data/keys"
         "#;
         let mut lock = MUTEX.lock();
         lock.set_env(config);
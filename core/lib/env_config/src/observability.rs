@@ -26,7 +26,7 @@ impl FromEnv for ObservabilityConfig {
             }
         };
         let log_format = if let Ok(log_format) = std::env::var("MISC_LOG_FORMAT") {
-            if log_format != "plain" && log_format != "json" {
+            if !["plain", "json", "logfmt"].contains(&log_format.as_str()) {
                 anyhow::bail!("MISC_LOG_FORMAT has an unexpected value {}", log_format);
             }
             log_format
@@ -46,6 +46,7 @@ impl FromEnv for ObservabilityConfig {
         };
 
         let log_directives = std::env::var("RUST_LOG").ok();
+        let log_sampling = std::env::var("MISC_LOG_SAMPLING").ok();
 
         Ok(ObservabilityConfig {
             sentry_url,
@@ -53,6 +54,7 @@ impl FromEnv for ObservabilityConfig {
             log_format,
             opentelemetry,
             log_directives,
+            log_sampling,
         })
     }
 }
@@ -13,7 +13,7 @@ impl FromEnv for ProofDataHandlerConfig {
 
 #[cfg(test)]
 mod tests {
-    use zksync_basic_types::L1BatchNumber;
+    use zksync_basic_types::{tee_types::TeeType, L1BatchNumber};
     use zksync_config::configs::TeeConfig;
 
     use super::*;
@@ -30,6 +30,12 @@ mod tests {
                 first_tee_processed_batch: L1BatchNumber(1337),
                 tee_proof_generation_timeout_in_secs: 600,
                 tee_batch_permanently_ignored_timeout_in_hours: 240,
+                verify_attestations: true,
+                allowed_mrenclaves: vec!["00".repeat(32), "ff".repeat(32)],
+                allowed_mrtds: vec![],
+                allowed_tcb_statuses: vec!["UpToDate".to_owned(), "SWHardeningNeeded".to_owned()],
+                attestation_collateral_path: Some("/etc/tee/collateral".to_owned()),
+                quorum_tee_types: vec![TeeType::Sgx, TeeType::Tdx],
             },
         }
     }
@@ -43,6 +49,11 @@ mod tests {
             PROOF_DATA_HANDLER_FIRST_TEE_PROCESSED_BATCH="1337"
             PROOF_DATA_HANDLER_TEE_PROOF_GENERATION_TIMEOUT_IN_SECS="600"
             PROOF_DATA_HANDLER_TEE_BATCH_PERMANENTLY_IGNORED_TIMEOUT_IN_HOURS="240"
+            PROOF_DATA_HANDLER_VERIFY_ATTESTATIONS="true"
+            PROOF_DATA_HANDLER_ALLOWED_MRENCLAVES="0000000000000000000000000000000000000000000000000000000000000000,ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+            PROOF_DATA_HANDLER_ALLOWED_TCB_STATUSES="UpToDate,SWHardeningNeeded"
+            PROOF_DATA_HANDLER_ATTESTATION_COLLATERAL_PATH="/etc/tee/collateral"
+            PROOF_DATA_HANDLER_QUORUM_TEE_TYPES="sgx,tdx"
         "#;
         let mut lock = MUTEX.lock();
         lock.set_env(config);
@@ -31,6 +31,7 @@ mod tests {
             prover_queue_reporter_run_interval_ms: 10000,
             witness_generator_queue_reporter_run_interval_ms: 10000,
             witness_job_queuer_run_interval_ms: 10000,
+            batch_priority_ager_run_interval_ms: 60000,
             http_port: 3074,
         }
     }
@@ -49,6 +50,7 @@ mod tests {
         config.prover_queue_reporter_run_interval_ms += 1;
         config.witness_generator_queue_reporter_run_interval_ms += 1;
         config.witness_job_queuer_run_interval_ms += 1;
+        config.batch_priority_ager_run_interval_ms += 1;
         config
     }
 
@@ -82,6 +84,7 @@ mod tests {
             PROVER_JOB_MONITOR_PROVER_QUEUE_REPORTER_RUN_INTERVAL_MS=10001
             PROVER_JOB_MONITOR_WITNESS_GENERATOR_QUEUE_REPORTER_RUN_INTERVAL_MS=10001
             PROVER_JOB_MONITOR_WITNESS_JOB_QUEUER_RUN_INTERVAL_MS=10001
+            PROVER_JOB_MONITOR_BATCH_PRIORITY_AGER_RUN_INTERVAL_MS=60001
             PROVER_JOB_MONITOR_HTTP_PORT=3074
         "#;
         let mut lock = MUTEX.lock();
@@ -60,6 +60,7 @@ mod tests {
             EXPERIMENTAL_VM_PLAYGROUND_DB_PATH=/db/vm_playground
             EXPERIMENTAL_VM_PLAYGROUND_FIRST_PROCESSED_BATCH=123
             EXPERIMENTAL_VM_PLAYGROUND_RESET=true
+            EXPERIMENTAL_VM_PLAYGROUND_RECORD_DIVERGENCES=true
         "#;
         lock.set_env(config);
 
@@ -70,6 +71,7 @@ mod tests {
         assert_eq!(config.playground.db_path.unwrap(), "/db/vm_playground");
         assert_eq!(config.playground.first_processed_batch, L1BatchNumber(123));
         assert!(config.playground.reset);
+        assert!(config.playground.record_divergences);
 
         lock.remove_env(&["EXPERIMENTAL_VM_PLAYGROUND_RESET"]);
         let config = ExperimentalVmConfig::from_env().unwrap();
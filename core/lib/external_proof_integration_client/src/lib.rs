@@ -0,0 +1,178 @@
+//! Client for the external proof integration API, which allows external provers to lease batches,
+//! fetch their proof generation data and submit proofs.
+
+use futures::{stream, Stream, StreamExt};
+use reqwest::{multipart, RequestBuilder, Response, StatusCode};
+use url::Url;
+use zksync_basic_types::L1BatchNumber;
+use zksync_object_store::{StoredObject, _reexports::BoxedError};
+use zksync_prover_interface::{
+    api::{ExternalProofLease, ExternalProofStatusResponse},
+    inputs::WitnessInputData,
+    outputs::L1BatchProofForL1,
+};
+
+#[cfg(test)]
+mod tests;
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("API responded with {status}: {message}")]
+    Api { status: StatusCode, message: String },
+    #[error("failed serializing or deserializing data: {0}")]
+    Serialization(BoxedError),
+    #[error("failed parsing status event: {0}")]
+    StatusEvent(#[from] serde_json::Error),
+}
+
+impl ClientError {
+    /// Returns the HTTP status returned by the API, if any. E.g., `409 Conflict` is returned
+    /// when submitting a proof for an already proven batch.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Http(err) => err.status(),
+            Self::Api { status, .. } => Some(*status),
+            Self::Serialization(_) | Self::StatusEvent(_) => None,
+        }
+    }
+}
+
+/// Client for a single external prover. The prover is identified by its API key, which the server maps
+/// to the prover ID used as the owner of batch leases.
+#[derive(Debug, Clone)]
+pub struct ExternalProofIntegrationClient {
+    client: reqwest::Client,
+    base_url: Url,
+    api_key: String,
+}
+
+impl ExternalProofIntegrationClient {
+    pub fn new(base_url: Url, api_key: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            api_key: api_key.into(),
+        }
+    }
+
+    fn url(&self, path: &str) -> Url {
+        // `Url::join()` could only fail on an invalid path, and all paths are hardcoded.
+        self.base_url.join(path).expect("invalid API path")
+    }
+
+    fn request(&self, builder: RequestBuilder) -> RequestBuilder {
+        builder.header(API_KEY_HEADER, &self.api_key)
+    }
+
+    async fn send(&self, builder: RequestBuilder) -> Result<Response, ClientError> {
+        let response = self.request(builder).send().await?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(response)
+        } else {
+            let message = response.text().await.unwrap_or_default();
+            Err(ClientError::Api { status, message })
+        }
+    }
+
+    /// Leases the oldest batch available for proving. Returns `None` if there are no such batches.
+    pub async fn lease_batch(&self) -> Result<Option<ExternalProofLease>, ClientError> {
+        let response = self.send(self.client.post(self.url("lease_batch"))).await?;
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+        Ok(Some(response.json().await?))
+    }
+
+    /// Fetches proof generation data for a batch. The batch must be leased by this prover, or be already proven.
+    pub async fn proof_generation_data(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<WitnessInputData, ClientError> {
+        let url = self.url(&format!("proof_generation_data/{l1_batch_number}"));
+        let bytes = self.send(self.client.get(url)).await?.bytes().await?;
+        WitnessInputData::deserialize(bytes.to_vec()).map_err(ClientError::Serialization)
+    }
+
+    /// Submits a proof for a batch leased by this prover.
+    pub async fn submit_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        proof: &L1BatchProofForL1,
+    ) -> Result<(), ClientError> {
+        let serialized_proof = proof.serialize().map_err(ClientError::Serialization)?;
+        let part = multipart::Part::bytes(serialized_proof)
+            .file_name(format!("proof_{l1_batch_number}.cbor"))
+            .mime_str("application/octet-stream")?;
+        let form = multipart::Form::new().part("proof", part);
+        let url = self.url(&format!("submit_proof/{l1_batch_number}"));
+        self.send(self.client.post(url).multipart(form)).await?;
+        Ok(())
+    }
+
+    pub async fn batch_status(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<ExternalProofStatusResponse, ClientError> {
+        let url = self.url(&format!("batch_status/{l1_batch_number}"));
+        Ok(self.send(self.client.get(url)).await?.json().await?)
+    }
+
+    /// Subscribes to status updates for a batch. The stream starts with the current status and ends
+    /// after a final status is received.
+    pub async fn batch_status_updates(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<impl Stream<Item = Result<ExternalProofStatusResponse, ClientError>>, ClientError>
+    {
+        let url = self.url(&format!("batch_status/{l1_batch_number}/stream"));
+        let response = self.send(self.client.get(url)).await?;
+        let mut parser = SseParser::default();
+        let updates = response
+            .bytes_stream()
+            .map(move |chunk| match chunk {
+                Ok(chunk) => parser
+                    .push(&chunk)
+                    .into_iter()
+                    .map(|data| {
+                        serde_json::from_str::<ExternalProofStatusResponse>(&data)
+                            .map_err(ClientError::from)
+                    })
+                    .collect::<Vec<_>>(),
+                Err(err) => vec![Err(err.into())],
+            })
+            .flat_map(stream::iter);
+        Ok(updates)
+    }
+}
+
+/// Minimal incremental parser of server-sent events returning `data` of each event.
+#[derive(Debug, Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = vec![];
+        while let Some(pos) = self.buffer.windows(2).position(|window| window == b"\n\n") {
+            let raw_event: Vec<_> = self.buffer.drain(..pos + 2).collect();
+            let raw_event = String::from_utf8_lossy(&raw_event);
+            let data_lines: Vec<_> = raw_event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect();
+            // Events without data (e.g., keep-alive comments) are skipped.
+            if !data_lines.is_empty() {
+                events.push(data_lines.join("\n"));
+            }
+        }
+        events
+    }
+}
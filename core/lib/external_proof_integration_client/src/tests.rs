@@ -0,0 +1,110 @@
+use futures::TryStreamExt;
+use httpmock::{Method, MockServer};
+use zksync_prover_interface::api::{ExternalProofGenerationStatus, ExternalProofLeaseInfo};
+
+use super::*;
+
+fn client(server: &MockServer) -> ExternalProofIntegrationClient {
+    let base_url = Url::parse(&server.base_url()).unwrap();
+    ExternalProofIntegrationClient::new(base_url, "key")
+}
+
+fn timestamp() -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+}
+
+fn status(status: ExternalProofGenerationStatus) -> ExternalProofStatusResponse {
+    ExternalProofStatusResponse {
+        l1_batch_number: L1BatchNumber(1),
+        status,
+        lease: Some(ExternalProofLeaseInfo {
+            owner: "prover".to_owned(),
+            expires_at: timestamp(),
+        }),
+        updated_at: timestamp(),
+    }
+}
+
+#[tokio::test]
+async fn leasing_batch() {
+    let server = MockServer::start();
+    let lease = ExternalProofLease {
+        l1_batch_number: L1BatchNumber(1),
+        expires_at: timestamp(),
+    };
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/lease_batch")
+            .header(API_KEY_HEADER, "key");
+        then.status(200).json_body_obj(&lease);
+    });
+
+    let leased = client(&server).lease_batch().await.unwrap();
+    mock.assert();
+    assert_eq!(leased, Some(lease));
+}
+
+#[tokio::test]
+async fn leasing_batch_without_available_batches() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::POST).path("/lease_batch");
+        then.status(204);
+    });
+
+    let leased = client(&server).lease_batch().await.unwrap();
+    assert_eq!(leased, None);
+}
+
+#[tokio::test]
+async fn api_errors_are_propagated() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::GET).path("/batch_status/1");
+        then.status(404).body("Batch 1 is not known");
+    });
+
+    let err = client(&server)
+        .batch_status(L1BatchNumber(1))
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+    assert!(err.to_string().contains("Batch 1 is not known"), "{err}");
+}
+
+#[tokio::test]
+async fn streaming_batch_status() {
+    let server = MockServer::start();
+    let statuses = [
+        status(ExternalProofGenerationStatus::PickedByProver),
+        status(ExternalProofGenerationStatus::Generated),
+    ];
+    let body: String = statuses
+        .iter()
+        .map(|status| {
+            let data = serde_json::to_string(status).unwrap();
+            format!(":\n\nevent: status\ndata: {data}\n\n")
+        })
+        .collect();
+    server.mock(|when, then| {
+        when.method(Method::GET).path("/batch_status/1/stream");
+        then.status(200)
+            .header("content-type", "text/event-stream")
+            .body(body);
+    });
+
+    let updates = client(&server)
+        .batch_status_updates(L1BatchNumber(1))
+        .await
+        .unwrap();
+    let updates: Vec<_> = updates.try_collect().await.unwrap();
+    assert_eq!(updates, statuses);
+}
+
+#[test]
+fn parsing_chunked_events() {
+    let mut parser = SseParser::default();
+    assert!(parser.push(b"event: status\ndata: {\"a\"").is_empty());
+    assert_eq!(parser.push(b": 1}\n\n:\n\ndata: 2\n"), ["{\"a\": 1}"]);
+    assert_eq!(parser.push(b"data: 3\n\n"), ["2\n3"]);
+}
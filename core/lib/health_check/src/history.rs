@@ -0,0 +1,198 @@
+//! Bounded history of component health transitions.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::{Health, HealthStatus};
+
+/// Transition of the health status of a component, as observed by [`AppHealthCheck`](crate::AppHealthCheck).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthTransition {
+    /// Name of the component.
+    pub component: &'static str,
+    /// Previous status of the component. `None` if the component is observed for the first time.
+    pub from: Option<HealthStatus>,
+    /// New status of the component.
+    pub to: HealthStatus,
+    /// Health details of the component after the transition.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    /// UNIX timestamp of the transition in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+#[derive(Debug)]
+pub(crate) struct HealthHistory {
+    capacity: usize,
+    /// Last recorded status of each component together with the time it was observed.
+    last_statuses: HashMap<&'static str, (HealthStatus, Instant)>,
+    transitions: HashMap<&'static str, VecDeque<HealthTransition>>,
+    sender: broadcast::Sender<HealthTransition>,
+}
+
+impl HealthHistory {
+    pub(crate) const DEFAULT_CAPACITY: usize = 32;
+    /// Capacity of the channel broadcasting transitions. Slow subscribers will miss the oldest transitions.
+    const CHANNEL_CAPACITY: usize = 128;
+
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            last_statuses: HashMap::new(),
+            transitions: HashMap::new(),
+            sender: broadcast::channel(Self::CHANNEL_CAPACITY).0,
+        }
+    }
+
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        for transitions in self.transitions.values_mut() {
+            let excess = transitions.len().saturating_sub(capacity);
+            transitions.drain(..excess);
+        }
+    }
+
+    /// Records the component health observed at `observed_at`. Health checks may run concurrently, so observations
+    /// can arrive out of order; observations older than the last recorded one for the component are dropped.
+    pub(crate) fn observe(
+        &mut self,
+        component: &'static str,
+        health: &Health,
+        observed_at: Instant,
+    ) {
+        self.observe_at(component, health, observed_at, SystemTime::now());
+    }
+
+    fn observe_at(
+        &mut self,
+        component: &'static str,
+        health: &Health,
+        observed_at: Instant,
+        now: SystemTime,
+    ) {
+        if let Some((_, last_observed_at)) = self.last_statuses.get(component) {
+            if observed_at < *last_observed_at {
+                tracing::debug!("Dropped outdated health observation for component `{component}`");
+                return;
+            }
+        }
+
+        let from = self
+            .last_statuses
+            .insert(component, (health.status, observed_at))
+            .map(|(status, _)| status);
+        if from == Some(health.status) {
+            return;
+        }
+
+        let timestamp_ms = now
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |timestamp| timestamp.as_millis() as u64);
+        let transition = HealthTransition {
+            component,
+            from,
+            to: health.status,
+            details: health.details.clone(),
+            timestamp_ms,
+        };
+        tracing::debug!("Recorded health transition: {transition:?}");
+
+        if self.capacity > 0 {
+            let transitions = self.transitions.entry(component).or_default();
+            if transitions.len() >= self.capacity {
+                transitions.pop_front();
+            }
+            transitions.push_back(transition.clone());
+        }
+        // Sending only fails if there are no subscribers, which is fine.
+        self.sender.send(transition).ok();
+    }
+
+    pub(crate) fn snapshot(&self) -> HashMap<&'static str, Vec<HealthTransition>> {
+        self.transitions
+            .iter()
+            .map(|(&component, transitions)| (component, transitions.iter().cloned().collect()))
+            .collect()
+    }
+
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<HealthTransition> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn recording_transitions() {
+        let mut history = HealthHistory::new(2);
+        let mut transitions = history.subscribe();
+        let observed_at = Instant::now();
+        let start = UNIX_EPOCH + Duration::from_secs(1_000);
+        history.observe_at("test", &HealthStatus::NotReady.into(), observed_at, start);
+        history.observe_at("test", &HealthStatus::NotReady.into(), observed_at, start);
+        history.observe_at(
+            "test",
+            &HealthStatus::Ready.into(),
+            observed_at,
+            start + Duration::from_secs(1),
+        );
+        let affected = Health::from(HealthStatus::Affected).with_details("lagging");
+        history.observe_at(
+            "test",
+            &affected,
+            observed_at,
+            start + Duration::from_secs(2),
+        );
+
+        let snapshot = history.snapshot();
+        let recorded = &snapshot["test"];
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(recorded[0].from, Some(HealthStatus::NotReady));
+        assert_eq!(recorded[0].to, HealthStatus::Ready);
+        assert_eq!(recorded[0].timestamp_ms, 1_001_000);
+        assert_eq!(recorded[1].to, HealthStatus::Affected);
+        assert_eq!(recorded[1].details, Some("lagging".into()));
+
+        let first_transition = transitions.try_recv().unwrap();
+        assert_eq!(first_transition.from, None);
+        assert_eq!(first_transition.to, HealthStatus::NotReady);
+        assert_eq!(transitions.len(), 2);
+
+        history.set_capacity(1);
+        let snapshot = history.snapshot();
+        assert_eq!(snapshot["test"].len(), 1);
+        assert_eq!(snapshot["test"][0].to, HealthStatus::Affected);
+    }
+
+    #[test]
+    fn dropping_outdated_observations() {
+        let mut history = HealthHistory::new(4);
+        let first_observed_at = Instant::now();
+        let last_observed_at = first_observed_at + Duration::from_millis(10);
+        history.observe("test", &HealthStatus::NotReady.into(), first_observed_at);
+        history.observe("test", &HealthStatus::Ready.into(), last_observed_at);
+        // Emulate a concurrent health check that has computed health earlier, but records it later.
+        history.observe("test", &HealthStatus::NotReady.into(), first_observed_at);
+
+        let snapshot = history.snapshot();
+        let statuses: Vec<_> = snapshot["test"]
+            .iter()
+            .map(|transition| transition.to)
+            .collect();
+        assert_eq!(statuses, [HealthStatus::NotReady, HealthStatus::Ready]);
+
+        history.observe("test", &HealthStatus::Affected.into(), last_observed_at);
+        let snapshot = history.snapshot();
+        assert_eq!(snapshot["test"].len(), 3);
+        assert_eq!(snapshot["test"][2].from, Some(HealthStatus::Ready));
+    }
+}
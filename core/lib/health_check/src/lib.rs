@@ -3,17 +3,22 @@ use std::{
     fmt,
     sync::{Arc, Mutex},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 // Public re-export for other crates to be able to implement the interface.
 pub use async_trait::async_trait;
 use futures::future;
 use serde::Serialize;
-use tokio::sync::watch;
+use tokio::sync::{broadcast, watch};
 
-use crate::metrics::{AppHealthCheckConfig, CheckResult, METRICS};
+pub use crate::history::HealthTransition;
+use crate::{
+    history::HealthHistory,
+    metrics::{AppHealthCheckConfig, CheckResult, METRICS},
+};
 
+mod history;
 mod metrics;
 
 #[cfg(test)]
@@ -107,6 +112,7 @@ pub enum AppHealthCheckError {
 #[derive(Debug)]
 pub struct AppHealthCheck {
     inner: Mutex<AppHealthCheckInner>,
+    history: Mutex<HealthHistory>,
 }
 
 #[derive(Debug, Clone)]
@@ -141,6 +147,7 @@ impl AppHealthCheck {
         };
         Self {
             inner: Mutex::new(inner),
+            history: Mutex::new(HealthHistory::new(HealthHistory::DEFAULT_CAPACITY)),
         }
     }
 
@@ -163,6 +170,33 @@ impl AppHealthCheck {
         );
     }
 
+    /// Sets the maximum number of health transitions retained for each component.
+    pub fn set_history_capacity(&self, capacity: usize) {
+        let mut history = self.history.lock().expect("health history is poisoned");
+        history.set_capacity(capacity);
+        tracing::debug!("Set health history capacity to {capacity}");
+    }
+
+    /// Returns recorded health transitions for each component, from oldest to newest.
+    ///
+    /// Transitions are recorded when the application health is checked using [`Self::check_health()`];
+    /// thus, transitions between checks may be missed.
+    pub fn history(&self) -> HashMap<&'static str, Vec<HealthTransition>> {
+        self.history
+            .lock()
+            .expect("health history is poisoned")
+            .snapshot()
+    }
+
+    /// Subscribes to health transitions. Transitions are broadcast as they are recorded (see [`Self::history()`]);
+    /// lagging receivers miss the oldest transitions.
+    pub fn subscribe_to_transitions(&self) -> broadcast::Receiver<HealthTransition> {
+        self.history
+            .lock()
+            .expect("health history is poisoned")
+            .subscribe()
+    }
+
     /// Sets the info metrics for the metrics time limits.
     /// This method should be called at most once when all the health checks are collected.
     pub fn expose_metrics(&self) {
@@ -240,6 +274,15 @@ impl AppHealthCheck {
             Self::check_health_with_time_limit(check.as_ref(), slow_time_limit, hard_time_limit)
         });
         let components: HashMap<_, _> = future::join_all(check_futures).await.into_iter().collect();
+        // Concurrent checks may lock the history in a different order than they have finished, so the history
+        // uses this timestamp to drop outdated observations.
+        let observed_at = Instant::now();
+
+        let mut history = self.history.lock().expect("health history is poisoned");
+        for (&component, health) in &components {
+            history.observe(component, health, observed_at);
+        }
+        drop(history);
 
         let aggregated_status = components
             .values()
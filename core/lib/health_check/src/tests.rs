@@ -153,3 +153,37 @@ fn adding_duplicate_component() {
         .unwrap_err();
     assert_matches!(err, AppHealthCheckError::RedefinedComponent("test"));
 }
+
+#[tokio::test]
+async fn recording_health_history() {
+    let checks = AppHealthCheck::default();
+    checks.set_history_capacity(2);
+    let mut transitions = checks.subscribe_to_transitions();
+    let (health_check, health_updater) = ReactiveHealthCheck::new("test");
+    checks.insert_component(health_check).unwrap();
+
+    checks.check_health().await;
+    health_updater.update(HealthStatus::Ready.into());
+    checks.check_health().await;
+    checks.check_health().await;
+    health_updater.update(Health::from(HealthStatus::Affected).with_details("lagging"));
+    checks.check_health().await;
+
+    let history = checks.history();
+    let statuses: Vec<_> = history["test"]
+        .iter()
+        .map(|transition| (transition.from, transition.to))
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            (Some(HealthStatus::NotReady), HealthStatus::Ready),
+            (Some(HealthStatus::Ready), HealthStatus::Affected)
+        ]
+    );
+
+    let first_transition = transitions.try_recv().unwrap();
+    assert_eq!(first_transition.component, "test");
+    assert_eq!(first_transition.from, None);
+    assert_eq!(first_transition.to, HealthStatus::NotReady);
+}
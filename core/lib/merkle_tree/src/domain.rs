@@ -438,6 +438,20 @@ impl ZkSyncTreeReader {
         })
     }
 
+    /// Reads entries with the specified keys from the tree. The entries are returned in the same order as requested.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn entries(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        keys: &[Key],
+    ) -> Result<Vec<TreeEntry>, NoVersionError> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.entries(version, keys)
+    }
+
     /// Reads entries together with Merkle proofs with the specified keys from the tree. The entries are returned
     /// in the same order as requested.
     ///
@@ -4,7 +4,9 @@ use crate::{
     hasher::HasherWithStats,
     recovery::MerkleTreeRecovery,
     storage::{LoadAncestorsResult, SortedKeys, WorkingPatchSet},
-    types::{Nibbles, Node, ProfiledTreeOperation, TreeEntry, TreeEntryWithProof},
+    types::{
+        InternalNode, Nibbles, Node, ProfiledTreeOperation, Root, TreeEntry, TreeEntryWithProof,
+    },
     Database, HashTree, Key, MerkleTree, NoVersionError, PruneDatabase, ValueHash,
 };
 
@@ -61,6 +63,68 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
     }
 }
 
+impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
+    /// Visits all entries in the tree at the specified `version` in the increasing key order.
+    /// The tree is traversed depth-first, so memory consumption doesn't depend on the tree size.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing, or if the `visitor` returns an error.
+    /// In the latter case, traversal is stopped immediately.
+    pub fn for_each_entry(
+        &self,
+        version: u64,
+        mut visitor: impl FnMut(TreeEntry) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let root = self.db.root(version).ok_or_else(|| {
+            let manifest = self.db.manifest().unwrap_or_default();
+            NoVersionError {
+                missing_version: version,
+                version_count: manifest.version_count,
+            }
+        })?;
+        match root {
+            Root::Empty => Ok(()),
+            Root::Filled {
+                node: Node::Leaf(leaf),
+                ..
+            } => visitor(leaf.into()),
+            Root::Filled {
+                node: Node::Internal(node),
+                ..
+            } => visit_internal_node(&self.db, &node, Nibbles::EMPTY, &mut visitor),
+        }
+    }
+}
+
+fn visit_internal_node(
+    db: &impl Database,
+    node: &InternalNode,
+    nibbles: Nibbles,
+    visitor: &mut dyn FnMut(TreeEntry) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    // Children are iterated in the increasing nibble order, which translates to the increasing key order for leaves.
+    let child_keys: Vec<_> = node
+        .children()
+        .map(|(nibble, child_ref)| {
+            let child_nibbles = nibbles.push(nibble).expect("internal node at max depth");
+            (
+                child_nibbles.with_version(child_ref.version),
+                child_ref.is_leaf,
+            )
+        })
+        .collect();
+    let children = db.tree_nodes(&child_keys);
+    for ((child_key, _), child) in child_keys.iter().zip(children) {
+        let child = child.ok_or_else(|| anyhow::anyhow!("node with key {child_key} is missing"))?;
+        match child {
+            Node::Leaf(leaf) => visitor(leaf.into())?,
+            Node::Internal(child) => visit_internal_node(db, &child, child_key.nibbles, visitor)?,
+        }
+    }
+    Ok(())
+}
+
 fn load_and_transform_entries<T>(
     db: &impl Database,
     version: u64,
@@ -157,4 +221,58 @@ mod tests {
         assert!(entries[1].base.is_empty());
         entries[1].verify(&tree.hasher, output.root_hash).unwrap();
     }
+
+    fn collect_entries(tree: &MerkleTree<PatchSet>, version: u64) -> Vec<TreeEntry> {
+        let mut entries = vec![];
+        tree.for_each_entry(version, |entry| {
+            entries.push(entry);
+            Ok(())
+        })
+        .unwrap();
+        entries
+    }
+
+    #[test]
+    fn iterating_over_entries() {
+        let mut tree = MerkleTree::new(PatchSet::default()).unwrap();
+        tree.extend(vec![]).unwrap();
+        assert!(collect_entries(&tree, 0).is_empty());
+
+        let key = Key::from(987_654);
+        tree.extend(vec![TreeEntry::new(key, 1, ValueHash::repeat_byte(1))])
+            .unwrap();
+        assert_eq!(
+            collect_entries(&tree, 1),
+            [TreeEntry::new(key, 1, ValueHash::repeat_byte(1))]
+        );
+
+        let new_entries: Vec<_> = (2..=100_u64)
+            .map(|i| {
+                let key = Key::from(i.wrapping_mul(0x_9e37_79b9_7f4a_7c15)) << 128;
+                TreeEntry::new(key, i, ValueHash::from_low_u64_be(i))
+            })
+            .collect();
+        tree.extend(new_entries.clone()).unwrap();
+
+        let mut expected_entries = new_entries;
+        expected_entries.push(TreeEntry::new(key, 1, ValueHash::repeat_byte(1)));
+        expected_entries.sort_unstable_by_key(|entry| entry.key);
+        assert_eq!(collect_entries(&tree, 2), expected_entries);
+        // Check that the previous versions are still traversed correctly.
+        assert_eq!(collect_entries(&tree, 1).len(), 1);
+
+        let err = tree.for_each_entry(3, |_| Ok(())).unwrap_err();
+        assert!(err.to_string().contains("does not exist"), "{err}");
+
+        let mut visited_count = 0;
+        let err = tree
+            .for_each_entry(2, |_| {
+                visited_count += 1;
+                anyhow::ensure!(visited_count < 10, "stop");
+                Ok(())
+            })
+            .unwrap_err();
+        assert_eq!(err.to_string(), "stop");
+        assert_eq!(visited_count, 10);
+    }
 }
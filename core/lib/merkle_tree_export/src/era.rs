@@ -0,0 +1,109 @@
+//! Export / import for the Era Merkle tree.
+
+use std::{
+    io::{Read, Write},
+    mem,
+};
+
+use anyhow::Context as _;
+use zksync_basic_types::{h256_to_u256, u256_to_h256};
+use zksync_merkle_tree::{
+    recovery::MerkleTreeRecovery, unstable::Root, Database, MerkleTree, PruneDatabase, TreeEntry,
+};
+
+use crate::{
+    format::{ExportEntry, ExportHeader, ExportReader, ExportWriter, TreeKind},
+    IMPORT_CHUNK_SIZE,
+};
+
+/// Exports the specified `version` of the Era tree stored in `db`. Entries are written in the increasing key order.
+///
+/// # Errors
+///
+/// Errors if the version is missing from the tree, or on I/O errors.
+pub fn export_era_tree<DB: Database>(
+    db: DB,
+    version: u64,
+    writer: impl Write,
+) -> anyhow::Result<ExportHeader> {
+    let root = db
+        .try_root(version)?
+        .with_context(|| format!("Era tree version {version} does not exist"))?;
+    let entry_count = match root {
+        Root::Empty => 0,
+        Root::Filled { leaf_count, .. } => leaf_count.get(),
+    };
+    let tree = MerkleTree::new(db).context("failed initializing Era tree")?;
+    let root_hash = tree
+        .root_hash(version)
+        .with_context(|| format!("Era tree version {version} does not exist"))?;
+
+    let header = ExportHeader {
+        tree_kind: TreeKind::Era,
+        tree_version: version,
+        root_hash,
+        entry_count,
+    };
+    tracing::info!(?header, "Exporting Era Merkle tree");
+    let mut writer = ExportWriter::new(writer, header)?;
+    tree.for_each_entry(version, |entry| {
+        let entry = ExportEntry {
+            key: u256_to_h256(entry.key),
+            value: entry.value,
+            leaf_index: entry.leaf_index,
+        };
+        writer.write_entry(&entry).map_err(Into::into)
+    })?;
+    writer.finish()?;
+    Ok(header)
+}
+
+/// Imports the Era tree from an export file into an empty `db`. The tree version and the root hash are taken
+/// from the file header; the root hash is checked after all entries are imported.
+///
+/// # Errors
+///
+/// Errors if the file is malformed or corrupted, or if the root hash of the imported tree doesn't match.
+/// In this case, the tree in `db` remains in the recovery state.
+pub fn import_era_tree<DB: PruneDatabase>(
+    mut reader: ExportReader<impl Read>,
+    db: DB,
+) -> anyhow::Result<DB> {
+    let header = *reader.header();
+    anyhow::ensure!(
+        header.tree_kind == TreeKind::Era,
+        "export file contains {:?} tree, while the Era tree was expected",
+        header.tree_kind
+    );
+    tracing::info!(?header, "Importing Era Merkle tree");
+
+    let mut recovery = MerkleTreeRecovery::new(db, header.tree_version)?;
+    let mut chunk = Vec::with_capacity(IMPORT_CHUNK_SIZE);
+    let mut prev_key = None;
+    while let Some(entry) = reader.read_entry()? {
+        let key = h256_to_u256(entry.key);
+        // Linear recovery panics on misordered keys, so we check ordering beforehand.
+        anyhow::ensure!(
+            prev_key.map_or(true, |prev| prev < key),
+            "entries in the export file are not ordered by increasing key: {prev_key:?} is followed by {key:?}"
+        );
+        prev_key = Some(key);
+
+        chunk.push(TreeEntry::new(key, entry.leaf_index, entry.value));
+        if chunk.len() == IMPORT_CHUNK_SIZE {
+            recovery.extend_linear(mem::take(&mut chunk))?;
+        }
+    }
+    if !chunk.is_empty() {
+        recovery.extend_linear(chunk)?;
+    }
+    reader.finish()?;
+
+    let root_hash = recovery.root_hash();
+    anyhow::ensure!(
+        root_hash == header.root_hash,
+        "root hash of the imported tree {root_hash:?} differs from the expected {:?}",
+        header.root_hash
+    );
+    recovery.finalize()
+}
@@ -0,0 +1,245 @@
+//! Export file format. See the crate README for the format specification.
+
+use std::io::{self, Read, Write};
+
+use anyhow::Context as _;
+use sha2::{Digest, Sha256};
+use zksync_basic_types::H256;
+
+const MAGIC: [u8; 8] = *b"zkstree\0";
+const FORMAT_VERSION: u32 = 1;
+
+/// Kind of the exported Merkle tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeKind {
+    /// Era tree from the `zksync_merkle_tree` crate.
+    Era,
+    /// ZK OS tree from the `zk_os_merkle_tree` crate.
+    ZkOs,
+}
+
+impl TreeKind {
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::Era => 0,
+            Self::ZkOs => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> anyhow::Result<Self> {
+        Ok(match byte {
+            0 => Self::Era,
+            1 => Self::ZkOs,
+            _ => anyhow::bail!("unknown tree kind: {byte}"),
+        })
+    }
+}
+
+/// Header of an export file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportHeader {
+    /// Kind of the exported tree.
+    pub tree_kind: TreeKind,
+    /// Exported tree version.
+    pub tree_version: u64,
+    /// Root hash of the exported tree version.
+    pub root_hash: H256,
+    /// Number of entries in the file.
+    pub entry_count: u64,
+}
+
+/// Tree entry stored in an export file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportEntry {
+    /// Tree key. For the Era tree, this is a big-endian encoding of the `U256` key.
+    pub key: H256,
+    /// Value associated with the key.
+    pub value: H256,
+    /// Leaf index (aka enumeration index) of the entry.
+    pub leaf_index: u64,
+}
+
+impl ExportEntry {
+    const BYTE_LEN: usize = 72;
+}
+
+/// Streaming writer of export files.
+#[derive(Debug)]
+pub struct ExportWriter<W> {
+    inner: W,
+    hasher: Sha256,
+    header: ExportHeader,
+    written_entry_count: u64,
+}
+
+impl<W: Write> ExportWriter<W> {
+    /// Creates a writer and writes the header to it.
+    ///
+    /// # Errors
+    ///
+    /// Proxies I/O errors.
+    pub fn new(writer: W, header: ExportHeader) -> io::Result<Self> {
+        let mut this = Self {
+            inner: writer,
+            hasher: Sha256::new(),
+            header,
+            written_entry_count: 0,
+        };
+        this.write(&MAGIC)?;
+        this.write(&FORMAT_VERSION.to_le_bytes())?;
+        this.write(&[header.tree_kind.to_byte()])?;
+        this.write(&header.tree_version.to_le_bytes())?;
+        this.write(header.root_hash.as_bytes())?;
+        this.write(&header.entry_count.to_le_bytes())?;
+        Ok(this)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.hasher.update(bytes);
+        self.inner.write_all(bytes)
+    }
+
+    /// Writes a single entry.
+    ///
+    /// # Errors
+    ///
+    /// Proxies I/O errors.
+    pub fn write_entry(&mut self, entry: &ExportEntry) -> io::Result<()> {
+        let mut buffer = [0_u8; ExportEntry::BYTE_LEN];
+        buffer[..32].copy_from_slice(entry.key.as_bytes());
+        buffer[32..64].copy_from_slice(entry.value.as_bytes());
+        buffer[64..].copy_from_slice(&entry.leaf_index.to_le_bytes());
+        self.write(&buffer)?;
+        self.written_entry_count += 1;
+        Ok(())
+    }
+
+    /// Writes the checksum and flushes the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// - Errors if the number of written entries differs from the one declared in the header.
+    /// - Proxies I/O errors.
+    pub fn finish(mut self) -> anyhow::Result<W> {
+        anyhow::ensure!(
+            self.written_entry_count == self.header.entry_count,
+            "written {} entries, while the header declares {}",
+            self.written_entry_count,
+            self.header.entry_count
+        );
+        let checksum = self.hasher.finalize();
+        self.inner.write_all(&checksum)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Streaming reader of export files.
+///
+/// Entries are returned before the file checksum is verified; thus, the consumer must not treat them as valid until
+/// [`Self::finish()`] returns successfully.
+#[derive(Debug)]
+pub struct ExportReader<R> {
+    inner: R,
+    hasher: Sha256,
+    header: ExportHeader,
+    read_entry_count: u64,
+}
+
+impl<R: Read> ExportReader<R> {
+    /// Creates a reader and reads the header from it.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the header is malformed or has an unsupported format version.
+    pub fn new(reader: R) -> anyhow::Result<Self> {
+        let mut this = Self {
+            inner: reader,
+            hasher: Sha256::new(),
+            header: ExportHeader {
+                tree_kind: TreeKind::Era,
+                tree_version: 0,
+                root_hash: H256::zero(),
+                entry_count: 0,
+            },
+            read_entry_count: 0,
+        };
+
+        let magic: [u8; 8] = this.read().context("failed reading magic")?;
+        anyhow::ensure!(magic == MAGIC, "not a Merkle tree export file");
+        let format_version = u32::from_le_bytes(this.read()?);
+        anyhow::ensure!(
+            format_version == FORMAT_VERSION,
+            "unsupported export format version: {format_version}, expected {FORMAT_VERSION}"
+        );
+        let [tree_kind] = this.read()?;
+        this.header = ExportHeader {
+            tree_kind: TreeKind::from_byte(tree_kind)?,
+            tree_version: u64::from_le_bytes(this.read()?),
+            root_hash: H256(this.read()?),
+            entry_count: u64::from_le_bytes(this.read()?),
+        };
+        Ok(this)
+    }
+
+    fn read<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut buffer = [0_u8; N];
+        self.inner.read_exact(&mut buffer)?;
+        self.hasher.update(buffer);
+        Ok(buffer)
+    }
+
+    /// Returns the file header.
+    pub fn header(&self) -> &ExportHeader {
+        &self.header
+    }
+
+    /// Reads the next entry, or returns `None` if all entries were read.
+    ///
+    /// # Errors
+    ///
+    /// Proxies I/O errors (e.g., if the file is truncated).
+    pub fn read_entry(&mut self) -> anyhow::Result<Option<ExportEntry>> {
+        if self.read_entry_count == self.header.entry_count {
+            return Ok(None);
+        }
+        let buffer: [u8; ExportEntry::BYTE_LEN] = self.read().with_context(|| {
+            format!(
+                "failed reading entry #{} / {}",
+                self.read_entry_count, self.header.entry_count
+            )
+        })?;
+        self.read_entry_count += 1;
+        Ok(Some(ExportEntry {
+            key: H256::from_slice(&buffer[..32]),
+            value: H256::from_slice(&buffer[32..64]),
+            leaf_index: u64::from_le_bytes(buffer[64..].try_into().unwrap()),
+        }))
+    }
+
+    /// Reads and verifies the file checksum.
+    ///
+    /// # Errors
+    ///
+    /// Errors if not all entries were read, if the checksum doesn't match, or if there is data after the checksum.
+    pub fn finish(mut self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.read_entry_count == self.header.entry_count,
+            "read {} entries, while the header declares {}",
+            self.read_entry_count,
+            self.header.entry_count
+        );
+        let expected_checksum = self.hasher.finalize();
+        let mut checksum = [0_u8; 32];
+        self.inner
+            .read_exact(&mut checksum)
+            .context("failed reading checksum")?;
+        anyhow::ensure!(
+            checksum == expected_checksum.as_slice(),
+            "checksum mismatch: the file is corrupted"
+        );
+        let trailing_len = self.inner.read(&mut [0_u8; 1])?;
+        anyhow::ensure!(trailing_len == 0, "unexpected data after the checksum");
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+//! Portable export / import of ZKsync Merkle trees.
+//!
+//! A specific version of either the Era tree (`zksync_merkle_tree`) or the ZK OS tree (`zk_os_merkle_tree`)
+//! can be streamed into a versioned, checksummed file, and a tree can be rebuilt from such a file using
+//! the tree recovery logic. The file format is independent of the RocksDB layout; see the crate README for its spec.
+
+pub use self::{
+    era::{export_era_tree, import_era_tree},
+    format::{ExportEntry, ExportHeader, ExportReader, ExportWriter, TreeKind},
+    zk_os::{export_zk_os_tree, import_zk_os_tree},
+};
+
+mod era;
+mod format;
+#[cfg(test)]
+mod tests;
+mod zk_os;
+
+/// Number of entries fed to tree recovery at once during import.
+const IMPORT_CHUNK_SIZE: usize = if cfg!(test) { 16 } else { 100_000 };
@@ -0,0 +1,259 @@
+//! Tests for Merkle tree export / import.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use zksync_basic_types::{H256, U256};
+
+use super::*;
+
+const RNG_SEED: u64 = 123;
+const VERSION_COUNT: u64 = 3;
+const HEADER_LEN: usize = 61;
+
+fn create_era_tree() -> zksync_merkle_tree::PatchSet {
+    use zksync_merkle_tree::{MerkleTree, PatchSet, TreeEntry};
+
+    let mut rng = StdRng::seed_from_u64(RNG_SEED);
+    let mut db = PatchSet::default();
+    let mut tree = MerkleTree::new(&mut db).unwrap();
+    let mut all_entries: Vec<TreeEntry> = vec![];
+    for _ in 0..VERSION_COUNT {
+        let mut entries: Vec<_> = all_entries
+            .iter()
+            .take(5)
+            .map(|entry| TreeEntry::new(entry.key, entry.leaf_index, H256(rng.gen())))
+            .collect();
+        let new_entries = (0..50).map(|i| {
+            let leaf_index = all_entries.len() as u64 + i + 1;
+            TreeEntry::new(U256(rng.gen()), leaf_index, H256(rng.gen()))
+        });
+        let new_entries: Vec<_> = new_entries.collect();
+        all_entries.extend_from_slice(&new_entries);
+        entries.extend(new_entries);
+        tree.extend(entries).unwrap();
+    }
+    db
+}
+
+fn create_zk_os_tree() -> zk_os_merkle_tree::PatchSet {
+    use zk_os_merkle_tree::{MerkleTree, PatchSet, TreeEntry};
+
+    let mut rng = StdRng::seed_from_u64(RNG_SEED);
+    let mut db = PatchSet::default();
+    let mut tree = MerkleTree::new(&mut db).unwrap();
+    let mut all_keys = vec![];
+    for _ in 0..VERSION_COUNT {
+        let updates = all_keys.iter().take(5).map(|&key| TreeEntry {
+            key,
+            value: H256(rng.gen()),
+        });
+        let mut entries: Vec<_> = updates.collect();
+        let new_keys: Vec<_> = (0..50).map(|_| H256(rng.gen())).collect();
+        all_keys.extend_from_slice(&new_keys);
+        entries.extend(new_keys.into_iter().map(|key| TreeEntry {
+            key,
+            value: H256(rng.gen()),
+        }));
+        tree.extend(&entries).unwrap();
+    }
+    db
+}
+
+fn collect_era_entries(
+    tree: &zksync_merkle_tree::MerkleTree<impl zksync_merkle_tree::Database>,
+    version: u64,
+) -> Vec<zksync_merkle_tree::TreeEntry> {
+    let mut entries = vec![];
+    tree.for_each_entry(version, |entry| {
+        entries.push(entry);
+        Ok(())
+    })
+    .unwrap();
+    entries
+}
+
+fn collect_zk_os_entries(
+    tree: &zk_os_merkle_tree::MerkleTree<impl zk_os_merkle_tree::Database>,
+    version: u64,
+) -> Vec<(u64, zk_os_merkle_tree::TreeEntry)> {
+    let mut entries = vec![];
+    tree.for_each_entry(version, |index, entry| {
+        entries.push((index, entry));
+        Ok(())
+    })
+    .unwrap();
+    entries
+}
+
+#[test]
+fn era_tree_roundtrip() {
+    use zksync_merkle_tree::{MerkleTree, PatchSet};
+
+    let mut db = create_era_tree();
+    for version in 0..VERSION_COUNT {
+        println!("Testing version {version}");
+        let mut buffer = vec![];
+        let header = export_era_tree(&mut db, version, &mut buffer).unwrap();
+        assert_eq!(header.tree_kind, TreeKind::Era);
+        assert_eq!(header.tree_version, version);
+        assert_eq!(header.entry_count, (version + 1) * 50);
+        assert_eq!(
+            buffer.len(),
+            HEADER_LEN + 72 * header.entry_count as usize + 32
+        );
+
+        let reader = ExportReader::new(buffer.as_slice()).unwrap();
+        assert_eq!(*reader.header(), header);
+        let imported_db = import_era_tree(reader, PatchSet::default()).unwrap();
+        let imported_tree = MerkleTree::new(imported_db).unwrap();
+        assert_eq!(imported_tree.latest_version(), Some(version));
+        assert_eq!(imported_tree.latest_root_hash(), header.root_hash);
+
+        let tree = MerkleTree::new(&mut db).unwrap();
+        assert_eq!(tree.root_hash(version), Some(header.root_hash));
+        assert_eq!(
+            collect_era_entries(&imported_tree, version),
+            collect_era_entries(&tree, version)
+        );
+    }
+}
+
+#[test]
+fn zk_os_tree_roundtrip() {
+    use zk_os_merkle_tree::{MerkleTree, PatchSet, TreeEntry};
+
+    let mut db = create_zk_os_tree();
+    for version in 0..VERSION_COUNT {
+        println!("Testing version {version}");
+        let mut buffer = vec![];
+        let header = export_zk_os_tree(&mut db, version, &mut buffer).unwrap();
+        assert_eq!(header.tree_kind, TreeKind::ZkOs);
+        assert_eq!(header.tree_version, version);
+        assert_eq!(header.entry_count, (version + 1) * 50);
+
+        let reader = ExportReader::new(buffer.as_slice()).unwrap();
+        let imported_db = import_zk_os_tree(reader, PatchSet::default()).unwrap();
+        let mut imported_tree = MerkleTree::new(imported_db).unwrap();
+        assert_eq!(imported_tree.latest_version().unwrap(), Some(version));
+        assert_eq!(
+            imported_tree.latest_root_hash().unwrap(),
+            Some(header.root_hash)
+        );
+        imported_tree.verify_consistency(version).unwrap();
+
+        let tree = MerkleTree::new(&mut db).unwrap();
+        assert_eq!(
+            collect_zk_os_entries(&imported_tree, version),
+            collect_zk_os_entries(&tree, version)
+        );
+
+        // Check that the imported tree can be extended.
+        let new_entry = TreeEntry {
+            key: H256::repeat_byte(0x42),
+            value: H256::repeat_byte(1),
+        };
+        let output = imported_tree.extend(&[new_entry]).unwrap();
+        assert_eq!(output.leaf_count, header.entry_count + 3);
+    }
+}
+
+fn export_zk_os_file() -> Vec<u8> {
+    let mut buffer = vec![];
+    export_zk_os_tree(create_zk_os_tree(), VERSION_COUNT - 1, &mut buffer).unwrap();
+    buffer
+}
+
+fn import_zk_os_file(buffer: &[u8]) -> anyhow::Error {
+    let reader = match ExportReader::new(buffer) {
+        Ok(reader) => reader,
+        Err(err) => return err,
+    };
+    import_zk_os_tree(reader, zk_os_merkle_tree::PatchSet::default()).unwrap_err()
+}
+
+#[test]
+fn import_errors_on_corrupted_file() {
+    let buffer = export_zk_os_file();
+
+    let mut corrupted = buffer.clone();
+    // Corrupt an entry value, which doesn't influence entry ordering.
+    corrupted[HEADER_LEN + 72 * 10 + 40] ^= 1;
+    let err = format!("{:#}", import_zk_os_file(&corrupted));
+    assert!(err.contains("checksum mismatch"), "{err}");
+
+    let mut corrupted = buffer.clone();
+    *corrupted.last_mut().unwrap() ^= 1;
+    let err = format!("{:#}", import_zk_os_file(&corrupted));
+    assert!(err.contains("checksum mismatch"), "{err}");
+
+    let truncated = &buffer[..buffer.len() - 100];
+    let err = format!("{:#}", import_zk_os_file(truncated));
+    assert!(err.contains("failed reading entry"), "{err}");
+
+    let truncated = &buffer[..buffer.len() - 10];
+    let err = format!("{:#}", import_zk_os_file(truncated));
+    assert!(err.contains("failed reading checksum"), "{err}");
+
+    let mut extended = buffer.clone();
+    extended.push(0);
+    let err = format!("{:#}", import_zk_os_file(&extended));
+    assert!(err.contains("unexpected data"), "{err}");
+}
+
+#[test]
+fn import_errors_on_invalid_header() {
+    let buffer = export_zk_os_file();
+
+    let mut corrupted = buffer.clone();
+    corrupted[0] = b'Z';
+    let err = format!("{:#}", import_zk_os_file(&corrupted));
+    assert!(err.contains("not a Merkle tree export file"), "{err}");
+
+    let mut corrupted = buffer.clone();
+    corrupted[8] = 2;
+    let err = format!("{:#}", import_zk_os_file(&corrupted));
+    assert!(err.contains("unsupported export format version"), "{err}");
+
+    let mut corrupted = buffer.clone();
+    corrupted[12] = 5;
+    let err = format!("{:#}", import_zk_os_file(&corrupted));
+    assert!(err.contains("unknown tree kind"), "{err}");
+
+    let reader = ExportReader::new(buffer.as_slice()).unwrap();
+    let err = import_era_tree(reader, zksync_merkle_tree::PatchSet::default()).unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("while the Era tree was expected"), "{err}");
+}
+
+#[test]
+fn import_errors_on_root_hash_mismatch() {
+    let buffer = export_zk_os_file();
+    let mut reader = ExportReader::new(buffer.as_slice()).unwrap();
+    let header = ExportHeader {
+        root_hash: H256::repeat_byte(1),
+        ..*reader.header()
+    };
+
+    // Re-export entries with a valid checksum, but an incorrect root hash.
+    let mut writer = ExportWriter::new(vec![], header).unwrap();
+    while let Some(entry) = reader.read_entry().unwrap() {
+        writer.write_entry(&entry).unwrap();
+    }
+    reader.finish().unwrap();
+    let buffer = writer.finish().unwrap();
+
+    let err = format!("{:#}", import_zk_os_file(&buffer));
+    assert!(err.contains("differs from the expected"), "{err}");
+}
+
+#[test]
+fn writer_checks_entry_count() {
+    let header = ExportHeader {
+        tree_kind: TreeKind::Era,
+        tree_version: 0,
+        root_hash: H256::zero(),
+        entry_count: 1,
+    };
+    let writer = ExportWriter::new(vec![], header).unwrap();
+    let err = writer.finish().unwrap_err().to_string();
+    assert!(err.contains("header declares 1"), "{err}");
+}
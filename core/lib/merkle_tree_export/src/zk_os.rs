@@ -0,0 +1,109 @@
+//! Export / import for the ZK OS Merkle tree.
+
+use std::{
+    io::{Read, Write},
+    mem,
+};
+
+use anyhow::Context as _;
+use zk_os_merkle_tree::{Database, MerkleTree, MerkleTreeRecovery, RecoveryDatabase, TreeEntry};
+
+use crate::{
+    format::{ExportEntry, ExportHeader, ExportReader, ExportWriter, TreeKind},
+    IMPORT_CHUNK_SIZE,
+};
+
+/// Exports the specified `version` of the ZK OS tree stored in `db`. Entries are written in the increasing
+/// leaf index order.
+///
+/// # Errors
+///
+/// Errors if the version is missing from the tree, or on I/O errors.
+pub fn export_zk_os_tree<DB: Database>(
+    db: DB,
+    version: u64,
+    writer: impl Write,
+) -> anyhow::Result<ExportHeader> {
+    let tree = MerkleTree::new(db).context("failed initializing ZK OS tree")?;
+    let (root_hash, leaf_count) = tree
+        .root_info(version)?
+        .with_context(|| format!("ZK OS tree version {version} does not exist"))?;
+    // Min / max guards are not exported.
+    let entry_count = leaf_count
+        .checked_sub(2)
+        .context("ZK OS tree has no guards")?;
+
+    let header = ExportHeader {
+        tree_kind: TreeKind::ZkOs,
+        tree_version: version,
+        root_hash,
+        entry_count,
+    };
+    tracing::info!(?header, "Exporting ZK OS Merkle tree");
+    let mut writer = ExportWriter::new(writer, header)?;
+    tree.for_each_entry(version, |leaf_index, entry| {
+        let entry = ExportEntry {
+            key: entry.key,
+            value: entry.value,
+            leaf_index,
+        };
+        writer.write_entry(&entry).map_err(Into::into)
+    })?;
+    writer.finish()?;
+    Ok(header)
+}
+
+/// Imports the ZK OS tree from an export file into an empty `db`. The tree version and the root hash are taken
+/// from the file header; the root hash is checked after all entries are imported.
+///
+/// # Errors
+///
+/// Errors if the file is malformed or corrupted, or if the root hash of the imported tree doesn't match.
+/// In this case, the tree in `db` remains in the recovery state.
+pub fn import_zk_os_tree<DB: RecoveryDatabase>(
+    mut reader: ExportReader<impl Read>,
+    db: DB,
+) -> anyhow::Result<DB> {
+    let header = *reader.header();
+    anyhow::ensure!(
+        header.tree_kind == TreeKind::ZkOs,
+        "export file contains {:?} tree, while the ZK OS tree was expected",
+        header.tree_kind
+    );
+    tracing::info!(?header, "Importing ZK OS Merkle tree");
+
+    let mut recovery = MerkleTreeRecovery::new(db, header.tree_version)?;
+    let mut chunk = Vec::with_capacity(IMPORT_CHUNK_SIZE);
+    while let Some(entry) = reader.read_entry()? {
+        let tree_entry = TreeEntry {
+            key: entry.key,
+            value: entry.value,
+        };
+        chunk.push((entry.leaf_index, tree_entry));
+        if chunk.len() == IMPORT_CHUNK_SIZE {
+            extend_recovery(&mut recovery, mem::take(&mut chunk))?;
+        }
+    }
+    extend_recovery(&mut recovery, chunk)?;
+    reader.finish()?;
+
+    let root_hash = recovery.root_hash()?;
+    anyhow::ensure!(
+        root_hash == header.root_hash,
+        "root hash of the imported tree {root_hash:?} differs from the expected {:?}",
+        header.root_hash
+    );
+    recovery.finalize()
+}
+
+fn extend_recovery<DB: RecoveryDatabase>(
+    recovery: &mut MerkleTreeRecovery<DB>,
+    mut chunk: Vec<(u64, TreeEntry)>,
+) -> anyhow::Result<()> {
+    if chunk.is_empty() {
+        return Ok(());
+    }
+    // Entries are exported in the leaf index order, but recovery requires them to be sorted by key.
+    chunk.sort_unstable_by_key(|(_, entry)| entry.key);
+    recovery.extend(&chunk)
+}
@@ -8,7 +8,8 @@ use prost::Message;
 use zksync_protobuf::{decode, ProtoFmt};
 use zksync_types::{
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotFactoryDependencies, SnapshotHistoryStorageKey, SnapshotL1BatchHistory,
+        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
     },
     L1BatchNumber,
 };
@@ -120,6 +121,33 @@ where
     }
 }
 
+// Unlike other snapshot objects, history is serialized as JSON since it contains transactions,
+// which don't have a protobuf representation.
+impl StoredObject for SnapshotL1BatchHistory {
+    const BUCKET: Bucket = Bucket::StorageSnapshot;
+    type Key<'a> = SnapshotHistoryStorageKey;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!(
+            "snapshot_l1_batch_{}_history_l1_batch_{}.json.gzip",
+            key.snapshot_l1_batch_number, key.l1_batch_number
+        )
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        serde_json::to_writer(&mut encoder, self)?;
+        encoder.finish().map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        let decoder = GzDecoder::new(&bytes[..]);
+        serde_json::from_reader(decoder)
+            .context("deserialization of JSON to SnapshotL1BatchHistory")
+            .map_err(From::from)
+    }
+}
+
 impl dyn ObjectStore + '_ {
     /// Fetches the value for the given key if it exists.
     ///
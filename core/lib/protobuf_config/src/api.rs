@@ -152,6 +152,17 @@ impl ProtoRepr for proto::Web3JsonRpc {
             api_namespaces,
             deployment_allowlist: read_optional_repr(&self.deployment_allowlist)?
                 .unwrap_or_default(),
+            admin_port: self
+                .admin_port
+                .map(|p| p.try_into())
+                .transpose()
+                .context("admin_port")?,
+            admin_host: self
+                .admin_host
+                .as_ref()
+                .map(|host| host.parse())
+                .transpose()
+                .context("admin_host")?,
         })
     }
 
@@ -219,6 +230,8 @@ impl ProtoRepr for proto::Web3JsonRpc {
             deployment_allowlist: Some(proto::DeploymentAllowlist::build(
                 &this.deployment_allowlist,
             )),
+            admin_port: this.admin_port.map(Into::into),
+            admin_host: this.admin_host.map(|host| host.to_string()),
         }
     }
 }
@@ -251,6 +264,12 @@ impl ProtoRepr for proto::HealthCheck {
                 .context("port")?,
             slow_time_limit_ms: self.slow_time_limit_ms,
             hard_time_limit_ms: self.hard_time_limit_ms,
+            history_capacity: self
+                .history_capacity
+                .map(|capacity| capacity.try_into())
+                .transpose()
+                .context("history_capacity")?,
+            webhook_url: self.webhook_url.clone(),
         })
     }
 
@@ -259,6 +278,8 @@ impl ProtoRepr for proto::HealthCheck {
             port: Some(this.port.into()),
             slow_time_limit_ms: this.slow_time_limit_ms,
             hard_time_limit_ms: this.hard_time_limit_ms,
+            history_capacity: this.history_capacity.map(|capacity| capacity as u64),
+            webhook_url: this.webhook_url.clone(),
         }
     }
 }
@@ -1,5 +1,5 @@
 use anyhow::Context as _;
-use zksync_config::configs;
+use zksync_config::configs::{self, chain::CircuitBreakerAction};
 use zksync_protobuf::{required, ProtoRepr};
 
 use crate::proto::circuit_breaker as proto;
@@ -7,6 +7,13 @@ use crate::proto::circuit_breaker as proto;
 impl ProtoRepr for proto::CircuitBreaker {
     type Type = configs::chain::CircuitBreakerConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
+        let actions = self
+            .actions
+            .iter()
+            .enumerate()
+            .map(|(i, action)| action.read().with_context(|| format!("[{i}]")))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .context("actions")?;
         Ok(Self::Type {
             sync_interval_ms: *required(&self.sync_interval_ms).context("sync_interval_ms")?,
             http_req_max_retry_number: required(&self.http_req_max_retry_number)
@@ -16,6 +23,11 @@ impl ProtoRepr for proto::CircuitBreaker {
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("http_req_retry_interval_sec")?,
             replication_lag_limit_sec: self.replication_lag_limit_sec,
+            min_operator_l1_balance_gwei: self.min_operator_l1_balance_gwei,
+            da_dispatch_backlog_limit: self.da_dispatch_backlog_limit,
+            proof_generation_lag_limit: self.proof_generation_lag_limit,
+            base_token_ratio_staleness_limit_sec: self.base_token_ratio_staleness_limit_sec,
+            actions: actions.into_iter().collect(),
         })
     }
 
@@ -25,6 +37,51 @@ impl ProtoRepr for proto::CircuitBreaker {
             http_req_max_retry_number: Some(this.http_req_max_retry_number.try_into().unwrap()),
             http_req_retry_interval_sec: Some(this.http_req_retry_interval_sec.into()),
             replication_lag_limit_sec: this.replication_lag_limit_sec,
+            min_operator_l1_balance_gwei: this.min_operator_l1_balance_gwei,
+            da_dispatch_backlog_limit: this.da_dispatch_backlog_limit,
+            proof_generation_lag_limit: this.proof_generation_lag_limit,
+            base_token_ratio_staleness_limit_sec: this.base_token_ratio_staleness_limit_sec,
+            actions: this
+                .actions
+                .iter()
+                .map(|(breaker, action)| {
+                    proto::Action::build(&(breaker.to_owned(), action.clone()))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ProtoRepr for proto::Action {
+    type Type = (String, CircuitBreakerAction);
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        let breaker = required(&self.breaker).context("breaker")?.clone();
+        let action = match required(&self.action).context("action")?.as_str() {
+            "stop" => CircuitBreakerAction::Stop,
+            "alert" => CircuitBreakerAction::Alert,
+            "pause" => {
+                anyhow::ensure!(
+                    !self.paused_components.is_empty(),
+                    "paused_components must be specified for the `pause` action"
+                );
+                CircuitBreakerAction::Pause(self.paused_components.clone())
+            }
+            other => anyhow::bail!("unknown action: `{other}`"),
+        };
+        Ok((breaker, action))
+    }
+
+    fn build((breaker, action): &Self::Type) -> Self {
+        let (action, paused_components) = match action {
+            CircuitBreakerAction::Stop => ("stop", vec![]),
+            CircuitBreakerAction::Pause(components) => ("pause", components.clone()),
+            CircuitBreakerAction::Alert => ("alert", vec![]),
+        };
+        Self {
+            breaker: Some(breaker.clone()),
+            action: Some(action.to_owned()),
+            paused_components,
         }
     }
 }
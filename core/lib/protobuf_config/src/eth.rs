@@ -138,6 +138,7 @@ impl ProtoRepr for proto::Sender {
                 .context("gas_limit_mode")?
                 .map(|a| a.parse())
                 .unwrap_or(Self::Type::default_gas_limit_mode()),
+            tee_quorum_required_from_batch: self.tee_quorum_required_from_batch,
         })
     }
 
@@ -168,6 +169,7 @@ impl ProtoRepr for proto::Sender {
             time_in_mempool_in_l1_blocks_cap: Some(this.time_in_mempool_in_l1_blocks_cap),
             is_verifier_pre_fflonk: Some(this.is_verifier_pre_fflonk),
             gas_limit_mode: Some(proto::GasLimitMode::new(&this.gas_limit_mode).into()),
+            tee_quorum_required_from_batch: this.tee_quorum_required_from_batch,
         }
     }
 }
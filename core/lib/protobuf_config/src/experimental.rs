@@ -93,6 +93,7 @@ impl ProtoRepr for proto::VmPlayground {
             window_size: NonZeroU32::new(self.window_size.unwrap_or(1))
                 .context("window_size cannot be 0")?,
             reset: self.reset.unwrap_or(false),
+            record_divergences: self.record_divergences.unwrap_or(false),
         })
     }
 
@@ -103,6 +104,7 @@ impl ProtoRepr for proto::VmPlayground {
             first_processed_batch: Some(this.first_processed_batch.0),
             window_size: Some(this.window_size.get()),
             reset: Some(this.reset),
+            record_divergences: Some(this.record_divergences),
         }
     }
 }
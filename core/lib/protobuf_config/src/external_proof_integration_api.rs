@@ -1,9 +1,29 @@
 use anyhow::Context;
-use zksync_config::ExternalProofIntegrationApiConfig;
+use zksync_config::{
+    configs::external_proof_integration_api::ExternalProverApiKey,
+    ExternalProofIntegrationApiConfig,
+};
 use zksync_protobuf::{required, ProtoRepr};
 
 use crate::proto::external_proof_integration_api as proto;
 
+impl ProtoRepr for proto::ProverApiKey {
+    type Type = ExternalProverApiKey;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            prover_id: required(&self.prover_id).context("prover_id")?.clone(),
+            api_key: required(&self.api_key).context("api_key")?.clone(),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            prover_id: Some(this.prover_id.clone()),
+            api_key: Some(this.api_key.clone()),
+        }
+    }
+}
+
 impl ProtoRepr for proto::ExternalProofIntegrationApi {
     type Type = ExternalProofIntegrationApiConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -11,12 +31,33 @@ impl ProtoRepr for proto::ExternalProofIntegrationApi {
             http_port: required(&self.http_port)
                 .and_then(|p| Ok((*p).try_into()?))
                 .context("http_port")?,
+            lease_timeout_in_secs: self
+                .lease_timeout_in_secs
+                .unwrap_or(Self::Type::default_lease_timeout_in_secs()),
+            api_keys: self
+                .api_keys
+                .iter()
+                .enumerate()
+                .map(|(i, x)| x.read().context(i))
+                .collect::<Result<_, _>>()
+                .context("api_keys")?,
+            max_leases_per_prover: self
+                .max_leases_per_prover
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_leases_per_prover")?
+                .unwrap_or(Self::Type::default_max_leases_per_prover()),
+            verification_keys_path: self.verification_keys_path.clone(),
         })
     }
 
     fn build(this: &Self::Type) -> Self {
         Self {
             http_port: Some(this.http_port.into()),
+            lease_timeout_in_secs: Some(this.lease_timeout_in_secs),
+            api_keys: this.api_keys.iter().map(ProtoRepr::build).collect(),
+            max_leases_per_prover: Some(this.max_leases_per_prover as u64),
+            verification_keys_path: this.verification_keys_path.clone(),
         }
     }
 }
@@ -32,6 +32,7 @@ impl ProtoRepr for proto::Observability {
             log_format: required(&self.log_format).context("log_format")?.clone(),
             opentelemetry: self.opentelemetry.as_ref().and_then(|cfg| cfg.read().ok()),
             log_directives: self.log_directives.clone(),
+            log_sampling: self.log_sampling.clone(),
         })
     }
 
@@ -51,6 +52,7 @@ impl ProtoRepr for proto::Observability {
             log_format: Some(this.log_format.clone()),
             opentelemetry: this.opentelemetry.as_ref().map(ProtoRepr::build),
             log_directives: this.log_directives.clone(),
+            log_sampling: this.log_sampling.clone(),
         }
     }
 }
@@ -35,6 +35,23 @@ impl ProtoRepr for proto::ProofDataHandler {
                     .unwrap_or_else(
                         configs::TeeConfig::default_tee_batch_permanently_ignored_timeout_in_hours,
                     ),
+                verify_attestations: self
+                    .verify_attestations
+                    .unwrap_or_else(configs::TeeConfig::default_verify_attestations),
+                allowed_mrenclaves: self.allowed_mrenclaves.clone(),
+                allowed_mrtds: self.allowed_mrtds.clone(),
+                allowed_tcb_statuses: if self.allowed_tcb_statuses.is_empty() {
+                    configs::TeeConfig::default_allowed_tcb_statuses()
+                } else {
+                    self.allowed_tcb_statuses.clone()
+                },
+                attestation_collateral_path: self.attestation_collateral_path.clone(),
+                quorum_tee_types: self
+                    .quorum_tee_types
+                    .iter()
+                    .map(|tee_type| tee_type.parse().map_err(anyhow::Error::msg))
+                    .collect::<anyhow::Result<_>>()
+                    .context("quorum_tee_types")?,
             },
         })
     }
@@ -53,6 +70,17 @@ impl ProtoRepr for proto::ProofDataHandler {
                     .tee_batch_permanently_ignored_timeout_in_hours
                     .into(),
             ),
+            verify_attestations: Some(this.tee_config.verify_attestations),
+            allowed_mrenclaves: this.tee_config.allowed_mrenclaves.clone(),
+            allowed_mrtds: this.tee_config.allowed_mrtds.clone(),
+            allowed_tcb_statuses: this.tee_config.allowed_tcb_statuses.clone(),
+            attestation_collateral_path: this.tee_config.attestation_collateral_path.clone(),
+            quorum_tee_types: this
+                .tee_config
+                .quorum_tee_types
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
         }
     }
 }
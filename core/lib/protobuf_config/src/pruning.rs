@@ -14,6 +14,7 @@ impl ProtoRepr for proto::Pruning {
             chunk_size: self.chunk_size,
             removal_delay_sec: self.removal_delay_sec.and_then(NonZeroU64::new),
             data_retention_sec: self.data_retention_sec,
+            tree_retained_l1_batches: self.tree_retained_l1_batches,
         })
     }
 
@@ -23,6 +24,7 @@ impl ProtoRepr for proto::Pruning {
             chunk_size: this.chunk_size,
             removal_delay_sec: this.removal_delay_sec.map(|a| a.get()),
             data_retention_sec: this.data_retention_sec,
+            tree_retained_l1_batches: this.tree_retained_l1_batches,
         }
     }
 }
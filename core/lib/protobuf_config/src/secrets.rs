@@ -12,7 +12,7 @@ use zksync_config::configs::{
         avail::AvailSecrets, celestia::CelestiaSecrets, eigen::EigenSecrets, nomos::NomosSecrets,
     },
     secrets::{DataAvailabilitySecrets, Secrets},
-    ContractVerifierSecrets, DatabaseSecrets, L1Secrets,
+    ApiSecrets, ContractVerifierSecrets, DatabaseSecrets, L1Secrets,
 };
 use zksync_protobuf::{required, ProtoRepr};
 
@@ -31,6 +31,7 @@ impl ProtoRepr for proto::Secrets {
             l1: read_optional_repr(&self.l1),
             data_availability: read_optional_repr(&self.da),
             contract_verifier: read_optional_repr(&self.contract_verifier),
+            api: read_optional_repr(&self.api),
         })
     }
 
@@ -41,6 +42,7 @@ impl ProtoRepr for proto::Secrets {
             consensus: this.consensus.as_ref().map(ProtoRepr::build),
             da: this.data_availability.as_ref().map(ProtoRepr::build),
             contract_verifier: this.contract_verifier.as_ref().map(ProtoRepr::build),
+            api: this.api.as_ref().map(ProtoRepr::build),
         }
     }
 }
@@ -281,3 +283,22 @@ impl ProtoRepr for proto::ContractVerifierSecrets {
         Self { etherscan_api_key }
     }
 }
+
+impl ProtoRepr for proto::ApiSecrets {
+    type Type = ApiSecrets;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(ApiSecrets {
+            admin_token: self.admin_token.as_deref().map(APIKey::from),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            admin_token: this
+                .admin_token
+                .as_ref()
+                .map(|token| token.0.expose_secret().to_string()),
+        }
+    }
+}
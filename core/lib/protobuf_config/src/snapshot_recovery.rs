@@ -17,12 +17,14 @@ impl ProtoRepr for proto::Postgres {
             max_concurrency: self
                 .max_concurrency
                 .and_then(|a| NonZeroUsize::new(a as usize)),
+            import_history: self.import_history.unwrap_or_default(),
         })
     }
 
     fn build(this: &Self::Type) -> Self {
         Self {
             max_concurrency: this.max_concurrency.map(|a| a.get() as u64),
+            import_history: Some(this.import_history),
         }
     }
 }
@@ -24,6 +24,8 @@ impl ProtoRepr for proto::SnapshotsCreator {
                 .context("storage_logs_chunk_size")?,
             concurrent_queries_count: *required(&self.concurrent_queries_count)
                 .context("concurrent_queries_count")?,
+            history_l1_batches: self.history_l1_batches.unwrap_or_default(),
+            max_incremental_snapshots: self.max_incremental_snapshots.unwrap_or_default(),
             object_store,
         })
     }
@@ -34,6 +36,8 @@ impl ProtoRepr for proto::SnapshotsCreator {
             l1_batch_number: this.l1_batch_number.map(|num| num.0),
             storage_logs_chunk_size: Some(this.storage_logs_chunk_size),
             concurrent_queries_count: Some(this.concurrent_queries_count),
+            history_l1_batches: Some(this.history_l1_batches),
+            max_incremental_snapshots: Some(this.max_incremental_snapshots),
             object_store: this.object_store.as_ref().map(ProtoRepr::build),
         }
     }
@@ -51,6 +51,45 @@ pub enum RegisterTeeAttestationResponse {
     Success,
 }
 
+/// Batch leased to an external prover.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalProofLease {
+    pub l1_batch_number: L1BatchNumber,
+    /// Once the lease expires, the batch may be leased to another prover.
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalProofGenerationStatus {
+    Unpicked,
+    PickedByProver,
+    Generated,
+    Skipped,
+}
+
+impl ExternalProofGenerationStatus {
+    /// Returns `true` if the status won't change anymore.
+    pub fn is_final(self) -> bool {
+        matches!(self, Self::Generated | Self::Skipped)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalProofStatusResponse {
+    pub l1_batch_number: L1BatchNumber,
+    pub status: ExternalProofGenerationStatus,
+    /// Last lease of the batch, if any. The lease may be expired.
+    pub lease: Option<ExternalProofLeaseInfo>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalProofLeaseInfo {
+    pub owner: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
 // Structs to hold data necessary for making HTTP requests
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1,25 +1,38 @@
 //! Logic for applying application-level snapshots to Postgres storage.
 
 use std::{
-    cmp::Ordering, collections::HashMap, fmt, mem, num::NonZeroUsize, sync::Arc, time::Duration,
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+    fmt, mem,
+    num::NonZeroUsize,
+    sync::Arc,
+    time::Duration,
 };
 
 use anyhow::Context as _;
 use async_trait::async_trait;
 use serde::Serialize;
 use tokio::sync::{watch, Semaphore};
+use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalError, SqlxError};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_types::{
     api,
     bytecode::{BytecodeHash, BytecodeMarker},
+    protocol_version::{ProtocolSemanticVersion, VersionPatch},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotHeader, SnapshotRecoveryStatus, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey, SnapshotVersion,
+        SnapshotFactoryDependencies, SnapshotHeader, SnapshotHistoryStorageKey,
+        SnapshotL1BatchHistory, SnapshotRecoveryStatus, SnapshotStorageLog,
+        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey, SnapshotTransaction,
+        SnapshotVersion,
     },
     tokens::TokenInfo,
-    L1BatchNumber, L2BlockNumber, StorageKey, H256,
+    tx::IncludedTxLocation,
+    L1BatchNumber, L2BlockNumber, ProtocolVersionId, StorageKey, H256,
+};
+use zksync_vm_interface::{
+    Call, TransactionExecutionResult, TxExecutionStatus, VmEvent, VmExecutionMetrics,
 };
 use zksync_web3_decl::{
     client::{DynClient, L2},
@@ -28,7 +41,7 @@ use zksync_web3_decl::{
     namespaces::{EnNamespaceClient, SnapshotsNamespaceClient, ZksNamespaceClient},
 };
 
-use self::metrics::{InitialStage, StorageLogsChunksStage, METRICS};
+use self::metrics::{HistoryStage, InitialStage, StorageLogsChunksStage, METRICS};
 
 mod metrics;
 #[cfg(test)]
@@ -140,6 +153,11 @@ pub trait SnapshotsApplierMainNodeClient: fmt::Debug + Send + Sync {
         &self,
         at_l2_block: L2BlockNumber,
     ) -> EnrichedClientResult<Vec<TokenInfo>>;
+
+    async fn fetch_protocol_version(
+        &self,
+        protocol_version: ProtocolVersionId,
+    ) -> EnrichedClientResult<Option<api::ProtocolVersion>>;
 }
 
 #[async_trait]
@@ -193,6 +211,16 @@ impl SnapshotsApplierMainNodeClient for Box<DynClient<L2>> {
             .with_arg("l2_block_number", &at_l2_block)
             .await
     }
+
+    async fn fetch_protocol_version(
+        &self,
+        protocol_version: ProtocolVersionId,
+    ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+        self.get_protocol_version(Some(protocol_version as u16))
+            .rpc_context("get_protocol_version")
+            .with_arg("protocol_version", &protocol_version)
+            .await
+    }
 }
 
 /// Reported status of the snapshot recovery progress.
@@ -200,9 +228,10 @@ impl SnapshotsApplierMainNodeClient for Box<DynClient<L2>> {
 pub enum RecoveryCompletionStatus {
     /// There is no infomration about snapshot recovery in the database.
     NoRecoveryDetected,
-    /// Snapshot recovery is not finished yet.
+    /// Snapshot recovery is not finished yet. This includes the case when transaction history import
+    /// is interrupted midway, i.e., the last sealed L2 block precedes the snapshot L2 block.
     InProgress,
-    /// Snapshot recovery is completed.
+    /// Snapshot recovery is completed. If transaction history is imported, it's imported in full.
     Completed,
 }
 
@@ -219,6 +248,9 @@ pub struct SnapshotsApplierConfig {
     /// Maximum concurrency factor when performing concurrent operations (for now, the only such operation
     /// is recovering chunks of storage logs).
     pub max_concurrency: NonZeroUsize,
+    /// Whether to import transaction history (L2 block headers, transactions, receipts and events) if it's included
+    /// into the snapshot.
+    pub import_history: bool,
 }
 
 impl Default for SnapshotsApplierConfig {
@@ -228,6 +260,7 @@ impl Default for SnapshotsApplierConfig {
             initial_retry_backoff: Duration::from_secs(2),
             retry_backoff_multiplier: 2.0,
             max_concurrency: NonZeroUsize::new(10).unwrap(),
+            import_history: false,
         }
     }
 }
@@ -282,6 +315,10 @@ impl SnapshotsApplierTask {
 
     /// Checks whether the snapshot recovery is already completed.
     ///
+    /// Transaction history is imported last, after storage logs and tokens. Since imported L2 blocks are inserted
+    /// in the ascending order and the snapshot L2 block is the last one, recovery is considered completed only
+    /// if the last sealed L2 block is not older than the snapshot one.
+    ///
     /// Returns `None` if no snapshot recovery information is detected in the DB.
     /// Returns `Some(true)` if the recovery is completed.
     /// Returns `Some(false)` if the recovery is not completed.
@@ -300,6 +337,13 @@ impl SnapshotsApplierTask {
         if applied_snapshot_status.storage_logs_chunks_left_to_process() != 0 {
             return Ok(RecoveryCompletionStatus::InProgress);
         }
+        // If transaction history is partially imported, the recovery is not complete either.
+        let sealed_l2_block_number = conn.blocks_dal().get_sealed_l2_block_number().await?;
+        if sealed_l2_block_number
+            .is_some_and(|number| number < applied_snapshot_status.l2_block_number)
+        {
+            return Ok(RecoveryCompletionStatus::InProgress);
+        }
         // Currently, migrating tokens is the last step of the recovery.
         // The number of tokens is not a part of the snapshot header, so we have to re-query the main node.
         let added_tokens = conn
@@ -407,14 +451,94 @@ impl SnapshotsApplierTask {
     }
 }
 
-/// Strategy determining how snapshot recovery should proceed.
+/// Part of a [`SnapshotChain`]: either the full snapshot at the start of the chain, or an incremental snapshot.
 #[derive(Debug, Clone, Copy)]
+struct SnapshotPart {
+    l1_batch_number: L1BatchNumber,
+    /// L1 batch of the previous snapshot in the chain; `None` for the full snapshot.
+    base_l1_batch_number: Option<L1BatchNumber>,
+    version: SnapshotVersion,
+    chunk_count: usize,
+    /// Index of the first storage logs chunk of this part in [`SnapshotRecoveryStatus::storage_logs_chunks_processed`].
+    first_chunk_index: usize,
+}
+
+/// Snapshots to apply in order to recover from a (potentially incremental) snapshot: a full snapshot followed
+/// by zero or more incremental snapshots, each based on the previous one. Storage log chunks of all parts
+/// are tracked in a single recovery status, ordered by the part.
+#[derive(Debug, Clone)]
+struct SnapshotChain {
+    parts: Vec<SnapshotPart>,
+}
+
+impl SnapshotChain {
+    async fn fetch(
+        main_node_client: &dyn SnapshotsApplierMainNodeClient,
+        snapshot: &SnapshotHeader,
+    ) -> Result<Self, SnapshotsApplierError> {
+        let mut headers = vec![snapshot.clone()];
+        loop {
+            // `unwrap()` is safe: `headers` is never empty
+            let last_header = headers.last().unwrap();
+            let Some(base_l1_batch_number) = last_header.base_l1_batch_number else {
+                break;
+            };
+            if base_l1_batch_number >= last_header.l1_batch_number {
+                let err = anyhow::anyhow!(
+                    "snapshot for L1 batch #{} references base snapshot for L1 batch #{base_l1_batch_number} from the future",
+                    last_header.l1_batch_number
+                );
+                return Err(err.into());
+            }
+            let base_header = main_node_client
+                .fetch_snapshot(base_l1_batch_number)
+                .await?
+                .with_context(|| {
+                    format!("base snapshot for L1 batch #{base_l1_batch_number} is not present on main node")
+                })?;
+            headers.push(base_header);
+        }
+        headers.reverse();
+
+        let mut parts = Vec::with_capacity(headers.len());
+        let mut first_chunk_index = 0;
+        for header in headers {
+            let version = SnapshotRecoveryStrategy::check_snapshot_version(header.version)?;
+            if header.base_l1_batch_number.is_some() && version != SnapshotVersion::Version1 {
+                let err = anyhow::anyhow!(
+                    "incremental snapshot for L1 batch #{} has version {version:?}; only version 1 is supported \
+                     for incremental snapshots",
+                    header.l1_batch_number
+                );
+                return Err(err.into());
+            }
+            let chunk_count = header.storage_logs_chunks.len();
+            parts.push(SnapshotPart {
+                l1_batch_number: header.l1_batch_number,
+                base_l1_batch_number: header.base_l1_batch_number,
+                version,
+                chunk_count,
+                first_chunk_index,
+            });
+            first_chunk_index += chunk_count;
+        }
+        Ok(Self { parts })
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.parts.iter().map(|part| part.chunk_count).sum()
+    }
+}
+
+/// Strategy determining how snapshot recovery should proceed.
+#[derive(Debug)]
 enum SnapshotRecoveryStrategy {
     /// Snapshot recovery should proceed from scratch with the specified params.
-    New(SnapshotVersion),
+    New(SnapshotChain),
     /// Snapshot recovery should continue with the specified params.
-    Resumed(SnapshotVersion),
-    /// Snapshot recovery has already been completed.
+    Resumed(SnapshotChain),
+    /// Snapshot recovery has already been completed. This is determined by the presence of the snapshot L2 block
+    /// (or a newer one) in Postgres, which is inserted only after the transaction history import (if any).
     Completed,
 }
 
@@ -432,8 +556,9 @@ impl SnapshotRecoveryStrategy {
             .await?;
 
         if let Some(applied_snapshot_status) = applied_snapshot_status {
+            // L2 blocks before the snapshot one may be present if transaction history import was interrupted.
             let sealed_l2_block_number = storage.blocks_dal().get_sealed_l2_block_number().await?;
-            if sealed_l2_block_number.is_some() {
+            if sealed_l2_block_number >= Some(applied_snapshot_status.l2_block_number) {
                 return Ok((Self::Completed, applied_snapshot_status));
             }
 
@@ -446,11 +571,20 @@ impl SnapshotRecoveryStrategy {
                 })?;
             // Old snapshots can theoretically be removed by the node, but in this case the snapshot data may be removed as well,
             // so returning an error looks appropriate here.
-            let snapshot_version = Self::check_snapshot_version(snapshot_header.version)?;
+            let snapshot_chain = SnapshotChain::fetch(main_node_client, &snapshot_header).await?;
+            let expected_chunk_count = applied_snapshot_status.storage_logs_chunks_processed.len();
+            if snapshot_chain.chunk_count() != expected_chunk_count {
+                let err = anyhow::anyhow!(
+                    "snapshot chain for L1 batch #{l1_batch_number} has {} storage log chunks, while recovery status \
+                     expects {expected_chunk_count}",
+                    snapshot_chain.chunk_count()
+                );
+                return Err(SnapshotsApplierError::Fatal(err));
+            }
 
             let latency = latency.observe();
             tracing::info!("Re-initialized snapshots applier after reset/failure in {latency:?}");
-            Ok((Self::Resumed(snapshot_version), applied_snapshot_status))
+            Ok((Self::Resumed(snapshot_chain), applied_snapshot_status))
         } else {
             let is_genesis_needed = storage.blocks_dal().is_genesis_needed().await?;
             if !is_genesis_needed {
@@ -460,7 +594,7 @@ impl SnapshotRecoveryStrategy {
                 return Err(SnapshotsApplierError::Fatal(err));
             }
 
-            let (recovery_status, snapshot_version) =
+            let (recovery_status, snapshot_chain) =
                 Self::create_fresh_recovery_status(main_node_client, snapshot_l1_batch).await?;
 
             let storage_logs_count = storage
@@ -478,14 +612,14 @@ impl SnapshotRecoveryStrategy {
 
             let latency = latency.observe();
             tracing::info!("Initialized fresh snapshots applier in {latency:?}");
-            Ok((Self::New(snapshot_version), recovery_status))
+            Ok((Self::New(snapshot_chain), recovery_status))
         }
     }
 
     async fn create_fresh_recovery_status(
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
         snapshot_l1_batch: Option<L1BatchNumber>,
-    ) -> Result<(SnapshotRecoveryStatus, SnapshotVersion), SnapshotsApplierError> {
+    ) -> Result<(SnapshotRecoveryStatus, SnapshotChain), SnapshotsApplierError> {
         let l1_batch_number = match snapshot_l1_batch {
             Some(num) => num,
             None => main_node_client
@@ -505,7 +639,17 @@ impl SnapshotRecoveryStrategy {
             version = snapshot.version,
             chunk_count = snapshot.storage_logs_chunks.len()
         );
-        let snapshot_version = Self::check_snapshot_version(snapshot.version)?;
+        let snapshot_chain = SnapshotChain::fetch(main_node_client, &snapshot).await?;
+        if snapshot_chain.parts.len() > 1 {
+            let l1_batches: Vec<_> = snapshot_chain
+                .parts
+                .iter()
+                .map(|part| part.l1_batch_number)
+                .collect();
+            tracing::info!(
+                "Snapshot is incremental; recovery will apply snapshots for L1 batches {l1_batches:?} in order"
+            );
+        }
 
         let l1_batch = main_node_client
             .fetch_l1_batch_details(l1_batch_number)
@@ -541,9 +685,9 @@ impl SnapshotRecoveryStrategy {
             l2_block_timestamp: l2_block.base.timestamp,
             l2_block_hash,
             protocol_version,
-            storage_logs_chunks_processed: vec![false; snapshot.storage_logs_chunks.len()],
+            storage_logs_chunks_processed: vec![false; snapshot_chain.chunk_count()],
         };
-        Ok((status, snapshot_version))
+        Ok((status, snapshot_chain))
     }
 
     fn check_snapshot_version(raw_version: u16) -> anyhow::Result<SnapshotVersion> {
@@ -651,7 +795,7 @@ struct SnapshotsApplier<'a> {
     blob_store: &'a dyn ObjectStore,
     applied_snapshot_status: SnapshotRecoveryStatus,
     health_updater: &'a HealthUpdater,
-    snapshot_version: SnapshotVersion,
+    snapshot_chain: SnapshotChain,
     max_concurrency: usize,
     drop_storage_key_preimages: bool,
     factory_deps_recovered: bool,
@@ -684,10 +828,10 @@ impl<'a> SnapshotsApplier<'a> {
         )
         .await?;
         tracing::info!("Chosen snapshot recovery strategy: {strategy:?} with status: {applied_snapshot_status:?}");
-        let (created_from_scratch, snapshot_version) = match strategy {
+        let (created_from_scratch, snapshot_chain) = match &strategy {
             SnapshotRecoveryStrategy::Completed => return Ok((strategy, applied_snapshot_status)),
-            SnapshotRecoveryStrategy::New(version) => (true, version),
-            SnapshotRecoveryStrategy::Resumed(version) => (false, version),
+            SnapshotRecoveryStrategy::New(chain) => (true, chain.clone()),
+            SnapshotRecoveryStrategy::Resumed(chain) => (false, chain.clone()),
         };
 
         let mut this = Self {
@@ -696,7 +840,7 @@ impl<'a> SnapshotsApplier<'a> {
             blob_store: task.blob_store.as_ref(),
             applied_snapshot_status,
             health_updater,
-            snapshot_version,
+            snapshot_chain,
             max_concurrency: task.config.max_concurrency.get(),
             drop_storage_key_preimages: task.drop_storage_key_preimages,
             factory_deps_recovered: !created_from_scratch,
@@ -752,6 +896,10 @@ impl<'a> SnapshotsApplier<'a> {
         this.recover_tokens().await?;
         this.tokens_recovered = true;
         this.update_health();
+
+        if task.config.import_history {
+            this.recover_history(stop_receiver).await?;
+        }
         Ok((strategy, this.applied_snapshot_status))
     }
 
@@ -782,9 +930,24 @@ impl<'a> SnapshotsApplier<'a> {
         storage: &mut Connection<'_, Core>,
     ) -> Result<(), SnapshotsApplierError> {
         let latency = METRICS.initial_stage_duration[&InitialStage::ApplyFactoryDeps].start();
+        // Each incremental snapshot only contains factory deps deployed after its base snapshot.
+        for part in &self.snapshot_chain.parts {
+            self.recover_factory_deps_for_part(part.l1_batch_number, storage)
+                .await?;
+        }
+        let latency = latency.observe();
+        tracing::info!("Applied factory dependencies in {latency:?}");
+        Ok(())
+    }
 
-        tracing::debug!("Fetching factory dependencies from object store");
-        let l1_batch_number = self.applied_snapshot_status.l1_batch_number;
+    async fn recover_factory_deps_for_part(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        storage: &mut Connection<'_, Core>,
+    ) -> Result<(), SnapshotsApplierError> {
+        tracing::debug!(
+            "Fetching factory dependencies for L1 batch #{l1_batch_number} from object store"
+        );
         let factory_deps: SnapshotFactoryDependencies =
             self.blob_store.get(l1_batch_number).await.map_err(|err| {
                 let context = format!(
@@ -845,10 +1008,6 @@ impl<'a> SnapshotsApplier<'a> {
                 )
                 .await?;
         }
-
-        let latency = latency.observe();
-        tracing::info!("Applied factory dependencies in {latency:?}");
-
         Ok(())
     }
 
@@ -897,6 +1056,7 @@ impl<'a> SnapshotsApplier<'a> {
     async fn recover_storage_logs_single_chunk(
         &self,
         semaphore: &Semaphore,
+        part: &SnapshotPart,
         chunk_id: u64,
     ) -> Result<(), SnapshotsApplierError> {
         // `unwrap()` is safe: the semaphore is never closed
@@ -908,16 +1068,15 @@ impl<'a> SnapshotsApplier<'a> {
 
         let storage_key = SnapshotStorageLogsStorageKey {
             chunk_id,
-            l1_batch_number: self.applied_snapshot_status.l1_batch_number,
+            l1_batch_number: part.l1_batch_number,
         };
-        let mut storage_logs =
-            StorageLogs::load(self.blob_store, storage_key, self.snapshot_version)
-                .await
-                .map_err(|err| {
-                    let context =
-                        format!("cannot fetch storage logs {storage_key:?} from object store");
-                    SnapshotsApplierError::object_store(err, context)
-                })?;
+        let mut storage_logs = StorageLogs::load(self.blob_store, storage_key, part.version)
+            .await
+            .map_err(|err| {
+                let context =
+                    format!("cannot fetch storage logs {storage_key:?} from object store");
+                SnapshotsApplierError::object_store(err, context)
+            })?;
 
         storage_logs.validate(&self.applied_snapshot_status)?;
         if self.drop_storage_key_preimages {
@@ -940,15 +1099,34 @@ impl<'a> SnapshotsApplier<'a> {
 
         tracing::info!("Loading {} storage logs into Postgres", storage_logs.len());
 
-        self.insert_storage_logs_chunk(&storage_logs, &mut storage_transaction)
-            .await?;
-        let storage_logs = storage_logs.without_preimages();
-        self.insert_initial_writes_chunk(&storage_logs, &mut storage_transaction)
-            .await?;
+        if let Some(base_l1_batch_number) = part.base_l1_batch_number {
+            // Logs in an incremental snapshot overwrite logs for the same keys from the previous parts of the chain.
+            let storage_logs = storage_logs.without_preimages();
+            storage_transaction
+                .storage_logs_dal()
+                .upsert_storage_logs_from_snapshot(
+                    self.applied_snapshot_status.l2_block_number,
+                    &storage_logs,
+                )
+                .await?;
+            // Initial writes for keys written before the base snapshot are already inserted.
+            let new_storage_logs: Vec<_> = storage_logs
+                .into_iter()
+                .filter(|log| log.l1_batch_number_of_initial_write > base_l1_batch_number)
+                .collect();
+            self.insert_initial_writes_chunk(&new_storage_logs, &mut storage_transaction)
+                .await?;
+        } else {
+            self.insert_storage_logs_chunk(&storage_logs, &mut storage_transaction)
+                .await?;
+            let storage_logs = storage_logs.without_preimages();
+            self.insert_initial_writes_chunk(&storage_logs, &mut storage_transaction)
+                .await?;
+        }
 
         storage_transaction
             .snapshot_recovery_dal()
-            .mark_storage_logs_chunk_as_processed(chunk_id)
+            .mark_storage_logs_chunk_as_processed(part.first_chunk_index as u64 + chunk_id)
             .await?;
         storage_transaction.commit().await?;
 
@@ -970,23 +1148,27 @@ impl<'a> SnapshotsApplier<'a> {
         );
         let semaphore = Semaphore::new(effective_concurrency);
 
-        let tasks = self
-            .applied_snapshot_status
-            .storage_logs_chunks_processed
-            .iter()
-            .enumerate()
-            .filter(|(_, is_processed)| !**is_processed)
-            .map(|(chunk_id, _)| {
-                self.recover_storage_logs_single_chunk(&semaphore, chunk_id as u64)
-            });
-        let job_completion = futures::future::try_join_all(tasks);
-
-        tokio::select! {
-            res = job_completion => {
-                res?;
-            },
-            _ = stop_receiver.changed() => {
-                return Err(SnapshotsApplierError::Canceled);
+        // Parts of the snapshot chain must be applied sequentially, since incremental snapshots overwrite storage logs
+        // from the previous parts.
+        for part in &self.snapshot_chain.parts {
+            let chunks_processed = &self.applied_snapshot_status.storage_logs_chunks_processed
+                [part.first_chunk_index..part.first_chunk_index + part.chunk_count];
+            let tasks = chunks_processed
+                .iter()
+                .enumerate()
+                .filter(|(_, is_processed)| !**is_processed)
+                .map(|(chunk_id, _)| {
+                    self.recover_storage_logs_single_chunk(&semaphore, part, chunk_id as u64)
+                });
+            let job_completion = futures::future::try_join_all(tasks);
+
+            tokio::select! {
+                res = job_completion => {
+                    res?;
+                },
+                _ = stop_receiver.changed() => {
+                    return Err(SnapshotsApplierError::Canceled);
+                }
             }
         }
 
@@ -1072,4 +1254,341 @@ impl<'a> SnapshotsApplier<'a> {
         storage.tokens_dal().add_tokens(&tokens).await?;
         Ok(())
     }
+
+    /// Imports transaction history included into the snapshot. Must run after all other recovery steps,
+    /// since the presence of the snapshot L2 block in Postgres signals that recovery is completed.
+    async fn recover_history(
+        &self,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> Result<(), SnapshotsApplierError> {
+        let l1_batch_number = self.applied_snapshot_status.l1_batch_number;
+        let snapshot = self
+            .main_node_client
+            .fetch_snapshot(l1_batch_number)
+            .await?
+            .with_context(|| {
+                format!(
+                    "snapshot for L1 batch #{l1_batch_number} is no longer present on main node"
+                )
+            })?;
+        let history_chunks = snapshot.history_chunks;
+        let Some(last_chunk) = history_chunks.last() else {
+            tracing::info!("Snapshot doesn't contain transaction history; skipping history import");
+            return Ok(());
+        };
+        // The last imported L2 block must be the snapshot one; otherwise, the node won't be able to proceed
+        // after recovery.
+        if last_chunk.l1_batch_number != l1_batch_number {
+            let err = anyhow::anyhow!(
+                "transaction history in snapshot ends with L1 batch #{}, expected #{l1_batch_number}",
+                last_chunk.l1_batch_number
+            );
+            return Err(err.into());
+        }
+        let is_contiguous = history_chunks
+            .windows(2)
+            .all(|window| window[0].l1_batch_number + 1 == window[1].l1_batch_number);
+        if !is_contiguous {
+            let err = anyhow::anyhow!("transaction history in snapshot is not contiguous");
+            return Err(err.into());
+        }
+
+        let mut storage = self
+            .connection_pool
+            .connection_tagged("snapshots_applier")
+            .await?;
+        let sealed_l2_block_number = storage.blocks_dal().get_sealed_l2_block_number().await?;
+        drop(storage);
+
+        METRICS
+            .history_l1_batches_left_to_process
+            .set(history_chunks.len());
+        for chunk in &history_chunks {
+            if *stop_receiver.borrow() {
+                return Err(SnapshotsApplierError::Canceled);
+            }
+
+            let latency =
+                METRICS.history_l1_batch_duration[&HistoryStage::LoadHistoryFromGcs].start();
+            let key = SnapshotHistoryStorageKey {
+                snapshot_l1_batch_number: l1_batch_number,
+                l1_batch_number: chunk.l1_batch_number,
+            };
+            let history: SnapshotL1BatchHistory =
+                self.blob_store.get(key).await.map_err(|err| {
+                    let context = format!("cannot fetch history {key:?} from object store");
+                    SnapshotsApplierError::object_store(err, context)
+                })?;
+            latency.observe();
+
+            if history.header.number != chunk.l1_batch_number {
+                let err = anyhow::anyhow!(
+                    "history {key:?} contains unexpected L1 batch #{}",
+                    history.header.number
+                );
+                return Err(err.into());
+            }
+            let last_l2_block = history
+                .l2_blocks
+                .last()
+                .with_context(|| format!("history {key:?} doesn't contain L2 blocks"))?
+                .header
+                .number;
+            if sealed_l2_block_number >= Some(last_l2_block) {
+                tracing::info!(
+                    "History for L1 batch #{} is already imported",
+                    chunk.l1_batch_number
+                );
+            } else {
+                let latency =
+                    METRICS.history_l1_batch_duration[&HistoryStage::SaveHistoryToPostgres].start();
+                self.insert_l1_batch_history(&history).await?;
+                let latency = latency.observe();
+                tracing::info!(
+                    "Imported history for L1 batch #{} ({} L2 blocks) in {latency:?}",
+                    chunk.l1_batch_number,
+                    history.l2_blocks.len()
+                );
+            }
+            METRICS.history_l1_batches_left_to_process.dec_by(1);
+        }
+
+        let mut storage = self
+            .connection_pool
+            .connection_tagged("snapshots_applier")
+            .await?;
+        let sealed_l2_block_number = storage.blocks_dal().get_sealed_l2_block_number().await?;
+        if sealed_l2_block_number != Some(self.applied_snapshot_status.l2_block_number) {
+            let err = anyhow::anyhow!(
+                "last imported L2 block {sealed_l2_block_number:?} doesn't match the snapshot L2 block #{}",
+                self.applied_snapshot_status.l2_block_number
+            );
+            return Err(SnapshotsApplierError::Fatal(err));
+        }
+        Ok(())
+    }
+
+    /// Converts a transaction from the snapshot history to the format expected by the DAL.
+    fn tx_execution_result(tx: &SnapshotTransaction) -> anyhow::Result<TransactionExecutionResult> {
+        let hash = tx.transaction.hash();
+        let execution_info = if tx.execution_info.is_null() {
+            VmExecutionMetrics::default()
+        } else {
+            serde_json::from_value(tx.execution_info.clone())
+                .with_context(|| format!("invalid execution info for transaction {hash:?}"))?
+        };
+        // The top-level call is reconstructed from the transaction and its nested calls when it's persisted;
+        // see `TransactionExecutionResult::call_trace()`.
+        let (revert_reason, call_traces) = if let Some(call_trace) = &tx.call_trace {
+            let call: Call = serde_json::from_value(call_trace.clone())
+                .with_context(|| format!("invalid call trace for transaction {hash:?}"))?;
+            (call.revert_reason, call.calls)
+        } else {
+            (None, vec![])
+        };
+
+        Ok(TransactionExecutionResult {
+            hash,
+            transaction: tx.transaction.clone(),
+            execution_info,
+            execution_status: if tx.success {
+                TxExecutionStatus::Success
+            } else {
+                TxExecutionStatus::Failure
+            },
+            refunded_gas: tx.refunded_gas,
+            call_traces,
+            revert_reason,
+        })
+    }
+
+    /// Ensures that the protocol version referenced by imported L1 batches / L2 blocks is persisted, fetching it
+    /// from the main node if necessary.
+    async fn ensure_protocol_version_is_saved(
+        &self,
+        protocol_version: ProtocolVersionId,
+    ) -> Result<(), SnapshotsApplierError> {
+        let mut storage = self
+            .connection_pool
+            .connection_tagged("snapshots_applier")
+            .await?;
+        let base_system_contract_hashes = storage
+            .protocol_versions_dal()
+            .get_base_system_contract_hashes_by_version_id(protocol_version)
+            .await?;
+        if base_system_contract_hashes.is_some() {
+            return Ok(());
+        }
+        tracing::info!("Fetching protocol version {protocol_version:?} from the main node");
+
+        let version = self
+            .main_node_client
+            .fetch_protocol_version(protocol_version)
+            .await?
+            .with_context(|| {
+                format!("protocol version {protocol_version:?} is missing on the main node")
+            })?;
+        let bootloader = version
+            .bootloader_code_hash()
+            .context("missing bootloader code hash")?;
+        let default_aa = version
+            .default_account_code_hash()
+            .context("missing default account code hash")?;
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version(
+                ProtocolSemanticVersion {
+                    minor: protocol_version,
+                    patch: VersionPatch(0),
+                },
+                version.timestamp,
+                Default::default(), // verification keys are unused for EN
+                BaseSystemContractsHashes {
+                    bootloader,
+                    default_aa,
+                    evm_emulator: version.evm_emulator_code_hash(),
+                },
+                version.l2_system_upgrade_tx_hash(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Inserts history for a single L1 batch atomically. Besides L2 blocks and their transactions, inserts the sealed
+    /// L1 batch header; the L1 batch doesn't have metadata (e.g., the state root hash), so it's not processed by
+    /// the Merkle tree or commitment generation.
+    ///
+    /// Inserted rows aren't protected from pruning; since they precede the snapshot L1 batch, they are removed
+    /// by the first hard pruning after recovery.
+    async fn insert_l1_batch_history(
+        &self,
+        history: &SnapshotL1BatchHistory,
+    ) -> Result<(), SnapshotsApplierError> {
+        let l1_batch_number = history.header.number;
+        let protocol_versions = history.header.protocol_version.into_iter().chain(
+            history
+                .l2_blocks
+                .iter()
+                .filter_map(|block| block.header.protocol_version),
+        );
+        for protocol_version in protocol_versions.collect::<BTreeSet<_>>() {
+            self.ensure_protocol_version_is_saved(protocol_version)
+                .await?;
+        }
+
+        let mut storage = self
+            .connection_pool
+            .connection_tagged("snapshots_applier")
+            .await?;
+        let mut transaction = storage.start_transaction().await?;
+        transaction
+            .blocks_dal()
+            .insert_l1_batch(history.header.to_unsealed_header())
+            .await?;
+
+        let mut all_tx_results = vec![];
+        for block in &history.l2_blocks {
+            let header = &block.header;
+            transaction.blocks_dal().insert_l2_block(header).await?;
+
+            let tx_results = block
+                .transactions
+                .iter()
+                .map(Self::tx_execution_result)
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let protocol_version = header
+                .protocol_version
+                .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+            transaction
+                .transactions_dal()
+                .mark_txs_as_executed_in_l2_block(
+                    header.number,
+                    &tx_results,
+                    header.base_fee_per_gas.into(),
+                    protocol_version,
+                    true,
+                )
+                .await?;
+
+            let tx_location = |tx_index_in_l2_block: u32| {
+                let tx = block
+                    .transactions
+                    .get(tx_index_in_l2_block as usize)
+                    .with_context(|| {
+                        format!(
+                            "L2 block #{} references missing transaction #{tx_index_in_l2_block}",
+                            header.number
+                        )
+                    })?;
+                anyhow::Ok((
+                    IncludedTxLocation {
+                        tx_hash: tx.transaction.hash(),
+                        tx_index_in_l2_block,
+                    },
+                    tx.index_in_l1_batch,
+                ))
+            };
+
+            let mut events: Vec<(IncludedTxLocation, Vec<VmEvent>)> = vec![];
+            for event in &block.events {
+                let (location, tx_index_in_l1_batch) = tx_location(event.tx_index_in_l2_block)?;
+                let vm_event = VmEvent {
+                    location: (l1_batch_number, tx_index_in_l1_batch),
+                    address: event.address,
+                    indexed_topics: event.indexed_topics.clone(),
+                    value: event.value.0.clone(),
+                };
+                match events.last_mut() {
+                    Some((last_location, tx_events))
+                        if last_location.tx_index_in_l2_block == location.tx_index_in_l2_block =>
+                    {
+                        tx_events.push(vm_event);
+                    }
+                    _ => events.push((location, vec![vm_event])),
+                }
+            }
+            let events: Vec<_> = events
+                .iter()
+                .map(|(location, events)| (*location, events.iter().collect()))
+                .collect();
+            transaction
+                .events_dal()
+                .save_events(header.number, &events)
+                .await?;
+
+            let mut logs: Vec<(IncludedTxLocation, Vec<_>)> = vec![];
+            for log in &block.user_l2_to_l1_logs {
+                let (location, _) = tx_location(log.tx_index_in_l2_block)?;
+                match logs.last_mut() {
+                    Some((last_location, tx_logs))
+                        if last_location.tx_index_in_l2_block == location.tx_index_in_l2_block =>
+                    {
+                        tx_logs.push(&log.log);
+                    }
+                    _ => logs.push((location, vec![&log.log])),
+                }
+            }
+            transaction
+                .events_dal()
+                .save_user_l2_to_l1_logs(header.number, &logs)
+                .await?;
+
+            all_tx_results.extend(tx_results);
+        }
+
+        transaction
+            .blocks_dal()
+            .mark_l1_batch_as_sealed(&history.header, &[], &[], &[], Default::default())
+            .await?;
+        transaction
+            .blocks_dal()
+            .mark_l2_blocks_as_executed_in_l1_batch(l1_batch_number)
+            .await?;
+        transaction
+            .transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(l1_batch_number, &all_tx_results)
+            .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
 }
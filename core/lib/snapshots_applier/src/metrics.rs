@@ -11,6 +11,13 @@ pub(crate) enum StorageLogsChunksStage {
     SaveToPostgres,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(crate) enum HistoryStage {
+    LoadHistoryFromGcs,
+    SaveHistoryToPostgres,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
 pub(crate) enum InitialStage {
@@ -38,6 +45,13 @@ pub(crate) struct SnapshotsApplierMetrics {
     /// Latency of storage log chunk processing split by stage.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub storage_logs_chunks_duration: Family<StorageLogsChunksStage, Histogram<Duration>>,
+
+    /// Number of L1 batches with transaction history left to import.
+    pub history_l1_batches_left_to_process: Gauge<usize>,
+
+    /// Latency of importing transaction history for a single L1 batch split by stage.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub history_l1_batch_duration: Family<HistoryStage, Histogram<Duration>>,
 }
 
 #[vise::register]
@@ -9,11 +9,17 @@ use assert_matches::assert_matches;
 use test_casing::test_casing;
 use tokio::sync::Barrier;
 use zksync_health_check::CheckHealth;
+use zksync_node_test_utils::create_l2_transaction;
 use zksync_object_store::MockObjectStore;
 use zksync_types::{
     api::{BlockDetails, L1BatchDetails},
-    block::L1BatchHeader,
-    get_code_key, L1BatchNumber, ProtocolVersion, ProtocolVersionId,
+    block::{L1BatchHeader, L2BlockHeader},
+    get_code_key,
+    snapshots::{
+        SnapshotEvent, SnapshotFactoryDependency, SnapshotHistoryChunkMetadata,
+        SnapshotL2BlockHistory, SnapshotTransaction,
+    },
+    Address, L1BatchNumber, ProtocolVersion, ProtocolVersionId,
 };
 
 use self::utils::{
@@ -463,6 +469,14 @@ async fn health_status_immediately_after_task_start() {
             self.0.wait().await;
             future::pending().await
         }
+
+        async fn fetch_protocol_version(
+            &self,
+            _protocol_version: ProtocolVersionId,
+        ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+            self.0.wait().await;
+            future::pending().await
+        }
     }
 
     let object_store = MockObjectStore::arc();
@@ -716,6 +730,496 @@ async fn recovering_tokens() {
     task.run(stop_receiver).await.unwrap();
 }
 
+#[tokio::test]
+async fn recovering_incremental_snapshot() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let base_l1_batch_number = L1BatchNumber(100);
+
+    let base_storage_logs = random_storage_logs::<H256>(L1BatchNumber(50), 100);
+    // The incremental snapshot updates some of the base logs and adds new ones.
+    let updated_logs = base_storage_logs
+        .iter()
+        .step_by(5)
+        .map(|log| SnapshotStorageLog {
+            value: H256::random(),
+            ..log.clone()
+        });
+    let new_logs = random_storage_logs::<H256>(L1BatchNumber(110), 30)
+        .into_iter()
+        .map(|log| SnapshotStorageLog {
+            enumeration_index: log.enumeration_index + base_storage_logs.len() as u64,
+            ..log
+        });
+    let incremental_logs: Vec<_> = updated_logs.chain(new_logs).collect();
+    let factory_deps = mock_factory_deps(None);
+    let (object_store, mut client) =
+        prepare_clients(&expected_status, &factory_deps, &incremental_logs).await;
+
+    let base_status = SnapshotRecoveryStatus {
+        l1_batch_number: base_l1_batch_number,
+        l2_block_number: L2BlockNumber(200),
+        storage_logs_chunks_processed: vec![true; 3],
+        ..mock_recovery_status()
+    };
+    let base_factory_deps = SnapshotFactoryDependencies {
+        factory_deps: vec![SnapshotFactoryDependency {
+            bytecode: vec![0; 32].into(),
+            hash: None,
+        }],
+    };
+    object_store
+        .put(base_l1_batch_number, &base_factory_deps)
+        .await
+        .unwrap();
+    for (chunk_id, chunk) in base_storage_logs.chunks(40).enumerate() {
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: base_l1_batch_number,
+            chunk_id: chunk_id as u64,
+        };
+        let chunk = SnapshotStorageLogsChunk {
+            storage_logs: chunk.to_vec(),
+        };
+        object_store.put(key, &chunk).await.unwrap();
+    }
+    client.fetch_snapshot_responses.insert(
+        base_l1_batch_number,
+        mock_snapshot_header(SnapshotVersion::Version1.into(), &base_status),
+    );
+    client
+        .fetch_newest_snapshot_response
+        .as_mut()
+        .unwrap()
+        .base_l1_batch_number = Some(base_l1_batch_number);
+
+    let task = SnapshotsApplierTask::new(
+        SnapshotsApplierConfig::for_tests(),
+        pool.clone(),
+        Box::new(client.clone()),
+        object_store,
+    );
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let stats = task.run(stop_receiver).await.unwrap();
+    assert!(stats.done_work);
+    assert_eq!(
+        is_recovery_completed(&pool, &client).await,
+        RecoveryCompletionStatus::Completed
+    );
+
+    let mut storage = pool.connection().await.unwrap();
+    let recovery_status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap()
+        .expect("no recovery status");
+    assert_eq!(
+        recovery_status.l1_batch_number,
+        expected_status.l1_batch_number
+    );
+    // 3 chunks from the base snapshot + 2 chunks from the incremental one
+    assert_eq!(recovery_status.storage_logs_chunks_processed, [true; 5]);
+
+    let mut expected_logs: HashMap<_, _> = base_storage_logs
+        .iter()
+        .chain(&incremental_logs)
+        .map(|log| (log.key, log))
+        .collect();
+    assert_eq!(expected_logs.len(), 130);
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), expected_logs.len());
+    for db_log in all_storage_logs {
+        let expected_log = expected_logs.remove(&db_log.hashed_key).unwrap();
+        assert_eq!(db_log.value, expected_log.value);
+        assert_eq!(db_log.operation_number, expected_log.enumeration_index);
+        assert_eq!(db_log.l2_block_number, expected_status.l2_block_number);
+    }
+
+    let all_factory_deps = storage
+        .factory_deps_dal()
+        .dump_all_factory_deps_for_tests()
+        .await;
+    assert_eq!(all_factory_deps.len(), 2);
+}
+
+/// Stores `histories` in the object store and references them in the snapshot header returned by the client.
+async fn put_history(
+    object_store: &Arc<dyn ObjectStore>,
+    client: &mut MockMainNodeClient,
+    snapshot_l1_batch: L1BatchNumber,
+    histories: &[SnapshotL1BatchHistory],
+) {
+    // The protocol version isn't persisted by the applier during the main recovery phase, so it must be fetched
+    // from the main node.
+    client.fetch_protocol_version_responses.insert(
+        ProtocolVersionId::default(),
+        api::ProtocolVersion::new(
+            ProtocolVersionId::default() as u16,
+            0,
+            H256::repeat_byte(1),
+            H256::repeat_byte(2),
+            None,
+            None,
+        ),
+    );
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    for history in histories {
+        let key = SnapshotHistoryStorageKey {
+            snapshot_l1_batch_number: snapshot_l1_batch,
+            l1_batch_number: history.header.number,
+        };
+        let filepath = object_store.put(key, history).await.unwrap();
+        snapshot_header
+            .history_chunks
+            .push(SnapshotHistoryChunkMetadata {
+                l1_batch_number: history.header.number,
+                filepath,
+            });
+    }
+}
+
+#[tokio::test]
+async fn recovering_history() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let factory_deps = mock_factory_deps(None);
+    let storage_logs = random_storage_logs::<H256>(expected_status.l1_batch_number, 200);
+    let (object_store, mut client) =
+        prepare_clients(&expected_status, &factory_deps, &storage_logs).await;
+
+    // History spans 2 L1 batches: the snapshot one with a single L2 block, and the previous one with 2 blocks.
+    let snapshot_l1_batch = expected_status.l1_batch_number;
+    let snapshot_l2_block = expected_status.l2_block_number;
+    let tx = create_l2_transaction(10, 100);
+    let tx_hash = tx.hash();
+    let failed_tx = create_l2_transaction(10, 100);
+    let failed_tx_hash = failed_tx.hash();
+    let event = SnapshotEvent {
+        tx_index_in_l2_block: 0,
+        address: Address::repeat_byte(0x23),
+        indexed_topics: vec![H256::repeat_byte(1)],
+        value: vec![1, 2, 3].into(),
+    };
+    let execution_info = VmExecutionMetrics {
+        gas_used: 10_000,
+        storage_logs: 3,
+        ..VmExecutionMetrics::default()
+    };
+    let nested_call = Call {
+        from: Address::repeat_byte(0x23),
+        to: Address::repeat_byte(0x42),
+        gas_used: 500,
+        ..Call::default()
+    };
+    let call_trace = Call::new_high_level(
+        0,
+        0,
+        0.into(),
+        vec![],
+        vec![],
+        None,
+        vec![nested_call.clone()],
+    );
+    let failed_call_trace = Call::new_high_level(
+        0,
+        0,
+        0.into(),
+        vec![],
+        vec![],
+        Some("oops".to_owned()),
+        vec![nested_call.clone()],
+    );
+    let histories = [
+        SnapshotL1BatchHistory {
+            header: L1BatchHeader {
+                l2_tx_count: 2,
+                ..L1BatchHeader::new(
+                    snapshot_l1_batch - 1,
+                    1,
+                    Default::default(),
+                    ProtocolVersionId::default(),
+                )
+            },
+            l2_blocks: vec![
+                SnapshotL2BlockHistory {
+                    header: mock_l2_block_header(snapshot_l2_block - 2),
+                    transactions: vec![],
+                    events: vec![],
+                    user_l2_to_l1_logs: vec![],
+                },
+                SnapshotL2BlockHistory {
+                    header: L2BlockHeader {
+                        l2_tx_count: 2,
+                        ..mock_l2_block_header(snapshot_l2_block - 1)
+                    },
+                    transactions: vec![
+                        SnapshotTransaction {
+                            transaction: tx.into(),
+                            index_in_l1_batch: 0,
+                            success: true,
+                            refunded_gas: 0,
+                            execution_info: serde_json::to_value(execution_info).unwrap(),
+                            call_trace: Some(serde_json::to_value(&call_trace).unwrap()),
+                        },
+                        SnapshotTransaction {
+                            transaction: failed_tx.into(),
+                            index_in_l1_batch: 1,
+                            success: false,
+                            refunded_gas: 0,
+                            execution_info: serde_json::Value::Null,
+                            call_trace: Some(serde_json::to_value(&failed_call_trace).unwrap()),
+                        },
+                    ],
+                    events: vec![event.clone()],
+                    user_l2_to_l1_logs: vec![],
+                },
+            ],
+        },
+        SnapshotL1BatchHistory {
+            header: L1BatchHeader::new(
+                snapshot_l1_batch,
+                2,
+                Default::default(),
+                ProtocolVersionId::default(),
+            ),
+            l2_blocks: vec![SnapshotL2BlockHistory {
+                header: mock_l2_block_header(snapshot_l2_block),
+                transactions: vec![],
+                events: vec![],
+                user_l2_to_l1_logs: vec![],
+            }],
+        },
+    ];
+    put_history(&object_store, &mut client, snapshot_l1_batch, &histories).await;
+
+    let config = SnapshotsApplierConfig {
+        import_history: true,
+        ..SnapshotsApplierConfig::for_tests()
+    };
+    let task = SnapshotsApplierTask::new(
+        config.clone(),
+        pool.clone(),
+        Box::new(client.clone()),
+        object_store.clone(),
+    );
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    task.run(stop_receiver).await.unwrap();
+
+    assert_eq!(
+        is_recovery_completed(&pool, &client).await,
+        RecoveryCompletionStatus::Completed
+    );
+    let mut storage = pool.connection().await.unwrap();
+    let sealed_l2_block = storage
+        .blocks_dal()
+        .get_sealed_l2_block_number()
+        .await
+        .unwrap();
+    assert_eq!(sealed_l2_block, Some(snapshot_l2_block));
+
+    let receipts = storage
+        .transactions_web3_dal()
+        .get_transaction_receipts(&[tx_hash])
+        .await
+        .unwrap();
+    assert_eq!(receipts.len(), 1);
+    let receipt = &receipts[0];
+    assert_eq!(receipt.inner.block_number, (snapshot_l2_block.0 - 1).into());
+    assert_eq!(
+        receipt.inner.l1_batch_number,
+        Some((snapshot_l1_batch.0 - 1).into())
+    );
+    assert_eq!(receipt.inner.status, 1.into());
+    assert_eq!(receipt.inner.logs.len(), 1);
+    assert_eq!(receipt.inner.logs[0].address, event.address);
+    assert_eq!(receipt.inner.logs[0].topics, event.indexed_topics);
+
+    let (imported_call_trace, _) = storage
+        .transactions_dal()
+        .get_call_trace(tx_hash)
+        .await
+        .unwrap()
+        .expect("no call trace");
+    assert_eq!(imported_call_trace.revert_reason, None);
+    assert_eq!(imported_call_trace.calls.len(), 1);
+    assert_eq!(imported_call_trace.calls[0].to, nested_call.to);
+    let imported_execution_info = storage
+        .transactions_web3_dal()
+        .get_unstable_transaction_execution_info(tx_hash)
+        .await
+        .unwrap()
+        .expect("no execution info");
+    let imported_execution_info: VmExecutionMetrics =
+        serde_json::from_value(imported_execution_info).unwrap();
+    assert_eq!(imported_execution_info, execution_info);
+
+    let receipts = storage
+        .transactions_web3_dal()
+        .get_transaction_receipts(&[failed_tx_hash])
+        .await
+        .unwrap();
+    assert_eq!(receipts[0].inner.status, 0.into());
+    let (imported_call_trace, _) = storage
+        .transactions_dal()
+        .get_call_trace(failed_tx_hash)
+        .await
+        .unwrap()
+        .expect("no call trace");
+    assert_eq!(imported_call_trace.revert_reason.as_deref(), Some("oops"));
+
+    // L1 batches must be inserted as sealed, but without metadata.
+    for history in &histories {
+        let header = storage
+            .blocks_dal()
+            .get_l1_batch_header(history.header.number)
+            .await
+            .unwrap()
+            .expect("no L1 batch header");
+        assert_eq!(header.timestamp, history.header.timestamp);
+        assert_eq!(header.l2_tx_count, history.header.l2_tx_count);
+        let (first_l2_block, last_l2_block) = storage
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(history.header.number)
+            .await
+            .unwrap()
+            .expect("no L2 blocks for L1 batch");
+        assert_eq!(first_l2_block, history.l2_blocks[0].header.number);
+        assert_eq!(
+            last_l2_block,
+            history.l2_blocks.last().unwrap().header.number
+        );
+    }
+    let sealed_l1_batch = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .unwrap();
+    assert_eq!(sealed_l1_batch, Some(snapshot_l1_batch));
+    drop(storage);
+
+    // Recovering again should be a no-op.
+    let task = SnapshotsApplierTask::new(config, pool, Box::new(client), object_store);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let stats = task.run(stop_receiver).await.unwrap();
+    assert!(!stats.done_work);
+}
+
+/// Imported history is older than the snapshot L1 batch, so it's removed by the first hard pruning after recovery.
+#[tokio::test]
+async fn pruning_imported_history() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let factory_deps = mock_factory_deps(None);
+    let storage_logs = random_storage_logs::<H256>(expected_status.l1_batch_number, 200);
+    let (object_store, mut client) =
+        prepare_clients(&expected_status, &factory_deps, &storage_logs).await;
+
+    let snapshot_l1_batch = expected_status.l1_batch_number;
+    let snapshot_l2_block = expected_status.l2_block_number;
+    let tx = create_l2_transaction(10, 100);
+    let tx_hash = tx.hash();
+    let histories = [
+        SnapshotL1BatchHistory {
+            header: L1BatchHeader {
+                l2_tx_count: 1,
+                ..L1BatchHeader::new(
+                    snapshot_l1_batch - 1,
+                    1,
+                    Default::default(),
+                    ProtocolVersionId::default(),
+                )
+            },
+            l2_blocks: vec![SnapshotL2BlockHistory {
+                header: L2BlockHeader {
+                    l2_tx_count: 1,
+                    ..mock_l2_block_header(snapshot_l2_block - 1)
+                },
+                transactions: vec![SnapshotTransaction {
+                    transaction: tx.into(),
+                    index_in_l1_batch: 0,
+                    success: true,
+                    refunded_gas: 0,
+                    execution_info: serde_json::Value::Null,
+                    call_trace: None,
+                }],
+                events: vec![SnapshotEvent {
+                    tx_index_in_l2_block: 0,
+                    address: Address::repeat_byte(0x23),
+                    indexed_topics: vec![H256::repeat_byte(1)],
+                    value: vec![1, 2, 3].into(),
+                }],
+                user_l2_to_l1_logs: vec![],
+            }],
+        },
+        SnapshotL1BatchHistory {
+            header: L1BatchHeader::new(
+                snapshot_l1_batch,
+                2,
+                Default::default(),
+                ProtocolVersionId::default(),
+            ),
+            l2_blocks: vec![SnapshotL2BlockHistory {
+                header: mock_l2_block_header(snapshot_l2_block),
+                transactions: vec![],
+                events: vec![],
+                user_l2_to_l1_logs: vec![],
+            }],
+        },
+    ];
+    put_history(&object_store, &mut client, snapshot_l1_batch, &histories).await;
+
+    let config = SnapshotsApplierConfig {
+        import_history: true,
+        ..SnapshotsApplierConfig::for_tests()
+    };
+    let task = SnapshotsApplierTask::new(config, pool.clone(), Box::new(client), object_store);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    task.run(stop_receiver).await.unwrap();
+
+    let mut storage = pool.connection().await.unwrap();
+    let receipts = storage
+        .transactions_web3_dal()
+        .get_transaction_receipts(&[tx_hash])
+        .await
+        .unwrap();
+    assert_eq!(receipts.len(), 1);
+
+    // Emulate the DB pruner pruning the snapshot L1 batch, which happens once the first L1 batch after recovery
+    // falls outside the data retention period.
+    let stats = storage
+        .pruning_dal()
+        .hard_prune_batches_range(snapshot_l1_batch, snapshot_l2_block)
+        .await
+        .unwrap();
+    assert_eq!(stats.deleted_l1_batches, 2);
+    assert_eq!(stats.deleted_l2_blocks, 2);
+    assert_eq!(stats.deleted_events, 1);
+
+    for history in &histories {
+        let header = storage
+            .blocks_dal()
+            .get_l1_batch_header(history.header.number)
+            .await
+            .unwrap();
+        assert!(header.is_none(), "{header:?}");
+        let l2_block_range = storage
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(history.header.number)
+            .await
+            .unwrap();
+        assert!(l2_block_range.is_none(), "{l2_block_range:?}");
+    }
+    let receipts = storage
+        .transactions_web3_dal()
+        .get_transaction_receipts(&[tx_hash])
+        .await
+        .unwrap();
+    assert!(receipts.is_empty(), "{receipts:?}");
+}
+
 #[tokio::test]
 async fn snapshot_applier_can_be_canceled() {
     let pool = ConnectionPool::<Core>::test_pool().await;
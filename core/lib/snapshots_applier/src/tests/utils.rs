@@ -54,7 +54,10 @@ pub(super) struct MockMainNodeClient {
     pub fetch_l1_batch_responses: HashMap<L1BatchNumber, api::L1BatchDetails>,
     pub fetch_l2_block_responses: HashMap<L2BlockNumber, api::BlockDetails>,
     pub fetch_newest_snapshot_response: Option<SnapshotHeader>,
+    /// Responses for older snapshots (e.g., base snapshots for an incremental snapshot).
+    pub fetch_snapshot_responses: HashMap<L1BatchNumber, SnapshotHeader>,
     pub tokens_response: Vec<TokenInfo>,
+    pub fetch_protocol_version_responses: HashMap<ProtocolVersionId, api::ProtocolVersion>,
     pub tokens_response_error: Arc<RwLock<Option<EnrichedClientError>>>,
 }
 
@@ -99,10 +102,12 @@ impl SnapshotsApplierMainNodeClient for MockMainNodeClient {
         &self,
         l1_batch_number: L1BatchNumber,
     ) -> EnrichedClientResult<Option<SnapshotHeader>> {
-        Ok(self
+        let newest_snapshot = self
             .fetch_newest_snapshot_response
             .clone()
-            .filter(|response| response.l1_batch_number == l1_batch_number))
+            .filter(|response| response.l1_batch_number == l1_batch_number);
+        let older_snapshot = || self.fetch_snapshot_responses.get(&l1_batch_number).cloned();
+        Ok(newest_snapshot.or_else(older_snapshot))
     }
 
     async fn fetch_tokens(
@@ -115,6 +120,16 @@ impl SnapshotsApplierMainNodeClient for MockMainNodeClient {
 
         Ok(self.tokens_response.clone())
     }
+
+    async fn fetch_protocol_version(
+        &self,
+        protocol_version: ProtocolVersionId,
+    ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+        Ok(self
+            .fetch_protocol_version_responses
+            .get(&protocol_version)
+            .cloned())
+    }
 }
 
 type ValidateFn = dyn Fn(&str) -> Result<(), ObjectStoreError> + Send + Sync;
@@ -314,6 +329,8 @@ pub(super) fn mock_snapshot_header(
             })
             .collect(),
         factory_deps_filepath: "some_filepath".to_string(),
+        base_l1_batch_number: None,
+        history_chunks: vec![],
     }
 }
 
@@ -933,6 +933,10 @@ pub struct TeeProof {
     pub status: String,
     #[serde_as(as = "Option<Hex>")]
     pub attestation: Option<Vec<u8>>,
+    /// Time the batch has reached the TEE proof quorum. `None` if the quorum isn't reached (yet)
+    /// or isn't configured.
+    #[serde(default)]
+    pub quorum_reached_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -988,6 +992,49 @@ pub struct L1ToL2TxsStatus {
     pub l1_to_l2_txs_paused: bool,
 }
 
+/// Lengths of transaction queues on the node returned by `admin_getNodeStatus`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeQueueLengths {
+    /// Number of L1 transactions in the in-memory state keeper mempool. `None` if the node doesn't run the state keeper.
+    pub state_keeper_l1_txs: Option<usize>,
+    /// Number of L2 transactions in the in-memory state keeper mempool. `None` if the node doesn't run the state keeper.
+    pub state_keeper_l2_txs: Option<u64>,
+    /// Number of L1-to-L2 transactions in the Postgres mempool.
+    pub l1_to_l2_txs_in_mempool: usize,
+    /// Number of L1 transactions sent by the node that are not confirmed yet.
+    pub unconfirmed_eth_txs: usize,
+}
+
+/// Node status returned by `admin_getNodeStatus`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeStatus {
+    /// Whether the state keeper is paused. `None` if the node doesn't run the state keeper.
+    pub state_keeper_paused: Option<bool>,
+    pub queue_lengths: NodeQueueLengths,
+    /// Health of the node components, in the same format as returned by the healthcheck server.
+    pub health: Option<Value>,
+}
+
+/// Divergence between the main and shadow VM recorded in the divergence registry.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VmDivergence {
+    pub l1_batch_number: L1BatchNumber,
+    /// 0-based index of the diverging transaction in the batch. `None` if the divergence
+    /// is not attributed to a transaction (e.g., if it occurred when finishing the batch).
+    pub tx_index_in_l1_batch: Option<u32>,
+    /// Comma-separated list of mismatched VM output fields.
+    pub kind: String,
+    /// Key of the VM dump in the `VmDumps` object store bucket, if the dump was saved.
+    pub dump_key: Option<String>,
+    /// Number of times this divergence was observed.
+    pub occurrences: u32,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct EcosystemContracts {
     pub bridgehub_proxy_addr: Address,
@@ -111,7 +111,7 @@ pub struct CommonL1BatchHeader {
 }
 
 /// Holder for the L2 block metadata that is not available from transactions themselves.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct L2BlockHeader {
     pub number: L2BlockNumber,
     pub timestamp: u64,
@@ -2,9 +2,15 @@ use std::ops;
 
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::{L1BatchNumber, L2BlockNumber, H256};
+use zksync_basic_types::{Address, L1BatchNumber, L2BlockNumber, H256};
 
-use crate::{u256_to_h256, utils, web3::Bytes, ProtocolVersionId, StorageKey, StorageValue, U256};
+use crate::{
+    block::{L1BatchHeader, L2BlockHeader},
+    l2_to_l1_log::UserL2ToL1Log,
+    u256_to_h256, utils,
+    web3::Bytes,
+    ProtocolVersionId, StorageKey, StorageValue, Transaction, U256,
+};
 
 /// Information about all snapshots persisted by the node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,17 +39,30 @@ pub struct SnapshotMetadata {
     pub version: SnapshotVersion,
     /// L1 batch for the snapshot. The data in the snapshot captures node storage at the end of this batch.
     pub l1_batch_number: L1BatchNumber,
+    /// L1 batch of the snapshot this snapshot is based on. If set, the snapshot is incremental: its storage logs
+    /// and factory dependencies only contain changes after the base snapshot.
+    pub base_l1_batch_number: Option<L1BatchNumber>,
     /// Path to the factory dependencies blob.
     pub factory_deps_filepath: String,
     /// Paths to the storage log blobs. Ordered by the chunk ID. If a certain chunk is not produced yet,
     /// the corresponding path is `None`.
     pub storage_logs_filepaths: Vec<Option<String>>,
+    /// Paths to the transaction history blobs for the trailing window of L1 batches ending at `l1_batch_number`.
+    /// Ordered by the L1 batch number; if a certain blob is not produced yet, the corresponding path is `None`.
+    /// Empty if the snapshot doesn't include history.
+    pub history_filepaths: Vec<Option<String>>,
 }
 
 impl SnapshotMetadata {
     /// Checks whether a snapshot is complete (contains all information to restore from).
     pub fn is_complete(&self) -> bool {
         self.storage_logs_filepaths.iter().all(Option::is_some)
+            && self.history_filepaths.iter().all(Option::is_some)
+    }
+
+    /// Returns the L1 batch covered by the history blob with the specified index in [`Self::history_filepaths`].
+    pub fn history_l1_batch(&self, index: usize) -> L1BatchNumber {
+        self.l1_batch_number + index as u32 + 1 - self.history_filepaths.len() as u32
     }
 }
 
@@ -61,6 +80,14 @@ pub struct SnapshotHeader {
     /// Ordered by chunk IDs.
     pub storage_logs_chunks: Vec<SnapshotStorageLogsChunkMetadata>,
     pub factory_deps_filepath: String,
+    /// For incremental snapshots, L1 batch of the base snapshot. To recover from an incremental snapshot,
+    /// the base snapshot (which may be incremental itself) must be applied first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_l1_batch_number: Option<L1BatchNumber>,
+    /// Transaction history for the trailing L1 batches up to and including `l1_batch_number`,
+    /// ordered by the L1 batch number. Empty if the snapshot doesn't include history.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history_chunks: Vec<SnapshotHistoryChunkMetadata>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -71,6 +98,14 @@ pub struct SnapshotStorageLogsChunkMetadata {
     pub filepath: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotHistoryChunkMetadata {
+    pub l1_batch_number: L1BatchNumber,
+    // can be either be a file available under HTTP(s) or local filesystem path
+    pub filepath: String,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotStorageLogsStorageKey {
@@ -78,6 +113,15 @@ pub struct SnapshotStorageLogsStorageKey {
     pub chunk_id: u64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotHistoryStorageKey {
+    /// L1 batch of the snapshot the history belongs to.
+    pub snapshot_l1_batch_number: L1BatchNumber,
+    /// L1 batch covered by the history blob.
+    pub l1_batch_number: L1BatchNumber,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SnapshotStorageLogsChunk<K = H256> {
     pub storage_logs: Vec<SnapshotStorageLog<K>>,
@@ -117,6 +161,66 @@ pub struct SnapshotFactoryDependency {
     pub hash: Option<H256>,
 }
 
+/// Transaction history of a single L1 batch included into a snapshot. Allows a node recovered from the snapshot
+/// to serve receipts and logs for the trailing L1 batches before the snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotL1BatchHistory {
+    /// Header of the sealed L1 batch. It's imported together with L2 blocks so that they reference an existing L1 batch.
+    pub header: L1BatchHeader,
+    /// Ordered by the L2 block number.
+    pub l2_blocks: Vec<SnapshotL2BlockHistory>,
+}
+
+/// Header, transactions and their execution results for a single L2 block in [`SnapshotL1BatchHistory`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotL2BlockHistory {
+    pub header: L2BlockHeader,
+    /// Ordered by the index in the L2 block.
+    pub transactions: Vec<SnapshotTransaction>,
+    /// Ordered by the index in the L2 block.
+    pub events: Vec<SnapshotEvent>,
+    /// Ordered by the index in the L2 block.
+    pub user_l2_to_l1_logs: Vec<SnapshotUserL2ToL1Log>,
+}
+
+/// Executed transaction in [`SnapshotL2BlockHistory`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotTransaction {
+    pub transaction: Transaction,
+    /// Index of the transaction in its L1 batch.
+    pub index_in_l1_batch: u32,
+    pub success: bool,
+    pub refunded_gas: u64,
+    /// Execution metrics of the transaction as stored in Postgres (serialized `VmExecutionMetrics` from the VM interface).
+    #[serde(default)]
+    pub execution_info: serde_json::Value,
+    /// Top-level call trace of the transaction (serialized `Call` from the VM interface), if it's stored. Contains
+    /// the revert reason and nested calls of the transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_trace: Option<serde_json::Value>,
+}
+
+/// Event emitted by a transaction in [`SnapshotL2BlockHistory`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotEvent {
+    pub tx_index_in_l2_block: u32,
+    pub address: Address,
+    pub indexed_topics: Vec<H256>,
+    pub value: Bytes,
+}
+
+/// User L2-to-L1 log emitted by a transaction in [`SnapshotL2BlockHistory`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotUserL2ToL1Log {
+    pub tx_index_in_l2_block: u32,
+    pub log: UserL2ToL1Log,
+}
+
 #[cfg(feature = "protobuf")]
 mod proto_impl {
     use anyhow::Context;
@@ -7,7 +7,11 @@ use ::sentry::ClientInitGuard;
 use anyhow::Context as _;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-pub use crate::{logs::Logs, opentelemetry::OpenTelemetry, sentry::Sentry};
+pub use crate::{
+    logs::{Logs, LogsReloadHandle},
+    opentelemetry::OpenTelemetry,
+    sentry::Sentry,
+};
 
 pub mod logs;
 pub mod opentelemetry;
@@ -32,9 +36,16 @@ pub struct ObservabilityGuard {
     otlp_logging_provider: Option<opentelemetry_sdk::logs::LoggerProvider>,
     /// Sentry client guard
     sentry_guard: Option<ClientInitGuard>,
+    /// Handle for the global logs filter
+    logs_reload_handle: LogsReloadHandle,
 }
 
 impl ObservabilityGuard {
+    /// Returns a handle allowing to change log directives at runtime.
+    pub fn logs_reload_handle(&self) -> LogsReloadHandle {
+        self.logs_reload_handle.clone()
+    }
+
     /// Forces flushing of pending events.
     /// This method is blocking.
     pub fn force_flush(&self) {
@@ -135,9 +146,10 @@ impl ObservabilityBuilder {
 
         // For now we use logs filter as a global filter for subscriber.
         // Later we may want to enforce each layer to have its own filter.
-        let global_filter = logs.build_filter();
+        let (global_filter, logs_reload_handle) = logs.build_reloadable_filter();
 
-        let logs_layer = logs.into_layer();
+        // The logs layer isn't filtered on its own, so that changes in the global filter apply to it.
+        let logs_layer = logs.into_unfiltered_layer();
         let (otlp_tracing_provider, otlp_tracing_layer) = self
             .opentelemetry_layer
             .as_ref()
@@ -162,6 +174,7 @@ impl ObservabilityBuilder {
             otlp_tracing_provider,
             otlp_logging_provider,
             sentry_guard,
+            logs_reload_handle,
         })
     }
 
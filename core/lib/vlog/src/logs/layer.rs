@@ -1,7 +1,9 @@
 use tracing::{span, Subscriber};
 use tracing_subscriber::{fmt, registry::LookupSpan, Layer};
 
-/// Implementation of statically typed logs layer, which can be either plain or JSON.
+use super::logfmt::LogfmtFormat;
+
+/// Implementation of statically typed logs layer, which can be either plain, JSON or logfmt.
 /// This is mostly required to avoid [boxing the layer][layer_box].
 ///
 /// [layer_box]: https://docs.rs/tracing-subscriber/latest/tracing_subscriber/layer/trait.Layer.html#method.boxed
@@ -9,6 +11,7 @@ use tracing_subscriber::{fmt, registry::LookupSpan, Layer};
 pub enum LogsLayer<S> {
     Plain(fmt::Layer<S>),
     Json(JsonLayer<S>),
+    Logfmt(LogfmtLayer<S>),
 }
 
 macro_rules! dispatch_layer {
@@ -16,6 +19,7 @@ macro_rules! dispatch_layer {
         match $self {
             LogsLayer::Plain(layer) => layer.$method($($arg),*),
             LogsLayer::Json(layer) => layer.$method($($arg),*),
+            LogsLayer::Logfmt(layer) => layer.$method($($arg),*),
         }
     };
 }
@@ -138,3 +142,6 @@ type JsonLayer<S> = tracing_subscriber::fmt::Layer<
         tracing_subscriber::fmt::time::UtcTime<time::format_description::well_known::Rfc3339>,
     >,
 >;
+
+type LogfmtLayer<S> =
+    tracing_subscriber::fmt::Layer<S, tracing_subscriber::fmt::format::DefaultFields, LogfmtFormat>;
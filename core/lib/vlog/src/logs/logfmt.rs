@@ -0,0 +1,161 @@
+//! [logfmt](https://brandur.org/logfmt) formatting for log events.
+
+use std::fmt::{self, Write as _};
+
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{
+    fmt::{
+        format::Writer,
+        time::{FormatTime, UtcTime},
+        FmtContext, FormatEvent, FormatFields,
+    },
+    registry::LookupSpan,
+};
+
+/// Formats events as a single line of space-separated `key=value` pairs, e.g.
+///
+/// ```text
+/// ts=2024-01-01T00:00:00.000000Z level=info target=zksync_state_keeper::keeper span=state_keeper msg="Sealed L1 batch" number=42
+/// ```
+///
+/// Only names of the entered spans are output; span fields are not included.
+#[derive(Debug)]
+pub(super) struct LogfmtFormat {
+    timer: UtcTime<time::format_description::well_known::Rfc3339>,
+}
+
+impl LogfmtFormat {
+    pub(super) fn new() -> Self {
+        Self {
+            timer: UtcTime::rfc_3339(),
+        }
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for LogfmtFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        writer.write_str("ts=")?;
+        self.timer.format_time(&mut writer)?;
+        let level = match *metadata.level() {
+            Level::TRACE => "trace",
+            Level::DEBUG => "debug",
+            Level::INFO => "info",
+            Level::WARN => "warn",
+            Level::ERROR => "error",
+        };
+        write!(writer, " level={level} target=")?;
+        write_value(&mut writer, metadata.target())?;
+
+        if let Some(scope) = ctx.event_scope() {
+            let mut span_names = String::new();
+            for span in scope.from_root() {
+                if !span_names.is_empty() {
+                    span_names.push(':');
+                }
+                span_names.push_str(span.name());
+            }
+            writer.write_str(" span=")?;
+            write_value(&mut writer, &span_names)?;
+        }
+
+        let mut visitor = LogfmtVisitor {
+            writer: &mut writer,
+            result: Ok(()),
+        };
+        event.record(&mut visitor);
+        visitor.result?;
+        writeln!(writer)
+    }
+}
+
+struct LogfmtVisitor<'a, W> {
+    writer: &'a mut W,
+    result: fmt::Result,
+}
+
+impl<W: fmt::Write> LogfmtVisitor<'_, W> {
+    fn record_value(&mut self, field: &Field, value: &str) {
+        let name = field.name();
+        // Skip metadata fields added by `tracing-log` for events originating from `log` records.
+        if self.result.is_err() || name.starts_with("log.") {
+            return;
+        }
+        let key = if name == "message" { "msg" } else { name };
+        self.result = write!(self.writer, " {key}=").and_then(|()| write_value(self.writer, value));
+    }
+}
+
+impl<W: fmt::Write> Visit for LogfmtVisitor<'_, W> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_value(field, value);
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.record_value(field, &value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_value(field, &format!("{value:?}"));
+    }
+}
+
+/// Writes a value, quoting and escaping it if necessary.
+fn write_value(writer: &mut impl fmt::Write, value: &str) -> fmt::Result {
+    let needs_quoting = value.is_empty()
+        || value
+            .chars()
+            .any(|ch| ch == ' ' || ch == '=' || ch == '"' || ch == '\\' || ch.is_control());
+    if !needs_quoting {
+        return writer.write_str(value);
+    }
+
+    writer.write_char('"')?;
+    for ch in value.chars() {
+        match ch {
+            '"' => writer.write_str("\\\"")?,
+            '\\' => writer.write_str("\\\\")?,
+            '\n' => writer.write_str("\\n")?,
+            '\r' => writer.write_str("\\r")?,
+            '\t' => writer.write_str("\\t")?,
+            _ => writer.write_char(ch)?,
+        }
+    }
+    writer.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format_value(value: &str) -> String {
+        let mut buffer = String::new();
+        write_value(&mut buffer, value).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn quoting_values() {
+        assert_eq!(format_value("zksync_state_keeper"), "zksync_state_keeper");
+        assert_eq!(format_value("0x01"), "0x01");
+        assert_eq!(format_value(""), "\"\"");
+        assert_eq!(format_value("Sealed L1 batch"), "\"Sealed L1 batch\"");
+        assert_eq!(format_value("a=b"), "\"a=b\"");
+        assert_eq!(
+            format_value("error: \"test\"\nat C:\\"),
+            r#""error: \"test\"\nat C:\\""#
+        );
+    }
+}
@@ -1,9 +1,14 @@
-use std::{backtrace::Backtrace, str::FromStr};
+use std::{backtrace::Backtrace, fmt as std_fmt, str::FromStr};
 
+use anyhow::Context as _;
 use serde::Deserialize;
-use tracing_subscriber::{fmt, registry::LookupSpan, EnvFilter, Layer};
+use tracing_subscriber::{fmt, registry::LookupSpan, reload, EnvFilter, Layer, Registry};
+
+pub use self::sampling::{LogSampling, LogSamplingError};
 
 mod layer;
+mod logfmt;
+mod sampling;
 
 /// Specifies the format of the logs in stdout.
 #[derive(Debug, Clone, Copy, Default, Deserialize)]
@@ -12,6 +17,8 @@ pub enum LogFormat {
     #[default]
     Plain,
     Json,
+    /// [logfmt](https://brandur.org/logfmt) format, i.e. space-separated `key=value` pairs.
+    Logfmt,
 }
 
 impl FromStr for LogFormat {
@@ -21,6 +28,7 @@ impl FromStr for LogFormat {
         match s {
             "plain" => Ok(Self::Plain),
             "json" => Ok(Self::Json),
+            "logfmt" => Ok(Self::Logfmt),
             _ => Err(LogFormatError::InvalidFormat),
         }
     }
@@ -38,6 +46,7 @@ pub struct Logs {
     format: LogFormat,
     log_directives: Option<String>,
     disable_default_logs: bool,
+    sampling: Option<LogSampling>,
 }
 
 impl From<LogFormat> for Logs {
@@ -46,6 +55,7 @@ impl From<LogFormat> for Logs {
             format,
             log_directives: None,
             disable_default_logs: false,
+            sampling: None,
         }
     }
 }
@@ -56,6 +66,7 @@ impl Logs {
             format: format.parse()?,
             log_directives: None,
             disable_default_logs: false,
+            sampling: None,
         })
     }
 
@@ -72,17 +83,30 @@ impl Logs {
     ///
     /// [1]: https://docs.rs/tracing-subscriber/0.3.18/tracing_subscriber/filter/targets/struct.Targets.html#filtering-with-targets
     pub(super) fn build_filter(&self) -> EnvFilter {
-        let mut directives = if self.disable_default_logs {
-            "".to_string()
+        EnvFilter::new(self.directives())
+    }
+
+    fn directives(&self) -> String {
+        let directives = if let Some(log_directives) = &self.log_directives {
+            log_directives.clone()
         } else {
-            "zksync=info,".to_string()
+            std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default()
         };
-        if let Some(log_directives) = &self.log_directives {
-            directives.push_str(log_directives);
-        } else if let Ok(env_directives) = std::env::var(EnvFilter::DEFAULT_ENV) {
-            directives.push_str(&env_directives);
+        full_directives(self.disable_default_logs, &directives)
+    }
+
+    /// Builds a reloadable version of [the logs filter](Self::build_filter()) to be used as a global filter.
+    pub(super) fn build_reloadable_filter(
+        &self,
+    ) -> (reload::Layer<EnvFilter, Registry>, LogsReloadHandle) {
+        let initial_directives = self.directives();
+        let (filter, inner) = reload::Layer::new(EnvFilter::new(&initial_directives));
+        let handle = LogsReloadHandle {
+            inner,
+            initial_directives,
+            disable_default_logs: self.disable_default_logs,
         };
-        EnvFilter::new(directives)
+        (filter, handle)
     }
 
     pub fn with_log_directives(mut self, log_directives: Option<String>) -> Self {
@@ -95,6 +119,12 @@ impl Logs {
         self
     }
 
+    /// Sets rate limits for noisy log targets. Limits only apply to the stdout logs.
+    pub fn with_sampling(mut self, sampling: Option<LogSampling>) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
     pub fn install_panic_hook(&self) {
         // Check whether we need to change the default panic handler.
         // Note that this must happen before we initialize Sentry, since otherwise
@@ -113,6 +143,15 @@ impl Logs {
         S: tracing::Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
     {
         let filter = self.build_filter();
+        self.into_unfiltered_layer().with_filter(filter)
+    }
+
+    /// Same as [`Self::into_layer()`], but without applying log directives. Useful if directives are applied
+    /// by a global filter.
+    pub(crate) fn into_unfiltered_layer<S>(self) -> impl Layer<S>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
+    {
         let layer = match self.format {
             LogFormat::Plain => layer::LogsLayer::Plain(fmt::Layer::new()),
             LogFormat::Json => {
@@ -124,8 +163,77 @@ impl Logs {
                     .json();
                 layer::LogsLayer::Json(json_layer)
             }
+            LogFormat::Logfmt => {
+                let logfmt_layer = fmt::Layer::default().event_format(logfmt::LogfmtFormat::new());
+                layer::LogsLayer::Logfmt(logfmt_layer)
+            }
         };
-        layer.with_filter(filter)
+        layer.with_filter(self.sampling)
+    }
+}
+
+fn full_directives(disable_default_logs: bool, directives: &str) -> String {
+    if disable_default_logs {
+        directives.to_owned()
+    } else {
+        format!("zksync=info,{directives}")
+    }
+}
+
+/// Handle allowing to change log directives at runtime. Obtained via
+/// [`ObservabilityGuard::logs_reload_handle()`](crate::ObservabilityGuard::logs_reload_handle()).
+///
+/// Directives are processed in the same way as on startup; e.g., the default `zksync=info` directive is prepended
+/// to them unless default logs were disabled.
+#[derive(Clone)]
+pub struct LogsReloadHandle {
+    inner: reload::Handle<EnvFilter, Registry>,
+    initial_directives: String,
+    disable_default_logs: bool,
+}
+
+impl std_fmt::Debug for LogsReloadHandle {
+    fn fmt(&self, formatter: &mut std_fmt::Formatter<'_>) -> std_fmt::Result {
+        formatter
+            .debug_struct("LogsReloadHandle")
+            .field("initial_directives", &self.initial_directives)
+            .field("disable_default_logs", &self.disable_default_logs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LogsReloadHandle {
+    /// Returns currently applied directives.
+    pub fn directives(&self) -> String {
+        self.inner
+            .with_current(EnvFilter::to_string)
+            .unwrap_or_default()
+    }
+
+    /// Sets new directives.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the directives cannot be parsed. In this case, the current directives are retained.
+    pub fn set_directives(&self, directives: &str) -> anyhow::Result<()> {
+        let directives = full_directives(self.disable_default_logs, directives);
+        let filter = EnvFilter::builder()
+            .parse(&directives)
+            .context("invalid log directives")?;
+        self.reload(filter)
+    }
+
+    /// Resets directives to the ones used on startup.
+    pub fn reset(&self) -> anyhow::Result<()> {
+        self.reload(EnvFilter::new(&self.initial_directives))
+    }
+
+    fn reload(&self, filter: EnvFilter) -> anyhow::Result<()> {
+        self.inner
+            .reload(filter)
+            .context("failed reloading log filter")?;
+        tracing::info!("Updated log directives to `{}`", self.directives());
+        Ok(())
     }
 }
 
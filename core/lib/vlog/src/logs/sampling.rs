@@ -0,0 +1,187 @@
+//! Rate limiting for noisy log targets.
+
+use std::{
+    num::NonZeroU32,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tracing::{Event, Level, Metadata};
+use tracing_subscriber::layer::{Context, Filter};
+use vise::{Counter, LabeledFamily, Metrics};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "vlog")]
+struct SamplingMetrics {
+    /// Number of log events dropped because of sampling, labeled by the sampling rule target.
+    #[metrics(labels = ["target"])]
+    dropped_events: LabeledFamily<String, Counter>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<SamplingMetrics> = vise::Global::new();
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum LogSamplingError {
+    #[error("invalid log sampling rule `{0}`; expected `target=max_events_per_second`")]
+    InvalidRule(String),
+}
+
+#[derive(Debug)]
+struct SamplingWindow {
+    started_at: Instant,
+    event_count: u32,
+}
+
+#[derive(Debug)]
+struct SamplingRule {
+    target: String,
+    max_events_per_second: NonZeroU32,
+    window: Mutex<SamplingWindow>,
+}
+
+impl SamplingRule {
+    const WINDOW_DURATION: Duration = Duration::from_secs(1);
+
+    fn new(target: String, max_events_per_second: NonZeroU32) -> Self {
+        Self {
+            target,
+            max_events_per_second,
+            window: Mutex::new(SamplingWindow {
+                started_at: Instant::now(),
+                event_count: 0,
+            }),
+        }
+    }
+
+    fn matches(&self, target: &str) -> bool {
+        target
+            .strip_prefix(&self.target)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    }
+
+    fn try_acquire(&self, now: Instant) -> bool {
+        let mut window = self.window.lock().expect("log sampling window is poisoned");
+        if now.duration_since(window.started_at) >= Self::WINDOW_DURATION {
+            window.started_at = now;
+            window.event_count = 0;
+        }
+        if window.event_count < self.max_events_per_second.get() {
+            window.event_count += 1;
+            true
+        } else {
+            drop(window);
+            METRICS.dropped_events[&self.target].inc();
+            false
+        }
+    }
+}
+
+/// Rate limits for log events from noisy targets, e.g. `zksync_state_keeper=100,zksync_eth_sender=50`.
+/// Each limit is specified as the maximum number of events per second for a target and its submodules.
+/// If multiple limits match an event target, the most specific one is applied.
+///
+/// Warnings and errors are never dropped. The number of dropped events is reported as a metric.
+#[derive(Debug)]
+pub struct LogSampling {
+    rules: Vec<SamplingRule>,
+}
+
+impl LogSampling {
+    pub fn new(limits: impl IntoIterator<Item = (String, NonZeroU32)>) -> Self {
+        let mut rules: Vec<_> = limits
+            .into_iter()
+            .map(|(target, limit)| SamplingRule::new(target, limit))
+            .collect();
+        rules.sort_unstable_by_key(|rule| std::cmp::Reverse(rule.target.len()));
+        Self { rules }
+    }
+
+    fn find_rule(&self, target: &str) -> Option<&SamplingRule> {
+        self.rules.iter().find(|rule| rule.matches(target))
+    }
+}
+
+impl FromStr for LogSampling {
+    type Err = LogSamplingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let limits = s
+            .split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .map(|rule| {
+                let (target, limit) = rule
+                    .split_once('=')
+                    .ok_or_else(|| LogSamplingError::InvalidRule(rule.to_owned()))?;
+                let limit = limit
+                    .trim()
+                    .parse()
+                    .map_err(|_| LogSamplingError::InvalidRule(rule.to_owned()))?;
+                Ok((target.trim().to_owned(), limit))
+            });
+        Ok(Self::new(limits.collect::<Result<Vec<_>, _>>()?))
+    }
+}
+
+impl<S> Filter<S> for LogSampling {
+    fn enabled(&self, _metadata: &Metadata<'_>, _cx: &Context<'_, S>) -> bool {
+        true
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, _cx: &Context<'_, S>) -> bool {
+        let metadata = event.metadata();
+        if *metadata.level() <= Level::WARN {
+            return true;
+        }
+        match self.find_rule(metadata.target()) {
+            Some(rule) => rule.try_acquire(Instant::now()),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_sampling_rules() {
+        let sampling: LogSampling = "zksync_state_keeper=100, zksync_state_keeper::io=10,"
+            .parse()
+            .unwrap();
+        let rule = sampling
+            .find_rule("zksync_state_keeper::io::mempool")
+            .unwrap();
+        assert_eq!(rule.max_events_per_second.get(), 10);
+        let rule = sampling.find_rule("zksync_state_keeper").unwrap();
+        assert_eq!(rule.max_events_per_second.get(), 100);
+        assert!(sampling.find_rule("zksync_state_keeper_2").is_none());
+        assert!(sampling.find_rule("zksync_eth_sender").is_none());
+
+        for invalid in [
+            "zksync_state_keeper",
+            "zksync_state_keeper=0",
+            "target=many",
+        ] {
+            invalid.parse::<LogSampling>().unwrap_err();
+        }
+    }
+
+    #[test]
+    fn limiting_events() {
+        let rule = SamplingRule::new("test".to_owned(), NonZeroU32::new(2).unwrap());
+        let start = Instant::now();
+        assert!(rule.try_acquire(start));
+        assert!(rule.try_acquire(start + Duration::from_millis(100)));
+        assert!(!rule.try_acquire(start + Duration::from_millis(200)));
+        assert!(!rule.try_acquire(start + Duration::from_millis(900)));
+
+        let next_window_start = start + SamplingRule::WINDOW_DURATION;
+        assert!(rule.try_acquire(next_window_start));
+        assert!(rule.try_acquire(next_window_start + Duration::from_millis(500)));
+        assert!(!rule.try_acquire(next_window_start + Duration::from_millis(999)));
+    }
+}
@@ -201,7 +201,8 @@ impl Default for TransactionExecutionMetrics {
 }
 
 /// Metrics for a (part of) VM execution.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct VmExecutionMetrics {
     pub gas_used: usize,
     pub published_bytecode_bytes: usize,
@@ -142,6 +142,11 @@ impl<S: ReadStorage, Vm: VmTrackingContracts> DumpingVm<S, Vm> {
         self.last_block_mut().txs.push(tx);
     }
 
+    /// Returns the number of transactions recorded in the batch so far.
+    pub fn tx_count(&self) -> usize {
+        self.l2_blocks.iter().map(|block| block.txs.len()).sum()
+    }
+
     pub fn dump_state(&self) -> VmDump {
         VmDump {
             l1_batch_env: self.l1_batch_env.clone(),
@@ -380,6 +380,11 @@ where
     Main: VmTrackingContracts,
     Shadow: VmInterface,
 {
+    /// Returns the 0-based index of the last transaction in the batch. Should only be called after a transaction was pushed.
+    fn last_tx_index(&self) -> Option<usize> {
+        self.main.tx_count().checked_sub(1)
+    }
+
     /// Mutable ref is not necessary, but it automatically drops potential borrows.
     fn report(&mut self, err: DivergenceErrors) {
         self.report_shared(err);
@@ -528,7 +533,8 @@ where
             );
             if let Err(err) = errors.into_result() {
                 let ctx = format!("pushing transaction {tx_repr}");
-                self.report(err.context(ctx));
+                let tx_index = self.last_tx_index();
+                self.report(err.context(ctx).with_tx_index(tx_index));
             }
         }
         main_result
@@ -545,7 +551,11 @@ where
             let errors = main_result.check_divergence(&shadow_result);
             if let Err(err) = errors.into_result() {
                 let ctx = format!("executing VM with mode {execution_mode:?}");
-                self.report(err.context(ctx));
+                let mut err = err.context(ctx);
+                if matches!(execution_mode, InspectExecutionMode::OneTx) {
+                    err = err.with_tx_index(self.last_tx_index());
+                }
+                self.report(err);
             }
         }
         main_result
@@ -588,7 +598,8 @@ where
                 let ctx = format!(
                     "inspecting transaction {tx_repr}, with_compression={with_compression:?}"
                 );
-                self.report(err.context(ctx));
+                let tx_index = self.last_tx_index();
+                self.report(err.context(ctx).with_tx_index(tx_index));
             }
         }
         (main_bytecodes_result, main_tx_result)
@@ -607,10 +618,13 @@ where
     }
 }
 
+/// Divergences between the main and shadow VM detected during a single VM operation.
 #[derive(Debug)]
 pub struct DivergenceErrors {
     divergences: Vec<String>,
+    mismatched_fields: Vec<String>,
     context: Option<String>,
+    tx_index_in_l1_batch: Option<usize>,
 }
 
 impl fmt::Display for DivergenceErrors {
@@ -635,13 +649,27 @@ impl DivergenceErrors {
     fn new() -> Self {
         Self {
             divergences: vec![],
+            mismatched_fields: vec![],
             context: None,
+            tx_index_in_l1_batch: None,
         }
     }
 
     /// Extends this instance from another set of errors.
     pub fn extend(&mut self, from: Self) {
         self.divergences.extend(from.divergences);
+        self.mismatched_fields.extend(from.mismatched_fields);
+    }
+
+    /// Returns names of the mismatched fields (e.g., `logs.events`) in the order they were checked.
+    pub fn mismatched_fields(&self) -> &[String] {
+        &self.mismatched_fields
+    }
+
+    /// Returns the 0-based index of the diverging transaction in the L1 batch, if the divergence
+    /// is attributed to a specific transaction.
+    pub fn tx_index_in_l1_batch(&self) -> Option<usize> {
+        self.tx_index_in_l1_batch
     }
 
     fn context(mut self, context: String) -> Self {
@@ -649,11 +677,17 @@ impl DivergenceErrors {
         self
     }
 
+    fn with_tx_index(mut self, tx_index: Option<usize>) -> Self {
+        self.tx_index_in_l1_batch = tx_index;
+        self
+    }
+
     fn check_match<T: fmt::Debug + PartialEq>(&mut self, context: &str, main: &T, shadow: &T) {
         if main != shadow {
             let comparison = pretty_assertions::Comparison::new(main, shadow);
             let err = format!("`{context}` mismatch: {comparison}");
             self.divergences.push(err);
+            self.mismatched_fields.push(context.to_owned());
         }
     }
 
@@ -0,0 +1,67 @@
+#[cfg_attr(not(feature = "server"), allow(unused_imports))]
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use zksync_types::{
+    api::{NodeStatus, VmDivergence},
+    L1BatchNumber,
+};
+
+use crate::client::{ForWeb3Network, L2};
+
+/// Operator-only RPCs. These RPCs are served on a separate port and require authentication.
+#[cfg_attr(
+    feature = "server",
+    rpc(server, client, namespace = "admin", client_bounds(Self: ForWeb3Network<Net = L2>))
+)]
+#[cfg_attr(
+    not(feature = "server"),
+    rpc(client, namespace = "admin", client_bounds(Self: ForWeb3Network<Net = L2>))
+)]
+pub trait AdminNamespace {
+    /// Pauses executing transactions in the state keeper. Returns `false` if the state keeper is already paused.
+    #[method(name = "pauseStateKeeper")]
+    async fn pause_state_keeper(&self) -> RpcResult<bool>;
+
+    /// Resumes executing transactions in the state keeper. Returns `false` if the state keeper isn't paused.
+    #[method(name = "resumeStateKeeper")]
+    async fn resume_state_keeper(&self) -> RpcResult<bool>;
+
+    /// Requests the state keeper to seal the current L1 batch. The request is ignored if the batch is empty.
+    #[method(name = "sealL1Batch")]
+    async fn seal_l1_batch(&self) -> RpcResult<()>;
+
+    /// Removes failed L1 transactions sent by the node and all transactions following them,
+    /// so that they are resent by the ETH sender.
+    ///
+    /// **Important.** This method doesn't synchronize with the ETH sender. It must only be called when ETH sender components
+    /// (`eth_tx_aggregator` and `eth_tx_manager`) are not running on any node connected to the same database. Otherwise,
+    /// transactions created or sent concurrently may be removed while the ETH sender still tracks them, which can lead
+    /// to nonce gaps or duplicate L1 transactions.
+    #[method(name = "clearFailedL1Transactions")]
+    async fn clear_failed_l1_transactions(&self) -> RpcResult<()>;
+
+    /// Returns the current log filter directives.
+    #[method(name = "getLogFilter")]
+    async fn get_log_filter(&self) -> RpcResult<String>;
+
+    /// Replaces log filter directives (e.g., `zksync_state_keeper=debug,info`).
+    #[method(name = "setLogFilter")]
+    async fn set_log_filter(&self, directives: String) -> RpcResult<()>;
+
+    /// Reloads the node config from its source and applies changes in the reloadable part of the config.
+    /// Returns `false` if the reloadable part hasn't changed. Changes in non-reloadable fields are rejected
+    /// with an error; in this case, no part of the new config is applied.
+    #[method(name = "reloadConfig")]
+    async fn reload_config(&self) -> RpcResult<bool>;
+
+    #[method(name = "getNodeStatus")]
+    async fn get_node_status(&self) -> RpcResult<NodeStatus>;
+
+    /// Returns VM divergences recorded by the VM playground for L1 batches starting from `from_l1_batch`.
+    #[method(name = "getVmDivergences")]
+    async fn get_vm_divergences(
+        &self,
+        from_l1_batch: L1BatchNumber,
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<VmDivergence>>;
+}
@@ -1,15 +1,17 @@
 pub use self::{
-    debug::DebugNamespaceClient, en::EnNamespaceClient, eth::EthNamespaceClient,
-    net::NetNamespaceClient, snapshots::SnapshotsNamespaceClient,
+    admin::AdminNamespaceClient, debug::DebugNamespaceClient, en::EnNamespaceClient,
+    eth::EthNamespaceClient, net::NetNamespaceClient, snapshots::SnapshotsNamespaceClient,
     unstable::UnstableNamespaceClient, web3::Web3NamespaceClient, zks::ZksNamespaceClient,
 };
 #[cfg(feature = "server")]
 pub use self::{
-    debug::DebugNamespaceServer, en::EnNamespaceServer, eth::EthNamespaceServer,
-    eth::EthPubSubServer, net::NetNamespaceServer, snapshots::SnapshotsNamespaceServer,
-    unstable::UnstableNamespaceServer, web3::Web3NamespaceServer, zks::ZksNamespaceServer,
+    admin::AdminNamespaceServer, debug::DebugNamespaceServer, en::EnNamespaceServer,
+    eth::EthNamespaceServer, eth::EthPubSubServer, net::NetNamespaceServer,
+    snapshots::SnapshotsNamespaceServer, unstable::UnstableNamespaceServer,
+    web3::Web3NamespaceServer, zks::ZksNamespaceServer,
 };
 
+mod admin;
 mod debug;
 mod en;
 mod eth;
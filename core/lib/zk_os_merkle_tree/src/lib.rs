@@ -7,23 +7,34 @@ use zksync_basic_types::H256;
 pub use zksync_crypto_primitives::hasher::blake2::Blake2Hasher;
 
 pub use self::{
+    consistency::ConsistencyError,
     errors::DeserializeError,
     hasher::{BatchTreeProof, HashTree, TreeOperation},
+    metrics::PruningStats,
+    pruning::{MerkleTreePruner, MerkleTreePrunerHandle, PrunerStoppedError},
     reader::MerkleTreeReader,
-    storage::{Database, MerkleTreeColumnFamily, PatchSet, Patched, RocksDBWrapper},
+    recovery::MerkleTreeRecovery,
+    repair::RepairStats,
+    storage::{
+        Database, MerkleTreeColumnFamily, PatchSet, Patched, PruneDatabase, PrunePatchSet,
+        RecoveryDatabase, RocksDBWrapper,
+    },
     types::{BatchOutput, TreeEntry},
 };
 use crate::{
     metrics::{BatchProofStage, LoadStage, MerkleTreeInfo, METRICS},
     storage::{AsEntry, TreeUpdate, WorkingPatchSet},
-    types::{Leaf, MAX_TREE_DEPTH},
+    types::{InternalNode, Leaf, Node, NodeKey, MAX_TREE_DEPTH},
 };
 
 mod consistency;
 mod errors;
 mod hasher;
 mod metrics;
+mod pruning;
 mod reader;
+mod recovery;
+mod repair;
 mod storage;
 #[cfg(test)]
 mod tests;
@@ -129,7 +140,7 @@ impl<DB: Database, P: TreeParams> MerkleTree<DB, P> {
     pub fn with_hasher(db: DB, hasher: P::Hasher) -> anyhow::Result<Self> {
         let maybe_manifest = db.try_manifest().context("failed reading tree manifest")?;
         if let Some(manifest) = &maybe_manifest {
-            manifest.tags.ensure_consistency::<P>(&hasher)?;
+            manifest.tags.ensure_consistency::<P>(&hasher, false)?;
         }
 
         let info = MerkleTreeInfo {
@@ -196,6 +207,59 @@ impl<DB: Database, P: TreeParams> MerkleTree<DB, P> {
         Ok(patch.create_batch_proof(&self.hasher, vec![], update.take_read_operations()))
     }
 
+    /// Visits all entries in the tree at the specified `version` together with their leaf indices,
+    /// in the increasing leaf index order. Min / max guards are not visited.
+    ///
+    /// # Errors
+    ///
+    /// - Returns an error if the version doesn't exist.
+    /// - Proxies database errors and errors returned by `visitor`. In the latter case, traversal is stopped immediately.
+    pub fn for_each_entry(
+        &self,
+        version: u64,
+        mut visitor: impl FnMut(u64, TreeEntry) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let root = self
+            .db
+            .try_root(version)?
+            .with_context(|| format!("tree version {version} does not exist"))?;
+        self.visit_internal_node(&root.root_node, NodeKey::root(version), &mut visitor)
+    }
+
+    fn visit_internal_node(
+        &self,
+        node: &InternalNode,
+        key: NodeKey,
+        visitor: &mut dyn FnMut(u64, TreeEntry) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let child_keys: Vec<_> = node
+            .children
+            .iter()
+            .enumerate()
+            .map(|(i, child_ref)| NodeKey {
+                version: child_ref.version,
+                nibble_count: key.nibble_count + 1,
+                index_on_level: i as u64 + (key.index_on_level << P::INTERNAL_NODE_DEPTH),
+            })
+            .collect();
+        let children = self.db.try_nodes(&child_keys)?;
+        for (child_key, child) in child_keys.into_iter().zip(children) {
+            match child {
+                Node::Internal(node) => self.visit_internal_node(&node, child_key, visitor)?,
+                // Leaves with indices 0 and 1 are the min / max guards
+                Node::Leaf(leaf) if child_key.index_on_level >= 2 => {
+                    let entry = TreeEntry {
+                        key: leaf.key,
+                        value: leaf.value,
+                    };
+                    visitor(child_key.index_on_level, entry)?;
+                }
+                Node::Leaf(_) => { /* skip guards */ }
+            }
+        }
+        Ok(())
+    }
+
     /// Extends this tree by creating its new version.
     ///
     /// All keys in the provided entries must be distinct.
@@ -327,8 +391,8 @@ impl<DB: Database, P: TreeParams> MerkleTree<DB, P> {
         let mut manifest = self.db.try_manifest()?.unwrap_or_default();
         let current_version_count = manifest.version_count;
         if current_version_count > retained_version_count {
-            // TODO: It is necessary to remove "future" stale keys since otherwise they may be used in future pruning and lead
-            //   to non-obsolete tree nodes getting removed.
+            // The database is responsible for removing "future" stale keys since otherwise they may be used in future pruning
+            // and lead to non-obsolete tree nodes getting removed.
             manifest.version_count = retained_version_count;
             self.db.truncate(manifest, ..current_version_count)?;
         }
@@ -336,6 +400,19 @@ impl<DB: Database, P: TreeParams> MerkleTree<DB, P> {
     }
 }
 
+impl<DB: PruneDatabase, P: TreeParams> MerkleTree<DB, P> {
+    /// Returns the first retained version of the tree, or `None` if the tree is empty.
+    pub fn first_retained_version(&self) -> anyhow::Result<Option<u64>> {
+        Ok(match self.db.min_stale_key_version() {
+            // Min stale key version is next after the first retained version since at least
+            // the root is updated on each version.
+            Some(version) => version.checked_sub(1),
+            // No stale keys means all past versions of the tree have been pruned
+            None => self.latest_version()?,
+        })
+    }
+}
+
 impl<DB: Database, P: TreeParams> MerkleTree<Patched<DB>, P> {
     /// Flushes changes to the underlying storage.
     pub fn flush(&mut self) -> anyhow::Result<()> {
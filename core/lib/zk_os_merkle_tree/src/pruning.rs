@@ -0,0 +1,457 @@
+//! Tree pruning logic.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc, Weak,
+    },
+    time::Duration,
+};
+
+use anyhow::Context as _;
+
+use crate::{
+    metrics::{PruningStats, PRUNING_METRICS},
+    storage::{PruneDatabase, PrunePatchSet},
+};
+
+/// Error returned by [`MerkleTreePrunerHandle::set_target_retained_version()`].
+#[derive(Debug)]
+pub struct PrunerStoppedError(());
+
+impl fmt::Display for PrunerStoppedError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("Merkle tree pruner stopped")
+    }
+}
+
+/// Handle for a [`MerkleTreePruner`] allowing to abort its operation.
+///
+/// The pruner is aborted once the handle is dropped.
+#[must_use = "Pruner is aborted once handle is dropped"]
+#[derive(Debug)]
+pub struct MerkleTreePrunerHandle {
+    _aborted_sender: mpsc::Sender<()>,
+    target_retained_version: Weak<AtomicU64>,
+}
+
+impl MerkleTreePrunerHandle {
+    /// Sets the version of the tree the pruner should attempt to prune to. Calls should provide
+    /// monotonically increasing versions; call with a lesser version will have no effect.
+    ///
+    /// Returns the previously set target retained version.
+    ///
+    /// # Errors
+    ///
+    /// If the pruner has stopped (e.g., due to a panic), this method will return an error.
+    pub fn set_target_retained_version(&self, new_version: u64) -> Result<u64, PrunerStoppedError> {
+        if let Some(version) = self.target_retained_version.upgrade() {
+            Ok(version.fetch_max(new_version, Ordering::Relaxed))
+        } else {
+            Err(PrunerStoppedError(()))
+        }
+    }
+}
+
+/// Component responsible for Merkle tree pruning, i.e. removing nodes not referenced by new versions
+/// of the tree.
+///
+/// A pruner should be instantiated using a [`Clone`] of the tree database, possibly
+/// configured and then [`run()`](Self::run()) on its own thread. [`MerkleTreePrunerHandle`] provides
+/// a way to gracefully shut down the pruner.
+///
+/// # Implementation details
+///
+/// Each tree update records keys of the nodes it replaces (including the root of the previous version)
+/// as stale; in RocksDB, stale keys are stored in a separate column family and are ordered by the version
+/// in which they became stale. A node that became stale in version `v` is unreachable from roots of versions `v..`,
+/// so the pruner can remove it once the target retained version is at least `v`.
+pub struct MerkleTreePruner<DB> {
+    db: DB,
+    target_pruned_key_count: usize,
+    poll_interval: Duration,
+    aborted_receiver: mpsc::Receiver<()>,
+    target_retained_version: Arc<AtomicU64>,
+}
+
+impl<DB> fmt::Debug for MerkleTreePruner<DB> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("MerkleTreePruner")
+            .field("target_pruned_key_count", &self.target_pruned_key_count)
+            .field("poll_interval", &self.poll_interval)
+            .field("target_retained_version", &self.target_retained_version)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<DB: PruneDatabase> MerkleTreePruner<DB> {
+    /// Creates a pruner with the specified database.
+    ///
+    /// # Return value
+    ///
+    /// Returns the created pruner and a handle to it. *The pruner will be aborted when its handle is dropped.*
+    pub fn new(db: DB) -> (Self, MerkleTreePrunerHandle) {
+        let (aborted_sender, aborted_receiver) = mpsc::channel();
+        let target_retained_version = Arc::new(AtomicU64::new(0));
+        let handle = MerkleTreePrunerHandle {
+            _aborted_sender: aborted_sender,
+            target_retained_version: Arc::downgrade(&target_retained_version),
+        };
+        let this = Self {
+            db,
+            target_pruned_key_count: 500_000,
+            poll_interval: Duration::from_secs(60),
+            aborted_receiver,
+            target_retained_version,
+        };
+        (this, handle)
+    }
+
+    /// Sets the target number of stale keys pruned on a single iteration. This limits the size of
+    /// a produced RocksDB `WriteBatch` and the RAM consumption of the pruner.
+    ///
+    /// The default value is 500k.
+    pub fn set_target_pruned_key_count(&mut self, count: usize) {
+        self.target_pruned_key_count = count;
+    }
+
+    /// Sets the sleep duration when the pruner cannot progress. This time should be enough
+    /// for the tree to produce enough stale keys.
+    ///
+    /// The default value is 60 seconds.
+    pub fn set_poll_interval(&mut self, poll_interval: Duration) {
+        self.poll_interval = poll_interval;
+    }
+
+    /// Returns max version number that can be safely pruned, so that there is at least one version present after pruning.
+    #[doc(hidden)] // Used in tests; logically private
+    pub fn last_prunable_version(&self) -> anyhow::Result<Option<u64>> {
+        let manifest = self.db.try_manifest()?;
+        Ok(manifest.and_then(|manifest| manifest.version_count.checked_sub(1)))
+    }
+
+    #[doc(hidden)] // Used in tests; logically private
+    #[allow(clippy::range_plus_one)] // exclusive range is required by `PrunePatchSet` constructor
+    pub fn prune_up_to(
+        &mut self,
+        target_retained_version: u64,
+    ) -> anyhow::Result<Option<PruningStats>> {
+        let Some(min_stale_key_version) = self.db.min_stale_key_version() else {
+            return Ok(None);
+        };
+
+        // We must retain at least one tree version.
+        let last_prunable_version = self
+            .last_prunable_version()
+            .context("failed getting last prunable version")?;
+        let Some(last_prunable_version) = last_prunable_version else {
+            tracing::debug!("Nothing to prune; skipping");
+            return Ok(None);
+        };
+        let target_retained_version = last_prunable_version.min(target_retained_version);
+        let stale_key_new_versions = min_stale_key_version..=target_retained_version;
+        if stale_key_new_versions.is_empty() {
+            tracing::debug!(
+                "No Merkle tree versions can be pruned; min stale key version is {min_stale_key_version}, \
+                 target retained version is {target_retained_version}"
+            );
+            return Ok(None);
+        }
+        tracing::info!("Collecting stale keys with new versions in {stale_key_new_versions:?}");
+
+        let load_stale_keys_latency = PRUNING_METRICS.load_stale_keys_latency.start();
+        let mut pruned_keys = vec![];
+        let mut max_stale_key_version = min_stale_key_version;
+        for version in stale_key_new_versions {
+            max_stale_key_version = version;
+            pruned_keys.extend_from_slice(&self.db.stale_keys(version));
+            if pruned_keys.len() >= self.target_pruned_key_count {
+                break;
+            }
+        }
+        let load_stale_keys_latency = load_stale_keys_latency.observe();
+
+        if pruned_keys.is_empty() {
+            tracing::debug!("No stale keys to remove; skipping");
+            return Ok(None);
+        }
+        let deleted_stale_key_versions = min_stale_key_version..(max_stale_key_version + 1);
+        tracing::info!(
+            "Collected {} stale keys with new versions in {deleted_stale_key_versions:?} in {load_stale_keys_latency:?}",
+            pruned_keys.len()
+        );
+
+        let stats = PruningStats {
+            target_retained_version,
+            pruned_key_count: pruned_keys.len(),
+            deleted_stale_key_versions: deleted_stale_key_versions.clone(),
+        };
+        let patch = PrunePatchSet::new(pruned_keys, deleted_stale_key_versions);
+        let apply_patch_latency = PRUNING_METRICS.apply_patch_latency.start();
+        self.db.prune(patch)?;
+        let apply_patch_latency = apply_patch_latency.observe();
+        tracing::info!("Pruned stale keys in {apply_patch_latency:?}: {stats:?}");
+        Ok(Some(stats))
+    }
+
+    fn wait_for_abort(&mut self, timeout: Duration) -> bool {
+        match self.aborted_receiver.recv_timeout(timeout) {
+            Ok(()) | Err(mpsc::RecvTimeoutError::Disconnected) => true,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                // The pruner handle is alive and wasn't used to abort the pruner.
+                false
+            }
+        }
+    }
+
+    /// Runs this pruner indefinitely until it is aborted, or a database error occurs.
+    ///
+    /// # Errors
+    ///
+    /// Propagates database I/O errors.
+    pub fn run(mut self) -> anyhow::Result<()> {
+        tracing::info!("Started Merkle tree pruner {self:?}");
+
+        let mut wait_interval = Duration::ZERO;
+        while !self.wait_for_abort(wait_interval) {
+            let retained_version = self.target_retained_version.load(Ordering::Relaxed);
+            wait_interval = if let Some(stats) = self.prune_up_to(retained_version)? {
+                tracing::debug!(
+                    "Performed pruning for target retained version {retained_version}: {stats:?}"
+                );
+                stats.report();
+                if stats.has_more_work() {
+                    // Continue pruning right away instead of waiting for abort.
+                    Duration::ZERO
+                } else {
+                    self.poll_interval
+                }
+            } else {
+                tracing::debug!(
+                    "Pruning was not performed; waiting {:?}",
+                    self.poll_interval
+                );
+                self.poll_interval
+            };
+        }
+        tracing::info!("Stop signal received, tree pruning is shut down");
+        Ok(())
+    }
+}
+
+impl PruningStats {
+    fn has_more_work(&self) -> bool {
+        self.target_retained_version + 1 > self.deleted_stale_key_versions.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Instant};
+
+    use tempfile::TempDir;
+    use zksync_basic_types::H256;
+
+    use super::*;
+    use crate::{storage::PatchSet, Database, MerkleTree, RocksDBWrapper, TreeEntry};
+
+    fn generate_entries(indices: impl Iterator<Item = u64>) -> Vec<TreeEntry> {
+        indices
+            .map(|i| TreeEntry {
+                key: H256::from_low_u64_be(i + 1),
+                value: H256::from_low_u64_be(i),
+            })
+            .collect()
+    }
+
+    fn create_db() -> PatchSet {
+        let mut db = PatchSet::default();
+        for i in 0..5 {
+            MerkleTree::new(&mut db)
+                .unwrap()
+                .extend(&generate_entries(i..i + 1))
+                .unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn pruner_basics() {
+        let mut db = create_db();
+        assert_eq!(
+            MerkleTree::new(&mut db)
+                .unwrap()
+                .first_retained_version()
+                .unwrap(),
+            Some(0)
+        );
+
+        let (mut pruner, _handle) = MerkleTreePruner::new(&mut db);
+        let last_prunable_version = pruner.last_prunable_version().unwrap().unwrap();
+        let stats = pruner
+            .prune_up_to(last_prunable_version)
+            .unwrap()
+            .expect("tree was not pruned");
+        assert!(stats.pruned_key_count > 0);
+        assert_eq!(stats.deleted_stale_key_versions, 1..5);
+        assert_eq!(stats.target_retained_version, 4);
+        assert!(!stats.has_more_work());
+
+        for version in 0..4 {
+            assert!(db.try_root(version).unwrap().is_none());
+        }
+        assert!(db.try_root(4).unwrap().is_some());
+
+        let tree = MerkleTree::new(&mut db).unwrap();
+        assert_eq!(tree.first_retained_version().unwrap(), Some(4));
+        tree.verify_consistency(4).unwrap();
+    }
+
+    #[test]
+    fn pruner_with_intermediate_commits() {
+        let mut db = create_db();
+        let (mut pruner, _handle) = MerkleTreePruner::new(&mut db);
+        pruner.set_target_pruned_key_count(1);
+
+        for i in 1..5 {
+            let last_prunable_version = pruner.last_prunable_version().unwrap().unwrap();
+            let stats = pruner
+                .prune_up_to(last_prunable_version)
+                .unwrap()
+                .expect("tree was not pruned");
+            assert!(stats.pruned_key_count > 0);
+            assert_eq!(stats.deleted_stale_key_versions, i..(i + 1));
+            assert_eq!(stats.target_retained_version, 4);
+            assert_eq!(stats.has_more_work(), i != 4);
+        }
+    }
+
+    #[test]
+    fn pruner_is_aborted_immediately_when_requested() {
+        let (mut pruner, pruner_handle) = MerkleTreePruner::new(PatchSet::default());
+        pruner.set_poll_interval(Duration::from_secs(30));
+        let join_handle = thread::spawn(|| pruner.run());
+
+        drop(pruner_handle);
+        let start = Instant::now();
+        join_handle.join().unwrap().unwrap();
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    fn test_tree_is_consistent_after_pruning(past_versions_to_keep: u64) {
+        let temp_dir = TempDir::new().unwrap();
+        let mut db = RocksDBWrapper::new(temp_dir.path()).unwrap();
+        let mut tree = MerkleTree::new(&mut db).unwrap();
+        let entries = generate_entries(0..100);
+        for chunk in entries.chunks(20) {
+            tree.extend(chunk).unwrap();
+        }
+        // Overwrite some of the entries so that there are stale leaves.
+        let updated_entries: Vec<_> = entries
+            .iter()
+            .step_by(3)
+            .map(|entry| TreeEntry {
+                value: H256::repeat_byte(0xaa),
+                ..*entry
+            })
+            .collect();
+        tree.extend(&updated_entries).unwrap();
+        let latest_version = tree.latest_version().unwrap().unwrap();
+
+        let node_count_before_pruning = db.node_count();
+
+        let (mut pruner, _handle) = MerkleTreePruner::new(&mut db);
+        let last_prunable_version = pruner.last_prunable_version().unwrap().unwrap();
+        let stats = pruner
+            .prune_up_to(last_prunable_version - past_versions_to_keep)
+            .unwrap()
+            .expect("tree was not pruned");
+        assert!(stats.pruned_key_count > 0);
+        // Each stale node is removed exactly once.
+        assert_eq!(
+            db.node_count(),
+            node_count_before_pruning - stats.pruned_key_count
+        );
+        let first_retained_version = latest_version - past_versions_to_keep;
+        assert_eq!(stats.target_retained_version, first_retained_version);
+        assert_eq!(
+            stats.deleted_stale_key_versions,
+            1..(first_retained_version + 1)
+        );
+
+        let tree = MerkleTree::new(&mut db).unwrap();
+        assert_eq!(
+            tree.first_retained_version().unwrap(),
+            Some(first_retained_version)
+        );
+        for version in 0..first_retained_version {
+            assert_eq!(tree.root_hash(version).unwrap(), None);
+        }
+        for version in first_retained_version..=latest_version {
+            tree.verify_consistency(version).unwrap();
+        }
+
+        // Nodes that are stale for the retained versions must still be present; they are removed once
+        // the retained versions are pruned.
+        let retained_stale_key_count: usize = (first_retained_version + 1..=latest_version)
+            .map(|version| db.stale_keys(version).len())
+            .sum();
+        assert_eq!(retained_stale_key_count > 0, past_versions_to_keep > 0);
+        if past_versions_to_keep > 0 {
+            let node_count = db.node_count();
+            let (mut pruner, _handle) = MerkleTreePruner::new(&mut db);
+            let stats = pruner
+                .prune_up_to(latest_version)
+                .unwrap()
+                .expect("tree was not pruned");
+            assert_eq!(stats.pruned_key_count, retained_stale_key_count);
+            assert_eq!(db.node_count(), node_count - retained_stale_key_count);
+        }
+
+        let mut tree = MerkleTree::new(&mut db).unwrap();
+        // Check that the tree can be extended after pruning.
+        for chunk in generate_entries(100..200).chunks(25) {
+            tree.extend(chunk).unwrap();
+        }
+        let latest_version = tree.latest_version().unwrap().unwrap();
+        tree.verify_consistency(latest_version).unwrap();
+    }
+
+    #[test]
+    fn tree_is_consistent_after_pruning() {
+        test_tree_is_consistent_after_pruning(0);
+    }
+
+    #[test]
+    fn tree_is_consistent_after_partial_pruning() {
+        test_tree_is_consistent_after_pruning(2);
+    }
+
+    #[test]
+    fn stale_keys_are_removed_on_truncation() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut db = RocksDBWrapper::new(temp_dir.path()).unwrap();
+        let mut tree = MerkleTree::new(&mut db).unwrap();
+        for chunk in generate_entries(0..30).chunks(10) {
+            tree.extend(chunk).unwrap();
+        }
+        assert!(!db.stale_keys(2).is_empty());
+
+        let mut tree = MerkleTree::new(&mut db).unwrap();
+        tree.truncate_recent_versions(2).unwrap();
+        assert!(!db.stale_keys(1).is_empty());
+        assert!(db.stale_keys(2).is_empty());
+
+        // Recreate version #2 with different contents; pruning must not remove any nodes reachable from it.
+        let mut tree = MerkleTree::new(&mut db).unwrap();
+        tree.extend(&generate_entries(100..105)).unwrap();
+
+        let (mut pruner, _handle) = MerkleTreePruner::new(&mut db);
+        pruner.prune_up_to(2).unwrap().expect("tree was not pruned");
+        let tree = MerkleTree::new(&mut db).unwrap();
+        assert_eq!(tree.first_retained_version().unwrap(), Some(2));
+        tree.verify_consistency(2).unwrap();
+    }
+}
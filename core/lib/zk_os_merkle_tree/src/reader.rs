@@ -5,9 +5,10 @@ use std::fmt;
 use zksync_basic_types::H256;
 
 use crate::{
+    consistency::ConsistencyError,
     hasher::BatchTreeProof,
     types::{NodeKey, RawNode},
-    Database, DefaultTreeParams, MerkleTree, RocksDBWrapper, TreeParams,
+    Database, DefaultTreeParams, MerkleTree, PruneDatabase, RocksDBWrapper, TreeEntry, TreeParams,
 };
 
 pub struct MerkleTreeReader<DB, P: TreeParams = DefaultTreeParams>(MerkleTree<DB, P>);
@@ -75,6 +76,37 @@ impl<DB: Database, P: TreeParams> MerkleTreeReader<DB, P> {
     pub fn prove(&self, version: u64, keys: &[H256]) -> anyhow::Result<BatchTreeProof> {
         self.0.prove(version, keys)
     }
+
+    /// Visits all entries in the tree at the specified `version` together with their leaf indices.
+    /// See [`MerkleTree::for_each_entry()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Proxies errors from [`MerkleTree::for_each_entry()`].
+    pub fn for_each_entry(
+        &self,
+        version: u64,
+        visitor: impl FnMut(u64, TreeEntry) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        self.0.for_each_entry(version, visitor)
+    }
+
+    /// Verifies the internal tree consistency at the specified `version`. See [`MerkleTree::verify_consistency()`]
+    /// for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first encountered inconsistency.
+    pub fn verify_consistency(&self, version: u64) -> Result<(), ConsistencyError> {
+        self.0.verify_consistency(version)
+    }
+}
+
+impl<DB: PruneDatabase, P: TreeParams> MerkleTreeReader<DB, P> {
+    /// Returns the first retained version of the tree, or `None` if the tree is empty.
+    pub fn first_retained_version(&self) -> anyhow::Result<Option<u64>> {
+        self.0.first_retained_version()
+    }
 }
 
 impl<P: TreeParams> MerkleTreeReader<RocksDBWrapper, P> {
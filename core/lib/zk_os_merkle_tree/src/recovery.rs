@@ -0,0 +1,748 @@
+//! Merkle tree recovery logic.
+//!
+//! # Overview
+//!
+//! **Recovery process** is responsible for restoring a Merkle tree from a snapshot, i.e., from all tree entries
+//! together with their leaf indices at a specific tree version. As a result of recovery, we create a Merkle tree
+//! with the same entries and leaf indices as the snapshot; thus, the recovered tree has the same root hash as the original one,
+//! and any changes applied to the tree afterwards will have the same outcome as if they were applied to the original tree.
+//! Similar to the Era tree, all nodes in the recovered tree initially have the same version (the snapshot version).
+//!
+//! Recovery proceeds as follows:
+//!
+//! 1. Initialize a tree in the recovery mode using [`MerkleTreeRecovery::new()`]. Until recovery is finished,
+//!    the tree cannot be accessed using ordinary [`MerkleTree`](crate::MerkleTree) APIs.
+//! 2. Feed snapshot entries to the tree in chunks via [`MerkleTreeRecovery::extend()`]. Entries in each chunk must be sorted
+//!    by key, but chunks may be supplied in any order (e.g., if they are loaded concurrently).
+//! 3. Check [`MerkleTreeRecovery::root_hash()`] against the reference value and finalize recovery using
+//!    [`MerkleTreeRecovery::finalize()`].
+//!
+//! Each chunk is persisted atomically, so recovery is tolerant to crashes and may be resumed from the middle.
+//! To find out which chunks are already recovered, you may use [`MerkleTreeRecovery::leaf_indices()`].
+//!
+//! # Implementation details
+//!
+//! Since leaf indices are provided by the snapshot, leaves can be written at their final positions right away.
+//! The only cross-chunk dependency is the linked list of leaves ordered by key, which is maintained in the same way
+//! as for ordinary insertions (i.e., by updating the `next_index` pointer of the preceding leaf). Internal nodes
+//! are only built when computing the root hash; this is done bottom-up in batches of leaves, so that RAM usage
+//! doesn't depend on the tree size.
+
+use std::{collections::HashMap, iter};
+
+use anyhow::Context as _;
+use zksync_basic_types::H256;
+
+use crate::{
+    leaf_nibbles, max_node_children,
+    metrics::{RecoveryStage, RECOVERY_METRICS},
+    storage::{PatchSet, RecoveryDatabase},
+    types::{ChildRef, InternalNode, KeyLookup, Leaf, Manifest, Node, NodeKey, Root, TreeTags},
+    Blake2Hasher, DefaultTreeParams, HashTree, TreeEntry, TreeParams,
+};
+
+/// Handle to a Merkle tree during its recovery.
+#[derive(Debug)]
+pub struct MerkleTreeRecovery<DB, P: TreeParams = DefaultTreeParams> {
+    db: DB,
+    hasher: P::Hasher,
+    manifest: Manifest,
+    recovered_version: u64,
+}
+
+impl<DB: RecoveryDatabase> MerkleTreeRecovery<DB> {
+    /// Creates tree recovery with the default Blake2 hasher.
+    ///
+    /// # Errors
+    ///
+    /// Errors in the same situations as [`Self::with_hasher()`].
+    pub fn new(db: DB, recovered_version: u64) -> anyhow::Result<Self> {
+        Self::with_hasher(db, recovered_version, Blake2Hasher)
+    }
+
+    /// Returns the version of the tree being recovered in `db`, or `None` if the tree is not being recovered
+    /// (i.e., it's empty or is ready for normal operation).
+    ///
+    /// # Errors
+    ///
+    /// Proxies database errors.
+    pub fn recovering_version(db: &DB) -> anyhow::Result<Option<u64>> {
+        let manifest = db.try_manifest().context("failed reading tree manifest")?;
+        Ok(manifest.and_then(|manifest| {
+            if manifest.tags.is_recovering {
+                manifest.version_count.checked_sub(1)
+            } else {
+                None
+            }
+        }))
+    }
+}
+
+impl<DB: RecoveryDatabase, P: TreeParams> MerkleTreeRecovery<DB, P> {
+    /// Number of tree levels hashed per batch of leaves when computing the root hash, i.e. each batch
+    /// contains ~`1 << HASHING_BATCH_DEPTH` leaves.
+    const HASHING_BATCH_DEPTH: u8 = 18;
+
+    /// Initializes recovery with the specified hasher, or resumes recovery if it was started previously.
+    ///
+    /// # Errors
+    ///
+    /// - Errors if the tree DB exists and it's not being recovered, or if it's being recovered
+    ///   for a different tree version.
+    /// - Errors if the hasher or basic tree parameters (e.g., the tree depth)
+    ///   do not match those of the tree loaded from the database.
+    pub fn with_hasher(
+        mut db: DB,
+        recovered_version: u64,
+        hasher: P::Hasher,
+    ) -> anyhow::Result<Self> {
+        let maybe_manifest = db.try_manifest().context("failed reading tree manifest")?;
+        let manifest = if let Some(manifest) = maybe_manifest {
+            manifest.tags.ensure_consistency::<P>(&hasher, true)?;
+            anyhow::ensure!(
+                manifest.tags.is_recovering,
+                "Merkle tree is already initialized and is not being recovered"
+            );
+            let expected_version = manifest.version_count.checked_sub(1);
+            anyhow::ensure!(
+                expected_version == Some(recovered_version),
+                "Requested to recover tree version {recovered_version}, but it is currently being recovered \
+                 for version {expected_version:?}"
+            );
+            tracing::info!(recovered_version, "Resuming Merkle tree recovery");
+            manifest
+        } else {
+            let mut tags = TreeTags::for_params::<P>(&hasher);
+            tags.is_recovering = true;
+            let manifest = Manifest {
+                version_count: recovered_version + 1,
+                tags,
+            };
+
+            // Guards are inserted at the start of recovery since they are not a part of snapshots.
+            let leaves = HashMap::from([(0, Leaf::MIN_GUARD), (1, Leaf::MAX_GUARD)]);
+            let guard_keys = [(Leaf::MIN_GUARD.key, 0), (Leaf::MAX_GUARD.key, 1)];
+            let patch = PatchSet::for_recovery(
+                manifest.clone(),
+                recovered_version,
+                Some(Self::placeholder_root(2)),
+                Self::empty_levels(),
+                leaves,
+                guard_keys.into_iter(),
+            );
+            db.apply_recovery_patch(patch)
+                .context("failed initializing tree recovery")?;
+            tracing::info!(recovered_version, "Started Merkle tree recovery");
+            manifest
+        };
+
+        Ok(Self {
+            db,
+            hasher,
+            manifest,
+            recovered_version,
+        })
+    }
+
+    /// Returns the version of the tree being recovered.
+    pub fn recovered_version(&self) -> u64 {
+        self.recovered_version
+    }
+
+    /// Returns a reference to the database.
+    pub fn db(&self) -> &DB {
+        &self.db
+    }
+
+    /// Returns leaf indices for the specified keys, or `None` for the keys not yet recovered.
+    ///
+    /// # Errors
+    ///
+    /// Proxies database errors.
+    pub fn leaf_indices(&self, keys: &[H256]) -> anyhow::Result<Vec<Option<u64>>> {
+        let lookups = self
+            .db
+            .indices(self.recovered_version, keys)
+            .context("failed loading indices")?;
+        let indices = lookups.into_iter().map(|lookup| match lookup {
+            KeyLookup::Existing(idx) => Some(idx),
+            KeyLookup::Missing { .. } => None,
+        });
+        Ok(indices.collect())
+    }
+
+    /// Returns a root with the specified leaf count and no children. Such a root is persisted when the tree isn't hashed yet;
+    /// it is recognized and replaced by [`Self::root_hash()`].
+    fn placeholder_root(leaf_count: u64) -> Root {
+        Root {
+            leaf_count,
+            root_node: InternalNode::empty(),
+        }
+    }
+
+    fn empty_levels() -> Vec<HashMap<u64, InternalNode>> {
+        vec![HashMap::new(); usize::from(leaf_nibbles::<P>())]
+    }
+
+    fn leaf_key(&self, index: u64) -> NodeKey {
+        NodeKey {
+            version: self.recovered_version,
+            nibble_count: leaf_nibbles::<P>(),
+            index_on_level: index,
+        }
+    }
+
+    fn load_root(&self) -> anyhow::Result<Root> {
+        self.db
+            .try_root(self.recovered_version)?
+            .context("root of the recovered tree is missing")
+    }
+
+    /// Extends the tree with a chunk of entries with their leaf indices.
+    ///
+    /// Entries must be sorted by increasing key. Entries already present in the tree are skipped (provided that their
+    /// leaf index matches), so it's safe to supply the same chunk several times.
+    ///
+    /// # Errors
+    ///
+    /// - Errors if entries are not sorted, or if leaf indices are inconsistent with the tree.
+    /// - Proxies database errors.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(recovered_version = self.recovered_version, entries.len = entries.len())
+    )]
+    pub fn extend(&mut self, entries: &[(u64, TreeEntry)]) -> anyhow::Result<()> {
+        RECOVERY_METRICS.chunk_size.observe(entries.len());
+        let extend_latency = RECOVERY_METRICS.stage_latency[&RecoveryStage::Extend].start();
+
+        for window in entries.windows(2) {
+            let [(_, prev_entry), (_, next_entry)] = window else {
+                unreachable!();
+            };
+            anyhow::ensure!(
+                prev_entry.key < next_entry.key,
+                "Recovered entries must be sorted by increasing key; got {prev_entry:?} followed by {next_entry:?}"
+            );
+        }
+
+        let keys: Vec<_> = entries.iter().map(|(_, entry)| entry.key).collect();
+        let lookups = self
+            .db
+            .indices(self.recovered_version, &keys)
+            .context("failed loading indices")?;
+        let mut leaf_count = self.load_root()?.leaf_count;
+
+        let mut leaves = HashMap::with_capacity(entries.len());
+        let mut new_keys = Vec::with_capacity(entries.len());
+        // Updates for `next_index` pointers of the existing leaves preceding runs of new leaves.
+        let mut prev_leaf_updates = vec![];
+        // Neighbors of the current run of new leaves, and the index of the last leaf in the run.
+        let mut current_run: Option<((H256, u64), (H256, u64), u64)> = None;
+
+        for (&(index, entry), lookup) in entries.iter().zip(lookups) {
+            match lookup {
+                KeyLookup::Existing(existing_index) => {
+                    anyhow::ensure!(
+                        existing_index == index,
+                        "Mismatch between leaf index for {entry:?}: supplied {index}, but the tree contains {existing_index}"
+                    );
+                    current_run = None;
+                }
+                KeyLookup::Missing {
+                    prev_key_and_index,
+                    next_key_and_index,
+                } => {
+                    anyhow::ensure!(
+                        index >= 2,
+                        "Leaf index {index} for {entry:?} is reserved for guards"
+                    );
+
+                    match &mut current_run {
+                        Some((prev, next, last_index))
+                            if *prev == prev_key_and_index && *next == next_key_and_index =>
+                        {
+                            // The leaf is inserted into the same gap between existing leaves as the previous one.
+                            leaves.get_mut(&*last_index).unwrap().next_index = index;
+                            *last_index = index;
+                        }
+                        _ => {
+                            prev_leaf_updates.push((prev_key_and_index.1, index));
+                            current_run = Some((prev_key_and_index, next_key_and_index, index));
+                        }
+                    }
+
+                    let leaf = Leaf {
+                        key: entry.key,
+                        value: entry.value,
+                        next_index: next_key_and_index.1,
+                    };
+                    anyhow::ensure!(
+                        leaves.insert(index, leaf).is_none(),
+                        "Leaf index {index} is duplicated in the recovered entries"
+                    );
+                    new_keys.push((entry.key, index));
+                    leaf_count = leaf_count.max(index + 1);
+                }
+            }
+        }
+
+        let prev_leaf_keys: Vec<_> = prev_leaf_updates
+            .iter()
+            .map(|&(prev_index, _)| self.leaf_key(prev_index))
+            .collect();
+        let prev_leaves = self
+            .db
+            .try_nodes(&prev_leaf_keys)
+            .context("failed loading neighbor leaves")?;
+        for (node, (prev_index, next_index)) in prev_leaves.into_iter().zip(prev_leaf_updates) {
+            let Node::Leaf(mut leaf) = node else {
+                anyhow::bail!("Unexpected internal node at leaf index {prev_index}");
+            };
+            leaf.next_index = next_index;
+            leaves.insert(prev_index, leaf);
+        }
+        let extend_latency = extend_latency.observe();
+        tracing::debug!(
+            ?extend_latency,
+            new_leaves = new_keys.len(),
+            leaf_count,
+            "processed recovered entries"
+        );
+
+        let apply_patch_latency =
+            RECOVERY_METRICS.stage_latency[&RecoveryStage::ApplyPatch].start();
+        // Writing the placeholder root invalidates the root hash computed previously (if any).
+        let patch = PatchSet::for_recovery(
+            self.manifest.clone(),
+            self.recovered_version,
+            Some(Self::placeholder_root(leaf_count)),
+            Self::empty_levels(),
+            leaves,
+            new_keys.into_iter(),
+        );
+        self.db.apply_recovery_patch(patch)?;
+        let apply_patch_latency = apply_patch_latency.observe();
+        tracing::debug!(?apply_patch_latency, "persisted recovered entries");
+        Ok(())
+    }
+
+    /// Returns the root hash of the recovered tree at this point.
+    ///
+    /// The first call after [`Self::extend()`] is expensive since it hashes the entire tree and persists its internal nodes.
+    ///
+    /// # Errors
+    ///
+    /// - Errors if the recovered tree has gaps in leaf indices.
+    /// - Proxies database errors.
+    #[tracing::instrument(level = "debug", skip_all, fields(recovered_version = self.recovered_version))]
+    pub fn root_hash(&mut self) -> anyhow::Result<H256> {
+        let root = self.load_root()?;
+        if !root.root_node.children.is_empty() {
+            return Ok(root.hash::<P>(&self.hasher));
+        }
+
+        let hashing_latency = RECOVERY_METRICS.stage_latency[&RecoveryStage::Hashing].start();
+        let root_hash = self.hash_tree(root.leaf_count)?;
+        let hashing_latency = hashing_latency.observe();
+        tracing::info!(
+            ?hashing_latency,
+            leaf_count = root.leaf_count,
+            "Hashed recovered Merkle tree"
+        );
+        Ok(root_hash)
+    }
+
+    fn hash_tree(&mut self, leaf_count: u64) -> anyhow::Result<H256> {
+        use rayon::prelude::*;
+
+        let leaf_nibbles = leaf_nibbles::<P>();
+        // Levels hashed in batches never include the root level; the remaining levels have few nodes and are hashed in one go.
+        let batch_levels = Self::HASHING_BATCH_DEPTH
+            .div_ceil(P::INTERNAL_NODE_DEPTH)
+            .min(leaf_nibbles - 1);
+        let batch_size = 1_u64 << (batch_levels * P::INTERNAL_NODE_DEPTH);
+
+        let mut top_hashes = vec![];
+        let mut batch_start = 0;
+        while batch_start < leaf_count {
+            let batch_end = (batch_start + batch_size).min(leaf_count);
+            let leaf_keys: Vec<_> = (batch_start..batch_end)
+                .map(|idx| self.leaf_key(idx))
+                .collect();
+            let leaves = self.db.try_nodes(&leaf_keys).with_context(|| {
+                format!(
+                    "failed loading leaves {batch_start}..{batch_end}; the recovered tree may have gaps in leaf indices"
+                )
+            })?;
+            let mut hashes = leaves
+                .par_iter()
+                .map(|node| match node {
+                    Node::Leaf(leaf) => Ok(self.hasher.hash_leaf(leaf)),
+                    Node::Internal(_) => Err(anyhow::anyhow!(
+                        "unexpected internal node in place of a leaf"
+                    )),
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            let mut levels = Self::empty_levels();
+            let mut first_index = batch_start;
+            for nibble_count in (leaf_nibbles - batch_levels..leaf_nibbles).rev() {
+                let (nodes, node_hashes) = self.hash_level(nibble_count, first_index, &hashes);
+                levels[usize::from(nibble_count)].extend(nodes);
+                hashes = node_hashes;
+                first_index >>= P::INTERNAL_NODE_DEPTH;
+            }
+            top_hashes.extend(hashes);
+
+            let patch = PatchSet::for_recovery(
+                self.manifest.clone(),
+                self.recovered_version,
+                None,
+                levels,
+                HashMap::new(),
+                iter::empty(),
+            );
+            self.db.apply_recovery_patch(patch)?;
+            tracing::debug!("Hashed leaves {batch_start}..{batch_end}");
+            batch_start = batch_end;
+        }
+
+        let mut levels = Self::empty_levels();
+        let mut hashes = top_hashes;
+        for nibble_count in (0..leaf_nibbles - batch_levels).rev() {
+            let (nodes, node_hashes) = self.hash_level(nibble_count, 0, &hashes);
+            levels[usize::from(nibble_count)].extend(nodes);
+            hashes = node_hashes;
+        }
+        let root_node = levels[0].remove(&0).context("root node was not created")?;
+        anyhow::ensure!(hashes.len() == 1, "unexpected number of root hashes");
+
+        let root = Root {
+            leaf_count,
+            root_node,
+        };
+        let patch = PatchSet::for_recovery(
+            self.manifest.clone(),
+            self.recovered_version,
+            Some(root),
+            levels,
+            HashMap::new(),
+            iter::empty(),
+        );
+        self.db.apply_recovery_patch(patch)?;
+        Ok(hashes[0])
+    }
+
+    /// Creates internal nodes with the specified `nibble_count` from the contiguous hashes of their children, starting
+    /// from `first_child_index` (which must be aligned to the node boundary). Returns the created nodes together with their hashes.
+    fn hash_level(
+        &self,
+        nibble_count: u8,
+        first_child_index: u64,
+        child_hashes: &[H256],
+    ) -> (Vec<(u64, InternalNode)>, Vec<H256>) {
+        use rayon::prelude::*;
+
+        let first_index = first_child_index >> P::INTERNAL_NODE_DEPTH;
+        let nodes: Vec<_> = child_hashes
+            .chunks(max_node_children::<P>().into())
+            .zip(first_index..)
+            .map(|(hashes, idx)| {
+                let children = hashes.iter().map(|&hash| ChildRef {
+                    version: self.recovered_version,
+                    hash,
+                });
+                let node = InternalNode {
+                    children: children.collect(),
+                };
+                (idx, node)
+            })
+            .collect();
+
+        let depth = (leaf_nibbles::<P>() - 1 - nibble_count) * P::INTERNAL_NODE_DEPTH;
+        let hashes = nodes
+            .par_iter()
+            .map(|(_, node)| node.hash::<P>(&self.hasher, depth))
+            .collect();
+        (nodes, hashes)
+    }
+
+    /// Finalizes the recovery process marking it as complete in the tree manifest. Returns the database
+    /// that can be used to instantiate a [`MerkleTree`](crate::MerkleTree).
+    ///
+    /// # Errors
+    ///
+    /// Errors in the same situations as [`Self::root_hash()`].
+    #[tracing::instrument(level = "debug", skip_all, fields(recovered_version = self.recovered_version))]
+    pub fn finalize(mut self) -> anyhow::Result<DB> {
+        // Ensure that the tree is hashed.
+        let root_hash = self.root_hash()?;
+
+        let mut manifest = self.manifest;
+        manifest.tags.is_recovering = false;
+        let patch = PatchSet::for_recovery(
+            manifest,
+            self.recovered_version,
+            None,
+            Self::empty_levels(),
+            HashMap::new(),
+            iter::empty(),
+        );
+        self.db.apply_recovery_patch(patch)?;
+        tracing::info!(
+            recovered_version = self.recovered_version,
+            ?root_hash,
+            "Finalized Merkle tree recovery"
+        );
+        Ok(self.db)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{Database, MerkleTree, RocksDBWrapper};
+
+    /// Builds a reference tree with several versions and returns it together with all its entries sorted by key.
+    fn create_reference_tree(rng: &mut StdRng) -> (MerkleTree<PatchSet>, Vec<(u64, TreeEntry)>) {
+        let mut tree = MerkleTree::new(PatchSet::default()).unwrap();
+        let mut entries = BTreeMap::new();
+        for _ in 0..3 {
+            let mut batch: Vec<_> = (0..100)
+                .map(|_| TreeEntry {
+                    key: H256(rng.gen()),
+                    value: H256(rng.gen()),
+                })
+                .collect();
+            // Update some of the existing entries.
+            let existing_keys: Vec<_> = entries.keys().copied().collect();
+            batch.extend(
+                existing_keys
+                    .choose_multiple(rng, 10)
+                    .map(|&key| TreeEntry {
+                        key,
+                        value: H256(rng.gen()),
+                    }),
+            );
+            tree.extend(&batch).unwrap();
+
+            let latest_version = tree.latest_version().unwrap().unwrap();
+            let keys: Vec<_> = batch.iter().map(|entry| entry.key).collect();
+            let lookups = tree.db().indices(latest_version, &keys).unwrap();
+            for (entry, lookup) in batch.into_iter().zip(lookups) {
+                let KeyLookup::Existing(idx) = lookup else {
+                    panic!("missing entry: {entry:?}");
+                };
+                entries.insert(entry.key, (idx, entry));
+            }
+        }
+        (tree, entries.into_values().collect())
+    }
+
+    fn recover_tree<DB: RecoveryDatabase>(
+        db: DB,
+        version: u64,
+        entries: &[(u64, TreeEntry)],
+        chunk_size: usize,
+        rng: &mut StdRng,
+    ) -> MerkleTreeRecovery<DB> {
+        let mut recovery = MerkleTreeRecovery::new(db, version).unwrap();
+        let mut chunks: Vec<_> = entries.chunks(chunk_size).collect();
+        chunks.shuffle(rng);
+        for chunk in chunks {
+            recovery.extend(chunk).unwrap();
+        }
+        recovery
+    }
+
+    fn assert_recovered_tree<DB: Database>(
+        recovered_db: DB,
+        reference: &mut MerkleTree<PatchSet>,
+        rng: &mut StdRng,
+    ) {
+        let version = reference.latest_version().unwrap().unwrap();
+        let mut tree = MerkleTree::new(recovered_db).unwrap();
+        assert_eq!(tree.latest_version().unwrap(), Some(version));
+        assert_eq!(
+            tree.root_info(version).unwrap(),
+            reference.root_info(version).unwrap()
+        );
+        tree.verify_consistency(version).unwrap();
+
+        // Check that the recovered tree can be updated in the same way as the reference one.
+        let new_entries: Vec<_> = (0..50)
+            .map(|_| TreeEntry {
+                key: H256(rng.gen()),
+                value: H256(rng.gen()),
+            })
+            .collect();
+        let output = tree.extend(&new_entries).unwrap();
+        let reference_output = reference.extend(&new_entries).unwrap();
+        assert_eq!(output.root_hash, reference_output.root_hash);
+        assert_eq!(output.leaf_count, reference_output.leaf_count);
+        tree.verify_consistency(version + 1).unwrap();
+    }
+
+    #[test]
+    fn recovering_tree() {
+        let rng = &mut StdRng::seed_from_u64(123);
+        let (reference, entries) = create_reference_tree(rng);
+        let version = reference.latest_version().unwrap().unwrap();
+        let expected_root_hash = reference.root_hash(version).unwrap().unwrap();
+
+        for chunk_size in [1, 7, 50, 1_000] {
+            println!("Testing recovery with chunk size {chunk_size}");
+            let mut recovery =
+                recover_tree(PatchSet::default(), version, &entries, chunk_size, rng);
+            assert_eq!(recovery.root_hash().unwrap(), expected_root_hash);
+            let db = recovery.finalize().unwrap();
+
+            let mut reference = MerkleTree::new(reference.db().clone()).unwrap();
+            assert_recovered_tree(db, &mut reference, rng);
+        }
+    }
+
+    #[test]
+    fn recovering_empty_tree() {
+        let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 42).unwrap();
+        assert_eq!(
+            recovery.root_hash().unwrap(),
+            MerkleTree::<PatchSet>::empty_tree_hash()
+        );
+        let tree = MerkleTree::new(recovery.finalize().unwrap()).unwrap();
+        assert_eq!(tree.latest_version().unwrap(), Some(42));
+        tree.verify_consistency(42).unwrap();
+    }
+
+    #[test]
+    fn root_hash_is_recomputed_after_extension() {
+        let rng = &mut StdRng::seed_from_u64(321);
+        let (reference, entries) = create_reference_tree(rng);
+        let version = reference.latest_version().unwrap().unwrap();
+
+        let (first_half, second_half) = entries.split_at(entries.len() / 2);
+        let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), version).unwrap();
+        recovery.extend(second_half).unwrap();
+        let intermediate_hash = recovery.root_hash().unwrap();
+        assert_ne!(
+            intermediate_hash,
+            reference.root_hash(version).unwrap().unwrap()
+        );
+
+        recovery.extend(first_half).unwrap();
+        assert_eq!(
+            recovery.root_hash().unwrap(),
+            reference.root_hash(version).unwrap().unwrap()
+        );
+    }
+
+    #[test]
+    fn resuming_recovery() {
+        let rng = &mut StdRng::seed_from_u64(456);
+        let (mut reference, entries) = create_reference_tree(rng);
+        let version = reference.latest_version().unwrap().unwrap();
+        let chunks: Vec<_> = entries.chunks(30).collect();
+
+        let mut db = PatchSet::default();
+        let mut recovery = MerkleTreeRecovery::new(&mut db, version).unwrap();
+        for chunk in &chunks[..chunks.len() / 2] {
+            recovery.extend(chunk).unwrap();
+        }
+        drop(recovery);
+
+        assert_eq!(
+            MerkleTreeRecovery::recovering_version(&db).unwrap(),
+            Some(version)
+        );
+        let err = MerkleTree::new(&mut db).unwrap_err().to_string();
+        assert!(err.contains("being recovered"), "{err}");
+        let err = MerkleTreeRecovery::new(&mut db, version + 1)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Requested to recover tree version"), "{err}");
+
+        let mut recovery = MerkleTreeRecovery::new(&mut db, version).unwrap();
+        // Check that recovered chunks can be detected, and that repeating a chunk is a no-op.
+        let first_keys: Vec<_> = chunks.iter().map(|chunk| chunk[0].1.key).collect();
+        let indices = recovery.leaf_indices(&first_keys).unwrap();
+        for (i, (chunk, idx)) in chunks.iter().zip(indices).enumerate() {
+            if i < chunks.len() / 2 {
+                assert_eq!(idx, Some(chunk[0].0));
+            } else {
+                assert_eq!(idx, None);
+            }
+        }
+        for chunk in &chunks[chunks.len() / 2 - 1..] {
+            recovery.extend(chunk).unwrap();
+        }
+        recovery.finalize().unwrap();
+
+        assert_eq!(MerkleTreeRecovery::recovering_version(&db).unwrap(), None);
+        assert_recovered_tree(db, &mut reference, rng);
+    }
+
+    #[test]
+    fn recovery_errors_on_invalid_indices() {
+        let entry = TreeEntry {
+            key: H256::repeat_byte(1),
+            value: H256::repeat_byte(2),
+        };
+        let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 0).unwrap();
+        let err = recovery.extend(&[(1, entry)]).unwrap_err().to_string();
+        assert!(err.contains("reserved for guards"), "{err}");
+
+        recovery.extend(&[(2, entry)]).unwrap();
+        let err = recovery.extend(&[(3, entry)]).unwrap_err().to_string();
+        assert!(err.contains("Mismatch between leaf index"), "{err}");
+
+        // Leaf index 3 is never recovered.
+        let other_entry = TreeEntry {
+            key: H256::repeat_byte(3),
+            ..entry
+        };
+        recovery.extend(&[(4, other_entry)]).unwrap();
+        let err = format!("{:#}", recovery.root_hash().unwrap_err());
+        assert!(err.contains("gaps in leaf indices"), "{err}");
+    }
+
+    #[test]
+    fn recovering_tree_with_rocksdb() {
+        let rng = &mut StdRng::seed_from_u64(789);
+        let (mut reference, entries) = create_reference_tree(rng);
+        let version = reference.latest_version().unwrap().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+
+        let db = RocksDBWrapper::new(temp_dir.path()).unwrap();
+        let chunks: Vec<_> = entries.chunks(25).collect();
+        let mut recovery = MerkleTreeRecovery::new(db, version).unwrap();
+        for chunk in &chunks[..3] {
+            recovery.extend(chunk).unwrap();
+        }
+        drop(recovery);
+
+        // Reopen the DB to check that the recovery state is persisted.
+        let db = RocksDBWrapper::new(temp_dir.path()).unwrap();
+        assert_eq!(
+            MerkleTreeRecovery::recovering_version(&db).unwrap(),
+            Some(version)
+        );
+        let mut recovery = MerkleTreeRecovery::new(db, version).unwrap();
+        for chunk in chunks[3..].iter().rev() {
+            recovery.extend(chunk).unwrap();
+        }
+        assert_eq!(
+            recovery.root_hash().unwrap(),
+            reference.root_hash(version).unwrap().unwrap()
+        );
+        recovery.finalize().unwrap();
+
+        let db = RocksDBWrapper::new(temp_dir.path()).unwrap();
+        assert_recovered_tree(db, &mut reference, rng);
+    }
+}
@@ -0,0 +1,129 @@
+//! Repairing damaged internal nodes of the tree.
+
+use anyhow::Context as _;
+use zksync_basic_types::H256;
+
+use crate::{
+    leaf_nibbles, max_nibbles_for_internal_node,
+    storage::{PatchSet, RecoveryDatabase},
+    types::{InternalNode, Node, NodeKey},
+    HashTree, MerkleTree, TreeParams,
+};
+
+/// Outcome of [`MerkleTree::repair()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepairStats {
+    /// Number of internal nodes (including the root) that were overwritten.
+    pub repaired_node_count: usize,
+    /// Root hash of the repaired tree version.
+    pub root_hash: H256,
+}
+
+impl<DB: RecoveryDatabase, P: TreeParams> MerkleTree<DB, P> {
+    /// Repairs the specified tree `version` by recomputing hashes of all its internal nodes from leaves
+    /// and overwriting internal nodes that have incorrect child hashes. Leaves are treated as the source of truth;
+    /// thus, the repaired tree has the same root hash as the original tree (provided that leaves are not damaged).
+    ///
+    /// Repaired nodes may be shared with older tree versions; since node hashes are recomputed from the same leaves,
+    /// this doesn't break older versions.
+    ///
+    /// # Errors
+    ///
+    /// - Errors if the version doesn't exist.
+    /// - Errors if a node cannot be loaded or deserialized; such damage cannot be repaired by this method.
+    pub fn repair(&mut self, version: u64) -> anyhow::Result<RepairStats> {
+        let manifest = self.db.try_manifest()?.context("Merkle tree is empty")?;
+        anyhow::ensure!(
+            version < manifest.version_count,
+            "tree version {version} does not exist"
+        );
+        let mut root = self
+            .db
+            .try_root(version)?
+            .with_context(|| format!("missing root for tree version {version}"))?;
+
+        let mut repaired_nodes = vec![];
+        let (root_hash, is_root_repaired) = self.repair_internal_node(
+            &mut root.root_node,
+            NodeKey::root(version),
+            &mut repaired_nodes,
+        )?;
+        let repaired_node_count = repaired_nodes.len() + usize::from(is_root_repaired);
+        if repaired_node_count > 0 {
+            tracing::info!(
+                version,
+                repaired_node_count,
+                ?root_hash,
+                "Writing repaired internal nodes"
+            );
+            let patch = PatchSet::for_repair(
+                manifest,
+                leaf_nibbles::<P>(),
+                is_root_repaired.then_some((version, root)),
+                repaired_nodes.into_iter(),
+            );
+            self.db.apply_recovery_patch(patch)?;
+        }
+
+        Ok(RepairStats {
+            repaired_node_count,
+            root_hash,
+        })
+    }
+
+    /// Returns the recomputed hash of the node and a flag whether the node was modified.
+    /// Modified descendants of the node are pushed to `repaired_nodes`.
+    fn repair_internal_node(
+        &self,
+        node: &mut InternalNode,
+        key: NodeKey,
+        repaired_nodes: &mut Vec<(NodeKey, InternalNode)>,
+    ) -> anyhow::Result<(H256, bool)> {
+        let child_depth =
+            (max_nibbles_for_internal_node::<P>() - key.nibble_count) * P::INTERNAL_NODE_DEPTH;
+        let child_keys: Vec<_> = node
+            .children
+            .iter()
+            .enumerate()
+            .map(|(i, child_ref)| NodeKey {
+                version: child_ref.version,
+                nibble_count: key.nibble_count + 1,
+                index_on_level: (key.index_on_level << P::INTERNAL_NODE_DEPTH) + i as u64,
+            })
+            .collect();
+        let children = self
+            .db
+            .try_nodes(&child_keys)
+            .with_context(|| format!("failed loading children of node {key}"))?;
+
+        let mut is_modified = false;
+        let children = node.children.iter_mut().zip(child_keys).zip(children);
+        for ((child_ref, child_key), child) in children {
+            let child_hash = match child {
+                Node::Internal(mut child) => {
+                    // Recursion here is OK; the tree isn't that deep.
+                    let (hash, is_child_modified) =
+                        self.repair_internal_node(&mut child, child_key, repaired_nodes)?;
+                    if is_child_modified {
+                        repaired_nodes.push((child_key, child));
+                    }
+                    hash
+                }
+                Node::Leaf(leaf) => self.hasher.hash_leaf(&leaf),
+            };
+
+            if child_ref.hash != child_hash {
+                tracing::info!(
+                    %key,
+                    %child_key,
+                    stored_hash = ?child_ref.hash,
+                    ?child_hash,
+                    "Repairing child hash"
+                );
+                child_ref.hash = child_hash;
+                is_modified = true;
+            }
+        }
+        Ok((node.hash::<P>(&self.hasher, child_depth), is_modified))
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     cmp,
-    collections::{BTreeMap, HashMap},
+    collections::{hash_map, BTreeMap, HashMap},
     ops, slice,
 };
 
@@ -118,6 +118,77 @@ impl<DB: Database + ?Sized> Database for &mut DB {
     }
 }
 
+/// Analogue of [`PatchSet`] used when pruning past versions of the Merkle tree.
+#[derive(Debug)]
+pub struct PrunePatchSet {
+    /// Keys that need to be removed from the tree. Logically, the version of each key
+    /// should be less than the minimum retained version.
+    pruned_node_keys: Vec<NodeKey>,
+    /// Range of replacing versions for stale keys that need to be removed.
+    deleted_stale_key_versions: ops::Range<u64>,
+}
+
+impl PrunePatchSet {
+    pub(crate) fn new(
+        pruned_node_keys: Vec<NodeKey>,
+        deleted_stale_key_versions: ops::Range<u64>,
+    ) -> Self {
+        Self {
+            pruned_node_keys,
+            deleted_stale_key_versions,
+        }
+    }
+}
+
+/// Functionality to prune past versions of the Merkle tree.
+pub trait PruneDatabase: Database {
+    /// Returns the minimum new version for stale keys present in this database, or `None`
+    /// if there are no stale keys.
+    fn min_stale_key_version(&self) -> Option<u64>;
+
+    /// Returns a list of node keys obsoleted in the specified `version` of the tree.
+    fn stale_keys(&self, version: u64) -> Vec<NodeKey>;
+
+    /// Atomically prunes the tree and removes the processed stale keys.
+    ///
+    /// # Errors
+    ///
+    /// Propagates database I/O errors.
+    fn prune(&mut self, patch: PrunePatchSet) -> anyhow::Result<()>;
+}
+
+impl<DB: PruneDatabase + ?Sized> PruneDatabase for &mut DB {
+    fn min_stale_key_version(&self) -> Option<u64> {
+        (**self).min_stale_key_version()
+    }
+
+    fn stale_keys(&self, version: u64) -> Vec<NodeKey> {
+        (**self).stale_keys(version)
+    }
+
+    fn prune(&mut self, patch: PrunePatchSet) -> anyhow::Result<()> {
+        (**self).prune(patch)
+    }
+}
+
+/// Functionality to recover the Merkle tree from a snapshot.
+pub trait RecoveryDatabase: Database {
+    /// Merges changes in the `patch` into this database. Unlike [`Database::apply_patch()`], existing nodes
+    /// for the patched versions are retained; a root node is only overwritten if it is present in the patch.
+    /// This operation should be atomic.
+    ///
+    /// # Errors
+    ///
+    /// Returns I/O errors.
+    fn apply_recovery_patch(&mut self, patch: PatchSet) -> anyhow::Result<()>;
+}
+
+impl<DB: RecoveryDatabase + ?Sized> RecoveryDatabase for &mut DB {
+    fn apply_recovery_patch(&mut self, patch: PatchSet) -> anyhow::Result<()> {
+        (**self).apply_recovery_patch(patch)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(test, derive(PartialEq))]
 struct InsertedKeyEntry {
@@ -159,6 +230,17 @@ impl PartialPatchSet {
     fn total_internal_nodes(&self) -> usize {
         self.internal.iter().map(HashMap::len).sum()
     }
+
+    /// Merges nodes from `other` into this patch. The leaf count is taken from `other` only if it contains the root node.
+    fn merge(&mut self, other: Self) {
+        if other.internal[0].contains_key(&0) {
+            self.leaf_count = other.leaf_count;
+        }
+        for (level, other_level) in self.internal.iter_mut().zip(other.internal) {
+            level.extend(other_level);
+        }
+        self.leaves.extend(other.leaves);
+    }
 }
 
 /// Immutable in-memory changeset that can atomically applied to a [`Database`].
@@ -168,7 +250,8 @@ pub struct PatchSet {
     patches_by_version: HashMap<u64, PartialPatchSet>,
     // We maintain a joint index for all versions to make it easier to use `PatchSet` as a `Database` or in a `Patched` wrapper.
     sorted_new_leaves: BTreeMap<H256, InsertedKeyEntry>,
-    // TODO: stale keys
+    /// Keys of nodes that became stale (i.e., were replaced) in each version.
+    stale_keys_by_version: HashMap<u64, Vec<NodeKey>>,
 }
 
 impl PatchSet {
@@ -203,6 +286,86 @@ impl PatchSet {
         }
     }
 
+    /// Creates a patch for a tree being recovered. The patch should be applied using [`RecoveryDatabase::apply_recovery_patch()`].
+    ///
+    /// `internal` must contain a level for each nibble count of internal nodes, including the root level (which must be empty;
+    /// the root is specified separately). New keys are marked as inserted at `version`.
+    pub(crate) fn for_recovery(
+        manifest: Manifest,
+        version: u64,
+        root: Option<Root>,
+        mut internal: Vec<HashMap<u64, InternalNode>>,
+        leaves: HashMap<u64, Leaf>,
+        new_keys: impl Iterator<Item = (H256, u64)>,
+    ) -> Self {
+        let mut leaf_count = 0;
+        if let Some(root) = root {
+            leaf_count = root.leaf_count;
+            internal[0].insert(0, root.root_node);
+        }
+        let sorted_new_leaves = new_keys
+            .map(|(key, index)| {
+                let entry = InsertedKeyEntry {
+                    index,
+                    inserted_at: version,
+                };
+                (key, entry)
+            })
+            .collect();
+
+        Self {
+            manifest,
+            patches_by_version: HashMap::from([(
+                version,
+                PartialPatchSet {
+                    leaf_count,
+                    internal,
+                    leaves,
+                },
+            )]),
+            sorted_new_leaves,
+            stale_keys_by_version: HashMap::new(),
+        }
+    }
+
+    /// Creates a patch overwriting internal nodes in existing tree versions. The patch should be applied
+    /// using [`RecoveryDatabase::apply_recovery_patch()`]; the manifest is not changed.
+    pub(crate) fn for_repair(
+        manifest: Manifest,
+        leaf_nibbles: u8,
+        root: Option<(u64, Root)>,
+        nodes: impl Iterator<Item = (NodeKey, InternalNode)>,
+    ) -> Self {
+        let empty_patch = || PartialPatchSet {
+            leaf_count: 0,
+            internal: vec![HashMap::new(); usize::from(leaf_nibbles)],
+            leaves: HashMap::new(),
+        };
+
+        let mut patches_by_version = HashMap::<_, PartialPatchSet>::new();
+        if let Some((version, root)) = root {
+            let patch = patches_by_version
+                .entry(version)
+                .or_insert_with(empty_patch);
+            patch.leaf_count = root.leaf_count;
+            patch.internal[0].insert(0, root.root_node);
+        }
+        for (key, node) in nodes {
+            assert!(key.nibble_count > 0 && key.nibble_count < leaf_nibbles);
+            let patch = patches_by_version
+                .entry(key.version)
+                .or_insert_with(empty_patch);
+            patch.internal[usize::from(key.nibble_count)].insert(key.index_on_level, node);
+        }
+
+        Self {
+            manifest,
+            patches_by_version,
+            sorted_new_leaves: BTreeMap::new(),
+            stale_keys_by_version: HashMap::new(),
+        }
+    }
+
     fn copied_hashes_count(&self) -> usize {
         let copied_hashes = self.patches_by_version.iter().map(|(&version, patch)| {
             let copied_hashes = patch
@@ -245,6 +408,7 @@ impl Database for PatchSet {
         Ok(self
             .patches_by_version
             .get(&version)
+            .filter(|patch| patch.internal[0].contains_key(&0)) // the root may be pruned
             .map(PartialPatchSet::root))
     }
 
@@ -274,6 +438,8 @@ impl Database for PatchSet {
         self.manifest = patch.manifest;
         self.patches_by_version.extend(patch.patches_by_version);
         self.sorted_new_leaves.extend(patch.sorted_new_leaves);
+        self.stale_keys_by_version
+            .extend(patch.stale_keys_by_version);
         Ok(())
     }
 
@@ -288,12 +454,64 @@ impl Database for PatchSet {
         // This requires a full scan, but we assume there aren't that many data in a patch (it's mostly used as a `Database` for testing).
         self.sorted_new_leaves
             .retain(|_, entry| entry.inserted_at < new_version_count);
+        self.stale_keys_by_version
+            .retain(|&version, _| version < new_version_count);
 
         self.manifest = manifest;
         Ok(())
     }
 }
 
+impl PruneDatabase for PatchSet {
+    fn min_stale_key_version(&self) -> Option<u64> {
+        self.stale_keys_by_version
+            .iter()
+            .filter_map(|(&version, keys)| (!keys.is_empty()).then_some(version))
+            .min()
+    }
+
+    fn stale_keys(&self, version: u64) -> Vec<NodeKey> {
+        self.stale_keys_by_version
+            .get(&version)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn prune(&mut self, patch: PrunePatchSet) -> anyhow::Result<()> {
+        for key in &patch.pruned_node_keys {
+            let Some(sub_patch) = self.patches_by_version.get_mut(&key.version) else {
+                continue;
+            };
+            let nibble_count = usize::from(key.nibble_count);
+            if nibble_count < sub_patch.internal.len() {
+                sub_patch.internal[nibble_count].remove(&key.index_on_level);
+            } else {
+                sub_patch.leaves.remove(&key.index_on_level);
+            }
+        }
+
+        self.stale_keys_by_version
+            .retain(|version, _| !patch.deleted_stale_key_versions.contains(version));
+        Ok(())
+    }
+}
+
+impl RecoveryDatabase for PatchSet {
+    fn apply_recovery_patch(&mut self, patch: PatchSet) -> anyhow::Result<()> {
+        self.manifest = patch.manifest;
+        for (version, sub_patch) in patch.patches_by_version {
+            match self.patches_by_version.entry(version) {
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(sub_patch);
+                }
+                hash_map::Entry::Occupied(entry) => entry.into_mut().merge(sub_patch),
+            }
+        }
+        self.sorted_new_leaves.extend(patch.sorted_new_leaves);
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct Patched<DB> {
     inner: DB,
@@ -478,3 +696,12 @@ impl<DB: Database> Database for Patched<DB> {
         Ok(())
     }
 }
+
+impl<DB: RecoveryDatabase> RecoveryDatabase for Patched<DB> {
+    /// Flushes changes held in RAM before merging the `patch` into the wrapped database, so that the recovery patch
+    /// is never overwritten by an older in-memory patch.
+    fn apply_recovery_patch(&mut self, patch: PatchSet) -> anyhow::Result<()> {
+        self.flush()?;
+        self.inner.apply_recovery_patch(patch)
+    }
+}
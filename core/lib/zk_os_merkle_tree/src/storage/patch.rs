@@ -132,25 +132,38 @@ impl TreeUpdate {
 pub(crate) struct FinalTreeUpdate {
     pub(super) version: u64,
     pub(super) sorted_new_leaves: BTreeMap<H256, InsertedKeyEntry>,
+    /// Keys of nodes from previous tree versions replaced by this update.
+    pub(super) stale_keys: Vec<NodeKey>,
 }
 
 impl PartialPatchSet {
     /// Updates ancestor's `ChildRef` version for all loaded internal nodes. This should be called before adding new leaves
     /// to the tree; it works because the loaded leaves are exactly the leaves for which ancestor versions must be updated.
-    fn update_ancestor_versions<P: TreeParams>(&mut self, version: u64) {
+    ///
+    /// Returns keys of the replaced nodes (i.e., nodes with the previous child ref versions), which become stale.
+    fn update_ancestor_versions<P: TreeParams>(&mut self, version: u64) -> Vec<NodeKey> {
         let mut indices: Vec<_> = self.leaves.keys().copied().collect();
         indices.sort_unstable();
+        let mut stale_keys = vec![];
 
-        for internal_level in self.internal.iter_mut().rev() {
+        for (i, internal_level) in self.internal.iter_mut().enumerate().rev() {
+            let child_nibble_count = i as u8 + 1;
             let mut prev_index = None;
             indices = indices
                 .into_iter()
                 .filter_map(|idx| {
                     let parent_idx = idx >> P::INTERNAL_NODE_DEPTH;
                     let parent = internal_level.get_mut(&parent_idx).unwrap();
-                    parent
-                        .child_mut((idx % u64::from(max_node_children::<P>())) as usize)
-                        .version = version;
+                    let child_ref =
+                        parent.child_mut((idx % u64::from(max_node_children::<P>())) as usize);
+                    if child_ref.version != version {
+                        stale_keys.push(NodeKey {
+                            version: child_ref.version,
+                            nibble_count: child_nibble_count,
+                            index_on_level: idx,
+                        });
+                    }
+                    child_ref.version = version;
 
                     if prev_index == Some(parent_idx) {
                         None
@@ -161,6 +174,7 @@ impl PartialPatchSet {
                 })
                 .collect();
         }
+        stale_keys
     }
 
     fn remove_readonly_nodes(&mut self, updated_version: u64) -> usize {
@@ -408,7 +422,7 @@ impl<P: TreeParams> WorkingPatchSet<P> {
         }
 
         // Update ancestor versions based on the remaining leaves.
-        this.update_ancestor_versions::<P>(version);
+        let stale_keys = this.update_ancestor_versions::<P>(version);
         if readonly_leaf_indices_len > 0 {
             // Filter out all internal nodes that were not updated (= don't have updated child refs).
             let removed_node_count = this.remove_readonly_nodes(version);
@@ -478,6 +492,7 @@ impl<P: TreeParams> WorkingPatchSet<P> {
         FinalTreeUpdate {
             version,
             sorted_new_leaves: update.sorted_new_leaves,
+            stale_keys,
         }
     }
 
@@ -526,6 +541,12 @@ impl<P: TreeParams> WorkingPatchSet<P> {
             internal_level.shrink_to_fit();
         }
 
+        let mut stale_keys = update.stale_keys;
+        if let Some(prev_version) = update.version.checked_sub(1) {
+            // The root is rewritten on each update, even if the tree hasn't changed.
+            stale_keys.push(NodeKey::root(prev_version));
+        }
+
         let patch = PatchSet {
             manifest: Manifest {
                 version_count: update.version + 1,
@@ -533,6 +554,7 @@ impl<P: TreeParams> WorkingPatchSet<P> {
             },
             patches_by_version: HashMap::from([(update.version, this)]),
             sorted_new_leaves: update.sorted_new_leaves,
+            stale_keys_by_version: HashMap::from([(update.version, stale_keys)]),
         };
         (patch, output)
     }
@@ -5,12 +5,19 @@ use std::{ops, path::Path};
 use anyhow::Context as _;
 use once_cell::sync::OnceCell;
 use zksync_basic_types::H256;
-use zksync_storage::{db::NamedColumnFamily, rocksdb, rocksdb::DBPinnableSlice, RocksDB};
+use zksync_storage::{
+    db::{NamedColumnFamily, WriteBatch},
+    rocksdb,
+    rocksdb::DBPinnableSlice,
+    RocksDB,
+};
 
 use crate::{
     errors::{DeserializeContext, DeserializeErrorKind},
     metrics::{LoadStage, METRICS},
-    storage::{InsertedKeyEntry, PartialPatchSet, PatchSet},
+    storage::{
+        InsertedKeyEntry, PartialPatchSet, PatchSet, PruneDatabase, PrunePatchSet, RecoveryDatabase,
+    },
     types::{InternalNode, KeyLookup, Leaf, Manifest, Node, NodeKey, Root},
     Database, DeserializeError,
 };
@@ -25,6 +32,24 @@ impl NodeKey {
         buffer[9..].copy_from_slice(&self.index_on_level.to_be_bytes());
         buffer
     }
+
+    fn from_db_key(buffer: &[u8]) -> Self {
+        assert_eq!(buffer.len(), Self::DB_KEY_LEN, "invalid node key length");
+        Self {
+            version: u64::from_be_bytes(buffer[..8].try_into().unwrap()),
+            nibble_count: buffer[8],
+            index_on_level: u64::from_be_bytes(buffer[9..].try_into().unwrap()),
+        }
+    }
+
+    /// Key in the `StaleKeys` column family: the version at which the node became stale, followed by the node key.
+    /// Ordering by the stale version first allows to efficiently iterate over stale keys during pruning.
+    fn as_stale_db_key(&self, stale_version: u64) -> [u8; 8 + Self::DB_KEY_LEN] {
+        let mut buffer = [0_u8; 8 + Self::DB_KEY_LEN];
+        buffer[..8].copy_from_slice(&stale_version.to_be_bytes());
+        buffer[8..].copy_from_slice(&self.as_db_key());
+        buffer
+    }
 }
 
 /// RocksDB column families used by the tree.
@@ -35,17 +60,19 @@ pub enum MerkleTreeColumnFamily {
     Tree,
     /// Resolves keys to (index, version) tuples.
     KeyIndices,
-    // TODO: stale keys
+    /// Column family containing stale node keys that are eventually removed by the pruning logic.
+    StaleKeys,
 }
 
 impl NamedColumnFamily for MerkleTreeColumnFamily {
     const DB_NAME: &'static str = "zkos_merkle_tree";
-    const ALL: &'static [Self] = &[Self::Tree, Self::KeyIndices];
+    const ALL: &'static [Self] = &[Self::Tree, Self::KeyIndices, Self::StaleKeys];
 
     fn name(&self) -> &'static str {
         match self {
             Self::Tree => "default",
             Self::KeyIndices => "key_indices",
+            Self::StaleKeys => "stale_keys",
         }
     }
 
@@ -222,10 +249,61 @@ impl RocksDBWrapper {
         })
     }
 
+    /// Writes all nodes from `sub_patch` to the `write_batch`. The root node is only written if it is present in the patch.
+    fn write_nodes(
+        write_batch: &mut WriteBatch<'_, MerkleTreeColumnFamily>,
+        version: u64,
+        sub_patch: PartialPatchSet,
+        leaf_nibbles: u8,
+        node_bytes: &mut Vec<u8>,
+    ) {
+        let tree_cf = MerkleTreeColumnFamily::Tree;
+        if sub_patch.internal[0].contains_key(&0) {
+            node_bytes.clear();
+            sub_patch.root().serialize(node_bytes);
+            write_batch.put_cf(tree_cf, &NodeKey::root(version).as_db_key(), node_bytes);
+        }
+
+        // The root is serialized above, hence `skip(1)`
+        for (i, level) in sub_patch.internal.into_iter().enumerate().skip(1) {
+            let nibble_count = i as u8;
+            for (index_on_level, node) in level {
+                let node_key = NodeKey {
+                    version,
+                    nibble_count,
+                    index_on_level,
+                };
+                node_bytes.clear();
+                node.serialize(node_bytes);
+                write_batch.put_cf(tree_cf, &node_key.as_db_key(), node_bytes);
+            }
+        }
+
+        for (index_on_level, leaf) in sub_patch.leaves {
+            let node_key = NodeKey {
+                version,
+                nibble_count: leaf_nibbles,
+                index_on_level,
+            };
+            node_bytes.clear();
+            leaf.serialize(node_bytes);
+            write_batch.put_cf(tree_cf, &node_key.as_db_key(), node_bytes);
+        }
+    }
+
     /// Returns the wrapped RocksDB instance.
     pub fn into_inner(self) -> RocksDB<MerkleTreeColumnFamily> {
         self.db
     }
+
+    /// Returns the total number of nodes (including roots) across all tree versions stored in the DB.
+    #[cfg(test)]
+    pub(crate) fn node_count(&self) -> usize {
+        self.db
+            .prefix_iterator_cf(MerkleTreeColumnFamily::Tree, &[])
+            .filter(|(raw_key, _)| **raw_key != *Self::MANIFEST_KEY)
+            .count()
+    }
 }
 
 impl From<RocksDB<MerkleTreeColumnFamily>> for RocksDBWrapper {
@@ -329,6 +407,7 @@ impl Database for RocksDBWrapper {
 
         let copied_hashes = patch.copied_hashes_count();
         let new_leaves = patch.sorted_new_leaves.len();
+        let stale_keys: usize = patch.stale_keys_by_version.values().map(Vec::len).sum();
         let total_leaves: usize = patch
             .patches_by_version
             .values()
@@ -354,41 +433,26 @@ impl Database for RocksDBWrapper {
         }
 
         for (version, sub_patch) in patch.patches_by_version {
-            let root_key = NodeKey::root(version);
             // Delete the key range corresponding to the entire new version. This removes
             // potential garbage left after reverting the tree to a previous version.
+            let root_key = NodeKey::root(version);
             let next_root_key = NodeKey::root(version + 1);
             let keys_to_delete = &root_key.as_db_key()[..]..&next_root_key.as_db_key()[..];
             write_batch.delete_range_cf(tree_cf, keys_to_delete);
 
-            node_bytes.clear();
-            sub_patch.root().serialize(&mut node_bytes);
-            write_batch.put_cf(tree_cf, &root_key.as_db_key(), &node_bytes);
-
-            // The root is serialized above, hence `skip(1)`
-            for (i, level) in sub_patch.internal.into_iter().enumerate().skip(1) {
-                let nibble_count = i as u8;
-                for (index_on_level, node) in level {
-                    let node_key = NodeKey {
-                        version,
-                        nibble_count,
-                        index_on_level,
-                    };
-                    node_bytes.clear();
-                    node.serialize(&mut node_bytes);
-                    write_batch.put_cf(tree_cf, &node_key.as_db_key(), &node_bytes);
-                }
-            }
+            Self::write_nodes(
+                &mut write_batch,
+                version,
+                sub_patch,
+                leaf_nibbles,
+                &mut node_bytes,
+            );
+        }
 
-            for (index_on_level, leaf) in sub_patch.leaves {
-                let node_key = NodeKey {
-                    version,
-                    nibble_count: leaf_nibbles,
-                    index_on_level,
-                };
-                node_bytes.clear();
-                leaf.serialize(&mut node_bytes);
-                write_batch.put_cf(tree_cf, &node_key.as_db_key(), &node_bytes);
+        let stale_keys_cf = MerkleTreeColumnFamily::StaleKeys;
+        for (version, keys) in patch.stale_keys_by_version {
+            for key in keys {
+                write_batch.put_cf(stale_keys_cf, &key.as_stale_db_key(version), &[]);
             }
         }
 
@@ -400,12 +464,14 @@ impl Database for RocksDBWrapper {
             .apply_patch_internal_nodes_count
             .observe(total_internal_nodes);
         METRICS.apply_patch_copied_hashes.observe(copied_hashes);
+        METRICS.apply_patch_stale_keys_count.observe(stale_keys);
         tracing::debug!(
             total_size = write_batch.size_in_bytes(),
             new_leaves,
             total_leaves,
             total_internal_nodes,
             copied_hashes,
+            stale_keys,
             "writing to RocksDB"
         );
 
@@ -475,6 +541,15 @@ impl Database for RocksDBWrapper {
             first_new_leaf_index = new_leaf_count;
         }
 
+        // Remove stale keys produced by the truncated versions. Otherwise, they could be used by the pruner
+        // once the versions are recreated, which would lead to removing non-stale nodes.
+        let first_version = &manifest.version_count.to_be_bytes() as &[_];
+        let last_version = &truncated_versions.end.to_be_bytes();
+        write_batch.delete_range_cf(
+            MerkleTreeColumnFamily::StaleKeys,
+            first_version..last_version,
+        );
+
         self.db
             .write(write_batch)
             .context("Failed writing a batch to RocksDB")?;
@@ -482,6 +557,46 @@ impl Database for RocksDBWrapper {
     }
 }
 
+impl PruneDatabase for RocksDBWrapper {
+    fn min_stale_key_version(&self) -> Option<u64> {
+        let stale_keys_cf = MerkleTreeColumnFamily::StaleKeys;
+        let (raw_key, _) = self.db.prefix_iterator_cf(stale_keys_cf, &[]).next()?;
+        let version_prefix: [u8; 8] = raw_key[..8].try_into().unwrap();
+        Some(u64::from_be_bytes(version_prefix))
+    }
+
+    fn stale_keys(&self, version: u64) -> Vec<NodeKey> {
+        let stale_keys_cf = MerkleTreeColumnFamily::StaleKeys;
+        let version_prefix = version.to_be_bytes();
+        let keys = self
+            .db
+            .prefix_iterator_cf(stale_keys_cf, &version_prefix)
+            .map(|(raw_key, _)| {
+                debug_assert_eq!(raw_key[..8], version_prefix);
+                NodeKey::from_db_key(&raw_key[8..])
+            });
+        keys.collect()
+    }
+
+    fn prune(&mut self, patch: PrunePatchSet) -> anyhow::Result<()> {
+        let mut write_batch = self.db.new_write_batch();
+
+        let tree_cf = MerkleTreeColumnFamily::Tree;
+        for pruned_key in patch.pruned_node_keys {
+            write_batch.delete_cf(tree_cf, &pruned_key.as_db_key());
+        }
+
+        let stale_keys_cf = MerkleTreeColumnFamily::StaleKeys;
+        let first_version = &patch.deleted_stale_key_versions.start.to_be_bytes() as &[_];
+        let last_version = &patch.deleted_stale_key_versions.end.to_be_bytes();
+        write_batch.delete_range_cf(stale_keys_cf, first_version..last_version);
+
+        self.db
+            .write(write_batch)
+            .context("Failed writing a batch to RocksDB")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::{BTreeMap, HashMap};
@@ -706,3 +821,44 @@ mod tests {
         assert_eq!(all_keys, [H256::zero(), H256::repeat_byte(0xff)]);
     }
 }
+
+impl RecoveryDatabase for RocksDBWrapper {
+    fn apply_recovery_patch(&mut self, patch: PatchSet) -> anyhow::Result<()> {
+        let leaf_nibbles = self.set_leaf_nibbles(&patch.manifest)?;
+        let mut write_batch = self.db.new_write_batch();
+        let mut node_bytes = Vec::with_capacity(128);
+
+        patch.manifest.serialize(&mut node_bytes);
+        write_batch.put_cf(
+            MerkleTreeColumnFamily::Tree,
+            Self::MANIFEST_KEY,
+            &node_bytes,
+        );
+
+        for (key, entry) in patch.sorted_new_leaves {
+            node_bytes.clear();
+            entry.serialize(&mut node_bytes);
+            write_batch.put_cf(
+                MerkleTreeColumnFamily::KeyIndices,
+                key.as_bytes(),
+                &node_bytes,
+            );
+        }
+
+        // Unlike `apply_patch()`, existing nodes for the version are retained.
+        for (version, sub_patch) in patch.patches_by_version {
+            Self::write_nodes(
+                &mut write_batch,
+                version,
+                sub_patch,
+                leaf_nibbles,
+                &mut node_bytes,
+            );
+        }
+
+        self.db
+            .write(write_batch)
+            .context("Failed writing a batch to RocksDB")?;
+        Ok(())
+    }
+}
@@ -162,6 +162,7 @@ impl TreeTags {
         let mut hasher = None;
         let mut depth = None;
         let mut internal_node_depth = None;
+        let mut is_recovering = false;
 
         for _ in 0..tag_count {
             let key = Self::deserialize_str(bytes)?;
@@ -189,6 +190,14 @@ impl TreeTags {
                             })?;
                     internal_node_depth = Some(parsed);
                 }
+                "is_recovering" => {
+                    is_recovering = value.parse::<bool>().map_err(|err| {
+                        DeserializeErrorKind::MalformedTag {
+                            name: "is_recovering",
+                            err: err.into(),
+                        }
+                    })?;
+                }
                 _ => return Err(DeserializeErrorKind::UnknownTag(key.to_owned()).into()),
             }
         }
@@ -198,11 +207,13 @@ impl TreeTags {
             internal_node_depth: internal_node_depth
                 .ok_or(DeserializeErrorKind::MissingTag("internal_node_depth"))?,
             hasher: hasher.ok_or(DeserializeErrorKind::MissingTag("hasher"))?,
+            is_recovering,
         })
     }
 
     fn serialize(&self, buffer: &mut Vec<u8>) {
-        let entry_count = 4; // custom tags aren't supported (yet?)
+        // Custom tags aren't supported (yet?). The recovery flag is only persisted while it's set.
+        let entry_count = if self.is_recovering { 5 } else { 4 };
         leb128::write::unsigned(buffer, entry_count).unwrap();
 
         Self::serialize_str(buffer, "architecture");
@@ -213,6 +224,10 @@ impl TreeTags {
         Self::serialize_str(buffer, &self.internal_node_depth.to_string());
         Self::serialize_str(buffer, "hasher");
         Self::serialize_str(buffer, &self.hasher);
+        if self.is_recovering {
+            Self::serialize_str(buffer, "is_recovering");
+            Self::serialize_str(buffer, "true");
+        }
     }
 }
 
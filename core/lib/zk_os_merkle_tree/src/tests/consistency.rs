@@ -2,7 +2,13 @@ use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use tempfile::TempDir;
 use zksync_basic_types::H256;
 
-use crate::{MerkleTree, MerkleTreeColumnFamily, RocksDBWrapper, TreeEntry};
+use crate::{
+    consistency::ConsistencyError,
+    leaf_nibbles,
+    types::{Node, NodeKey},
+    Database, DefaultTreeParams, MerkleTree, MerkleTreeColumnFamily, PatchSet, RecoveryDatabase,
+    RocksDBWrapper, TreeEntry,
+};
 
 // Something (maybe RocksDB) makes the test below work very slowly in the debug mode;
 // thus, the number of test cases is conditionally reduced.
@@ -66,3 +72,120 @@ fn five_thousand_angry_monkeys_vs_merkle_tree() {
         raw_db.write(reverse_batch).unwrap();
     }
 }
+
+fn create_patch_set_tree() -> PatchSet {
+    let mut rng = StdRng::seed_from_u64(123);
+    let mut db = PatchSet::default();
+    let mut tree = MerkleTree::new(&mut db).unwrap();
+    let entries: Vec<_> = (0..100)
+        .map(|_| TreeEntry {
+            key: H256(rng.gen()),
+            value: H256(rng.gen()),
+        })
+        .collect();
+    tree.extend(&entries).unwrap();
+
+    let updated_entries: Vec<_> = entries
+        .iter()
+        .step_by(7)
+        .map(|entry| TreeEntry {
+            key: entry.key,
+            value: H256(rng.gen()),
+        })
+        .collect();
+    tree.extend(&updated_entries).unwrap();
+    db
+}
+
+/// Overwrites the hash of the first child of the specified internal node.
+fn damage_node(db: &mut PatchSet, key: NodeKey) {
+    let manifest = db.try_manifest().unwrap().unwrap();
+    let leaf_nibbles = leaf_nibbles::<DefaultTreeParams>();
+    let patch = if key.nibble_count == 0 {
+        let mut root = db.try_root(key.version).unwrap().unwrap();
+        root.root_node.children[0].hash = H256::repeat_byte(0x23);
+        PatchSet::for_repair(
+            manifest,
+            leaf_nibbles,
+            Some((key.version, root)),
+            std::iter::empty(),
+        )
+    } else {
+        let Node::Internal(mut node) = db.try_nodes(&[key]).unwrap().remove(0) else {
+            panic!("unexpected leaf at {key}");
+        };
+        node.children[0].hash = H256::repeat_byte(0x23);
+        PatchSet::for_repair(manifest, leaf_nibbles, None, [(key, node)].into_iter())
+    };
+    db.apply_recovery_patch(patch).unwrap();
+}
+
+#[test]
+fn repairing_consistent_tree_is_noop() {
+    let mut db = create_patch_set_tree();
+    let mut tree = MerkleTree::new(&mut db).unwrap();
+    let root_hash = tree.latest_root_hash().unwrap().unwrap();
+
+    for version in [0, 1] {
+        let stats = tree.repair(version).unwrap();
+        assert_eq!(stats.repaired_node_count, 0);
+        assert_eq!(stats.root_hash, tree.root_info(version).unwrap().unwrap().0);
+    }
+    assert_eq!(tree.latest_root_hash().unwrap(), Some(root_hash));
+}
+
+#[test]
+fn repairing_damaged_internal_nodes() {
+    let mut db = create_patch_set_tree();
+    let root = db.try_root(1).unwrap().unwrap();
+    let root_hash = MerkleTree::new(&mut db)
+        .unwrap()
+        .latest_root_hash()
+        .unwrap()
+        .unwrap();
+
+    let mut damaged_keys = vec![NodeKey::root(1)];
+    // Damage a node on each internal level on the leftmost path of the tree.
+    let mut node_version = root.root_node.children[0].version;
+    for nibble_count in 1..leaf_nibbles::<DefaultTreeParams>() {
+        let key = NodeKey {
+            version: node_version,
+            nibble_count,
+            index_on_level: 0,
+        };
+        let Node::Internal(node) = db.try_nodes(&[key]).unwrap().remove(0) else {
+            panic!("unexpected leaf at {key}");
+        };
+        node_version = node.children[0].version;
+        damaged_keys.push(key);
+    }
+    for &key in &damaged_keys {
+        damage_node(&mut db, key);
+    }
+
+    let mut tree = MerkleTree::new(&mut db).unwrap();
+    let err = tree.verify_consistency(1).unwrap_err();
+    assert!(
+        matches!(err, ConsistencyError::HashMismatch { .. }),
+        "{err:?}"
+    );
+
+    let stats = tree.repair(1).unwrap();
+    assert_eq!(stats.repaired_node_count, damaged_keys.len());
+    assert_eq!(stats.root_hash, root_hash);
+    assert_eq!(tree.latest_root_hash().unwrap(), Some(root_hash));
+    tree.verify_consistency(1).unwrap();
+    // Repaired nodes may be shared with the previous version, which should remain consistent.
+    tree.verify_consistency(0).unwrap();
+
+    let stats = tree.repair(1).unwrap();
+    assert_eq!(stats.repaired_node_count, 0);
+}
+
+#[test]
+fn repair_errors_on_missing_version() {
+    let mut db = create_patch_set_tree();
+    let mut tree = MerkleTree::new(&mut db).unwrap();
+    let err = tree.repair(2).unwrap_err().to_string();
+    assert!(err.contains("does not exist"), "{err}");
+}
@@ -526,6 +526,72 @@ fn read_proofs() {
     test_read_proofs(PatchSet::default());
 }
 
+fn collect_entries<DB: Database>(tree: &MerkleTree<DB>, version: u64) -> Vec<(u64, TreeEntry)> {
+    let mut entries = vec![];
+    tree.for_each_entry(version, |index, entry| {
+        entries.push((index, entry));
+        Ok(())
+    })
+    .unwrap();
+    entries
+}
+
+fn test_iterating_over_entries(db: impl Database) {
+    const RNG_SEED: u64 = 42;
+
+    let mut rng = StdRng::seed_from_u64(RNG_SEED);
+    let mut tree = MerkleTree::new(db).unwrap();
+    tree.extend(&[]).unwrap();
+    assert!(collect_entries(&tree, 0).is_empty());
+
+    let inserts: Vec<_> = (0..100)
+        .map(|_| TreeEntry {
+            key: H256(rng.gen()),
+            value: H256(rng.gen()),
+        })
+        .collect();
+    tree.extend(&inserts).unwrap();
+    let mut expected_entries: BTreeMap<_, _> = (2..).zip(inserts.iter().copied()).collect();
+    assert_eq!(
+        collect_entries(&tree, 1),
+        expected_entries.clone().into_iter().collect::<Vec<_>>()
+    );
+
+    let mut updates: Vec<_> = inserts.iter().step_by(2).copied().collect();
+    for update in &mut updates {
+        update.value = H256(rng.gen());
+    }
+    let new_inserts = (0..50).map(|_| TreeEntry {
+        key: H256(rng.gen()),
+        value: H256(rng.gen()),
+    });
+    updates.extend(new_inserts);
+    tree.extend(&updates).unwrap();
+
+    for (i, update) in updates.iter().enumerate() {
+        let index = if i < 50 {
+            2 * i as u64 + 2
+        } else {
+            i as u64 + 52
+        };
+        expected_entries.insert(index, *update);
+    }
+    assert_eq!(
+        collect_entries(&tree, 2),
+        expected_entries.into_iter().collect::<Vec<_>>()
+    );
+    // Previous versions must be unaffected.
+    assert_eq!(collect_entries(&tree, 1).len(), 100);
+
+    let err = tree.for_each_entry(3, |_, _| Ok(())).unwrap_err();
+    assert!(err.to_string().contains("does not exist"), "{err}");
+}
+
+#[test]
+fn iterating_over_entries() {
+    test_iterating_over_entries(PatchSet::default());
+}
+
 mod rocksdb {
     use serde::{Deserialize, Serialize};
     use serde_with::{hex::Hex, serde_as};
@@ -624,6 +690,13 @@ mod rocksdb {
         test_comparing_tree_hash_with_updates(db);
     }
 
+    #[test]
+    fn iterating_over_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = RocksDBWrapper::new(temp_dir.path()).unwrap();
+        test_iterating_over_entries(db);
+    }
+
     #[test]
     fn extending_tree_with_proof() {
         let temp_dir = TempDir::new().unwrap();
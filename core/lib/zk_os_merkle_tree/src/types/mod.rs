@@ -226,6 +226,8 @@ pub(crate) struct TreeTags {
     pub depth: u8,
     pub internal_node_depth: u8,
     pub hasher: String,
+    /// Set while the tree is being recovered from a snapshot.
+    pub is_recovering: bool,
 }
 
 impl Default for TreeTags {
@@ -243,13 +245,19 @@ impl TreeTags {
             depth: P::TREE_DEPTH,
             internal_node_depth: P::INTERNAL_NODE_DEPTH,
             hasher: hasher.name().to_owned(),
+            is_recovering: false,
         }
     }
 
     pub(crate) fn ensure_consistency<P: TreeParams>(
         &self,
         hasher: &P::Hasher,
+        allow_recovering: bool,
     ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            allow_recovering || !self.is_recovering,
+            "Merkle tree is being recovered; it cannot be used until recovery is finalized"
+        );
         anyhow::ensure!(
             self.architecture == Self::ARCHITECTURE,
             "Unsupported tree architecture `{}`, expected `{}`",
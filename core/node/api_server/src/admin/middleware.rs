@@ -0,0 +1,123 @@
+use std::fmt;
+
+use futures::future::BoxFuture;
+use tower_http::validate_request::ValidateRequest;
+use zksync_web3_decl::jsonrpsee::{
+    server::{middleware::rpc::RpcServiceT, HttpBody},
+    types::Request,
+    MethodResponse,
+};
+
+use super::AUDIT_LOG_TARGET;
+
+/// Checks that HTTP requests contain the expected bearer token.
+#[derive(Clone)]
+pub(super) struct AdminTokenValidator {
+    expected_header: Vec<u8>,
+}
+
+impl fmt::Debug for AdminTokenValidator {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AdminTokenValidator")
+            .finish_non_exhaustive()
+    }
+}
+
+impl AdminTokenValidator {
+    pub(super) fn new(token: &str) -> Self {
+        Self {
+            expected_header: format!("Bearer {token}").into_bytes(),
+        }
+    }
+
+    /// Compares the header in constant time w.r.t. the compared bytes, so that the token cannot be guessed
+    /// based on response timings.
+    fn is_valid(&self, header: &[u8]) -> bool {
+        header.len() == self.expected_header.len()
+            && header
+                .iter()
+                .zip(&self.expected_header)
+                .fold(0_u8, |acc, (x, y)| acc | (x ^ y))
+                == 0
+    }
+}
+
+impl<B> ValidateRequest<B> for AdminTokenValidator {
+    type ResponseBody = HttpBody;
+
+    fn validate(
+        &mut self,
+        request: &mut http::Request<B>,
+    ) -> Result<(), http::Response<Self::ResponseBody>> {
+        let header = request.headers().get(http::header::AUTHORIZATION);
+        if header.is_some_and(|header| self.is_valid(header.as_bytes())) {
+            return Ok(());
+        }
+
+        tracing::warn!(
+            target: AUDIT_LOG_TARGET,
+            "Rejected admin API request with missing or invalid token"
+        );
+        let mut response = http::Response::new(HttpBody::from("Unauthorized".to_owned()));
+        *response.status_mut() = http::StatusCode::UNAUTHORIZED;
+        Err(response)
+    }
+}
+
+/// RPC-level middleware logging all calls together with their params and outcome.
+#[derive(Debug)]
+pub(super) struct AuditMiddleware<S> {
+    inner: S,
+}
+
+impl<S> AuditMiddleware<S> {
+    pub(super) fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for AuditMiddleware<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+    S::Future: 'a,
+{
+    type Future = BoxFuture<'a, MethodResponse>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let method = request.method_name().to_owned();
+        let params = request.params().as_str().unwrap_or("[]").to_owned();
+        tracing::info!(target: AUDIT_LOG_TARGET, %method, %params, "Admin API call");
+
+        let response = self.inner.call(request);
+        Box::pin(async move {
+            let response = response.await;
+            if response.is_success() {
+                tracing::info!(target: AUDIT_LOG_TARGET, %method, "Admin API call succeeded");
+            } else {
+                tracing::warn!(
+                    target: AUDIT_LOG_TARGET,
+                    %method,
+                    response = response.as_result(),
+                    "Admin API call failed"
+                );
+            }
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validating_token() {
+        let validator = AdminTokenValidator::new("secret");
+        assert!(validator.is_valid(b"Bearer secret"));
+        assert!(!validator.is_valid(b"Bearer secreT"));
+        assert!(!validator.is_valid(b"Bearer secret2"));
+        assert!(!validator.is_valid(b"secret"));
+        assert!(!validator.is_valid(b""));
+    }
+}
@@ -0,0 +1,339 @@
+//! Admin JSON-RPC server allowing node operators to control the node at runtime.
+//!
+//! The server is served on a separate port from the Web3 API and requires a bearer token for all requests.
+//! All calls are logged with the [`AUDIT_LOG_TARGET`] target.
+
+use std::{fmt, net::SocketAddr, sync::Arc};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use secrecy::ExposeSecret;
+use tokio::sync::{oneshot, watch};
+use tower_http::validate_request::ValidateRequestHeaderLayer;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_health_check::AppHealthCheck;
+use zksync_state_keeper::{MempoolGuard, StateKeeperControl};
+use zksync_types::{
+    api::{NodeQueueLengths, NodeStatus, VmDivergence},
+    secrets::APIKey,
+    L1BatchNumber,
+};
+use zksync_web3_decl::{
+    jsonrpsee::{
+        core::RpcResult,
+        server::{RpcServiceBuilder, ServerBuilder},
+        types::{error::ErrorCode, ErrorObjectOwned},
+    },
+    namespaces::AdminNamespaceServer,
+};
+
+use self::middleware::{AdminTokenValidator, AuditMiddleware};
+
+mod middleware;
+#[cfg(test)]
+mod tests;
+
+/// Tracing target used for audit logs of admin API calls.
+pub const AUDIT_LOG_TARGET: &str = "zksync_admin_audit";
+
+/// Maximum number of VM divergences returned by `admin_getVmDivergences`.
+const MAX_VM_DIVERGENCES_LIMIT: usize = 1_000;
+
+/// Handle allowing to read and change log filter directives at runtime.
+pub trait LogFilterHandle: 'static + fmt::Debug + Send + Sync {
+    /// Returns the current directives.
+    fn directives(&self) -> String;
+
+    /// Replaces the directives.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directives cannot be parsed.
+    fn set_directives(&self, directives: &str) -> anyhow::Result<()>;
+}
+
+impl LogFilterHandle for zksync_vlog::LogsReloadHandle {
+    fn directives(&self) -> String {
+        self.directives()
+    }
+
+    fn set_directives(&self, directives: &str) -> anyhow::Result<()> {
+        self.set_directives(directives)
+    }
+}
+
+/// Handle allowing to reload the node config at runtime.
+pub trait ConfigReloadHandle: 'static + fmt::Debug + Send + Sync {
+    /// Reloads the config. Returns `Ok(false)` if the reloadable part of the config hasn't changed.
+    /// This method is blocking; it's called on a blocking thread by the namespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config cannot be loaded, or if it contains changes that cannot be applied at runtime.
+    fn reload(&self) -> anyhow::Result<bool>;
+}
+
+/// Implementation of the `admin_` namespace. Components not provided to the namespace are considered
+/// to be absent on the node; the corresponding methods return an error.
+#[derive(Debug, Clone)]
+pub struct AdminNamespace {
+    pool: ConnectionPool<Core>,
+    state_keeper: Option<StateKeeperControl>,
+    mempool: Option<MempoolGuard>,
+    log_filter: Option<Arc<dyn LogFilterHandle>>,
+    config_reloader: Option<Arc<dyn ConfigReloadHandle>>,
+    app_health: Option<Arc<AppHealthCheck>>,
+}
+
+impl AdminNamespace {
+    pub fn new(pool: ConnectionPool<Core>) -> Self {
+        Self {
+            pool,
+            state_keeper: None,
+            mempool: None,
+            log_filter: None,
+            config_reloader: None,
+            app_health: None,
+        }
+    }
+
+    pub fn with_state_keeper_control(mut self, control: StateKeeperControl) -> Self {
+        self.state_keeper = Some(control);
+        self
+    }
+
+    pub fn with_mempool(mut self, mempool: MempoolGuard) -> Self {
+        self.mempool = Some(mempool);
+        self
+    }
+
+    pub fn with_log_filter(mut self, handle: Arc<dyn LogFilterHandle>) -> Self {
+        self.log_filter = Some(handle);
+        self
+    }
+
+    pub fn with_config_reloader(mut self, handle: Arc<dyn ConfigReloadHandle>) -> Self {
+        self.config_reloader = Some(handle);
+        self
+    }
+
+    pub fn with_app_health(mut self, app_health: Arc<AppHealthCheck>) -> Self {
+        self.app_health = Some(app_health);
+        self
+    }
+
+    fn unavailable(component: &str) -> ErrorObjectOwned {
+        ErrorObjectOwned::owned(
+            ErrorCode::MethodNotFound.code(),
+            format!("{component} is not available on this node"),
+            None::<()>,
+        )
+    }
+
+    /// Unlike the Web3 API, internal errors are returned to the caller since it's the node operator.
+    fn internal_error(err: anyhow::Error) -> ErrorObjectOwned {
+        ErrorObjectOwned::owned(
+            ErrorCode::InternalError.code(),
+            format!("{err:#}"),
+            None::<()>,
+        )
+    }
+
+    fn state_keeper(&self) -> RpcResult<&StateKeeperControl> {
+        self.state_keeper
+            .as_ref()
+            .ok_or_else(|| Self::unavailable("State keeper"))
+    }
+
+    fn log_filter(&self) -> RpcResult<&dyn LogFilterHandle> {
+        self.log_filter
+            .as_deref()
+            .ok_or_else(|| Self::unavailable("Log filter reloading"))
+    }
+
+    async fn queue_lengths(&self) -> anyhow::Result<NodeQueueLengths> {
+        let mut connection = self.pool.connection_tagged("admin_api").await?;
+        let l1_to_l2_txs_in_mempool = connection
+            .transactions_dal()
+            .get_priority_txs_in_mempool()
+            .await?;
+        let unconfirmed_eth_txs = connection
+            .eth_sender_dal()
+            .get_unconfirmed_txs_count()
+            .await?;
+        drop(connection);
+
+        let mempool_stats = self.mempool.as_ref().map(MempoolGuard::stats);
+        Ok(NodeQueueLengths {
+            state_keeper_l1_txs: mempool_stats
+                .as_ref()
+                .map(|stats| stats.l1_transaction_count),
+            state_keeper_l2_txs: mempool_stats
+                .as_ref()
+                .map(|stats| stats.l2_transaction_count),
+            l1_to_l2_txs_in_mempool,
+            unconfirmed_eth_txs,
+        })
+    }
+}
+
+#[async_trait]
+impl AdminNamespaceServer for AdminNamespace {
+    async fn pause_state_keeper(&self) -> RpcResult<bool> {
+        Ok(self.state_keeper()?.pause())
+    }
+
+    async fn resume_state_keeper(&self) -> RpcResult<bool> {
+        Ok(self.state_keeper()?.resume())
+    }
+
+    async fn seal_l1_batch(&self) -> RpcResult<()> {
+        self.state_keeper()?.request_l1_batch_seal();
+        Ok(())
+    }
+
+    async fn clear_failed_l1_transactions(&self) -> RpcResult<()> {
+        let mut connection = self
+            .pool
+            .connection_tagged("admin_api")
+            .await
+            .map_err(|err| Self::internal_error(err.generalize()))?;
+        connection
+            .eth_sender_dal()
+            .clear_failed_transactions()
+            .await
+            .map_err(|err| Self::internal_error(err.into()))
+    }
+
+    async fn get_log_filter(&self) -> RpcResult<String> {
+        Ok(self.log_filter()?.directives())
+    }
+
+    async fn set_log_filter(&self, directives: String) -> RpcResult<()> {
+        self.log_filter()?
+            .set_directives(&directives)
+            .map_err(|err| {
+                ErrorObjectOwned::owned(
+                    ErrorCode::InvalidParams.code(),
+                    format!("{err:#}"),
+                    None::<()>,
+                )
+            })
+    }
+
+    async fn reload_config(&self) -> RpcResult<bool> {
+        let reloader = self
+            .config_reloader
+            .clone()
+            .ok_or_else(|| Self::unavailable("Config reloading"))?;
+        tokio::task::spawn_blocking(move || reloader.reload())
+            .await
+            .context("panicked while reloading config")
+            .and_then(|res| res)
+            .map_err(Self::internal_error)
+    }
+
+    async fn get_node_status(&self) -> RpcResult<NodeStatus> {
+        let queue_lengths = self.queue_lengths().await.map_err(Self::internal_error)?;
+        let health = if let Some(app_health) = &self.app_health {
+            let health = app_health.check_health().await;
+            Some(serde_json::to_value(health).map_err(|err| Self::internal_error(err.into()))?)
+        } else {
+            None
+        };
+        Ok(NodeStatus {
+            state_keeper_paused: self
+                .state_keeper
+                .as_ref()
+                .map(StateKeeperControl::is_paused),
+            queue_lengths,
+            health,
+        })
+    }
+
+    async fn get_vm_divergences(
+        &self,
+        from_l1_batch: L1BatchNumber,
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<VmDivergence>> {
+        let limit = limit
+            .unwrap_or(MAX_VM_DIVERGENCES_LIMIT)
+            .min(MAX_VM_DIVERGENCES_LIMIT);
+        let mut connection = self
+            .pool
+            .connection_tagged("admin_api")
+            .await
+            .map_err(|err| Self::internal_error(err.generalize()))?;
+        connection
+            .vm_divergences_dal()
+            .get_divergences(from_l1_batch, limit)
+            .await
+            .map_err(|err| Self::internal_error(err.generalize()))
+    }
+}
+
+/// Admin JSON-RPC server. Only HTTP transport is supported.
+#[derive(Debug)]
+pub struct AdminServer {
+    bind_addr: SocketAddr,
+    token: APIKey,
+    namespace: AdminNamespace,
+}
+
+impl AdminServer {
+    /// Creates a server. All requests must contain `Authorization: Bearer {token}` header.
+    pub fn new(bind_addr: SocketAddr, token: APIKey, namespace: AdminNamespace) -> Self {
+        Self {
+            bind_addr,
+            token,
+            namespace,
+        }
+    }
+
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.run_inner(stop_receiver, None).await
+    }
+
+    async fn run_inner(
+        self,
+        mut stop_receiver: watch::Receiver<bool>,
+        local_addr_sender: Option<oneshot::Sender<SocketAddr>>,
+    ) -> anyhow::Result<()> {
+        let token = self.token.0.expose_secret();
+        anyhow::ensure!(!token.is_empty(), "admin API token must not be empty");
+
+        let http_middleware = tower::ServiceBuilder::new().layer(
+            ValidateRequestHeaderLayer::custom(AdminTokenValidator::new(token)),
+        );
+        let rpc_middleware = RpcServiceBuilder::new().layer_fn(AuditMiddleware::new);
+        let server = ServerBuilder::default()
+            .http_only()
+            .set_http_middleware(http_middleware)
+            .set_rpc_middleware(rpc_middleware)
+            .build(self.bind_addr)
+            .await
+            .context("Failed building admin JSON-RPC server")?;
+        let local_addr = server
+            .local_addr()
+            .context("Failed getting local address for admin JSON-RPC server")?;
+        let server_handle = server.start(self.namespace.into_rpc());
+        tracing::info!("Initialized admin API on {local_addr:?}");
+        if let Some(sender) = local_addr_sender {
+            sender.send(local_addr).ok();
+        }
+
+        let close_handle = server_handle.clone();
+        tokio::spawn(async move {
+            if stop_receiver.changed().await.is_err() {
+                tracing::warn!(
+                    "Stop signal sender for admin JSON-RPC server was dropped without sending a signal"
+                );
+            }
+            tracing::info!("Stop signal received, admin JSON-RPC server is shutting down");
+            close_handle.stop().ok();
+        });
+        server_handle.stopped().await;
+        tracing::info!("Admin JSON-RPC server stopped");
+        Ok(())
+    }
+}
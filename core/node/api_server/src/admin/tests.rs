@@ -0,0 +1,209 @@
+use serde_json::json;
+use tokio::task::JoinHandle;
+use zksync_web3_decl::jsonrpsee::types::error::ErrorCode;
+
+use super::*;
+
+const TOKEN: &str = "admin-token";
+
+#[derive(Debug)]
+struct TestServer {
+    url: String,
+    client: reqwest::Client,
+    stop_sender: watch::Sender<bool>,
+    server_task: JoinHandle<anyhow::Result<()>>,
+}
+
+impl TestServer {
+    async fn new(namespace: AdminNamespace) -> Self {
+        let server = AdminServer::new(([127, 0, 0, 1], 0).into(), TOKEN.into(), namespace);
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let (local_addr_sender, local_addr) = oneshot::channel();
+        let server_task = tokio::spawn(server.run_inner(stop_receiver, Some(local_addr_sender)));
+        let local_addr = local_addr.await.expect("server failed to start");
+        Self {
+            url: format!("http://{local_addr}"),
+            client: reqwest::Client::new(),
+            stop_sender,
+            server_task,
+        }
+    }
+
+    async fn send(&self, token: Option<&str>, method: &str) -> reqwest::Response {
+        self.send_with_params(token, method, json!([])).await
+    }
+
+    async fn send_with_params(
+        &self,
+        token: Option<&str>,
+        method: &str,
+        params: serde_json::Value,
+    ) -> reqwest::Response {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await.unwrap()
+    }
+
+    async fn call(&self, method: &str) -> serde_json::Value {
+        self.call_with_params(method, json!([])).await
+    }
+
+    async fn call_with_params(&self, method: &str, params: serde_json::Value) -> serde_json::Value {
+        let response = self.send_with_params(Some(TOKEN), method, params).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        serde_json::from_str(&response.text().await.unwrap()).unwrap()
+    }
+
+    async fn stop(self) {
+        self.stop_sender.send_replace(true);
+        self.server_task.await.unwrap().unwrap();
+    }
+}
+
+#[tokio::test]
+async fn requests_without_valid_token_are_rejected() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let server = TestServer::new(AdminNamespace::new(pool)).await;
+
+    for token in [None, Some("wrong-token")] {
+        let response = server.send(token, "admin_getNodeStatus").await;
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    }
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn controlling_state_keeper() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    let mempool = MempoolGuard::from_storage(&mut storage, 100).await;
+    drop(storage);
+    let control = StateKeeperControl::default();
+    let namespace = AdminNamespace::new(pool)
+        .with_state_keeper_control(control.clone())
+        .with_mempool(mempool);
+    let server = TestServer::new(namespace).await;
+
+    let response = server.call("admin_pauseStateKeeper").await;
+    assert_eq!(response["result"], true, "{response}");
+    assert!(control.is_paused());
+    let response = server.call("admin_pauseStateKeeper").await;
+    assert_eq!(response["result"], false, "{response}");
+
+    let response = server.call("admin_getNodeStatus").await;
+    let status: NodeStatus = serde_json::from_value(response["result"].clone()).unwrap();
+    assert_eq!(status.state_keeper_paused, Some(true));
+    assert_eq!(status.queue_lengths.state_keeper_l2_txs, Some(0));
+    assert_eq!(status.queue_lengths.unconfirmed_eth_txs, 0);
+
+    let response = server.call("admin_resumeStateKeeper").await;
+    assert_eq!(response["result"], true, "{response}");
+    assert!(!control.is_paused());
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn calling_methods_for_absent_components() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let server = TestServer::new(AdminNamespace::new(pool)).await;
+
+    for method in [
+        "admin_sealL1Batch",
+        "admin_getLogFilter",
+        "admin_reloadConfig",
+    ] {
+        let response = server.call(method).await;
+        assert_eq!(
+            response["error"]["code"],
+            ErrorCode::MethodNotFound.code(),
+            "{response}"
+        );
+    }
+
+    let response = server.call("admin_clearFailedL1Transactions").await;
+    assert!(response["error"].is_null(), "{response}");
+    let response = server.call("admin_getNodeStatus").await;
+    assert_eq!(response["result"]["stateKeeperPaused"], json!(null));
+    server.stop().await;
+}
+
+#[derive(Debug, Default)]
+struct MockConfigReloader {
+    results: std::sync::Mutex<Vec<anyhow::Result<bool>>>,
+}
+
+impl ConfigReloadHandle for MockConfigReloader {
+    fn reload(&self) -> anyhow::Result<bool> {
+        self.results.lock().unwrap().remove(0)
+    }
+}
+
+#[tokio::test]
+async fn reloading_config() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let reloader = MockConfigReloader {
+        results: std::sync::Mutex::new(vec![
+            Ok(true),
+            Ok(false),
+            Err(anyhow::anyhow!(
+                "config sections [\"mempool_config\"] changed"
+            )),
+        ]),
+    };
+    let namespace = AdminNamespace::new(pool).with_config_reloader(Arc::new(reloader));
+    let server = TestServer::new(namespace).await;
+
+    let response = server.call("admin_reloadConfig").await;
+    assert_eq!(response["result"], true, "{response}");
+    let response = server.call("admin_reloadConfig").await;
+    assert_eq!(response["result"], false, "{response}");
+    let response = server.call("admin_reloadConfig").await;
+    assert_eq!(
+        response["error"]["code"],
+        ErrorCode::InternalError.code(),
+        "{response}"
+    );
+    let message = response["error"]["message"].as_str().unwrap();
+    assert!(message.contains("mempool_config"), "{message}");
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn getting_vm_divergences() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    for l1_batch_number in [1, 2] {
+        storage
+            .vm_divergences_dal()
+            .insert_divergence(L1BatchNumber(l1_batch_number), Some(0), "result", None)
+            .await
+            .unwrap();
+    }
+    drop(storage);
+    let server = TestServer::new(AdminNamespace::new(pool)).await;
+
+    let response = server
+        .call_with_params("admin_getVmDivergences", json!([2]))
+        .await;
+    let divergences: Vec<VmDivergence> =
+        serde_json::from_value(response["result"].clone()).unwrap();
+    assert_eq!(divergences.len(), 1);
+    assert_eq!(divergences[0].l1_batch_number, L1BatchNumber(2));
+    assert_eq!(divergences[0].kind, "result");
+
+    let response = server
+        .call_with_params("admin_getVmDivergences", json!([0, 1]))
+        .await;
+    let divergences: Vec<VmDivergence> =
+        serde_json::from_value(response["result"].clone()).unwrap();
+    assert_eq!(divergences.len(), 1);
+    assert_eq!(divergences[0].l1_batch_number, L1BatchNumber(1));
+    server.stop().await;
+}
@@ -1,8 +1,9 @@
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use std::{collections::HashMap, future::IntoFuture, net::SocketAddr, sync::Arc, time::Duration};
 
+use anyhow::Context as _;
 use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
-use tokio::sync::watch;
-use zksync_health_check::{AppHealth, AppHealthCheck};
+use tokio::sync::{broadcast::error::RecvError, watch};
+use zksync_health_check::{AppHealth, AppHealthCheck, HealthStatus, HealthTransition};
 
 async fn check_health(
     app_health_check: State<Arc<AppHealthCheck>>,
@@ -16,6 +17,36 @@ async fn check_health(
     (response_code, Json(response))
 }
 
+async fn health_history(
+    app_health_check: State<Arc<AppHealthCheck>>,
+) -> Json<HashMap<&'static str, Vec<HealthTransition>>> {
+    Json(app_health_check.history())
+}
+
+/// Periodically checks application health so that health transitions are recorded even if the server
+/// is not queried.
+async fn monitor_health(
+    app_health_check: Arc<AppHealthCheck>,
+    mut stop_receiver: watch::Receiver<bool>,
+) {
+    const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+    while !*stop_receiver.borrow_and_update() {
+        app_health_check.check_health().await;
+        // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+        tokio::time::timeout(POLL_INTERVAL, stop_receiver.changed())
+            .await
+            .ok();
+    }
+}
+
+fn create_router(app_health_check: Arc<AppHealthCheck>) -> Router {
+    Router::new()
+        .route("/health", get(check_health))
+        .route("/health/history", get(health_history))
+        .with_state(app_health_check)
+}
+
 async fn run_server(
     bind_address: &SocketAddr,
     app_health_check: Arc<AppHealthCheck>,
@@ -26,21 +57,22 @@ async fn run_server(
     );
 
     app_health_check.expose_metrics();
-    let app = Router::new()
-        .route("/health", get(check_health))
-        .with_state(app_health_check);
+    // The monitor stops on the same signal as the server, so it's joined together with the server.
+    let monitor = monitor_health(app_health_check.clone(), stop_receiver.clone());
+    let app = create_router(app_health_check);
     let listener = tokio::net::TcpListener::bind(bind_address)
         .await
         .unwrap_or_else(|err| panic!("Failed binding healthcheck server to {bind_address}: {err}"));
-    axum::serve(listener, app)
-        .with_graceful_shutdown(async move {
-            if stop_receiver.changed().await.is_err() {
-                tracing::warn!("Stop signal sender for healthcheck server was dropped without sending a signal");
-            }
-            tracing::info!("Stop signal received, healthcheck server is shutting down");
-        })
-        .await
-        .expect("Healthcheck server failed");
+    let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+        if stop_receiver.changed().await.is_err() {
+            tracing::warn!(
+                "Stop signal sender for healthcheck server was dropped without sending a signal"
+            );
+        }
+        tracing::info!("Stop signal received, healthcheck server is shutting down");
+    });
+    let (server_result, ()) = tokio::join!(server.into_future(), monitor);
+    server_result.expect("Healthcheck server failed");
     tracing::info!("Healthcheck server shut down");
 }
 
@@ -78,3 +110,207 @@ impl HealthCheckHandle {
         }
     }
 }
+
+/// Sends notifications about component health transitioning to [`HealthStatus::Affected`] or [`HealthStatus::ShutDown`]
+/// as JSON POST requests to the configured URL. The request body is a serialized [`HealthTransition`].
+#[derive(Debug)]
+pub struct HealthWebhook {
+    client: reqwest::Client,
+    url: reqwest::Url,
+}
+
+impl HealthWebhook {
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new(url: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::new(),
+            url: url.parse().context("invalid health webhook URL")?,
+        })
+    }
+
+    fn should_notify(transition: &HealthTransition) -> bool {
+        matches!(
+            transition.to,
+            HealthStatus::Affected | HealthStatus::ShutDown
+        )
+    }
+
+    async fn notify(&self, transition: &HealthTransition) -> anyhow::Result<()> {
+        let body = serde_json::to_string(transition).context("failed serializing transition")?;
+        self.client
+            .post(self.url.clone())
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .timeout(Self::REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn run(
+        self,
+        app_health_check: Arc<AppHealthCheck>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let mut transitions = app_health_check.subscribe_to_transitions();
+        loop {
+            let transition = tokio::select! {
+                res = transitions.recv() => match res {
+                    Ok(transition) => transition,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Health webhook lagged behind; skipped {skipped} health transitions");
+                        continue;
+                    }
+                    // The sender is owned by `app_health_check`, so it cannot be dropped.
+                    Err(RecvError::Closed) => break,
+                },
+                _ = stop_receiver.changed() => break,
+            };
+
+            if Self::should_notify(&transition) {
+                // Notification failures are not fatal; the transition is still available via the health history.
+                if let Err(err) = self.notify(&transition).await {
+                    tracing::warn!(
+                        "Failed notifying health webhook about transition of `{}` to {:?}: {err:#}",
+                        transition.component,
+                        transition.to
+                    );
+                }
+            }
+        }
+        tracing::info!("Stop signal received, health webhook is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use axum::routing::post;
+    use tokio::sync::mpsc;
+    use zksync_health_check::{Health, ReactiveHealthCheck};
+
+    use super::*;
+
+    async fn serve(router: Router) -> SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        local_addr
+    }
+
+    /// Spawns a webhook receiver failing the first request.
+    async fn spawn_receiver() -> (String, mpsc::UnboundedReceiver<serde_json::Value>) {
+        let (requests_sender, requests) = mpsc::unbounded_channel();
+        let received_requests = Arc::new(AtomicBool::new(false));
+        let handler = move |Json(body): Json<serde_json::Value>| {
+            let is_first = !received_requests.swap(true, Ordering::SeqCst);
+            requests_sender.send(body).ok();
+            async move {
+                if is_first {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                } else {
+                    StatusCode::OK
+                }
+            }
+        };
+        let local_addr = serve(Router::new().route("/", post(handler))).await;
+        (format!("http://{local_addr}/"), requests)
+    }
+
+    #[tokio::test]
+    async fn health_webhook_basics() {
+        let (url, mut requests) = spawn_receiver().await;
+        let app_health_check = Arc::new(AppHealthCheck::default());
+        let (health_check, health_updater) = ReactiveHealthCheck::new("test");
+        app_health_check.insert_component(health_check).unwrap();
+        let webhook = HealthWebhook::new(&url).unwrap();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+
+        let update_health = |health: Health| {
+            let app_health_check = app_health_check.clone();
+            health_updater.update(health);
+            async move { app_health_check.check_health().await }
+        };
+        let driver = async {
+            // Ensures that the webhook has subscribed to transitions.
+            tokio::task::yield_now().await;
+            update_health(HealthStatus::Ready.into()).await;
+            update_health(Health::from(HealthStatus::Affected).with_details("lagging")).await;
+            // The first request fails, which must not stop the webhook.
+            let failed_request = requests.recv().await.unwrap();
+            assert_eq!(failed_request["to"], "affected");
+
+            update_health(HealthStatus::Ready.into()).await;
+            update_health(HealthStatus::NotReady.into()).await;
+            update_health(HealthStatus::ShutDown.into()).await;
+            let request = requests.recv().await.unwrap();
+            assert_eq!(request["component"], "test");
+            assert_eq!(request["from"], "not_ready");
+            assert_eq!(request["to"], "shut_down");
+            assert!(request.get("details").is_none(), "{request}");
+            assert!(request["timestamp_ms"].as_u64().unwrap() > 0);
+            // Transitions to `ready` and `not_ready` must not be notified about.
+            assert!(requests.try_recv().is_err());
+
+            stop_sender.send_replace(true);
+        };
+        let (webhook_result, ()) =
+            tokio::join!(webhook.run(app_health_check.clone(), stop_receiver), driver);
+        webhook_result.unwrap();
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn health_webhook_with_unreachable_receiver() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+        let app_health_check = Arc::new(AppHealthCheck::default());
+        let (health_check, health_updater) = ReactiveHealthCheck::new("test");
+        app_health_check.insert_component(health_check).unwrap();
+        let webhook = HealthWebhook::new(&url).unwrap();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+
+        let driver = async {
+            tokio::task::yield_now().await;
+            health_updater.update(HealthStatus::Affected.into());
+            app_health_check.check_health().await;
+            // Let the webhook process the transition.
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            stop_sender.send_replace(true);
+        };
+        let (webhook_result, ()) =
+            tokio::join!(webhook.run(app_health_check.clone(), stop_receiver), driver);
+        webhook_result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn health_history_route() {
+        let app_health_check = Arc::new(AppHealthCheck::default());
+        let (health_check, health_updater) = ReactiveHealthCheck::new("test");
+        app_health_check.insert_component(health_check).unwrap();
+        health_updater.update(HealthStatus::Ready.into());
+        app_health_check.check_health().await;
+        health_updater.update(Health::from(HealthStatus::Affected).with_details("lagging"));
+        app_health_check.check_health().await;
+
+        let local_addr = serve(create_router(app_health_check)).await;
+        let response = reqwest::get(format!("http://{local_addr}/health/history"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let history: serde_json::Value =
+            serde_json::from_str(&response.text().await.unwrap()).unwrap();
+        let transitions = history["test"].as_array().unwrap();
+        assert_eq!(transitions.len(), 2, "{history}");
+        assert_eq!(transitions[0]["from"], serde_json::Value::Null);
+        assert_eq!(transitions[0]["to"], "ready");
+        assert_eq!(transitions[1]["from"], "ready");
+        assert_eq!(transitions[1]["to"], "affected");
+        assert_eq!(transitions[1]["details"], "lagging");
+    }
+}
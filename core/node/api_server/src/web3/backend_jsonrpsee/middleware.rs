@@ -4,7 +4,7 @@ use std::{
     future::Future,
     num::NonZeroU32,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
     time::{Duration, Instant},
 };
@@ -23,6 +23,7 @@ use tracing::instrument::{Instrument, Instrumented};
 use vise::{
     Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, GaugeGuard, Histogram, Metrics,
 };
+use zksync_config::configs::ReloadableConfig;
 use zksync_web3_decl::jsonrpsee::{
     server::middleware::rpc::{layer::ResponseFuture, RpcServiceT},
     types::{error::ErrorCode, ErrorObject, Request},
@@ -53,26 +54,79 @@ struct LimitMiddlewareMetrics {
 #[vise::register]
 static METRICS: vise::Global<LimitMiddlewareMetrics> = vise::Global::new();
 
+type DirectRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
+
+/// Rate limiter for a single session that can change its limit on the fly.
+#[derive(Debug)]
+struct SessionRateLimiter {
+    requests_per_minute_limit: Option<NonZeroU32>,
+    inner: Option<DirectRateLimiter>,
+}
+
+impl SessionRateLimiter {
+    fn new(requests_per_minute_limit: Option<NonZeroU32>) -> Self {
+        Self {
+            requests_per_minute_limit,
+            inner: requests_per_minute_limit
+                .map(|limit| RateLimiter::direct(Quota::per_minute(limit))),
+        }
+    }
+
+    /// Updates the limit. If the limit has changed, the quota of the session is reset.
+    fn set_limit(&mut self, requests_per_minute_limit: Option<NonZeroU32>) {
+        if self.requests_per_minute_limit != requests_per_minute_limit {
+            *self = Self::new(requests_per_minute_limit);
+        }
+    }
+
+    fn check(&self) -> bool {
+        let num_requests = NonZeroU32::MIN; // 1 request, no batches possible
+
+        // Note: if required, we can extract data on rate limiting from the error.
+        self.inner
+            .as_ref()
+            .map_or(true, |limiter| limiter.check_n(num_requests).is_ok())
+    }
+}
+
 /// A rate-limiting middleware.
 ///
-/// `jsonrpsee` will allocate the instance of this struct once per session.
+/// `jsonrpsee` will allocate the instance of this struct once per session. If config updates are provided,
+/// the limit is re-read on each request, so that reloaded limits apply to already open sessions as well.
 pub(crate) struct LimitMiddleware<S> {
     inner: S,
-    rate_limiter: Option<RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>>,
+    default_limit: Option<NonZeroU32>,
+    config_updates: Option<watch::Receiver<ReloadableConfig>>,
+    rate_limiter: Mutex<SessionRateLimiter>,
     transport: Transport,
     _guard: GaugeGuard,
 }
 
 impl<S> LimitMiddleware<S> {
-    pub(crate) fn new(inner: S, requests_per_minute_limit: Option<NonZeroU32>) -> Self {
+    pub(crate) fn new(
+        inner: S,
+        requests_per_minute_limit: Option<NonZeroU32>,
+        config_updates: Option<watch::Receiver<ReloadableConfig>>,
+    ) -> Self {
+        let current_limit = Self::limit(requests_per_minute_limit, config_updates.as_ref());
         Self {
             inner,
-            rate_limiter: requests_per_minute_limit
-                .map(|limit| RateLimiter::direct(Quota::per_minute(limit))),
+            default_limit: requests_per_minute_limit,
+            config_updates,
+            rate_limiter: Mutex::new(SessionRateLimiter::new(current_limit)),
             transport: Transport::Ws,
             _guard: API_METRICS.ws_open_sessions.inc_guard(1),
         }
     }
+
+    fn limit(
+        default_limit: Option<NonZeroU32>,
+        config_updates: Option<&watch::Receiver<ReloadableConfig>>,
+    ) -> Option<NonZeroU32> {
+        config_updates
+            .and_then(|updates| updates.borrow().websocket_requests_per_minute_limit)
+            .or(default_limit)
+    }
 }
 
 impl<'a, S> RpcServiceT<'a> for LimitMiddleware<S>
@@ -82,24 +136,27 @@ where
     type Future = ResponseFuture<S::Future>;
 
     fn call(&self, request: Request<'a>) -> Self::Future {
-        if let Some(rate_limiter) = &self.rate_limiter {
-            let num_requests = NonZeroU32::MIN; // 1 request, no batches possible
-
-            // Note: if required, we can extract data on rate limiting from the error.
-            if rate_limiter.check_n(num_requests).is_err() {
-                METRICS.rate_limited[&self.transport].inc();
-
-                let rp = MethodResponse::error(
-                    request.id,
-                    ErrorObject::borrowed(
-                        ErrorCode::ServerError(http::StatusCode::TOO_MANY_REQUESTS.as_u16().into())
-                            .code(),
-                        "Too many requests",
-                        None,
-                    ),
-                );
-                return ResponseFuture::ready(rp);
+        let is_allowed = {
+            let mut rate_limiter = self.rate_limiter.lock().expect("rate limiter is poisoned");
+            if let Some(updates) = &self.config_updates {
+                rate_limiter.set_limit(Self::limit(self.default_limit, Some(updates)));
             }
+            rate_limiter.check()
+        };
+
+        if !is_allowed {
+            METRICS.rate_limited[&self.transport].inc();
+
+            let rp = MethodResponse::error(
+                request.id,
+                ErrorObject::borrowed(
+                    ErrorCode::ServerError(http::StatusCode::TOO_MANY_REQUESTS.as_u16().into())
+                        .code(),
+                    "Too many requests",
+                    None,
+                ),
+            );
+            return ResponseFuture::ready(rp);
         }
         ResponseFuture::future(self.inner.call(request))
     }
@@ -398,6 +455,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn session_rate_limiter_updates() {
+        let mut limiter = SessionRateLimiter::new(None);
+        assert!((0..100).all(|_| limiter.check()));
+
+        limiter.set_limit(NonZeroU32::new(2));
+        assert!(limiter.check());
+        assert!(limiter.check());
+        assert!(!limiter.check());
+        // Setting the same limit must not reset the quota.
+        limiter.set_limit(NonZeroU32::new(2));
+        assert!(!limiter.check());
+
+        limiter.set_limit(NonZeroU32::new(3));
+        assert!((0..3).all(|_| limiter.check()));
+        assert!(!limiter.check());
+
+        limiter.set_limit(None);
+        assert!(limiter.check());
+    }
+
     #[tokio::test]
     async fn traffic_tracker_basics() {
         let traffic_tracker = TrafficTracker::default();
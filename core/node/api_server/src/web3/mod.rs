@@ -9,7 +9,10 @@ use tokio::{
     task::JoinHandle,
 };
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
-use zksync_config::configs::api::{MaxResponseSize, MaxResponseSizeOverrides};
+use zksync_config::configs::{
+    api::{MaxResponseSize, MaxResponseSizeOverrides},
+    ReloadableConfig,
+};
 use zksync_dal::{helpers::wait_for_l1_batch, ConnectionPool, Core};
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_metadata_calculator::api_server::TreeApiClient;
@@ -134,6 +137,7 @@ struct OptionalApiParams {
     batch_request_size_limit: Option<usize>,
     response_body_size_limit: Option<MaxResponseSize>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    config_updates: Option<watch::Receiver<ReloadableConfig>>,
     tree_api: Option<Arc<dyn TreeApiClient>>,
     mempool_cache: Option<MempoolCache>,
     extended_tracing: bool,
@@ -246,6 +250,13 @@ impl ApiBuilder {
         self
     }
 
+    /// Makes the server apply the WebSocket rate limit from config updates. Updated limits apply both to new
+    /// and already open sessions; the quota of an open session is reset when its limit changes.
+    pub fn with_config_updates(mut self, updates: watch::Receiver<ReloadableConfig>) -> Self {
+        self.optional.config_updates = Some(updates);
+        self
+    }
+
     pub fn with_sync_state(mut self, sync_state: SyncState) -> Self {
         self.optional.sync_state = Some(sync_state);
         self
@@ -637,6 +648,7 @@ impl ApiServer {
                 (u32::MAX, MaxResponseSizeOverrides::empty())
             };
         let websocket_requests_per_minute_limit = self.optional.websocket_requests_per_minute_limit;
+        let config_updates = self.optional.config_updates.clone();
         let subscriptions_limit = self.optional.subscriptions_limit;
         let vm_barrier = self.optional.vm_barrier.clone();
         let health_updater = self.health_updater.clone();
@@ -706,7 +718,11 @@ impl ApiServer {
             // We want to capture limit middleware errors with `metadata_layer`; hence, `LimitMiddleware` is placed after it.
             .option_layer((!is_http).then(|| {
                 tower::layer::layer_fn(move |svc| {
-                    LimitMiddleware::new(svc, websocket_requests_per_minute_limit)
+                    LimitMiddleware::new(
+                        svc,
+                        websocket_requests_per_minute_limit,
+                        config_updates.clone(),
+                    )
                 })
             }));
 
@@ -1,7 +1,10 @@
 use anyhow::Context as _;
 use zksync_dal::{CoreDal, DalError};
 use zksync_types::{
-    snapshots::{AllSnapshots, SnapshotHeader, SnapshotStorageLogsChunkMetadata},
+    snapshots::{
+        AllSnapshots, SnapshotHeader, SnapshotHistoryChunkMetadata,
+        SnapshotStorageLogsChunkMetadata,
+    },
     L1BatchNumber,
 };
 use zksync_web3_decl::error::Web3Error;
@@ -46,14 +49,24 @@ impl SnapshotsNamespace {
             return Ok(None);
         };
 
-        let snapshot_files = snapshot_metadata.storage_logs_filepaths;
-        let is_complete = snapshot_files.iter().all(Option::is_some);
-        if !is_complete {
+        if !snapshot_metadata.is_complete() {
             // We don't return incomplete snapshots via API.
             return Ok(None);
         }
 
-        let chunks = snapshot_files
+        let history_chunks = snapshot_metadata
+            .history_filepaths
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, filepath)| {
+                Some(SnapshotHistoryChunkMetadata {
+                    l1_batch_number: snapshot_metadata.history_l1_batch(idx),
+                    filepath: filepath.clone()?,
+                })
+            })
+            .collect();
+        let chunks = snapshot_metadata
+            .storage_logs_filepaths
             .into_iter()
             .enumerate()
             .filter_map(|(chunk_id, filepath)| {
@@ -76,6 +89,8 @@ impl SnapshotsNamespace {
             l2_block_number,
             storage_logs_chunks: chunks,
             factory_deps_filepath: snapshot_metadata.factory_deps_filepath,
+            base_l1_batch_number: snapshot_metadata.base_l1_batch_number,
+            history_chunks,
         }))
     }
 }
@@ -52,6 +52,11 @@ impl UnstableNamespace {
         tee_type: Option<TeeType>,
     ) -> Result<Vec<TeeProof>, Web3Error> {
         let mut storage = self.state.acquire_connection().await?;
+        let quorum_reached_at = storage
+            .tee_proof_generation_dal()
+            .get_tee_quorum_reached_at(l1_batch_number)
+            .await
+            .map_err(DalError::generalize)?;
         let proofs = storage
             .tee_proof_generation_dal()
             .get_tee_proofs(l1_batch_number, tee_type)
@@ -67,6 +72,7 @@ impl UnstableNamespace {
                 proved_at: DateTime::<Utc>::from_naive_utc_and_offset(proof.updated_at, Utc),
                 status: proof.status,
                 attestation: proof.attestation,
+                quorum_reached_at,
             })
             .collect::<Vec<_>>();
 
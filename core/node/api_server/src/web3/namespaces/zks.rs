@@ -625,10 +625,22 @@ impl ZksNamespace {
         l1_batch_number: L1BatchNumber,
     ) -> Result<Option<Proof>, Web3Error> {
         let mut storage = self.state.acquire_connection().await?;
-        self.state
+        // If the batch is pruned in Postgres, the tree may still retain it (if it's configured to lag behind Postgres
+        // pruning), so we try to serve the request from the tree before returning an error. Note that this is the only
+        // method served for pruned data; e.g., `eth_getStorageAt` cannot be served since the mapping of pruned
+        // L2 blocks to L1 batches is removed from Postgres, and the tree only contains state as of L1 batch ends.
+        let pruned_error = match self
+            .state
             .start_info
             .ensure_not_pruned(l1_batch_number, &mut storage)
-            .await?;
+            .await
+        {
+            Ok(()) => None,
+            Err(err @ Web3Error::PrunedL1Batch(_)) => Some(err),
+            Err(err) => return Err(err),
+        };
+        drop(storage);
+
         let hashed_keys = keys
             .iter()
             .map(|key| StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256())
@@ -643,6 +655,10 @@ impl ZksNamespace {
             Ok(proofs) => proofs,
             Err(TreeApiError::NotReady(_)) => return Err(Web3Error::TreeApiUnavailable),
             Err(TreeApiError::NoVersion(err)) => {
+                if let Some(pruned_error) = pruned_error {
+                    // The batch is pruned both in Postgres and in the tree.
+                    return Err(pruned_error);
+                }
                 return if err.missing_version > err.version_count {
                     Ok(None)
                 } else {
@@ -108,6 +108,7 @@ pub struct TestServerBuilder {
     tx_executor: MockOneshotExecutor,
     executor_options: Option<SandboxExecutorOptions>,
     method_tracer: Arc<MethodTracer>,
+    tree_api: Option<Arc<dyn TreeApiClient>>,
 }
 
 impl TestServerBuilder {
@@ -119,6 +120,7 @@ impl TestServerBuilder {
             tx_executor: MockOneshotExecutor::default(),
             executor_options: None,
             method_tracer: Arc::default(),
+            tree_api: None,
         }
     }
 
@@ -142,6 +144,13 @@ impl TestServerBuilder {
         self
     }
 
+    /// Sets a Merkle tree API client for this builder.
+    #[must_use]
+    pub fn with_tree_api(mut self, tree_api: Arc<dyn TreeApiClient>) -> Self {
+        self.tree_api = Some(tree_api);
+        self
+    }
+
     /// Builds an HTTP server.
     pub async fn build_http(self, stop_receiver: watch::Receiver<bool>) -> ApiServerHandles {
         self.spawn_server(ApiTransportLabel::Http, None, stop_receiver)
@@ -175,6 +184,7 @@ impl TestServerBuilder {
             pool,
             api_config,
             method_tracer,
+            tree_api,
         } = self;
 
         let tx_executor = if let Some(options) = executor_options {
@@ -192,7 +202,7 @@ impl TestServerBuilder {
         let bridge_addresses_handle =
             BridgeAddressesHandle::new(api_config.bridge_addresses.clone());
 
-        let server_builder = match transport {
+        let mut server_builder = match transport {
             ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
             ApiTransportLabel::Ws => {
                 let mut builder = ApiBuilder::jsonrpsee_backend(api_config, pool)
@@ -208,6 +218,9 @@ impl TestServerBuilder {
                 builder
             }
         };
+        if let Some(tree_api) = tree_api {
+            server_builder = server_builder.with_tree_api(tree_api);
+        }
         let server_handles = server_builder
             .with_polling_interval(POLL_INTERVAL)
             .with_tx_sender(tx_sender)
@@ -9,11 +9,15 @@ use assert_matches::assert_matches;
 use async_trait::async_trait;
 use tokio::sync::watch;
 use zksync_config::{
-    configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig},
+    configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig, database::MerkleTreeMode},
     ContractsConfig, GenesisConfig,
 };
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{Connection, ConnectionPool, CoreDal};
+use zksync_metadata_calculator::{
+    api_server::{NoVersionError, TreeApiError, TreeEntryWithProof, TreeProofs, TreeProofsQuery},
+    MerkleTreeInfo,
+};
 use zksync_multivm::interface::{
     tracer::ValidationTraces, TransactionExecutionMetrics, TransactionExecutionResult, VmEvent,
 };
@@ -161,6 +165,11 @@ trait HttpTest: Send + Sync {
     fn filters_disabled(&self) -> bool {
         false
     }
+
+    /// Allows to specify a Merkle tree API client used by the server.
+    fn tree_api(&self) -> Option<Arc<dyn TreeApiClient>> {
+        None
+    }
 }
 
 /// Storage initialization strategy.
@@ -292,6 +301,9 @@ async fn test_http_server(test: impl HttpTest) {
     if let Some(executor_options) = test.executor_options() {
         server_builder = server_builder.with_executor_options(executor_options);
     }
+    if let Some(tree_api) = test.tree_api() {
+        server_builder = server_builder.with_tree_api(tree_api);
+    }
     let mut server_handles = server_builder.build_http(stop_receiver).await;
 
     let local_addr = server_handles.wait_until_ready().await;
@@ -614,6 +626,148 @@ fn assert_pruned_l1_batch_error(error: &ClientError, first_retained_l1_batch: L1
     }
 }
 
+/// Mock Merkle tree retaining all versions starting from `min_l1_batch_number`.
+#[derive(Debug)]
+struct MockTreeApiClient {
+    min_l1_batch_number: L1BatchNumber,
+    next_l1_batch_number: L1BatchNumber,
+}
+
+impl MockTreeApiClient {
+    fn mock_entry(l1_batch_number: L1BatchNumber) -> TreeEntryWithProof {
+        TreeEntryWithProof {
+            value: H256::from_low_u64_be(l1_batch_number.0.into()),
+            index: 1,
+            merkle_path: vec![H256::repeat_byte(0xff)],
+        }
+    }
+
+    fn check_version(&self, l1_batch_number: L1BatchNumber) -> Result<(), NoVersionError> {
+        if l1_batch_number < self.min_l1_batch_number
+            || l1_batch_number >= self.next_l1_batch_number
+        {
+            return Err(NoVersionError {
+                missing_version: l1_batch_number.0.into(),
+                version_count: self.next_l1_batch_number.0.into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TreeApiClient for MockTreeApiClient {
+    async fn get_info(&self) -> Result<MerkleTreeInfo, TreeApiError> {
+        Ok(MerkleTreeInfo {
+            mode: MerkleTreeMode::Full,
+            root_hash: H256::zero(),
+            next_l1_batch_number: self.next_l1_batch_number,
+            min_l1_batch_number: Some(self.min_l1_batch_number),
+            leaf_count: 1,
+        })
+    }
+
+    async fn get_proofs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<Vec<TreeEntryWithProof>, TreeApiError> {
+        self.check_version(l1_batch_number)
+            .map_err(TreeApiError::NoVersion)?;
+        Ok(hashed_keys
+            .iter()
+            .map(|_| Self::mock_entry(l1_batch_number))
+            .collect())
+    }
+
+    async fn get_proofs_batch(
+        &self,
+        queries: Vec<TreeProofsQuery>,
+    ) -> Result<Vec<Result<TreeProofs, NoVersionError>>, TreeApiError> {
+        Ok(queries
+            .into_iter()
+            .map(|query| {
+                self.check_version(query.l1_batch_number)?;
+                let entries = query
+                    .hashed_keys
+                    .iter()
+                    .map(|_| Self::mock_entry(query.l1_batch_number))
+                    .collect();
+                Ok(TreeProofs::Entries(entries))
+            })
+            .collect())
+    }
+}
+
+/// Checks that `zks_getProof` is served for L1 batches pruned in Postgres, but retained in the Merkle tree.
+#[derive(Debug)]
+struct ProofsWithPrunedL1Batches;
+
+impl ProofsWithPrunedL1Batches {
+    /// The tree retains 1 L1 batch pruned in Postgres.
+    const MIN_TREE_L1_BATCH: L1BatchNumber = StorageInitialization::SNAPSHOT_RECOVERY_BATCH;
+}
+
+#[async_trait]
+impl HttpTest for ProofsWithPrunedL1Batches {
+    fn storage_initialization(&self) -> StorageInitialization {
+        StorageInitialization::empty_recovery()
+    }
+
+    fn tree_api(&self) -> Option<Arc<dyn TreeApiClient>> {
+        Some(Arc::new(MockTreeApiClient {
+            min_l1_batch_number: Self::MIN_TREE_L1_BATCH,
+            next_l1_batch_number: StorageInitialization::SNAPSHOT_RECOVERY_BATCH + 2,
+        }))
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let address = Address::repeat_byte(1);
+        let keys = vec![H256::zero(), H256::repeat_byte(1)];
+        let first_retained_l1_batch = StorageInitialization::SNAPSHOT_RECOVERY_BATCH + 1;
+
+        for l1_batch_number in [Self::MIN_TREE_L1_BATCH, first_retained_l1_batch] {
+            let proof = client
+                .get_proof(address, keys.clone(), l1_batch_number)
+                .await?
+                .with_context(|| format!("no proof for L1 batch #{l1_batch_number}"))?;
+            assert_eq!(proof.address, address);
+            assert_eq!(proof.storage_proof.len(), keys.len());
+            for (storage_proof, key) in proof.storage_proof.iter().zip(&keys) {
+                assert_eq!(storage_proof.key, *key);
+                assert_eq!(
+                    storage_proof.value,
+                    H256::from_low_u64_be(l1_batch_number.0.into())
+                );
+                assert_eq!(storage_proof.proof, [H256::repeat_byte(0xff)]);
+            }
+        }
+
+        // The L1 batch is pruned both in Postgres and in the tree.
+        let error = client
+            .get_proof(address, keys.clone(), Self::MIN_TREE_L1_BATCH - 1)
+            .await
+            .unwrap_err();
+        assert_pruned_l1_batch_error(&error, first_retained_l1_batch);
+
+        // Future L1 batches are still reported as missing.
+        let proof = client
+            .get_proof(address, keys, first_retained_l1_batch + 10)
+            .await?;
+        assert!(proof.is_none(), "{proof:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn proofs_with_pruned_l1_batches() {
+    test_http_server(ProofsWithPrunedL1Batches).await;
+}
+
 #[tokio::test]
 async fn l1_batch_methods_with_snapshot_recovery() {
     test_http_server(L1BatchMethodsWithSnapshotRecovery).await;
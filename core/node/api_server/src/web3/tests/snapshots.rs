@@ -46,7 +46,9 @@ impl HttpTest for SnapshotBasicsTest {
             .add_snapshot(
                 SnapshotVersion::Version0,
                 L1BatchNumber(1),
+                None,
                 Self::CHUNK_COUNT,
+                0,
                 "file:///factory_deps",
             )
             .await?;
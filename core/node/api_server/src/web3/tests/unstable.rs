@@ -53,6 +53,8 @@ impl HttpTest for GetTeeProofsTest {
         assert!(proof.signature.as_ref() == Some(&signature));
         assert!(proof.proof.as_ref() == Some(&proof_vec));
         assert!(proof.attestation.as_ref() == Some(&attestation));
+        // The quorum is not configured for the test.
+        assert!(proof.quorum_reached_at.is_none());
 
         Ok(())
     }
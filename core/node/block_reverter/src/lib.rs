@@ -18,8 +18,8 @@ use zksync_types::{
     ethabi::Token,
     settlement::SettlementLayer,
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotMetadata, SnapshotStorageLogsChunk,
-        SnapshotStorageLogsStorageKey,
+        SnapshotFactoryDependencies, SnapshotHistoryStorageKey, SnapshotL1BatchHistory,
+        SnapshotMetadata, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
     },
     web3::BlockNumber,
     Address, L1BatchNumber, L2ChainId, H160, H256, U256,
@@ -478,6 +478,25 @@ impl BlockReverter {
             for result in remove_results {
                 combine_results(&mut overall_result, result);
             }
+
+            let history_keys = snapshot
+                .history_filepaths
+                .iter()
+                .enumerate()
+                .filter(|(_, path)| path.is_some())
+                .map(|(idx, _)| SnapshotHistoryStorageKey {
+                    snapshot_l1_batch_number: snapshot.l1_batch_number,
+                    l1_batch_number: snapshot.history_l1_batch(idx),
+                });
+            for key in history_keys {
+                tracing::info!("Removing L1 batch history {key:?}");
+                let result = object_store
+                    .remove::<SnapshotL1BatchHistory>(key)
+                    .await
+                    .or_else(ignore_not_found_errors)
+                    .with_context(|| format!("failed removing L1 batch history {key:?}"));
+                combine_results(&mut overall_result, result);
+            }
         }
         overall_result
     }
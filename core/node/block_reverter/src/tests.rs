@@ -232,7 +232,9 @@ async fn create_mock_snapshot(
         .add_snapshot(
             SnapshotVersion::Version0,
             l1_batch_number,
+            None,
             storage_logs_chunk_count,
+            0,
             &factory_deps_key,
         )
         .await
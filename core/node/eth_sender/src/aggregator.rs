@@ -273,6 +273,37 @@ impl Aggregator {
         self.priority_merkle_tree.as_mut().unwrap()
     }
 
+    /// Truncates `l1_batches` to the prefix that can be executed given the TEE proof quorum requirement.
+    pub(crate) async fn retain_batches_with_tee_quorum(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        l1_batches: Vec<L1BatchWithMetadata>,
+    ) -> Vec<L1BatchWithMetadata> {
+        let Some(first_gated_batch) = self.config.tee_quorum_required_from_batch else {
+            return l1_batches;
+        };
+
+        let mut ready_batches = Vec::with_capacity(l1_batches.len());
+        for l1_batch in l1_batches {
+            let number = l1_batch.header.number;
+            if number.0 >= first_gated_batch {
+                let quorum_reached_at = storage
+                    .tee_proof_generation_dal()
+                    .get_tee_quorum_reached_at(number)
+                    .await
+                    .unwrap();
+                if quorum_reached_at.is_none() {
+                    tracing::debug!(
+                        "L1 batch #{number} cannot be executed yet since it hasn't reached TEE proof quorum"
+                    );
+                    break;
+                }
+            }
+            ready_batches.push(l1_batch);
+        }
+        ready_batches
+    }
+
     async fn get_execute_operations(
         &mut self,
         storage: &mut Connection<'_, Core>,
@@ -289,6 +320,9 @@ impl Aggregator {
             .get_ready_for_execute_l1_batches(limit, max_l1_batch_timestamp_millis)
             .await
             .unwrap();
+        let ready_for_execute_batches = self
+            .retain_batches_with_tee_quorum(storage, ready_for_execute_batches)
+            .await;
         let Some(l1_batches) = extract_ready_subrange(
             storage,
             &mut self.execute_criteria,
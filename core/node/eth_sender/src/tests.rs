@@ -1,5 +1,6 @@
 use assert_matches::assert_matches;
 use test_casing::{test_casing, Product};
+use zksync_config::{configs::eth_sender::SenderConfig, EthConfig};
 use zksync_contracts::hyperchain_contract;
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_eth_client::{
@@ -11,6 +12,7 @@ use zksync_l1_contract_interface::{
     i_executor::methods::ExecuteBatches, multicall3::Multicall3Call, Tokenizable,
 };
 use zksync_node_test_utils::create_l1_batch;
+use zksync_object_store::MockObjectStore;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     api::TransactionRequest,
@@ -20,15 +22,17 @@ use zksync_types::{
     },
     ethabi::{self, Token},
     helpers::unix_timestamp_ms,
+    protocol_version::ProtocolVersion,
     settlement::SettlementLayer,
     web3::{self, contract::Error},
-    Address, K256PrivateKey, L2ChainId, ProtocolVersionId, SLChainId, H256, U256,
+    Address, K256PrivateKey, L1BatchNumber, L2ChainId, ProtocolVersionId, SLChainId, H256, U256,
 };
 use zksync_web3_decl::client::MockClient;
 
 use crate::{
     abstract_l1_interface::{AbstractL1Interface, OperatorType, RealL1Interface},
     aggregated_operations::AggregatedOperation,
+    aggregator::Aggregator,
     tester::{
         EthSenderTester, TestL1Batch, STATE_TRANSITION_CONTRACT_ADDRESS,
         STATE_TRANSITION_MANAGER_CONTRACT_ADDRESS,
@@ -608,6 +612,58 @@ async fn correct_order_for_confirmations(
     Ok(())
 }
 
+#[tokio::test]
+async fn execution_is_gated_on_tee_quorum() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(&ProtocolVersion::default())
+        .await
+        .unwrap();
+    let l1_batches: Vec<_> = (1..=4)
+        .map(|number| l1_batch_with_metadata(create_l1_batch(number)))
+        .collect();
+    for l1_batch in &l1_batches {
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&l1_batch.header)
+            .await
+            .unwrap();
+    }
+    for number in [2, 4] {
+        storage
+            .tee_proof_generation_dal()
+            .mark_tee_quorum_reached(L1BatchNumber(number))
+            .await
+            .unwrap();
+    }
+
+    let config = SenderConfig {
+        tee_quorum_required_from_batch: Some(2),
+        ..EthConfig::for_tests().sender.unwrap()
+    };
+    let aggregator = Aggregator::new(
+        config,
+        MockObjectStore::arc(),
+        None,
+        L1BatchCommitmentMode::Rollup,
+        pool.clone(),
+        SettlementLayer::L1(10.into()),
+    )
+    .await
+    .unwrap();
+    // Batch #1 precedes the gated range; batch #3 hasn't reached the quorum, which blocks execution of batch #4.
+    let ready_batches = aggregator
+        .retain_batches_with_tee_quorum(&mut storage, l1_batches)
+        .await;
+    let ready_batch_numbers: Vec<_> = ready_batches
+        .iter()
+        .map(|l1_batch| l1_batch.header.number.0)
+        .collect();
+    assert_eq!(ready_batch_numbers, [1, 2]);
+}
+
 #[test_casing(2, COMMITMENT_MODES)]
 #[test_log::test(tokio::test)]
 async fn skipped_l1_batch_at_the_start(
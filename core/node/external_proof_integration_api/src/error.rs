@@ -2,7 +2,7 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
-use zksync_basic_types::L1BatchNumber;
+use zksync_basic_types::{protocol_version::ProtocolSemanticVersion, L1BatchNumber};
 use zksync_dal::DalError;
 use zksync_object_store::{ObjectStoreError, _reexports::BoxedError};
 
@@ -20,6 +20,21 @@ pub(crate) enum ProcessorError {
     Internal,
     #[error("Proof verification not possible anymore, batch is too old")]
     ProofIsGone,
+    #[error("Batch {0} is not known to the proof generation pipeline")]
+    BatchNotFound(L1BatchNumber),
+    #[error("Batch {0} is not leased by the prover, or the lease has expired")]
+    BatchNotLeased(L1BatchNumber),
+    #[error("Proof for batch {0} was already submitted or is not required")]
+    ProofAlreadySubmitted(L1BatchNumber),
+    #[error("Proof protocol version {proof_version} doesn't match the protocol version of batch {l1_batch_number}")]
+    ProtocolVersionMismatch {
+        l1_batch_number: L1BatchNumber,
+        proof_version: ProtocolSemanticVersion,
+    },
+    #[error("Missing or invalid API key")]
+    Unauthorized,
+    #[error("Proof submission is not enabled on the server")]
+    ProofSubmissionDisabled,
 }
 
 impl ProcessorError {
@@ -31,6 +46,12 @@ impl ProcessorError {
             Self::InvalidFile(_) => StatusCode::BAD_REQUEST,
             Self::BatchNotReady(_) => StatusCode::NOT_FOUND,
             Self::ProofIsGone => StatusCode::GONE,
+            Self::BatchNotFound(_) => StatusCode::NOT_FOUND,
+            Self::BatchNotLeased(_) => StatusCode::FORBIDDEN,
+            Self::ProofAlreadySubmitted(_) => StatusCode::CONFLICT,
+            Self::ProtocolVersionMismatch { .. } => StatusCode::BAD_REQUEST,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::ProofSubmissionDisabled => StatusCode::NOT_IMPLEMENTED,
         }
     }
 }
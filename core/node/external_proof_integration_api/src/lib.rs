@@ -2,34 +2,53 @@ mod error;
 mod metrics;
 mod middleware;
 mod processor;
+#[cfg(test)]
+mod tests;
 mod types;
+mod verifier;
 
-use std::net::SocketAddr;
+use std::{collections::HashMap, convert::Infallible, net::SocketAddr};
 
 use anyhow::Context;
 use axum::{
     extract::{Path, Request, State},
+    http::StatusCode,
     middleware::Next,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::{get, post},
-    Router,
+    Json, Router,
 };
 use error::ProcessorError;
+use futures::{Stream, StreamExt};
 use tokio::sync::watch;
-use types::{ExternalProof, ProofGenerationDataResponse};
+use types::{ExternalProof, ProofGenerationDataResponse, ProverId};
 use zksync_basic_types::L1BatchNumber;
+use zksync_prover_interface::api::ExternalProofStatusResponse;
 
-pub use crate::processor::Processor;
-use crate::{metrics::Method, middleware::MetricsMiddleware};
+use crate::{
+    metrics::Method,
+    middleware::{ApiKeyAuth, MetricsMiddleware},
+};
+pub use crate::{processor::Processor, verifier::ProofVerifier};
 
 /// External API implementation.
 #[derive(Debug)]
 pub struct Api {
     router: Router,
     port: u16,
+    /// Used to terminate status streams on shutdown; otherwise, they would block graceful shutdown of the server.
+    stop_streams_sender: watch::Sender<bool>,
 }
 
 impl Api {
-    pub fn new(processor: Processor, port: u16) -> Self {
+    /// Creates an API server. Requests to lease batches, submit proofs and get batch statuses must provide one of
+    /// `api_keys` in the `X-API-Key` header; the key determines the prover ID used as the lease owner. `api_keys` map
+    /// API keys to prover IDs; if they are empty, these requests are rejected. Other endpoints don't require
+    /// an API key.
+    pub fn new(processor: Processor, port: u16, api_keys: HashMap<String, String>) -> Self {
         let middleware_factory = |method: Method| {
             axum::middleware::from_fn(move |req: Request, next: Next| async move {
                 let middleware = MetricsMiddleware::new(method);
@@ -38,6 +57,16 @@ impl Api {
                 response
             })
         };
+        let auth = ApiKeyAuth::new(api_keys);
+        let identify_prover = {
+            let auth = auth.clone();
+            axum::middleware::from_fn(move |req: Request, next: Next| {
+                auth.clone().identify(req, next)
+            })
+        };
+        let authenticate_prover = axum::middleware::from_fn(move |req: Request, next: Next| {
+            auth.clone().authenticate(req, next)
+        });
 
         let router = Router::new()
             .route(
@@ -48,15 +77,44 @@ impl Api {
             .route(
                 "/proof_generation_data/:l1_batch_number",
                 get(Api::generation_data_for_existing_batch)
-                    .layer(middleware_factory(Method::GetSpecificProofGenerationData)),
+                    .layer(middleware_factory(Method::GetSpecificProofGenerationData))
+                    .layer(identify_prover),
             )
             .route(
                 "/verify_proof/:l1_batch_number",
                 post(Api::verify_proof).layer(middleware_factory(Method::VerifyProof)),
+            );
+
+        let (stop_streams_sender, stop_streams_receiver) = watch::channel(false);
+        let authenticated_router = Router::new()
+            .route(
+                "/lease_batch",
+                post(Api::lease_batch).layer(middleware_factory(Method::LeaseBatch)),
+            )
+            .route(
+                "/submit_proof/:l1_batch_number",
+                post(Api::submit_proof).layer(middleware_factory(Method::SubmitProof)),
+            )
+            .route(
+                "/batch_status/:l1_batch_number",
+                get(Api::batch_status).layer(middleware_factory(Method::GetBatchStatus)),
             )
-            .with_state(processor);
+            .route(
+                "/batch_status/:l1_batch_number/stream",
+                get(move |state: State<Processor>, path: Path<u32>| {
+                    Api::batch_status_stream(state, path, stop_streams_receiver.clone())
+                })
+                .layer(middleware_factory(Method::StreamBatchStatus)),
+            )
+            .route_layer(authenticate_prover);
 
-        Self { router, port }
+        let router = router.merge(authenticated_router).with_state(processor);
+
+        Self {
+            router,
+            port,
+            stop_streams_sender,
+        }
     }
 
     pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
@@ -68,12 +126,14 @@ impl Api {
             .with_context(|| {
                 format!("Failed binding external prover API server to {bind_address}")
             })?;
+        let stop_streams_sender = self.stop_streams_sender;
         axum::serve(listener, self.router)
         .with_graceful_shutdown(async move {
             if stop_receiver.changed().await.is_err() {
                 tracing::warn!("Stop signal sender for external prover API server was dropped without sending a signal");
             }
             tracing::info!("Stop signal received, external prover API server is shutting down");
+            stop_streams_sender.send_replace(true);
         })
         .await
         .context("External prover API server failed")?;
@@ -90,9 +150,13 @@ impl Api {
     async fn generation_data_for_existing_batch(
         State(processor): State<Processor>,
         Path(l1_batch_number): Path<u32>,
+        prover_id: Option<ProverId>,
     ) -> Result<ProofGenerationDataResponse, ProcessorError> {
         processor
-            .proof_generation_data_for_existing_batch(L1BatchNumber(l1_batch_number))
+            .proof_generation_data_for_existing_batch(
+                L1BatchNumber(l1_batch_number),
+                prover_id.as_ref(),
+            )
             .await
     }
 
@@ -105,4 +169,62 @@ impl Api {
             .verify_proof(L1BatchNumber(l1_batch_number), proof)
             .await
     }
+
+    async fn lease_batch(
+        State(processor): State<Processor>,
+        prover_id: ProverId,
+    ) -> Result<Response, ProcessorError> {
+        Ok(match processor.lease_batch(&prover_id).await? {
+            Some(lease) => Json(lease).into_response(),
+            None => StatusCode::NO_CONTENT.into_response(),
+        })
+    }
+
+    async fn submit_proof(
+        State(processor): State<Processor>,
+        Path(l1_batch_number): Path<u32>,
+        prover_id: ProverId,
+        proof: ExternalProof,
+    ) -> Result<(), ProcessorError> {
+        processor
+            .submit_proof(L1BatchNumber(l1_batch_number), &prover_id, proof)
+            .await
+    }
+
+    async fn batch_status(
+        State(processor): State<Processor>,
+        Path(l1_batch_number): Path<u32>,
+    ) -> Result<Json<ExternalProofStatusResponse>, ProcessorError> {
+        let status = processor
+            .batch_status(L1BatchNumber(l1_batch_number))
+            .await?;
+        Ok(Json(status))
+    }
+
+    async fn batch_status_stream(
+        State(processor): State<Processor>,
+        Path(l1_batch_number): Path<u32>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ProcessorError> {
+        // Query the status upfront, so that unknown batches are reported with a proper status code.
+        let initial_status = processor
+            .batch_status(L1BatchNumber(l1_batch_number))
+            .await?;
+        let stop = async move {
+            stop_receiver.wait_for(|&stop| stop).await.ok();
+        };
+        let events = processor
+            .batch_status_updates(initial_status)
+            .take_until(stop)
+            .filter_map(|status| async move {
+                match Event::default().event("status").json_data(&status) {
+                    Ok(event) => Some(Ok::<_, Infallible>(event)),
+                    Err(err) => {
+                        tracing::warn!("Failed serializing batch status: {err}");
+                        None
+                    }
+                }
+            });
+        Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+    }
 }
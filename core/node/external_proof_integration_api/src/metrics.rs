@@ -8,6 +8,10 @@ pub(crate) enum Method {
     GetLatestProofGenerationData,
     GetSpecificProofGenerationData,
     VerifyProof,
+    LeaseBatch,
+    SubmitProof,
+    GetBatchStatus,
+    StreamBatchStatus,
 }
 
 #[derive(Debug, Metrics)]
@@ -1,7 +1,18 @@
-use axum::http::StatusCode;
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
 use tokio::time::Instant;
 
-use crate::metrics::{Method, METRICS};
+use crate::{
+    error::ProcessorError,
+    metrics::{Method, METRICS},
+    types::ProverId,
+};
 
 #[derive(Debug)]
 pub(crate) struct MetricsMiddleware {
@@ -22,3 +33,71 @@ impl MetricsMiddleware {
             .observe(self.started_at.elapsed());
     }
 }
+
+/// Authenticates requests by the `X-API-Key` header and attaches the [`ProverId`] mapped to the key to the request.
+/// If no keys are configured, requests requiring authentication are rejected.
+#[derive(Debug, Clone)]
+pub(crate) struct ApiKeyAuth {
+    /// Maps API keys to the IDs of provers authenticated by them.
+    api_keys: Arc<HashMap<String, ProverId>>,
+}
+
+impl ApiKeyAuth {
+    const HEADER_NAME: &'static str = "x-api-key";
+
+    pub fn new(api_keys: HashMap<String, String>) -> Self {
+        if api_keys.is_empty() {
+            tracing::warn!(
+                "No API keys are configured; external prover API requests to lease batches, submit proofs \
+                 and get batch statuses will be rejected"
+            );
+        }
+        let api_keys = api_keys
+            .into_iter()
+            .map(|(key, prover_id)| (key, ProverId(prover_id)))
+            .collect();
+        Self {
+            api_keys: Arc::new(api_keys),
+        }
+    }
+
+    fn prover_id(&self, provided_key: &[u8]) -> Option<ProverId> {
+        // Compare against all keys to not leak the matching key via timing.
+        self.api_keys
+            .iter()
+            .fold(None, |matched, (key, prover_id)| {
+                if constant_time_eq(key.as_bytes(), provided_key) {
+                    Some(prover_id.clone())
+                } else {
+                    matched
+                }
+            })
+    }
+
+    /// Rejects requests without a valid API key.
+    pub async fn authenticate(self, mut req: Request, next: Next) -> Response {
+        let prover_id = req
+            .headers()
+            .get(Self::HEADER_NAME)
+            .and_then(|key| self.prover_id(key.as_bytes()));
+        let Some(prover_id) = prover_id else {
+            return ProcessorError::Unauthorized.into_response();
+        };
+        req.extensions_mut().insert(prover_id);
+        next.run(req).await
+    }
+
+    /// Identifies the prover if the request provides an API key; requests without a key are passed through.
+    /// Used for endpoints that predate authentication, so that they keep working for existing clients.
+    pub async fn identify(self, req: Request, next: Next) -> Response {
+        if req.headers().contains_key(Self::HEADER_NAME) {
+            self.authenticate(req, next).await
+        } else {
+            next.run(req).await
+        }
+    }
+}
+
+fn constant_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    lhs.len() == rhs.len() && lhs.iter().zip(rhs).fold(0, |acc, (l, r)| acc | (l ^ r)) == 0
+}
@@ -1,30 +1,50 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use futures::{stream, Stream};
 use zksync_basic_types::{
     basic_fri_types::Eip4844Blobs, commitment::L1BatchCommitmentMode, L1BatchNumber,
 };
-use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_dal::{
+    proof_generation_dal::{ProofGenerationDetails, ProofGenerationJobStatus},
+    ConnectionPool, Core, CoreDal,
+};
 use zksync_object_store::ObjectStore;
 use zksync_prover_interface::{
-    api::ProofGenerationData,
+    api::{
+        ExternalProofGenerationStatus, ExternalProofLease, ExternalProofLeaseInfo,
+        ExternalProofStatusResponse, ProofGenerationData,
+    },
     inputs::{
         L1BatchMetadataHashes, VMRunWitnessInputData, WitnessInputData, WitnessInputMerklePaths,
     },
     outputs::L1BatchProofForL1,
     Bincode, CBOR,
 };
+use zksync_types::{
+    commitment::{serialize_commitments, L1BatchWithMetadata},
+    web3::keccak256,
+    ProtocolVersionId, H256, STATE_DIFF_HASH_KEY_PRE_GATEWAY,
+};
 
 use crate::{
     error::ProcessorError,
-    types::{ExternalProof, ProofGenerationDataResponse},
+    types::{ExternalProof, ProofGenerationDataResponse, ProverId},
+    verifier::ProofVerifier,
 };
 
+/// Interval between polling batch status for status streams.
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Backend-agnostic implementation of the API logic.
 #[derive(Clone)]
 pub struct Processor {
     blob_store: Arc<dyn ObjectStore>,
     pool: ConnectionPool<Core>,
     commitment_mode: L1BatchCommitmentMode,
+    lease_timeout: Duration,
+    max_leases_per_prover: usize,
+    /// If not set, batches cannot be leased and proofs cannot be submitted.
+    verifier: Option<Arc<ProofVerifier>>,
 }
 
 impl Processor {
@@ -32,12 +52,198 @@ impl Processor {
         blob_store: Arc<dyn ObjectStore>,
         pool: ConnectionPool<Core>,
         commitment_mode: L1BatchCommitmentMode,
+        lease_timeout: Duration,
+        max_leases_per_prover: usize,
+        verifier: Option<ProofVerifier>,
     ) -> Self {
         Self {
             blob_store,
             pool,
             commitment_mode,
+            lease_timeout,
+            max_leases_per_prover,
+            verifier: verifier.map(Arc::new),
+        }
+    }
+
+    pub(crate) async fn lease_batch(
+        &self,
+        prover_id: &ProverId,
+    ) -> Result<Option<ExternalProofLease>, ProcessorError> {
+        if self.verifier.is_none() {
+            return Err(ProcessorError::ProofSubmissionDisabled);
+        }
+        let lease = self
+            .pool
+            .connection()
+            .await?
+            .proof_generation_dal()
+            .lease_batch_for_external_proving(
+                &prover_id.0,
+                self.lease_timeout,
+                self.max_leases_per_prover,
+            )
+            .await?;
+        if let Some(lease) = &lease {
+            tracing::info!(
+                "Leased batch {} to prover `{}` until {}",
+                lease.l1_batch_number,
+                prover_id.0,
+                lease.expires_at
+            );
+        }
+        Ok(lease.map(|lease| ExternalProofLease {
+            l1_batch_number: lease.l1_batch_number,
+            expires_at: lease.expires_at,
+        }))
+    }
+
+    pub(crate) async fn submit_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        prover_id: &ProverId,
+        proof: ExternalProof,
+    ) -> Result<(), ProcessorError> {
+        let verifier = self
+            .verifier
+            .clone()
+            .ok_or(ProcessorError::ProofSubmissionDisabled)?;
+        tracing::info!(
+            "Received proof for batch {l1_batch_number} from prover `{}`",
+            prover_id.0
+        );
+        let mut conn = self.pool.connection().await?;
+        let details = conn
+            .proof_generation_dal()
+            .get_proof_generation_details(l1_batch_number)
+            .await?
+            .ok_or(ProcessorError::BatchNotFound(l1_batch_number))?;
+        match details.status {
+            ProofGenerationJobStatus::Generated | ProofGenerationJobStatus::Skipped => {
+                return Err(ProcessorError::ProofAlreadySubmitted(l1_batch_number));
+            }
+            ProofGenerationJobStatus::Unpicked | ProofGenerationJobStatus::PickedByProver => {}
+        }
+        // Reject proofs for batches not leased by the prover before the expensive verification. The lease
+        // is checked again when saving the proof, since it may expire in the meantime.
+        if !is_leased_by(&details, prover_id) {
+            return Err(ProcessorError::BatchNotLeased(l1_batch_number));
+        }
+
+        let l1_batch = conn
+            .blocks_dal()
+            .get_l1_batch_metadata(l1_batch_number)
+            .await?
+            .ok_or(ProcessorError::Internal)?;
+        let prev_l1_batch_number = l1_batch_number
+            .checked_sub(1)
+            .ok_or(ProcessorError::Internal)?;
+        let prev_l1_batch = conn
+            .blocks_dal()
+            .get_l1_batch_metadata(L1BatchNumber(prev_l1_batch_number))
+            .await?
+            .ok_or(ProcessorError::Internal)?;
+        let proof_version = proof.protocol_version();
+        if l1_batch.header.protocol_version != Some(proof_version.minor) {
+            return Err(ProcessorError::ProtocolVersionMismatch {
+                l1_batch_number,
+                proof_version,
+            });
+        }
+        let verifier_config = conn
+            .protocol_versions_dal()
+            .get_protocol_version_with_latest_patch(proof_version.minor)
+            .await?
+            .ok_or(ProcessorError::Internal)?
+            .l1_verifier_config;
+        check_aggregation_result_coords(&l1_batch, proof.proof())?;
+        drop(conn);
+
+        let proof = proof.into_proof();
+        let proof = tokio::task::spawn_blocking(move || {
+            verifier.verify(
+                &proof,
+                &verifier_config,
+                prev_l1_batch.metadata.commitment,
+                l1_batch.metadata.commitment,
+            )?;
+            Ok::<_, ProcessorError>(proof)
+        })
+        .await
+        .map_err(|_| ProcessorError::Internal)??;
+
+        let blob_url = self
+            .blob_store
+            .put((l1_batch_number, proof_version), &proof)
+            .await?;
+        let saved = self
+            .pool
+            .connection()
+            .await?
+            .proof_generation_dal()
+            .save_leased_proof_artifacts_metadata(l1_batch_number, &prover_id.0, &blob_url)
+            .await?;
+        if !saved {
+            return Err(ProcessorError::BatchNotLeased(l1_batch_number));
         }
+        tracing::info!(
+            "Saved proof for batch {l1_batch_number} submitted by prover `{}`",
+            prover_id.0
+        );
+        Ok(())
+    }
+
+    pub(crate) async fn batch_status(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<ExternalProofStatusResponse, ProcessorError> {
+        let details = self
+            .pool
+            .connection()
+            .await?
+            .proof_generation_dal()
+            .get_proof_generation_details(l1_batch_number)
+            .await?
+            .ok_or(ProcessorError::BatchNotFound(l1_batch_number))?;
+        Ok(status_response(details))
+    }
+
+    /// Returns a stream starting with `initial_status` and yielding each subsequent change of the batch status.
+    /// The stream ends after yielding a final status (see [`ExternalProofGenerationStatus::is_final()`]).
+    pub(crate) fn batch_status_updates(
+        self,
+        initial_status: ExternalProofStatusResponse,
+    ) -> impl Stream<Item = ExternalProofStatusResponse> {
+        let state = (
+            self,
+            Some(initial_status),
+            None::<ExternalProofStatusResponse>,
+        );
+        stream::unfold(state, |(processor, mut pending, mut last)| async move {
+            loop {
+                if let Some(status) = pending.take() {
+                    last = Some(status.clone());
+                    return Some((status, (processor, pending, last)));
+                }
+                let last_status = last.as_ref()?;
+                if last_status.status.is_final() {
+                    return None;
+                }
+
+                tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+                match processor.batch_status(last_status.l1_batch_number).await {
+                    Ok(status) if status != *last_status => pending = Some(status),
+                    Ok(_) => {}
+                    Err(err) => {
+                        tracing::warn!(
+                            "Failed polling status of batch {}: {err}",
+                            last_status.l1_batch_number
+                        );
+                        return None;
+                    }
+                }
+            }
+        })
     }
 
     pub(crate) async fn verify_proof(
@@ -72,9 +278,12 @@ impl Processor {
             .map(ProofGenerationDataResponse)
     }
 
+    /// Returns proof generation data for a batch. Besides proven batches, data can be requested
+    /// by a prover for the batch it has leased.
     pub(crate) async fn proof_generation_data_for_existing_batch(
         &self,
         l1_batch_number: L1BatchNumber,
+        prover_id: Option<&ProverId>,
     ) -> Result<ProofGenerationDataResponse, ProcessorError> {
         tracing::debug!(
             "Received request for proof generation data for batch: {:?}",
@@ -83,7 +292,9 @@ impl Processor {
 
         let latest_available_batch = self.latest_available_batch().await?;
 
-        if l1_batch_number > latest_available_batch {
+        if l1_batch_number > latest_available_batch
+            && !self.is_batch_leased_by(l1_batch_number, prover_id).await?
+        {
             tracing::error!(
                 "Requested batch is not available: {:?}, latest available batch is {:?}",
                 l1_batch_number,
@@ -97,6 +308,24 @@ impl Processor {
             .map(ProofGenerationDataResponse)
     }
 
+    async fn is_batch_leased_by(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        prover_id: Option<&ProverId>,
+    ) -> Result<bool, ProcessorError> {
+        let Some(prover_id) = prover_id else {
+            return Ok(false);
+        };
+        let details = self
+            .pool
+            .connection()
+            .await?
+            .proof_generation_dal()
+            .get_proof_generation_details(l1_batch_number)
+            .await?;
+        Ok(details.is_some_and(|details| is_leased_by(&details, prover_id)))
+    }
+
     async fn latest_available_batch(&self) -> Result<L1BatchNumber, ProcessorError> {
         Ok(self
             .pool
@@ -199,3 +428,78 @@ impl Processor {
         })
     }
 }
+
+fn is_leased_by(details: &ProofGenerationDetails, prover_id: &ProverId) -> bool {
+    details.status == ProofGenerationJobStatus::PickedByProver
+        && details.lease.as_ref().is_some_and(|lease| {
+            lease.owner == prover_id.0 && lease.expires_at > chrono::Utc::now()
+        })
+}
+
+fn status_response(details: ProofGenerationDetails) -> ExternalProofStatusResponse {
+    let status = match details.status {
+        ProofGenerationJobStatus::Unpicked => ExternalProofGenerationStatus::Unpicked,
+        ProofGenerationJobStatus::PickedByProver => ExternalProofGenerationStatus::PickedByProver,
+        ProofGenerationJobStatus::Generated => ExternalProofGenerationStatus::Generated,
+        ProofGenerationJobStatus::Skipped => ExternalProofGenerationStatus::Skipped,
+    };
+    ExternalProofStatusResponse {
+        l1_batch_number: details.l1_batch_number,
+        status,
+        lease: details.lease.map(|lease| ExternalProofLeaseInfo {
+            owner: lease.owner,
+            expires_at: lease.expires_at,
+        }),
+        updated_at: details.updated_at,
+    }
+}
+
+/// Checks that the aggregation outputs of the proof match the batch. Unlike the proof data handler,
+/// this doesn't panic on a mismatch since the proof comes from an untrusted party.
+fn check_aggregation_result_coords(
+    l1_batch: &L1BatchWithMetadata,
+    proof: &L1BatchProofForL1,
+) -> Result<(), ProcessorError> {
+    let coords = proof.aggregation_result_coords();
+    let system_logs_hash_from_prover = H256(coords[0]);
+    let state_diff_hash_from_prover = H256(coords[1]);
+    let bootloader_heap_initial_content_from_prover = H256(coords[2]);
+    let events_queue_state_from_prover = H256(coords[3]);
+
+    let metadata = &l1_batch.metadata;
+    let events_queue_state = metadata
+        .events_queue_commitment
+        .ok_or(ProcessorError::Internal)?;
+    let bootloader_heap_initial_content = metadata
+        .bootloader_initial_content_commitment
+        .ok_or(ProcessorError::Internal)?;
+    let system_logs_hash = H256(keccak256(&serialize_commitments(
+        &l1_batch.header.system_logs,
+    )));
+    let protocol_version = l1_batch
+        .header
+        .protocol_version
+        .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+    let state_diff_hash = if protocol_version.is_pre_gateway() {
+        l1_batch.header.system_logs.iter().find_map(|log| {
+            (log.0.key == H256::from_low_u64_be(STATE_DIFF_HASH_KEY_PRE_GATEWAY as u64))
+                .then_some(log.0.value)
+        })
+    } else {
+        metadata.state_diff_hash
+    }
+    .ok_or(ProcessorError::Internal)?;
+
+    if events_queue_state != events_queue_state_from_prover
+        || bootloader_heap_initial_content != bootloader_heap_initial_content_from_prover
+        || state_diff_hash != state_diff_hash_from_prover
+        || system_logs_hash != system_logs_hash_from_prover
+    {
+        tracing::warn!(
+            "Aggregation outputs of the proof for batch {} don't match the batch",
+            l1_batch.header.number
+        );
+        return Err(ProcessorError::InvalidProof);
+    }
+    Ok(())
+}
@@ -0,0 +1,382 @@
+use std::{str::FromStr, time::Duration};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Method, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use bellman::plonk::better_better_cs::proof::Proof;
+use tower::ServiceExt;
+use zksync_basic_types::protocol_version::{L1VerifierConfig, ProtocolSemanticVersion};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_node_test_utils::create_l1_batch;
+use zksync_object_store::{MockObjectStore, StoredObject};
+use zksync_prover_interface::{
+    api::{ExternalProofGenerationStatus, ExternalProofLease, ExternalProofStatusResponse},
+    outputs::{L1BatchProofForL1, PlonkL1BatchProofForL1},
+};
+use zksync_types::{
+    block::L1BatchTreeData,
+    commitment::{
+        serialize_commitments, AuxCommitments, L1BatchCommitmentArtifacts, L1BatchCommitmentMode,
+    },
+    web3::keccak256,
+    L1BatchNumber, ProtocolVersion, ProtocolVersionId, H256,
+};
+use zksync_utils::env::Workspace;
+
+use super::*;
+
+const EVENTS_QUEUE_COMMITMENT: H256 = H256::repeat_byte(1);
+const BOOTLOADER_INITIAL_CONTENT_COMMITMENT: H256 = H256::repeat_byte(2);
+const STATE_DIFF_HASH: H256 = H256::repeat_byte(3);
+
+fn verification_keys_path() -> std::path::PathBuf {
+    Workspace::locate().prover().join("data/keys")
+}
+
+fn snark_wrapper_vk_hash() -> H256 {
+    let commitments =
+        std::fs::read_to_string(verification_keys_path().join("commitments.json")).unwrap();
+    let commitments: serde_json::Value = serde_json::from_str(&commitments).unwrap();
+    H256::from_str(commitments["snark_wrapper"].as_str().unwrap()).unwrap()
+}
+
+/// API key of the prover with the specified ID used in tests.
+fn api_key(prover_id: &str) -> String {
+    format!("{prover_id}_key")
+}
+
+fn create_router(pool: ConnectionPool<Core>, with_verifier: bool, provers: &[&str]) -> Router {
+    let verifier = with_verifier.then(|| ProofVerifier::load(&verification_keys_path()).unwrap());
+    let processor = Processor::new(
+        MockObjectStore::arc(),
+        pool,
+        L1BatchCommitmentMode::Rollup,
+        Duration::from_secs(3_600),
+        1,
+        verifier,
+    );
+    let api_keys = provers
+        .iter()
+        .map(|&prover_id| (api_key(prover_id), prover_id.to_owned()))
+        .collect();
+    Api::new(processor, 0, api_keys).router
+}
+
+async fn prepare_batches(pool: &ConnectionPool<Core>) {
+    let mut conn = pool.connection().await.unwrap();
+    let protocol_version = ProtocolVersion {
+        l1_verifier_config: L1VerifierConfig {
+            snark_wrapper_vk_hash: snark_wrapper_vk_hash(),
+            fflonk_snark_wrapper_vk_hash: None,
+        },
+        ..ProtocolVersion::default()
+    };
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(&protocol_version)
+        .await
+        .unwrap();
+
+    for number in 0..=2 {
+        let number = L1BatchNumber(number);
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(number.0))
+            .await
+            .unwrap();
+        let tree_data = L1BatchTreeData {
+            hash: H256::repeat_byte(0x10 + number.0 as u8),
+            rollup_last_leaf_index: 1,
+        };
+        conn.blocks_dal()
+            .save_l1_batch_tree_data(number, &tree_data)
+            .await
+            .unwrap();
+        let commitment_artifacts = L1BatchCommitmentArtifacts {
+            aux_commitments: Some(AuxCommitments {
+                events_queue_commitment: EVENTS_QUEUE_COMMITMENT,
+                bootloader_initial_content_commitment: BOOTLOADER_INITIAL_CONTENT_COMMITMENT,
+            }),
+            state_diff_hash: STATE_DIFF_HASH,
+            ..L1BatchCommitmentArtifacts::default()
+        };
+        conn.blocks_dal()
+            .save_l1_batch_commitment_artifacts(number, &commitment_artifacts)
+            .await
+            .unwrap();
+        if number.0 == 0 {
+            continue;
+        }
+
+        let mut dal = conn.proof_generation_dal();
+        dal.insert_proof_generation_details(number).await.unwrap();
+        dal.save_vm_runner_artifacts_metadata(number, "vm_run")
+            .await
+            .unwrap();
+        dal.save_merkle_paths_artifacts_metadata(number, "data")
+            .await
+            .unwrap();
+    }
+}
+
+/// Sends a request with the API key of the specified prover, or without an API key if `prover_id` is `None`.
+async fn send(router: &Router, method: Method, uri: &str, prover_id: Option<&str>) -> Response {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(prover_id) = prover_id {
+        request = request.header("x-api-key", api_key(prover_id));
+    }
+    router
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn submit_proof(
+    router: &Router,
+    l1_batch_number: L1BatchNumber,
+    prover_id: &str,
+    proof: &L1BatchProofForL1,
+) -> Response {
+    const BOUNDARY: &str = "proof-boundary";
+
+    let proof = <L1BatchProofForL1 as StoredObject>::serialize(proof).unwrap();
+    let mut body = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"proof\"; filename=\"proof.bin\"\r\n\
+         Content-Type: application/octet-stream\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend(proof);
+    body.extend(format!("\r\n--{BOUNDARY}--\r\n").into_bytes());
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/submit_proof/{l1_batch_number}"))
+        .header("x-api-key", api_key(prover_id))
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={BOUNDARY}"),
+        )
+        .body(Body::from(body))
+        .unwrap();
+    router.clone().oneshot(request).await.unwrap()
+}
+
+async fn batch_status(
+    router: &Router,
+    l1_batch_number: L1BatchNumber,
+) -> ExternalProofStatusResponse {
+    let response = send(
+        router,
+        Method::GET,
+        &format!("/batch_status/{l1_batch_number}"),
+        Some("prover"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+/// Creates an (invalid) proof with aggregation outputs matching the prepared batches.
+fn create_proof(protocol_version: ProtocolSemanticVersion) -> L1BatchProofForL1 {
+    let system_logs_hash = keccak256(&serialize_commitments(&create_l1_batch(1).system_logs));
+    L1BatchProofForL1::new_plonk(PlonkL1BatchProofForL1 {
+        aggregation_result_coords: [
+            system_logs_hash,
+            STATE_DIFF_HASH.0,
+            BOOTLOADER_INITIAL_CONTENT_COMMITMENT.0,
+            EVENTS_QUEUE_COMMITMENT.0,
+        ],
+        scheduler_proof: Proof::empty(),
+        protocol_version,
+    })
+}
+
+#[tokio::test]
+async fn requests_require_api_key() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let router = create_router(pool.clone(), true, &["prover", "other_prover"]);
+
+    for api_key in [None, Some("wrong"), Some("")] {
+        let mut request = Request::builder().uri("/batch_status/1");
+        if let Some(api_key) = api_key {
+            request = request.header("x-api-key", api_key);
+        }
+        let response = router
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{api_key:?}");
+    }
+    let response = send(&router, Method::POST, "/lease_batch", None).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Authenticated requests reach the handler, which doesn't know about the batch.
+    let response = send(&router, Method::GET, "/batch_status/1", Some("prover")).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Endpoints that predate authentication don't require an API key, but reject invalid keys.
+    let response = send(&router, Method::GET, "/proof_generation_data/1", None).await;
+    assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = send(
+        &router,
+        Method::GET,
+        "/proof_generation_data/1",
+        Some("unknown"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn api_without_configured_keys() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_batches(&pool).await;
+    let router = create_router(pool, true, &[]);
+
+    // Existing endpoints keep working without API keys.
+    let response = send(&router, Method::GET, "/proof_generation_data/1", None).await;
+    assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = send(&router, Method::GET, "/proof_generation_data", None).await;
+    assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Leasing and other prover-specific endpoints are unavailable.
+    for (method, uri) in [
+        (Method::POST, "/lease_batch"),
+        (Method::GET, "/batch_status/1"),
+        (Method::GET, "/batch_status/1/stream"),
+    ] {
+        let response = send(&router, method, uri, Some("prover")).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn leasing_batches() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_batches(&pool).await;
+    let router = create_router(
+        pool.clone(),
+        true,
+        &["prover", "other_prover", "third_prover"],
+    );
+
+    let response = send(&router, Method::POST, "/lease_batch", Some("prover")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let lease: ExternalProofLease = serde_json::from_slice(&body).unwrap();
+    assert_eq!(lease.l1_batch_number, L1BatchNumber(1));
+
+    let status = batch_status(&router, L1BatchNumber(1)).await;
+    assert_eq!(status.status, ExternalProofGenerationStatus::PickedByProver);
+    assert_eq!(status.lease.unwrap().owner, "prover");
+
+    // The prover already holds the maximum number of leases.
+    let response = send(&router, Method::POST, "/lease_batch", Some("prover")).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = send(&router, Method::POST, "/lease_batch", Some("other_prover")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let lease: ExternalProofLease = serde_json::from_slice(&body).unwrap();
+    assert_eq!(lease.l1_batch_number, L1BatchNumber(2));
+
+    let response = send(&router, Method::POST, "/lease_batch", Some("third_prover")).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn leasing_and_submission_are_disabled_without_verification_keys() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_batches(&pool).await;
+    let router = create_router(pool, false, &["prover"]);
+
+    let response = send(&router, Method::POST, "/lease_batch", Some("prover")).await;
+    assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+    let proof = create_proof(ProtocolSemanticVersion::default());
+    let response = submit_proof(&router, L1BatchNumber(1), "prover", &proof).await;
+    assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+}
+
+#[tokio::test]
+async fn submitting_proofs() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_batches(&pool).await;
+    let router = create_router(pool.clone(), true, &["prover", "other_prover"]);
+    let response = send(&router, Method::POST, "/lease_batch", Some("prover")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let proof = create_proof(ProtocolSemanticVersion::default());
+    let response = submit_proof(&router, L1BatchNumber(1), "other_prover", &proof).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = submit_proof(&router, L1BatchNumber(2), "prover", &proof).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = submit_proof(&router, L1BatchNumber(3), "prover", &proof).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let other_version = ProtocolSemanticVersion::new(ProtocolVersionId::Version0, 0.into());
+    let response = submit_proof(
+        &router,
+        L1BatchNumber(1),
+        "prover",
+        &create_proof(other_version),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // The aggregation outputs match the batch, but the SNARK proof itself is invalid.
+    let response = submit_proof(&router, L1BatchNumber(1), "prover", &proof).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let status = batch_status(&router, L1BatchNumber(1)).await;
+    assert_eq!(status.status, ExternalProofGenerationStatus::PickedByProver);
+}
+
+#[tokio::test]
+async fn streaming_batch_status() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_batches(&pool).await;
+    let router = create_router(pool.clone(), true, &["prover"]);
+
+    let response = send(
+        &router,
+        Method::GET,
+        "/batch_status/3/stream",
+        Some("prover"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    pool.connection()
+        .await
+        .unwrap()
+        .proof_generation_dal()
+        .save_proof_artifacts_metadata(L1BatchNumber(1), "proof")
+        .await
+        .unwrap();
+    // The stream ends after the first event since the status is final.
+    let response = send(
+        &router,
+        Method::GET,
+        "/batch_status/1/stream",
+        Some("prover"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/event-stream"
+    );
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    let mut lines = body.lines().filter(|line| !line.is_empty());
+    assert_eq!(lines.next(), Some("event: status"));
+    let data = lines.next().unwrap().strip_prefix("data: ").unwrap();
+    let status: ExternalProofStatusResponse = serde_json::from_str(data).unwrap();
+    assert_eq!(status.l1_batch_number, L1BatchNumber(1));
+    assert_eq!(status.status, ExternalProofGenerationStatus::Generated);
+    assert_eq!(lines.next(), None);
+}
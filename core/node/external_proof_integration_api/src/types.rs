@@ -1,6 +1,6 @@
 use axum::{
-    extract::{FromRequest, Multipart, Request},
-    http::header,
+    extract::{FromRequest, FromRequestParts, Multipart, Request},
+    http::{header, request::Parts},
     response::{IntoResponse, Response},
 };
 use zksync_basic_types::protocol_version::ProtocolSemanticVersion;
@@ -38,10 +38,30 @@ impl IntoResponse for ProofGenerationDataResponse {
     }
 }
 
+/// Identifier of an external prover mapped to the API key provided with the request (see [`ApiKeyAuth`]).
+/// Used as the owner of batch leases.
+///
+/// [`ApiKeyAuth`]: crate::middleware::ApiKeyAuth
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ProverId(pub String);
+
+#[async_trait::async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ProverId {
+    type Rejection = ProcessorError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Self>()
+            .cloned()
+            .ok_or(ProcessorError::Unauthorized)
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct ExternalProof {
     raw: Vec<u8>,
-    protocol_version: ProtocolSemanticVersion,
+    proof: L1BatchProofForL1,
 }
 
 impl ExternalProof {
@@ -49,11 +69,19 @@ impl ExternalProof {
     const CONTENT_TYPE: &'static str = "application/octet-stream";
 
     pub fn protocol_version(&self) -> ProtocolSemanticVersion {
-        self.protocol_version
+        self.proof.protocol_version()
+    }
+
+    pub fn proof(&self) -> &L1BatchProofForL1 {
+        &self.proof
+    }
+
+    pub fn into_proof(self) -> L1BatchProofForL1 {
+        self.proof
     }
 
     pub fn verify(&self, correct: L1BatchProofForL1) -> Result<(), ProcessorError> {
-        if correct.protocol_version() != self.protocol_version {
+        if correct.protocol_version() != self.protocol_version() {
             return Err(ProcessorError::InvalidProof);
         }
 
@@ -102,7 +130,7 @@ impl<S: Send + Sync> FromRequest<S> for ExternalProof {
 
         Ok(Self {
             raw: serialized_proof,
-            protocol_version: proof.protocol_version(),
+            proof,
         })
     }
 }
@@ -0,0 +1,280 @@
+//! Verification of SNARK proofs submitted by external provers.
+
+use std::{fmt, fs, path::Path};
+
+use anyhow::Context as _;
+use bellman::{
+    plonk::{
+        better_better_cs::{setup::VerificationKey, verifier::verify as verify_plonk},
+        commitments::transcript::keccak_transcript::RollingKeccakTranscript,
+    },
+    CurveAffine, PrimeField, PrimeFieldRepr,
+};
+use circuit_definitions::{
+    boojum::pairing::bn256::{Bn256, Fr},
+    circuit_definitions::aux_layer::{
+        ZkSyncSnarkWrapperCircuit, ZkSyncSnarkWrapperCircuitNoLookupCustomGate,
+    },
+};
+use fflonk::{verify as verify_fflonk, FflonkVerificationKey};
+use zksync_basic_types::protocol_version::L1VerifierConfig;
+use zksync_prover_interface::outputs::{L1BatchProofForL1, TypedL1BatchProofForL1};
+use zksync_types::{web3::keccak256, H256, U256};
+
+use crate::error::ProcessorError;
+
+type PlonkVerificationKey = VerificationKey<Bn256, ZkSyncSnarkWrapperCircuit>;
+type FflonkSnarkVerificationKey =
+    FflonkVerificationKey<Bn256, ZkSyncSnarkWrapperCircuitNoLookupCustomGate>;
+
+/// Number of bits the public input hash is shifted by to fit into the scalar field; matches the L1 executor contract.
+const PUBLIC_INPUT_SHIFT: usize = 32;
+
+/// Verifies SNARK proofs the same way the L1 verifier contract does: against the SNARK wrapper verification key,
+/// with the public input computed from the commitments of the proven batch and the previous one.
+pub struct ProofVerifier {
+    plonk_vk: PlonkVerificationKey,
+    plonk_vk_hash: H256,
+    fflonk_vk: Option<(FflonkSnarkVerificationKey, H256)>,
+}
+
+impl fmt::Debug for ProofVerifier {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ProofVerifier")
+            .field("plonk_vk_hash", &self.plonk_vk_hash)
+            .field(
+                "fflonk_vk_hash",
+                &self.fflonk_vk.as_ref().map(|(_, hash)| hash),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl ProofVerifier {
+    const PLONK_VK_FILE_NAME: &'static str = "verification_snark_key.json";
+    const FFLONK_VK_FILE_NAME: &'static str = "fflonk_verification_snark_key.json";
+
+    /// Loads verification keys from the specified directory. The FFLONK key is optional.
+    pub fn load(keys_path: &Path) -> anyhow::Result<Self> {
+        let plonk_vk_path = keys_path.join(Self::PLONK_VK_FILE_NAME);
+        let plonk_vk = fs::read_to_string(&plonk_vk_path)
+            .with_context(|| format!("failed reading {plonk_vk_path:?}"))?;
+        let plonk_vk: PlonkVerificationKey = serde_json::from_str(&plonk_vk)
+            .with_context(|| format!("failed parsing {plonk_vk_path:?}"))?;
+        let plonk_vk_hash = plonk_vk_hash(&plonk_vk).context("failed hashing PLONK key")?;
+
+        let fflonk_vk_path = keys_path.join(Self::FFLONK_VK_FILE_NAME);
+        let fflonk_vk = if fflonk_vk_path.exists() {
+            let fflonk_vk = fs::read_to_string(&fflonk_vk_path)
+                .with_context(|| format!("failed reading {fflonk_vk_path:?}"))?;
+            let fflonk_vk: FflonkSnarkVerificationKey = serde_json::from_str(&fflonk_vk)
+                .with_context(|| format!("failed parsing {fflonk_vk_path:?}"))?;
+            let hash = fflonk_vk_hash(&fflonk_vk).context("failed hashing FFLONK key")?;
+            Some((fflonk_vk, hash))
+        } else {
+            None
+        };
+
+        tracing::info!(
+            "Loaded SNARK wrapper verification keys; PLONK key hash: {plonk_vk_hash:?}, FFLONK key hash: {:?}",
+            fflonk_vk.as_ref().map(|(_, hash)| hash)
+        );
+        Ok(Self {
+            plonk_vk,
+            plonk_vk_hash,
+            fflonk_vk,
+        })
+    }
+
+    /// Verifies a proof for a batch. `verifier_config` must correspond to the protocol version of the batch.
+    /// This is CPU-heavy, so it shouldn't be called on async runtime threads.
+    pub(crate) fn verify(
+        &self,
+        proof: &L1BatchProofForL1,
+        verifier_config: &L1VerifierConfig,
+        prev_batch_commitment: H256,
+        batch_commitment: H256,
+    ) -> Result<(), ProcessorError> {
+        let expected_input = public_input(prev_batch_commitment, batch_commitment);
+        let is_valid = match proof.inner() {
+            TypedL1BatchProofForL1::Plonk(proof) => {
+                check_vk_hash(
+                    "PLONK",
+                    self.plonk_vk_hash,
+                    verifier_config.snark_wrapper_vk_hash,
+                )?;
+                let proof = &proof.scheduler_proof;
+                has_public_input(&proof.inputs, expected_input)
+                    && verify_plonk::<_, _, RollingKeccakTranscript<Fr>>(
+                        &self.plonk_vk,
+                        proof,
+                        None,
+                    )
+                    .unwrap_or(false)
+            }
+            TypedL1BatchProofForL1::Fflonk(proof) => {
+                let Some(expected_vk_hash) = verifier_config.fflonk_snark_wrapper_vk_hash else {
+                    tracing::info!(
+                        "FFLONK proof submitted for a protocol version without FFLONK verifier"
+                    );
+                    return Err(ProcessorError::InvalidProof);
+                };
+                let Some((fflonk_vk, vk_hash)) = &self.fflonk_vk else {
+                    tracing::error!(
+                        "FFLONK verification key is not configured, cannot verify FFLONK proofs"
+                    );
+                    return Err(ProcessorError::Internal);
+                };
+                check_vk_hash("FFLONK", *vk_hash, expected_vk_hash)?;
+                let proof = &proof.scheduler_proof;
+                has_public_input(&proof.inputs, expected_input)
+                    && verify_fflonk::<_, _, RollingKeccakTranscript<Fr>>(fflonk_vk, proof, None)
+                        .unwrap_or(false)
+            }
+        };
+
+        if is_valid {
+            Ok(())
+        } else {
+            Err(ProcessorError::InvalidProof)
+        }
+    }
+}
+
+fn check_vk_hash(kind: &str, actual: H256, expected: H256) -> Result<(), ProcessorError> {
+    if actual == expected {
+        Ok(())
+    } else {
+        tracing::error!(
+            "{kind} verification key hash {actual:?} doesn't match the hash {expected:?} for the batch protocol version; \
+             update the configured verification keys"
+        );
+        Err(ProcessorError::Internal)
+    }
+}
+
+/// Computes the proof public input the same way as the L1 executor contract.
+fn public_input(prev_batch_commitment: H256, batch_commitment: H256) -> U256 {
+    let hash = keccak256(
+        &[
+            prev_batch_commitment.as_bytes(),
+            batch_commitment.as_bytes(),
+        ]
+        .concat(),
+    );
+    U256::from_big_endian(&hash) >> PUBLIC_INPUT_SHIFT
+}
+
+fn has_public_input(inputs: &[Fr], expected: U256) -> bool {
+    let [input] = inputs else {
+        return false;
+    };
+    let mut bytes = [0_u8; 32];
+    input
+        .into_repr()
+        .write_be(&mut bytes[..])
+        .expect("failed serializing field element");
+    U256::from_big_endian(&bytes) == expected
+}
+
+fn write_point<G: CurveAffine>(point: &G, buffer: &mut Vec<u8>) -> anyhow::Result<()>
+where
+    G::Base: PrimeField,
+{
+    let (x, y) = point.as_xy();
+    x.into_repr().write_be(&mut *buffer)?;
+    y.into_repr().write_be(&mut *buffer)?;
+    Ok(())
+}
+
+/// Computes the hash of the PLONK key the same way as the L1 verifier contract.
+fn plonk_vk_hash(vk: &PlonkVerificationKey) -> anyhow::Result<H256> {
+    anyhow::ensure!(
+        vk.gate_setup_commitments.len() == 8,
+        "unexpected number of gate setup commitments"
+    );
+    anyhow::ensure!(
+        vk.gate_selectors_commitments.len() == 2,
+        "unexpected number of gate selector commitments"
+    );
+    anyhow::ensure!(
+        vk.permutation_commitments.len() == 4,
+        "unexpected number of permutation commitments"
+    );
+    anyhow::ensure!(
+        vk.lookup_tables_commitments.len() == 4,
+        "unexpected number of lookup table commitments"
+    );
+    let lookup_selector = vk
+        .lookup_selector_commitment
+        .context("missing lookup selector commitment")?;
+    let lookup_table_type = vk
+        .lookup_table_type_commitment
+        .context("missing lookup table type commitment")?;
+
+    let mut buffer = vec![];
+    let points = vk
+        .gate_setup_commitments
+        .iter()
+        .chain(&vk.gate_selectors_commitments)
+        .chain(&vk.permutation_commitments)
+        .chain([&lookup_selector])
+        .chain(&vk.lookup_tables_commitments)
+        .chain([&lookup_table_type]);
+    for point in points {
+        write_point(point, &mut buffer)?;
+    }
+    // Flag for using the recursive part
+    buffer.extend([0_u8; 32]);
+    Ok(H256(keccak256(&buffer)))
+}
+
+/// Computes the hash of the FFLONK key the same way as the L1 verifier contract.
+fn fflonk_vk_hash(vk: &FflonkSnarkVerificationKey) -> anyhow::Result<H256> {
+    let mut buffer = [0_u8; 32].to_vec();
+    U256::from(vk.num_inputs).to_big_endian(&mut buffer);
+    write_point(&vk.c0, &mut buffer)?;
+    for non_residue in &vk.non_residues {
+        non_residue.into_repr().write_be(&mut buffer)?;
+    }
+    for g2_element in &vk.g2_elements {
+        buffer.extend(g2_element.into_uncompressed().as_ref());
+    }
+    Ok(H256(keccak256(&buffer)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bellman::Field;
+    use zksync_utils::env::Workspace;
+
+    use super::*;
+
+    #[test]
+    fn verification_key_hashes() {
+        let keys_path = Workspace::locate().prover().join("data/keys");
+        let verifier = ProofVerifier::load(&keys_path).unwrap();
+
+        let commitments = fs::read_to_string(keys_path.join("commitments.json")).unwrap();
+        let commitments: serde_json::Value = serde_json::from_str(&commitments).unwrap();
+        let expected_hash =
+            |field: &str| H256::from_str(commitments[field].as_str().unwrap()).unwrap();
+        assert_eq!(verifier.plonk_vk_hash, expected_hash("snark_wrapper"));
+        let (_, fflonk_vk_hash) = verifier.fflonk_vk.as_ref().unwrap();
+        assert_eq!(*fflonk_vk_hash, expected_hash("fflonk_snark_wrapper"));
+    }
+
+    #[test]
+    fn public_input_fits_into_field() {
+        let input = public_input(H256::repeat_byte(1), H256::repeat_byte(2));
+        assert!(input.bits() <= 256 - PUBLIC_INPUT_SHIFT);
+
+        let input_fr = <Fr as PrimeField>::from_str(&input.to_string()).unwrap();
+        assert!(has_public_input(&[input_fr], input));
+        assert!(!has_public_input(&[input_fr, input_fr], input));
+        assert!(!has_public_input(&[Fr::one()], input));
+    }
+}
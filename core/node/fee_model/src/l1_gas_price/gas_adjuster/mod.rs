@@ -6,7 +6,7 @@ use std::{
 };
 
 use tokio::sync::watch;
-use zksync_config::GasAdjusterConfig;
+use zksync_config::{configs::GasPricingMultipliers, GasAdjusterConfig};
 use zksync_eth_client::EthFeeInterface;
 use zksync_types::{
     commitment::L1BatchCommitmentMode, pubdata_da::PubdataSendingMode, L1_GAS_PER_PUBDATA_BYTE,
@@ -61,6 +61,8 @@ pub struct GasAdjuster {
     pub(super) gas_per_pubdata_price_statistic: GasStatistics<u64>,
 
     pub(super) config: GasAdjusterConfig,
+    /// Initialized from `config`, but can be changed at runtime.
+    pricing_multipliers: RwLock<GasPricingMultipliers>,
     pubdata_sending_mode: PubdataSendingMode,
     client: GasAdjusterClient,
     commitment_mode: L1BatchCommitmentMode,
@@ -113,18 +115,39 @@ impl GasAdjuster {
                 .map(|base_fee| base_fee.gas_per_pubdata()),
         );
 
+        let pricing_multipliers = RwLock::new(GasPricingMultipliers {
+            internal_l1_pricing_multiplier: config.internal_l1_pricing_multiplier,
+            internal_pubdata_pricing_multiplier: config.internal_pubdata_pricing_multiplier,
+        });
         Ok(Self {
             base_fee_statistics,
             blob_base_fee_statistics,
             l2_pubdata_price_statistics,
             gas_per_pubdata_price_statistic,
             config,
+            pricing_multipliers,
             pubdata_sending_mode,
             client,
             commitment_mode,
         })
     }
 
+    pub fn pricing_multipliers(&self) -> GasPricingMultipliers {
+        *self
+            .pricing_multipliers
+            .read()
+            .expect("pricing multipliers lock is poisoned")
+    }
+
+    /// Changes pricing multipliers; the change is applied to all subsequent price estimates.
+    pub fn set_pricing_multipliers(&self, multipliers: GasPricingMultipliers) {
+        tracing::info!("Changing gas adjuster pricing multipliers to {multipliers:?}");
+        *self
+            .pricing_multipliers
+            .write()
+            .expect("pricing multipliers lock is poisoned") = multipliers;
+    }
+
     /// Performs an actualization routine for `GasAdjuster`.
     /// This method is intended to be invoked periodically.
     pub async fn keep_updated(&self) -> anyhow::Result<()> {
@@ -235,8 +258,8 @@ impl GasAdjuster {
 
         let effective_gas_price = self.get_base_fee(0) + self.get_priority_fee();
 
-        let calculated_price =
-            (self.config.internal_l1_pricing_multiplier * effective_gas_price as f64) as u64;
+        let multiplier = self.pricing_multipliers().internal_l1_pricing_multiplier;
+        let calculated_price = (multiplier * effective_gas_price as f64) as u64;
 
         // Bound the price if it's too high.
         self.bound_gas_price(calculated_price)
@@ -264,7 +287,9 @@ impl GasAdjuster {
                     .set(blob_base_fee_median.as_u64());
                 let calculated_price = blob_base_fee_median.as_u64() as f64
                     * BLOB_GAS_PER_BYTE as f64
-                    * self.config.internal_pubdata_pricing_multiplier;
+                    * self
+                        .pricing_multipliers()
+                        .internal_pubdata_pricing_multiplier;
 
                 self.cap_pubdata_fee(calculated_price)
             }
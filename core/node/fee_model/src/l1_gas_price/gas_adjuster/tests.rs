@@ -1,7 +1,7 @@
 use std::{collections::VecDeque, sync::RwLockReadGuard};
 
 use test_casing::test_casing;
-use zksync_config::GasAdjusterConfig;
+use zksync_config::{configs::GasPricingMultipliers, GasAdjusterConfig};
 use zksync_eth_client::{clients::MockSettlementLayer, BaseFees};
 use zksync_types::{commitment::L1BatchCommitmentMode, pubdata_da::PubdataSendingMode};
 use zksync_web3_decl::client::{DynClient, L1, L2};
@@ -208,3 +208,44 @@ async fn kept_updated_l2(commitment_mode: L1BatchCommitmentMode) {
         expected_median_blob_base_fee.into()
     );
 }
+
+#[tokio::test]
+async fn changing_pricing_multipliers() {
+    let base_fees = TEST_BLOCK_FEES
+        .into_iter()
+        .map(|block| BaseFees {
+            base_fee_per_gas: block,
+            base_fee_per_blob_gas: 1.into(),
+            l2_pubdata_price: 0.into(),
+        })
+        .collect();
+    let eth_client = MockSettlementLayer::builder()
+        .with_fee_history(base_fees)
+        .build();
+    eth_client.advance_block_number(6);
+
+    let client: Box<DynClient<L1>> = Box::new(eth_client.into_client());
+    let adjuster = GasAdjuster::new(
+        GasAdjusterClient::from(client),
+        test_config(),
+        PubdataSendingMode::Calldata,
+        L1BatchCommitmentMode::Rollup,
+    )
+    .await
+    .unwrap();
+
+    let raw_gas_price = adjuster.get_base_fee(0) + adjuster.get_priority_fee();
+    assert_eq!(
+        adjuster.estimate_effective_gas_price(),
+        (0.8 * raw_gas_price as f64) as u64
+    );
+
+    adjuster.set_pricing_multipliers(GasPricingMultipliers {
+        internal_l1_pricing_multiplier: 2.0,
+        internal_pubdata_pricing_multiplier: 1.0,
+    });
+    assert_eq!(
+        adjuster.estimate_effective_gas_price(),
+        (2.0 * raw_gas_price as f64) as u64
+    );
+}
@@ -9,6 +9,8 @@ use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics
 pub(super) enum MerkleTreeApiMethod {
     Info,
     GetProofs,
+    /// Latency is measured for each query in the batch separately.
+    GetProofsBatch,
     GetNodes,
     GetStaleKeys,
     GetBogusStaleKeys,
@@ -1,22 +1,28 @@
 //! Primitive Merkle tree API used internally to fetch proofs.
 
-use std::{collections::HashMap, fmt, future::Future, net::SocketAddr, pin::Pin};
+use std::{
+    collections::HashMap, fmt, future::Future, mem, net::SocketAddr, num::NonZeroUsize, pin::Pin,
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
 use axum::{
+    body::Body,
     extract::State,
     http::{header, StatusCode},
     response::{IntoResponse, Response},
     routing, Json, Router,
 };
+use futures::{stream, StreamExt, TryStreamExt};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use tokio::sync::watch;
 use zksync_crypto_primitives::hasher::blake2::Blake2Hasher;
 use zksync_health_check::{CheckHealth, Health, HealthStatus};
+// Re-exported since it's a part of the `TreeApiClient` interface.
+pub use zksync_merkle_tree::NoVersionError;
 use zksync_merkle_tree::{
     unstable::{NodeKey, RawNode},
-    NoVersionError, ValueHash,
+    TreeRangeDigest, ValueHash,
 };
 use zksync_types::{u256_to_h256, web3, L1BatchNumber, H256, U256};
 
@@ -64,8 +70,7 @@ impl TreeEntryWithProof {
         }
     }
 
-    /// Verifies the entry.
-    pub fn verify(&self, key: U256, trusted_root_hash: H256) -> anyhow::Result<()> {
+    fn to_tree_entry(&self, key: U256) -> zksync_merkle_tree::TreeEntryWithProof {
         let mut merkle_path = self.merkle_path.clone();
         merkle_path.reverse();
         zksync_merkle_tree::TreeEntryWithProof {
@@ -76,7 +81,228 @@ impl TreeEntryWithProof {
             },
             merkle_path,
         }
-        .verify(&Blake2Hasher, trusted_root_hash)
+    }
+
+    /// Checks whether this entry proves that the key is absent from the tree.
+    pub fn is_absent(&self) -> bool {
+        self.index == 0
+    }
+
+    /// Verifies the entry.
+    pub fn verify(&self, key: U256, trusted_root_hash: H256) -> anyhow::Result<()> {
+        self.to_tree_entry(key)
+            .verify(&Blake2Hasher, trusted_root_hash)
+    }
+}
+
+/// Tree entry without a Merkle proof. Used for intermediate entries in a [`TreeRangeProof`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TreeRangeEntry {
+    #[serde(default, skip_serializing_if = "H256::is_zero")]
+    pub value: H256,
+    #[serde(default, skip_serializing_if = "TreeEntryWithProof::is_zero")]
+    pub index: u64,
+}
+
+/// Proof for a range of hashed keys. Only the first and the last entries in the range come with Merkle proofs;
+/// intermediate entries are used to reconstruct the rest of the tree.
+///
+/// A range proof can only be verified if the range contains all non-empty tree entries between its first
+/// and last keys. Thus, a successfully verified range proof additionally proves that there are no other entries
+/// in the range.
+///
+/// The first and the last entries are boundary proofs, each of which is verified on its own. If a boundary key is
+/// [absent](TreeEntryWithProof::is_absent()) from the tree, its proof shows that the key is missing; thus, choosing
+/// missing keys as range ends proves that the range covers all tree entries between these ends.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TreeRangeProof {
+    pub start: TreeEntryWithProof,
+    pub entries: Vec<TreeRangeEntry>,
+    pub end: TreeEntryWithProof,
+}
+
+impl TreeRangeProof {
+    /// Verifies this proof. `hashed_keys` must be the same keys as used in the proof query.
+    pub fn verify(&self, hashed_keys: &[U256], trusted_root_hash: H256) -> anyhow::Result<()> {
+        let [start_key, intermediate_keys @ .., end_key] = hashed_keys else {
+            anyhow::bail!("range must contain at least 2 keys");
+        };
+        anyhow::ensure!(
+            hashed_keys.windows(2).all(|window| window[0] < window[1]),
+            "range keys are not sorted in the ascending order"
+        );
+        anyhow::ensure!(
+            intermediate_keys.len() == self.entries.len(),
+            "unexpected number of intermediate entries: expected {}, got {}",
+            intermediate_keys.len(),
+            self.entries.len()
+        );
+        // Corresponds to the tree depth; longer paths would lead to a panic in `TreeRangeDigest`.
+        const MAX_MERKLE_PATH_LEN: usize = 256;
+        anyhow::ensure!(
+            self.start.merkle_path.len() <= MAX_MERKLE_PATH_LEN
+                && self.end.merkle_path.len() <= MAX_MERKLE_PATH_LEN,
+            "Merkle path is too long"
+        );
+
+        self.start
+            .verify(*start_key, trusted_root_hash)
+            .context("invalid start boundary proof")?;
+        self.end
+            .verify(*end_key, trusted_root_hash)
+            .context("invalid end boundary proof")?;
+
+        let start = self.start.to_tree_entry(*start_key);
+        let mut digest = TreeRangeDigest::new(&Blake2Hasher, *start_key, &start);
+        for (&key, entry) in intermediate_keys.iter().zip(&self.entries) {
+            anyhow::ensure!(
+                entry.index != 0 || entry.value.is_zero(),
+                "invalid entry for key {key:#x}: leaf index is zero, but value is non-default"
+            );
+            digest.update(zksync_merkle_tree::TreeEntry {
+                key,
+                value: entry.value,
+                leaf_index: entry.index,
+            });
+        }
+        let root_hash = digest.finalize(&self.end.to_tree_entry(*end_key));
+        anyhow::ensure!(
+            root_hash == trusted_root_hash,
+            "Root hash mismatch: got {root_hash:?}, want {trusted_root_hash:?}"
+        );
+        Ok(())
+    }
+}
+
+/// Query for [`TreeApiClient::get_proofs_batch()`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeProofsQuery {
+    /// Tree version (= L1 batch number) to query.
+    pub l1_batch_number: L1BatchNumber,
+    pub hashed_keys: Vec<U256>,
+    /// Whether to request a single range proof for `hashed_keys` instead of proofs for each key.
+    #[serde(default)]
+    pub range: bool,
+}
+
+impl TreeProofsQuery {
+    /// Creates a query for proofs for each of the specified keys.
+    pub fn entries(l1_batch_number: L1BatchNumber, hashed_keys: Vec<U256>) -> Self {
+        Self {
+            l1_batch_number,
+            hashed_keys,
+            range: false,
+        }
+    }
+
+    /// Creates a query for a range proof. `hashed_keys` must contain at least 2 keys sorted in the ascending order.
+    pub fn range(l1_batch_number: L1BatchNumber, hashed_keys: Vec<U256>) -> Self {
+        Self {
+            l1_batch_number,
+            hashed_keys,
+            range: true,
+        }
+    }
+
+    fn validate(&self) -> Result<(), &'static str> {
+        if self.hashed_keys.len() > MAX_KEYS_PER_BATCH {
+            return Err("query contains too many keys");
+        }
+        if self.range {
+            if self.hashed_keys.len() < 2 {
+                return Err("range must contain at least 2 keys");
+            }
+            if !self
+                .hashed_keys
+                .windows(2)
+                .all(|window| window[0] < window[1])
+            {
+                return Err("range keys are not sorted in the ascending order");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Proofs returned for a [`TreeProofsQuery`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TreeProofs {
+    /// Proofs for each of the queried keys, in the same order as the keys.
+    Entries(Vec<TreeEntryWithProof>),
+    /// Range proof for the queried keys.
+    Range(TreeRangeProof),
+}
+
+/// Maximum number of queries in a single batched proofs request.
+const MAX_QUERIES_PER_BATCH: usize = 1_024;
+/// Maximum total number of keys in all queries of a single batched proofs request.
+const MAX_KEYS_PER_BATCH: usize = 16_384;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TreeProofsBatchRequest {
+    queries: Vec<TreeProofsQuery>,
+}
+
+impl TreeProofsBatchRequest {
+    fn validate(&self) -> Result<(), TreeApiServerError> {
+        let key_count: usize = self
+            .queries
+            .iter()
+            .map(|query| query.hashed_keys.len())
+            .sum();
+        if self.queries.len() > MAX_QUERIES_PER_BATCH || key_count > MAX_KEYS_PER_BATCH {
+            return Err(TreeApiServerError::BatchTooLarge {
+                query_count: self.queries.len(),
+                key_count,
+            });
+        }
+
+        for (query_index, query) in self.queries.iter().enumerate() {
+            query
+                .validate()
+                .map_err(|reason| TreeApiServerError::InvalidQuery {
+                    query_index,
+                    reason,
+                })?;
+        }
+        Ok(())
+    }
+}
+
+/// Item of the batched proofs response, which is streamed as newline-delimited JSON.
+#[derive(Debug, Serialize, Deserialize)]
+struct TreeProofsBatchItem {
+    l1_batch_number: L1BatchNumber,
+    #[serde(flatten)]
+    outcome: TreeProofsBatchOutcome,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TreeProofsBatchOutcome {
+    Entries(Vec<TreeEntryWithProof>),
+    Range(TreeRangeProof),
+    NoVersion(NoVersionErrorData),
+}
+
+impl From<Result<TreeProofs, NoVersionError>> for TreeProofsBatchOutcome {
+    fn from(result: Result<TreeProofs, NoVersionError>) -> Self {
+        match result {
+            Ok(TreeProofs::Entries(entries)) => Self::Entries(entries),
+            Ok(TreeProofs::Range(proof)) => Self::Range(proof),
+            Err(err) => Self::NoVersion(err.into()),
+        }
+    }
+}
+
+impl TreeProofsBatchOutcome {
+    fn into_result(self) -> Result<TreeProofs, NoVersionError> {
+        match self {
+            Self::Entries(entries) => Ok(TreeProofs::Entries(entries)),
+            Self::Range(proof) => Ok(TreeProofs::Range(proof)),
+            Self::NoVersion(data) => Err(data.into()),
+        }
     }
 }
 
@@ -195,6 +421,14 @@ struct StaleKeysResponse {
 #[derive(Debug)]
 enum TreeApiServerError {
     NoTreeVersion(NoVersionError),
+    InvalidQuery {
+        query_index: usize,
+        reason: &'static str,
+    },
+    BatchTooLarge {
+        query_count: usize,
+        key_count: usize,
+    },
 }
 
 // Contains the same fields as `NoVersionError` and is serializable.
@@ -213,6 +447,17 @@ impl From<NoVersionError> for NoVersionErrorData {
     }
 }
 
+#[derive(Debug, Serialize)]
+struct InvalidQueryErrorData {
+    query_index: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchLimitsErrorData {
+    max_queries: usize,
+    max_keys: usize,
+}
+
 impl From<NoVersionErrorData> for NoVersionError {
     fn from(data: NoVersionErrorData) -> Self {
         Self {
@@ -233,6 +478,7 @@ struct Problem<T> {
 }
 
 const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
 
 impl IntoResponse for TreeApiServerError {
     fn into_response(self) -> Response {
@@ -247,6 +493,36 @@ impl IntoResponse for TreeApiServerError {
                 };
                 (StatusCode::NOT_FOUND, headers, Json(body)).into_response()
             }
+            Self::InvalidQuery {
+                query_index,
+                reason,
+            } => {
+                let body = Problem {
+                    r#type: "/errors#invalid-query",
+                    title: "Invalid query",
+                    detail: format!("query #{query_index} is invalid: {reason}"),
+                    data: InvalidQueryErrorData { query_index },
+                };
+                (StatusCode::BAD_REQUEST, headers, Json(body)).into_response()
+            }
+            Self::BatchTooLarge {
+                query_count,
+                key_count,
+            } => {
+                let body = Problem {
+                    r#type: "/errors#batch-too-large",
+                    title: "Batch too large",
+                    detail: format!(
+                        "batch contains {query_count} queries with {key_count} keys in total; \
+                         at most {MAX_QUERIES_PER_BATCH} queries with {MAX_KEYS_PER_BATCH} keys are allowed"
+                    ),
+                    data: BatchLimitsErrorData {
+                        max_queries: MAX_QUERIES_PER_BATCH,
+                        max_keys: MAX_KEYS_PER_BATCH,
+                    },
+                };
+                (StatusCode::PAYLOAD_TOO_LARGE, headers, Json(body)).into_response()
+            }
         }
     }
 }
@@ -289,6 +565,22 @@ pub trait TreeApiClient: 'static + Send + Sync + fmt::Debug {
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
     ) -> Result<Vec<TreeEntryWithProof>, TreeApiError>;
+
+    /// Obtains proofs for multiple queries, potentially targeting different tree versions. Results are returned
+    /// in the same order as `queries`; a missing tree version only fails the corresponding query.
+    async fn get_proofs_batch(
+        &self,
+        queries: Vec<TreeProofsQuery>,
+    ) -> Result<Vec<Result<TreeProofs, NoVersionError>>, TreeApiError>;
+}
+
+fn validate_queries(queries: &[TreeProofsQuery]) -> anyhow::Result<()> {
+    for (i, query) in queries.iter().enumerate() {
+        query
+            .validate()
+            .map_err(|reason| anyhow::anyhow!("query #{i} is invalid: {reason}"))?;
+    }
+    Ok(())
 }
 
 /// In-memory client implementation.
@@ -316,6 +608,24 @@ impl TreeApiClient for LazyAsyncTreeReader {
             Err(TreeApiError::NotReady(None))
         }
     }
+
+    async fn get_proofs_batch(
+        &self,
+        queries: Vec<TreeProofsQuery>,
+    ) -> Result<Vec<Result<TreeProofs, NoVersionError>>, TreeApiError> {
+        /// Maximum number of queries processed concurrently.
+        const MAX_CONCURRENCY: usize = 4;
+
+        let Some(reader) = self.read() else {
+            return Err(TreeApiError::NotReady(None));
+        };
+        validate_queries(&queries)?;
+        Ok(stream::iter(queries)
+            .map(|query| reader.get_tree_proofs_inner(query))
+            .buffered(MAX_CONCURRENCY)
+            .collect()
+            .await)
+    }
 }
 
 /// [`TreeApiClient`] implementation requesting data from a Merkle tree API server.
@@ -324,9 +634,15 @@ pub struct TreeApiHttpClient {
     inner: reqwest::Client,
     info_url: String,
     proofs_url: String,
+    proofs_batch_url: String,
+    batch_size: usize,
+    max_concurrent_requests: usize,
 }
 
 impl TreeApiHttpClient {
+    const DEFAULT_BATCH_SIZE: usize = 64;
+    const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+
     /// Creates a new HTTP client with default settings.
     pub fn new(url_base: &str) -> Self {
         Self::from_client(reqwest::Client::new(), url_base)
@@ -338,7 +654,78 @@ impl TreeApiHttpClient {
             inner: client,
             info_url: url_base.to_owned(),
             proofs_url: format!("{url_base}/proofs"),
+            proofs_batch_url: format!("{url_base}/proofs/batch"),
+            batch_size: Self::DEFAULT_BATCH_SIZE,
+            max_concurrent_requests: Self::DEFAULT_MAX_CONCURRENT_REQUESTS,
+        }
+    }
+
+    /// Sets the maximum number of queries sent in a single HTTP request by [`TreeApiClient::get_proofs_batch()`].
+    /// The default value is 64. Values exceeding the server limit (1,024 queries) are capped.
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: NonZeroUsize) -> Self {
+        self.batch_size = batch_size.get().min(MAX_QUERIES_PER_BATCH);
+        self
+    }
+
+    /// Sets the maximum number of concurrent HTTP requests sent by [`TreeApiClient::get_proofs_batch()`].
+    /// The default value is 4.
+    #[must_use]
+    pub fn with_max_concurrent_requests(mut self, max_concurrent_requests: NonZeroUsize) -> Self {
+        self.max_concurrent_requests = max_concurrent_requests.get();
+        self
+    }
+
+    async fn request_proofs_batch(
+        &self,
+        queries: Vec<TreeProofsQuery>,
+    ) -> Result<Vec<Result<TreeProofs, NoVersionError>>, TreeApiError> {
+        let l1_batch_numbers: Vec<_> = queries.iter().map(|query| query.l1_batch_number).collect();
+        let response = self
+            .inner
+            .post(&self.proofs_batch_url)
+            .json(&TreeProofsBatchRequest { queries })
+            .send()
+            .await
+            .map_err(|err| TreeApiError::for_request(err, "batched proofs"))?;
+        let mut response = response
+            .error_for_status()
+            .context("requesting batched proofs returned non-OK response")?;
+
+        // Items are parsed as they arrive, so that the entire response doesn't need to be buffered.
+        let mut results = Vec::with_capacity(l1_batch_numbers.len());
+        let mut buffer = vec![];
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|err| TreeApiError::for_request(err, "batched proofs"))?
+        {
+            buffer.extend_from_slice(&chunk);
+            while let Some(line_len) = buffer.iter().position(|&byte| byte == b'\n') {
+                let line: Vec<_> = buffer.drain(..=line_len).collect();
+                let item: TreeProofsBatchItem =
+                    serde_json::from_slice(&line).context("failed deserializing batched proofs")?;
+                let expected_number = l1_batch_numbers.get(results.len()).copied();
+                if expected_number != Some(item.l1_batch_number) {
+                    let err = anyhow::anyhow!(
+                        "unexpected item in batched proofs response: expected L1 batch {expected_number:?}, got {}",
+                        item.l1_batch_number
+                    );
+                    return Err(err.into());
+                }
+                results.push(item.outcome.into_result());
+            }
+        }
+
+        if !buffer.is_empty() || results.len() != l1_batch_numbers.len() {
+            let err = anyhow::anyhow!(
+                "batched proofs response is truncated: expected {} items, got {}",
+                l1_batch_numbers.len(),
+                results.len()
+            );
+            return Err(err.into());
         }
+        Ok(results)
     }
 }
 
@@ -421,6 +808,39 @@ impl TreeApiClient for TreeApiHttpClient {
         })?;
         Ok(response.entries)
     }
+
+    async fn get_proofs_batch(
+        &self,
+        queries: Vec<TreeProofsQuery>,
+    ) -> Result<Vec<Result<TreeProofs, NoVersionError>>, TreeApiError> {
+        validate_queries(&queries)?;
+
+        // Split queries so that each request respects both the configured batch size and the server limit on keys.
+        let mut chunks = vec![];
+        let mut chunk = vec![];
+        let mut chunk_key_count = 0;
+        for query in queries {
+            let key_count = query.hashed_keys.len();
+            let is_chunk_full =
+                chunk.len() == self.batch_size || chunk_key_count + key_count > MAX_KEYS_PER_BATCH;
+            if !chunk.is_empty() && is_chunk_full {
+                chunks.push(mem::take(&mut chunk));
+                chunk_key_count = 0;
+            }
+            chunk.push(query);
+            chunk_key_count += key_count;
+        }
+        if !chunk.is_empty() {
+            chunks.push(chunk);
+        }
+
+        let results: Vec<_> = stream::iter(chunks)
+            .map(|chunk| self.request_proofs_batch(chunk))
+            .buffered(self.max_concurrent_requests)
+            .try_collect()
+            .await?;
+        Ok(results.into_iter().flatten().collect())
+    }
 }
 
 impl AsyncTreeReader {
@@ -443,6 +863,45 @@ impl AsyncTreeReader {
         Ok(proofs.into_iter().map(TreeEntryWithProof::new).collect())
     }
 
+    /// Assumes that the query is validated.
+    async fn get_tree_proofs_inner(
+        &self,
+        query: TreeProofsQuery,
+    ) -> Result<TreeProofs, NoVersionError> {
+        let l1_batch_number = query.l1_batch_number;
+        if !query.range {
+            let entries = self
+                .get_proofs_inner(l1_batch_number, query.hashed_keys)
+                .await?;
+            return Ok(TreeProofs::Entries(entries));
+        }
+
+        let [start_key, intermediate_keys @ .., end_key] = query.hashed_keys.as_slice() else {
+            unreachable!("range query must be validated");
+        };
+        let mut proofs = self
+            .get_proofs_inner(l1_batch_number, vec![*start_key, *end_key])
+            .await?;
+        let end = proofs.pop().unwrap();
+        let start = proofs.pop().unwrap();
+        let entries = self
+            .clone()
+            .entries(l1_batch_number, intermediate_keys.to_vec())
+            .await?;
+        let entries = entries
+            .into_iter()
+            .map(|entry| TreeRangeEntry {
+                value: entry.value,
+                index: entry.leaf_index,
+            })
+            .collect();
+        Ok(TreeProofs::Range(TreeRangeProof {
+            start,
+            entries,
+            end,
+        }))
+    }
+
     async fn get_proofs_handler(
         State(this): State<Self>,
         Json(request): Json<TreeProofsRequest>,
@@ -457,6 +916,33 @@ impl AsyncTreeReader {
         Ok(Json(response))
     }
 
+    async fn get_proofs_batch_handler(
+        State(this): State<Self>,
+        Json(request): Json<TreeProofsBatchRequest>,
+    ) -> Result<Response, TreeApiServerError> {
+        request.validate()?;
+
+        // Queries are processed sequentially; each response item is streamed as soon as it's ready.
+        let items = stream::iter(request.queries).then(move |query| {
+            let this = this.clone();
+            async move {
+                let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetProofsBatch].start();
+                let l1_batch_number = query.l1_batch_number;
+                let outcome = this.get_tree_proofs_inner(query).await.into();
+                latency.observe();
+
+                let mut line = serde_json::to_vec(&TreeProofsBatchItem {
+                    l1_batch_number,
+                    outcome,
+                })?;
+                line.push(b'\n');
+                Ok::<_, serde_json::Error>(line)
+            }
+        });
+        let headers = [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)];
+        Ok((headers, Body::from_stream(items)).into_response())
+    }
+
     async fn get_nodes_handler(
         State(this): State<Self>,
         Json(request): Json<TreeNodesRequest>,
@@ -507,6 +993,10 @@ impl AsyncTreeReader {
         let app = Router::new()
             .route("/", routing::get(Self::info_handler))
             .route("/proofs", routing::post(Self::get_proofs_handler))
+            .route(
+                "/proofs/batch",
+                routing::post(Self::get_proofs_batch_handler),
+            )
             .route("/debug/nodes", routing::post(Self::get_nodes_handler))
             .route(
                 "/debug/stale-keys",
@@ -34,7 +34,9 @@ async fn merkle_tree_api() {
         .unwrap();
     let local_addr = *api_server.local_addr();
     let api_server_task = tokio::spawn(api_server.run());
-    let api_client = TreeApiHttpClient::new(&format!("http://{local_addr}"));
+    // Use a small batch size to test splitting batched queries into multiple requests.
+    let api_client = TreeApiHttpClient::new(&format!("http://{local_addr}"))
+        .with_batch_size(NonZeroUsize::new(2).unwrap());
 
     // Wait until the calculator processes initial L1 batches.
     calculator_task.await.unwrap();
@@ -52,7 +54,7 @@ async fn merkle_tree_api() {
     hashed_keys.extend((0_u8..10).map(|byte| U256::from_big_endian(&[byte; 32])));
 
     let proofs = api_client
-        .get_proofs(L1BatchNumber(5), hashed_keys)
+        .get_proofs(L1BatchNumber(5), hashed_keys.clone())
         .await
         .unwrap();
     assert_eq!(proofs.len(), 20);
@@ -72,6 +74,55 @@ async fn merkle_tree_api() {
     assert_eq!(err.version_count, 6);
     assert_eq!(err.missing_version, 10);
 
+    test_batched_proofs(&api_client, tree_info.root_hash, &hashed_keys).await;
+    let err = api_client
+        .get_proofs_batch(vec![TreeProofsQuery::range(
+            L1BatchNumber(5),
+            vec![hashed_keys[1], hashed_keys[0]],
+        )])
+        .await
+        .unwrap_err();
+    assert_matches!(err, TreeApiError::Internal(_));
+
+    let invalid_query_response = api_client
+        .inner
+        .post(format!("http://{local_addr}/proofs/batch"))
+        .json(&serde_json::json!({
+            "queries": [
+                { "l1_batch_number": 5, "hashed_keys": [] },
+                { "l1_batch_number": 5, "hashed_keys": [], "range": true },
+            ],
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(invalid_query_response.status(), StatusCode::BAD_REQUEST);
+    let invalid_query_response: serde_json::Value = invalid_query_response.json().await.unwrap();
+    assert_eq!(invalid_query_response["query_index"], 1);
+
+    let queries: Vec<_> = (0..=MAX_QUERIES_PER_BATCH)
+        .map(|_| TreeProofsQuery::entries(L1BatchNumber(5), vec![]))
+        .collect();
+    let too_large_response = api_client
+        .inner
+        .post(format!("http://{local_addr}/proofs/batch"))
+        .json(&TreeProofsBatchRequest { queries })
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(too_large_response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let too_large_response: serde_json::Value = too_large_response.json().await.unwrap();
+    assert_eq!(too_large_response["max_queries"], MAX_QUERIES_PER_BATCH);
+
+    let err = api_client
+        .get_proofs_batch(vec![TreeProofsQuery::entries(
+            L1BatchNumber(5),
+            vec![U256::zero(); MAX_KEYS_PER_BATCH + 1],
+        )])
+        .await
+        .unwrap_err();
+    assert_matches!(err, TreeApiError::Internal(err) if err.to_string().contains("too many keys"));
+
     let raw_nodes_response = api_client
         .inner
         .post(format!("http://{local_addr}/debug/nodes"))
@@ -116,6 +167,68 @@ async fn merkle_tree_api() {
     api_server_task.await.unwrap().unwrap();
 }
 
+/// `hashed_keys` must start with existing keys.
+async fn test_batched_proofs(client: &dyn TreeApiClient, root_hash: H256, hashed_keys: &[U256]) {
+    // It's overwhelmingly unlikely that there are other keys in the tree between `existing_key - 1` and `existing_key + 1`.
+    let existing_key = hashed_keys[0];
+    let narrow_range = vec![existing_key - 1, existing_key, existing_key + 1];
+    let wide_range = vec![U256::zero(), U256::MAX];
+    let queries = vec![
+        TreeProofsQuery::entries(L1BatchNumber(5), hashed_keys.to_vec()),
+        TreeProofsQuery::range(L1BatchNumber(5), narrow_range.clone()),
+        TreeProofsQuery::entries(L1BatchNumber(10), hashed_keys.to_vec()),
+        TreeProofsQuery::range(L1BatchNumber(5), wide_range.clone()),
+        TreeProofsQuery::entries(L1BatchNumber(4), vec![]),
+    ];
+    let results = client.get_proofs_batch(queries).await.unwrap();
+    assert_eq!(results.len(), 5);
+
+    let Ok(TreeProofs::Entries(entries)) = &results[0] else {
+        panic!("Unexpected result: {:?}", results[0]);
+    };
+    assert_eq!(entries.len(), hashed_keys.len());
+    for (entry, &key) in entries.iter().zip(hashed_keys) {
+        entry.verify(key, root_hash).unwrap();
+    }
+
+    let Ok(TreeProofs::Range(range_proof)) = &results[1] else {
+        panic!("Unexpected result: {:?}", results[1]);
+    };
+    assert_eq!(range_proof.start.index, 0);
+    assert_eq!(range_proof.entries.len(), 1);
+    assert_ne!(range_proof.entries[0].index, 0);
+    assert_eq!(range_proof.end.index, 0);
+    assert!(range_proof.start.is_absent() && range_proof.end.is_absent());
+    range_proof.verify(&narrow_range, root_hash).unwrap();
+    range_proof
+        .verify(&narrow_range, H256::repeat_byte(1))
+        .unwrap_err();
+
+    // Boundary proofs are checked on their own; a boundary entry cannot claim a value for a missing key.
+    let mut tampered_proof = range_proof.clone();
+    tampered_proof.start.value = H256::repeat_byte(1);
+    let err = tampered_proof
+        .verify(&narrow_range, root_hash)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("start boundary"), "{err}");
+
+    let Err(err) = &results[2] else {
+        panic!("Unexpected result: {:?}", results[2]);
+    };
+    assert_eq!(err.missing_version, 10);
+    assert_eq!(err.version_count, 6);
+
+    // The wide range doesn't include existing keys, so its proof cannot be verified.
+    let Ok(TreeProofs::Range(range_proof)) = &results[3] else {
+        panic!("Unexpected result: {:?}", results[3]);
+    };
+    assert!(range_proof.entries.is_empty());
+    range_proof.verify(&wide_range, root_hash).unwrap_err();
+
+    assert_matches!(&results[4], Ok(TreeProofs::Entries(entries)) if entries.is_empty());
+}
+
 fn assert_raw_nodes_response(response: &serde_json::Value) {
     let response = response.as_object().expect("not an object");
     let response = response["nodes"].as_object().expect("not an object");
@@ -205,6 +318,12 @@ async fn local_merkle_tree_client() {
     assert!(tree_info.leaf_count > 20);
     assert_eq!(tree_info.next_l1_batch_number, L1BatchNumber(6));
 
+    let hashed_keys: Vec<_> = gen_storage_logs(20..30, 1)[0]
+        .iter()
+        .map(|log| log.key.hashed_key_u256())
+        .collect();
+    test_batched_proofs(&tree_reader, tree_info.root_hash, &hashed_keys).await;
+
     let err = tree_reader
         .get_proofs(L1BatchNumber(10), vec![])
         .await
@@ -400,6 +400,16 @@ impl AsyncTreeReader {
             .map_err(Into::into)
     }
 
+    pub async fn entries(
+        self,
+        l1_batch_number: L1BatchNumber,
+        keys: Vec<Key>,
+    ) -> Result<Vec<TreeEntry>, NoVersionError> {
+        tokio::task::spawn_blocking(move || self.inner.entries(l1_batch_number, &keys))
+            .await
+            .unwrap()
+    }
+
     pub async fn entries_with_proofs(
         self,
         l1_batch_number: L1BatchNumber,
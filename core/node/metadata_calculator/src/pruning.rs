@@ -44,6 +44,7 @@ pub struct MerkleTreePruningTask {
     pool: ConnectionPool<Core>,
     health_updater: HealthUpdater,
     poll_interval: Duration,
+    retained_l1_batches: u32,
 }
 
 impl MerkleTreePruningTask {
@@ -57,9 +58,18 @@ impl MerkleTreePruningTask {
             pool,
             health_updater: ReactiveHealthCheck::new("tree_pruner").1,
             poll_interval,
+            retained_l1_batches: 0,
         }
     }
 
+    /// Sets the number of L1 batches pruned in Postgres that will still be retained in the tree. Storage values
+    /// (with proofs) for these batches can be served from the tree after they are pruned in Postgres.
+    /// By default, the tree is pruned in lockstep with Postgres.
+    pub fn with_retained_l1_batches(mut self, count: u32) -> Self {
+        self.retained_l1_batches = count;
+        self
+    }
+
     pub fn health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
     }
@@ -102,7 +112,8 @@ impl MerkleTreePruningTask {
             drop(storage);
 
             if let Some(pruned) = pruning_info.last_hard_pruned {
-                let target_retained_l1_batch_number = pruned.l1_batch + 1;
+                let target_retained_l1_batch_number =
+                    L1BatchNumber((pruned.l1_batch.0 + 1).saturating_sub(self.retained_l1_batches));
                 let target_retained_version = u64::from(target_retained_l1_batch_number.0);
                 let Ok(prev_target_version) =
                     pruner_handle.set_target_retained_version(target_retained_version)
@@ -115,7 +126,10 @@ impl MerkleTreePruningTask {
                         .context("Merkle tree pruning thread panicked")?;
                 };
 
-                if prev_target_version != target_retained_version {
+                // The pruner only ever advances its target (e.g., if the number of retained L1 batches was increased
+                // after a restart, the computed target may be lower than the one already set), so we only log
+                // and update health when the target has actually changed.
+                if prev_target_version < target_retained_version {
                     let health = MerkleTreePruningTaskHealth::Pruning {
                         target_retained_l1_batch_number: Some(target_retained_l1_batch_number),
                     };
@@ -148,7 +162,7 @@ mod tests {
     use test_casing::test_casing;
     use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
     use zksync_node_test_utils::prepare_recovery_snapshot;
-    use zksync_types::{L1BatchNumber, L2BlockNumber, H256};
+    use zksync_types::{L1BatchNumber, L2BlockNumber, H256, U256};
 
     use super::*;
     use crate::{
@@ -214,6 +228,72 @@ mod tests {
             .await;
     }
 
+    #[tokio::test]
+    async fn tree_pruning_with_retained_l1_batches() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+        let config = mock_config(temp_dir.path());
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        reset_db_state(&pool, 5).await;
+
+        let mut calculator = MetadataCalculator::new(config, None, pool.clone())
+            .await
+            .unwrap();
+        let reader = calculator.tree_reader();
+        let pruning_task = calculator
+            .pruning_task(POLL_INTERVAL)
+            .with_retained_l1_batches(2);
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let calculator_handle = tokio::spawn(calculator.run(stop_receiver.clone()));
+        let pruning_task_handle = tokio::spawn(pruning_task.run(stop_receiver));
+
+        let reader = reader.wait().await.unwrap();
+        while reader.clone().info().await.next_l1_batch_number < L1BatchNumber(6) {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        storage
+            .pruning_dal()
+            .hard_prune_batches_range(L1BatchNumber(3), L2BlockNumber(3))
+            .await
+            .unwrap();
+        storage
+            .pruning_dal()
+            .insert_hard_pruning_log(L1BatchNumber(3), L2BlockNumber(3), H256::zero())
+            .await
+            .unwrap();
+
+        // L1 batches #2 and #3 are pruned in Postgres, but must be retained in the tree.
+        let min_l1_batch_number = loop {
+            let min_l1_batch_number = reader.clone().info().await.min_l1_batch_number.unwrap();
+            if min_l1_batch_number > L1BatchNumber(0) {
+                break min_l1_batch_number;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        };
+        assert_eq!(min_l1_batch_number, L1BatchNumber(2));
+
+        let keys = vec![U256::from(1)];
+        reader
+            .clone()
+            .entries_with_proofs(L1BatchNumber(2), keys.clone())
+            .await
+            .unwrap();
+        let err = reader
+            .clone()
+            .entries_with_proofs(L1BatchNumber(1), keys)
+            .await
+            .unwrap_err();
+        assert_eq!(err.missing_version, 1);
+
+        stop_sender.send_replace(true);
+        calculator_handle.await.unwrap().unwrap();
+        pruning_task_handle.await.unwrap().unwrap();
+    }
+
     #[derive(Debug)]
     enum PrematureExitScenario {
         CalculatorDrop,
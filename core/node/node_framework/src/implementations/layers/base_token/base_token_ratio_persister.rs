@@ -8,12 +8,13 @@ use crate::{
     implementations::resources::{
         contracts::{L1ChainContractsResource, L1EcosystemContractsResource},
         eth_interface::EthInterfaceResource,
+        healthcheck::AppHealthCheckResource,
         l1_tx_params::TxParamsResource,
         pools::{MasterPool, PoolResource},
         price_api_client::PriceAPIClientResource,
     },
     service::StopReceiver,
-    task::{Task, TaskId},
+    task::{RestartPolicy, RestartableTask, Task, TaskId},
     wiring_layer::{WiringError, WiringLayer},
     FromContext, IntoContext,
 };
@@ -39,13 +40,15 @@ pub struct Input {
     pub tx_params: TxParamsResource,
     pub l1_contracts_resource: L1ChainContractsResource,
     pub l1_ecosystem_contracts_resource: L1EcosystemContractsResource,
+    #[context(default)]
+    pub app_health: AppHealthCheckResource,
 }
 
 #[derive(Debug, IntoContext)]
 #[context(crate = crate)]
 pub struct Output {
     #[context(task)]
-    pub persister: BaseTokenRatioPersister,
+    pub persister: RestartableTask,
 }
 
 impl BaseTokenRatioPersisterLayer {
@@ -117,6 +120,17 @@ impl WiringLayer for BaseTokenRatioPersisterLayer {
             price_api_client.0,
             l1_behaviour,
         );
+        // The persister fetches and persists the latest ratio on each iteration, so it's safe to restart.
+        let persister = RestartableTask::new(
+            "base_token_ratio_persister",
+            RestartPolicy::on_failure(),
+            move || persister.clone(),
+        );
+        input
+            .app_health
+            .0
+            .insert_component(persister.health_check())
+            .map_err(WiringError::internal)?;
 
         Ok(Output { persister })
     }
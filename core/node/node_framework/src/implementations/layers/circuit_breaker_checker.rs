@@ -2,7 +2,9 @@ use zksync_circuit_breaker::CircuitBreakerChecker;
 use zksync_config::configs::chain::CircuitBreakerConfig;
 
 use crate::{
-    implementations::resources::circuit_breakers::CircuitBreakersResource,
+    implementations::resources::{
+        circuit_breakers::CircuitBreakersResource, healthcheck::AppHealthCheckResource,
+    },
     service::StopReceiver,
     task::{Task, TaskId, TaskKind},
     wiring_layer::{WiringError, WiringLayer},
@@ -13,7 +15,17 @@ use crate::{
 ///
 /// Expects other layers to insert different components' circuit breakers into
 /// [`zksync_circuit_breaker::CircuitBreakers`] collection using [`CircuitBreakersResource`].
-/// The added task periodically runs checks for all inserted circuit breakers.
+/// The added task periodically runs checks for all inserted circuit breakers and takes actions configured
+/// for the tripped ones.
+///
+/// ## Requests resources
+///
+/// - `CircuitBreakersResource`
+/// - `AppHealthCheckResource`
+///
+/// ## Adds tasks
+///
+/// - `CircuitBreakerChecker`
 #[derive(Debug)]
 pub struct CircuitBreakerCheckerLayer(pub CircuitBreakerConfig);
 
@@ -22,6 +34,8 @@ pub struct CircuitBreakerCheckerLayer(pub CircuitBreakerConfig);
 pub struct Input {
     #[context(default)]
     pub circuit_breakers: CircuitBreakersResource,
+    #[context(default)]
+    pub app_health: AppHealthCheckResource,
 }
 
 #[derive(Debug, IntoContext)]
@@ -41,8 +55,16 @@ impl WiringLayer for CircuitBreakerCheckerLayer {
     }
 
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        zksync_circuit_breaker::validate_actions(&self.0.actions)
+            .map_err(|err| WiringError::Configuration(format!("{err:#}")))?;
         let circuit_breaker_checker =
-            CircuitBreakerChecker::new(input.circuit_breakers.breakers, self.0.sync_interval());
+            CircuitBreakerChecker::new(input.circuit_breakers.breakers, self.0.sync_interval())
+                .with_actions(self.0.actions);
+        input
+            .app_health
+            .0
+            .insert_component(circuit_breaker_checker.health_check())
+            .map_err(WiringError::internal)?;
 
         Ok(Output {
             circuit_breaker_checker,
@@ -0,0 +1,106 @@
+use zksync_circuit_breaker::{
+    base_token::BaseTokenRatioStalenessChecker, da_dispatch::DaDispatchBacklogChecker,
+    l1_balance::L1BalanceChecker, proof_generation::ProofGenerationLagChecker, CircuitBreaker,
+};
+use zksync_config::configs::chain::CircuitBreakerConfig;
+use zksync_types::Address;
+
+use crate::{
+    implementations::resources::{
+        circuit_breakers::CircuitBreakersResource,
+        eth_interface::EthInterfaceResource,
+        pools::{PoolResource, ReplicaPool},
+    },
+    wiring_layer::{WiringError, WiringLayer},
+    FromContext,
+};
+
+/// Wiring layer for circuit breakers that are not tied to a specific component. Only circuit breakers
+/// with a configured threshold are inserted.
+///
+/// ## Requests resources
+///
+/// - `PoolResource<ReplicaPool>`
+/// - `EthInterfaceResource` (optional; required for the L1 balance circuit breaker)
+/// - `CircuitBreakersResource`
+#[derive(Debug)]
+pub struct CircuitBreakersLayer {
+    config: CircuitBreakerConfig,
+    operator_addresses: Vec<Address>,
+}
+
+impl CircuitBreakersLayer {
+    /// Creates a layer. `operator_addresses` are checked by the L1 balance circuit breaker.
+    pub fn new(config: CircuitBreakerConfig, operator_addresses: Vec<Address>) -> Self {
+        Self {
+            config,
+            operator_addresses,
+        }
+    }
+}
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+pub struct Input {
+    pub replica_pool: PoolResource<ReplicaPool>,
+    pub eth_client: Option<EthInterfaceResource>,
+    #[context(default)]
+    pub circuit_breakers: CircuitBreakersResource,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for CircuitBreakersLayer {
+    type Input = Input;
+    type Output = ();
+
+    fn layer_name(&self) -> &'static str {
+        "circuit_breakers_layer"
+    }
+
+    async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        let config = &self.config;
+        let mut breakers: Vec<Box<dyn CircuitBreaker>> = vec![];
+        if let Some(min_balance_gwei) = config.min_operator_l1_balance_gwei {
+            let EthInterfaceResource(eth_client) = input.eth_client.ok_or_else(|| {
+                WiringError::Configuration(
+                    "L1 balance circuit breaker requires an L1 client".to_owned(),
+                )
+            })?;
+            breakers.push(Box::new(L1BalanceChecker::new(
+                Box::new(eth_client),
+                self.operator_addresses,
+                min_balance_gwei,
+            )));
+        }
+
+        let needs_pool = config.da_dispatch_backlog_limit.is_some()
+            || config.proof_generation_lag_limit.is_some()
+            || config.base_token_ratio_staleness_limit_sec.is_some();
+        if needs_pool {
+            let pool = input.replica_pool.get().await?;
+            if let Some(backlog_limit) = config.da_dispatch_backlog_limit {
+                breakers.push(Box::new(DaDispatchBacklogChecker {
+                    pool: pool.clone(),
+                    backlog_limit,
+                }));
+            }
+            if let Some(lag_limit) = config.proof_generation_lag_limit {
+                breakers.push(Box::new(ProofGenerationLagChecker {
+                    pool: pool.clone(),
+                    lag_limit,
+                }));
+            }
+            if let Some(staleness_limit) = config.base_token_ratio_staleness_limit() {
+                breakers.push(Box::new(BaseTokenRatioStalenessChecker {
+                    pool,
+                    staleness_limit,
+                }));
+            }
+        }
+
+        for breaker in breakers {
+            input.circuit_breakers.breakers.insert(breaker).await;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::Context as _;
+use zksync_config::configs::GeneralConfig;
+
+use crate::{
+    implementations::resources::config_reload::{
+        ConfigReloader, ConfigReloaderResource, ConfigSource, ReloadableConfigResource,
+    },
+    service::StopReceiver,
+    task::{Task, TaskId, TaskKind},
+    wiring_layer::{WiringError, WiringLayer},
+    IntoContext,
+};
+
+/// Wiring layer for runtime config reloading. Reloading is triggered by the SIGHUP signal (on Unix),
+/// or via [`ConfigReloaderResource`].
+///
+/// ## Adds resources
+///
+/// - `ReloadableConfigResource`
+/// - `ConfigReloaderResource`
+///
+/// ## Adds tasks
+///
+/// - `ConfigReloadSignalTask`
+#[derive(Debug)]
+pub struct ConfigReloadLayer {
+    reloader: ConfigReloader,
+}
+
+impl ConfigReloadLayer {
+    pub fn new(source: impl ConfigSource, initial: GeneralConfig) -> Self {
+        Self {
+            reloader: ConfigReloader::new(source, initial),
+        }
+    }
+}
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+pub struct Output {
+    pub reloadable_config: ReloadableConfigResource,
+    pub reloader: ConfigReloaderResource,
+    #[context(task)]
+    pub task: ConfigReloadSignalTask,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ConfigReloadLayer {
+    type Input = ();
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "config_reload_layer"
+    }
+
+    async fn wire(self, _input: Self::Input) -> Result<Self::Output, WiringError> {
+        Ok(Output {
+            reloadable_config: ReloadableConfigResource(self.reloader.subscribe()),
+            reloader: ConfigReloaderResource(self.reloader.clone()),
+            task: ConfigReloadSignalTask {
+                reloader: self.reloader,
+            },
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ConfigReloadSignalTask {
+    reloader: ConfigReloader,
+}
+
+impl ConfigReloadSignalTask {
+    async fn reload(&self) -> anyhow::Result<()> {
+        let reloader = self.reloader.clone();
+        let changed = tokio::task::spawn_blocking(move || reloader.reload())
+            .await
+            .context("panicked while reloading config")??;
+        if !changed {
+            tracing::info!("Reloadable part of the config hasn't changed");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for ConfigReloadSignalTask {
+    fn kind(&self) -> TaskKind {
+        // Config may be reloaded while the node is waiting for preconditions.
+        TaskKind::UnconstrainedTask
+    }
+
+    fn id(&self) -> TaskId {
+        "config_reload_signal_handler".into()
+    }
+
+    #[cfg(unix)]
+    async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sighup =
+            signal(SignalKind::hangup()).context("failed installing SIGHUP handler")?;
+        loop {
+            tokio::select! {
+                _ = sighup.recv() => {
+                    tracing::info!("Received SIGHUP signal, reloading config");
+                    // Reload errors are not fatal; the node continues running with the current config.
+                    if let Err(err) = self.reload().await {
+                        tracing::error!("Failed reloading config: {err:#}");
+                    }
+                }
+                _ = stop_receiver.0.changed() => break,
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        tracing::info!("Config reloading on signals is only supported on Unix");
+        stop_receiver.0.changed().await?;
+        Ok(())
+    }
+}
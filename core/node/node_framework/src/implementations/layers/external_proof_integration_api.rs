@@ -1,11 +1,14 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::Context as _;
 use zksync_config::configs::external_proof_integration_api::ExternalProofIntegrationApiConfig;
-use zksync_external_proof_integration_api::{Api, Processor};
+use zksync_external_proof_integration_api::{Api, Processor, ProofVerifier};
 use zksync_types::commitment::L1BatchCommitmentMode;
 
 use crate::{
     implementations::resources::{
         object_store::ObjectStoreResource,
-        pools::{PoolResource, ReplicaPool},
+        pools::{MasterPool, PoolResource},
     },
     service::StopReceiver,
     task::{Task, TaskId},
@@ -23,7 +26,7 @@ pub struct ExternalProofIntegrationApiLayer {
 #[derive(Debug, FromContext)]
 #[context(crate = crate)]
 pub struct Input {
-    pub replica_pool: PoolResource<ReplicaPool>,
+    pub master_pool: PoolResource<MasterPool>,
     pub object_store: ObjectStoreResource,
 }
 
@@ -56,14 +59,36 @@ impl WiringLayer for ExternalProofIntegrationApiLayer {
     }
 
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
-        let replica_pool = input.replica_pool.get().await.unwrap();
+        let main_pool = input.master_pool.get().await?;
         let blob_store = input.object_store.0;
 
-        let processor = Processor::new(blob_store, replica_pool, self.commitment_mode);
-        let task = Api::new(
-            processor,
-            self.external_proof_integration_api_config.http_port,
+        let config = self.external_proof_integration_api_config;
+        let verifier = config
+            .verification_keys_path
+            .as_deref()
+            .map(|path| ProofVerifier::load(Path::new(path)))
+            .transpose()
+            .context("failed loading verification keys")?;
+        if verifier.is_none() {
+            tracing::info!("Verification keys are not configured; proof submission is disabled");
+        }
+        let processor = Processor::new(
+            blob_store,
+            main_pool,
+            self.commitment_mode,
+            config.lease_timeout(),
+            config.max_leases_per_prover,
+            verifier,
         );
+        let mut api_keys = HashMap::with_capacity(config.api_keys.len());
+        for key in config.api_keys {
+            if api_keys.insert(key.api_key, key.prover_id).is_some() {
+                return Err(WiringError::Configuration(
+                    "the same API key is configured for multiple external provers".to_owned(),
+                ));
+            }
+        }
+        let task = Api::new(processor, config.http_port, api_keys);
 
         Ok(Output { task })
     }
@@ -1,11 +1,16 @@
 use std::sync::Arc;
 
 use anyhow::Context;
-use zksync_config::{configs::eth_sender::SenderConfig, GasAdjusterConfig, GenesisConfig};
+use tokio::sync::watch;
+use zksync_config::{
+    configs::{eth_sender::SenderConfig, ReloadableConfig},
+    GasAdjusterConfig, GenesisConfig,
+};
 use zksync_node_fee_model::l1_gas_price::GasAdjuster;
 
 use crate::{
     implementations::resources::{
+        config_reload::ReloadableConfigResource,
         eth_interface::{SettlementLayerClient, SettlementLayerClientResource},
         gas_adjuster::GasAdjusterResource,
     },
@@ -28,6 +33,8 @@ pub struct GasAdjusterLayer {
 pub struct Input {
     pub client: SettlementLayerClientResource,
     pub sender_config: SenderConfig,
+    /// Used to update pricing multipliers.
+    pub reloadable_config: Option<ReloadableConfigResource>,
 }
 
 #[derive(Debug, IntoContext)]
@@ -75,7 +82,10 @@ impl WiringLayer for GasAdjusterLayer {
 
         Ok(Output {
             gas_adjuster: gas_adjuster.clone().into(),
-            gas_adjuster_task: GasAdjusterTask { gas_adjuster },
+            gas_adjuster_task: GasAdjusterTask {
+                gas_adjuster,
+                config_updates: input.reloadable_config.map(|resource| resource.0),
+            },
         })
     }
 }
@@ -83,6 +93,24 @@ impl WiringLayer for GasAdjusterLayer {
 #[derive(Debug)]
 pub struct GasAdjusterTask {
     gas_adjuster: Arc<GasAdjuster>,
+    config_updates: Option<watch::Receiver<ReloadableConfig>>,
+}
+
+impl GasAdjusterTask {
+    /// Applies pricing multipliers from config updates. Returns once the config reloader is stopped.
+    async fn apply_config_updates(
+        gas_adjuster: &GasAdjuster,
+        mut config_updates: watch::Receiver<ReloadableConfig>,
+    ) {
+        while config_updates.changed().await.is_ok() {
+            let multipliers = config_updates.borrow_and_update().gas_pricing_multipliers;
+            if let Some(multipliers) = multipliers {
+                if multipliers != gas_adjuster.pricing_multipliers() {
+                    gas_adjuster.set_pricing_multipliers(multipliers);
+                }
+            }
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -102,6 +130,18 @@ impl Task for GasAdjusterTask {
             return Ok(());
         }
 
-        self.gas_adjuster.run(stop_receiver.0).await
+        let Some(config_updates) = self.config_updates else {
+            return self.gas_adjuster.run(stop_receiver.0).await;
+        };
+        let gas_adjuster = self.gas_adjuster.clone();
+        let gas_adjuster_task = self.gas_adjuster.run(stop_receiver.0);
+        tokio::pin!(gas_adjuster_task);
+        tokio::select! {
+            res = &mut gas_adjuster_task => return res,
+            () = Self::apply_config_updates(&gas_adjuster, config_updates) => {}
+        }
+        // Stopping the config reloader shouldn't influence the gas adjuster.
+        tracing::info!("Config updates are stopped; gas adjuster continues with the current pricing multipliers");
+        gas_adjuster_task.await
     }
 }
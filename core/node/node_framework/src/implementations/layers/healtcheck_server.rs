@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use zksync_config::configs::api::HealthCheckConfig;
 use zksync_health_check::AppHealthCheck;
-use zksync_node_api_server::healthcheck::HealthCheckHandle;
+use zksync_node_api_server::healthcheck::{HealthCheckHandle, HealthWebhook};
 use zksync_shared_metrics::metadata::{GitMetadata, RustMetadata, GIT_METRICS, RUST_METRICS};
 use zksync_web3_decl::jsonrpsee::core::Serialize;
 
@@ -26,6 +26,16 @@ pub struct BinMetadata {
 /// Expects other layers to insert different components' health checks
 /// into [`AppHealthCheck`] aggregating heath using [`AppHealthCheckResource`].
 /// The added task spawns a health check server that only exposes the state provided by other tasks.
+/// If a webhook URL is configured, an additional task sends notifications about health transitions to it.
+///
+/// ## Requests resources
+///
+/// - `AppHealthCheckResource`
+///
+/// ## Adds tasks
+///
+/// - `HealthCheckTask`
+/// - `HealthWebhookTask` (if a webhook URL is configured)
 #[derive(Debug)]
 pub struct HealthCheckLayer(pub HealthCheckConfig);
 
@@ -41,6 +51,8 @@ pub struct Input {
 pub struct Output {
     #[context(task)]
     pub health_check_task: HealthCheckTask,
+    #[context(task)]
+    pub webhook_task: Option<HealthWebhookTask>,
 }
 
 #[async_trait::async_trait]
@@ -55,13 +67,26 @@ impl WiringLayer for HealthCheckLayer {
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
         let AppHealthCheckResource(app_health_check) = input.app_health_check;
         app_health_check.override_limits(self.0.slow_time_limit(), self.0.hard_time_limit());
-
+        if let Some(capacity) = self.0.history_capacity {
+            app_health_check.set_history_capacity(capacity);
+        }
+
+        let webhook_task = match &self.0.webhook_url {
+            Some(url) => Some(HealthWebhookTask {
+                webhook: HealthWebhook::new(url)?,
+                app_health_check: app_health_check.clone(),
+            }),
+            None => None,
+        };
         let health_check_task = HealthCheckTask {
             config: self.0,
             app_health_check,
         };
 
-        Ok(Output { health_check_task })
+        Ok(Output {
+            health_check_task,
+            webhook_task,
+        })
     }
 }
 
@@ -94,3 +119,26 @@ impl Task for HealthCheckTask {
         Ok(())
     }
 }
+
+#[derive(Debug)]
+pub struct HealthWebhookTask {
+    webhook: HealthWebhook,
+    app_health_check: Arc<AppHealthCheck>,
+}
+
+#[async_trait::async_trait]
+impl Task for HealthWebhookTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::UnconstrainedTask
+    }
+
+    fn id(&self) -> TaskId {
+        "health_webhook".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.webhook
+            .run(self.app_health_check, stop_receiver.0)
+            .await
+    }
+}
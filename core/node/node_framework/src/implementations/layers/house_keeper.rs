@@ -4,9 +4,12 @@ use zksync_house_keeper::{
 };
 
 use crate::{
-    implementations::resources::pools::{PoolResource, ReplicaPool},
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        pools::{PoolResource, ReplicaPool},
+    },
     service::StopReceiver,
-    task::{Task, TaskId},
+    task::{RestartPolicy, RestartableTask, Task, TaskId},
     wiring_layer::{WiringError, WiringLayer},
     FromContext, IntoContext,
 };
@@ -22,13 +25,15 @@ pub struct HouseKeeperLayer {
 #[context(crate = crate)]
 pub struct Input {
     pub replica_pool: PoolResource<ReplicaPool>,
+    #[context(default)]
+    pub app_health: AppHealthCheckResource,
 }
 
 #[derive(Debug, IntoContext)]
 #[context(crate = crate)]
 pub struct Output {
     #[context(task)]
-    pub l1_batch_metrics_reporter: L1BatchMetricsReporter,
+    pub l1_batch_metrics_reporter: RestartableTask,
 }
 
 impl HouseKeeperLayer {
@@ -58,6 +63,17 @@ impl WiringLayer for HouseKeeperLayer {
                 .l1_batch_metrics_reporting_interval_ms,
             replica_pool,
         );
+        // The reporter only reads from the database, so it can be restarted after transient failures.
+        let l1_batch_metrics_reporter = RestartableTask::new(
+            "l1_batch_metrics_reporter",
+            RestartPolicy::on_failure(),
+            move || l1_batch_metrics_reporter.clone(),
+        );
+        input
+            .app_health
+            .0
+            .insert_component(l1_batch_metrics_reporter.health_check())
+            .map_err(WiringError::internal)?;
 
         Ok(Output {
             l1_batch_metrics_reporter,
@@ -0,0 +1,133 @@
+use tokio::sync::watch;
+use zksync_config::configs::ReloadableConfig;
+use zksync_vlog::LogsReloadHandle;
+
+use crate::{
+    implementations::resources::{
+        config_reload::ReloadableConfigResource, logs::LogsReloadHandleResource,
+    },
+    service::StopReceiver,
+    task::{Task, TaskId, TaskKind},
+    wiring_layer::{WiringError, WiringLayer},
+    FromContext, IntoContext,
+};
+
+/// Wiring layer exposing the handle to change log directives at runtime. If config reloading is enabled,
+/// also adds a task applying directives from the reloaded config.
+///
+/// ## Requests resources
+///
+/// - `ReloadableConfigResource` (optional)
+///
+/// ## Adds resources
+///
+/// - `LogsReloadHandleResource`
+///
+/// ## Adds tasks
+///
+/// - `LogDirectivesReloadTask` (if `ReloadableConfigResource` is present)
+#[derive(Debug)]
+pub struct LogsReloadLayer {
+    handle: LogsReloadHandle,
+}
+
+impl LogsReloadLayer {
+    pub fn new(handle: LogsReloadHandle) -> Self {
+        Self { handle }
+    }
+}
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+pub struct Input {
+    pub reloadable_config: Option<ReloadableConfigResource>,
+}
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+pub struct Output {
+    pub handle: LogsReloadHandleResource,
+    #[context(task)]
+    pub reload_task: Option<LogDirectivesReloadTask>,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for LogsReloadLayer {
+    type Input = Input;
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "logs_reload_layer"
+    }
+
+    async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        let reload_task = input
+            .reloadable_config
+            .map(|config| LogDirectivesReloadTask {
+                handle: self.handle.clone(),
+                config_updates: config.0,
+            });
+        Ok(Output {
+            handle: LogsReloadHandleResource(self.handle),
+            reload_task,
+        })
+    }
+}
+
+/// Applies log directives from the reloaded config. Directives are only applied if they were changed
+/// in the config; thus, directives set via other means (e.g., the admin API) are not overwritten
+/// by unrelated config changes.
+#[derive(Debug)]
+pub struct LogDirectivesReloadTask {
+    handle: LogsReloadHandle,
+    config_updates: watch::Receiver<ReloadableConfig>,
+}
+
+#[async_trait::async_trait]
+impl Task for LogDirectivesReloadTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::UnconstrainedTask
+    }
+
+    fn id(&self) -> TaskId {
+        "logs_reload".into()
+    }
+
+    async fn run(mut self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let mut directives = self
+            .config_updates
+            .borrow_and_update()
+            .log_directives
+            .clone();
+        loop {
+            tokio::select! {
+                res = self.config_updates.changed() => {
+                    if res.is_err() {
+                        tracing::info!("Config reloader was dropped; stopping logs reloading");
+                        break;
+                    }
+                }
+                _ = stop_receiver.0.changed() => break,
+            }
+
+            let new_directives = self
+                .config_updates
+                .borrow_and_update()
+                .log_directives
+                .clone();
+            if new_directives == directives {
+                continue;
+            }
+            directives = new_directives;
+            // Invalid directives are not fatal; the current directives are retained in this case.
+            let res = match &directives {
+                Some(directives) => self.handle.set_directives(directives),
+                None => self.handle.reset(),
+            };
+            if let Err(err) = res {
+                tracing::error!("Failed applying reloaded log directives: {err:#}");
+            }
+        }
+        Ok(())
+    }
+}
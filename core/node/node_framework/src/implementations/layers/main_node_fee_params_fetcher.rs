@@ -5,10 +5,11 @@ use zksync_node_fee_model::l1_gas_price::MainNodeFeeParamsFetcher;
 use crate::{
     implementations::resources::{
         fee_input::{ApiFeeInputResource, SequencerFeeInputResource},
+        healthcheck::AppHealthCheckResource,
         main_node_client::MainNodeClientResource,
     },
     service::StopReceiver,
-    task::{Task, TaskId},
+    task::{RestartPolicy, RestartableTask, Task, TaskId},
     wiring_layer::{WiringError, WiringLayer},
     FromContext, IntoContext,
 };
@@ -22,6 +23,8 @@ pub struct MainNodeFeeParamsFetcherLayer;
 #[context(crate = crate)]
 pub struct Input {
     pub main_node_client: MainNodeClientResource,
+    #[context(default)]
+    pub app_health: AppHealthCheckResource,
 }
 
 #[derive(Debug, IntoContext)]
@@ -30,7 +33,7 @@ pub struct Output {
     pub sequencer_fee_input: SequencerFeeInputResource,
     pub api_fee_input: ApiFeeInputResource,
     #[context(task)]
-    pub fetcher: MainNodeFeeParamsFetcherTask,
+    pub fetcher: RestartableTask,
 }
 
 #[async_trait::async_trait]
@@ -45,15 +48,30 @@ impl WiringLayer for MainNodeFeeParamsFetcherLayer {
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
         let MainNodeClientResource(main_node_client) = input.main_node_client;
         let fetcher = Arc::new(MainNodeFeeParamsFetcher::new(main_node_client));
+        let task = MainNodeFeeParamsFetcherTask {
+            fetcher: fetcher.clone(),
+        };
+        // Fetched params are stored in the shared fetcher, so restarting the task doesn't lose them.
+        let task = RestartableTask::new(
+            "main_node_fee_params_fetcher",
+            RestartPolicy::on_failure(),
+            move || task.clone(),
+        );
+        input
+            .app_health
+            .0
+            .insert_component(task.health_check())
+            .map_err(WiringError::internal)?;
+
         Ok(Output {
             sequencer_fee_input: fetcher.clone().into(),
-            api_fee_input: fetcher.clone().into(),
-            fetcher: MainNodeFeeParamsFetcherTask { fetcher },
+            api_fee_input: fetcher.into(),
+            fetcher: task,
         })
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MainNodeFeeParamsFetcherTask {
     fetcher: Arc<MainNodeFeeParamsFetcher>,
 }
@@ -31,6 +31,7 @@ pub struct MetadataCalculatorLayer {
     config: MetadataCalculatorConfig,
     tree_api_config: Option<MerkleTreeApiConfig>,
     pruning_config: Option<Duration>,
+    tree_retained_l1_batches: u32,
     stale_keys_repair_enabled: bool,
 }
 
@@ -69,6 +70,7 @@ impl MetadataCalculatorLayer {
             config,
             tree_api_config: None,
             pruning_config: None,
+            tree_retained_l1_batches: 0,
             stale_keys_repair_enabled: false,
         }
     }
@@ -83,6 +85,13 @@ impl MetadataCalculatorLayer {
         self
     }
 
+    /// Sets the number of L1 batches pruned in Postgres that should still be retained in the tree.
+    /// Only has effect if pruning is enabled via [`Self::with_pruning_config()`].
+    pub fn with_tree_retained_l1_batches(mut self, count: u32) -> Self {
+        self.tree_retained_l1_batches = count;
+        self
+    }
+
     pub fn with_stale_keys_repair(mut self) -> Self {
         self.stale_keys_repair_enabled = true;
         self
@@ -142,7 +151,9 @@ impl WiringLayer for MetadataCalculatorLayer {
             .pruning_config
             .map(
                 |pruning_removal_delay| -> Result<MerkleTreePruningTask, WiringError> {
-                    let pruning_task = metadata_calculator.pruning_task(pruning_removal_delay);
+                    let pruning_task = metadata_calculator
+                        .pruning_task(pruning_removal_delay)
+                        .with_retained_l1_batches(self.tree_retained_l1_batches);
                     app_health
                         .insert_component(pruning_task.health_check())
                         .map_err(|err| WiringError::Internal(err.into()))?;
@@ -2,7 +2,9 @@ pub mod base_token;
 pub mod batch_status_updater;
 pub mod block_reverter;
 pub mod circuit_breaker_checker;
+pub mod circuit_breakers;
 pub mod commitment_generator;
+pub mod config_reload;
 pub mod consensus;
 pub mod consistency_checker;
 pub mod contract_verification_api;
@@ -19,6 +21,7 @@ pub mod house_keeper;
 pub mod l1_batch_commitment_mode_validation;
 pub mod l1_gas;
 pub mod logs_bloom_backfill;
+pub mod logs_reload;
 pub mod main_node_client;
 pub mod main_node_fee_params_fetcher;
 pub mod metadata_calculator;
@@ -41,3 +44,4 @@ pub mod tree_data_fetcher;
 pub mod validate_chain_ids;
 pub mod vm_runner;
 pub mod web3_api;
+pub mod zk_os_tree_manager;
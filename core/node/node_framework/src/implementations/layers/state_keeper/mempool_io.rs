@@ -8,10 +8,11 @@ use zksync_types::{commitment::PubdataType, L2ChainId};
 
 use crate::{
     implementations::resources::{
+        config_reload::ReloadableConfigResource,
         contracts::{L2ContractsResource, SettlementLayerContractsResource},
         fee_input::SequencerFeeInputResource,
         pools::{MasterPool, PoolResource},
-        state_keeper::{ConditionalSealerResource, StateKeeperIOResource},
+        state_keeper::{ConditionalSealerResource, MempoolResource, StateKeeperIOResource},
     },
     service::StopReceiver,
     task::{Task, TaskId},
@@ -25,11 +26,13 @@ use crate::{
 ///
 /// - `FeeInputResource`
 /// - `PoolResource<MasterPool>`
+/// - `ReloadableConfigResource` (optional; used to update mempool filters)
 ///
 /// ## Adds resources
 ///
 /// - `StateKeeperIOResource`
 /// - `ConditionalSealerResource`
+/// - `MempoolResource`
 ///
 /// ## Adds tasks
 ///
@@ -50,6 +53,7 @@ pub struct Input {
     pub master_pool: PoolResource<MasterPool>,
     pub contracts_resource: SettlementLayerContractsResource,
     pub l2_contracts_resource: L2ContractsResource,
+    pub reloadable_config: Option<ReloadableConfigResource>,
 }
 
 #[derive(Debug, IntoContext)]
@@ -57,6 +61,7 @@ pub struct Input {
 pub struct Output {
     pub state_keeper_io: StateKeeperIOResource,
     pub conditional_sealer: ConditionalSealerResource,
+    pub mempool: MempoolResource,
     #[context(task)]
     pub mempool_fetcher: MempoolFetcher,
 }
@@ -115,12 +120,15 @@ impl WiringLayer for MempoolIOLayer {
             .get_singleton()
             .await
             .context("Get master pool")?;
-        let mempool_fetcher = MempoolFetcher::new(
+        let mut mempool_fetcher = MempoolFetcher::new(
             mempool_guard.clone(),
             batch_fee_input_provider.clone(),
             &self.mempool_config,
             mempool_fetcher_pool,
         );
+        if let Some(ReloadableConfigResource(config_updates)) = input.reloadable_config {
+            mempool_fetcher = mempool_fetcher.with_config_updates(config_updates);
+        }
 
         // Create mempool IO resource.
         let mempool_db_pool = master_pool
@@ -128,7 +136,7 @@ impl WiringLayer for MempoolIOLayer {
             .await
             .context("Get master pool")?;
         let io = MempoolIO::new(
-            mempool_guard,
+            mempool_guard.clone(),
             batch_fee_input_provider,
             mempool_db_pool,
             &self.state_keeper_config,
@@ -145,6 +153,7 @@ impl WiringLayer for MempoolIOLayer {
         Ok(Output {
             state_keeper_io: io.into(),
             conditional_sealer: sealer.into(),
+            mempool: MempoolResource(mempool_guard),
             mempool_fetcher,
         })
     }
@@ -1,19 +1,21 @@
 use std::sync::Arc;
 
 use anyhow::Context;
+use zksync_circuit_breaker::{PausableComponent, STATE_KEEPER_COMPONENT};
 use zksync_health_check::ReactiveHealthCheck;
 use zksync_state::AsyncCatchupTask;
 pub use zksync_state::RocksdbStorageOptions;
-use zksync_state_keeper::{AsyncRocksdbCache, ZkSyncStateKeeper};
+use zksync_state_keeper::{AsyncRocksdbCache, StateKeeperControl, ZkSyncStateKeeper};
 use zksync_storage::RocksDB;
 
 use crate::{
     implementations::resources::{
+        circuit_breakers::CircuitBreakersResource,
         healthcheck::AppHealthCheckResource,
         pools::{MasterPool, PoolResource},
         state_keeper::{
             BatchExecutorResource, ConditionalSealerResource, OutputHandlerResource,
-            StateKeeperIOResource,
+            StateKeeperControlResource, StateKeeperIOResource,
         },
     },
     service::{ShutdownHook, StopReceiver},
@@ -44,11 +46,14 @@ pub struct Input {
     pub master_pool: PoolResource<MasterPool>,
     #[context(default)]
     pub app_health: AppHealthCheckResource,
+    #[context(default)]
+    pub circuit_breakers: CircuitBreakersResource,
 }
 
 #[derive(Debug, IntoContext)]
 #[context(crate = crate)]
 pub struct Output {
+    pub state_keeper_control: StateKeeperControlResource,
     #[context(task)]
     pub state_keeper: StateKeeperTask,
     #[context(task)]
@@ -107,6 +112,16 @@ impl WiringLayer for StateKeeperLayer {
             Arc::new(storage_factory),
         );
 
+        let control = state_keeper.control();
+        input
+            .circuit_breakers
+            .breakers
+            .insert_pausable_component(
+                STATE_KEEPER_COMPONENT,
+                Arc::new(PausableStateKeeper(control.clone())),
+            )
+            .await;
+        let state_keeper_control = StateKeeperControlResource(control);
         let state_keeper = StateKeeperTask { state_keeper };
 
         input
@@ -122,6 +137,7 @@ impl WiringLayer for StateKeeperLayer {
                 .context("failed terminating RocksDB instances")
         });
         Ok(Output {
+            state_keeper_control,
             state_keeper,
             rocksdb_catchup,
             rocksdb_termination_hook,
@@ -129,6 +145,20 @@ impl WiringLayer for StateKeeperLayer {
     }
 }
 
+/// Allows circuit breakers to pause the state keeper.
+#[derive(Debug)]
+struct PausableStateKeeper(StateKeeperControl);
+
+impl PausableComponent for PausableStateKeeper {
+    fn pause(&self) -> bool {
+        self.0.pause()
+    }
+
+    fn resume(&self) -> bool {
+        self.0.resume()
+    }
+}
+
 #[derive(Debug)]
 pub struct StateKeeperTask {
     state_keeper: ZkSyncStateKeeper,
@@ -14,7 +14,7 @@ use crate::{
     implementations::resources::{
         healthcheck::AppHealthCheckResource,
         object_store::ObjectStoreResource,
-        pools::{PoolResource, ReplicaPool},
+        pools::{MasterPool, PoolResource, ReplicaPool},
     },
     StopReceiver, Task, TaskId, WiringError, WiringLayer,
 };
@@ -39,6 +39,8 @@ impl VmPlaygroundLayer {
 pub struct Input {
     // We use a replica pool because VM playground doesn't write anything to the DB by design.
     pub replica_pool: PoolResource<ReplicaPool>,
+    /// Only used to record VM divergences if this is enabled in the config.
+    pub master_pool: Option<PoolResource<MasterPool>>,
     pub dumps_object_store: Option<ObjectStoreResource>,
     #[context(default)]
     pub app_health: AppHealthCheckResource,
@@ -67,6 +69,7 @@ impl WiringLayer for VmPlaygroundLayer {
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
         let Input {
             replica_pool,
+            master_pool,
             dumps_object_store,
             app_health,
         } = input;
@@ -86,6 +89,17 @@ impl WiringLayer for VmPlaygroundLayer {
             })
             .await?;
 
+        let divergences_pool = if self.config.record_divergences {
+            let master_pool = master_pool.ok_or_else(|| {
+                WiringError::Configuration(
+                    "Recording VM divergences requires a master DB pool".to_owned(),
+                )
+            })?;
+            Some(master_pool.get_singleton().await?)
+        } else {
+            None
+        };
+
         let cursor = VmPlaygroundCursorOptions {
             first_processed_batch: self.config.first_processed_batch,
             window_size: self.config.window_size,
@@ -99,6 +113,7 @@ impl WiringLayer for VmPlaygroundLayer {
         let (playground, tasks) = VmPlayground::new(
             connection_pool,
             dumps_object_store.map(|resource| resource.0),
+            divergences_pool,
             self.config.fast_vm_mode,
             storage,
             self.zksync_network_id,
@@ -0,0 +1,114 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use zksync_node_api_server::admin::{AdminNamespace, AdminServer};
+use zksync_types::secrets::APIKey;
+
+use crate::{
+    implementations::resources::{
+        config_reload::ConfigReloaderResource,
+        healthcheck::AppHealthCheckResource,
+        logs::LogsReloadHandleResource,
+        pools::{MasterPool, PoolResource},
+        state_keeper::{MempoolResource, StateKeeperControlResource},
+    },
+    service::StopReceiver,
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+    FromContext, IntoContext,
+};
+
+/// Wiring layer for the token-protected admin JSON-RPC server.
+///
+/// Methods controlling components that are not present on the node (e.g., the state keeper on the external node)
+/// return an error.
+///
+/// ## Requests resources
+///
+/// - `PoolResource<MasterPool>`
+/// - `StateKeeperControlResource` (optional)
+/// - `MempoolResource` (optional)
+/// - `LogsReloadHandleResource` (optional)
+/// - `ConfigReloaderResource` (optional)
+/// - `AppHealthCheckResource`
+///
+/// ## Adds tasks
+///
+/// - `AdminApiTask`
+#[derive(Debug)]
+pub struct AdminApiLayer {
+    bind_addr: SocketAddr,
+    token: APIKey,
+}
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+pub struct Input {
+    pub master_pool: PoolResource<MasterPool>,
+    pub state_keeper_control: Option<StateKeeperControlResource>,
+    pub mempool: Option<MempoolResource>,
+    pub logs_reload_handle: Option<LogsReloadHandleResource>,
+    pub config_reloader: Option<ConfigReloaderResource>,
+    #[context(default)]
+    pub app_health: AppHealthCheckResource,
+}
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+pub struct Output {
+    #[context(task)]
+    pub admin_api_task: AdminApiTask,
+}
+
+impl AdminApiLayer {
+    pub fn new(bind_addr: SocketAddr, token: APIKey) -> Self {
+        Self { bind_addr, token }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for AdminApiLayer {
+    type Input = Input;
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "admin_api_layer"
+    }
+
+    async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        let pool = input.master_pool.get_custom(1).await?;
+        let mut namespace = AdminNamespace::new(pool).with_app_health(input.app_health.0);
+        if let Some(StateKeeperControlResource(control)) = input.state_keeper_control {
+            namespace = namespace.with_state_keeper_control(control);
+        }
+        if let Some(MempoolResource(mempool)) = input.mempool {
+            namespace = namespace.with_mempool(mempool);
+        }
+        if let Some(LogsReloadHandleResource(handle)) = input.logs_reload_handle {
+            namespace = namespace.with_log_filter(Arc::new(handle));
+        }
+        if let Some(ConfigReloaderResource(reloader)) = input.config_reloader {
+            namespace = namespace.with_config_reloader(Arc::new(reloader));
+        }
+
+        let server = AdminServer::new(self.bind_addr, self.token, namespace);
+        Ok(Output {
+            admin_api_task: AdminApiTask { server },
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct AdminApiTask {
+    server: AdminServer,
+}
+
+#[async_trait::async_trait]
+impl Task for AdminApiTask {
+    fn id(&self) -> TaskId {
+        "admin_api".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.server.run(stop_receiver.0).await
+    }
+}
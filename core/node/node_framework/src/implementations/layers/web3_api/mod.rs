@@ -1,3 +1,4 @@
+pub mod admin;
 pub mod caches;
 pub mod server;
 pub mod tree_api_client;
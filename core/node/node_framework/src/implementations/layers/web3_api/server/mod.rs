@@ -18,6 +18,7 @@ use crate::{
         },
         resources::{
             circuit_breakers::CircuitBreakersResource,
+            config_reload::ReloadableConfigResource,
             contracts::{
                 L1ChainContractsResource, L1EcosystemContractsResource, L2ContractsResource,
                 SettlementLayerContractsResource,
@@ -108,6 +109,7 @@ enum Transport {
 /// - `SyncStateResource` (optional)
 /// - `TreeApiClientResource` (optional)
 /// - `MempoolCacheResource`
+/// - `ReloadableConfigResource` (optional; used to update the WebSocket rate limit)
 /// - `CircuitBreakersResource` (adds a circuit breaker)
 /// - `AppHealthCheckResource` (adds a health check)
 ///
@@ -131,6 +133,7 @@ pub struct Input {
     pub sync_state: Option<SyncStateResource>,
     pub tree_api_client: Option<TreeApiClientResource>,
     pub mempool_cache: MempoolCacheResource,
+    pub reloadable_config: Option<ReloadableConfigResource>,
     #[context(default)]
     pub circuit_breakers: CircuitBreakersResource,
     #[context(default)]
@@ -262,6 +265,9 @@ impl WiringLayer for Web3ServerLayer {
             }
             Transport::Ws => {
                 api_builder = api_builder.ws(self.port);
+                if let Some(ReloadableConfigResource(config_updates)) = input.reloadable_config {
+                    api_builder = api_builder.with_config_updates(config_updates);
+                }
             }
         }
         if let Some(sync_state) = sync_state {
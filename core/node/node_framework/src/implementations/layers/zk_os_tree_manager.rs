@@ -0,0 +1,178 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use zk_os_tree_manager::{
+    TreeConsistencyCheckTask, TreeManager, TreeManagerConfig, TreePruningTask,
+};
+use zksync_storage::RocksDB;
+
+use crate::{
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        pools::{MasterPool, PoolResource, ReplicaPool},
+    },
+    service::{ShutdownHook, StopReceiver},
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+    FromContext, IntoContext,
+};
+
+/// Wiring layer for the ZK OS Merkle tree manager.
+#[derive(Debug)]
+pub struct ZkOsTreeManagerLayer {
+    config: TreeManagerConfig,
+    pruning_config: Option<Duration>,
+    tree_retained_l1_batches: u32,
+    consistency_check_interval: Option<Duration>,
+}
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+pub struct Input {
+    pub master_pool: PoolResource<MasterPool>,
+    pub replica_pool: PoolResource<ReplicaPool>,
+    #[context(default)]
+    pub app_health: AppHealthCheckResource,
+}
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+pub struct Output {
+    #[context(task)]
+    pub tree_manager: TreeManager,
+    /// Only provided if configuration is provided.
+    #[context(task)]
+    pub pruning_task: Option<TreePruningTask>,
+    /// Only provided if the check interval is set.
+    #[context(task)]
+    pub consistency_check_task: Option<TreeConsistencyCheckTask>,
+    pub rocksdb_shutdown_hook: ShutdownHook,
+}
+
+impl ZkOsTreeManagerLayer {
+    pub fn new(config: TreeManagerConfig) -> Self {
+        Self {
+            config,
+            pruning_config: None,
+            tree_retained_l1_batches: 0,
+            consistency_check_interval: None,
+        }
+    }
+
+    pub fn with_pruning_config(mut self, pruning_config: Duration) -> Self {
+        self.pruning_config = Some(pruning_config);
+        self
+    }
+
+    /// Sets the number of L1 batches pruned in Postgres that should still be retained in the tree.
+    /// Only has effect if pruning is enabled via [`Self::with_pruning_config()`].
+    pub fn with_tree_retained_l1_batches(mut self, count: u32) -> Self {
+        self.tree_retained_l1_batches = count;
+        self
+    }
+
+    /// Enables periodic consistency checks of the latest tree version with the specified interval.
+    /// Each check traverses the entire tree, so the interval should be large for big trees.
+    pub fn with_consistency_check_interval(mut self, interval: Duration) -> Self {
+        self.consistency_check_interval = Some(interval);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ZkOsTreeManagerLayer {
+    type Input = Input;
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "zk_os_tree_manager_layer"
+    }
+
+    async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        let main_pool = input.master_pool.get().await?;
+        // Same as for the metadata calculator; the recovery pool size doesn't need to be particularly accurate.
+        let recovery_pool = input.replica_pool.get_custom(10).await?;
+        let app_health = input.app_health.0;
+
+        let mut tree_manager =
+            TreeManager::new(self.config, main_pool).with_recovery_pool(recovery_pool);
+        app_health
+            .insert_custom_component(Arc::new(tree_manager.tree_health_check()))
+            .map_err(WiringError::internal)?;
+
+        let pruning_task = self
+            .pruning_config
+            .map(
+                |pruning_removal_delay| -> Result<TreePruningTask, WiringError> {
+                    let pruning_task = tree_manager
+                        .pruning_task(pruning_removal_delay)
+                        .with_retained_l1_batches(self.tree_retained_l1_batches);
+                    app_health
+                        .insert_component(pruning_task.health_check())
+                        .map_err(|err| WiringError::Internal(err.into()))?;
+                    Ok(pruning_task)
+                },
+            )
+            .transpose()?;
+
+        let consistency_check_task = self
+            .consistency_check_interval
+            .map(
+                |interval| -> Result<TreeConsistencyCheckTask, WiringError> {
+                    let check_task = tree_manager.consistency_check_task(interval);
+                    app_health
+                        .insert_component(check_task.health_check())
+                        .map_err(|err| WiringError::Internal(err.into()))?;
+                    Ok(check_task)
+                },
+            )
+            .transpose()?;
+
+        let rocksdb_shutdown_hook = ShutdownHook::new("zk_os_tree_rocksdb_termination", async {
+            // Wait for all the instances of RocksDB to be destroyed.
+            tokio::task::spawn_blocking(RocksDB::await_rocksdb_termination)
+                .await
+                .context("failed terminating RocksDB instances")
+        });
+
+        Ok(Output {
+            tree_manager,
+            pruning_task,
+            consistency_check_task,
+            rocksdb_shutdown_hook,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for TreeManager {
+    fn id(&self) -> TaskId {
+        "zk_os_tree_manager".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        (*self).run(stop_receiver.0).await
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for TreePruningTask {
+    fn id(&self) -> TaskId {
+        "zk_os_tree_pruning_task".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        (*self).run(stop_receiver.0).await
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for TreeConsistencyCheckTask {
+    fn id(&self) -> TaskId {
+        "zk_os_tree_consistency_check_task".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        (*self).run(stop_receiver.0).await
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::{GeneralConfig, ReloadableConfig};
+use zksync_node_api_server::admin::ConfigReloadHandle;
+
+use crate::resource::Resource;
+
+/// Source of the node configuration that can be re-read at runtime, e.g. a YAML file.
+pub trait ConfigSource: 'static + fmt::Debug + Send + Sync {
+    fn load(&self) -> anyhow::Result<GeneralConfig>;
+}
+
+/// Re-reads the config from a [`ConfigSource`] and publishes changes in its reloadable part.
+///
+/// Changes in non-reloadable fields are rejected as a whole, i.e., no part of the new config is applied.
+#[derive(Debug, Clone)]
+pub struct ConfigReloader {
+    source: Arc<dyn ConfigSource>,
+    /// Non-reloadable part of the config loaded on node startup. The mutex also ensures that reloads are sequential.
+    static_config: Arc<Mutex<GeneralConfig>>,
+    sender: Arc<watch::Sender<ReloadableConfig>>,
+}
+
+impl ConfigReloader {
+    /// Creates a reloader with the `initial` config loaded on node startup.
+    pub fn new(source: impl ConfigSource, initial: GeneralConfig) -> Self {
+        let (static_config, reloadable) = initial.split_reloadable();
+        Self {
+            source: Arc::new(source),
+            static_config: Arc::new(Mutex::new(static_config)),
+            sender: Arc::new(watch::channel(reloadable).0),
+        }
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<ReloadableConfig> {
+        self.sender.subscribe()
+    }
+
+    /// Re-reads the config and publishes its reloadable part. Returns `Ok(false)` if the reloadable part
+    /// hasn't changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config cannot be loaded, or if any of its non-reloadable fields has changed.
+    pub fn reload(&self) -> anyhow::Result<bool> {
+        let static_config = self
+            .static_config
+            .lock()
+            .expect("config reloader is poisoned");
+        let new_config = self.source.load().context("failed loading config")?;
+        let (new_static_config, reloadable) = new_config.split_reloadable();
+        let changed_sections = new_static_config.changed_sections(&static_config);
+        anyhow::ensure!(
+            changed_sections.is_empty(),
+            "config sections {changed_sections:?} contain changes in non-reloadable fields; \
+             the node must be restarted to apply them"
+        );
+
+        let changed = self.sender.send_if_modified(|current| {
+            if *current == reloadable {
+                false
+            } else {
+                *current = reloadable;
+                true
+            }
+        });
+        if changed {
+            tracing::info!("Reloaded config: {:?}", *self.sender.borrow());
+        }
+        Ok(changed)
+    }
+}
+
+impl ConfigReloadHandle for ConfigReloader {
+    fn reload(&self) -> anyhow::Result<bool> {
+        ConfigReloader::reload(self)
+    }
+}
+
+/// A resource that provides the reloadable part of the node config. Components opting into config reloading
+/// should subscribe to updates via the contained receiver.
+#[derive(Debug, Clone)]
+pub struct ReloadableConfigResource(pub watch::Receiver<ReloadableConfig>);
+
+impl Resource for ReloadableConfigResource {
+    fn name() -> String {
+        "common/reloadable_config".into()
+    }
+}
+
+/// A resource that provides [`ConfigReloader`] to the service, so that config reloading can be triggered
+/// by components other than the signal handler.
+#[derive(Debug, Clone)]
+pub struct ConfigReloaderResource(pub ConfigReloader);
+
+impl Resource for ConfigReloaderResource {
+    fn name() -> String {
+        "common/config_reloader".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::{chain::MempoolConfig, MempoolFilters};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockConfigSource(Arc<Mutex<GeneralConfig>>);
+
+    impl ConfigSource for MockConfigSource {
+        fn load(&self) -> anyhow::Result<GeneralConfig> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+    }
+
+    fn mempool_config() -> MempoolConfig {
+        MempoolConfig {
+            sync_interval_ms: 10,
+            sync_batch_size: 100,
+            capacity: 100,
+            stuck_tx_timeout: 0,
+            remove_stuck_txs: false,
+            delay_interval: 10,
+            l1_to_l2_txs_paused: false,
+            skip_unsafe_deposit_checks: false,
+        }
+    }
+
+    #[test]
+    fn reloading_config() {
+        let config = GeneralConfig {
+            mempool_config: Some(mempool_config()),
+            ..GeneralConfig::default()
+        };
+        let source_config = Arc::new(Mutex::new(config.clone()));
+        let reloader = ConfigReloader::new(MockConfigSource(source_config.clone()), config);
+        let mut updates = reloader.subscribe();
+        assert!(!reloader.reload().unwrap());
+        assert!(!updates.has_changed().unwrap());
+
+        source_config
+            .lock()
+            .unwrap()
+            .mempool_config
+            .as_mut()
+            .unwrap()
+            .l1_to_l2_txs_paused = true;
+        assert!(reloader.reload().unwrap());
+        assert!(updates.has_changed().unwrap());
+        let expected_filters = MempoolFilters {
+            l1_to_l2_txs_paused: true,
+            skip_unsafe_deposit_checks: false,
+        };
+        assert_eq!(
+            updates.borrow_and_update().mempool_filters,
+            Some(expected_filters)
+        );
+
+        // Changes in non-reloadable fields must be rejected as a whole.
+        {
+            let mut source_config = source_config.lock().unwrap();
+            let mempool_config = source_config.mempool_config.as_mut().unwrap();
+            mempool_config.capacity = 1_000;
+            mempool_config.l1_to_l2_txs_paused = false;
+        }
+        let err = reloader.reload().unwrap_err().to_string();
+        assert!(err.contains("mempool_config"), "{err}");
+        assert!(!updates.has_changed().unwrap());
+        assert_eq!(
+            reloader.subscribe().borrow().mempool_filters,
+            Some(expected_filters)
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use zksync_vlog::LogsReloadHandle;
+
+use crate::resource::Resource;
+
+/// A resource that provides [`LogsReloadHandle`] to the service, allowing to change log directives at runtime.
+#[derive(Debug, Clone)]
+pub struct LogsReloadHandleResource(pub LogsReloadHandle);
+
+impl Resource for LogsReloadHandleResource {
+    fn name() -> String {
+        "common/logs_reload_handle".into()
+    }
+}
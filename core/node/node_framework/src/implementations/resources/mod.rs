@@ -1,6 +1,7 @@
 pub mod action_queue;
 pub mod base_token_ratio_provider;
 pub mod circuit_breakers;
+pub mod config_reload;
 pub mod contracts;
 pub mod da_client;
 pub mod eth_interface;
@@ -8,6 +9,7 @@ pub mod fee_input;
 pub mod gas_adjuster;
 pub mod healthcheck;
 pub mod l1_tx_params;
+pub mod logs;
 pub mod main_node_client;
 pub mod object_store;
 pub mod pools;
@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use zksync_state::OwnedStorage;
-use zksync_state_keeper::{seal_criteria::ConditionalSealer, OutputHandler, StateKeeperIO};
+use zksync_state_keeper::{
+    seal_criteria::ConditionalSealer, MempoolGuard, OutputHandler, StateKeeperControl,
+    StateKeeperIO,
+};
 use zksync_vm_executor::interface::BatchExecutorFactory;
 
 use crate::resource::{Resource, Unique};
@@ -78,3 +81,23 @@ where
         Self(Arc::new(sealer))
     }
 }
+
+/// A resource that provides [`StateKeeperControl`] for the state keeper running on the node.
+#[derive(Debug, Clone)]
+pub struct StateKeeperControlResource(pub StateKeeperControl);
+
+impl Resource for StateKeeperControlResource {
+    fn name() -> String {
+        "state_keeper/control".into()
+    }
+}
+
+/// A resource that provides read access to the [`MempoolGuard`] used by the main node state keeper.
+#[derive(Debug, Clone)]
+pub struct MempoolResource(pub MempoolGuard);
+
+impl Resource for MempoolResource {
+    fn name() -> String {
+        "state_keeper/mempool".into()
+    }
+}
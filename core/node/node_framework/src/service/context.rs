@@ -53,6 +53,9 @@ impl<'a> ServiceContext<'a> {
     /// are met.
     pub fn add_task<T: Task>(&mut self, task: T) -> &mut Self {
         tracing::info!("Layer {} has added a new task: {}", self.layer, task.id());
+        self.service
+            .wiring_graph
+            .record_task(self.layer, &task.id(), task.kind());
         self.service.runnables.tasks.push(Box::new(task));
         self
     }
@@ -106,7 +109,11 @@ impl<'a> ServiceContext<'a> {
                 T::name(),
                 type_name::<T>()
             );
-            return Ok(downcast_clone(resource));
+            let resource = downcast_clone(resource);
+            self.service
+                .wiring_graph
+                .record_request::<T>(self.layer, true);
+            return Ok(resource);
         }
 
         tracing::info!(
@@ -115,6 +122,9 @@ impl<'a> ServiceContext<'a> {
             T::name(),
             type_name::<T>()
         );
+        self.service
+            .wiring_graph
+            .record_request::<T>(self.layer, false);
 
         // No such resource.
         // The requester is allowed to decide whether this is an error or not.
@@ -139,6 +149,9 @@ impl<'a> ServiceContext<'a> {
         self.service
             .resources
             .insert(ResourceId::of::<T>(), Box::new(resource.clone()));
+        self.service
+            .wiring_graph
+            .record_provider::<T>(self.layer, true);
         tracing::info!(
             "Layer {} has created a new resource {}",
             self.layer,
@@ -165,12 +178,18 @@ impl<'a> ServiceContext<'a> {
                 T::name(),
                 type_name::<T>()
             );
+            self.service
+                .wiring_graph
+                .record_provider::<T>(self.layer, false);
             return Err(WiringError::ResourceAlreadyProvided {
                 id: ResourceId::of::<T>(),
                 name: T::name(),
             });
         }
         self.service.resources.insert(id, Box::new(resource));
+        self.service
+            .wiring_graph
+            .record_provider::<T>(self.layer, true);
         tracing::info!(
             "Layer {} has provided a new resource {}",
             self.layer,
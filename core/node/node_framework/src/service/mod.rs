@@ -11,6 +11,9 @@ pub use self::{
     error::{TaskError, ZkStackServiceError},
     shutdown_hook::ShutdownHook,
     stop_receiver::StopReceiver,
+    wiring_graph::{
+        WiringGraph, WiringGraphFormat, WiringLayerNode, WiringResourceNode, WiringTaskNode,
+    },
 };
 use crate::{
     resource::{ResourceId, StoredResource},
@@ -31,6 +34,7 @@ mod shutdown_hook;
 mod stop_receiver;
 #[cfg(test)]
 mod tests;
+mod wiring_graph;
 
 // A reasonable amount of time for any task to finish the shutdown process
 const TASK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
@@ -110,6 +114,7 @@ impl ZkStackServiceBuilder {
             stop_sender,
             runtime: self.runtime,
             errors: Vec::new(),
+            wiring_graph: WiringGraph::default(),
         }
     }
 }
@@ -132,6 +137,8 @@ pub struct ZkStackService {
 
     /// Collector for the task errors met during the service execution.
     errors: Vec<TaskError>,
+    /// Dependencies among layers, resources and tasks recorded during wiring.
+    wiring_graph: WiringGraph,
 }
 
 type TaskFuture = NamedFuture<Fuse<JoinHandle<anyhow::Result<()>>>>;
@@ -174,10 +181,25 @@ impl ZkStackService {
         }
     }
 
-    /// Performs wiring of the service.
-    /// After invoking this method, the collected tasks will be collected in `self.runnables`.
-    fn wire(&mut self) -> Result<(), ZkStackServiceError> {
-        // Initialize tasks.
+    /// Wires all layers without running any tasks, and returns the recorded dependency graph
+    /// among layers, resources and tasks. Wiring errors are recorded in the graph rather than returned.
+    ///
+    /// Note that wiring layers are still executed, so any I/O they perform during wiring
+    /// (e.g., connecting to the database) will happen.
+    pub fn dry_run(mut self) -> WiringGraph {
+        let errors = self.wire_layers();
+        for (layer, error) in &errors {
+            tracing::error!("Wiring layer {layer} can't be initialized: {error:?}");
+        }
+        tracing::info!(
+            "Dry-run wiring complete; {} task(s) would be launched",
+            self.wiring_graph.tasks.len()
+        );
+        std::mem::take(&mut self.wiring_graph)
+    }
+
+    /// Invokes all wiring layers, returning errors for the layers that have failed.
+    fn wire_layers(&mut self) -> Vec<(String, WiringError)> {
         let wiring_layers = std::mem::take(&mut self.layers);
 
         let mut errors: Vec<(String, WiringError)> = Vec::new();
@@ -185,16 +207,26 @@ impl ZkStackService {
         let runtime_handle = self.runtime.handle().clone();
         for (name, WireFn(wire_fn)) in wiring_layers {
             // We must process wiring layers sequentially and in the same order as they were added.
+            self.wiring_graph.record_layer(name);
             let mut context = ServiceContext::new(name, self);
             let task_result = wire_fn(&runtime_handle, &mut context);
             if let Err(err) = task_result {
                 // We don't want to bail on the first error, since it'll provide worse DevEx:
                 // People likely want to fix as much problems as they can in one go, rather than have
                 // to fix them one by one.
+                self.wiring_graph.record_layer_error(name, err.to_string());
                 errors.push((name.to_string(), err));
                 continue;
             };
         }
+        errors
+    }
+
+    /// Performs wiring of the service.
+    /// After invoking this method, the collected tasks will be collected in `self.runnables`.
+    fn wire(&mut self) -> Result<(), ZkStackServiceError> {
+        // Initialize tasks.
+        let errors = self.wire_layers();
 
         // Report all the errors we've met during the init.
         if !errors.is_empty() {
@@ -5,9 +5,13 @@ use assert_matches::assert_matches;
 use tokio::{runtime::Runtime, sync::Barrier};
 
 use crate::{
-    service::{StopReceiver, WiringError, WiringLayer, ZkStackServiceBuilder, ZkStackServiceError},
+    resource::Resource,
+    service::{
+        StopReceiver, WiringError, WiringGraphFormat, WiringLayer, ZkStackServiceBuilder,
+        ZkStackServiceError,
+    },
     task::{Task, TaskId},
-    IntoContext,
+    FromContext, IntoContext,
 };
 
 // `ZkStack` Service's `new()` method has to have a check for nested runtime.
@@ -252,3 +256,136 @@ fn test_task_run() {
     let res2 = *remaining_task_was_run.lock().unwrap();
     assert!(res2, "Incorrect resource value");
 }
+
+#[derive(Debug, Clone)]
+struct NumberResource(u64);
+
+impl Resource for NumberResource {
+    fn name() -> String {
+        "test/number".into()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct UnusedResource;
+
+impl Resource for UnusedResource {
+    fn name() -> String {
+        "test/unused".into()
+    }
+}
+
+#[derive(Debug)]
+struct ProviderLayer(&'static str);
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+struct ProviderLayerOutput {
+    number: NumberResource,
+    unused: Option<UnusedResource>,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ProviderLayer {
+    type Input = ();
+    type Output = ProviderLayerOutput;
+
+    fn layer_name(&self) -> &'static str {
+        self.0
+    }
+
+    async fn wire(self, _input: Self::Input) -> Result<Self::Output, WiringError> {
+        Ok(ProviderLayerOutput {
+            number: NumberResource(42),
+            unused: Some(UnusedResource),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct ConsumerLayer;
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+struct ConsumerLayerInput {
+    number: NumberResource,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ConsumerLayer {
+    type Input = ConsumerLayerInput;
+    type Output = TaskErrorLayerOutput;
+
+    fn layer_name(&self) -> &'static str {
+        "consumer_layer"
+    }
+
+    async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        assert_eq!(input.number.0, 42);
+        Ok(TaskErrorLayerOutput { task: ErrorTask })
+    }
+}
+
+#[test]
+fn dry_run_records_wiring_graph() {
+    let mut zk_stack_service = ZkStackServiceBuilder::new().unwrap();
+    zk_stack_service
+        .add_layer(ConsumerLayer)
+        .add_layer(ProviderLayer("provider_layer"))
+        .add_layer(ConsumerLayer);
+    // `ConsumerLayer` is only added once, so it fails due to the missing resource.
+    let graph = zk_stack_service.build().dry_run();
+
+    assert!(graph.has_errors());
+    assert!(graph.tasks.is_empty());
+    let number = graph
+        .resources
+        .iter()
+        .find(|node| node.name == "test/number")
+        .unwrap();
+    assert_eq!(number.missing_for, ["consumer_layer"]);
+    assert!(number.consumers.is_empty());
+
+    let mut zk_stack_service = ZkStackServiceBuilder::new().unwrap();
+    zk_stack_service
+        .add_layer(ProviderLayer("provider_layer"))
+        .add_layer(ConsumerLayer)
+        .add_layer(ProviderLayer("conflicting_layer"));
+    let graph = zk_stack_service.build().dry_run();
+
+    assert_eq!(graph.layers.len(), 3);
+    assert!(graph.has_errors());
+    assert!(graph.layers[2].error.is_some());
+    assert_eq!(graph.tasks.len(), 1);
+    assert_eq!(graph.tasks[0].id, "error_task");
+    assert_eq!(graph.tasks[0].layer, "consumer_layer");
+
+    let number = &graph.resources[0];
+    assert_eq!(number.name, "test/number");
+    assert_eq!(number.providers, ["provider_layer"]);
+    assert_eq!(number.consumers, ["consumer_layer"]);
+    assert_eq!(number.conflicting_providers, ["conflicting_layer"]);
+    let unused: Vec<_> = graph.unused_resources().map(|node| &node.name).collect();
+    assert_eq!(unused, ["test/unused"]);
+    let conflicting: Vec<_> = graph
+        .conflicting_resources()
+        .map(|node| &node.name)
+        .collect();
+    assert_eq!(conflicting, ["test/number"]);
+
+    let dot = graph.render(WiringGraphFormat::Dot);
+    assert!(dot.starts_with("digraph wiring {"), "{dot}");
+    assert!(dot.contains("layer0 -> resource0;"), "{dot}");
+    assert!(dot.contains("resource0 -> layer1;"), "{dot}");
+    assert!(dot.contains("layer2 -> resource0 [color=red];"), "{dot}");
+    assert!(dot.contains("layer1 -> task0;"), "{dot}");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&graph.render(WiringGraphFormat::Json)).unwrap();
+    assert_eq!(json["unused_resources"], serde_json::json!(["test/unused"]));
+    assert_eq!(
+        json["conflicting_resources"],
+        serde_json::json!(["test/number"])
+    );
+    assert_eq!(json["layers"][2]["name"], "conflicting_layer");
+}
@@ -0,0 +1,269 @@
+//! Introspection of dependencies among wiring layers, resources and tasks.
+
+use std::{any::type_name, collections::HashMap, fmt::Write as _, str::FromStr};
+
+use serde::Serialize;
+
+use crate::{
+    resource::{Resource, ResourceId},
+    task::{TaskId, TaskKind},
+};
+
+/// Output format for [`WiringGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WiringGraphFormat {
+    /// Graphviz DOT format.
+    Dot,
+    /// JSON.
+    Json,
+}
+
+impl FromStr for WiringGraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Self::Dot),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "unknown wiring graph format `{s}`; expected `dot` or `json`"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WiringLayerNode {
+    pub name: String,
+    /// Wiring error returned by the layer, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WiringResourceNode {
+    pub name: String,
+    pub type_name: &'static str,
+    /// Layers that have provided the resource.
+    pub providers: Vec<String>,
+    /// Layers that have attempted to provide the resource after it was already provided.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicting_providers: Vec<String>,
+    /// Layers that have received the resource.
+    pub consumers: Vec<String>,
+    /// Layers that have requested the resource while it wasn't available. This is not necessarily an error;
+    /// some layers treat resources as optional.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_for: Vec<String>,
+}
+
+impl WiringResourceNode {
+    /// Checks whether the resource is provided, but not consumed by any layer other than its providers.
+    pub fn is_unused(&self) -> bool {
+        !self.providers.is_empty()
+            && self
+                .consumers
+                .iter()
+                .all(|consumer| self.providers.contains(consumer))
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicting_providers.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WiringTaskNode {
+    pub id: String,
+    pub kind: String,
+    /// Layer that has added the task.
+    pub layer: String,
+}
+
+/// Dependency graph among wiring layers, resources and tasks recorded during wiring.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WiringGraph {
+    pub layers: Vec<WiringLayerNode>,
+    pub resources: Vec<WiringResourceNode>,
+    pub tasks: Vec<WiringTaskNode>,
+    #[serde(skip)]
+    resource_indices: HashMap<ResourceId, usize>,
+}
+
+impl WiringGraph {
+    pub(super) fn record_layer(&mut self, layer: &str) {
+        self.layers.push(WiringLayerNode {
+            name: layer.to_owned(),
+            error: None,
+        });
+    }
+
+    pub(super) fn record_layer_error(&mut self, layer: &str, error: String) {
+        if let Some(node) = self.layers.iter_mut().rev().find(|node| node.name == layer) {
+            node.error = Some(error);
+        }
+    }
+
+    fn resource_mut<T: Resource>(&mut self) -> &mut WiringResourceNode {
+        let next_idx = self.resources.len();
+        let idx = *self
+            .resource_indices
+            .entry(ResourceId::of::<T>())
+            .or_insert(next_idx);
+        if idx == next_idx {
+            self.resources.push(WiringResourceNode {
+                name: T::name(),
+                type_name: type_name::<T>(),
+                providers: vec![],
+                conflicting_providers: vec![],
+                consumers: vec![],
+                missing_for: vec![],
+            });
+        }
+        &mut self.resources[idx]
+    }
+
+    pub(super) fn record_request<T: Resource>(&mut self, layer: &str, is_available: bool) {
+        let node = self.resource_mut::<T>();
+        let layers = if is_available {
+            &mut node.consumers
+        } else {
+            &mut node.missing_for
+        };
+        if !layers.iter().any(|name| name == layer) {
+            layers.push(layer.to_owned());
+        }
+    }
+
+    pub(super) fn record_provider<T: Resource>(&mut self, layer: &str, is_accepted: bool) {
+        let node = self.resource_mut::<T>();
+        if is_accepted {
+            // Happens if the resource is lazily created by the layer that has requested it.
+            node.missing_for.retain(|name| name != layer);
+            node.providers.push(layer.to_owned());
+        } else {
+            node.conflicting_providers.push(layer.to_owned());
+        }
+    }
+
+    pub(super) fn record_task(&mut self, layer: &str, id: &TaskId, kind: TaskKind) {
+        self.tasks.push(WiringTaskNode {
+            id: id.to_string(),
+            kind: format!("{kind:?}"),
+            layer: layer.to_owned(),
+        });
+    }
+
+    pub fn unused_resources(&self) -> impl Iterator<Item = &WiringResourceNode> + '_ {
+        self.resources.iter().filter(|node| node.is_unused())
+    }
+
+    pub fn conflicting_resources(&self) -> impl Iterator<Item = &WiringResourceNode> + '_ {
+        self.resources.iter().filter(|node| node.has_conflicts())
+    }
+
+    /// Checks whether any layer has failed wiring.
+    pub fn has_errors(&self) -> bool {
+        self.layers.iter().any(|layer| layer.error.is_some())
+    }
+
+    pub fn render(&self, format: WiringGraphFormat) -> String {
+        match format {
+            WiringGraphFormat::Dot => self.to_dot(),
+            WiringGraphFormat::Json => {
+                serde_json::to_string_pretty(&self.to_json()).expect("failed serializing graph")
+            }
+        }
+    }
+
+    /// Returns the graph as JSON, including lists of unused and conflicting resources.
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::to_value(self).expect("failed serializing graph");
+        let names = |nodes: Vec<&WiringResourceNode>| -> Vec<_> {
+            nodes.into_iter().map(|node| node.name.clone()).collect()
+        };
+        json["unused_resources"] = names(self.unused_resources().collect()).into();
+        json["conflicting_resources"] = names(self.conflicting_resources().collect()).into();
+        json
+    }
+
+    /// Returns the graph in the Graphviz DOT format. Layers are represented as boxes, resources as ellipses,
+    /// and tasks as hexagons. Unused resources are grayed out; conflicting resources and failed layers are red.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph wiring {\n    rankdir=LR;\n");
+        let layer_ids: HashMap<_, _> = self
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(i, layer)| (layer.name.as_str(), format!("layer{i}")))
+            .collect();
+
+        for layer in &self.layers {
+            let id = &layer_ids[layer.name.as_str()];
+            let color = if layer.error.is_some() {
+                ", color=red"
+            } else {
+                ""
+            };
+            writeln!(
+                dot,
+                "    {id} [shape=box, label={}{color}];",
+                quote(&layer.name)
+            )
+            .unwrap();
+        }
+
+        for (i, resource) in self.resources.iter().enumerate() {
+            let id = format!("resource{i}");
+            let style = if resource.has_conflicts() {
+                ", color=red"
+            } else if resource.is_unused() {
+                ", style=dashed, color=gray"
+            } else {
+                ""
+            };
+            writeln!(
+                dot,
+                "    {id} [shape=ellipse, label={}{style}];",
+                quote(&resource.name)
+            )
+            .unwrap();
+
+            let layer_id = |name: &str| layer_ids.get(name).cloned();
+            for provider in &resource.providers {
+                if let Some(layer_id) = layer_id(provider) {
+                    writeln!(dot, "    {layer_id} -> {id};").unwrap();
+                }
+            }
+            for provider in &resource.conflicting_providers {
+                if let Some(layer_id) = layer_id(provider) {
+                    writeln!(dot, "    {layer_id} -> {id} [color=red];").unwrap();
+                }
+            }
+            for consumer in &resource.consumers {
+                if let Some(layer_id) = layer_id(consumer) {
+                    writeln!(dot, "    {id} -> {layer_id};").unwrap();
+                }
+            }
+            for consumer in &resource.missing_for {
+                if let Some(layer_id) = layer_id(consumer) {
+                    writeln!(dot, "    {id} -> {layer_id} [style=dashed];").unwrap();
+                }
+            }
+        }
+
+        for (i, task) in self.tasks.iter().enumerate() {
+            let id = format!("task{i}");
+            writeln!(dot, "    {id} [shape=hexagon, label={}];", quote(&task.id)).unwrap();
+            if let Some(layer_id) = layer_ids.get(task.layer.as_str()) {
+                writeln!(dot, "    {layer_id} -> {id};").unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,14 @@
+//! Metrics for tasks.
+
+use vise::{Counter, LabeledFamily, Metrics};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "node_framework_task")]
+pub(crate) struct TaskMetrics {
+    /// Number of restarts of restartable tasks.
+    #[metrics(labels = ["task"])]
+    pub restarts: LabeledFamily<String, Counter>,
+}
+
+#[vise::register]
+pub(crate) static TASK_METRICS: vise::Global<TaskMetrics> = vise::Global::new();
@@ -7,9 +7,14 @@ use std::{
 
 use tokio::sync::Barrier;
 
-pub use self::types::{TaskId, TaskKind};
+pub use self::{
+    restart::{RestartPolicy, RestartableTask},
+    types::{TaskId, TaskKind},
+};
 use crate::service::StopReceiver;
 
+mod metrics;
+mod restart;
 mod types;
 
 /// A task implementation.
@@ -30,6 +35,9 @@ mod types;
 /// irrecoverable error happens (then task should return an error), or stop signal is received (then task should
 /// return `Ok(())`).
 ///
+/// By default, a task returning an error stops the whole service. Tasks that are safe to restart can be wrapped
+/// into [`RestartableTask`] with a [`RestartPolicy`] by their wiring layer.
+///
 /// ### `OneshotTask`
 ///
 /// A task that can exit when completed without causing the service to terminate.
@@ -0,0 +1,360 @@
+//! Restart policies for tasks.
+
+use std::{collections::VecDeque, time::Duration};
+
+use serde::Serialize;
+use tokio::{task::JoinHandle, time::Instant};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_utils::panic_extractor::try_extract_panic_message;
+
+use super::{metrics::TASK_METRICS, Task, TaskId, TaskKind};
+use crate::service::StopReceiver;
+
+/// Policy determining whether a failed task is restarted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// The task is never restarted; its failure stops the service. This is how all tasks not wrapped
+    /// in [`RestartableTask`] behave.
+    Never,
+    /// The task is restarted after each failure (i.e., returning an error or panicking) with an exponential backoff
+    /// starting from `initial_backoff` and capped at `max_backoff`. If the task fails after being restarted
+    /// `max_restarts` times within `window`, the failure is propagated to the service.
+    OnFailure {
+        initial_backoff: Duration,
+        max_backoff: Duration,
+        max_restarts: usize,
+        window: Duration,
+    },
+}
+
+impl RestartPolicy {
+    /// Restart policy on failures with reasonable defaults: backoff from 1s to 1min, at most 5 restarts within 10 minutes.
+    pub const fn on_failure() -> Self {
+        Self::OnFailure {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            max_restarts: 5,
+            window: Duration::from_secs(600),
+        }
+    }
+
+    /// Sets backoff bounds. No-op for [`Self::Never`].
+    #[must_use]
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        if let Self::OnFailure {
+            initial_backoff,
+            max_backoff,
+            ..
+        } = &mut self
+        {
+            *initial_backoff = initial;
+            *max_backoff = max;
+        }
+        self
+    }
+
+    /// Sets the maximum number of restarts within the sliding `window`. No-op for [`Self::Never`].
+    #[must_use]
+    pub fn with_max_restarts(mut self, max: usize, within: Duration) -> Self {
+        if let Self::OnFailure {
+            max_restarts,
+            window,
+            ..
+        } = &mut self
+        {
+            *max_restarts = max;
+            *window = within;
+        }
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RestartableTaskHealthDetails<'a> {
+    restarts: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_error: Option<&'a str>,
+}
+
+/// Aborts the wrapped Tokio task on drop, so that it doesn't outlive the service.
+struct AbortOnDrop<T>(JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Task that is re-created using a factory closure and restarted according to a [`RestartPolicy`].
+///
+/// Only tasks that can be safely restarted should be wrapped, e.g. ones that periodically fetch or report data
+/// and don't hold state that can become inconsistent after a failure. The number of restarts is reported
+/// by the [health check](Self::health_check()) and in metrics.
+pub struct RestartableTask {
+    id: TaskId,
+    kind: TaskKind,
+    policy: RestartPolicy,
+    factory: Box<dyn FnMut() -> Box<dyn Task> + Send>,
+    /// Task instance created on construction to determine the task kind; used for the first run.
+    first_instance: Option<Box<dyn Task>>,
+    health_updater: HealthUpdater,
+}
+
+impl std::fmt::Debug for RestartableTask {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter
+            .debug_struct("RestartableTask")
+            .field("id", &self.id)
+            .field("kind", &self.kind)
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RestartableTask {
+    /// Creates a restartable task. `name` is used both as the task ID and as the name of the health check component.
+    pub fn new<T: Task>(
+        name: &'static str,
+        policy: RestartPolicy,
+        mut factory: impl FnMut() -> T + Send + 'static,
+    ) -> Self {
+        let first_instance: Box<dyn Task> = Box::new(factory());
+        let (_, health_updater) = ReactiveHealthCheck::new(name);
+        Self {
+            id: name.into(),
+            kind: first_instance.kind(),
+            policy,
+            factory: Box::new(move || Box::new(factory())),
+            first_instance: Some(first_instance),
+            health_updater,
+        }
+    }
+
+    /// Returns the health check for the task reporting the number of restarts and the last error.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    fn update_health(&self, status: HealthStatus, restarts: u64, last_error: Option<&str>) {
+        let details = RestartableTaskHealthDetails {
+            restarts,
+            last_error,
+        };
+        self.health_updater
+            .update(Health::from(status).with_details(details));
+    }
+
+    /// Returns the backoff before the next restart, or `None` if the task should not be restarted.
+    fn next_backoff(&self, recent_restarts: &mut VecDeque<Instant>) -> Option<Duration> {
+        let RestartPolicy::OnFailure {
+            initial_backoff,
+            max_backoff,
+            max_restarts,
+            window,
+        } = self.policy
+        else {
+            return None;
+        };
+
+        let now = Instant::now();
+        while recent_restarts
+            .front()
+            .is_some_and(|&restarted_at| now.duration_since(restarted_at) > window)
+        {
+            recent_restarts.pop_front();
+        }
+        if recent_restarts.len() >= max_restarts {
+            return None;
+        }
+
+        let exponent = u32::try_from(recent_restarts.len()).unwrap_or(u32::MAX);
+        let backoff = initial_backoff
+            .saturating_mul(2_u32.saturating_pow(exponent))
+            .min(max_backoff);
+        recent_restarts.push_back(now);
+        Some(backoff)
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for RestartableTask {
+    fn kind(&self) -> TaskKind {
+        self.kind
+    }
+
+    fn id(&self) -> TaskId {
+        self.id.clone()
+    }
+
+    async fn run(mut self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let mut recent_restarts = VecDeque::new();
+        let mut restarts = 0_u64;
+        loop {
+            let task = match self.first_instance.take() {
+                Some(task) => task,
+                None => (self.factory)(),
+            };
+            self.update_health(HealthStatus::Ready, restarts, None);
+
+            // Spawn the task so that panics are caught.
+            let mut handle = AbortOnDrop(tokio::spawn(task.run(stop_receiver.clone())));
+            let err = match (&mut handle.0).await {
+                Ok(Ok(())) => return Ok(()),
+                Ok(Err(err)) => err,
+                Err(join_err) => anyhow::anyhow!(
+                    "Task {} panicked: {}",
+                    self.id,
+                    try_extract_panic_message(join_err)
+                ),
+            };
+
+            if *stop_receiver.0.borrow() {
+                return Err(err);
+            }
+            let Some(backoff) = self.next_backoff(&mut recent_restarts) else {
+                return Err(if restarts == 0 {
+                    err
+                } else {
+                    err.context(format!("task failed after {restarts} restart(s)"))
+                });
+            };
+
+            restarts += 1;
+            TASK_METRICS.restarts[&self.id.to_string()].inc();
+            tracing::warn!(
+                "Task {} failed, restarting it in {backoff:?} (restart #{restarts}): {err:#}",
+                self.id
+            );
+            self.update_health(HealthStatus::Affected, restarts, Some(&format!("{err:#}")));
+
+            if tokio::time::timeout(backoff, stop_receiver.0.changed())
+                .await
+                .is_ok()
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use tokio::sync::watch;
+    use zksync_health_check::CheckHealth;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct FailingTask {
+        runs: Arc<AtomicUsize>,
+        succeed_on_run: usize,
+        panic: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl Task for FailingTask {
+        fn id(&self) -> TaskId {
+            "failing".into()
+        }
+
+        async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+            let run = self.runs.fetch_add(1, Ordering::SeqCst) + 1;
+            if run >= self.succeed_on_run {
+                Ok(())
+            } else if self.panic {
+                panic!("oops");
+            } else {
+                anyhow::bail!("run #{run} failed");
+            }
+        }
+    }
+
+    fn restartable_task(
+        policy: RestartPolicy,
+        succeed_on_run: usize,
+        panic: bool,
+    ) -> (RestartableTask, Arc<AtomicUsize>) {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let task = RestartableTask::new("failing", policy, {
+            let runs = runs.clone();
+            move || FailingTask {
+                runs: runs.clone(),
+                succeed_on_run,
+                panic,
+            }
+        });
+        (task, runs)
+    }
+
+    fn test_policy() -> RestartPolicy {
+        RestartPolicy::on_failure()
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(10))
+            .with_max_restarts(3, Duration::from_secs(60))
+    }
+
+    #[test]
+    fn backoff_is_exponential_and_capped() {
+        let policy = RestartPolicy::on_failure()
+            .with_backoff(Duration::from_secs(1), Duration::from_secs(5))
+            .with_max_restarts(4, Duration::from_secs(60));
+        let (task, _) = restartable_task(policy, 1, false);
+        let mut recent_restarts = VecDeque::new();
+        let backoffs: Vec<_> = (0..5)
+            .map(|_| task.next_backoff(&mut recent_restarts))
+            .collect();
+        let expected_backoffs = [1, 2, 4, 5].map(|secs| Some(Duration::from_secs(secs)));
+        assert_eq!(backoffs[..4], expected_backoffs);
+        assert_eq!(backoffs[4], None);
+    }
+
+    #[tokio::test]
+    async fn task_is_restarted_on_failure() {
+        for panic in [false, true] {
+            let (task, runs) = restartable_task(test_policy(), 3, panic);
+            let health_check = task.health_check();
+            let (_stop_sender, stop_receiver) = watch::channel(false);
+            Box::new(task)
+                .run(StopReceiver(stop_receiver))
+                .await
+                .unwrap();
+
+            assert_eq!(runs.load(Ordering::SeqCst), 3);
+            let health = health_check.check_health().await;
+            assert_eq!(health.status(), HealthStatus::Ready);
+            assert_eq!(health.details().unwrap()["restarts"], 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn task_failure_is_propagated_after_max_restarts() {
+        let (task, runs) = restartable_task(test_policy(), usize::MAX, false);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let err = Box::new(task)
+            .run(StopReceiver(stop_receiver))
+            .await
+            .unwrap_err();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+        let err = format!("{err:#}");
+        assert!(err.contains("after 3 restart(s)"), "{err}");
+        assert!(err.contains("run #4 failed"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn task_is_not_restarted_with_never_policy() {
+        let (task, runs) = restartable_task(RestartPolicy::Never, usize::MAX, false);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let err = Box::new(task)
+            .run(StopReceiver(stop_receiver))
+            .await
+            .unwrap_err();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(err.to_string(), "run #1 failed");
+    }
+}
@@ -46,6 +46,7 @@ impl InitializeStorage for ExternalNodeSnapshotRecovery {
 
         let config = SnapshotsApplierConfig {
             max_concurrency: self.max_concurrency,
+            import_history: self.recovery_config.import_history,
             ..SnapshotsApplierConfig::default()
         };
         let mut snapshots_applier_task = SnapshotsApplierTask::new(
@@ -130,6 +131,7 @@ mod tests {
             recovery_config: SnapshotRecoveryConfig {
                 snapshot_l1_batch_override: None,
                 drop_storage_key_preimages: false,
+                import_history: false,
                 object_store_config: None,
             },
             app_health,
@@ -16,6 +16,8 @@ pub struct SnapshotRecoveryConfig {
     /// If not specified, the latest snapshot will be used.
     pub snapshot_l1_batch_override: Option<L1BatchNumber>,
     pub drop_storage_key_preimages: bool,
+    /// Whether to import transaction history included into the snapshot.
+    pub import_history: bool,
     pub object_store_config: Option<ObjectStoreConfig>,
 }
 
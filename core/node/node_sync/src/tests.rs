@@ -5,11 +5,11 @@ use std::{iter, sync::Arc, time::Duration};
 use backon::{ConstantBuilder, Retryable};
 use test_casing::test_casing;
 use tokio::{sync::watch, task::JoinHandle};
-use zksync_contracts::BaseSystemContractsHashes;
+use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_node_test_utils::{
-    create_l1_batch_metadata, create_l2_transaction, prepare_recovery_snapshot,
+    create_l1_batch_metadata, create_l2_transaction, prepare_recovery_snapshot, recover, Snapshot,
 };
 use zksync_state_keeper::{
     io::{L1BatchParams, L2BlockParams},
@@ -275,6 +275,100 @@ async fn external_io_basics(snapshot_recovery: bool) {
     assert_eq!(tx_receipt.transaction_index, 0.into());
 }
 
+#[tokio::test]
+async fn external_io_after_snapshot_history_import() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    let snapshot = Snapshot::new(
+        L1BatchNumber(23),
+        L2BlockNumber(42),
+        vec![],
+        &BaseSystemContracts::load_from_disk(),
+        ProtocolVersionId::latest(),
+    );
+    let l1_batch_header = snapshot.l1_batch.clone();
+    let l2_block_header = snapshot.l2_block.clone();
+    let snapshot = recover(&mut storage, snapshot).await;
+
+    // Emulate transaction history import by the snapshots applier. The snapshot L1 batch and L2 block are
+    // the last ones imported, so it's sufficient to only insert them.
+    storage
+        .blocks_dal()
+        .insert_l1_batch(l1_batch_header.to_unsealed_header())
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .insert_l2_block(&l2_block_header)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_l1_batch_as_sealed(&l1_batch_header, &[], &[], &[], Default::default())
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_l2_blocks_as_executed_in_l1_batch(l1_batch_header.number)
+        .await
+        .unwrap();
+
+    let open_l1_batch = open_l1_batch(
+        snapshot.l1_batch_number.0 + 1,
+        snapshot.l2_block_timestamp + 1,
+        snapshot.l2_block_number.0 + 1,
+    );
+    let tx = create_l2_transaction(10, 100);
+    let tx_hash = tx.hash();
+    let tx = FetchedTransaction::new(tx.into());
+    let actions = vec![open_l1_batch, tx.into(), SyncAction::SealL2Block];
+
+    let (actions_sender, action_queue) = ActionQueue::new();
+    let state_keeper = StateKeeperHandles::new(
+        pool.clone(),
+        MockMainNodeClient::default(),
+        action_queue,
+        &[&extract_tx_hashes(&actions)],
+    )
+    .await;
+    actions_sender.push_actions(actions).await.unwrap();
+    state_keeper
+        .wait_for_local_block(snapshot.l2_block_number + 1)
+        .await;
+
+    let l2_block = storage
+        .blocks_dal()
+        .get_l2_block_header(snapshot.l2_block_number + 1)
+        .await
+        .unwrap()
+        .expect("New L2 block is not persisted");
+    assert_eq!(l2_block.timestamp, snapshot.l2_block_timestamp + 1);
+    assert_eq!(l2_block.l2_tx_count, 1);
+
+    let tx_receipts = storage
+        .transactions_web3_dal()
+        .get_transaction_receipts(&[tx_hash])
+        .await
+        .unwrap();
+    let tx_receipt = &tx_receipts
+        .first()
+        .expect("Transaction not persisted")
+        .inner;
+    assert_eq!(
+        tx_receipt.block_number,
+        (snapshot.l2_block_number.0 + 1).into()
+    );
+
+    // The imported L1 batch must be retained intact.
+    let imported_l1_batch = storage
+        .blocks_dal()
+        .get_l1_batch_header(snapshot.l1_batch_number)
+        .await
+        .unwrap()
+        .expect("Imported L1 batch is removed");
+    assert_eq!(imported_l1_batch.timestamp, l1_batch_header.timestamp);
+}
+
 #[test_casing(2, [false, true])]
 #[tokio::test]
 async fn external_io_works_without_local_protocol_version(snapshot_recovery: bool) {
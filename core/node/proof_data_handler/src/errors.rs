@@ -5,10 +5,15 @@ use axum::{
 use zksync_dal::DalError;
 use zksync_object_store::ObjectStoreError;
 
+use crate::attestation::AttestationError;
+
 pub(crate) enum RequestProcessorError {
     GeneralError(String),
     ObjectStore(ObjectStoreError),
     Dal(DalError),
+    Attestation(AttestationError),
+    /// A TEE proof is signed by a key without a verified attestation.
+    UnattestedKey,
 }
 
 impl From<DalError> for RequestProcessorError {
@@ -17,6 +22,12 @@ impl From<DalError> for RequestProcessorError {
     }
 }
 
+impl From<AttestationError> for RequestProcessorError {
+    fn from(err: AttestationError) -> Self {
+        RequestProcessorError::Attestation(err)
+    }
+}
+
 impl IntoResponse for RequestProcessorError {
     fn into_response(self) -> Response {
         let (status_code, message) = match self {
@@ -41,6 +52,31 @@ impl IntoResponse for RequestProcessorError {
                     "Failed fetching/saving from db".to_owned(),
                 )
             }
+            Self::Attestation(AttestationError::Collateral(err)) => {
+                tracing::error!("Failed loading attestation collateral: {err:#}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Attestation cannot be verified".to_owned(),
+                )
+            }
+            Self::Attestation(AttestationError::Internal(err)) => {
+                tracing::error!("Failed verifying attestation: {err:#}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Attestation cannot be verified".to_owned(),
+                )
+            }
+            Self::Attestation(err) => {
+                tracing::warn!("Rejected attestation: {err}");
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid attestation: {err}"),
+                )
+            }
+            Self::UnattestedKey => (
+                StatusCode::FORBIDDEN,
+                "Proof is signed by a key without a verified attestation".to_owned(),
+            ),
         };
         (status_code, message).into_response()
     }
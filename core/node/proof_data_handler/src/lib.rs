@@ -17,8 +17,10 @@ use zksync_types::{commitment::L1BatchCommitmentMode, L2ChainId};
 #[cfg(test)]
 mod tests;
 
+mod attestation;
 mod errors;
 mod metrics;
+mod quorum;
 mod request_processor;
 mod tee_request_processor;
 
@@ -38,7 +40,7 @@ pub async fn run_server(
         config,
         commitment_mode,
         l2_chain_id,
-    );
+    )?;
 
     let listener = tokio::net::TcpListener::bind(bind_address)
         .await
@@ -62,7 +64,7 @@ fn create_proof_processing_router(
     config: ProofDataHandlerConfig,
     commitment_mode: L1BatchCommitmentMode,
     l2_chain_id: L2ChainId,
-) -> Router {
+) -> anyhow::Result<Router> {
     let get_proof_gen_processor = RequestProcessor::new(
         blob_store.clone(),
         connection_pool.clone(),
@@ -96,7 +98,8 @@ fn create_proof_processing_router(
 
     if config.tee_config.tee_support {
         let get_tee_proof_gen_processor =
-            TeeRequestProcessor::new(blob_store, connection_pool, config.clone(), l2_chain_id);
+            TeeRequestProcessor::new(blob_store, connection_pool, config.clone(), l2_chain_id)
+                .context("invalid TEE attestation config")?;
         let submit_tee_proof_processor = get_tee_proof_gen_processor.clone();
         let register_tee_attestation_processor = get_tee_proof_gen_processor.clone();
 
@@ -138,7 +141,7 @@ fn create_proof_processing_router(
         );
     }
 
-    router
+    Ok(router
         .layer(tower_http::compression::CompressionLayer::new())
-        .layer(tower_http::decompression::RequestDecompressionLayer::new().zstd(true))
+        .layer(tower_http::decompression::RequestDecompressionLayer::new().zstd(true)))
 }
@@ -0,0 +1,133 @@
+//! TEE proof quorum evaluated for batches once TEE proofs are submitted.
+
+use std::collections::HashSet;
+
+use zksync_dal::tee_proof_generation_dal::TeeProofSigner;
+use zksync_types::tee_types::TeeType;
+
+/// Quorum requiring a proof of each of the specified TEE types, all signed by distinct attested keys.
+/// If verified attestations are required, a proof only counts if the key was attested with a quote
+/// of the same TEE type as the proof.
+#[derive(Debug, Clone)]
+pub(crate) struct TeeQuorumPolicy {
+    required_tee_types: Vec<TeeType>,
+    require_verified_attestations: bool,
+}
+
+impl TeeQuorumPolicy {
+    /// Returns `None` if no TEE types are required, i.e. the quorum is disabled.
+    pub fn new(
+        required_tee_types: &[TeeType],
+        require_verified_attestations: bool,
+    ) -> Option<Self> {
+        let mut seen = HashSet::new();
+        let required_tee_types: Vec<_> = required_tee_types
+            .iter()
+            .copied()
+            .filter(|tee_type| seen.insert(*tee_type))
+            .collect();
+        (!required_tee_types.is_empty()).then_some(Self {
+            required_tee_types,
+            require_verified_attestations,
+        })
+    }
+
+    pub fn is_reached(&self, signers: &[TeeProofSigner]) -> bool {
+        let signers: Vec<_> = signers
+            .iter()
+            .filter(|signer| {
+                !self.require_verified_attestations
+                    || signer.attested_tee_type == Some(signer.tee_type)
+            })
+            .collect();
+        Self::assign_distinct_keys(&self.required_tee_types, &signers, &mut HashSet::new())
+    }
+
+    /// Checks whether each of `tee_types` can be covered by a signer with a key not used for other types.
+    fn assign_distinct_keys<'a>(
+        tee_types: &[TeeType],
+        signers: &[&'a TeeProofSigner],
+        used_keys: &mut HashSet<&'a [u8]>,
+    ) -> bool {
+        let Some((&tee_type, rest)) = tee_types.split_first() else {
+            return true;
+        };
+        for &signer in signers.iter().filter(|signer| signer.tee_type == tee_type) {
+            if used_keys.insert(signer.pubkey.as_slice()) {
+                if Self::assign_distinct_keys(rest, signers, used_keys) {
+                    return true;
+                }
+                used_keys.remove(signer.pubkey.as_slice());
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signer(tee_type: TeeType, key: u8, attestation_verified: bool) -> TeeProofSigner {
+        TeeProofSigner {
+            tee_type,
+            pubkey: vec![key; 33],
+            attested_tee_type: attestation_verified.then_some(tee_type),
+        }
+    }
+
+    #[test]
+    fn empty_policy_is_disabled() {
+        assert!(TeeQuorumPolicy::new(&[], true).is_none());
+    }
+
+    #[test]
+    fn quorum_requires_each_tee_type() {
+        let policy = TeeQuorumPolicy::new(&[TeeType::Sgx, TeeType::Tdx], false).unwrap();
+        assert!(!policy.is_reached(&[]));
+        assert!(!policy.is_reached(&[signer(TeeType::Sgx, 1, false)]));
+        assert!(policy.is_reached(&[
+            signer(TeeType::Sgx, 1, false),
+            signer(TeeType::Tdx, 2, false),
+        ]));
+    }
+
+    #[test]
+    fn quorum_requires_distinct_keys() {
+        let policy = TeeQuorumPolicy::new(&[TeeType::Sgx, TeeType::Tdx], false).unwrap();
+        assert!(!policy.is_reached(&[
+            signer(TeeType::Sgx, 1, false),
+            signer(TeeType::Tdx, 1, false),
+        ]));
+        // Another assignment of keys to TEE types may satisfy the quorum.
+        assert!(policy.is_reached(&[
+            signer(TeeType::Sgx, 1, false),
+            signer(TeeType::Tdx, 1, false),
+            signer(TeeType::Tdx, 2, false),
+        ]));
+    }
+
+    #[test]
+    fn quorum_with_verified_attestations() {
+        let policy = TeeQuorumPolicy::new(&[TeeType::Sgx, TeeType::Tdx], true).unwrap();
+        assert!(!policy.is_reached(&[
+            signer(TeeType::Sgx, 1, true),
+            signer(TeeType::Tdx, 2, false),
+        ]));
+        let verified_signers = [signer(TeeType::Sgx, 1, true), signer(TeeType::Tdx, 2, true)];
+        assert!(policy.is_reached(&verified_signers));
+
+        // A key attested with an SGX quote cannot sign proofs counted as TDX ones.
+        let mismatched_signer = TeeProofSigner {
+            attested_tee_type: Some(TeeType::Sgx),
+            ..signer(TeeType::Tdx, 2, true)
+        };
+        assert!(!policy.is_reached(&[signer(TeeType::Sgx, 1, true), mismatched_signer]));
+    }
+
+    #[test]
+    fn duplicate_tee_types_are_ignored() {
+        let policy = TeeQuorumPolicy::new(&[TeeType::Sgx, TeeType::Sgx], false).unwrap();
+        assert!(policy.is_reached(&[signer(TeeType::Sgx, 1, false)]));
+    }
+}
@@ -4,7 +4,7 @@ use axum::{extract::Path, Json};
 use chrono::{Duration as ChronoDuration, Utc};
 use zksync_config::configs::ProofDataHandlerConfig;
 use zksync_dal::{
-    tee_proof_generation_dal::{LockedBatch, TeeProofGenerationJobStatus},
+    tee_proof_generation_dal::{LockedBatch, TeeProofGenerationDal, TeeProofGenerationJobStatus},
     ConnectionPool, Core, CoreDal,
 };
 use zksync_object_store::{ObjectStore, ObjectStoreError};
@@ -20,7 +20,10 @@ use zksync_prover_interface::{
 use zksync_types::{tee_types::TeeType, L1BatchNumber, L2ChainId};
 use zksync_vm_executor::storage::L1BatchParamsProvider;
 
-use crate::{errors::RequestProcessorError, metrics::METRICS};
+use crate::{
+    attestation::AttestationVerifier, errors::RequestProcessorError, metrics::METRICS,
+    quorum::TeeQuorumPolicy,
+};
 
 #[derive(Clone)]
 pub(crate) struct TeeRequestProcessor {
@@ -28,6 +31,10 @@ pub(crate) struct TeeRequestProcessor {
     pool: ConnectionPool<Core>,
     config: ProofDataHandlerConfig,
     l2_chain_id: L2ChainId,
+    /// Set if attestations must be verified.
+    attestation_verifier: Option<AttestationVerifier>,
+    /// Set if the TEE proof quorum is evaluated for batches.
+    quorum_policy: Option<TeeQuorumPolicy>,
 }
 
 impl TeeRequestProcessor {
@@ -36,13 +43,24 @@ impl TeeRequestProcessor {
         pool: ConnectionPool<Core>,
         config: ProofDataHandlerConfig,
         l2_chain_id: L2ChainId,
-    ) -> Self {
-        Self {
+    ) -> anyhow::Result<Self> {
+        let attestation_verifier = if config.tee_config.verify_attestations {
+            Some(AttestationVerifier::new(&config.tee_config)?)
+        } else {
+            None
+        };
+        let quorum_policy = TeeQuorumPolicy::new(
+            &config.tee_config.quorum_tee_types,
+            config.tee_config.verify_attestations,
+        );
+        Ok(Self {
             blob_store,
             pool,
             config,
             l2_chain_id,
-        }
+            attestation_verifier,
+            quorum_policy,
+        })
     }
 
     pub(crate) async fn get_proof_generation_data(
@@ -212,6 +230,27 @@ impl TeeRequestProcessor {
         let mut connection = self.pool.connection_tagged("tee_request_processor").await?;
         let mut dal = connection.tee_proof_generation_dal();
 
+        if self.attestation_verifier.is_some()
+            && !dal
+                .is_attestation_verified(&proof.0.pubkey, proof.0.tee_type)
+                .await?
+        {
+            tracing::warn!(
+                %l1_batch_number,
+                "Rejected {} proof signed by key 0x{} without a verified attestation of the same TEE type",
+                proof.0.tee_type,
+                hex::encode(&proof.0.pubkey)
+            );
+            return Err(RequestProcessorError::UnattestedKey);
+        }
+
+        // The proof is saved and the quorum is evaluated in a single transaction holding the lock on the batch proofs,
+        // so that concurrent submissions for the same batch cannot evaluate the quorum based on stale data.
+        let mut transaction = connection.start_transaction().await?;
+        let mut dal = transaction.tee_proof_generation_dal();
+        if self.quorum_policy.is_some() {
+            dal.lock_tee_proofs_for_quorum(l1_batch_number).await?;
+        }
         dal.save_proof_artifacts_metadata(
             l1_batch_number,
             proof.0.tee_type,
@@ -220,6 +259,11 @@ impl TeeRequestProcessor {
             &proof.0.proof,
         )
         .await?;
+        if let Some(policy) = &self.quorum_policy {
+            // The new proof may replace a proof counted for the quorum, so the quorum is re-evaluated.
+            Self::update_quorum(policy, &mut dal, l1_batch_number).await?;
+        }
+        transaction.commit().await?;
 
         let sealed_at = connection
             .blocks_dal()
@@ -251,12 +295,68 @@ impl TeeRequestProcessor {
     ) -> Result<Json<RegisterTeeAttestationResponse>, RequestProcessorError> {
         tracing::info!("Received attestation: {:?}", payload);
 
-        let mut connection = self.pool.connection_tagged("tee_request_processor").await?;
-        let mut dal = connection.tee_proof_generation_dal();
+        let Some(verifier) = &self.attestation_verifier else {
+            let mut connection = self.pool.connection_tagged("tee_request_processor").await?;
+            connection
+                .tee_proof_generation_dal()
+                .save_attestation(&payload.pubkey, &payload.attestation)
+                .await?;
+            return Ok(Json(RegisterTeeAttestationResponse::Success));
+        };
 
-        dal.save_attestation(&payload.pubkey, &payload.attestation)
+        let quote = verifier
+            .verify(&payload.attestation, &payload.pubkey)
+            .await?;
+        tracing::info!(
+            "Verified {} attestation for key 0x{} with measurement 0x{}, TCB status: {}",
+            quote.kind,
+            hex::encode(&payload.pubkey),
+            hex::encode(&quote.measurement),
+            quote.tcb_status
+        );
+        let mut connection = self.pool.connection_tagged("tee_request_processor").await?;
+        connection
+            .tee_proof_generation_dal()
+            .save_verified_attestation(&payload.pubkey, &payload.attestation, quote.kind.into())
             .await?;
 
+        if let Some(policy) = &self.quorum_policy {
+            // Proofs signed by the key before its attestation was verified are only counted now.
+            let batches = connection
+                .tee_proof_generation_dal()
+                .get_batches_without_quorum_signed_by(&payload.pubkey)
+                .await?;
+            for l1_batch_number in batches {
+                let mut transaction = connection.start_transaction().await?;
+                let mut dal = transaction.tee_proof_generation_dal();
+                dal.lock_tee_proofs_for_quorum(l1_batch_number).await?;
+                Self::update_quorum(policy, &mut dal, l1_batch_number).await?;
+                transaction.commit().await?;
+            }
+        }
+
         Ok(Json(RegisterTeeAttestationResponse::Success))
     }
+
+    /// Evaluates the TEE proof quorum for the batch and records the result. Must be called in a transaction
+    /// holding the lock on the batch proofs (see [`TeeProofGenerationDal::lock_tee_proofs_for_quorum()`]).
+    async fn update_quorum(
+        policy: &TeeQuorumPolicy,
+        dal: &mut TeeProofGenerationDal<'_, '_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<(), RequestProcessorError> {
+        let signers = dal.get_tee_proof_signers(l1_batch_number).await?;
+        if policy.is_reached(&signers) {
+            tracing::info!(%l1_batch_number, "Batch has reached TEE proof quorum");
+            dal.mark_tee_quorum_reached(l1_batch_number).await?;
+        } else if dal
+            .get_tee_quorum_reached_at(l1_batch_number)
+            .await?
+            .is_some()
+        {
+            tracing::warn!(%l1_batch_number, "Batch lost TEE proof quorum after a proof was replaced");
+            dal.clear_tee_quorum_reached(l1_batch_number).await?;
+        }
+        Ok(())
+    }
 }
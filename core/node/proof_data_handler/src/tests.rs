@@ -8,10 +8,12 @@ use serde_json::json;
 use tower::ServiceExt;
 use zksync_config::configs::{ProofDataHandlerConfig, TeeConfig};
 use zksync_dal::{ConnectionPool, CoreDal};
+use zksync_node_test_utils::create_l1_batch;
 use zksync_object_store::MockObjectStore;
 use zksync_prover_interface::api::SubmitTeeProofRequest;
 use zksync_types::{
-    commitment::L1BatchCommitmentMode, tee_types::TeeType, L1BatchNumber, L2ChainId,
+    commitment::L1BatchCommitmentMode, protocol_version::ProtocolVersion, tee_types::TeeType,
+    L1BatchNumber, L2ChainId,
 };
 
 use crate::create_proof_processing_router;
@@ -31,11 +33,13 @@ async fn request_tee_proof_inputs() {
                 first_tee_processed_batch: L1BatchNumber(0),
                 tee_proof_generation_timeout_in_secs: 600,
                 tee_batch_permanently_ignored_timeout_in_hours: 10 * 24,
+                ..TeeConfig::default()
             },
         },
         L1BatchCommitmentMode::Rollup,
         L2ChainId::default(),
-    );
+    )
+    .unwrap();
     let test_cases = vec![
         (json!({ "tee_type": "sgx" }), StatusCode::NO_CONTENT),
         (
@@ -91,11 +95,13 @@ async fn submit_tee_proof() {
                 first_tee_processed_batch: L1BatchNumber(0),
                 tee_proof_generation_timeout_in_secs: 600,
                 tee_batch_permanently_ignored_timeout_in_hours: 10 * 24,
+                ..TeeConfig::default()
             },
         },
         L1BatchCommitmentMode::Rollup,
         L2ChainId::default(),
-    );
+    )
+    .unwrap();
 
     // this should fail because we haven't saved the attestation for the pubkey yet
 
@@ -149,6 +155,226 @@ async fn submit_tee_proof() {
     assert_eq!(proof.pubkey.as_ref().unwrap(), &tee_proof_request.0.pubkey);
 }
 
+// Test that with attestation verification enabled, invalid quotes are rejected on registration and
+// proofs are accepted only from keys with a verified attestation
+#[tokio::test]
+async fn submit_tee_proof_with_attestation_verification() {
+    let batch_number = L1BatchNumber::from(1);
+    let db_conn_pool = ConnectionPool::test_pool().await;
+
+    mock_tee_batch_status(db_conn_pool.clone(), batch_number).await;
+
+    let tee_proof_request = serde_json::from_value::<SubmitTeeProofRequest>(json!({
+        "signature": "0001020304",
+        "pubkey": "0506070809",
+        "proof": "0A0B0C0D0E",
+        "tee_type": "sgx"
+    }))
+    .unwrap();
+    let uri = format!("/tee/submit_proofs/{}", batch_number.0);
+    let collateral_dir = tempfile::TempDir::new().unwrap();
+    let app = create_proof_processing_router(
+        MockObjectStore::arc(),
+        db_conn_pool.clone(),
+        ProofDataHandlerConfig {
+            http_port: 1337,
+            proof_generation_timeout_in_secs: 10,
+            tee_config: TeeConfig {
+                tee_support: true,
+                verify_attestations: true,
+                allowed_mrenclaves: vec!["11".repeat(32)],
+                attestation_collateral_path: Some(
+                    collateral_dir.path().to_str().unwrap().to_owned(),
+                ),
+                ..TeeConfig::default()
+            },
+        },
+        L1BatchCommitmentMode::Rollup,
+        L2ChainId::default(),
+    )
+    .unwrap();
+
+    // a quote with an unknown TEE type in its header is rejected before loading collateral
+    let mut quote = vec![0_u8; 64];
+    quote[0] = 4;
+    quote[4] = 0x42;
+    let register_request = json!({
+        "attestation": hex::encode(&quote),
+        "pubkey": "0506070809",
+    });
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/tee/register_attestation")
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_vec(&register_request).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // an attestation saved without verification doesn't allow submitting proofs
+    let mut conn = db_conn_pool.connection().await.unwrap();
+    conn.tee_proof_generation_dal()
+        .save_attestation(&tee_proof_request.0.pubkey, &quote)
+        .await
+        .unwrap();
+    let response = send_submit_tee_proof_request(&app, &uri, &tee_proof_request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // an attestation verified for another TEE type doesn't allow submitting proofs either
+    let other_tee_type = match tee_proof_request.0.tee_type {
+        TeeType::Sgx => TeeType::Tdx,
+        _ => TeeType::Sgx,
+    };
+    conn.tee_proof_generation_dal()
+        .save_verified_attestation(&tee_proof_request.0.pubkey, &quote, other_tee_type)
+        .await
+        .unwrap();
+    assert!(!conn
+        .tee_proof_generation_dal()
+        .is_attestation_verified(&tee_proof_request.0.pubkey, tee_proof_request.0.tee_type)
+        .await
+        .unwrap());
+    let response = send_submit_tee_proof_request(&app, &uri, &tee_proof_request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    conn.tee_proof_generation_dal()
+        .save_verified_attestation(
+            &tee_proof_request.0.pubkey,
+            &quote,
+            tee_proof_request.0.tee_type,
+        )
+        .await
+        .unwrap();
+    assert!(conn
+        .tee_proof_generation_dal()
+        .is_attestation_verified(&tee_proof_request.0.pubkey, tee_proof_request.0.tee_type)
+        .await
+        .unwrap());
+    let response = send_submit_tee_proof_request(&app, &uri, &tee_proof_request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+async fn create_quorum_test_router(
+    db_conn_pool: &ConnectionPool<zksync_dal::Core>,
+    batch_number: L1BatchNumber,
+) -> Router {
+    let mut conn = db_conn_pool.connection().await.unwrap();
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(&ProtocolVersion::default())
+        .await
+        .unwrap();
+    conn.blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(batch_number.0))
+        .await
+        .unwrap();
+    for tee_type in [TeeType::Sgx, TeeType::Tdx] {
+        conn.tee_proof_generation_dal()
+            .insert_tee_proof_generation_job(batch_number, tee_type)
+            .await
+            .unwrap();
+    }
+    for pubkey in [[1_u8; 5], [2; 5]] {
+        conn.tee_proof_generation_dal()
+            .save_attestation(&pubkey, &[0; 5])
+            .await
+            .unwrap();
+    }
+
+    create_proof_processing_router(
+        MockObjectStore::arc(),
+        db_conn_pool.clone(),
+        ProofDataHandlerConfig {
+            http_port: 1337,
+            proof_generation_timeout_in_secs: 10,
+            tee_config: TeeConfig {
+                tee_support: true,
+                quorum_tee_types: vec![TeeType::Sgx, TeeType::Tdx],
+                ..TeeConfig::default()
+            },
+        },
+        L1BatchCommitmentMode::Rollup,
+        L2ChainId::default(),
+    )
+    .unwrap()
+}
+
+fn quorum_proof_request(tee_type: &str, pubkey: &str) -> SubmitTeeProofRequest {
+    serde_json::from_value(json!({
+        "signature": "0001020304",
+        "pubkey": pubkey,
+        "proof": "0A0B0C0D0E",
+        "tee_type": tee_type
+    }))
+    .unwrap()
+}
+
+// Test that a batch reaches the TEE proof quorum only once it has proofs of all required TEE types
+// signed by distinct keys, and loses it if a replaced proof breaks the quorum
+#[tokio::test]
+async fn tee_proof_quorum() {
+    let batch_number = L1BatchNumber(1);
+    let db_conn_pool = ConnectionPool::test_pool().await;
+    let app = create_quorum_test_router(&db_conn_pool, batch_number).await;
+    let mut conn = db_conn_pool.connection().await.unwrap();
+    let uri = format!("/tee/submit_proofs/{}", batch_number.0);
+
+    let steps = [
+        ("sgx", "0101010101", false),
+        // the same key cannot be counted for both TEE types
+        ("tdx", "0101010101", false),
+        ("tdx", "0202020202", true),
+        // replacing the TDX proof with one signed by the SGX key invalidates the quorum
+        ("tdx", "0101010101", false),
+        ("tdx", "0202020202", true),
+    ];
+    for (tee_type, pubkey, quorum_reached) in steps {
+        let response =
+            send_submit_tee_proof_request(&app, &uri, &quorum_proof_request(tee_type, pubkey))
+                .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let reached_at = conn
+            .tee_proof_generation_dal()
+            .get_tee_quorum_reached_at(batch_number)
+            .await
+            .unwrap();
+        assert_eq!(reached_at.is_some(), quorum_reached, "{tee_type} {pubkey}");
+    }
+}
+
+// Test that proofs completing the quorum and submitted concurrently don't miss the quorum
+#[tokio::test]
+async fn tee_proof_quorum_with_concurrent_submissions() {
+    let batch_number = L1BatchNumber(1);
+    let db_conn_pool = ConnectionPool::test_pool().await;
+    let app = create_quorum_test_router(&db_conn_pool, batch_number).await;
+    let uri = format!("/tee/submit_proofs/{}", batch_number.0);
+
+    let sgx_request = quorum_proof_request("sgx", "0101010101");
+    let tdx_request = quorum_proof_request("tdx", "0202020202");
+    let (sgx_response, tdx_response) = tokio::join!(
+        send_submit_tee_proof_request(&app, &uri, &sgx_request),
+        send_submit_tee_proof_request(&app, &uri, &tdx_request)
+    );
+    assert_eq!(sgx_response.status(), StatusCode::OK);
+    assert_eq!(tdx_response.status(), StatusCode::OK);
+
+    let reached_at = db_conn_pool
+        .connection()
+        .await
+        .unwrap()
+        .tee_proof_generation_dal()
+        .get_tee_quorum_reached_at(batch_number)
+        .await
+        .unwrap();
+    assert!(reached_at.is_some());
+}
+
 // Mock SQL db with information about the status of the TEE proof generation
 async fn mock_tee_batch_status(
     db_conn_pool: ConnectionPool<zksync_dal::Core>,
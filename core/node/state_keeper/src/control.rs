@@ -0,0 +1,124 @@
+//! Operator control over a running state keeper.
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct ControlState {
+    paused: bool,
+    seal_requested: bool,
+}
+
+/// Handle allowing to pause / resume a [`ZkSyncStateKeeper`](crate::ZkSyncStateKeeper) and to force sealing
+/// of the current L1 batch. Cloned handles control the same state keeper.
+#[derive(Debug, Clone, Default)]
+pub struct StateKeeperControl(Arc<watch::Sender<ControlState>>);
+
+impl StateKeeperControl {
+    /// Pauses transaction processing. Returns `false` if the state keeper is already paused.
+    ///
+    /// A paused state keeper doesn't execute new transactions, but still seals L1 batches if requested
+    /// via [`Self::request_l1_batch_seal()`].
+    pub fn pause(&self) -> bool {
+        self.0
+            .send_if_modified(|state| !std::mem::replace(&mut state.paused, true))
+    }
+
+    /// Resumes transaction processing. Returns `false` if the state keeper isn't paused.
+    pub fn resume(&self) -> bool {
+        self.0
+            .send_if_modified(|state| std::mem::replace(&mut state.paused, false))
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.borrow().paused
+    }
+
+    /// Requests sealing the current L1 batch. The request is processed asynchronously by the state keeper;
+    /// it is ignored if the batch doesn't contain any transactions at the time of processing.
+    pub fn request_l1_batch_seal(&self) {
+        self.0.send_modify(|state| state.seal_requested = true);
+    }
+
+    /// Checks whether sealing the current L1 batch was requested, and resets the request.
+    pub(crate) fn take_seal_request(&self) -> bool {
+        let mut taken = false;
+        self.0.send_if_modified(|state| {
+            taken = std::mem::take(&mut state.seal_requested);
+            false // the state keeper is the only party interested in this change
+        });
+        taken
+    }
+
+    /// Waits until the state keeper is resumed or sealing the current L1 batch is requested.
+    /// Returns `false` if the stop signal was received while waiting.
+    pub(crate) async fn wait_until_active(&self, stop_receiver: &watch::Receiver<bool>) -> bool {
+        let mut state = self.0.subscribe();
+        let mut stop_receiver = stop_receiver.clone();
+        let is_active = |state: &ControlState| !state.paused || state.seal_requested;
+        if is_active(&state.borrow()) {
+            return true;
+        }
+
+        tracing::info!("State keeper is paused; waiting until it's resumed");
+        tokio::select! {
+            res = state.wait_for(is_active) => {
+                // The sender is owned by `self`, so it cannot be dropped.
+                res.ok();
+                true
+            }
+            _ = stop_receiver.wait_for(|&stop| stop) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn pausing_and_resuming() {
+        let control = StateKeeperControl::default();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        assert!(control.wait_until_active(&stop_receiver).await);
+
+        assert!(control.pause());
+        assert!(!control.pause());
+        assert!(control.is_paused());
+        let wait = tokio::time::timeout(
+            Duration::from_millis(50),
+            control.wait_until_active(&stop_receiver),
+        );
+        assert!(wait.await.is_err());
+
+        let wait_task = tokio::spawn({
+            let control = control.clone();
+            let stop_receiver = stop_receiver.clone();
+            async move { control.wait_until_active(&stop_receiver).await }
+        });
+        assert!(control.resume());
+        assert!(!control.resume());
+        assert!(wait_task.await.unwrap());
+
+        control.pause();
+        stop_sender.send_replace(true);
+        assert!(!control.wait_until_active(&stop_receiver).await);
+    }
+
+    #[tokio::test]
+    async fn seal_request_wakes_paused_state_keeper() {
+        let control = StateKeeperControl::default();
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        assert!(!control.take_seal_request());
+
+        control.pause();
+        control.request_l1_batch_seal();
+        assert!(control.wait_until_active(&stop_receiver).await);
+        assert!(control.take_seal_request());
+        assert!(!control.take_seal_request());
+        assert!(control.is_paused());
+    }
+}
@@ -24,6 +24,7 @@ use zksync_types::{
 };
 
 use crate::{
+    control::StateKeeperControl,
     executor::TxExecutionResult,
     health::StateKeeperHealthDetails,
     io::{IoCursor, L1BatchParams, L2BlockParams, OutputHandler, PendingBatchData, StateKeeperIO},
@@ -73,6 +74,7 @@ pub struct ZkSyncStateKeeper {
     sealer: Arc<dyn ConditionalSealer>,
     storage_factory: Arc<dyn ReadStorageFactory>,
     health_updater: HealthUpdater,
+    control: StateKeeperControl,
 }
 
 impl ZkSyncStateKeeper {
@@ -90,6 +92,7 @@ impl ZkSyncStateKeeper {
             sealer,
             storage_factory,
             health_updater: ReactiveHealthCheck::new("state_keeper").1,
+            control: StateKeeperControl::default(),
         }
     }
 
@@ -597,12 +600,21 @@ impl ZkSyncStateKeeper {
         }
 
         while !is_canceled(stop_receiver) {
+            if !self.control.wait_until_active(stop_receiver).await {
+                break;
+            }
+            let seal_requested = self.take_seal_request(updates_manager);
+            if !seal_requested && self.control.is_paused() {
+                continue;
+            }
+
             let full_latency = KEEPER_METRICS.process_l1_batch_loop_iteration.start();
 
-            if self
-                .io
-                .should_seal_l1_batch_unconditionally(updates_manager)
-                .await?
+            if seal_requested
+                || self
+                    .io
+                    .should_seal_l1_batch_unconditionally(updates_manager)
+                    .await?
             {
                 tracing::debug!(
                     "L1 batch #{} should be sealed unconditionally as per sealing rules",
@@ -911,8 +923,36 @@ impl ZkSyncStateKeeper {
         Ok((resolution, exec_result))
     }
 
+    /// Checks whether sealing the current L1 batch was requested via [`StateKeeperControl`]. Requests
+    /// for batches without transactions are discarded.
+    fn take_seal_request(&self, updates_manager: &UpdatesManager) -> bool {
+        if !self.control.take_seal_request() {
+            return false;
+        }
+        let l1_batch_number = updates_manager.l1_batch.number;
+        if updates_manager.pending_executed_transactions_len() == 0 {
+            tracing::info!(
+                "Ignoring request to seal L1 batch #{l1_batch_number} since it has no transactions"
+            );
+            false
+        } else {
+            tracing::info!("Sealing L1 batch #{l1_batch_number} as requested");
+            true
+        }
+    }
+
     /// Returns the health check for state keeper.
     pub fn health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
     }
+
+    pub(crate) fn with_control(mut self, control: StateKeeperControl) -> Self {
+        self.control = control;
+        self
+    }
+
+    /// Returns a handle allowing to pause the state keeper or to force sealing the current L1 batch.
+    pub fn control(&self) -> StateKeeperControl {
+        self.control.clone()
+    }
 }
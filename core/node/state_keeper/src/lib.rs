@@ -1,4 +1,5 @@
 pub use self::{
+    control::StateKeeperControl,
     io::{
         mempool::MempoolIO, L2BlockParams, L2BlockSealerTask, OutputHandler, StateKeeperIO,
         StateKeeperOutputHandler, StateKeeperPersistence, TreeWritesPersistence,
@@ -11,6 +12,7 @@ pub use self::{
     updates::UpdatesManager,
 };
 
+mod control;
 pub mod executor;
 mod health;
 pub mod io;
@@ -4,7 +4,7 @@ use anyhow::Context as _;
 #[cfg(test)]
 use tokio::sync::mpsc;
 use tokio::sync::watch;
-use zksync_config::configs::chain::MempoolConfig;
+use zksync_config::configs::{chain::MempoolConfig, MempoolFilters, ReloadableConfig};
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_mempool::L2TxFilter;
 use zksync_multivm::utils::derive_base_fee_and_gas_per_pubdata;
@@ -42,6 +42,7 @@ pub struct MempoolFetcher {
     stuck_tx_timeout: Option<Duration>,
     skip_unsafe_deposit_checks: bool,
     l1_to_l2_txs_paused: bool,
+    config_updates: Option<watch::Receiver<ReloadableConfig>>,
     #[cfg(test)]
     transaction_hashes_sender: mpsc::UnboundedSender<Vec<H256>>,
 }
@@ -61,11 +62,42 @@ impl MempoolFetcher {
             stuck_tx_timeout: config.remove_stuck_txs.then(|| config.stuck_tx_timeout()),
             skip_unsafe_deposit_checks: config.skip_unsafe_deposit_checks,
             l1_to_l2_txs_paused: config.l1_to_l2_txs_paused,
+            config_updates: None,
             #[cfg(test)]
             transaction_hashes_sender: mpsc::unbounded_channel().0,
         }
     }
 
+    /// Makes the fetcher apply [`MempoolFilters`] from config updates. Updates are checked before each mempool sync.
+    pub fn with_config_updates(mut self, updates: watch::Receiver<ReloadableConfig>) -> Self {
+        self.config_updates = Some(updates);
+        self
+    }
+
+    fn apply_config_updates(&mut self) {
+        let Some(updates) = &mut self.config_updates else {
+            return;
+        };
+        // An error means that the config reloader has stopped, so there will be no more updates.
+        if !updates.has_changed().unwrap_or(false) {
+            return;
+        }
+        let Some(filters) = updates.borrow_and_update().mempool_filters else {
+            return;
+        };
+        let MempoolFilters {
+            l1_to_l2_txs_paused,
+            skip_unsafe_deposit_checks,
+        } = filters;
+        if (l1_to_l2_txs_paused, skip_unsafe_deposit_checks)
+            != (self.l1_to_l2_txs_paused, self.skip_unsafe_deposit_checks)
+        {
+            tracing::info!("Changing mempool filters to {filters:?}");
+            self.l1_to_l2_txs_paused = l1_to_l2_txs_paused;
+            self.skip_unsafe_deposit_checks = skip_unsafe_deposit_checks;
+        }
+    }
+
     pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let mut storage = self.pool.connection_tagged("state_keeper").await?;
         if let Some(stuck_tx_timeout) = self.stuck_tx_timeout {
@@ -84,6 +116,7 @@ impl MempoolFetcher {
                 tracing::info!("Stop signal received, mempool is shutting down");
                 break;
             }
+            self.apply_config_updates();
             let latency = KEEPER_METRICS.mempool_sync.start();
             let mut connection = self.pool.connection_tagged("state_keeper").await?;
             let mut storage_transaction = connection.start_transaction().await?;
@@ -296,6 +329,37 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn applying_config_updates() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let (updates_sender, updates) = watch::channel(ReloadableConfig::default());
+        let mut fetcher = MempoolFetcher::new(
+            MempoolGuard::new(PriorityOpId(0), 100),
+            Arc::new(MockBatchFeeParamsProvider::default()),
+            &TEST_MEMPOOL_CONFIG,
+            pool,
+        )
+        .with_config_updates(updates);
+
+        fetcher.apply_config_updates();
+        assert!(!fetcher.l1_to_l2_txs_paused);
+
+        updates_sender.send_modify(|config| {
+            config.mempool_filters = Some(MempoolFilters {
+                l1_to_l2_txs_paused: true,
+                skip_unsafe_deposit_checks: true,
+            });
+        });
+        fetcher.apply_config_updates();
+        assert!(fetcher.l1_to_l2_txs_paused);
+        assert!(fetcher.skip_unsafe_deposit_checks);
+
+        // Missing filters must not reset the current ones.
+        updates_sender.send_replace(ReloadableConfig::default());
+        fetcher.apply_config_updates();
+        assert!(fetcher.l1_to_l2_txs_paused);
+    }
+
     #[tokio::test]
     async fn syncing_mempool_basics() {
         let pool = ConnectionPool::constrained_test_pool(1).await;
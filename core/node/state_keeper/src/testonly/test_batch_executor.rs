@@ -37,7 +37,7 @@ use crate::{
     seal_criteria::{IoSealCriteria, SequencerSealer, UnexecutableReason},
     testonly::{successful_exec, BASE_SYSTEM_CONTRACTS},
     updates::UpdatesManager,
-    OutputHandler, StateKeeperOutputHandler, ZkSyncStateKeeper,
+    OutputHandler, StateKeeperControl, StateKeeperOutputHandler, ZkSyncStateKeeper,
 };
 
 pub const FEE_ACCOUNT: Address = Address::repeat_byte(0x11);
@@ -59,6 +59,7 @@ pub(crate) struct TestScenario {
     pending_batch: Option<PendingBatchData>,
     l1_batch_seal_fn: Box<SealFn>,
     l2_block_seal_fn: Box<SealFn>,
+    control: StateKeeperControl,
 }
 
 type SealFn = dyn FnMut(&UpdatesManager) -> bool + Send + Sync;
@@ -80,9 +81,15 @@ impl TestScenario {
             pending_batch: None,
             l1_batch_seal_fn: Box::new(|_| false),
             l2_block_seal_fn: Box::new(|_| false),
+            control: StateKeeperControl::default(),
         }
     }
 
+    /// Returns the control handle for the state keeper that will run the scenario.
+    pub(crate) fn control(&self) -> StateKeeperControl {
+        self.control.clone()
+    }
+
     /// Adds a pending batch data that would be fed into the state keeper.
     /// Note that during processing pending batch, state keeper do *not* call `seal_l2_block` method on the IO (since
     /// it only recovers the temporary state).
@@ -214,6 +221,7 @@ impl TestScenario {
 
         let batch_executor = TestBatchExecutorBuilder::new(&self);
         let (stop_sender, stop_receiver) = watch::channel(false);
+        let control = self.control.clone();
         let (io, output_handler) = TestIO::new(stop_sender, self);
         let state_keeper = ZkSyncStateKeeper::new(
             Box::new(io),
@@ -221,7 +229,8 @@ impl TestScenario {
             output_handler,
             Arc::new(sealer),
             Arc::new(MockReadStorageFactory),
-        );
+        )
+        .with_control(control);
         let sk_thread = tokio::spawn(state_keeper.run(stop_receiver));
 
         // We must assume that *theoretically* state keeper may ignore the stop signal from IO once scenario is
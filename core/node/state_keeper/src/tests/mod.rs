@@ -373,6 +373,32 @@ async fn unconditional_sealing() {
         .await;
 }
 
+#[tokio::test]
+async fn requested_batch_seal() {
+    let config = StateKeeperConfig {
+        transaction_slots: 10,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    let scenario = TestScenario::new();
+    let control = scenario.control();
+    control.request_l1_batch_seal();
+    scenario
+        .seal_l2_block_when(move |updates| {
+            if updates.pending_executed_transactions_len() != 0 {
+                control.request_l1_batch_seal();
+            }
+            false
+        })
+        .next_tx("The only tx", random_tx(1), successful_exec())
+        .no_txs_until_next_action("Seal request for an empty batch is ignored")
+        .l2_block_sealed("L2 block is sealed together with the batch")
+        .batch_sealed("Batch is sealed as requested")
+        .run(sealer)
+        .await;
+}
+
 /// Checks the next L2 block sealed after pending batch has a correct timestamp
 #[tokio::test]
 async fn l2_block_timestamp_after_pending_batch() {
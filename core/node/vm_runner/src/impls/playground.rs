@@ -20,11 +20,12 @@ use zksync_state::RocksdbStorage;
 use zksync_types::{vm::FastVmMode, L1BatchNumber, L2ChainId};
 use zksync_vm_executor::batch::MainBatchExecutorFactory;
 use zksync_vm_interface::{
-    utils::{DivergenceHandler, VmDump},
+    utils::{DivergenceErrors, DivergenceHandler, VmDump},
     L1BatchEnv, L2BlockEnv, SystemEnv,
 };
 
 use crate::{
+    metrics::METRICS,
     storage::{PostgresLoader, StorageLoader},
     ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask, L1BatchOutput,
     L2BlockOutput, OutputHandler, OutputHandlerFactory, StorageSyncTask, VmRunner, VmRunnerIo,
@@ -35,6 +36,24 @@ use crate::{
 struct VmPlaygroundHealth {
     vm_mode: FastVmMode,
     last_processed_batch: L1BatchNumber,
+    divergences: DivergencesSummary,
+}
+
+/// Summary of detected VM divergences.
+#[derive(Debug, Clone, Default, Serialize)]
+struct DivergencesSummary {
+    /// Number of divergences in the divergence registry, or the number of divergences detected since the playground
+    /// was started if divergences are not recorded.
+    count: u64,
+    last: Option<LastDivergence>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct LastDivergence {
+    l1_batch_number: L1BatchNumber,
+    tx_index_in_l1_batch: Option<u32>,
+    kind: String,
+    dump_key: Option<String>,
 }
 
 impl From<VmPlaygroundHealth> for Health {
@@ -81,8 +100,9 @@ enum VmPlaygroundStorage {
 
 /// Virtual machine playground.
 ///
-/// Does not persist anything in Postgres; instead, keeps an L1 batch cursor as a plain text file in the RocksDB directory
-/// (so that the playground doesn't repeatedly process same batches after a restart).
+/// Keeps an L1 batch cursor as a plain text file in the RocksDB directory (so that the playground doesn't repeatedly process
+/// same batches after a restart). The only data that can be persisted in Postgres are detected VM divergences, which are recorded
+/// in the divergence registry (see [`VmDivergencesDal`](zksync_dal::vm_divergences_dal::VmDivergencesDal)) if this is enabled.
 ///
 /// If the RocksDB directory is not specified, the playground works in the ephemeral mode: it takes all inputs from Postgres, doesn't maintain cache
 /// and doesn't persist the processed batch cursor. This is mostly useful for debugging purposes.
@@ -100,9 +120,13 @@ pub struct VmPlayground {
 
 impl VmPlayground {
     /// Creates a new playground.
+    ///
+    /// If `divergences_pool` is provided, VM divergences are recorded in the divergence registry using this pool.
+    /// If neither `divergences_pool` nor `dumps_object_store` is provided, the playground panics on a VM divergence.
     pub async fn new(
         pool: ConnectionPool<Core>,
         dumps_object_store: Option<Arc<dyn ObjectStore>>,
+        divergences_pool: Option<ConnectionPool<Core>>,
         vm_mode: FastVmMode,
         storage: VmPlaygroundStorageOptions,
         chain_id: L2ChainId,
@@ -135,24 +159,14 @@ impl VmPlayground {
             latest_processed_batch.unwrap_or(cursor.first_processed_batch)
         };
 
-        let mut batch_executor_factory = MainBatchExecutorFactory::new(false);
-        batch_executor_factory.set_fast_vm_mode(vm_mode);
-        batch_executor_factory.observe_storage_metrics();
-        let handle = tokio::runtime::Handle::current();
-        if let Some(store) = dumps_object_store {
-            tracing::info!("Using object store for VM dumps: {store:?}");
-
-            let handler = DivergenceHandler::new(move |err, dump| {
-                let err_message = err.to_string();
-                if let Err(err) = handle.block_on(Self::dump_vm_state(&*store, &err_message, &dump))
-                {
-                    let l1_batch_number = dump.l1_batch_number();
-                    tracing::error!(
-                        "Saving VM dump for L1 batch #{l1_batch_number} failed: {err:#}"
-                    );
-                }
-            });
-            batch_executor_factory.set_divergence_handler(handler);
+        let mut divergences = DivergencesSummary::default();
+        if let Some(divergences_pool) = &divergences_pool {
+            let mut conn = divergences_pool.connection_tagged("vm_playground").await?;
+            divergences.count = conn.vm_divergences_dal().get_divergences_count().await?;
+            tracing::info!(
+                "Recording VM divergences in the registry; {} divergences are already recorded",
+                divergences.count
+            );
         }
 
         let io = VmPlaygroundIo {
@@ -160,8 +174,29 @@ impl VmPlayground {
             vm_mode,
             window_size: cursor.window_size.get(),
             latest_processed_batch: Arc::new(watch::channel(latest_processed_batch).0),
+            divergences: Arc::new(watch::channel(divergences).0),
+            divergences_pool: divergences_pool.clone(),
             health_updater: Arc::new(ReactiveHealthCheck::new("vm_playground").1),
         };
+
+        let mut batch_executor_factory = MainBatchExecutorFactory::new(false);
+        batch_executor_factory.set_fast_vm_mode(vm_mode);
+        batch_executor_factory.observe_storage_metrics();
+        if let Some(store) = &dumps_object_store {
+            tracing::info!("Using object store for VM dumps: {store:?}");
+        }
+        if dumps_object_store.is_some() || divergences_pool.is_some() {
+            let handle = tokio::runtime::Handle::current();
+            let handler_io = io.clone();
+            let handler = DivergenceHandler::new(move |err, dump| {
+                handle.block_on(async {
+                    let divergence =
+                        Self::handle_divergence(dumps_object_store.as_deref(), &err, &dump).await;
+                    handler_io.record_divergence(divergence).await;
+                });
+            });
+            batch_executor_factory.set_divergence_handler(handler);
+        }
         let (output_handler_factory, output_handler_factory_task) =
             ConcurrentOutputHandlerFactory::new(
                 pool.clone(),
@@ -200,11 +235,48 @@ impl VmPlayground {
         ))
     }
 
+    /// Dumps the VM state (if the object store is configured). Errors are logged rather than propagated, so that
+    /// a failure to persist a divergence doesn't stop the playground.
+    async fn handle_divergence(
+        object_store: Option<&dyn ObjectStore>,
+        err: &DivergenceErrors,
+        dump: &VmDump,
+    ) -> LastDivergence {
+        let l1_batch_number = dump.l1_batch_number();
+        let dump_key = if let Some(store) = object_store {
+            match Self::dump_vm_state(store, &err.to_string(), dump).await {
+                Ok(key) => Some(key),
+                Err(err) => {
+                    tracing::error!(
+                        "Saving VM dump for L1 batch #{l1_batch_number} failed: {err:#}"
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut fields: Vec<&str> = vec![];
+        for field in err.mismatched_fields() {
+            if !fields.contains(&field.as_str()) {
+                fields.push(field);
+            }
+        }
+        LastDivergence {
+            l1_batch_number,
+            tx_index_in_l1_batch: err.tx_index_in_l1_batch().map(|idx| idx as u32),
+            kind: fields.join(","),
+            dump_key,
+        }
+    }
+
+    /// Dumps VM state to the object store and returns the key of the dump.
     async fn dump_vm_state(
         object_store: &dyn ObjectStore,
         err_message: &str,
         dump: &VmDump,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<String> {
         // Deduplicate VM dumps by the error hash so that we don't create a lot of dumps for the same error.
         let mut hasher = DefaultHasher::new();
         err_message.hash(&mut hasher);
@@ -218,7 +290,7 @@ impl VmPlayground {
             .put_raw(Bucket::VmDumps, &dump_filename, dump.into_bytes())
             .await
             .context("failed putting VM dump to object store")?;
-        Ok(())
+        Ok(dump_filename)
     }
 
     /// Returns a health check for this component.
@@ -340,6 +412,8 @@ pub struct VmPlaygroundIo {
     // We don't read this value from the cursor file in the `VmRunnerIo` implementation because reads / writes
     // aren't guaranteed to be atomic.
     latest_processed_batch: Arc<watch::Sender<L1BatchNumber>>,
+    divergences: Arc<watch::Sender<DivergencesSummary>>,
+    divergences_pool: Option<ConnectionPool<Core>>,
     health_updater: Arc<HealthUpdater>,
 }
 
@@ -377,10 +451,63 @@ impl VmPlaygroundIo {
         let health = VmPlaygroundHealth {
             vm_mode: self.vm_mode,
             last_processed_batch: *self.latest_processed_batch.borrow(),
+            divergences: self.divergences.borrow().clone(),
         };
         self.health_updater.update(health.into());
     }
 
+    /// Records the divergence in the Postgres registry (if recording is enabled) and updates the divergences summary.
+    /// Repeated divergences are deduplicated by the registry, so they don't increase the summary count.
+    async fn record_divergence(&self, divergence: LastDivergence) {
+        METRICS.playground_vm_divergences.inc();
+        let is_new = if let Some(pool) = &self.divergences_pool {
+            let record_result = async {
+                let mut conn = pool.connection_tagged("vm_playground").await?;
+                let is_new = conn
+                    .vm_divergences_dal()
+                    .insert_divergence(
+                        divergence.l1_batch_number,
+                        divergence.tx_index_in_l1_batch,
+                        &divergence.kind,
+                        divergence.dump_key.as_deref(),
+                    )
+                    .await?;
+                anyhow::Ok(is_new)
+            };
+            match record_result.await {
+                Ok(is_new) => is_new,
+                Err(err) => {
+                    tracing::error!(
+                        "Recording VM divergence for L1 batch #{} failed: {err:#}",
+                        divergence.l1_batch_number
+                    );
+                    false
+                }
+            }
+        } else {
+            true
+        };
+
+        self.divergences.send_modify(|summary| {
+            if is_new {
+                summary.count += 1;
+            }
+            summary.last = Some(divergence);
+        });
+        self.update_health();
+    }
+
+    #[cfg(test)]
+    pub(crate) async fn record_mock_divergence(&self, l1_batch_number: L1BatchNumber, kind: &str) {
+        let divergence = LastDivergence {
+            l1_batch_number,
+            tx_index_in_l1_batch: Some(0),
+            kind: kind.to_owned(),
+            dump_key: None,
+        };
+        self.record_divergence(divergence).await;
+    }
+
     #[cfg(test)]
     pub(crate) fn subscribe_to_completed_batches(&self) -> watch::Receiver<L1BatchNumber> {
         self.latest_processed_batch.subscribe()
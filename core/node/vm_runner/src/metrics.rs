@@ -2,7 +2,9 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
 use zksync_state::OwnedStorage;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
@@ -46,6 +48,8 @@ pub(super) struct VmRunnerMetrics {
     /// Total latency of handling output of an L1 batch.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub output_handle_time: Histogram<Duration>,
+    /// Number of divergences between the main and shadow VM detected by the VM playground.
+    pub playground_vm_divergences: Counter,
 }
 
 #[vise::register]
@@ -2,7 +2,7 @@ use std::num::NonZeroU32;
 
 use test_casing::test_casing;
 use tokio::sync::watch;
-use zksync_health_check::HealthStatus;
+use zksync_health_check::{CheckHealth, HealthStatus};
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_state::RocksdbStorage;
 use zksync_types::vm::FastVmMode;
@@ -75,6 +75,7 @@ async fn run_playground(
     let (playground, playground_tasks) = VmPlayground::new(
         pool.clone(),
         None,
+        None,
         FastVmMode::Shadow,
         storage,
         genesis_params.config().l2_chain_id,
@@ -146,6 +147,7 @@ async fn wait_for_all_batches(
             }
             let health_details = health.details().unwrap();
             assert_eq!(health_details["vm_mode"], "shadow");
+            assert_eq!(health_details["divergences"]["count"], 0);
             health_details["last_processed_batch"] == u64::from(last_batch_number.0)
         })
         .await;
@@ -257,6 +259,7 @@ async fn using_larger_window_size(window_size: u32) {
     let (playground, playground_tasks) = VmPlayground::new(
         pool.clone(),
         None,
+        None,
         FastVmMode::Shadow,
         VmPlaygroundStorageOptions::from(&rocksdb_dir),
         genesis_params.config().l2_chain_id,
@@ -268,3 +271,58 @@ async fn using_larger_window_size(window_size: u32) {
     let mut conn = pool.connection().await.unwrap();
     wait_for_all_batches(playground, playground_tasks, &mut conn).await;
 }
+
+#[tokio::test]
+async fn recording_repeated_divergences() {
+    let pool = ConnectionPool::test_pool().await;
+    let genesis_params = setup_storage(&pool, 1, false).await;
+    let cursor = VmPlaygroundCursorOptions {
+        first_processed_batch: L1BatchNumber(0),
+        window_size: NonZeroU32::new(1).unwrap(),
+        reset_state: false,
+    };
+    let (playground, _playground_tasks) = VmPlayground::new(
+        pool.clone(),
+        None,
+        Some(pool.clone()),
+        FastVmMode::Shadow,
+        VmPlaygroundStorageOptions::Snapshots { shadow: false },
+        genesis_params.config().l2_chain_id,
+        cursor,
+    )
+    .await
+    .unwrap();
+    let health_check = playground.health_check();
+    let playground_io = playground.io().clone();
+
+    playground_io
+        .record_mock_divergence(L1BatchNumber(1), "result")
+        .await;
+    playground_io
+        .record_mock_divergence(L1BatchNumber(1), "result")
+        .await;
+    let health = health_check.check_health().await;
+    let health_details = health.details().unwrap();
+    assert_eq!(health_details["divergences"]["count"], 1);
+    assert_eq!(health_details["divergences"]["last"]["kind"], "result");
+
+    playground_io
+        .record_mock_divergence(L1BatchNumber(1), "logs.events")
+        .await;
+    let health = health_check.check_health().await;
+    let health_details = health.details().unwrap();
+    assert_eq!(health_details["divergences"]["count"], 2);
+    assert_eq!(health_details["divergences"]["last"]["kind"], "logs.events");
+
+    let mut conn = pool.connection().await.unwrap();
+    let divergences = conn
+        .vm_divergences_dal()
+        .get_divergences(L1BatchNumber(0), 10)
+        .await
+        .unwrap();
+    let occurrences: Vec<_> = divergences
+        .iter()
+        .map(|div| (div.kind.as_str(), div.occurrences))
+        .collect();
+    assert_eq!(occurrences, [("logs.events", 1), ("result", 2)]);
+}
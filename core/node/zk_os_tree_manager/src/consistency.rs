@@ -0,0 +1,319 @@
+//! Background consistency checks for the Merkle tree.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+
+use crate::{helpers::LazyAsyncTreeReader, metrics::CONSISTENCY_CHECK_METRICS};
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+enum ConsistencyCheckHealth {
+    Initialization,
+    Consistent {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        last_checked_version: Option<u64>,
+    },
+    Inconsistent {
+        version: u64,
+        error: String,
+    },
+    ShuttingDown,
+}
+
+impl From<ConsistencyCheckHealth> for Health {
+    fn from(health: ConsistencyCheckHealth) -> Self {
+        let status = match &health {
+            ConsistencyCheckHealth::Initialization
+            | ConsistencyCheckHealth::Inconsistent { .. } => HealthStatus::Affected,
+            ConsistencyCheckHealth::Consistent { .. } => HealthStatus::Ready,
+            ConsistencyCheckHealth::ShuttingDown => HealthStatus::ShuttingDown,
+        };
+        Health::from(status).with_details(health)
+    }
+}
+
+/// Task periodically verifying consistency of the latest ZK OS Merkle tree version (hashes, key lookups
+/// and linkage of leaves). The task is read-only; inconsistencies are reported via the health check, metrics and logs.
+/// Damaged internal nodes can be repaired on the tree manager startup (see [`TreeManagerConfig::repair_on_startup`])
+/// or using the consistency checker binary.
+///
+/// [`TreeManagerConfig::repair_on_startup`]: crate::TreeManagerConfig::repair_on_startup
+#[derive(Debug)]
+#[must_use = "Task should `run()` in a managed Tokio task"]
+pub struct TreeConsistencyCheckTask {
+    tree_reader: LazyAsyncTreeReader,
+    health_updater: HealthUpdater,
+    poll_interval: Duration,
+}
+
+impl TreeConsistencyCheckTask {
+    pub(crate) fn new(tree_reader: LazyAsyncTreeReader, poll_interval: Duration) -> Self {
+        Self {
+            tree_reader,
+            health_updater: ReactiveHealthCheck::new("zk_os_tree_consistency_check").1,
+            poll_interval,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.health_updater
+            .update(ConsistencyCheckHealth::Initialization.into());
+
+        let tree_reader = tokio::select! {
+            res = self.tree_reader.wait() => {
+                match res {
+                    Some(reader) => reader,
+                    None => {
+                        tracing::info!("Merkle tree dropped; shutting down tree consistency checks");
+                        return Ok(());
+                    }
+                }
+            }
+            _ = stop_receiver.changed() => {
+                tracing::info!("Stop signal received before Merkle tree is initialized; shutting down tree consistency checks");
+                return Ok(());
+            }
+        };
+        let health = ConsistencyCheckHealth::Consistent {
+            last_checked_version: None,
+        };
+        self.health_updater.update(health.into());
+        tracing::info!("Obtained tree reader; starting Merkle tree consistency checks");
+
+        let mut last_checked_version = None;
+        while !*stop_receiver.borrow_and_update() {
+            let latest_version = tree_reader
+                .clone()
+                .info()
+                .await?
+                .next_version
+                .checked_sub(1);
+            let version_to_check =
+                latest_version.filter(|&version| Some(version) != last_checked_version);
+            if let Some(version) = version_to_check {
+                tracing::debug!(version, "Checking Merkle tree consistency");
+                let latency = CONSISTENCY_CHECK_METRICS.latency.start();
+                let check_result = tree_reader.clone().verify_consistency(version).await?;
+                let latency = latency.observe();
+                CONSISTENCY_CHECK_METRICS.last_checked_version.set(version);
+
+                let health = match check_result {
+                    Ok(()) => {
+                        tracing::info!(version, ?latency, "Merkle tree is consistent");
+                        ConsistencyCheckHealth::Consistent {
+                            last_checked_version: Some(version),
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!(version, ?latency, "Merkle tree is inconsistent: {err}");
+                        CONSISTENCY_CHECK_METRICS.inconsistent_versions.inc();
+                        ConsistencyCheckHealth::Inconsistent {
+                            version,
+                            error: err.to_string(),
+                        }
+                    }
+                };
+                self.health_updater.update(health.into());
+                last_checked_version = Some(version);
+            }
+
+            if tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+
+        self.health_updater
+            .update(ConsistencyCheckHealth::ShuttingDown.into());
+        tracing::info!("Stop signal received, Merkle tree consistency checks are shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tempfile::TempDir;
+    use zk_os_merkle_tree::{MerkleTree, MerkleTreeColumnFamily, RocksDBWrapper};
+    use zksync_dal::{ConnectionPool, Core, CoreDal};
+    use zksync_node_test_utils::generate_storage_logs;
+    use zksync_storage::RocksDB;
+    use zksync_types::L1BatchNumber;
+
+    use super::*;
+    use crate::{
+        tests::{insert_l1_batch, mock_config, setup_tree_manager},
+        TreeManager, TreeManagerConfig,
+    };
+
+    #[tokio::test]
+    async fn consistency_check_task_basics() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let temp_dir = TempDir::new().expect("failed to get temporary directory for RocksDB");
+        let tree_manager = setup_tree_manager(temp_dir.path(), pool.clone()).await;
+        let mut batches_subscriber = tree_manager.subscribe_to_l1_batches();
+        let check_task = tree_manager.consistency_check_task(Duration::from_millis(10));
+        let mut health_check = check_task.health_check();
+
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let manager_task = tokio::spawn(tree_manager.run(stop_receiver.clone()));
+        let check_task = tokio::spawn(check_task.run(stop_receiver));
+
+        batches_subscriber
+            .wait_for(|&batch| batch == L1BatchNumber(1))
+            .await
+            .unwrap();
+        health_check
+            .wait_for(|health| {
+                matches!(health.status(), HealthStatus::Ready)
+                    && health.details().unwrap()["last_checked_version"] == 0
+            })
+            .await;
+
+        let mut conn = pool.connection().await.unwrap();
+        insert_l1_batch(&mut conn, &generate_storage_logs(100..120)).await;
+        batches_subscriber
+            .wait_for(|&batch| batch == L1BatchNumber(2))
+            .await
+            .unwrap();
+        health_check
+            .wait_for(|health| {
+                matches!(health.status(), HealthStatus::Ready)
+                    && health.details().unwrap()["last_checked_version"] == 1
+            })
+            .await;
+
+        stop_sender.send_replace(true);
+        manager_task.await.unwrap().unwrap();
+        check_task.await.unwrap().unwrap();
+        health_check
+            .wait_for(|health| matches!(health.status(), HealthStatus::ShuttingDown))
+            .await;
+    }
+
+    /// Overwrites raw bytes of the specified tree node in RocksDB starting from the offset returned by `offset_fn`.
+    fn damage_raw_node(
+        db_path: &Path,
+        (version, nibble_count, index_on_level): (u64, u8, u64),
+        offset_fn: impl FnOnce(&[u8]) -> usize,
+    ) {
+        let db = RocksDB::<MerkleTreeColumnFamily>::new(db_path).unwrap();
+        let mut db_key = version.to_be_bytes().to_vec();
+        db_key.push(nibble_count);
+        db_key.extend_from_slice(&index_on_level.to_be_bytes());
+
+        let mut raw_node = db
+            .get_cf(MerkleTreeColumnFamily::Tree, &db_key)
+            .unwrap()
+            .expect("node is missing");
+        let offset = offset_fn(&raw_node);
+        raw_node[offset..offset + 32].copy_from_slice(&[0x23; 32]);
+        let mut batch = db.new_write_batch();
+        batch.put_cf(MerkleTreeColumnFamily::Tree, &db_key, &raw_node);
+        db.write(batch).unwrap();
+    }
+
+    /// Returns the offset of the first child hash in a serialized root node.
+    fn root_child_hash_offset(raw_root: &[u8]) -> usize {
+        // Skip the LEB128-encoded leaf count and the child count.
+        let leaf_count_len = raw_root.iter().position(|&byte| byte < 0x80).unwrap() + 1;
+        leaf_count_len + 1
+    }
+
+    /// Runs the tree manager until it processes L1 batches #0 and #1.
+    async fn create_tree_with_two_l1_batches(db_path: &Path, pool: &ConnectionPool<Core>) {
+        let tree_manager = setup_tree_manager(db_path, pool.clone()).await;
+        let mut conn = pool.connection().await.unwrap();
+        insert_l1_batch(&mut conn, &generate_storage_logs(100..120)).await;
+        let mut batches_subscriber = tree_manager.subscribe_to_l1_batches();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let manager_task = tokio::spawn(tree_manager.run(stop_receiver));
+        batches_subscriber
+            .wait_for(|&batch| batch == L1BatchNumber(2))
+            .await
+            .unwrap();
+        stop_sender.send_replace(true);
+        manager_task.await.unwrap().unwrap();
+    }
+
+    fn repairing_tree_manager(db_path: &Path, pool: &ConnectionPool<Core>) -> TreeManager {
+        let config = TreeManagerConfig {
+            repair_on_startup: true,
+            ..mock_config(db_path)
+        };
+        TreeManager::new(config, pool.clone())
+    }
+
+    #[tokio::test]
+    async fn repairing_tree_on_startup() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let temp_dir = TempDir::new().expect("failed to get temporary directory for RocksDB");
+        create_tree_with_two_l1_batches(temp_dir.path(), &pool).await;
+        damage_raw_node(temp_dir.path(), (1, 0, 0), root_child_hash_offset);
+
+        let tree_manager = repairing_tree_manager(temp_dir.path(), &pool);
+        let mut batches_subscriber = tree_manager.subscribe_to_l1_batches();
+        let tree_reader = tree_manager.tree_reader();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let manager_task = tokio::spawn(tree_manager.run(stop_receiver));
+
+        let mut conn = pool.connection().await.unwrap();
+        insert_l1_batch(&mut conn, &generate_storage_logs(200..220)).await;
+        batches_subscriber
+            .wait_for(|&batch| batch == L1BatchNumber(3))
+            .await
+            .unwrap();
+        let tree_reader = tree_reader.wait().await.unwrap();
+        tree_reader
+            .clone()
+            .verify_consistency(1)
+            .await
+            .unwrap()
+            .unwrap();
+        tree_reader.verify_consistency(2).await.unwrap().unwrap();
+        stop_sender.send_replace(true);
+        manager_task.await.unwrap().unwrap();
+
+        // The repaired root hash must match the one persisted in Postgres before the damage.
+        let expected_root_hash = conn
+            .blocks_dal()
+            .get_l1_batch_tree_data(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap()
+            .hash;
+        let db = RocksDBWrapper::from(RocksDB::new(temp_dir.path()).unwrap());
+        let tree = MerkleTree::new(db).unwrap();
+        let (root_hash, _) = tree.root_info(1).unwrap().unwrap();
+        assert_eq!(root_hash, expected_root_hash);
+    }
+
+    #[tokio::test]
+    async fn repaired_root_hash_mismatch_is_fatal() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let temp_dir = TempDir::new().expect("failed to get temporary directory for RocksDB");
+        create_tree_with_two_l1_batches(temp_dir.path(), &pool).await;
+        // Damage the value of the max guard leaf; repair treats leaves as the source of truth, so the root hash
+        // of the repaired tree will diverge from Postgres.
+        damage_raw_node(temp_dir.path(), (0, 22, 1), |_| 32);
+
+        let tree_manager = repairing_tree_manager(temp_dir.path(), &pool);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let err = tree_manager.run(stop_receiver).await.unwrap_err();
+        let err = format!("{err:#}");
+        assert!(
+            err.contains("differs from the one persisted in Postgres"),
+            "{err}"
+        );
+    }
+}
@@ -1,13 +1,14 @@
 use anyhow::Context as _;
 use tokio::sync::watch;
 use zk_os_merkle_tree::{
-    unstable, BatchTreeProof, MerkleTree, MerkleTreeColumnFamily, MerkleTreeReader, Patched,
-    RocksDBWrapper,
+    unstable, BatchTreeProof, ConsistencyError, Database, MerkleTree, MerkleTreeColumnFamily,
+    MerkleTreePruner, MerkleTreeReader, MerkleTreeRecovery, Patched, RepairStats, RocksDBWrapper,
+    TreeEntry,
 };
 use zksync_storage::{RocksDB, RocksDBOptions, StalledWritesRetries, WeakRocksDB};
 use zksync_types::{block::L1BatchTreeData, L1BatchNumber, H256};
 
-use crate::{health::MerkleTreeInfo, TreeManagerConfig};
+use crate::{health::MerkleTreeInfo, pruning::PruningHandles, TreeManagerConfig};
 
 /// Async version of [`ZkSyncTreeReader`].
 #[derive(Debug, Clone)]
@@ -43,12 +44,11 @@ impl AsyncTreeReader {
                     continue;
                 };
 
-                // `min_l1_batch_number` is not necessarily consistent with other retrieved tree data, but this looks fine.
+                // `min_version` is not necessarily consistent with other retrieved tree data, but this looks fine.
                 break Ok(MerkleTreeInfo {
                     root_hash,
                     next_version: latest_version.map_or(0, |ver| ver + 1),
-                    // Valid because pruning isn't implemented yet
-                    min_version: latest_version.is_some().then_some(0),
+                    min_version: self.inner.first_retained_version()?,
                     leaf_count,
                 });
             }
@@ -67,6 +67,16 @@ impl AsyncTreeReader {
             .context("getting proof panicked")?
     }
 
+    /// Verifies consistency of the specified tree version. The outer error is only returned if the check panics.
+    pub(crate) async fn verify_consistency(
+        self,
+        version: u64,
+    ) -> anyhow::Result<Result<(), ConsistencyError>> {
+        tokio::task::spawn_blocking(move || self.inner.verify_consistency(version))
+            .await
+            .context("consistency check panicked")
+    }
+
     pub(crate) async fn raw_nodes(
         self,
         keys: Vec<unstable::NodeKey>,
@@ -136,6 +146,17 @@ impl AsyncMerkleTree {
         .context("panicked creating Merkle tree")?
     }
 
+    pub(crate) fn pruner(&self) -> PruningHandles {
+        let db = self
+            .inner
+            .as_ref()
+            .expect(Self::INCONSISTENT_MSG)
+            .db()
+            .inner()
+            .clone();
+        MerkleTreePruner::new(db)
+    }
+
     pub(crate) fn reader(&self) -> AsyncTreeReader {
         let db = self
             .inner
@@ -202,12 +223,34 @@ impl AsyncMerkleTree {
     }
 
     pub(crate) async fn min_l1_batch_number(&mut self) -> anyhow::Result<Option<L1BatchNumber>> {
-        Ok(if self.is_empty().await? {
-            None
-        } else {
-            // TODO: change once tree pruning is implemented
-            Some(L1BatchNumber(0))
+        self.try_invoke_tree(|tree| {
+            if tree.latest_version()?.is_none() {
+                return Ok(None);
+            }
+            // Pruning only affects the underlying DB, so the patch doesn't need to be considered. If no versions
+            // were flushed to the DB yet, nothing could be pruned either.
+            let reader = MerkleTreeReader::new(tree.db().inner().clone())?;
+            let min_version = reader.first_retained_version()?.unwrap_or(0);
+            Ok(Some(L1BatchNumber(
+                min_version.try_into().context("tree version overflow")?,
+            )))
+        })
+        .await
+    }
+
+    /// Repairs damaged internal nodes for the latest tree version. Returns `None` if the tree is empty.
+    pub(crate) async fn repair(&mut self) -> anyhow::Result<Option<(L1BatchNumber, RepairStats)>> {
+        self.try_invoke_tree(|tree| {
+            let Some(version) = tree.latest_version()? else {
+                return Ok(None);
+            };
+            let l1_batch_number =
+                L1BatchNumber(version.try_into().context("tree version overflow")?);
+            // Any changes held in RAM are flushed before the repaired nodes are written.
+            let stats = tree.repair(version)?;
+            Ok(Some((l1_batch_number, stats)))
         })
+        .await
     }
 
     pub(crate) async fn save(&mut self) -> anyhow::Result<()> {
@@ -224,6 +267,115 @@ impl AsyncMerkleTree {
     }
 }
 
+type RocksDBTreeRecovery = MerkleTreeRecovery<RocksDBWrapper>;
+
+/// Async wrapper for [`MerkleTreeRecovery`].
+///
+/// Async methods provided by this wrapper are not cancel-safe!
+#[derive(Debug)]
+pub(crate) struct AsyncTreeRecovery {
+    inner: Option<RocksDBTreeRecovery>,
+    recovered_version: u64,
+}
+
+impl AsyncTreeRecovery {
+    const INCONSISTENT_MSG: &'static str =
+        "`AsyncTreeRecovery` is in inconsistent state, which could occur after one of its async methods was cancelled or returned an error";
+
+    pub(crate) async fn new(db: RocksDBWrapper, recovered_version: u64) -> anyhow::Result<Self> {
+        let recovery =
+            tokio::task::spawn_blocking(move || MerkleTreeRecovery::new(db, recovered_version))
+                .await
+                .context("panicked initializing Merkle tree recovery")??;
+        Ok(Self::from(recovery))
+    }
+
+    pub(crate) fn recovered_version(&self) -> u64 {
+        self.recovered_version
+    }
+
+    async fn try_invoke_recovery<T, F>(&mut self, f: F) -> anyhow::Result<T>
+    where
+        T: 'static + Send,
+        F: FnOnce(&mut RocksDBTreeRecovery) -> anyhow::Result<T> + 'static + Send,
+    {
+        let mut recovery = self.inner.take().context(Self::INCONSISTENT_MSG)?;
+        let (output, recovery) = tokio::task::spawn_blocking(|| (f(&mut recovery), recovery))
+            .await
+            .context("tree recovery action panicked")?;
+        self.inner = Some(recovery);
+        output
+    }
+
+    /// Returns leaf indices for the specified keys; `None` means that the key is not recovered yet.
+    pub(crate) async fn leaf_indices(
+        &mut self,
+        keys: Vec<H256>,
+    ) -> anyhow::Result<Vec<Option<u64>>> {
+        self.try_invoke_recovery(move |recovery| recovery.leaf_indices(&keys))
+            .await
+    }
+
+    pub(crate) async fn extend(&mut self, entries: Vec<(u64, TreeEntry)>) -> anyhow::Result<()> {
+        self.try_invoke_recovery(move |recovery| recovery.extend(&entries))
+            .await
+    }
+
+    pub(crate) async fn root_hash(&mut self) -> anyhow::Result<H256> {
+        self.try_invoke_recovery(RocksDBTreeRecovery::root_hash)
+            .await
+    }
+
+    pub(crate) async fn finalize(self) -> anyhow::Result<AsyncMerkleTree> {
+        let recovery = self.inner.context(Self::INCONSISTENT_MSG)?;
+        let db = tokio::task::spawn_blocking(|| recovery.finalize())
+            .await
+            .context("panicked finalizing tree recovery")??;
+        AsyncMerkleTree::new(db).await
+    }
+}
+
+impl From<RocksDBTreeRecovery> for AsyncTreeRecovery {
+    fn from(recovery: RocksDBTreeRecovery) -> Self {
+        Self {
+            recovered_version: recovery.recovered_version(),
+            inner: Some(recovery),
+        }
+    }
+}
+
+/// Tree at any of the possible lifecycle stages.
+#[derive(Debug)]
+pub(crate) enum GenericAsyncTree {
+    /// Uninitialized tree.
+    Empty(RocksDBWrapper),
+    /// The tree during recovery.
+    Recovering(AsyncTreeRecovery),
+    /// The tree after recovery.
+    Ready(AsyncMerkleTree),
+}
+
+impl GenericAsyncTree {
+    pub(crate) async fn new(db: RocksDBWrapper) -> anyhow::Result<Self> {
+        tokio::task::spawn_blocking(|| {
+            if db.try_manifest()?.is_none() {
+                return anyhow::Ok(Self::Empty(db));
+            }
+            Ok(
+                if let Some(version) = RocksDBTreeRecovery::recovering_version(&db)? {
+                    Self::Recovering(MerkleTreeRecovery::new(db, version)?.into())
+                } else {
+                    Self::Ready(AsyncMerkleTree {
+                        inner: Some(MerkleTree::new(Patched::new(db))?),
+                    })
+                },
+            )
+        })
+        .await
+        .context("panicked creating Merkle tree")?
+    }
+}
+
 /// Creates a RocksDB wrapper with the specified params.
 pub(crate) async fn create_db(config: TreeManagerConfig) -> anyhow::Result<RocksDBWrapper> {
     tokio::task::spawn_blocking(move || create_db_sync(&config))
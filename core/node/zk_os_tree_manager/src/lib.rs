@@ -5,28 +5,53 @@ use std::{
 };
 
 use anyhow::Context;
-use tokio::sync::watch;
+use tokio::sync::{oneshot, watch};
 use zksync_dal::{ConnectionPool, Core};
 use zksync_health_check::{CheckHealth, HealthUpdater, ReactiveHealthCheck};
 use zksync_shared_metrics::tree::{ConfigLabels, ModeLabel, METRICS};
-#[cfg(test)]
 use zksync_types::L1BatchNumber;
 
-pub use self::helpers::LazyAsyncTreeReader;
+pub use self::{
+    consistency::TreeConsistencyCheckTask, helpers::LazyAsyncTreeReader, pruning::TreePruningTask,
+};
 use crate::{
     health::{MerkleTreeHealth, MerkleTreeHealthCheck},
-    helpers::{create_db, AsyncMerkleTree, AsyncTreeReader},
+    helpers::{create_db, AsyncMerkleTree, AsyncTreeReader, GenericAsyncTree},
+    pruning::PruningHandles,
     updater::TreeUpdater,
 };
 
 pub mod api;
 mod batch;
+mod consistency;
 mod health;
 mod helpers;
+mod metrics;
+mod pruning;
+mod recovery;
 #[cfg(test)]
 mod tests;
 mod updater;
 
+/// Configuration of ZK OS Merkle tree recovery from a snapshot.
+#[derive(Debug, Clone)]
+pub struct TreeRecoveryConfig {
+    /// Approximate chunk size (measured in the number of entries) to recover in a single iteration.
+    /// Reasonable values are order of 100,000 (meaning an iteration takes several seconds).
+    ///
+    /// **Important.** This value cannot be changed in the middle of tree recovery (i.e., if a node is stopped in the middle
+    /// of recovery and then restarted with a different config).
+    pub desired_chunk_size: u64,
+}
+
+impl Default for TreeRecoveryConfig {
+    fn default() -> Self {
+        Self {
+            desired_chunk_size: 200_000,
+        }
+    }
+}
+
 /// Configuration of [`TreeManager`].
 #[derive(Debug, Clone)]
 pub struct TreeManagerConfig {
@@ -48,6 +73,11 @@ pub struct TreeManagerConfig {
     /// being loaded entirely into RAM on the RocksDB initialization. The block cache capacity should be increased
     /// correspondingly; otherwise, RocksDB performance can significantly degrade.
     pub include_indices_and_filters_in_block_cache: bool,
+    /// If set, damaged internal nodes of the latest tree version will be repaired (i.e., recomputed from leaves)
+    /// on the tree manager startup. This requires traversing the entire tree, so it may take a while for large trees.
+    pub repair_on_startup: bool,
+    /// Configuration specific to the Merkle tree recovery.
+    pub recovery: TreeRecoveryConfig,
 }
 
 impl TreeManagerConfig {
@@ -71,8 +101,10 @@ impl TreeManagerConfig {
 pub struct TreeManager {
     config: TreeManagerConfig,
     pool: ConnectionPool<Core>,
+    recovery_pool: ConnectionPool<Core>,
     health_updater: HealthUpdater,
     tree_reader: watch::Sender<Option<AsyncTreeReader>>,
+    pruning_handles_sender: oneshot::Sender<PruningHandles>,
     #[cfg(test)]
     next_l1_batch_sender: watch::Sender<L1BatchNumber>,
 }
@@ -90,14 +122,23 @@ impl TreeManager {
         let (_, health_updater) = ReactiveHealthCheck::new("tree");
         Self {
             config,
+            recovery_pool: pool.clone(),
             pool,
             health_updater,
             tree_reader: watch::channel(None).0,
+            pruning_handles_sender: oneshot::channel().0,
             #[cfg(test)]
             next_l1_batch_sender: watch::channel(L1BatchNumber(0)).0,
         }
     }
 
+    /// Sets the connection pool used for tree recovery. By default, the main pool is used; a dedicated pool
+    /// allows to control recovery concurrency, which is bounded by the pool size.
+    pub fn with_recovery_pool(mut self, recovery_pool: ConnectionPool<Core>) -> Self {
+        self.recovery_pool = recovery_pool;
+        self
+    }
+
     /// Returns a health check for the tree.
     pub fn tree_health_check(&self) -> impl CheckHealth {
         MerkleTreeHealthCheck::new(self.health_updater.subscribe(), self.tree_reader())
@@ -108,12 +149,26 @@ impl TreeManager {
         LazyAsyncTreeReader(self.tree_reader.subscribe())
     }
 
+    /// Returns a task that can be used to prune the tree according to the pruning logs in Postgres.
+    /// This method should be called once; only the latest returned task will do any job, all previous
+    /// ones will terminate immediately.
+    pub fn pruning_task(&mut self, poll_interval: Duration) -> TreePruningTask {
+        let (pruning_handles_sender, pruning_handles) = oneshot::channel();
+        self.pruning_handles_sender = pruning_handles_sender;
+        TreePruningTask::new(pruning_handles, self.pool.clone(), poll_interval)
+    }
+
+    /// Returns a task that periodically verifies consistency of the latest tree version.
+    pub fn consistency_check_task(&self, poll_interval: Duration) -> TreeConsistencyCheckTask {
+        TreeConsistencyCheckTask::new(self.tree_reader(), poll_interval)
+    }
+
     #[cfg(test)]
     fn subscribe_to_l1_batches(&self) -> watch::Receiver<L1BatchNumber> {
         self.next_l1_batch_sender.subscribe()
     }
 
-    async fn create_tree(&self) -> anyhow::Result<AsyncMerkleTree> {
+    async fn create_tree(&self) -> anyhow::Result<GenericAsyncTree> {
         self.health_updater
             .update(MerkleTreeHealth::Initialization.into());
 
@@ -130,11 +185,72 @@ impl TreeManager {
             started_at.elapsed()
         );
 
-        AsyncMerkleTree::new(db).await
+        GenericAsyncTree::new(db).await
+    }
+
+    /// Repairs the latest tree version and checks that its root hash matches the one persisted in Postgres.
+    async fn repair_tree(&self, tree: &mut AsyncMerkleTree) -> anyhow::Result<()> {
+        tracing::info!("Repairing Merkle tree");
+        let started_at = Instant::now();
+        let Some((l1_batch_number, stats)) = tree
+            .repair()
+            .await
+            .context("failed repairing Merkle tree")?
+        else {
+            tracing::info!("Merkle tree is empty; nothing to repair");
+            return Ok(());
+        };
+        tracing::info!(
+            ?stats,
+            "Merkle tree repair for L1 batch #{l1_batch_number} finished in {:?}",
+            started_at.elapsed()
+        );
+
+        if l1_batch_number == L1BatchNumber(0) {
+            // Root hash for L1 batch #0 persisted in Postgres is fictive (set to `H256::zero()`).
+            return Ok(());
+        }
+        let mut storage = self.pool.connection_tagged("tree_manager").await?;
+        let tree_data_from_postgres = storage
+            .blocks_dal()
+            .get_l1_batch_tree_data(l1_batch_number)
+            .await?;
+        drop(storage);
+
+        if let Some(tree_data) = tree_data_from_postgres {
+            anyhow::ensure!(
+                tree_data.hash == stats.root_hash,
+                "Root hash of the repaired Merkle tree for L1 batch #{l1_batch_number} ({:?}) differs from the one \
+                 persisted in Postgres ({:?}); the tree leaves are probably damaged and the tree should be recovered anew",
+                stats.root_hash,
+                tree_data.hash
+            );
+        } else {
+            tracing::info!(
+                "L1 batch #{l1_batch_number} has no tree data in Postgres; skipping root hash comparison for the repaired tree"
+            );
+        }
+        Ok(())
     }
 
     pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
-        let mut tree = self.create_tree().await?;
+        let tree = self.create_tree().await?;
+        let tree = tree
+            .ensure_ready(
+                &self.config.recovery,
+                &self.pool,
+                self.recovery_pool,
+                &self.health_updater,
+                &stop_receiver,
+            )
+            .await?;
+        let Some(mut tree) = tree else {
+            return Ok(()); // recovery was aborted because a stop signal was received
+        };
+
+        if self.config.repair_on_startup {
+            self.repair_tree(&mut tree).await?;
+        }
 
         // Set a tree reader before the tree is fully initialized to not wait for the first L1 batch to appear in Postgres.
         let tree_reader = tree.reader();
@@ -142,6 +258,11 @@ impl TreeManager {
 
         tree.ensure_consistency(self.config.delay_interval, &self.pool, &mut stop_receiver)
             .await?;
+        if !self.pruning_handles_sender.is_closed() {
+            // Unlike the tree reader, pruning (as a task modifying the tree) must not be initialized
+            // before the tree is guaranteed to be consistent with Postgres.
+            self.pruning_handles_sender.send(tree.pruner()).ok();
+        }
 
         let tree_info = tree.reader().info().await.context("cannot get tree info")?;
         tracing::info!("Merkle tree is initialized and ready to process L1 batches: {tree_info:?}");
@@ -0,0 +1,57 @@
+//! Metrics for the ZK OS tree manager.
+
+use std::time::Duration;
+
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(crate) enum RecoveryStage {
+    LoadChunkStarts,
+    Finalize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(crate) enum ChunkRecoveryStage {
+    AcquireConnection,
+    LoadEntries,
+    LockTree,
+    ExtendTree,
+}
+
+/// Metrics for ZK OS Merkle tree recovery triggered by the tree manager.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "zk_os_tree_manager_recovery")]
+pub(crate) struct TreeRecoveryMetrics {
+    /// Number of chunks recovered.
+    pub recovered_chunk_count: Gauge<u64>,
+    /// Latency of a specific stage of recovery for a single chunk.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub chunk_latency: Family<ChunkRecoveryStage, Histogram<Duration>>,
+    /// Latency of a specific stage of recovery for the entire tree.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub latency: Family<RecoveryStage, Histogram<Duration>>,
+}
+
+#[vise::register]
+pub(crate) static RECOVERY_METRICS: vise::Global<TreeRecoveryMetrics> = vise::Global::new();
+
+/// Metrics for background consistency checks of the ZK OS Merkle tree.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "zk_os_tree_manager_consistency_check")]
+pub(crate) struct ConsistencyCheckMetrics {
+    /// Latest checked tree version.
+    pub last_checked_version: Gauge<u64>,
+    /// Number of checked tree versions that turned out to be inconsistent.
+    pub inconsistent_versions: Counter,
+    /// Latency of checking a single tree version.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(crate) static CONSISTENCY_CHECK_METRICS: vise::Global<ConsistencyCheckMetrics> =
+    vise::Global::new();
@@ -0,0 +1,153 @@
+//! Merkle tree pruning logic.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::{oneshot, watch};
+use zk_os_merkle_tree::{MerkleTreePruner, MerkleTreePrunerHandle, RocksDBWrapper};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::L1BatchNumber;
+
+pub(crate) type PruningHandles = (MerkleTreePruner<RocksDBWrapper>, MerkleTreePrunerHandle);
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+enum TreePruningTaskHealth {
+    Initialization,
+    Pruning {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        target_retained_l1_batch_number: Option<L1BatchNumber>,
+    },
+    PruningStopped,
+    ShuttingDown,
+}
+
+impl From<TreePruningTaskHealth> for Health {
+    fn from(health: TreePruningTaskHealth) -> Self {
+        let status = match &health {
+            TreePruningTaskHealth::Initialization | TreePruningTaskHealth::PruningStopped => {
+                HealthStatus::Affected
+            }
+            TreePruningTaskHealth::Pruning { .. } => HealthStatus::Ready,
+            TreePruningTaskHealth::ShuttingDown => HealthStatus::ShuttingDown,
+        };
+        Health::from(status).with_details(health)
+    }
+}
+
+/// Task pruning the ZK OS Merkle tree according to the pruning entries in Postgres.
+#[derive(Debug)]
+#[must_use = "Task should `run()` in a managed Tokio task"]
+pub struct TreePruningTask {
+    handles: oneshot::Receiver<PruningHandles>,
+    pool: ConnectionPool<Core>,
+    health_updater: HealthUpdater,
+    poll_interval: Duration,
+    retained_l1_batches: u32,
+}
+
+impl TreePruningTask {
+    pub(crate) fn new(
+        handles: oneshot::Receiver<PruningHandles>,
+        pool: ConnectionPool<Core>,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            handles,
+            pool,
+            health_updater: ReactiveHealthCheck::new("zk_os_tree_pruner").1,
+            poll_interval,
+            retained_l1_batches: 0,
+        }
+    }
+
+    /// Sets the number of L1 batches pruned in Postgres that will still be retained in the tree.
+    /// By default, the tree is pruned in lockstep with Postgres.
+    pub fn with_retained_l1_batches(mut self, count: u32) -> Self {
+        self.retained_l1_batches = count;
+        self
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        // The pruning task is "affected" (not functioning) until the Merkle tree is initialized.
+        self.health_updater
+            .update(TreePruningTaskHealth::Initialization.into());
+
+        let (mut pruner, pruner_handle);
+        tokio::select! {
+            res = self.handles => {
+                match res {
+                    Ok(handles) => (pruner, pruner_handle) = handles,
+                    Err(_) => {
+                        tracing::info!("Merkle tree dropped; shutting down tree pruning");
+                        return Ok(());
+                    }
+                }
+            }
+            _ = stop_receiver.changed() => {
+                tracing::info!("Stop signal received before Merkle tree is initialized; shutting down tree pruning");
+                return Ok(());
+            }
+        }
+        let health = TreePruningTaskHealth::Pruning {
+            target_retained_l1_batch_number: None,
+        };
+        self.health_updater.update(health.into());
+        tracing::info!("Obtained pruning handles; starting Merkle tree pruning");
+
+        // The pruner is blocking, so it runs on a separate thread; it is stopped by dropping the pruner handle.
+        pruner.set_poll_interval(self.poll_interval);
+        let pruner_task_handle = tokio::task::spawn_blocking(|| pruner.run());
+
+        while !*stop_receiver.borrow_and_update() {
+            let mut storage = self.pool.connection_tagged("tree_pruner").await?;
+            let pruning_info = storage.pruning_dal().get_pruning_info().await?;
+            drop(storage);
+
+            if let Some(pruned) = pruning_info.last_hard_pruned {
+                let target_retained_l1_batch_number =
+                    L1BatchNumber((pruned.l1_batch.0 + 1).saturating_sub(self.retained_l1_batches));
+                let target_retained_version = u64::from(target_retained_l1_batch_number.0);
+                let Ok(prev_target_version) =
+                    pruner_handle.set_target_retained_version(target_retained_version)
+                else {
+                    self.health_updater
+                        .update(TreePruningTaskHealth::PruningStopped.into());
+                    tracing::error!("Merkle tree pruning thread unexpectedly stopped");
+                    return pruner_task_handle
+                        .await
+                        .context("Merkle tree pruning thread panicked")?;
+                };
+
+                if prev_target_version != target_retained_version {
+                    let health = TreePruningTaskHealth::Pruning {
+                        target_retained_l1_batch_number: Some(target_retained_l1_batch_number),
+                    };
+                    self.health_updater.update(health.into());
+                    tracing::info!("Set target retained tree version from {prev_target_version} to {target_retained_version}");
+                }
+            }
+
+            if tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+
+        self.health_updater
+            .update(TreePruningTaskHealth::ShuttingDown.into());
+        tracing::info!("Stop signal received, Merkle tree pruning is shutting down");
+        drop(pruner_handle);
+        pruner_task_handle
+            .await
+            .context("Merkle tree pruning thread panicked")?
+    }
+}
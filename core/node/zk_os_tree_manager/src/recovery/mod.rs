@@ -0,0 +1,439 @@
+//! High-level recovery logic for the ZK OS Merkle tree.
+//!
+//! Recovery is started on tree manager initialization if the tree is empty and Postgres contains a snapshot
+//! (or is pruned). The snapshot storage logs are loaded from Postgres in chunks defined by uniformly splitting
+//! the hashed key space; chunks are loaded concurrently and fed into the tree one by one. Before loading chunks,
+//! we filter out the chunks that have already been recovered by checking whether the first key of each chunk
+//! is present in the tree. Thus, chunks **must** always be defined in the same way, i.e. the desired chunk size
+//! must not change while recovery is in progress.
+//!
+//! If recovery is interrupted, the remaining chunks are recovered after the tree manager is restarted.
+//! After all chunks are recovered, the root hash of the tree is checked against the root hash of the snapshot L1 batch.
+
+use std::{
+    fmt, ops,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+use anyhow::Context as _;
+use futures::future;
+use tokio::sync::{watch, Mutex, Semaphore};
+use zk_os_merkle_tree::TreeEntry;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_health_check::HealthUpdater;
+use zksync_shared_metrics::{SnapshotRecoveryStage, APP_METRICS};
+use zksync_types::{snapshots::uniform_hashed_keys_chunk, L1BatchNumber, L2BlockNumber, H256};
+
+use crate::{
+    health::MerkleTreeHealth,
+    helpers::{AsyncMerkleTree, AsyncTreeRecovery, GenericAsyncTree},
+    metrics::{ChunkRecoveryStage, RecoveryStage, RECOVERY_METRICS},
+    TreeRecoveryConfig,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Handler of recovery life cycle events. Encapsulated in a trait to be able to control recovery in tests.
+trait HandleRecoveryEvent: fmt::Debug + Send + Sync {
+    fn recovery_started(&mut self, _chunk_count: u64, _recovered_chunk_count: u64) {
+        // Default implementation does nothing
+    }
+
+    fn chunk_recovered(&self) {
+        // Default implementation does nothing
+    }
+}
+
+/// [`HealthUpdater`]-based [`HandleRecoveryEvent`] implementation.
+#[derive(Debug)]
+struct RecoveryHealthUpdater<'a> {
+    inner: &'a HealthUpdater,
+    chunk_count: u64,
+    recovered_chunk_count: AtomicU64,
+}
+
+impl<'a> RecoveryHealthUpdater<'a> {
+    fn new(inner: &'a HealthUpdater) -> Self {
+        Self {
+            inner,
+            chunk_count: 0,
+            recovered_chunk_count: AtomicU64::new(0),
+        }
+    }
+}
+
+impl HandleRecoveryEvent for RecoveryHealthUpdater<'_> {
+    fn recovery_started(&mut self, chunk_count: u64, recovered_chunk_count: u64) {
+        self.chunk_count = chunk_count;
+        *self.recovered_chunk_count.get_mut() = recovered_chunk_count;
+        RECOVERY_METRICS
+            .recovered_chunk_count
+            .set(recovered_chunk_count);
+    }
+
+    fn chunk_recovered(&self) {
+        let recovered_chunk_count = self.recovered_chunk_count.fetch_add(1, Ordering::SeqCst) + 1;
+        let chunks_left = self.chunk_count.saturating_sub(recovered_chunk_count);
+        tracing::info!(
+            "Recovered {recovered_chunk_count}/{} Merkle tree chunks, there are {chunks_left} left to process",
+            self.chunk_count
+        );
+        RECOVERY_METRICS
+            .recovered_chunk_count
+            .set(recovered_chunk_count);
+        let health = MerkleTreeHealth::Recovery {
+            chunk_count: self.chunk_count,
+            recovered_chunk_count,
+        };
+        self.inner.update(health.into());
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct InitParameters {
+    l1_batch: L1BatchNumber,
+    l2_block: L2BlockNumber,
+    expected_root_hash: Option<H256>,
+    log_count: u64,
+    desired_chunk_size: u64,
+}
+
+impl InitParameters {
+    /// Returns `None` if the tree should be built from scratch, i.e., Postgres is neither recovered
+    /// from a snapshot nor pruned.
+    async fn new(
+        pool: &ConnectionPool<Core>,
+        config: &TreeRecoveryConfig,
+    ) -> anyhow::Result<Option<Self>> {
+        let mut storage = pool.connection_tagged("zk_os_tree_manager").await?;
+        let recovery_status = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await?;
+        let pruning_info = storage.pruning_dal().get_pruning_info().await?;
+
+        let (l1_batch, l2_block);
+        let mut expected_root_hash = None;
+        match (recovery_status, pruning_info.last_hard_pruned) {
+            (Some(recovery), None) => {
+                tracing::warn!(
+                    "Snapshot recovery {recovery:?} is present on the node, but pruning info is empty; assuming no pruning happened"
+                );
+                l1_batch = recovery.l1_batch_number;
+                l2_block = recovery.l2_block_number;
+                expected_root_hash = Some(recovery.l1_batch_root_hash);
+            }
+            (Some(recovery), Some(pruned)) => {
+                // We have both recovery and some pruning on top of it.
+                l2_block = pruned.l2_block.max(recovery.l2_block_number);
+                l1_batch = pruned.l1_batch;
+                if let Some(root_hash) = pruned.l1_batch_root_hash {
+                    expected_root_hash = Some(root_hash);
+                } else if l1_batch == recovery.l1_batch_number {
+                    expected_root_hash = Some(recovery.l1_batch_root_hash);
+                }
+            }
+            (None, Some(pruned)) => {
+                l2_block = pruned.l2_block;
+                l1_batch = pruned.l1_batch;
+                expected_root_hash = pruned.l1_batch_root_hash;
+            }
+            (None, None) => return Ok(None),
+        };
+
+        let log_count = storage
+            .storage_logs_dal()
+            .get_storage_logs_row_count(l2_block)
+            .await?;
+
+        Ok(Some(Self {
+            l1_batch,
+            l2_block,
+            expected_root_hash,
+            log_count,
+            desired_chunk_size: config.desired_chunk_size,
+        }))
+    }
+
+    fn chunk_count(&self) -> u64 {
+        self.log_count.div_ceil(self.desired_chunk_size)
+    }
+}
+
+/// Options for tree recovery.
+#[derive(Debug)]
+struct RecoveryOptions<'a> {
+    chunk_count: u64,
+    concurrency_limit: usize,
+    events: Box<dyn HandleRecoveryEvent + 'a>,
+}
+
+impl GenericAsyncTree {
+    /// Ensures that the tree is ready for the normal operation, recovering it from a Postgres snapshot
+    /// if necessary. Returns `None` if recovery was interrupted by a stop signal.
+    ///
+    /// `recovery_pool` is taken by value to free up its connections after recovery (provided that it's not shared
+    /// with other components).
+    pub(crate) async fn ensure_ready(
+        self,
+        config: &TreeRecoveryConfig,
+        main_pool: &ConnectionPool<Core>,
+        recovery_pool: ConnectionPool<Core>,
+        health_updater: &HealthUpdater,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<AsyncMerkleTree>> {
+        let started_at = Instant::now();
+        let (tree, init_params) = match self {
+            Self::Ready(tree) => return Ok(Some(tree)),
+            Self::Recovering(tree) => {
+                let params = InitParameters::new(main_pool, config).await?.context(
+                    "Merkle tree is recovering, but Postgres doesn't contain snapshot recovery information",
+                )?;
+                let recovered_version = tree.recovered_version();
+                anyhow::ensure!(
+                    u64::from(params.l1_batch.0) == recovered_version,
+                    "Snapshot L1 batch in Postgres ({params:?}) differs from the recovered Merkle tree version \
+                     ({recovered_version})"
+                );
+                tracing::info!("Resuming tree recovery with status: {params:?}");
+                (tree, params)
+            }
+            Self::Empty(db) => {
+                if let Some(params) = InitParameters::new(main_pool, config).await? {
+                    tracing::info!("Starting Merkle tree recovery with status {params:?}");
+                    let tree = AsyncTreeRecovery::new(db, params.l1_batch.0.into()).await?;
+                    (tree, params)
+                } else {
+                    // Start the tree from scratch. The genesis batch will be processed in `AsyncMerkleTree::ensure_consistency()`.
+                    return Ok(Some(AsyncMerkleTree::new(db).await?));
+                }
+            }
+        };
+
+        tracing::debug!(
+            "Obtained recovery init parameters: {init_params:?} based on recovery configuration {config:?}"
+        );
+        let recovery_options = RecoveryOptions {
+            chunk_count: init_params.chunk_count(),
+            concurrency_limit: recovery_pool.max_size() as usize,
+            events: Box::new(RecoveryHealthUpdater::new(health_updater)),
+        };
+        let tree = tree
+            .recover(init_params, recovery_options, &recovery_pool, stop_receiver)
+            .await?;
+        if tree.is_some() {
+            // Only report latency if recovery wasn't canceled
+            let elapsed = started_at.elapsed();
+            APP_METRICS.snapshot_recovery_latency[&SnapshotRecoveryStage::Tree].set(elapsed);
+            tracing::info!("Recovered Merkle tree from snapshot in {elapsed:?}");
+        }
+        Ok(tree)
+    }
+}
+
+impl AsyncTreeRecovery {
+    async fn recover(
+        mut self,
+        init_params: InitParameters,
+        mut options: RecoveryOptions<'_>,
+        pool: &ConnectionPool<Core>,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<AsyncMerkleTree>> {
+        let start_time = Instant::now();
+        let chunk_count = options.chunk_count;
+        let chunks: Vec<_> = (0..chunk_count)
+            .map(|chunk_id| uniform_hashed_keys_chunk(chunk_id, chunk_count))
+            .collect();
+        tracing::info!(
+            "Recovering Merkle tree from Postgres snapshot in {chunk_count} chunks with max concurrency {}. \
+             Be aware that enabling node pruning during recovery will probably result in a recovery error; always disable pruning \
+             until recovery is complete",
+            options.concurrency_limit
+        );
+
+        let mut storage = pool.connection_tagged("zk_os_tree_manager").await?;
+        let remaining_chunks = self
+            .filter_chunks(&mut storage, init_params.l2_block, &chunks)
+            .await?;
+        drop(storage);
+        options
+            .events
+            .recovery_started(chunk_count, chunk_count - remaining_chunks.len() as u64);
+        tracing::info!(
+            "Filtered recovered key chunks; {} / {chunk_count} chunks remaining",
+            remaining_chunks.len()
+        );
+
+        let tree = Mutex::new(self);
+        let semaphore = Semaphore::new(options.concurrency_limit);
+        let chunk_tasks = remaining_chunks.into_iter().map(|chunk| async {
+            let _permit = semaphore
+                .acquire()
+                .await
+                .context("semaphore is never closed")?;
+            if Self::recover_key_chunk(&tree, init_params.l2_block, chunk, pool, stop_receiver)
+                .await?
+            {
+                options.events.chunk_recovered();
+            }
+            anyhow::Ok(())
+        });
+        future::try_join_all(chunk_tasks).await?;
+
+        let mut tree = tree.into_inner();
+        if *stop_receiver.borrow() {
+            return Ok(None);
+        }
+
+        let finalize_latency = RECOVERY_METRICS.latency[&RecoveryStage::Finalize].start();
+        let actual_root_hash = tree.root_hash().await?;
+        if let Some(expected_root_hash) = init_params.expected_root_hash {
+            anyhow::ensure!(
+                actual_root_hash == expected_root_hash,
+                "Root hash of recovered tree {actual_root_hash:?} differs from expected root hash {expected_root_hash:?}"
+            );
+        }
+
+        // Check pruning info one last time before finalizing the tree.
+        let mut storage = pool.connection_tagged("zk_os_tree_manager").await?;
+        Self::check_pruning_info(&mut storage, init_params.l2_block).await?;
+        drop(storage);
+
+        let tree = tree.finalize().await?;
+        finalize_latency.observe();
+        tracing::info!(
+            "Tree recovery has finished, the recovery took {:?}! resuming normal tree operation",
+            start_time.elapsed()
+        );
+        Ok(Some(tree))
+    }
+
+    /// Filters out `key_chunks` for which recovery was successfully performed.
+    async fn filter_chunks(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        snapshot_l2_block: L2BlockNumber,
+        key_chunks: &[ops::RangeInclusive<H256>],
+    ) -> anyhow::Result<Vec<ops::RangeInclusive<H256>>> {
+        let chunk_starts_latency =
+            RECOVERY_METRICS.latency[&RecoveryStage::LoadChunkStarts].start();
+        let chunk_starts = storage
+            .storage_logs_dal()
+            .get_chunk_starts_for_l2_block(snapshot_l2_block, key_chunks)
+            .await?;
+        let chunk_starts_latency = chunk_starts_latency.observe();
+        tracing::debug!(
+            "Loaded start entries for {} chunks in {chunk_starts_latency:?}",
+            key_chunks.len()
+        );
+
+        let existing_starts = chunk_starts
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &start)| Some((i, start?)));
+        let start_keys = existing_starts
+            .clone()
+            .map(|(_, start_entry)| start_entry.key)
+            .collect();
+        let tree_indices = self.leaf_indices(start_keys).await?;
+
+        let mut output = vec![];
+        for (tree_index, (i, db_entry)) in tree_indices.into_iter().zip(existing_starts) {
+            let Some(tree_index) = tree_index else {
+                output.push(key_chunks[i].clone());
+                continue;
+            };
+            anyhow::ensure!(
+                tree_index == db_entry.leaf_index,
+                "Mismatch between entry for key {:?} in Postgres snapshot for L2 block #{snapshot_l2_block} \
+                 ({db_entry:?}) and leaf index in the tree ({tree_index}); the recovery procedure may be corrupted",
+                db_entry.key
+            );
+        }
+        Ok(output)
+    }
+
+    async fn check_pruning_info(
+        storage: &mut Connection<'_, Core>,
+        snapshot_l2_block: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        let pruning_info = storage.pruning_dal().get_pruning_info().await?;
+        if let Some(pruned) = pruning_info.last_hard_pruned {
+            anyhow::ensure!(
+                pruned.l2_block == snapshot_l2_block,
+                "Additional data was pruned compared to tree recovery L2 block #{snapshot_l2_block}: {pruning_info:?}. \
+                 Continuing recovery is impossible; to recover the tree, drop its RocksDB directory, stop pruning and restart recovery"
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns `Ok(true)` if the chunk was recovered, `Ok(false)` if the recovery process was interrupted.
+    async fn recover_key_chunk(
+        tree: &Mutex<Self>,
+        snapshot_l2_block: L2BlockNumber,
+        key_chunk: ops::RangeInclusive<H256>,
+        pool: &ConnectionPool<Core>,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<bool> {
+        let acquire_connection_latency =
+            RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::AcquireConnection].start();
+        let mut storage = pool.connection_tagged("zk_os_tree_manager").await?;
+        acquire_connection_latency.observe();
+
+        if *stop_receiver.borrow() {
+            return Ok(false);
+        }
+
+        let entries_latency =
+            RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::LoadEntries].start();
+        let all_entries = storage
+            .storage_logs_dal()
+            .get_tree_entries_for_l2_block(snapshot_l2_block, key_chunk.clone())
+            .await?;
+        Self::check_pruning_info(&mut storage, snapshot_l2_block).await?;
+        drop(storage);
+
+        let entries_latency = entries_latency.observe();
+        tracing::debug!(
+            "Loaded {} entries for chunk {key_chunk:?} in {entries_latency:?}",
+            all_entries.len()
+        );
+
+        if *stop_receiver.borrow() {
+            return Ok(false);
+        }
+
+        // Entries are sorted by the hashed key, and the tree checks that keys are strictly increasing, so there's
+        // no need to check for duplicate keys here.
+        let all_entries = all_entries
+            .into_iter()
+            .map(|entry| {
+                let tree_entry = TreeEntry {
+                    key: entry.key,
+                    value: entry.value,
+                };
+                (entry.leaf_index, tree_entry)
+            })
+            .collect();
+        let lock_tree_latency =
+            RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::LockTree].start();
+        let mut tree = tree.lock().await;
+        lock_tree_latency.observe();
+
+        if *stop_receiver.borrow() {
+            return Ok(false);
+        }
+
+        let extend_tree_latency =
+            RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::ExtendTree].start();
+        tree.extend(all_entries).await?;
+        let extend_tree_latency = extend_tree_latency.observe();
+        tracing::debug!(
+            "Extended Merkle tree with entries for chunk {key_chunk:?} in {extend_tree_latency:?}"
+        );
+        Ok(true)
+    }
+}
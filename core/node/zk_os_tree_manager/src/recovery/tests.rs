@@ -0,0 +1,254 @@
+//! Tests for ZK OS Merkle tree recovery.
+
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use tempfile::TempDir;
+use test_casing::test_casing;
+use zksync_health_check::ReactiveHealthCheck;
+use zksync_node_test_utils::generate_storage_logs;
+use zksync_types::{snapshots::SnapshotRecoveryStatus, ProtocolVersionId};
+
+use super::*;
+use crate::{
+    helpers::create_db,
+    tests::{
+        assert_leaf_indices, expected_tree_hash, insert_genesis, insert_l1_batch, mock_config,
+        setup_tree_manager,
+    },
+    TreeManagerConfig,
+};
+
+const SNAPSHOT_L1_BATCH: L1BatchNumber = L1BatchNumber(3);
+
+/// Event listener that sends a stop signal after the specified number of recovered chunks.
+#[derive(Debug)]
+struct TestEventListener {
+    stop_sender: watch::Sender<bool>,
+    stop_threshold: u64,
+    initially_recovered_chunk_count: AtomicU64,
+    processed_chunk_count: AtomicU64,
+}
+
+impl TestEventListener {
+    fn new(stop_sender: watch::Sender<bool>, stop_threshold: u64) -> Self {
+        Self {
+            stop_sender,
+            stop_threshold,
+            initially_recovered_chunk_count: AtomicU64::new(0),
+            processed_chunk_count: AtomicU64::new(0),
+        }
+    }
+}
+
+impl HandleRecoveryEvent for Arc<TestEventListener> {
+    fn recovery_started(&mut self, _chunk_count: u64, recovered_chunk_count: u64) {
+        self.initially_recovered_chunk_count
+            .store(recovered_chunk_count, Ordering::SeqCst);
+    }
+
+    fn chunk_recovered(&self) {
+        let processed_chunk_count = self.processed_chunk_count.fetch_add(1, Ordering::SeqCst) + 1;
+        if processed_chunk_count >= self.stop_threshold {
+            self.stop_sender.send_replace(true);
+        }
+    }
+}
+
+#[test]
+fn calculating_chunk_count() {
+    let mut params = InitParameters {
+        l1_batch: L1BatchNumber(1),
+        l2_block: L2BlockNumber(1),
+        log_count: 160_000_000,
+        expected_root_hash: Some(H256::zero()),
+        desired_chunk_size: 200_000,
+    };
+    assert_eq!(params.chunk_count(), 800);
+
+    params.log_count += 1;
+    assert_eq!(params.chunk_count(), 801);
+
+    params.log_count = 100;
+    assert_eq!(params.chunk_count(), 1);
+}
+
+/// Fills Postgres with several L1 batches and marks the last of them as recovered from a snapshot,
+/// with the specified root hash (or the correct root hash if not specified).
+async fn prepare_postgres(
+    pool: &ConnectionPool<Core>,
+    root_hash: Option<H256>,
+) -> SnapshotRecoveryStatus {
+    let mut conn = pool.connection().await.unwrap();
+    insert_genesis(&mut conn).await;
+    let all_storage_logs = generate_storage_logs(100..160);
+    for storage_logs in all_storage_logs.chunks(20) {
+        insert_l1_batch(&mut conn, storage_logs).await;
+    }
+
+    let l1_batch_root_hash = match root_hash {
+        Some(hash) => hash,
+        None => expected_tree_hash(&mut conn).await,
+    };
+    let status = SnapshotRecoveryStatus {
+        l1_batch_number: SNAPSHOT_L1_BATCH,
+        l1_batch_root_hash,
+        l1_batch_timestamp: SNAPSHOT_L1_BATCH.0.into(),
+        l2_block_number: L2BlockNumber(SNAPSHOT_L1_BATCH.0),
+        l2_block_hash: H256::zero(),
+        l2_block_timestamp: SNAPSHOT_L1_BATCH.0.into(),
+        protocol_version: ProtocolVersionId::latest(),
+        storage_logs_chunks_processed: vec![true],
+    };
+    conn.snapshot_recovery_dal()
+        .insert_initial_recovery_status(&status)
+        .await
+        .unwrap();
+    status
+}
+
+#[test_casing(3, [7, 50, 1_000])]
+#[tokio::test]
+async fn recovery_workflow(desired_chunk_size: u64) {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed to get temporary directory for RocksDB");
+    let snapshot = prepare_postgres(&pool, None).await;
+
+    let config = TreeManagerConfig {
+        recovery: TreeRecoveryConfig { desired_chunk_size },
+        ..mock_config(temp_dir.path())
+    };
+    let tree_manager = TreeManager::new(config, pool.clone());
+    let tree_reader = tree_manager.tree_reader();
+    let mut batches_subscriber = tree_manager.subscribe_to_l1_batches();
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    tokio::spawn(tree_manager.run(stop_receiver));
+
+    let tree_reader = tree_reader.wait().await.unwrap();
+    let tree_info = tree_reader.clone().info().await.unwrap();
+    assert_eq!(tree_info.next_version, u64::from(SNAPSHOT_L1_BATCH.0) + 1);
+    assert_eq!(tree_info.min_version, Some(tree_info.next_version - 1));
+    assert_eq!(tree_info.root_hash, snapshot.l1_batch_root_hash);
+    let mut conn = pool.connection().await.unwrap();
+    assert_leaf_indices(&mut conn, tree_reader.clone(), &tree_info).await;
+
+    // Check that the recovered tree continues processing L1 batches.
+    insert_l1_batch(&mut conn, &generate_storage_logs(200..220)).await;
+    batches_subscriber
+        .wait_for(|&batch| batch == SNAPSHOT_L1_BATCH + 2)
+        .await
+        .unwrap();
+    let tree_info = tree_reader.clone().info().await.unwrap();
+    assert_eq!(tree_info.root_hash, expected_tree_hash(&mut conn).await);
+    assert_leaf_indices(&mut conn, tree_reader, &tree_info).await;
+}
+
+#[tokio::test]
+async fn resuming_recovery() {
+    const STOP_THRESHOLD: u64 = 3;
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed to get temporary directory for RocksDB");
+    let snapshot = prepare_postgres(&pool, None).await;
+    let config = TreeRecoveryConfig {
+        desired_chunk_size: 10,
+    };
+    let init_params = InitParameters::new(&pool, &config)
+        .await
+        .unwrap()
+        .expect("no recovery params");
+    let chunk_count = init_params.chunk_count();
+    assert!(chunk_count > STOP_THRESHOLD * 2, "{init_params:?}");
+
+    let db = create_db(mock_config(temp_dir.path())).await.unwrap();
+    let GenericAsyncTree::Empty(db) = GenericAsyncTree::new(db).await.unwrap() else {
+        panic!("unexpected tree state");
+    };
+    let tree = AsyncTreeRecovery::new(db, init_params.l1_batch.0.into())
+        .await
+        .unwrap();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let listener = Arc::new(TestEventListener::new(stop_sender, STOP_THRESHOLD));
+    let options = RecoveryOptions {
+        chunk_count,
+        concurrency_limit: 1,
+        events: Box::new(listener.clone()),
+    };
+    let tree = tree
+        .recover(init_params, options, &pool, &stop_receiver)
+        .await
+        .unwrap();
+    assert!(tree.is_none());
+    assert_eq!(
+        listener.processed_chunk_count.load(Ordering::SeqCst),
+        STOP_THRESHOLD
+    );
+
+    // Resume recovery from the same RocksDB instance.
+    let db = create_db(mock_config(temp_dir.path())).await.unwrap();
+    let tree = GenericAsyncTree::new(db).await.unwrap();
+    let tree = assert_matches!(tree, GenericAsyncTree::Recovering(tree) => tree);
+    assert_eq!(tree.recovered_version(), SNAPSHOT_L1_BATCH.0.into());
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let listener = Arc::new(TestEventListener::new(stop_sender, u64::MAX));
+    let options = RecoveryOptions {
+        chunk_count,
+        concurrency_limit: 4,
+        events: Box::new(listener.clone()),
+    };
+    let tree = tree
+        .recover(init_params, options, &pool, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("recovery was interrupted");
+
+    let initially_recovered_chunk_count = listener
+        .initially_recovered_chunk_count
+        .load(Ordering::SeqCst);
+    assert!(initially_recovered_chunk_count >= STOP_THRESHOLD);
+    assert_eq!(
+        initially_recovered_chunk_count + listener.processed_chunk_count.load(Ordering::SeqCst),
+        chunk_count
+    );
+
+    let tree_info = tree.reader().info().await.unwrap();
+    assert_eq!(tree_info.root_hash, snapshot.l1_batch_root_hash);
+    assert_eq!(tree_info.next_version, u64::from(SNAPSHOT_L1_BATCH.0) + 1);
+}
+
+#[tokio::test]
+async fn recovery_fails_on_root_hash_mismatch() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed to get temporary directory for RocksDB");
+    prepare_postgres(&pool, Some(H256::repeat_byte(1))).await;
+
+    let db = create_db(mock_config(temp_dir.path())).await.unwrap();
+    let tree = GenericAsyncTree::new(db).await.unwrap();
+    let (_, health_updater) = ReactiveHealthCheck::new("tree");
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = tree
+        .ensure_ready(
+            &TreeRecoveryConfig::default(),
+            &pool,
+            pool.clone(),
+            &health_updater,
+            &stop_receiver,
+        )
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("differs from expected root hash"), "{err}");
+}
+
+#[tokio::test]
+async fn tree_is_not_recovered_without_snapshot() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed to get temporary directory for RocksDB");
+    setup_tree_manager(temp_dir.path(), pool.clone()).await;
+
+    let params = InitParameters::new(&pool, &TreeRecoveryConfig::default())
+        .await
+        .unwrap();
+    assert!(params.is_none(), "{params:?}");
+}
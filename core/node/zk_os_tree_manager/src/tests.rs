@@ -15,13 +15,18 @@ use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_node_test_utils::{
     create_l1_batch, create_l2_block, generate_storage_logs, insert_initial_writes_for_batch,
 };
-use zksync_types::{L1BatchNumber, StorageLog, H256};
+use zksync_types::{L1BatchNumber, L2BlockNumber, StorageLog, H256};
 
 use super::*;
 use crate::{batch::L1BatchWithLogs, health::MerkleTreeInfo};
 
 pub(crate) async fn setup_tree_manager(db_path: &Path, pool: ConnectionPool<Core>) -> TreeManager {
     let mut conn = pool.connection().await.unwrap();
+    insert_genesis(&mut conn).await;
+    TreeManager::new(mock_config(db_path), pool)
+}
+
+pub(crate) async fn insert_genesis(conn: &mut Connection<'_, Core>) {
     if conn.blocks_dal().is_genesis_needed().await.unwrap() {
         // Insert the max tree guard, so that leaf indices are assigned correctly for further keys.
         // The min guard is not inserted because `insert_initial_writes()` starts enum indices from 1.
@@ -31,12 +36,14 @@ pub(crate) async fn setup_tree_manager(db_path: &Path, pool: ConnectionPool<Core
             .await
             .unwrap();
 
-        insert_genesis_batch(&mut conn, &GenesisParams::mock())
+        insert_genesis_batch(conn, &GenesisParams::mock())
             .await
             .unwrap();
     }
+}
 
-    let config = TreeManagerConfig {
+pub(crate) fn mock_config(db_path: &Path) -> TreeManagerConfig {
+    TreeManagerConfig {
         db_path: db_path.to_owned(),
         max_open_files: None,
         delay_interval: Duration::from_millis(10),
@@ -44,11 +51,12 @@ pub(crate) async fn setup_tree_manager(db_path: &Path, pool: ConnectionPool<Core
         multi_get_chunk_size: 500,
         block_cache_capacity: 16 << 20,
         include_indices_and_filters_in_block_cache: false,
-    };
-    TreeManager::new(config, pool)
+        repair_on_startup: false,
+        recovery: TreeRecoveryConfig::default(),
+    }
 }
 
-async fn insert_l1_batch(conn: &mut Connection<'_, Core>, storage_logs: &[StorageLog]) {
+pub(crate) async fn insert_l1_batch(conn: &mut Connection<'_, Core>, storage_logs: &[StorageLog]) {
     let mut conn = conn.start_transaction().await.unwrap();
     let l1_batch_number = conn
         .blocks_dal()
@@ -79,7 +87,7 @@ async fn insert_l1_batch(conn: &mut Connection<'_, Core>, storage_logs: &[Storag
     conn.commit().await.unwrap();
 }
 
-async fn expected_tree_hash(conn: &mut Connection<'_, Core>) -> H256 {
+pub(crate) async fn expected_tree_hash(conn: &mut Connection<'_, Core>) -> H256 {
     let processed_l1_batch_number = conn
         .blocks_dal()
         .get_sealed_l1_batch_number()
@@ -144,7 +152,7 @@ async fn genesis_creation() {
 }
 
 /// Checks that leaf indices are assigned identically in the tree and Postgres.
-async fn assert_leaf_indices(
+pub(crate) async fn assert_leaf_indices(
     conn: &mut Connection<'_, Core>,
     tree_reader: AsyncTreeReader,
     current_tree_info: &MerkleTreeInfo,
@@ -298,3 +306,69 @@ async fn workflow_with_multiple_batches(sleep_between_batches: bool) {
 
     assert_leaf_indices(&mut conn, tree_reader, &tree_info).await;
 }
+
+#[test_casing(2, [0, 2])]
+#[tokio::test]
+async fn tree_pruning_workflow(retained_l1_batches: u32) {
+    const BATCH_COUNT: usize = 5;
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed to get temporary directory for RocksDB");
+
+    let mut tree_manager = setup_tree_manager(temp_dir.path(), pool.clone()).await;
+    let pruning_task = tree_manager
+        .pruning_task(POLL_INTERVAL)
+        .with_retained_l1_batches(retained_l1_batches);
+    let mut pruning_health_check = pruning_task.health_check();
+    let tree_reader = tree_manager.tree_reader();
+    let mut batches_subscriber = tree_manager.subscribe_to_l1_batches();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let manager_task = tokio::spawn(tree_manager.run(stop_receiver.clone()));
+    let pruning_task = tokio::spawn(pruning_task.run(stop_receiver));
+
+    let mut conn = pool.connection().await.unwrap();
+    let all_storage_logs = generate_storage_logs(100..200);
+    for storage_logs in all_storage_logs.chunks(all_storage_logs.len() / BATCH_COUNT) {
+        insert_l1_batch(&mut conn, storage_logs).await;
+    }
+    batches_subscriber
+        .wait_for(|&batch| batch == L1BatchNumber(BATCH_COUNT as u32) + 1)
+        .await
+        .unwrap();
+    pruning_health_check
+        .wait_for(|health| matches!(health.status(), HealthStatus::Ready))
+        .await;
+
+    let tree_reader = tree_reader.wait().await.unwrap();
+    let tree_info = tree_reader.clone().info().await.unwrap();
+    assert_eq!(tree_info.min_version, Some(0));
+
+    conn.pruning_dal()
+        .insert_hard_pruning_log(L1BatchNumber(3), L2BlockNumber(3), H256::zero())
+        .await
+        .unwrap();
+    let expected_min_version = 4 - u64::from(retained_l1_batches);
+    loop {
+        let tree_info = tree_reader.clone().info().await.unwrap();
+        if tree_info.min_version == Some(expected_min_version) {
+            break;
+        }
+        assert!(tree_info.min_version < Some(expected_min_version));
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    // Check that the retained tree versions are still accessible.
+    let tree_info = tree_reader.clone().info().await.unwrap();
+    assert_eq!(tree_info.next_version, BATCH_COUNT as u64 + 1);
+    assert_eq!(tree_info.root_hash, expected_tree_hash(&mut conn).await);
+    assert_leaf_indices(&mut conn, tree_reader.clone(), &tree_info).await;
+    tree_reader
+        .prove(expected_min_version, vec![H256::repeat_byte(1)])
+        .await
+        .unwrap();
+
+    stop_sender.send_replace(true);
+    manager_task.await.unwrap().unwrap();
+    pruning_task.await.unwrap().unwrap();
+}
@@ -224,7 +224,8 @@ impl AsyncMerkleTree {
 
         anyhow::ensure!(
             earliest_l1_batch == L1BatchNumber(0),
-            "Non-zero earliest L1 batch #{earliest_l1_batch} is not supported, and tree recovery isn't supported either"
+            "Merkle tree is empty, but the earliest L1 batch in Postgres is #{earliest_l1_batch}; the tree should have been \
+             recovered from a snapshot"
         );
         let batch = L1BatchWithLogs::new(storage, earliest_l1_batch)
             .await
@@ -18,6 +18,7 @@ use zksync_core_leftovers::temp_config_store::{load_database_secrets, load_gener
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_prover_dal::{ConnectionPool, Prover};
 use zksync_prover_fri_types::PROVER_PROTOCOL_SEMANTIC_VERSION;
+use zksync_prover_fri_utils::artifact_integrity::IntegrityCheckedObjectStore;
 use zksync_prover_keystore::keystore::Keystore;
 use zksync_task_management::ManagedTasks;
 use zksync_vlog::prometheus::PrometheusExporterConfig;
@@ -192,8 +193,9 @@ async fn load_resources(
     let database_url = database_secrets
         .prover_url
         .context("no prover DB URl present")?;
-    // 2 connections for the witness vector generator job pickers (1 each) and 1 for gpu circuit prover job saver
-    let max_connections = 3;
+    // 2 connections for the witness vector generator job pickers (1 each) and 1 for gpu circuit prover job saver,
+    // plus 2 connections for checking and recording artifact hashes.
+    let max_connections = 5;
     let connection_pool = ConnectionPool::<Prover>::builder(database_url, max_connections)
         .build()
         .await
@@ -203,6 +205,10 @@ async fn load_resources(
         .create_store()
         .await
         .context("failed to create object store")?;
+    let object_store: Arc<dyn ObjectStore> = Arc::new(IntegrityCheckedObjectStore::new(
+        object_store,
+        connection_pool.clone(),
+    ));
 
     let prover_context = match max_gpu_vram_allocation {
         Some(max_allocation) => ProverContext::create_with_config(
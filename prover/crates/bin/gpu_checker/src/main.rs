@@ -213,6 +213,7 @@ async fn main() -> anyhow::Result<()> {
         opentelemetry: None,
         log_format: "json".to_string(),
         log_directives: None,
+        log_sampling: None,
     };
     let _observability_guard = observability_config
         .install()
@@ -1,7 +1,7 @@
 #![allow(incomplete_features)] // We have to use generic const exprs.
 #![feature(generic_const_exprs)]
 
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use clap::Parser;
@@ -9,9 +9,10 @@ use tokio::sync::{oneshot, watch};
 use zksync_config::configs::FriProofCompressorConfig;
 use zksync_core_leftovers::temp_config_store::{load_database_secrets, load_general_config};
 use zksync_env_config::object_store::ProverObjectStoreConfig;
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_prover_dal::{ConnectionPool, Prover, ProverDal};
 use zksync_prover_fri_types::PROVER_PROTOCOL_SEMANTIC_VERSION;
+use zksync_prover_fri_utils::artifact_integrity::IntegrityCheckedObjectStore;
 use zksync_prover_keystore::keystore::Keystore;
 use zksync_queued_job_processor::JobProcessor;
 use zksync_task_management::ManagedTasks;
@@ -58,7 +59,8 @@ async fn main() -> anyhow::Result<()> {
     let config = general_config
         .proof_compressor_config
         .context("FriProofCompressorConfig")?;
-    let pool = ConnectionPool::<Prover>::singleton(database_secrets.prover_url()?)
+    // The second connection is used for artifact integrity checks while the job processor holds the first one.
+    let pool = ConnectionPool::<Prover>::builder(database_secrets.prover_url()?, 2)
         .build()
         .await
         .context("failed to build a connection pool")?;
@@ -73,6 +75,8 @@ async fn main() -> anyhow::Result<()> {
     let blob_store = ObjectStoreFactory::new(object_store_config.0)
         .create_store()
         .await?;
+    let blob_store: Arc<dyn ObjectStore> =
+        Arc::new(IntegrityCheckedObjectStore::new(blob_store, pool.clone()));
 
     let protocol_version = PROVER_PROTOCOL_SEMANTIC_VERSION;
 
@@ -1,4 +1,4 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 use anyhow::Context as _;
 use axum::{
@@ -12,10 +12,21 @@ use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use tokio::sync::watch;
 
-use crate::{
-    cluster_types::{Cluster, DeploymentName, NamespaceName},
-    k8s::{Scaler, Watcher},
-};
+use crate::cluster_types::{Cluster, DeploymentName, NamespaceName};
+
+/// Backend used by the Agent to watch and scale deployments in a cluster.
+#[async_trait::async_trait]
+pub trait AgentBackend: Send + Sync {
+    /// Returns the current state of the cluster.
+    async fn cluster(&self) -> anyhow::Result<Cluster>;
+    /// Scales the deployment to the specified number of replicas.
+    async fn scale(
+        &self,
+        namespace: &NamespaceName,
+        name: &DeploymentName,
+        size: usize,
+    ) -> anyhow::Result<()>;
+}
 
 struct AppError(anyhow::Error);
 
@@ -31,13 +42,12 @@ impl IntoResponse for AppError {
 
 pub async fn run_server(
     port: u16,
-    watcher: Watcher,
-    scaler: Scaler,
+    backend: Arc<dyn AgentBackend>,
     mut stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let bind_address = SocketAddr::from(([0, 0, 0, 0], port));
     tracing::debug!("Starting Autoscaler agent on {bind_address}");
-    let app = create_agent_router(watcher, scaler);
+    let app = create_agent_router(backend);
 
     let listener = tokio::net::TcpListener::bind(bind_address)
         .await
@@ -57,8 +67,8 @@ pub async fn run_server(
     Ok(())
 }
 
-fn create_agent_router(watcher: Watcher, scaler: Scaler) -> Router {
-    let app = App { watcher, scaler };
+fn create_agent_router(backend: Arc<dyn AgentBackend>) -> Router {
+    let app = App { backend };
     Router::new()
         .route("/healthz", get(health))
         .route("/cluster", get(get_cluster))
@@ -75,12 +85,11 @@ async fn health() -> &'static str {
 
 #[derive(Clone)]
 struct App {
-    watcher: Watcher,
-    scaler: Scaler,
+    backend: Arc<dyn AgentBackend>,
 }
 
 async fn get_cluster(State(app): State<App>) -> Result<Json<Cluster>, AppError> {
-    let cluster = app.watcher.cluster.lock().await.clone();
+    let cluster = app.backend.cluster().await.map_err(AppError)?;
     Ok(Json(cluster))
 }
 
@@ -111,9 +120,9 @@ async fn scale(
         .deployments
         .into_iter()
         .map(|d| {
-            let s = app.scaler.clone();
+            let backend = app.backend.clone();
             tokio::spawn(async move {
-                match s.scale(&d.namespace, &d.name, d.size).await {
+                match backend.scale(&d.namespace, &d.name, d.size).await {
                     Ok(()) => "".to_string(),
                     Err(err) => err.to_string(),
                 }
@@ -22,6 +22,9 @@ pub struct ProverAutoscalerConfig {
     pub graceful_shutdown_timeout: Duration,
     pub agent_config: Option<ProverAutoscalerAgentConfig>,
     pub scaler_config: Option<ProverAutoscalerScalerConfig>,
+    /// Parameters of the offline Scaler simulation. Only used by the simulator.
+    #[serde(default)]
+    pub simulation_config: ProverAutoscalerSimulationConfig,
     pub observability: Option<ObservabilityConfig>,
 }
 
@@ -37,6 +40,45 @@ pub struct ProverAutoscalerAgentConfig {
     /// If dry-run enabled don't do any k8s updates, just report success.
     #[serde(default = "ProverAutoscalerAgentConfig::default_dry_run")]
     pub dry_run: bool,
+    /// Backend to watch and scale deployments with.
+    #[serde(default)]
+    pub backend: AgentBackendConfig,
+    /// The interval between polls of worker group states. Not used by the Kubernetes backend.
+    #[serde(
+        with = "humantime_serde",
+        default = "ProverAutoscalerAgentConfig::default_poll_interval"
+    )]
+    pub poll_interval: Duration,
+}
+
+/// Backend used by Agent to watch and scale deployments.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentBackendConfig {
+    /// Kubernetes Deployments in the current cluster.
+    #[default]
+    Kubernetes,
+    /// Worker groups (e.g. docker-compose services or systemd units) managed by external commands.
+    Command {
+        /// Command printing worker group states as JSON.
+        status_command: Vec<String>,
+        /// Command scaling a worker group. Namespace, worker group name and the number of replicas are appended
+        /// as arguments.
+        scale_command: Vec<String>,
+        /// Time limit for a single command run; the command is killed if it doesn't finish in time.
+        #[serde(
+            with = "humantime_serde",
+            default = "AgentBackendConfig::default_command_timeout"
+        )]
+        command_timeout: Duration,
+    },
+    /// Worker groups managed by an external HTTP service.
+    Http {
+        /// URL returning worker group states as JSON on GET.
+        status_url: String,
+        /// URL accepting scale requests for a single worker group on POST.
+        scale_url: String,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -86,6 +128,19 @@ pub struct ProverAutoscalerScalerConfig {
     pub dry_run: bool,
 }
 
+/// Config used for replaying recorded queue reports against `scaler_config` offline.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ProverAutoscalerSimulationConfig {
+    /// Cost of running a single replica for an hour, per deployment. Deployments not listed here cost 1 per hour,
+    /// so that by default cost is measured in replica-hours.
+    #[serde(default)]
+    pub replica_hourly_cost: HashMap<DeploymentName, f64>,
+    /// Number of jobs a single replica processes per hour, per scaler target deployment. Used to estimate queue
+    /// latency; latency isn't reported for targets not listed here.
+    #[serde(default)]
+    pub replica_hourly_throughput: HashMap<DeploymentName, f64>,
+}
+
 // TODO: generate this enum by QueueReport from https://github.com/matter-labs/zksync-era/blob/main/prover/crates/bin/prover_job_monitor/src/autoscaler_queue_reporter.rs#L23
 // and remove allowing of non_camel_case_types by generating field name parser.
 #[derive(Debug, Display, PartialEq, Eq, Hash, Clone, Copy, Deserialize, EnumString, Default)]
@@ -172,6 +227,18 @@ impl ProverAutoscalerAgentConfig {
     pub fn default_dry_run() -> bool {
         true
     }
+
+    /// Default poll_interval -- 10s
+    pub fn default_poll_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+}
+
+impl AgentBackendConfig {
+    /// Default command_timeout -- 60s
+    pub fn default_command_timeout() -> Duration {
+        Duration::from_secs(60)
+    }
 }
 
 impl ProverAutoscalerScalerConfig {
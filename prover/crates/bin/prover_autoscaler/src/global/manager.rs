@@ -1,5 +1,7 @@
 use std::{collections::HashMap, sync::Arc};
 
+use chrono::Utc;
+
 use super::{
     queuer,
     scaler::{Scaler, ScalerConfig, ScalerTrait},
@@ -38,50 +40,8 @@ impl Manager {
                     .set(1);
             });
 
-        let mut scalers: Vec<Box<dyn ScalerTrait + Sync + Send>> = Vec::default();
-        let mut jobs = Vec::default();
-
-        let scaler_config = Arc::new(ScalerConfig {
-            cluster_priorities: config.cluster_priorities,
-            apply_min_to_namespace: config.apply_min_to_namespace,
-            long_pending_duration: chrono::Duration::seconds(
-                config.long_pending_duration.as_secs() as i64,
-            ),
-            scale_errors_duration: chrono::Duration::seconds(
-                config.scale_errors_duration.as_secs() as i64,
-            ),
-            need_to_move_duration: chrono::Duration::seconds(
-                config.need_to_move_duration.as_secs() as i64,
-            ),
-        });
-
-        for c in &config.scaler_targets {
-            jobs.push(c.queue_report_field);
-            match c.scaler_target_type {
-                ScalerTargetType::Gpu => scalers.push(Box::new(Scaler::<GpuKey>::new(
-                    c.queue_report_field,
-                    c.deployment.clone(),
-                    c.min_replicas,
-                    c.max_replicas
-                        .iter()
-                        .map(|(k, v)| (k.clone(), v.into_map_gpukey()))
-                        .collect(),
-                    c.speed.into_map_gpukey(),
-                    scaler_config.clone(),
-                ))),
-                ScalerTargetType::Simple => scalers.push(Box::new(Scaler::<NoKey>::new(
-                    c.queue_report_field,
-                    c.deployment.clone(),
-                    c.min_replicas,
-                    c.max_replicas
-                        .iter()
-                        .map(|(k, v)| (k.clone(), v.into_map_nokey()))
-                        .collect(),
-                    c.speed.into_map_nokey(),
-                    scaler_config.clone(),
-                ))),
-            };
-        }
+        let scalers = create_scalers(&config);
+        let jobs = scalers.iter().map(|s| s.queue_report_field()).collect();
         Self {
             namespaces: config.protocol_versions.clone(),
             watcher,
@@ -92,6 +52,55 @@ impl Manager {
     }
 }
 
+/// Creates scalers for all targets in the config.
+pub(crate) fn create_scalers(
+    config: &ProverAutoscalerScalerConfig,
+) -> Vec<Box<dyn ScalerTrait + Sync + Send>> {
+    let mut scalers: Vec<Box<dyn ScalerTrait + Sync + Send>> = Vec::default();
+
+    let scaler_config = Arc::new(ScalerConfig {
+        cluster_priorities: config.cluster_priorities.clone(),
+        apply_min_to_namespace: config.apply_min_to_namespace.clone(),
+        long_pending_duration: chrono::Duration::seconds(
+            config.long_pending_duration.as_secs() as i64
+        ),
+        scale_errors_duration: chrono::Duration::seconds(
+            config.scale_errors_duration.as_secs() as i64
+        ),
+        need_to_move_duration: chrono::Duration::seconds(
+            config.need_to_move_duration.as_secs() as i64
+        ),
+    });
+
+    for c in &config.scaler_targets {
+        match c.scaler_target_type {
+            ScalerTargetType::Gpu => scalers.push(Box::new(Scaler::<GpuKey>::new(
+                c.queue_report_field,
+                c.deployment.clone(),
+                c.min_replicas,
+                c.max_replicas
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.into_map_gpukey()))
+                    .collect(),
+                c.speed.into_map_gpukey(),
+                scaler_config.clone(),
+            ))),
+            ScalerTargetType::Simple => scalers.push(Box::new(Scaler::<NoKey>::new(
+                c.queue_report_field,
+                c.deployment.clone(),
+                c.min_replicas,
+                c.max_replicas
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.into_map_nokey()))
+                    .collect(),
+                c.speed.into_map_nokey(),
+                scaler_config.clone(),
+            ))),
+        };
+    }
+    scalers
+}
+
 #[async_trait::async_trait]
 impl Task for Manager {
     async fn invoke(&self) -> anyhow::Result<()> {
@@ -107,6 +116,7 @@ impl Task for Manager {
                 return Ok(());
             }
 
+            let now = Utc::now();
             for (ns, ppv) in &self.namespaces {
                 for scaler in &self.scalers {
                     let q = queue
@@ -118,7 +128,7 @@ impl Task for Manager {
                         "Running eval for namespace {ns}, PPV {ppv}, scaler {} found queue {q}",
                         scaler.deployment()
                     );
-                    scaler.run(ns, q, &guard.clusters, &mut scale_requests);
+                    scaler.run(ns, q, &guard.clusters, now, &mut scale_requests);
                 }
             }
         } // Unlock self.watcher.data.
@@ -1,4 +1,5 @@
 pub mod manager;
 pub mod queuer;
 pub mod scaler;
+pub mod simulator;
 pub mod watcher;
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, fmt::Debug, hash::Hash, str::FromStr, sync::Arc};
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use debug_map_sorted::SortedOutputExt;
 
 use crate::{
@@ -82,7 +82,12 @@ impl<K: Key> Scaler<K> {
         }
     }
 
-    fn convert_to_pool(&self, namespace: &NamespaceName, cluster: &Cluster) -> Vec<Pool<K>> {
+    fn convert_to_pool(
+        &self,
+        namespace: &NamespaceName,
+        cluster: &Cluster,
+        now: DateTime<Utc>,
+    ) -> Vec<Pool<K>> {
         let Some(namespace_value) = &cluster.namespaces.get(namespace) else {
             // No namespace in config, ignoring.
             return vec![];
@@ -106,7 +111,7 @@ impl<K: Key> Scaler<K> {
                 scale_errors: namespace_value
                     .scale_errors
                     .iter()
-                    .filter(|v| v.time > Utc::now() - self.config.scale_errors_duration)
+                    .filter(|v| v.time > now - self.config.scale_errors_duration)
                     .count(),
                 ..Default::default()
             });
@@ -118,7 +123,7 @@ impl<K: Key> Scaler<K> {
         let need_to_move_errors = namespace_value
             .scale_errors
             .iter()
-            .filter(|v| v.time > Utc::now() - self.config.need_to_move_duration)
+            .filter(|v| v.time > now - self.config.need_to_move_duration)
             .count();
 
         for (pod, pod_value) in namespace_value.pods.iter() {
@@ -133,7 +138,7 @@ impl<K: Key> Scaler<K> {
             });
             let mut status = PodStatus::from_str(&pod_value.status).unwrap_or_default();
             if status == PodStatus::Pending {
-                if pod_value.changed < Utc::now() - self.config.long_pending_duration {
+                if pod_value.changed < now - self.config.long_pending_duration {
                     status = PodStatus::LongPending;
                 } else if need_to_move_errors > 0 {
                     status = PodStatus::NeedToMove;
@@ -153,11 +158,16 @@ impl<K: Key> Scaler<K> {
         pool_map.into_values().collect()
     }
 
-    fn sorted_clusters(&self, namespace: &NamespaceName, clusters: &Clusters) -> Vec<Pool<K>> {
+    fn sorted_clusters(
+        &self,
+        namespace: &NamespaceName,
+        clusters: &Clusters,
+        now: DateTime<Utc>,
+    ) -> Vec<Pool<K>> {
         let mut pools: Vec<Pool<K>> = clusters
             .clusters
             .values()
-            .flat_map(|c| self.convert_to_pool(namespace, c))
+            .flat_map(|c| self.convert_to_pool(namespace, c, now))
             .collect();
 
         pools.sort_by(|a, b| {
@@ -209,13 +219,16 @@ impl<K: Key> Scaler<K> {
         (queue + speed - 1) / speed * speed
     }
 
+    /// Calculates the desired number of replicas for each pool. Pod states and scale errors are evaluated
+    /// relative to `now`.
     pub fn calculate(
         &self,
         namespace: &NamespaceName,
         queue: usize,
         clusters: &Clusters,
+        now: DateTime<Utc>,
     ) -> HashMap<PoolKey<K>, usize> {
-        let sorted_clusters = self.sorted_clusters(namespace, clusters);
+        let sorted_clusters = self.sorted_clusters(namespace, clusters, now);
         tracing::debug!(
             "Sorted clusters for namespace {}: {:?}",
             namespace,
@@ -354,8 +367,17 @@ pub trait ScalerTrait {
         namespace: &NamespaceName,
         queue: usize,
         clusters: &Clusters,
+        now: DateTime<Utc>,
         requests: &mut HashMap<ClusterName, ScaleRequest>,
     );
+    /// Calculates the desired number of replicas for each deployment without producing scale requests.
+    fn desired_replicas(
+        &self,
+        namespace: &NamespaceName,
+        queue: usize,
+        clusters: &Clusters,
+        now: DateTime<Utc>,
+    ) -> HashMap<(ClusterName, DeploymentName), usize>;
 }
 
 impl<K: Key> ScalerTrait for Scaler<K> {
@@ -371,9 +393,10 @@ impl<K: Key> ScalerTrait for Scaler<K> {
         namespace: &NamespaceName,
         queue: usize,
         clusters: &Clusters,
+        now: DateTime<Utc>,
         requests: &mut HashMap<ClusterName, ScaleRequest>,
     ) {
-        let replicas = self.calculate(namespace, queue, clusters);
+        let replicas = self.calculate(namespace, queue, clusters, now);
         for (k, num) in &replicas {
             let labels = JobLabels {
                 job: self.deployment.clone(),
@@ -395,6 +418,22 @@ impl<K: Key> ScalerTrait for Scaler<K> {
         }
         self.diff(namespace, replicas, clusters, requests);
     }
+
+    fn desired_replicas(
+        &self,
+        namespace: &NamespaceName,
+        queue: usize,
+        clusters: &Clusters,
+        now: DateTime<Utc>,
+    ) -> HashMap<(ClusterName, DeploymentName), usize> {
+        self.calculate(namespace, queue, clusters, now)
+            .into_iter()
+            .map(|(PoolKey { cluster, key }, replicas)| {
+                let deployment = key.to_deployment(self.deployment.to_str());
+                ((cluster, deployment), replicas)
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -465,6 +504,7 @@ mod tests {
                     .into(),
                     ..Default::default()
                 },
+                Utc::now(),
             ),
             [(
                 PoolKey {
@@ -533,6 +573,7 @@ mod tests {
                     .into(),
                     ..Default::default()
                 },
+                Utc::now(),
             ),
             [
                 (
@@ -617,6 +658,7 @@ mod tests {
                     .into(),
                     ..Default::default()
                 },
+                Utc::now(),
             ),
             [
                 (
@@ -730,6 +772,7 @@ mod tests {
                     .into(),
                     ..Default::default()
                 },
+                Utc::now(),
             ),
             [
                 (
@@ -848,6 +891,7 @@ mod tests {
                     .into(),
                     ..Default::default()
                 },
+                Utc::now(),
             ),
             [
                 (
@@ -943,6 +987,7 @@ mod tests {
                     .into(),
                     ..Default::default()
                 },
+                Utc::now(),
             ),
             [(
                 PoolKey {
@@ -1011,6 +1056,7 @@ mod tests {
                     .into(),
                     ..Default::default()
                 },
+                Utc::now(),
             ),
             [
                 (
@@ -1044,6 +1090,7 @@ mod tests {
             [(GpuKey(Gpu::L4), 500)].into(),
             scaler_config("prover"),
         );
+        let now = Utc::now();
 
         let cluster = &Cluster {
             name: "foo".into(),
@@ -1063,7 +1110,7 @@ mod tests {
                             "circuit-prover-gpu-7c5f8fc747-12345".into(),
                             Pod {
                                 status: "Pending".into(),
-                                changed: Utc::now() - chrono::Duration::minutes(15),
+                                changed: now - chrono::Duration::minutes(15),
                                 ..Default::default()
                             },
                         ),
@@ -1071,7 +1118,7 @@ mod tests {
                             "circuit-prover-gpu-7c5f8fc747-12346".into(),
                             Pod {
                                 status: "Pending".into(),
-                                changed: Utc::now() - chrono::Duration::minutes(2),
+                                changed: now - chrono::Duration::minutes(2),
                                 ..Default::default()
                             },
                         ),
@@ -1079,14 +1126,14 @@ mod tests {
                     .into(),
                     scale_errors: vec![ScaleEvent {
                         name: "".into(),
-                        time: Utc::now() - chrono::Duration::minutes(1),
+                        time: now - chrono::Duration::minutes(1),
                     }],
                 },
             )]
             .into(),
         };
         assert_eq!(
-            scaler.convert_to_pool(&"prover".into(), cluster),
+            scaler.convert_to_pool(&"prover".into(), cluster, now),
             vec![Pool {
                 name: "foo".into(),
                 key: GpuKey(Gpu::L4),
@@ -1100,5 +1147,18 @@ mod tests {
                 max_pool_size: 100,
             }]
         );
+
+        // Evaluated later, e.g. when replaying recorded states, the scale error is outdated and all pending pods
+        // become long pending.
+        assert_eq!(
+            scaler.convert_to_pool(&"prover".into(), cluster, now + chrono::Duration::hours(1)),
+            vec![Pool {
+                name: "foo".into(),
+                key: GpuKey(Gpu::L4),
+                pods: [(PodStatus::LongPending, 2), (PodStatus::Running, 1)].into(),
+                scale_errors: 0,
+                max_pool_size: 100,
+            }]
+        );
     }
 }
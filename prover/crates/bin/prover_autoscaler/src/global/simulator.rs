@@ -0,0 +1,448 @@
+//! Offline simulation of the Scaler driven by recorded queue reports.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    time::Duration,
+};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_prover_job_monitor::autoscaler_queue_reporter::VersionedQueueReport;
+
+use super::{manager::create_scalers, queuer::queue_from_reports, scaler::ScalerTrait};
+use crate::{
+    cluster_types::{
+        Cluster, ClusterName, Clusters, DeploymentName, Namespace, NamespaceName, Pod,
+    },
+    config::{ProverAutoscalerScalerConfig, ProverAutoscalerSimulationConfig, QueueReportFields},
+};
+
+/// Recorded state at a certain point in time.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulationRecord {
+    pub time: DateTime<Utc>,
+    /// Queue report as returned by prover-job-monitor.
+    pub queue: Vec<VersionedQueueReport>,
+    /// Cluster states as returned by Agents. If not set, cluster states are derived from the previous Scaler
+    /// decisions. Must be set for the first record.
+    #[serde(default)]
+    pub clusters: Option<Vec<Cluster>>,
+}
+
+/// Reads records from a file with one JSON-encoded [`SimulationRecord`] per line.
+pub fn read_records(path: &Path) -> anyhow::Result<Vec<SimulationRecord>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).with_context(|| format!("invalid record on line {}", i + 1))
+        })
+        .collect()
+}
+
+/// Scaler decision for a single target in a single namespace.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SimulationStep {
+    pub time: DateTime<Utc>,
+    pub namespace: NamespaceName,
+    /// Deployment of the scaler target.
+    pub deployment: DeploymentName,
+    pub queue: usize,
+    /// Number of replicas per `{cluster}/{deployment}`.
+    pub replicas: BTreeMap<String, usize>,
+    pub total_replicas: usize,
+    /// Cost of running the replicas until the next record.
+    pub cost: f64,
+    /// Estimated time to process the queue with the chosen replicas. Not set if the throughput of the target
+    /// is not configured, or if there are no replicas to process a non-empty queue.
+    pub queue_latency_secs: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TargetSummary {
+    pub namespace: NamespaceName,
+    pub deployment: DeploymentName,
+    pub total_cost: f64,
+    pub average_replicas: f64,
+    pub max_replicas: usize,
+    pub max_queue_latency_secs: Option<f64>,
+    /// Number of steps with a non-empty queue and no replicas to process it.
+    pub unserved_steps: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimulationReport {
+    pub steps: Vec<SimulationStep>,
+    pub summary: Vec<TargetSummary>,
+}
+
+/// Replays recorded queue reports and cluster states against the Scaler config.
+///
+/// Simulated replicas start immediately and never fail to be scheduled; thus, Pending pods and scale errors
+/// can only come from recorded cluster states.
+pub struct Simulator {
+    /// Namespace to Protocol Version configuration.
+    namespaces: BTreeMap<NamespaceName, String>,
+    jobs: Vec<QueueReportFields>,
+    scalers: Vec<Box<dyn ScalerTrait + Sync + Send>>,
+    /// Duration of the last step, which cannot be derived from record times.
+    last_step_duration: Duration,
+    config: ProverAutoscalerSimulationConfig,
+}
+
+impl Simulator {
+    pub fn new(
+        scaler_config: &ProverAutoscalerScalerConfig,
+        config: ProverAutoscalerSimulationConfig,
+    ) -> Self {
+        let scalers = create_scalers(scaler_config);
+        Self {
+            namespaces: scaler_config
+                .protocol_versions
+                .iter()
+                .map(|(ns, ppv)| (ns.clone(), ppv.clone()))
+                .collect(),
+            jobs: scalers.iter().map(|s| s.queue_report_field()).collect(),
+            scalers,
+            last_step_duration: scaler_config.scaler_run_interval,
+            config,
+        }
+    }
+
+    pub fn run(&self, records: &[SimulationRecord]) -> anyhow::Result<SimulationReport> {
+        anyhow::ensure!(
+            records.first().is_some_and(|r| r.clusters.is_some()),
+            "the first record must contain cluster states"
+        );
+
+        let mut clusters = Clusters::default();
+        let mut steps = vec![];
+        for (i, record) in records.iter().enumerate() {
+            if let Some(recorded) = &record.clusters {
+                clusters = Clusters {
+                    clusters: recorded
+                        .iter()
+                        .map(|c| (c.name.clone(), c.clone()))
+                        .collect(),
+                    ..Default::default()
+                };
+            }
+            let step_duration = match records.get(i + 1) {
+                Some(next) => (next.time - record.time)
+                    .to_std()
+                    .context("records must be ordered by time")?,
+                None => self.last_step_duration,
+            };
+            let queue = queue_from_reports(&record.queue, &self.jobs);
+
+            // Calculate all decisions on the same state, as the Manager does.
+            let mut decisions = vec![];
+            for (ns, ppv) in &self.namespaces {
+                for scaler in &self.scalers {
+                    let q = queue
+                        .get(&(ppv.clone(), scaler.queue_report_field()))
+                        .copied()
+                        .unwrap_or(0);
+                    let replicas = scaler.desired_replicas(ns, q, &clusters, record.time);
+                    decisions.push((ns, scaler.deployment(), q, replicas));
+                }
+            }
+
+            for (ns, deployment, q, replicas) in decisions {
+                let replicas = apply_replicas(&mut clusters, ns, replicas, record.time);
+                steps.push(self.step(record.time, ns, deployment, q, replicas, step_duration));
+            }
+        }
+
+        Ok(SimulationReport {
+            summary: summarize(&steps),
+            steps,
+        })
+    }
+
+    fn step(
+        &self,
+        time: DateTime<Utc>,
+        namespace: &NamespaceName,
+        deployment: DeploymentName,
+        queue: usize,
+        replicas: BTreeMap<(ClusterName, DeploymentName), usize>,
+        duration: Duration,
+    ) -> SimulationStep {
+        let hours = duration.as_secs_f64() / 3600.0;
+        let cost: f64 = replicas
+            .iter()
+            .map(|((_, d), n)| {
+                let hourly_cost = self.config.replica_hourly_cost.get(d).unwrap_or(&1.0);
+                *n as f64 * hourly_cost * hours
+            })
+            .sum();
+        let total_replicas: usize = replicas.values().sum();
+        let queue_latency_secs = if queue == 0 {
+            Some(0.0)
+        } else {
+            self.config
+                .replica_hourly_throughput
+                .get(&deployment)
+                .filter(|_| total_replicas > 0)
+                .map(|throughput| queue as f64 * 3600.0 / (total_replicas as f64 * throughput))
+        };
+
+        SimulationStep {
+            time,
+            namespace: namespace.clone(),
+            deployment,
+            queue,
+            replicas: replicas
+                .into_iter()
+                .map(|((c, d), n)| (format!("{c}/{d}"), n))
+                .collect(),
+            total_replicas,
+            cost,
+            queue_latency_secs,
+        }
+    }
+}
+
+/// Returns the deployment owning the pod. Pod names start with the name of their deployment, so the longest
+/// matching deployment is chosen (e.g., `circuit-prover-gpu-t4` rather than `circuit-prover-gpu`).
+fn owning_deployment<'a>(namespace: &'a Namespace, pod: &str) -> Option<&'a DeploymentName> {
+    namespace
+        .deployments
+        .keys()
+        .filter(|d| {
+            pod.strip_prefix(d.to_str())
+                .is_some_and(|suffix| suffix.starts_with('-'))
+        })
+        .max_by_key(|d| d.to_str().len())
+}
+
+/// Scales deployments in the simulated cluster states. Like the Scaler, skips deployments missing in the clusters.
+/// Returns the applied number of replicas per deployment.
+fn apply_replicas(
+    clusters: &mut Clusters,
+    namespace: &NamespaceName,
+    replicas: HashMap<(ClusterName, DeploymentName), usize>,
+    time: DateTime<Utc>,
+) -> BTreeMap<(ClusterName, DeploymentName), usize> {
+    let mut applied = BTreeMap::new();
+    for ((cluster, deployment), n) in replicas {
+        let Some(ns) = clusters
+            .clusters
+            .get_mut(&cluster)
+            .and_then(|c| c.namespaces.get_mut(namespace))
+        else {
+            tracing::warn!("Namespace {namespace} is missing in cluster {cluster}");
+            continue;
+        };
+        let Some(state) = ns.deployments.get_mut(&deployment) else {
+            tracing::warn!(
+                "Deployment {deployment} is missing in cluster {cluster}, namespace {namespace}"
+            );
+            continue;
+        };
+        state.running = n;
+        state.desired = n;
+
+        let stale_pods: Vec<_> = ns
+            .pods
+            .keys()
+            .filter(|pod| owning_deployment(ns, pod) == Some(&deployment))
+            .cloned()
+            .collect();
+        for pod in stale_pods {
+            ns.pods.remove(&pod);
+        }
+        for i in 0..n {
+            let pod = Pod {
+                owner: format!("ReplicaSet/{deployment}-sim"),
+                status: "Running".into(),
+                changed: time,
+            };
+            ns.pods.insert(format!("{deployment}-sim-{i}"), pod);
+        }
+        applied.insert((cluster, deployment), n);
+    }
+    applied
+}
+
+fn summarize(steps: &[SimulationStep]) -> Vec<TargetSummary> {
+    let mut steps_by_target: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for step in steps {
+        steps_by_target
+            .entry((step.namespace.clone(), step.deployment.clone()))
+            .or_default()
+            .push(step);
+    }
+
+    steps_by_target
+        .into_iter()
+        .map(|((namespace, deployment), steps)| TargetSummary {
+            namespace,
+            deployment,
+            total_cost: steps.iter().map(|s| s.cost).sum(),
+            average_replicas: steps.iter().map(|s| s.total_replicas as f64).sum::<f64>()
+                / steps.len() as f64,
+            max_replicas: steps.iter().map(|s| s.total_replicas).max().unwrap_or(0),
+            max_queue_latency_secs: steps
+                .iter()
+                .filter_map(|s| s.queue_latency_secs)
+                .reduce(f64::max),
+            unserved_steps: steps
+                .iter()
+                .filter(|s| s.queue > 0 && s.total_replicas == 0)
+                .count(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ScalarOrMap, ScalerTarget, ScalerTargetType};
+
+    fn scaler_config() -> ProverAutoscalerScalerConfig {
+        ProverAutoscalerScalerConfig {
+            prometheus_port: 0,
+            scaler_run_interval: Duration::from_secs(60),
+            prover_job_monitor_url: String::new(),
+            agents: vec![],
+            protocol_versions: [("prover".into(), "0.24.2".to_owned())].into(),
+            cluster_priorities: [("foo".into(), 0), ("bar".into(), 10)].into(),
+            apply_min_to_namespace: None,
+            long_pending_duration: Duration::from_secs(600),
+            scale_errors_duration: Duration::from_secs(3600),
+            need_to_move_duration: Duration::from_secs(4 * 60),
+            scaler_targets: vec![ScalerTarget {
+                scaler_target_type: ScalerTargetType::Simple,
+                queue_report_field: QueueReportFields::basic_witness_jobs,
+                deployment: "witness-generator".into(),
+                min_replicas: 0,
+                max_replicas: [
+                    ("foo".into(), ScalarOrMap::Scalar(2)),
+                    ("bar".into(), ScalarOrMap::Scalar(10)),
+                ]
+                .into(),
+                speed: ScalarOrMap::Scalar(10),
+            }],
+            dry_run: true,
+        }
+    }
+
+    fn record(minute: u32, basic_witness_jobs: usize, with_clusters: bool) -> SimulationRecord {
+        let clusters = serde_json::json!(["foo", "bar"].map(|name| serde_json::json!({
+            "name": name,
+            "namespaces": {
+                "prover": {
+                    "deployments": { "witness-generator": { "running": 0, "desired": 0 } },
+                    "pods": {},
+                },
+            },
+        })));
+        serde_json::from_value(serde_json::json!({
+            "time": format!("2025-01-01T00:{minute:02}:00Z"),
+            "queue": [{
+                "version": "0.24.2",
+                "report": {
+                    "basic_witness_jobs": { "queued": basic_witness_jobs, "in_progress": 0 },
+                    "leaf_witness_jobs": { "queued": 0, "in_progress": 0 },
+                    "node_witness_jobs": { "queued": 0, "in_progress": 0 },
+                    "recursion_tip_witness_jobs": { "queued": 0, "in_progress": 0 },
+                    "scheduler_witness_jobs": { "queued": 0, "in_progress": 0 },
+                    "prover_jobs": { "queued": 0, "in_progress": 0 },
+                    "proof_compressor_jobs": { "queued": 0, "in_progress": 0 },
+                },
+            }],
+            "clusters": with_clusters.then_some(clusters),
+        }))
+        .unwrap()
+    }
+
+    #[tracing_test::traced_test]
+    #[test]
+    fn test_simulation() {
+        let simulation_config = ProverAutoscalerSimulationConfig {
+            replica_hourly_cost: [("witness-generator".into(), 2.0)].into(),
+            replica_hourly_throughput: [("witness-generator".into(), 60.0)].into(),
+        };
+        let simulator = Simulator::new(&scaler_config(), simulation_config);
+        let records = [
+            record(0, 45, true),
+            record(30, 15, false),
+            record(40, 0, false),
+        ];
+        let report = simulator.run(&records).unwrap();
+
+        let replicas: Vec<_> = report.steps.iter().map(|s| s.replicas.clone()).collect();
+        assert_eq!(
+            replicas,
+            [
+                [
+                    ("bar/witness-generator".to_owned(), 3),
+                    ("foo/witness-generator".to_owned(), 2)
+                ]
+                .into(),
+                [
+                    ("bar/witness-generator".to_owned(), 0),
+                    ("foo/witness-generator".to_owned(), 2)
+                ]
+                .into(),
+                [
+                    ("bar/witness-generator".to_owned(), 0),
+                    ("foo/witness-generator".to_owned(), 0)
+                ]
+                .into(),
+            ],
+            "Fill the preferred cluster first and scale down the other one first"
+        );
+        let costs: Vec<_> = report.steps.iter().map(|s| s.cost).collect();
+        assert_eq!(costs, [5.0, 2.0 / 3.0, 0.0]);
+        assert_eq!(report.steps[0].queue_latency_secs, Some(540.0));
+
+        assert_eq!(
+            report.summary,
+            [TargetSummary {
+                namespace: "prover".into(),
+                deployment: "witness-generator".into(),
+                total_cost: 5.0 + 2.0 / 3.0,
+                average_replicas: 7.0 / 3.0,
+                max_replicas: 5,
+                max_queue_latency_secs: Some(540.0),
+                unserved_steps: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_first_record_without_clusters() {
+        let simulator = Simulator::new(&scaler_config(), Default::default());
+        let err = simulator.run(&[record(0, 1, false)]).unwrap_err();
+        assert!(err.to_string().contains("cluster states"), "{err}");
+    }
+
+    #[test]
+    fn test_owning_deployment() {
+        let namespace = Namespace {
+            deployments: [
+                ("circuit-prover-gpu".into(), Default::default()),
+                ("circuit-prover-gpu-t4".into(), Default::default()),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            owning_deployment(&namespace, "circuit-prover-gpu-7c5f8fc747-gmtcr"),
+            Some(&"circuit-prover-gpu".into())
+        );
+        assert_eq!(
+            owning_deployment(&namespace, "circuit-prover-gpu-t4-7c5f8fc747-gmtcr"),
+            Some(&"circuit-prover-gpu-t4".into())
+        );
+        assert_eq!(owning_deployment(&namespace, "circuit-prover-gpu"), None);
+    }
+}
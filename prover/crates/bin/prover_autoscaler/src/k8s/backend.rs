@@ -0,0 +1,28 @@
+use super::{Scaler, Watcher};
+use crate::{
+    agent::AgentBackend,
+    cluster_types::{Cluster, DeploymentName, NamespaceName},
+};
+
+/// Agent backend watching and scaling Kubernetes Deployments.
+#[derive(Clone)]
+pub struct KubernetesBackend {
+    pub watcher: Watcher,
+    pub scaler: Scaler,
+}
+
+#[async_trait::async_trait]
+impl AgentBackend for KubernetesBackend {
+    async fn cluster(&self) -> anyhow::Result<Cluster> {
+        Ok(self.watcher.cluster.lock().await.clone())
+    }
+
+    async fn scale(
+        &self,
+        namespace: &NamespaceName,
+        name: &DeploymentName,
+        size: usize,
+    ) -> anyhow::Result<()> {
+        self.scaler.scale(namespace, name, size).await
+    }
+}
@@ -9,3 +9,4 @@ pub mod k8s;
 pub(crate) mod key;
 pub(crate) mod metrics;
 pub mod task_wiring;
+pub mod worker_groups;
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use anyhow::Context;
 use structopt::StructOpt;
@@ -9,11 +9,19 @@ use tokio::{
 use zksync_prover_autoscaler::{
     agent,
     cluster_types::ClusterName,
-    config::{config_from_yaml, ProverAutoscalerConfig},
-    global::{manager::Manager, queuer::Queuer, watcher},
+    config::{
+        config_from_yaml, AgentBackendConfig, ProverAutoscalerAgentConfig, ProverAutoscalerConfig,
+    },
+    global::{
+        manager::Manager,
+        queuer::Queuer,
+        simulator::{self, Simulator},
+        watcher,
+    },
     http_client::HttpClient,
-    k8s::{Scaler, Watcher},
+    k8s::{KubernetesBackend, Scaler, Watcher},
     task_wiring::TaskRunner,
+    worker_groups::{CommandHook, HttpHook, WorkerGroupsBackend, WorkerGroupsHook},
 };
 use zksync_task_management::ManagedTasks;
 use zksync_vlog::prometheus::PrometheusExporterConfig;
@@ -23,6 +31,7 @@ use zksync_vlog::prometheus::PrometheusExporterConfig;
 pub enum AutoscalerType {
     Scaler,
     Agent,
+    Simulator,
 }
 
 impl std::str::FromStr for AutoscalerType {
@@ -32,6 +41,7 @@ impl std::str::FromStr for AutoscalerType {
         match s {
             "scaler" => Ok(AutoscalerType::Scaler),
             "agent" => Ok(AutoscalerType::Agent),
+            "simulator" => Ok(AutoscalerType::Simulator),
             other => Err(format!("{} is not a valid AutoscalerType", other)),
         }
     }
@@ -40,9 +50,9 @@ impl std::str::FromStr for AutoscalerType {
 #[derive(Debug, StructOpt)]
 #[structopt(name = "Prover Autoscaler", about = "Run Prover Autoscaler components")]
 struct Opt {
-    /// Prover Autoscaler can run Agent or Scaler type.
+    /// Prover Autoscaler can run Agent or Scaler type, or simulate Scaler offline.
     ///
-    /// Specify `agent` or `scaler`
+    /// Specify `agent`, `scaler` or `simulator`
     #[structopt(short, long, default_value = "agent")]
     job: AutoscalerType,
     /// Name of the cluster Agent is watching.
@@ -51,6 +61,13 @@ struct Opt {
     /// Path to the configuration file.
     #[structopt(long)]
     config_path: std::path::PathBuf,
+    /// Path to the recorded queue reports and cluster states to replay, one JSON record per line.
+    /// Required for the `simulator` job.
+    #[structopt(long)]
+    simulation_input: Option<std::path::PathBuf>,
+    /// Path to write the simulation report to. If not specified, the report is printed to stdout.
+    #[structopt(long)]
+    simulation_output: Option<std::path::PathBuf>,
 }
 
 #[tokio::main]
@@ -85,24 +102,52 @@ async fn main() -> anyhow::Result<()> {
             let exporter_config = PrometheusExporterConfig::pull(agent_config.prometheus_port);
             tasks.push(tokio::spawn(exporter_config.run(stop_receiver.clone())));
 
-            let _ = rustls::crypto::ring::default_provider().install_default();
-            let client = kube::Client::try_default().await?;
-
-            let watcher = Watcher::new(
-                http_client,
-                client.clone(),
-                opt.cluster_name,
-                agent_config.namespaces,
-            )
-            .await;
-            let scaler = Scaler::new(client, agent_config.dry_run);
-            tasks.push(tokio::spawn(watcher.clone().run()));
-            tasks.push(tokio::spawn(agent::run_server(
-                agent_config.http_port,
-                watcher,
-                scaler,
-                stop_receiver.clone(),
-            )))
+            match agent_config.backend {
+                AgentBackendConfig::Kubernetes => {
+                    let _ = rustls::crypto::ring::default_provider().install_default();
+                    let client = kube::Client::try_default().await?;
+
+                    let watcher = Watcher::new(
+                        http_client,
+                        client.clone(),
+                        opt.cluster_name,
+                        agent_config.namespaces,
+                    )
+                    .await;
+                    let scaler = Scaler::new(client, agent_config.dry_run);
+                    tasks.push(tokio::spawn(watcher.clone().run()));
+                    tasks.push(tokio::spawn(agent::run_server(
+                        agent_config.http_port,
+                        Arc::new(KubernetesBackend { watcher, scaler }),
+                        stop_receiver.clone(),
+                    )))
+                }
+                AgentBackendConfig::Command {
+                    status_command,
+                    scale_command,
+                    command_timeout,
+                } => {
+                    let hook = CommandHook::new(status_command, scale_command, command_timeout)?;
+                    tasks.extend(get_worker_groups_tasks(
+                        Arc::new(hook),
+                        opt.cluster_name,
+                        agent_config,
+                        stop_receiver.clone(),
+                    )?);
+                }
+                AgentBackendConfig::Http {
+                    status_url,
+                    scale_url,
+                } => {
+                    let hook = HttpHook::new(http_client, status_url, scale_url);
+                    tasks.extend(get_worker_groups_tasks(
+                        Arc::new(hook),
+                        opt.cluster_name,
+                        agent_config,
+                        stop_receiver.clone(),
+                    )?);
+                }
+            }
         }
         AutoscalerType::Scaler => {
             tracing::info!("Starting ProverAutoscaler Scaler");
@@ -119,6 +164,21 @@ async fn main() -> anyhow::Result<()> {
             let manager = Manager::new(watcher.clone(), queuer, scaler_config);
             tasks.extend(get_tasks(watcher, manager, interval, stop_receiver)?);
         }
+        AutoscalerType::Simulator => {
+            tracing::info!("Starting ProverAutoscaler Simulator");
+            let scaler_config = general_config.scaler_config.context("scaler_config")?;
+            let input = opt.simulation_input.context("--simulation-input")?;
+            let records = simulator::read_records(&input)?;
+            let report =
+                Simulator::new(&scaler_config, general_config.simulation_config).run(&records)?;
+            let report = serde_json::to_string_pretty(&report)?;
+            match opt.simulation_output {
+                Some(path) => std::fs::write(&path, report)
+                    .with_context(|| format!("failed to write {}", path.display()))?,
+                None => println!("{report}"),
+            }
+            return Ok(());
+        }
     }
 
     let mut tasks = ManagedTasks::new(tasks);
@@ -150,3 +210,30 @@ fn get_tasks(
 
     Ok(task_runner.spawn(stop_receiver))
 }
+
+fn get_worker_groups_tasks(
+    hook: Arc<dyn WorkerGroupsHook>,
+    cluster_name: Option<ClusterName>,
+    agent_config: ProverAutoscalerAgentConfig,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<Vec<JoinHandle<anyhow::Result<()>>>> {
+    let cluster_name =
+        cluster_name.context("--cluster-name is required for non-Kubernetes backends")?;
+    let backend = WorkerGroupsBackend::new(
+        hook,
+        cluster_name,
+        agent_config.namespaces,
+        agent_config.dry_run,
+    );
+
+    let mut task_runner = TaskRunner::default();
+    task_runner.add("WorkerGroups", agent_config.poll_interval, backend.clone());
+
+    let mut tasks = task_runner.spawn(stop_receiver.clone());
+    tasks.push(tokio::spawn(agent::run_server(
+        agent_config.http_port,
+        Arc::new(backend),
+        stop_receiver,
+    )));
+    Ok(tasks)
+}
@@ -0,0 +1,307 @@
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::Context;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, CONTENT_TYPE},
+    Method,
+};
+
+use crate::{
+    agent::ScaleDeploymentRequest,
+    cluster_types::{Deployment, DeploymentName, NamespaceName},
+    http_client::HttpClient,
+};
+
+/// States of worker groups per namespace, as reported by a hook. Serialized as JSON, e.g.:
+/// `{"prover-red": {"circuit-prover-gpu": {"running": 1, "desired": 2}}}`.
+pub type WorkerGroups = HashMap<NamespaceName, HashMap<DeploymentName, Deployment>>;
+
+/// External hook managing worker groups.
+#[async_trait::async_trait]
+pub trait WorkerGroupsHook: Send + Sync {
+    /// Returns states of all worker groups.
+    async fn status(&self) -> anyhow::Result<WorkerGroups>;
+    /// Scales the worker group to the specified number of replicas.
+    async fn scale(
+        &self,
+        namespace: &NamespaceName,
+        name: &DeploymentName,
+        size: usize,
+    ) -> anyhow::Result<()>;
+}
+
+/// Hook running local commands, e.g. wrappers around `docker compose` or `systemctl`.
+#[derive(Debug)]
+pub struct CommandHook {
+    status_command: Vec<String>,
+    scale_command: Vec<String>,
+    timeout: Duration,
+}
+
+impl CommandHook {
+    pub fn new(
+        status_command: Vec<String>,
+        scale_command: Vec<String>,
+        timeout: Duration,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(!status_command.is_empty(), "status_command is empty");
+        anyhow::ensure!(!scale_command.is_empty(), "scale_command is empty");
+        Ok(Self {
+            status_command,
+            scale_command,
+            timeout,
+        })
+    }
+
+    /// Runs the command with additional arguments and returns its stdout. The command is killed
+    /// if it doesn't finish within the timeout.
+    async fn run_command(
+        &self,
+        command: &[String],
+        extra_args: &[String],
+    ) -> anyhow::Result<Vec<u8>> {
+        let (program, args) = command.split_first().context("empty command")?;
+        let output = tokio::process::Command::new(program)
+            .args(args)
+            .args(extra_args)
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(self.timeout, output)
+            .await
+            .map_err(|_| anyhow::anyhow!("{program} timed out after {:?}", self.timeout))?
+            .with_context(|| format!("Failed to run {program}"))?;
+        anyhow::ensure!(
+            output.status.success(),
+            "{program} failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(output.stdout)
+    }
+}
+
+#[async_trait::async_trait]
+impl WorkerGroupsHook for CommandHook {
+    async fn status(&self) -> anyhow::Result<WorkerGroups> {
+        let stdout = self.run_command(&self.status_command, &[]).await?;
+        serde_json::from_slice(&stdout).context("Failed to parse status_command output as json")
+    }
+
+    async fn scale(
+        &self,
+        namespace: &NamespaceName,
+        name: &DeploymentName,
+        size: usize,
+    ) -> anyhow::Result<()> {
+        let args = [namespace.to_string(), name.to_string(), size.to_string()];
+        self.run_command(&self.scale_command, &args).await?;
+        Ok(())
+    }
+}
+
+/// Hook calling an external HTTP service.
+#[derive(Clone)]
+pub struct HttpHook {
+    http_client: HttpClient,
+    status_url: String,
+    scale_url: String,
+}
+
+impl HttpHook {
+    pub fn new(http_client: HttpClient, status_url: String, scale_url: String) -> Self {
+        Self {
+            http_client,
+            status_url,
+            scale_url,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WorkerGroupsHook for HttpHook {
+    async fn status(&self) -> anyhow::Result<WorkerGroups> {
+        let url = &self.status_url;
+        let response = self
+            .http_client
+            .send_request_with_retries(url, Method::GET, None, None)
+            .await
+            .map_err(|err| {
+                anyhow::anyhow!("Failed fetching worker groups from url: {url}: {err:?}")
+            })?;
+        response
+            .json::<WorkerGroups>()
+            .await
+            .context("Failed to read response as json")
+    }
+
+    async fn scale(
+        &self,
+        namespace: &NamespaceName,
+        name: &DeploymentName,
+        size: usize,
+    ) -> anyhow::Result<()> {
+        let url = &self.scale_url;
+        let request = ScaleDeploymentRequest {
+            namespace: namespace.clone(),
+            name: name.clone(),
+            size,
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        self.http_client
+            .send_request_with_retries(
+                url,
+                Method::POST,
+                Some(headers),
+                Some(serde_json::to_vec(&request)?),
+            )
+            .await
+            .map_err(|err| {
+                anyhow::anyhow!("Failed sending scale request to url: {url}: {err:?}")
+            })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{
+        extract::State,
+        routing::{get, post},
+        Json, Router,
+    };
+
+    use super::*;
+
+    #[cfg(unix)]
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[cfg(unix)]
+    fn sh(script: &str) -> Vec<String> {
+        vec!["sh".into(), "-c".into(), script.into()]
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_hook() {
+        let hook = CommandHook::new(
+            sh(r#"echo '{"prover": {"witness-generator": {"running": 1, "desired": 2}}}'"#),
+            // Arguments are passed to the script as `$0`, `$1`, ...
+            sh(r#"[ "$0 $1 $2" = "prover witness-generator 3" ]"#),
+            TIMEOUT,
+        )
+        .unwrap();
+
+        let groups = hook.status().await.unwrap();
+        let deployment =
+            &groups[&NamespaceName::from("prover")][&DeploymentName::from("witness-generator")];
+        assert_eq!((deployment.running, deployment.desired), (1, 2));
+
+        hook.scale(&"prover".into(), &"witness-generator".into(), 3)
+            .await
+            .unwrap();
+        let err = hook
+            .scale(&"prover".into(), &"witness-generator".into(), 4)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("failed with"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_hook_errors() {
+        let hook = CommandHook::new(sh("echo oops >&2; exit 1"), sh("true"), TIMEOUT).unwrap();
+        let err = hook.status().await.unwrap_err();
+        assert!(err.to_string().contains("oops"), "{err}");
+
+        let hook = CommandHook::new(sh("echo not-json"), sh("true"), TIMEOUT).unwrap();
+        hook.status().await.unwrap_err();
+
+        CommandHook::new(vec![], sh("true"), TIMEOUT).unwrap_err();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_hook_timeout() {
+        let hook =
+            CommandHook::new(sh("sleep 60"), sh("sleep 60"), Duration::from_millis(100)).unwrap();
+        let err = hook.status().await.unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{err}");
+        let err = hook
+            .scale(&"prover".into(), &"witness-generator".into(), 1)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{err}");
+    }
+
+    type ScaleRequests = Arc<Mutex<Vec<ScaleDeploymentRequest>>>;
+
+    /// Starts a local HTTP server emulating a worker groups service. Returns its URL.
+    async fn serve_worker_groups(
+        status: serde_json::Value,
+        scale_requests: ScaleRequests,
+    ) -> String {
+        let app = Router::new()
+            .route("/status", get(move || async move { Json(status) }))
+            .route(
+                "/scale",
+                post(
+                    |State(requests): State<ScaleRequests>,
+                     Json(request): Json<ScaleDeploymentRequest>| async move {
+                        requests.lock().unwrap().push(request);
+                    },
+                ),
+            )
+            .with_state(scale_requests);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_http_hook() {
+        let scale_requests = ScaleRequests::default();
+        let status = serde_json::json!({
+            "prover": { "witness-generator": { "running": 1, "desired": 2 } },
+        });
+        let url = serve_worker_groups(status, scale_requests.clone()).await;
+        let hook = HttpHook::new(
+            HttpClient::default(),
+            format!("{url}/status"),
+            format!("{url}/scale"),
+        );
+
+        let groups = hook.status().await.unwrap();
+        let deployment =
+            &groups[&NamespaceName::from("prover")][&DeploymentName::from("witness-generator")];
+        assert_eq!((deployment.running, deployment.desired), (1, 2));
+
+        hook.scale(&"prover".into(), &"witness-generator".into(), 3)
+            .await
+            .unwrap();
+        let scale_requests = scale_requests.lock().unwrap();
+        assert_eq!(scale_requests.len(), 1);
+        assert_eq!(scale_requests[0].namespace, NamespaceName::from("prover"));
+        assert_eq!(
+            scale_requests[0].name,
+            DeploymentName::from("witness-generator")
+        );
+        assert_eq!(scale_requests[0].size, 3);
+    }
+
+    #[tokio::test]
+    async fn test_http_hook_invalid_status() {
+        let url =
+            serve_worker_groups(serde_json::json!(["not", "groups"]), Default::default()).await;
+        let hook = HttpHook::new(
+            HttpClient::default(),
+            format!("{url}/status"),
+            format!("{url}/scale"),
+        );
+        let err = hook.status().await.unwrap_err();
+        assert!(err.to_string().contains("json"), "{err}");
+    }
+}
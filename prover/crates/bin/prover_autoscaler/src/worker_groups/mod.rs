@@ -0,0 +1,309 @@
+//! Agent backend for provers running outside of Kubernetes (e.g., on plain VMs or in Nomad). Workers are organized
+//! into worker groups, which are managed by an external hook and exposed to the Scaler as Deployments.
+
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use tokio::sync::Mutex;
+
+pub use self::hook::{CommandHook, HttpHook, WorkerGroups, WorkerGroupsHook};
+use crate::{
+    agent::AgentBackend,
+    cluster_types::{
+        Cluster, ClusterName, DeploymentName, Namespace, NamespaceName, Pod, ScaleEvent,
+    },
+    task_wiring::Task,
+};
+
+mod hook;
+
+/// Agent backend polling worker group states from a hook. Should be run as a periodic [`Task`].
+///
+/// Hooks only report the number of running and desired workers per group. Pods are synthesized from these numbers,
+/// so that missing workers are reported as Pending and can be moved to another cluster once long pending.
+#[derive(Clone)]
+pub struct WorkerGroupsBackend {
+    hook: Arc<dyn WorkerGroupsHook>,
+    cluster: Arc<Mutex<Cluster>>,
+    dry_run: bool,
+}
+
+impl WorkerGroupsBackend {
+    pub fn new(
+        hook: Arc<dyn WorkerGroupsHook>,
+        cluster_name: ClusterName,
+        namespaces: Vec<NamespaceName>,
+        dry_run: bool,
+    ) -> Self {
+        let namespaces = namespaces
+            .into_iter()
+            .map(|n| (n, Namespace::default()))
+            .collect();
+        Self {
+            hook,
+            cluster: Arc::new(Mutex::new(Cluster {
+                name: cluster_name,
+                namespaces,
+            })),
+            dry_run,
+        }
+    }
+}
+
+/// Updates watched namespaces of the cluster with the reported worker groups.
+fn update_cluster(cluster: &mut Cluster, mut groups: WorkerGroups, now: DateTime<Utc>) {
+    for (namespace, state) in cluster.namespaces.iter_mut() {
+        let deployments = groups.remove(namespace).unwrap_or_default();
+        let mut pods = HashMap::new();
+        for (name, deployment) in &deployments {
+            let pending = deployment.desired.saturating_sub(deployment.running);
+            let running_pods = (0..deployment.running).map(|i| (format!("{name}-{i}"), "Running"));
+            let pending_pods = (0..pending).map(|i| (format!("{name}-pending-{i}"), "Pending"));
+            for (pod, status) in running_pods.chain(pending_pods) {
+                // Keep the time of the last status change for pods which didn't change.
+                let changed = state
+                    .pods
+                    .get(&pod)
+                    .filter(|p| p.status == status)
+                    .map_or(now, |p| p.changed);
+                let pod_value = Pod {
+                    owner: format!("WorkerGroup/{name}"),
+                    status: status.to_owned(),
+                    changed,
+                };
+                pods.insert(pod, pod_value);
+            }
+        }
+        state.deployments = deployments;
+        state.pods = pods;
+    }
+
+    for namespace in groups.keys() {
+        tracing::debug!("Ignoring worker groups in not watched namespace {namespace}");
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for WorkerGroupsBackend {
+    async fn invoke(&self) -> anyhow::Result<()> {
+        match self.hook.status().await {
+            Ok(groups) => {
+                let mut cluster = self.cluster.lock().await;
+                update_cluster(&mut cluster, groups, Utc::now());
+            }
+            Err(err) => tracing::warn!("Failed to get worker groups: {err:?}"),
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AgentBackend for WorkerGroupsBackend {
+    async fn cluster(&self) -> anyhow::Result<Cluster> {
+        Ok(self.cluster.lock().await.clone())
+    }
+
+    async fn scale(
+        &self,
+        namespace: &NamespaceName,
+        name: &DeploymentName,
+        size: usize,
+    ) -> anyhow::Result<()> {
+        if self.dry_run {
+            tracing::info!(
+                "Dry run of scaled worker group {namespace}/{name} to {size} replica(s)."
+            );
+            return Ok(());
+        }
+        if let Err(err) = self.hook.scale(namespace, name, size).await {
+            // Report the failure like Kubernetes `FailedScaleUp` events, so that the Scaler can move pending workers
+            // to other clusters.
+            let mut cluster = self.cluster.lock().await;
+            if let Some(state) = cluster.namespaces.get_mut(namespace) {
+                state.scale_errors.push(ScaleEvent {
+                    name: format!("{namespace}/{name}"),
+                    time: Utc::now(),
+                });
+            }
+            return Err(err);
+        }
+        tracing::info!("Scaled worker group {namespace}/{name} to {size} replica(s).");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex as StdMutex;
+
+    use super::*;
+    use crate::cluster_types::Deployment;
+
+    /// Hook emulating worker groups which start instantly up to the configured capacity.
+    #[derive(Default)]
+    struct FakeHook {
+        groups: StdMutex<WorkerGroups>,
+        capacity: usize,
+        fail_scale: bool,
+        scale_calls: StdMutex<Vec<(NamespaceName, DeploymentName, usize)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl WorkerGroupsHook for FakeHook {
+        async fn status(&self) -> anyhow::Result<WorkerGroups> {
+            Ok(self.groups.lock().unwrap().clone())
+        }
+
+        async fn scale(
+            &self,
+            namespace: &NamespaceName,
+            name: &DeploymentName,
+            size: usize,
+        ) -> anyhow::Result<()> {
+            self.scale_calls
+                .lock()
+                .unwrap()
+                .push((namespace.clone(), name.clone(), size));
+            anyhow::ensure!(!self.fail_scale, "out of capacity");
+            let mut groups = self.groups.lock().unwrap();
+            let group = groups
+                .entry(namespace.clone())
+                .or_default()
+                .entry(name.clone())
+                .or_default();
+            group.desired = size;
+            group.running = size.min(self.capacity);
+            Ok(())
+        }
+    }
+
+    fn pod_statuses(cluster: &Cluster, namespace: &str) -> Vec<(String, String)> {
+        let mut statuses: Vec<_> = cluster.namespaces[&NamespaceName::from(namespace)]
+            .pods
+            .iter()
+            .map(|(name, pod)| (name.clone(), pod.status.clone()))
+            .collect();
+        statuses.sort();
+        statuses
+    }
+
+    #[tokio::test]
+    async fn test_worker_groups_backend() {
+        let hook = Arc::new(FakeHook {
+            groups: StdMutex::new(
+                [(
+                    "prover".into(),
+                    [("circuit-prover-gpu".into(), Deployment::default())].into(),
+                )]
+                .into(),
+            ),
+            capacity: 1,
+            ..FakeHook::default()
+        });
+        let backend =
+            WorkerGroupsBackend::new(hook.clone(), "vm".into(), vec!["prover".into()], false);
+
+        backend.invoke().await.unwrap();
+        let cluster = backend.cluster().await.unwrap();
+        assert_eq!(cluster.name, ClusterName::from("vm"));
+        assert!(cluster.namespaces[&NamespaceName::from("prover")]
+            .deployments
+            .contains_key(&DeploymentName::from("circuit-prover-gpu")));
+        assert!(pod_statuses(&cluster, "prover").is_empty());
+
+        backend
+            .scale(&"prover".into(), &"circuit-prover-gpu".into(), 3)
+            .await
+            .unwrap();
+        assert_eq!(
+            *hook.scale_calls.lock().unwrap(),
+            [(
+                NamespaceName::from("prover"),
+                DeploymentName::from("circuit-prover-gpu"),
+                3
+            )]
+        );
+        backend.invoke().await.unwrap();
+        let cluster = backend.cluster().await.unwrap();
+        assert_eq!(
+            pod_statuses(&cluster, "prover"),
+            [
+                ("circuit-prover-gpu-0".to_owned(), "Running".to_owned()),
+                (
+                    "circuit-prover-gpu-pending-0".to_owned(),
+                    "Pending".to_owned()
+                ),
+                (
+                    "circuit-prover-gpu-pending-1".to_owned(),
+                    "Pending".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_worker_groups_backend_dry_run() {
+        let hook = Arc::new(FakeHook::default());
+        let backend =
+            WorkerGroupsBackend::new(hook.clone(), "vm".into(), vec!["prover".into()], true);
+        backend
+            .scale(&"prover".into(), &"circuit-prover-gpu".into(), 3)
+            .await
+            .unwrap();
+        assert!(hook.scale_calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_worker_groups_backend_scale_error() {
+        let hook = Arc::new(FakeHook {
+            fail_scale: true,
+            ..FakeHook::default()
+        });
+        let backend = WorkerGroupsBackend::new(hook, "vm".into(), vec!["prover".into()], false);
+        let err = backend
+            .scale(&"prover".into(), &"circuit-prover-gpu".into(), 3)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("out of capacity"), "{err}");
+
+        // Status updates must not drop recorded errors.
+        backend.invoke().await.unwrap();
+        let cluster = backend.cluster().await.unwrap();
+        let scale_errors = &cluster.namespaces[&NamespaceName::from("prover")].scale_errors;
+        assert_eq!(scale_errors.len(), 1);
+        assert_eq!(scale_errors[0].name, "prover/circuit-prover-gpu");
+    }
+
+    #[test]
+    fn test_update_cluster_keeps_pending_time() {
+        let mut cluster = Cluster {
+            name: "vm".into(),
+            namespaces: [("prover".into(), Namespace::default())].into(),
+        };
+        let groups = |running, desired| -> WorkerGroups {
+            [
+                (
+                    "prover".into(),
+                    [("witness-generator".into(), Deployment { running, desired })].into(),
+                ),
+                (
+                    "other".into(),
+                    [("witness-generator".into(), Deployment::default())].into(),
+                ),
+            ]
+            .into()
+        };
+        let start = Utc::now();
+        update_cluster(&mut cluster, groups(0, 2), start);
+        let later = start + chrono::Duration::minutes(5);
+        update_cluster(&mut cluster, groups(1, 2), later);
+
+        assert!(!cluster
+            .namespaces
+            .contains_key(&NamespaceName::from("other")));
+        let pods = &cluster.namespaces[&NamespaceName::from("prover")].pods;
+        assert_eq!(pods.len(), 2);
+        assert_eq!(pods["witness-generator-0"].changed, later);
+        assert_eq!(pods["witness-generator-pending-0"].changed, start);
+    }
+}
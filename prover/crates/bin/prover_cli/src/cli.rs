@@ -2,8 +2,8 @@ use clap::{command, Args, Parser, Subcommand};
 use zksync_types::url::SensitiveUrl;
 
 use crate::commands::{
-    config, debug_proof, delete, get_file_info, insert_batch, insert_version, requeue, restart,
-    stats, status::StatusCommand,
+    config, debug_proof, delete, get_file_info, insert_batch, insert_version, prioritize, requeue,
+    restart, stats, status::StatusCommand, verify_artifacts, watch,
 };
 
 pub const VERSION_STRING: &str = env!("CARGO_PKG_VERSION");
@@ -30,6 +30,11 @@ impl ProverCLI {
             ProverCommand::Stats(args) => stats::run(args, self.config).await?,
             ProverCommand::InsertVersion(args) => insert_version::run(args, self.config).await?,
             ProverCommand::InsertBatch(args) => insert_batch::run(args, self.config).await?,
+            ProverCommand::Prioritize(args) => prioritize::run(args, self.config).await?,
+            ProverCommand::Watch(args) => watch::run(args, self.config).await?,
+            ProverCommand::VerifyArtifacts(args) => {
+                verify_artifacts::run(args, self.config).await?
+            }
         };
         Ok(())
     }
@@ -62,4 +67,14 @@ pub enum ProverCommand {
     Stats(stats::Options),
     InsertVersion(insert_version::Args),
     InsertBatch(insert_batch::Args),
+    #[command(about = "Sets or lists priority classes of L1 batches")]
+    Prioritize(prioritize::Args),
+    #[command(
+        about = "Continuously displays prover queues and estimated time to proof for pending batches"
+    )]
+    Watch(watch::Args),
+    #[command(
+        about = "Checks that artifacts of an L1 batch are present in the object store and not corrupted"
+    )]
+    VerifyArtifacts(verify_artifacts::Args),
 }
@@ -65,6 +65,14 @@ async fn delete_prover_db(mut conn: Connection<'_, Prover>) -> anyhow::Result<()
         .delete()
         .await
         .context("failed to delete witness generator")?;
+    conn.fri_batch_priorities_dal()
+        .delete()
+        .await
+        .context("failed to delete batch priorities")?;
+    conn.fri_artifact_hashes_dal()
+        .delete()
+        .await
+        .context("failed to delete artifact hashes")?;
     Ok(())
 }
 
@@ -72,6 +80,11 @@ async fn delete_batch_data(
     mut conn: Connection<'_, Prover>,
     block_number: L1BatchNumber,
 ) -> anyhow::Result<()> {
+    // Artifacts are looked up by the batch jobs, so their hashes must be deleted first.
+    conn.fri_artifact_hashes_dal()
+        .delete_batch_data(block_number)
+        .await
+        .context("failed to delete artifact hashes")?;
     conn.fri_proof_compressor_dal()
         .delete_batch_data(block_number)
         .await
@@ -84,5 +97,9 @@ async fn delete_batch_data(
         .delete_batch_data(block_number)
         .await
         .context("failed to delete witness generator data")?;
+    conn.fri_batch_priorities_dal()
+        .delete_batch_data(block_number)
+        .await
+        .context("failed to delete batch priority")?;
     Ok(())
 }
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use clap::Args as ClapArgs;
+use zksync_prover_dal::{ConnectionPool, Prover, ProverDal};
+use zksync_types::{prover_dal::BatchPriorityClass, L1BatchNumber};
+
+use crate::cli::ProverCLIConfig;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// Batches to set the priority class for. If not specified, batches with a non-default priority class are listed.
+    #[clap(short = 'n', num_args = 1..)]
+    batches: Vec<L1BatchNumber>,
+    /// Priority class to set: `urgent`, `normal` or `low`. Jobs for batches with a higher class are picked first;
+    /// batches waiting for too long are treated as urgent regardless of their class.
+    #[clap(short, long, default_value_t = BatchPriorityClass::Urgent)]
+    class: BatchPriorityClass,
+    /// Sets the aging interval in minutes. Batches sealed longer than this interval ago are treated as urgent.
+    /// The interval is shared by all witness generators, provers and proof compressors.
+    #[clap(long)]
+    aging_interval: Option<u64>,
+}
+
+pub async fn run(args: Args, config: ProverCLIConfig) -> anyhow::Result<()> {
+    let pool = ConnectionPool::<Prover>::singleton(config.db_url)
+        .build()
+        .await
+        .context("failed to build a prover_connection_pool")?;
+    let mut conn = pool
+        .connection()
+        .await
+        .context("failed to acquire a connection")?;
+
+    if let Some(minutes) = args.aging_interval {
+        conn.fri_batch_priorities_dal()
+            .set_aging_interval(Duration::from_secs(minutes * 60))
+            .await;
+        println!("Set batch priority aging interval to {minutes} minutes");
+    }
+
+    if args.batches.is_empty() {
+        if args.aging_interval.is_some() {
+            return Ok(());
+        }
+        let aging_interval = conn.fri_batch_priorities_dal().get_aging_interval().await;
+        println!(
+            "Batches sealed more than {} minutes ago are treated as urgent",
+            aging_interval.as_secs() / 60
+        );
+        let prioritized_batches = conn
+            .fri_batch_priorities_dal()
+            .get_prioritized_batches()
+            .await;
+        if prioritized_batches.is_empty() {
+            println!(
+                "All batches have the {} priority class",
+                BatchPriorityClass::Normal
+            );
+        }
+        for (batch_number, class) in prioritized_batches {
+            println!("Batch {batch_number}: {class}");
+        }
+        return Ok(());
+    }
+
+    for batch_number in args.batches {
+        conn.fri_batch_priorities_dal()
+            .set_batch_priority(batch_number, args.class)
+            .await;
+        println!(
+            "Set {} priority class for batch {batch_number} 🚦",
+            args.class
+        );
+    }
+    Ok(())
+}
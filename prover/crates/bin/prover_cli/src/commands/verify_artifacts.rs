@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use anyhow::Context as _;
+use clap::Args as ClapArgs;
+use colored::Colorize;
+use zksync_config::{configs::object_store::ObjectStoreMode, ObjectStoreConfig};
+use zksync_env_config::{object_store::ProverObjectStoreConfig, FromEnv};
+use zksync_object_store::{ObjectStore, ObjectStoreError, ObjectStoreFactory};
+use zksync_prover_dal::{ConnectionPool, Prover, ProverDal};
+use zksync_prover_fri_utils::artifact_integrity::{artifact_bucket, content_hash};
+use zksync_types::{prover_dal::ProverArtifactKind, L1BatchNumber};
+
+use crate::cli::ProverCLIConfig;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// Batch number to verify artifacts of.
+    #[clap(short = 'n', long)]
+    batch: L1BatchNumber,
+    /// Path to a local directory with the file-backed object store. If not specified, the object store
+    /// is configured from `PROVER_OBJECT_STORE_*` environment variables.
+    #[clap(long)]
+    object_store_path: Option<String>,
+    /// Prints the status of every artifact, not only missing or corrupted ones.
+    #[clap(short, long)]
+    verbose: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum ArtifactStatus {
+    Ok,
+    /// The artifact exists, but no content hash was recorded for it.
+    Unverified,
+    Missing,
+    Corrupted,
+}
+
+pub async fn run(args: Args, config: ProverCLIConfig) -> anyhow::Result<()> {
+    let object_store_config = match args.object_store_path {
+        Some(path) => ObjectStoreConfig {
+            mode: ObjectStoreMode::FileBacked {
+                file_backed_base_path: path,
+            },
+            max_retries: 5,
+            local_mirror_path: None,
+        },
+        None => {
+            ProverObjectStoreConfig::from_env()
+                .context("failed to load prover object store config")?
+                .0
+        }
+    };
+    let object_store = ObjectStoreFactory::new(object_store_config)
+        .create_store()
+        .await?;
+    let pool = ConnectionPool::<Prover>::singleton(config.db_url)
+        .build()
+        .await
+        .context("failed to build a prover_connection_pool")?;
+    let mut conn = pool
+        .connection()
+        .await
+        .context("failed to acquire a connection")?;
+
+    let artifacts = conn
+        .fri_artifact_hashes_dal()
+        .get_batch_artifacts(args.batch)
+        .await;
+    anyhow::ensure!(
+        !artifacts.is_empty(),
+        "No artifacts found for batch {}",
+        args.batch
+    );
+
+    let mut keys_by_kind = HashMap::<ProverArtifactKind, Vec<String>>::new();
+    for artifact in artifacts {
+        keys_by_kind
+            .entry(artifact.kind)
+            .or_default()
+            .push(artifact.object_key);
+    }
+    let mut keys_by_kind: Vec<_> = keys_by_kind.into_iter().collect();
+    keys_by_kind.sort_by_key(|(kind, _)| kind.to_string());
+
+    println!("== Batch {} Artifacts ==", args.batch);
+    let mut counts = HashMap::<ArtifactStatus, usize>::new();
+    for (kind, keys) in keys_by_kind {
+        let bucket = artifact_bucket(kind);
+        let hashes = conn
+            .fri_artifact_hashes_dal()
+            .get_artifact_hashes(&bucket.to_string(), &keys)
+            .await;
+        for key in &keys {
+            let status = match object_store.get_raw(bucket, key).await {
+                Ok(value) => match hashes.get(key) {
+                    None => ArtifactStatus::Unverified,
+                    Some(hash) if *hash == content_hash(&value) => ArtifactStatus::Ok,
+                    Some(_) => ArtifactStatus::Corrupted,
+                },
+                Err(ObjectStoreError::KeyNotFound(_)) => ArtifactStatus::Missing,
+                Err(err) => {
+                    return Err(err).with_context(|| format!("failed to fetch {bucket}/{key}"))
+                }
+            };
+            *counts.entry(status).or_default() += 1;
+
+            let status_str = match status {
+                ArtifactStatus::Ok => "ok".green(),
+                ArtifactStatus::Unverified => "unverified".yellow(),
+                ArtifactStatus::Missing => "missing".red(),
+                ArtifactStatus::Corrupted => "corrupted".red(),
+            };
+            if args.verbose || status >= ArtifactStatus::Missing {
+                println!("{:<24}{bucket}/{key}: {status_str}", kind.to_string());
+            }
+        }
+    }
+
+    let count = |status| counts.get(&status).copied().unwrap_or(0);
+    println!(
+        "> {} ok, {} unverified, {} missing, {} corrupted",
+        count(ArtifactStatus::Ok),
+        count(ArtifactStatus::Unverified),
+        count(ArtifactStatus::Missing),
+        count(ArtifactStatus::Corrupted)
+    );
+    let failures = count(ArtifactStatus::Missing) + count(ArtifactStatus::Corrupted);
+    anyhow::ensure!(
+        failures == 0,
+        "{failures} artifact(s) of batch {} are missing or corrupted",
+        args.batch
+    );
+    Ok(())
+}
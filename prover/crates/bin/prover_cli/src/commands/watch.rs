@@ -0,0 +1,352 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use chrono::Local;
+use clap::Args as ClapArgs;
+use colored::Colorize;
+use zksync_prover_dal::{Connection, ConnectionPool, Prover, ProverDal};
+use zksync_types::{
+    basic_fri_types::AggregationRound,
+    prover_dal::{BatchStageTimings, ExtendedJobCountStatistics},
+};
+
+use crate::cli::ProverCLIConfig;
+
+const ROUNDS: [AggregationRound; 5] = [
+    AggregationRound::BasicCircuits,
+    AggregationRound::LeafAggregation,
+    AggregationRound::NodeAggregation,
+    AggregationRound::RecursionTip,
+    AggregationRound::Scheduler,
+];
+/// Proving pipeline stages for a batch: witness generation for each round followed by proof compression.
+const STAGE_COUNT: usize = ROUNDS.len() + 1;
+/// Maximum number of latest batches loaded for each dashboard update.
+const MAX_LOADED_BATCHES: usize = 1_000;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// Interval between dashboard updates, in seconds.
+    #[clap(short, long, default_value_t = 10)]
+    interval: u64,
+    /// Time window (in hours) used to compute throughput and to estimate time to proof for pending batches.
+    #[clap(long, default_value_t = 24)]
+    history: u64,
+    /// Maximum number of pending batches to display.
+    #[clap(long, default_value_t = 20)]
+    max_batches: usize,
+    /// Prints the dashboard once and exits.
+    #[clap(long)]
+    once: bool,
+}
+
+pub async fn run(args: Args, config: ProverCLIConfig) -> anyhow::Result<()> {
+    anyhow::ensure!(args.history > 0, "history window must be positive");
+    let pool = ConnectionPool::<Prover>::singleton(config.db_url)
+        .build()
+        .await
+        .context("failed to build a prover_connection_pool")?;
+    let history = Duration::from_secs(args.history * 3_600);
+
+    loop {
+        let mut conn = pool
+            .connection()
+            .await
+            .context("failed to acquire a connection")?;
+        let snapshot = Snapshot::load(&mut conn, history).await?;
+        drop(conn);
+
+        if !args.once {
+            // Clear the terminal and move the cursor to the top left corner.
+            print!("\x1B[2J\x1B[H");
+        }
+        snapshot.print(args.max_batches);
+        if args.once {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_secs(args.interval)).await;
+    }
+}
+
+#[derive(Debug)]
+struct Snapshot {
+    history: Duration,
+    witness_generation: Vec<(AggregationRound, ExtendedJobCountStatistics)>,
+    prover_jobs: Vec<(AggregationRound, u8, ExtendedJobCountStatistics)>,
+    compression: ExtendedJobCountStatistics,
+    batches: Vec<BatchStageTimings>,
+}
+
+impl Snapshot {
+    async fn load(conn: &mut Connection<'_, Prover>, history: Duration) -> anyhow::Result<Self> {
+        let mut witness_generation = Vec::with_capacity(ROUNDS.len());
+        for round in ROUNDS {
+            let counts = conn
+                .fri_witness_generator_dal()
+                .get_witness_job_counts(round, history)
+                .await;
+            witness_generation.push((round, counts));
+        }
+        let prover_jobs = conn
+            .fri_prover_jobs_dal()
+            .get_circuit_job_counts(history)
+            .await;
+        let compression = conn
+            .fri_proof_compressor_dal()
+            .get_job_counts(history)
+            .await;
+        let batches = conn
+            .fri_witness_generator_dal()
+            .get_batch_stage_timings(history, MAX_LOADED_BATCHES)
+            .await;
+
+        Ok(Self {
+            history,
+            witness_generation,
+            prover_jobs,
+            compression,
+            batches,
+        })
+    }
+
+    fn print(&self, max_batches: usize) {
+        println!(
+            " ====== Prover Watch ({}, throughput over the last {}) ====== ",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            format_duration(self.history)
+        );
+
+        println!("\n-- Witness Generation --");
+        print_counts_header("Round");
+        for (round, counts) in &self.witness_generation {
+            self.print_counts(&round.to_string(), counts);
+        }
+
+        println!("\n-- Prover Jobs --");
+        print_counts_header("Round / circuit");
+        for (round, circuit_id, counts) in &self.prover_jobs {
+            self.print_counts(&format!("{round} / {circuit_id}"), counts);
+        }
+
+        println!("\n-- Proof Compression --");
+        print_counts_header("");
+        self.print_counts("", &self.compression);
+
+        self.print_pending_batches(max_batches);
+    }
+
+    fn print_counts(&self, label: &str, counts: &ExtendedJobCountStatistics) {
+        let failed = format!("{:>8}", counts.failed);
+        let failed = if counts.failed > 0 {
+            failed.red()
+        } else {
+            failed.normal()
+        };
+        let throughput = counts.successful as f64 / (self.history.as_secs_f64() / 3_600.0);
+        println!(
+            "{label:<24}{:>8}{:>13}{failed}{throughput:>12.1}",
+            counts.queued, counts.in_progress
+        );
+    }
+
+    fn print_pending_batches(&self, max_batches: usize) {
+        let durations = StageDurations::new(&self.batches);
+        let pending_batches: Vec<_> = self
+            .batches
+            .iter()
+            .filter(|batch| batch.compression.is_none())
+            .collect();
+
+        println!("\n-- Pending Batches ({}) --", pending_batches.len());
+        if pending_batches.is_empty() {
+            return;
+        }
+        println!(
+            "{:<12}{:>12}  {:<20}{:>20}",
+            "Batch", "Age", "Stage", "ETA to proof"
+        );
+        for batch in pending_batches.iter().take(max_batches) {
+            let completed_stages = completed_stage_count(batch);
+            let stage = ROUNDS
+                .get(completed_stages)
+                .map_or_else(|| "compression".to_owned(), ToString::to_string);
+            let eta = match durations.eta(batch) {
+                None => "n/a".normal(),
+                Some(Ok(eta)) => format!("~{}", format_duration(eta)).normal(),
+                Some(Err(overdue)) => format!("overdue by {}", format_duration(overdue)).yellow(),
+            };
+            println!(
+                "{:<12}{:>12}  {stage:<20}{eta:>20}",
+                batch.l1_batch_number.to_string(),
+                format_duration(batch.age)
+            );
+        }
+        if pending_batches.len() > max_batches {
+            println!("... and {} more", pending_batches.len() - max_batches);
+        }
+    }
+}
+
+fn print_counts_header(label: &str) {
+    println!(
+        "{label:<24}{:>8}{:>13}{:>8}{:>12}",
+        "Queued", "In progress", "Failed", "Done / h"
+    );
+}
+
+/// Returns completion times of all pipeline stages for the batch, measured from the moment its witness inputs were saved.
+fn stage_completions(batch: &BatchStageTimings) -> [Option<Duration>; STAGE_COUNT] {
+    let [basic, leaf, node, recursion_tip, scheduler] = batch.witness_generation;
+    [
+        basic,
+        leaf,
+        node,
+        recursion_tip,
+        scheduler,
+        batch.compression,
+    ]
+}
+
+/// Stages are completed in order, so this is also the index of the stage the batch is currently at.
+fn completed_stage_count(batch: &BatchStageTimings) -> usize {
+    stage_completions(batch)
+        .iter()
+        .take_while(|completion| completion.is_some())
+        .count()
+}
+
+/// Mean durations after which pipeline stages are completed, computed from fully proven batches.
+#[derive(Debug)]
+struct StageDurations([Option<Duration>; STAGE_COUNT]);
+
+impl StageDurations {
+    fn new(batches: &[BatchStageTimings]) -> Self {
+        let mut sums = [Duration::ZERO; STAGE_COUNT];
+        let mut counts = [0_u32; STAGE_COUNT];
+        let proven_batches = batches.iter().filter(|batch| batch.compression.is_some());
+        for batch in proven_batches {
+            for (i, completion) in stage_completions(batch).into_iter().enumerate() {
+                if let Some(completion) = completion {
+                    sums[i] += completion;
+                    counts[i] += 1;
+                }
+            }
+        }
+        Self(std::array::from_fn(|i| {
+            (counts[i] > 0).then(|| sums[i] / counts[i])
+        }))
+    }
+
+    /// Estimates the remaining time to proof for a pending batch. The estimate is based on the last completed stage:
+    /// the batch is expected to spend as much time on the remaining stages as proven batches did on average.
+    /// Returns `Err(_)` with the excess time if the batch is slower than expected, or `None` if there is not enough
+    /// historical data.
+    fn eta(&self, batch: &BatchStageTimings) -> Option<Result<Duration, Duration>> {
+        let total = self.0[STAGE_COUNT - 1]?;
+        let (expected_elapsed, elapsed) = match completed_stage_count(batch).checked_sub(1) {
+            None => (Duration::ZERO, Duration::ZERO),
+            Some(stage) => (self.0[stage]?, stage_completions(batch)[stage]?),
+        };
+        // Time spent on the current stage so far.
+        let current_stage_time = batch.age.saturating_sub(elapsed);
+        let remaining = total.saturating_sub(expected_elapsed);
+        Some(if remaining >= current_stage_time {
+            Ok(remaining - current_stage_time)
+        } else {
+            Err(current_stage_time - remaining)
+        })
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3_600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3_600, (secs / 60) % 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::L1BatchNumber;
+
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    fn batch(
+        number: u32,
+        age: Duration,
+        completions: [Option<Duration>; STAGE_COUNT],
+    ) -> BatchStageTimings {
+        let [basic, leaf, node, recursion_tip, scheduler, compression] = completions;
+        BatchStageTimings {
+            l1_batch_number: L1BatchNumber(number),
+            age,
+            witness_generation: [basic, leaf, node, recursion_tip, scheduler],
+            compression,
+        }
+    }
+
+    fn proven_batch(number: u32, stage_minutes: [u32; STAGE_COUNT]) -> BatchStageTimings {
+        let completions = stage_minutes.map(|minutes| Some(MINUTE * minutes));
+        batch(number, MINUTE * 1_000, completions)
+    }
+
+    fn pending_batch(number: u32, age: Duration, completed_minutes: &[u32]) -> BatchStageTimings {
+        let completions =
+            std::array::from_fn(|i| completed_minutes.get(i).map(|&minutes| MINUTE * minutes));
+        batch(number, age, completions)
+    }
+
+    #[test]
+    fn stage_durations_are_averaged_over_proven_batches() {
+        let batches = [
+            proven_batch(1, [10, 20, 30, 40, 50, 60]),
+            proven_batch(2, [20, 30, 40, 50, 60, 80]),
+            pending_batch(3, MINUTE * 500, &[500]),
+        ];
+        let durations = StageDurations::new(&batches);
+        assert_eq!(
+            durations.0,
+            [15, 25, 35, 45, 55, 70].map(|minutes| Some(MINUTE * minutes))
+        );
+    }
+
+    #[test]
+    fn eta_without_history() {
+        let durations = StageDurations::new(&[]);
+        let batch = pending_batch(1, MINUTE, &[]);
+        assert_eq!(durations.eta(&batch), None);
+
+        let batches = [pending_batch(1, MINUTE * 10, &[5, 8])];
+        let durations = StageDurations::new(&batches);
+        assert_eq!(durations.eta(&batches[0]), None);
+    }
+
+    #[test]
+    fn eta_for_batch_without_completed_stages() {
+        let durations = StageDurations::new(&[proven_batch(1, [10, 20, 30, 40, 50, 60])]);
+        let batch = pending_batch(2, MINUTE * 5, &[]);
+        assert_eq!(durations.eta(&batch), Some(Ok(MINUTE * 55)));
+
+        let batch = pending_batch(2, MINUTE * 75, &[]);
+        assert_eq!(durations.eta(&batch), Some(Err(MINUTE * 15)));
+    }
+
+    #[test]
+    fn eta_for_batch_on_track() {
+        let durations = StageDurations::new(&[proven_batch(1, [10, 20, 30, 40, 50, 60])]);
+        // The batch has completed leaf aggregation after 15 minutes and spent 10 more minutes on node aggregation.
+        let batch = pending_batch(2, MINUTE * 25, &[5, 15]);
+        assert_eq!(durations.eta(&batch), Some(Ok(MINUTE * 30)));
+    }
+
+    #[test]
+    fn eta_for_overdue_batch() {
+        let durations = StageDurations::new(&[proven_batch(1, [10, 20, 30, 40, 50, 60])]);
+        let batch = pending_batch(2, MINUTE * 80, &[5, 15, 25, 35, 45]);
+        assert_eq!(durations.eta(&batch), Some(Err(MINUTE * 25)));
+    }
+}
@@ -1,7 +1,16 @@
+use std::sync::Arc;
+
 use assert_cmd::Command;
+use chrono::Utc;
 use zksync_dal::ConnectionPool;
+use zksync_object_store::{Bucket, FileBackedObjectStore, ObjectStore};
 use zksync_prover_dal::{Prover, ProverDal};
-use zksync_types::protocol_version::{L1VerifierConfig, ProtocolSemanticVersion};
+use zksync_prover_fri_utils::artifact_integrity::IntegrityCheckedObjectStore;
+use zksync_types::{
+    basic_fri_types::AggregationRound,
+    protocol_version::{L1VerifierConfig, ProtocolSemanticVersion},
+    L1BatchNumber,
+};
 
 #[test]
 #[doc = "prover_cli"]
@@ -40,3 +49,94 @@ async fn pli_config_succeeds() {
         .assert()
         .success();
 }
+
+#[tokio::test]
+#[doc = "prover_cli watch --once"]
+async fn pli_watch_once_succeeds() {
+    let connection_pool = ConnectionPool::<Prover>::prover_test_pool().await;
+
+    Command::cargo_bin("prover_cli")
+        .unwrap()
+        .arg(connection_pool.database_url().expose_str())
+        .arg("watch")
+        .arg("--once")
+        .assert()
+        .success();
+}
+
+#[tokio::test]
+#[doc = "prover_cli verify-artifacts"]
+async fn pli_verify_artifacts_detects_corrupted_and_missing_artifacts() {
+    let connection_pool = ConnectionPool::<Prover>::prover_test_pool().await;
+    let mut connection = connection_pool.connection().await.unwrap();
+    connection
+        .fri_protocol_versions_dal()
+        .save_prover_protocol_version(
+            ProtocolSemanticVersion::default(),
+            L1VerifierConfig::default(),
+        )
+        .await;
+    connection
+        .fri_prover_jobs_dal()
+        .insert_prover_jobs(
+            L1BatchNumber(1),
+            vec![
+                (1, "circuit_1.bin".to_owned()),
+                (2, "circuit_2.bin".to_owned()),
+            ],
+            AggregationRound::BasicCircuits,
+            0,
+            ProtocolSemanticVersion::default(),
+            Utc::now(),
+        )
+        .await;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let object_store_path = dir.path().to_str().unwrap().to_owned();
+    let inner: Arc<dyn ObjectStore> = Arc::new(
+        FileBackedObjectStore::new(object_store_path.clone())
+            .await
+            .unwrap(),
+    );
+    let store = IntegrityCheckedObjectStore::new(inner.clone(), connection_pool.clone());
+    for key in ["circuit_1.bin", "circuit_2.bin"] {
+        store
+            .put_raw(Bucket::ProverJobsFri, key, vec![1, 2, 3])
+            .await
+            .unwrap();
+    }
+
+    let verify_artifacts = || {
+        let mut command = Command::cargo_bin("prover_cli").unwrap();
+        command
+            .arg(connection_pool.database_url().expose_str())
+            .arg("verify-artifacts")
+            .arg("-n")
+            .arg("1")
+            .arg("--object-store-path")
+            .arg(&object_store_path);
+        command
+    };
+    verify_artifacts()
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("2 ok"));
+
+    inner
+        .put_raw(Bucket::ProverJobsFri, "circuit_1.bin", vec![1, 2, 4])
+        .await
+        .unwrap();
+    inner
+        .remove_raw(Bucket::ProverJobsFri, "circuit_2.bin")
+        .await
+        .unwrap();
+    verify_artifacts()
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains(
+            "prover_jobs_fri/circuit_1.bin: corrupted",
+        ))
+        .stdout(predicates::str::contains(
+            "prover_jobs_fri/circuit_2.bin: missing",
+        ));
+}
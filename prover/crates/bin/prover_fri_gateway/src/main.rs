@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use clap::Parser;
@@ -8,8 +8,9 @@ use tokio::sync::{oneshot, watch};
 use traits::PeriodicApi as _;
 use zksync_core_leftovers::temp_config_store::{load_database_secrets, load_general_config};
 use zksync_env_config::object_store::ProverObjectStoreConfig;
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_prover_dal::{ConnectionPool, Prover};
+use zksync_prover_fri_utils::artifact_integrity::IntegrityCheckedObjectStore;
 use zksync_task_management::ManagedTasks;
 use zksync_vlog::prometheus::PrometheusExporterConfig;
 
@@ -51,17 +52,14 @@ async fn main() -> anyhow::Result<()> {
             .context("object store")?,
     );
     let store_factory = ObjectStoreFactory::new(object_store_config.0);
-
-    let proof_submitter = ProofSubmitter::new(
+    let blob_store: Arc<dyn ObjectStore> = Arc::new(IntegrityCheckedObjectStore::new(
         store_factory.create_store().await?,
-        config.api_url.clone(),
         pool.clone(),
-    );
-    let proof_gen_data_fetcher = ProofGenDataFetcher::new(
-        store_factory.create_store().await?,
-        config.api_url.clone(),
-        pool,
-    );
+    ));
+
+    let proof_submitter =
+        ProofSubmitter::new(blob_store.clone(), config.api_url.clone(), pool.clone());
+    let proof_gen_data_fetcher = ProofGenDataFetcher::new(blob_store, config.api_url.clone(), pool);
 
     let (stop_sender, stop_receiver) = watch::channel(false);
 
@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+use zksync_prover_dal::{Connection, Prover, ProverDal};
+
+use crate::{metrics::PROVER_JOB_MONITOR_METRICS, task_wiring::Task};
+
+/// `BatchPriorityAger` is a task that raises priority classes of queued jobs for batches sealed longer than
+/// the aging interval ago, so that batches with the normal or low priority class are not starved by urgent ones.
+/// Jobs are ordered by their stored priority class, so aging must be applied periodically.
+#[derive(Debug)]
+pub struct BatchPriorityAger;
+
+#[async_trait]
+impl Task for BatchPriorityAger {
+    async fn invoke(&self, connection: &mut Connection<Prover>) -> anyhow::Result<()> {
+        let aged_jobs = connection
+            .fri_batch_priorities_dal()
+            .age_priority_classes()
+            .await;
+        if aged_jobs > 0 {
+            tracing::info!("Raised priority class of {aged_jobs} jobs for aged batches");
+        }
+        PROVER_JOB_MONITOR_METRICS.aged_jobs.inc_by(aged_jobs);
+        Ok(())
+    }
+}
@@ -14,6 +14,7 @@ use zksync_prover_dal::{ConnectionPool, Prover};
 use zksync_prover_job_monitor::{
     attempts_reporter::ProverJobAttemptsReporter,
     autoscaler_queue_reporter::get_queue_reporter_router,
+    batch_priority_ager::BatchPriorityAger,
     job_requeuer::{ProofCompressorJobRequeuer, ProverJobRequeuer, WitnessGeneratorJobRequeuer},
     prover_jobs_archiver::ProverJobsArchiver,
     queue_reporter::{
@@ -207,6 +208,13 @@ fn get_tasks(
         witness_job_queuer,
     );
 
+    // batch priority ager
+    task_runner.add(
+        "BatchPriorityAger",
+        prover_job_monitor_config.batch_priority_ager_run_interval(),
+        BatchPriorityAger,
+    );
+
     // Reporter for reaching max attempts of jobs
     let attempts_reporter = ProverJobAttemptsReporter {
         prover_config: prover_config.clone(),
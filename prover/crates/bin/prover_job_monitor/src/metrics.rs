@@ -6,6 +6,8 @@ use zksync_types::protocol_version::ProtocolSemanticVersion;
 pub(crate) struct ProverJobMonitorMetrics {
     pub prover_job_archived: Counter,
     pub gpu_prover_archived: Counter,
+    /// Number of jobs with the priority class raised because their batch has aged.
+    pub aged_jobs: Counter,
     #[metrics(labels = ["job_type"])]
     pub reached_max_attempts: LabeledFamily<JobType, Gauge>,
 }
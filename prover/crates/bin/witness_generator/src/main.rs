@@ -1,7 +1,10 @@
 #![allow(incomplete_features)] // We have to use generic const exprs.
 #![feature(generic_const_exprs)]
 
-use std::time::{Duration, Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::{anyhow, Context as _};
 use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
@@ -11,9 +14,10 @@ use structopt::StructOpt;
 use tokio::sync::watch;
 use zksync_core_leftovers::temp_config_store::{load_database_secrets, load_general_config};
 use zksync_env_config::object_store::ProverObjectStoreConfig;
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_prover_dal::{ConnectionPool, Prover, ProverDal};
 use zksync_prover_fri_types::PROVER_PROTOCOL_SEMANTIC_VERSION;
+use zksync_prover_fri_utils::artifact_integrity::IntegrityCheckedObjectStore;
 use zksync_prover_keystore::keystore::Keystore;
 use zksync_queued_job_processor::JobProcessor;
 use zksync_task_management::ManagedTasks;
@@ -30,6 +34,10 @@ use zksync_witness_generator::{
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
 
+/// Number of DB connections used to check and record content hashes of artifacts. Artifacts are read and written
+/// concurrently (up to `max_circuits_in_flight` at a time), but each connection is only held for a single query.
+const ARTIFACT_HASH_CONNECTIONS: u32 = 10;
+
 #[derive(Debug, StructOpt)]
 #[structopt(
     name = "Run witness generator for different aggregation round",
@@ -143,18 +151,6 @@ async fn main() -> anyhow::Result<()> {
         PrometheusExporterConfig::pull(prometheus_listener_port)
     };
 
-    let connection_pool = ConnectionPool::<Prover>::singleton(database_secrets.prover_url()?)
-        .build()
-        .await
-        .context("failed to build a prover_connection_pool")?;
-    let (stop_sender, stop_receiver) = watch::channel(false);
-
-    let protocol_version = PROVER_PROTOCOL_SEMANTIC_VERSION;
-
-    ensure_protocol_alignment(&connection_pool, protocol_version, &keystore)
-        .await
-        .unwrap_or_else(|err| panic!("Protocol alignment check failed: {:?}", err));
-
     let rounds = match (opt.round, opt.all_rounds) {
         (Some(round), false) => vec![round],
         (None, true) => vec![
@@ -176,6 +172,25 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
+    // One connection per job processor, plus connections for checking and recording artifact hashes.
+    let max_connections = rounds.len() as u32 + ARTIFACT_HASH_CONNECTIONS;
+    let connection_pool =
+        ConnectionPool::<Prover>::builder(database_secrets.prover_url()?, max_connections)
+            .build()
+            .await
+            .context("failed to build a prover_connection_pool")?;
+    let object_store: Arc<dyn ObjectStore> = Arc::new(IntegrityCheckedObjectStore::new(
+        store_factory.create_store().await?,
+        connection_pool.clone(),
+    ));
+    let (stop_sender, stop_receiver) = watch::channel(false);
+
+    let protocol_version = PROVER_PROTOCOL_SEMANTIC_VERSION;
+
+    ensure_protocol_alignment(&connection_pool, protocol_version, &keystore)
+        .await
+        .unwrap_or_else(|err| panic!("Protocol alignment check failed: {:?}", err));
+
     let prometheus_task = prometheus_exporter_config.run(stop_receiver.clone());
 
     let mut tasks = Vec::new();
@@ -193,7 +208,7 @@ async fn main() -> anyhow::Result<()> {
             AggregationRound::BasicCircuits => {
                 let generator = WitnessGenerator::<BasicCircuits>::new(
                     config.clone(),
-                    store_factory.create_store().await?,
+                    object_store.clone(),
                     connection_pool.clone(),
                     protocol_version,
                     keystore.clone(),
@@ -203,7 +218,7 @@ async fn main() -> anyhow::Result<()> {
             AggregationRound::LeafAggregation => {
                 let generator = WitnessGenerator::<LeafAggregation>::new(
                     config.clone(),
-                    store_factory.create_store().await?,
+                    object_store.clone(),
                     connection_pool.clone(),
                     protocol_version,
                     keystore.clone(),
@@ -213,7 +228,7 @@ async fn main() -> anyhow::Result<()> {
             AggregationRound::NodeAggregation => {
                 let generator = WitnessGenerator::<NodeAggregation>::new(
                     config.clone(),
-                    store_factory.create_store().await?,
+                    object_store.clone(),
                     connection_pool.clone(),
                     protocol_version,
                     keystore.clone(),
@@ -223,7 +238,7 @@ async fn main() -> anyhow::Result<()> {
             AggregationRound::RecursionTip => {
                 let generator = WitnessGenerator::<RecursionTip>::new(
                     config.clone(),
-                    store_factory.create_store().await?,
+                    object_store.clone(),
                     connection_pool.clone(),
                     protocol_version,
                     keystore.clone(),
@@ -233,7 +248,7 @@ async fn main() -> anyhow::Result<()> {
             AggregationRound::Scheduler => {
                 let generator = WitnessGenerator::<Scheduler>::new(
                     config.clone(),
-                    store_factory.create_store().await?,
+                    object_store.clone(),
                     connection_pool.clone(),
                     protocol_version,
                     keystore.clone(),
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS batch_priorities_fri
+(
+    l1_batch_number BIGINT PRIMARY KEY,
+    priority_class  INTEGER   NOT NULL,
+    created_at      TIMESTAMP NOT NULL,
+    updated_at      TIMESTAMP NOT NULL
+);
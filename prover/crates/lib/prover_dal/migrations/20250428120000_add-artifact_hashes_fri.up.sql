@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS artifact_hashes_fri
+(
+    bucket       TEXT      NOT NULL,
+    object_key   TEXT      NOT NULL,
+    content_hash BYTEA     NOT NULL,
+    created_at   TIMESTAMP NOT NULL,
+    updated_at   TIMESTAMP NOT NULL,
+    PRIMARY KEY (bucket, object_key)
+);
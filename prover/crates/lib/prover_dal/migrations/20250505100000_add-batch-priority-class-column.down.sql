@@ -0,0 +1,57 @@
+DROP TRIGGER IF EXISTS batch_priorities_fri_propagate ON batch_priorities_fri;
+DROP TRIGGER IF EXISTS witness_inputs_fri_priority_class ON witness_inputs_fri;
+DROP TRIGGER IF EXISTS leaf_aggregation_witness_jobs_fri_priority_class ON leaf_aggregation_witness_jobs_fri;
+DROP TRIGGER IF EXISTS node_aggregation_witness_jobs_fri_priority_class ON node_aggregation_witness_jobs_fri;
+DROP TRIGGER IF EXISTS recursion_tip_witness_jobs_fri_priority_class ON recursion_tip_witness_jobs_fri;
+DROP TRIGGER IF EXISTS scheduler_witness_jobs_fri_priority_class ON scheduler_witness_jobs_fri;
+DROP TRIGGER IF EXISTS proof_compression_jobs_fri_priority_class ON proof_compression_jobs_fri;
+DROP TRIGGER IF EXISTS prover_jobs_fri_priority_class ON prover_jobs_fri;
+DROP FUNCTION IF EXISTS age_batch_priority_classes;
+DROP FUNCTION IF EXISTS propagate_batch_priority_class;
+DROP FUNCTION IF EXISTS set_job_batch_priority_class;
+DROP FUNCTION IF EXISTS effective_batch_priority_class;
+DROP TABLE IF EXISTS batch_priority_settings_fri;
+
+DROP INDEX IF EXISTS idx_witness_inputs_fri_priority;
+DROP INDEX IF EXISTS idx_leaf_aggregation_witness_jobs_fri_priority;
+DROP INDEX IF EXISTS idx_node_aggregation_witness_jobs_fri_priority;
+DROP INDEX IF EXISTS idx_recursion_tip_witness_jobs_fri_priority;
+DROP INDEX IF EXISTS idx_scheduler_witness_jobs_fri_priority;
+DROP INDEX IF EXISTS idx_proof_compression_jobs_fri_priority;
+DROP INDEX IF EXISTS idx_prover_jobs_fri_priority;
+
+ALTER TABLE witness_inputs_fri DROP COLUMN IF EXISTS priority_class;
+ALTER TABLE leaf_aggregation_witness_jobs_fri DROP COLUMN IF EXISTS priority_class;
+ALTER TABLE node_aggregation_witness_jobs_fri DROP COLUMN IF EXISTS priority_class;
+ALTER TABLE recursion_tip_witness_jobs_fri DROP COLUMN IF EXISTS priority_class;
+ALTER TABLE scheduler_witness_jobs_fri DROP COLUMN IF EXISTS priority_class;
+ALTER TABLE proof_compression_jobs_fri DROP COLUMN IF EXISTS priority_class;
+ALTER TABLE prover_jobs_fri DROP COLUMN IF EXISTS priority_class;
+
+CREATE INDEX IF NOT EXISTS idx_witness_inputs_fri_priority
+    ON witness_inputs_fri USING btree (priority, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_leaf_aggregation_witness_jobs_fri_priority
+    ON leaf_aggregation_witness_jobs_fri USING btree (priority, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_node_aggregation_witness_jobs_fri_priority
+    ON node_aggregation_witness_jobs_fri USING btree (priority, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_recursion_tip_witness_jobs_fri_priority
+    ON recursion_tip_witness_jobs_fri USING btree (priority, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_scheduler_witness_jobs_fri_priority
+    ON scheduler_witness_jobs_fri USING btree (priority, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_proof_compression_jobs_fri_priority
+    ON proof_compression_jobs_fri USING btree (priority, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_prover_jobs_fri_priority
+    ON prover_jobs_fri USING btree (priority, batch_sealed_at, aggregation_round, circuit_id)
+    WHERE (status = 'queued'::text);
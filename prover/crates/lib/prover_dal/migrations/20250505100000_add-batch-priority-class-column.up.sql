@@ -0,0 +1,196 @@
+-- Stores the priority class of the batch directly in job tables, so that picking a job doesn't require
+-- a lookup in `batch_priorities_fri` for each queued row.
+ALTER TABLE witness_inputs_fri ADD COLUMN IF NOT EXISTS priority_class INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE leaf_aggregation_witness_jobs_fri ADD COLUMN IF NOT EXISTS priority_class INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE node_aggregation_witness_jobs_fri ADD COLUMN IF NOT EXISTS priority_class INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE recursion_tip_witness_jobs_fri ADD COLUMN IF NOT EXISTS priority_class INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE scheduler_witness_jobs_fri ADD COLUMN IF NOT EXISTS priority_class INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE proof_compression_jobs_fri ADD COLUMN IF NOT EXISTS priority_class INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE prover_jobs_fri ADD COLUMN IF NOT EXISTS priority_class INTEGER NOT NULL DEFAULT 0;
+
+-- Single-row table with settings shared by all components picking jobs.
+CREATE TABLE IF NOT EXISTS batch_priority_settings_fri
+(
+    id                   BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    aging_interval_secs  BIGINT    NOT NULL,
+    updated_at           TIMESTAMP NOT NULL
+);
+INSERT INTO batch_priority_settings_fri (aging_interval_secs, updated_at)
+VALUES (2 * 60 * 60, NOW())
+ON CONFLICT DO NOTHING;
+
+-- Priority class stored for jobs. Jobs for batches sealed longer than the aging interval ago are treated
+-- as if the batch was urgent, so that batches with the normal or low class still progress. Since the class
+-- of a job depends on the current time, it's periodically re-evaluated by `age_batch_priority_classes()`.
+CREATE OR REPLACE FUNCTION effective_batch_priority_class(priority_class INTEGER, batch_sealed_at TIMESTAMP)
+RETURNS INTEGER AS $$
+    SELECT
+        CASE
+            WHEN batch_sealed_at <= NOW() - MAKE_INTERVAL(secs => (
+                SELECT aging_interval_secs FROM batch_priority_settings_fri
+            )) THEN 1
+            ELSE priority_class
+        END
+$$ LANGUAGE sql STABLE;
+
+-- Copies the batch priority class to newly created jobs.
+CREATE OR REPLACE FUNCTION set_job_batch_priority_class() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.priority_class := effective_batch_priority_class(
+        COALESCE(
+            (SELECT priority_class FROM batch_priorities_fri WHERE l1_batch_number = NEW.l1_batch_number),
+            0
+        ),
+        NEW.batch_sealed_at
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Propagates changes of batch priority classes to existing jobs.
+CREATE OR REPLACE FUNCTION propagate_batch_priority_class() RETURNS TRIGGER AS $$
+DECLARE
+    batch_number BIGINT;
+    class INTEGER;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        batch_number := OLD.l1_batch_number;
+        class := 0;
+    ELSE
+        batch_number := NEW.l1_batch_number;
+        class := NEW.priority_class;
+    END IF;
+
+    UPDATE witness_inputs_fri
+        SET priority_class = effective_batch_priority_class(class, batch_sealed_at)
+        WHERE l1_batch_number = batch_number;
+    UPDATE leaf_aggregation_witness_jobs_fri
+        SET priority_class = effective_batch_priority_class(class, batch_sealed_at)
+        WHERE l1_batch_number = batch_number;
+    UPDATE node_aggregation_witness_jobs_fri
+        SET priority_class = effective_batch_priority_class(class, batch_sealed_at)
+        WHERE l1_batch_number = batch_number;
+    UPDATE recursion_tip_witness_jobs_fri
+        SET priority_class = effective_batch_priority_class(class, batch_sealed_at)
+        WHERE l1_batch_number = batch_number;
+    UPDATE scheduler_witness_jobs_fri
+        SET priority_class = effective_batch_priority_class(class, batch_sealed_at)
+        WHERE l1_batch_number = batch_number;
+    UPDATE proof_compression_jobs_fri
+        SET priority_class = effective_batch_priority_class(class, batch_sealed_at)
+        WHERE l1_batch_number = batch_number;
+    UPDATE prover_jobs_fri
+        SET priority_class = effective_batch_priority_class(class, batch_sealed_at)
+        WHERE l1_batch_number = batch_number;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Raises the class of queued jobs for batches that have aged since the class was set. Returns the number
+-- of updated jobs.
+CREATE OR REPLACE FUNCTION age_batch_priority_classes() RETURNS BIGINT AS $$
+DECLARE
+    aged_before TIMESTAMP;
+    total BIGINT := 0;
+    updated BIGINT;
+BEGIN
+    aged_before := NOW() - MAKE_INTERVAL(secs => (SELECT aging_interval_secs FROM batch_priority_settings_fri));
+
+    UPDATE witness_inputs_fri SET priority_class = 1
+        WHERE status = 'queued' AND priority_class < 1 AND batch_sealed_at <= aged_before;
+    GET DIAGNOSTICS updated = ROW_COUNT;
+    total := total + updated;
+    UPDATE leaf_aggregation_witness_jobs_fri SET priority_class = 1
+        WHERE status = 'queued' AND priority_class < 1 AND batch_sealed_at <= aged_before;
+    GET DIAGNOSTICS updated = ROW_COUNT;
+    total := total + updated;
+    UPDATE node_aggregation_witness_jobs_fri SET priority_class = 1
+        WHERE status = 'queued' AND priority_class < 1 AND batch_sealed_at <= aged_before;
+    GET DIAGNOSTICS updated = ROW_COUNT;
+    total := total + updated;
+    UPDATE recursion_tip_witness_jobs_fri SET priority_class = 1
+        WHERE status = 'queued' AND priority_class < 1 AND batch_sealed_at <= aged_before;
+    GET DIAGNOSTICS updated = ROW_COUNT;
+    total := total + updated;
+    UPDATE scheduler_witness_jobs_fri SET priority_class = 1
+        WHERE status = 'queued' AND priority_class < 1 AND batch_sealed_at <= aged_before;
+    GET DIAGNOSTICS updated = ROW_COUNT;
+    total := total + updated;
+    UPDATE proof_compression_jobs_fri SET priority_class = 1
+        WHERE status = 'queued' AND priority_class < 1 AND batch_sealed_at <= aged_before;
+    GET DIAGNOSTICS updated = ROW_COUNT;
+    total := total + updated;
+    UPDATE prover_jobs_fri SET priority_class = 1
+        WHERE status = 'queued' AND priority_class < 1 AND batch_sealed_at <= aged_before;
+    GET DIAGNOSTICS updated = ROW_COUNT;
+    total := total + updated;
+
+    RETURN total;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER batch_priorities_fri_propagate
+    AFTER INSERT OR UPDATE OR DELETE ON batch_priorities_fri
+    FOR EACH ROW EXECUTE FUNCTION propagate_batch_priority_class();
+
+CREATE TRIGGER witness_inputs_fri_priority_class
+    BEFORE INSERT ON witness_inputs_fri
+    FOR EACH ROW EXECUTE FUNCTION set_job_batch_priority_class();
+CREATE TRIGGER leaf_aggregation_witness_jobs_fri_priority_class
+    BEFORE INSERT ON leaf_aggregation_witness_jobs_fri
+    FOR EACH ROW EXECUTE FUNCTION set_job_batch_priority_class();
+CREATE TRIGGER node_aggregation_witness_jobs_fri_priority_class
+    BEFORE INSERT ON node_aggregation_witness_jobs_fri
+    FOR EACH ROW EXECUTE FUNCTION set_job_batch_priority_class();
+CREATE TRIGGER recursion_tip_witness_jobs_fri_priority_class
+    BEFORE INSERT ON recursion_tip_witness_jobs_fri
+    FOR EACH ROW EXECUTE FUNCTION set_job_batch_priority_class();
+CREATE TRIGGER scheduler_witness_jobs_fri_priority_class
+    BEFORE INSERT ON scheduler_witness_jobs_fri
+    FOR EACH ROW EXECUTE FUNCTION set_job_batch_priority_class();
+CREATE TRIGGER proof_compression_jobs_fri_priority_class
+    BEFORE INSERT ON proof_compression_jobs_fri
+    FOR EACH ROW EXECUTE FUNCTION set_job_batch_priority_class();
+CREATE TRIGGER prover_jobs_fri_priority_class
+    BEFORE INSERT ON prover_jobs_fri
+    FOR EACH ROW EXECUTE FUNCTION set_job_batch_priority_class();
+
+-- Backfill classes for existing jobs.
+UPDATE batch_priorities_fri SET updated_at = updated_at;
+
+-- Indices matching the job ordering.
+DROP INDEX IF EXISTS idx_witness_inputs_fri_priority;
+DROP INDEX IF EXISTS idx_leaf_aggregation_witness_jobs_fri_priority;
+DROP INDEX IF EXISTS idx_node_aggregation_witness_jobs_fri_priority;
+DROP INDEX IF EXISTS idx_recursion_tip_witness_jobs_fri_priority;
+DROP INDEX IF EXISTS idx_scheduler_witness_jobs_fri_priority;
+DROP INDEX IF EXISTS idx_proof_compression_jobs_fri_priority;
+DROP INDEX IF EXISTS idx_prover_jobs_fri_priority;
+
+CREATE INDEX IF NOT EXISTS idx_witness_inputs_fri_priority
+    ON witness_inputs_fri USING btree (priority_class DESC, priority DESC, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_leaf_aggregation_witness_jobs_fri_priority
+    ON leaf_aggregation_witness_jobs_fri USING btree (priority_class DESC, priority DESC, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_node_aggregation_witness_jobs_fri_priority
+    ON node_aggregation_witness_jobs_fri USING btree (priority_class DESC, priority DESC, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_recursion_tip_witness_jobs_fri_priority
+    ON recursion_tip_witness_jobs_fri USING btree (priority_class DESC, priority DESC, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_scheduler_witness_jobs_fri_priority
+    ON scheduler_witness_jobs_fri USING btree (priority_class DESC, priority DESC, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_proof_compression_jobs_fri_priority
+    ON proof_compression_jobs_fri USING btree (priority_class DESC, priority DESC, batch_sealed_at)
+    WHERE (status = 'queued'::text);
+
+CREATE INDEX IF NOT EXISTS idx_prover_jobs_fri_priority
+    ON prover_jobs_fri USING btree (priority_class DESC, priority DESC, batch_sealed_at, aggregation_round, circuit_id)
+    WHERE (status = 'queued'::text);
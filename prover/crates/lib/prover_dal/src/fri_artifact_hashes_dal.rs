@@ -0,0 +1,449 @@
+//! Content hashes of artifacts stored in the prover object store. Hashes are recorded when artifacts are
+//! written and checked when they are read, so that corrupted artifacts are detected before being used.
+use std::{collections::HashMap, str::FromStr};
+
+use zksync_basic_types::{
+    prover_dal::{ProverArtifact, ProverArtifactKind},
+    L1BatchNumber,
+};
+use zksync_db_connection::connection::Connection;
+
+use crate::Prover;
+
+#[derive(Debug)]
+pub struct FriArtifactHashesDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Prover>,
+}
+
+impl FriArtifactHashesDal<'_, '_> {
+    /// Records the content hash of an artifact. Overwrites the previous hash if the artifact was rewritten.
+    pub async fn insert_artifact_hash(
+        &mut self,
+        bucket: &str,
+        object_key: &str,
+        content_hash: &[u8],
+    ) {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+            artifact_hashes_fri (
+                bucket,
+                object_key,
+                content_hash,
+                created_at,
+                updated_at
+            )
+            VALUES
+            ($1, $2, $3, NOW(), NOW())
+            ON CONFLICT (bucket, object_key) DO
+            UPDATE
+            SET
+            content_hash = excluded.content_hash,
+            updated_at = NOW()
+            "#,
+            bucket,
+            object_key,
+            content_hash,
+        )
+        .execute(self.storage.conn())
+        .await
+        .expect("failed to insert artifact hash");
+    }
+
+    /// Returns the content hash of an artifact, or `None` if no hash was recorded for it
+    /// (e.g., if the artifact was written before hashes were introduced).
+    pub async fn get_artifact_hash(&mut self, bucket: &str, object_key: &str) -> Option<Vec<u8>> {
+        sqlx::query!(
+            r#"
+            SELECT
+                content_hash
+            FROM
+                artifact_hashes_fri
+            WHERE
+                bucket = $1
+                AND object_key = $2
+            "#,
+            bucket,
+            object_key,
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .expect("failed to get artifact hash")
+        .map(|row| row.content_hash)
+    }
+
+    /// Returns content hashes recorded for the specified artifacts in the bucket, keyed by the object key.
+    pub async fn get_artifact_hashes(
+        &mut self,
+        bucket: &str,
+        object_keys: &[String],
+    ) -> HashMap<String, Vec<u8>> {
+        sqlx::query!(
+            r#"
+            SELECT
+                object_key,
+                content_hash
+            FROM
+                artifact_hashes_fri
+            WHERE
+                bucket = $1
+                AND object_key = ANY($2)
+            "#,
+            bucket,
+            object_keys,
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .expect("failed to get artifact hashes")
+        .into_iter()
+        .map(|row| (row.object_key, row.content_hash))
+        .collect()
+    }
+
+    pub async fn delete_artifact_hash(&mut self, bucket: &str, object_key: &str) {
+        sqlx::query!(
+            r#"
+            DELETE FROM artifact_hashes_fri
+            WHERE
+                bucket = $1
+                AND object_key = $2
+            "#,
+            bucket,
+            object_key,
+        )
+        .execute(self.storage.conn())
+        .await
+        .expect("failed to delete artifact hash");
+    }
+
+    /// Deletes content hashes of all artifacts referenced by jobs of the batch. Must be called before the jobs
+    /// themselves are deleted.
+    pub async fn delete_batch_data(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<sqlx::postgres::PgQueryResult> {
+        sqlx::query!(
+            r#"
+            DELETE FROM artifact_hashes_fri AS hashes
+            USING (
+                SELECT
+                    'witness_inputs' AS bucket,
+                    witness_inputs_blob_url AS object_key
+                FROM
+                    witness_inputs_fri
+                WHERE
+                    l1_batch_number = $1
+                    AND witness_inputs_blob_url IS NOT NULL
+                UNION ALL
+                SELECT
+                    'leaf_aggregation_witness_jobs_fri',
+                    closed_form_inputs_blob_url
+                FROM
+                    leaf_aggregation_witness_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                    AND closed_form_inputs_blob_url IS NOT NULL
+                UNION ALL
+                SELECT
+                    'node_aggregation_witness_jobs_fri',
+                    aggregations_url
+                FROM
+                    node_aggregation_witness_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                    AND aggregations_url IS NOT NULL
+                UNION ALL
+                SELECT
+                    'scheduler_witness_jobs_fri',
+                    scheduler_partial_input_blob_url
+                FROM
+                    scheduler_witness_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                UNION ALL
+                SELECT
+                    'prover_jobs_fri',
+                    circuit_blob_url
+                FROM
+                    prover_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                UNION ALL
+                SELECT
+                    'proofs_fri',
+                    proof_blob_url
+                FROM
+                    prover_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                    AND proof_blob_url IS NOT NULL
+                UNION ALL
+                SELECT
+                    'proofs_fri',
+                    l1_proof_blob_url
+                FROM
+                    proof_compression_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                    AND l1_proof_blob_url IS NOT NULL
+            ) AS artifacts
+            WHERE
+                hashes.bucket = artifacts.bucket
+                AND hashes.object_key = artifacts.object_key
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .execute(self.storage.conn())
+        .await
+    }
+
+    pub async fn delete(&mut self) -> sqlx::Result<sqlx::postgres::PgQueryResult> {
+        sqlx::query!(
+            r#"
+            DELETE FROM artifact_hashes_fri
+            "#
+        )
+        .execute(self.storage.conn())
+        .await
+    }
+
+    /// Returns all artifacts referenced by witness generator, prover and proof compressor jobs of the batch.
+    pub async fn get_batch_artifacts(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Vec<ProverArtifact> {
+        sqlx::query!(
+            r#"
+            SELECT
+                'witness_inputs' AS "kind!",
+                witness_inputs_blob_url AS "object_key!"
+            FROM
+                witness_inputs_fri
+            WHERE
+                l1_batch_number = $1
+                AND witness_inputs_blob_url IS NOT NULL
+            UNION ALL
+            SELECT
+                'closed_form_inputs',
+                closed_form_inputs_blob_url
+            FROM
+                leaf_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+                AND closed_form_inputs_blob_url IS NOT NULL
+            UNION ALL
+            SELECT
+                'aggregations',
+                aggregations_url
+            FROM
+                node_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+                AND aggregations_url IS NOT NULL
+            UNION ALL
+            SELECT
+                'scheduler_partial_input',
+                scheduler_partial_input_blob_url
+            FROM
+                scheduler_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            UNION ALL
+            SELECT
+                'circuit',
+                circuit_blob_url
+            FROM
+                prover_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            UNION ALL
+            SELECT
+                'proof',
+                proof_blob_url
+            FROM
+                prover_jobs_fri
+            WHERE
+                l1_batch_number = $1
+                AND proof_blob_url IS NOT NULL
+            UNION ALL
+            SELECT
+                'final_proof',
+                l1_proof_blob_url
+            FROM
+                proof_compression_jobs_fri
+            WHERE
+                l1_batch_number = $1
+                AND l1_proof_blob_url IS NOT NULL
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .expect("failed to get batch artifacts")
+        .into_iter()
+        .map(|row| ProverArtifact {
+            kind: ProverArtifactKind::from_str(&row.kind).expect("unknown artifact kind"),
+            object_key: row.object_key,
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::types::chrono::Utc;
+    use zksync_basic_types::{
+        basic_fri_types::AggregationRound,
+        protocol_version::{L1VerifierConfig, ProtocolSemanticVersion},
+    };
+    use zksync_db_connection::connection_pool::ConnectionPool;
+
+    use super::*;
+    use crate::ProverDal;
+
+    #[tokio::test]
+    async fn managing_artifact_hashes() {
+        let pool = ConnectionPool::<Prover>::prover_test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.fri_artifact_hashes_dal();
+
+        assert_eq!(
+            dal.get_artifact_hash("proofs_fri", "proof_1.bin").await,
+            None
+        );
+        dal.insert_artifact_hash("proofs_fri", "proof_1.bin", &[1; 32])
+            .await;
+        dal.insert_artifact_hash("proofs_fri", "proof_2.bin", &[2; 32])
+            .await;
+        // The same key in another bucket is a different artifact.
+        dal.insert_artifact_hash("prover_jobs_fri", "proof_1.bin", &[3; 32])
+            .await;
+        assert_eq!(
+            dal.get_artifact_hash("proofs_fri", "proof_1.bin").await,
+            Some(vec![1; 32])
+        );
+
+        dal.insert_artifact_hash("proofs_fri", "proof_1.bin", &[4; 32])
+            .await;
+        let keys = ["proof_1.bin".to_owned(), "proof_3.bin".to_owned()];
+        let hashes = dal.get_artifact_hashes("proofs_fri", &keys).await;
+        assert_eq!(
+            hashes,
+            HashMap::from([("proof_1.bin".to_owned(), vec![4; 32])])
+        );
+
+        dal.delete_artifact_hash("proofs_fri", "proof_1.bin").await;
+        assert_eq!(
+            dal.get_artifact_hash("proofs_fri", "proof_1.bin").await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn getting_batch_artifacts() {
+        let pool = ConnectionPool::<Prover>::prover_test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let protocol_version = ProtocolSemanticVersion::default();
+        conn.fri_protocol_versions_dal()
+            .save_prover_protocol_version(protocol_version, L1VerifierConfig::default())
+            .await;
+        for l1_batch_number in [L1BatchNumber(1), L1BatchNumber(2)] {
+            conn.fri_basic_witness_generator_dal()
+                .save_witness_inputs(
+                    l1_batch_number,
+                    &format!("witness_inputs_{l1_batch_number}.cbor"),
+                    protocol_version,
+                    Utc::now(),
+                )
+                .await;
+            conn.fri_prover_jobs_dal()
+                .insert_prover_jobs(
+                    l1_batch_number,
+                    vec![(1, format!("circuit_{l1_batch_number}.bin"))],
+                    AggregationRound::BasicCircuits,
+                    0,
+                    protocol_version,
+                    Utc::now(),
+                )
+                .await;
+        }
+
+        let mut artifacts = conn
+            .fri_artifact_hashes_dal()
+            .get_batch_artifacts(L1BatchNumber(1))
+            .await;
+        artifacts.sort_by(|a, b| a.object_key.cmp(&b.object_key));
+        assert_eq!(
+            artifacts,
+            [
+                ProverArtifact {
+                    kind: ProverArtifactKind::Circuit,
+                    object_key: "circuit_1.bin".to_owned(),
+                },
+                ProverArtifact {
+                    kind: ProverArtifactKind::WitnessInputs,
+                    object_key: "witness_inputs_1.cbor".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn deleting_batch_artifact_hashes() {
+        let pool = ConnectionPool::<Prover>::prover_test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let protocol_version = ProtocolSemanticVersion::default();
+        conn.fri_protocol_versions_dal()
+            .save_prover_protocol_version(protocol_version, L1VerifierConfig::default())
+            .await;
+        for l1_batch_number in [L1BatchNumber(1), L1BatchNumber(2)] {
+            conn.fri_basic_witness_generator_dal()
+                .save_witness_inputs(
+                    l1_batch_number,
+                    &format!("witness_inputs_{l1_batch_number}.cbor"),
+                    protocol_version,
+                    Utc::now(),
+                )
+                .await;
+            let mut dal = conn.fri_artifact_hashes_dal();
+            dal.insert_artifact_hash(
+                "witness_inputs",
+                &format!("witness_inputs_{l1_batch_number}.cbor"),
+                &[1; 32],
+            )
+            .await;
+        }
+        // An object with the same key in another bucket is not an artifact of the batch.
+        conn.fri_artifact_hashes_dal()
+            .insert_artifact_hash("proofs_fri", "witness_inputs_1.cbor", &[2; 32])
+            .await;
+
+        conn.fri_artifact_hashes_dal()
+            .delete_batch_data(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let mut dal = conn.fri_artifact_hashes_dal();
+        assert_eq!(
+            dal.get_artifact_hash("witness_inputs", "witness_inputs_1.cbor")
+                .await,
+            None
+        );
+        assert_eq!(
+            dal.get_artifact_hash("witness_inputs", "witness_inputs_2.cbor")
+                .await,
+            Some(vec![1; 32])
+        );
+        assert_eq!(
+            dal.get_artifact_hash("proofs_fri", "witness_inputs_1.cbor")
+                .await,
+            Some(vec![2; 32])
+        );
+
+        dal.delete().await.unwrap();
+        assert_eq!(
+            dal.get_artifact_hash("witness_inputs", "witness_inputs_2.cbor")
+                .await,
+            None
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Priority classes of L1 batches. Classes are respected when picking witness generator, prover
+//! and proof compressor jobs.
+//!
+//! Classes are copied to the `priority_class` column of job tables by DB triggers, both for existing jobs
+//! and for jobs created later, and jobs are ordered by this column. Jobs for batches that have aged since
+//! their class was copied are raised to the urgent class by [`FriBatchPrioritiesDal::age_priority_classes()`],
+//! which must be called periodically.
+use std::time::Duration;
+
+use zksync_basic_types::{prover_dal::BatchPriorityClass, L1BatchNumber};
+use zksync_db_connection::connection::Connection;
+
+use crate::Prover;
+
+#[derive(Debug)]
+pub struct FriBatchPrioritiesDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Prover>,
+}
+
+impl FriBatchPrioritiesDal<'_, '_> {
+    /// Sets the priority class for the specified batch. The class applies both to already queued jobs
+    /// and to jobs created for the batch later.
+    pub async fn set_batch_priority(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        class: BatchPriorityClass,
+    ) {
+        if class == BatchPriorityClass::Normal {
+            // The normal class is the default one, so there's no need to store it.
+            self.delete_batch_data(l1_batch_number)
+                .await
+                .expect("failed to reset batch priority");
+            return;
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+            batch_priorities_fri (
+                l1_batch_number,
+                priority_class,
+                created_at,
+                updated_at
+            )
+            VALUES
+            ($1, $2, NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+            priority_class = excluded.priority_class,
+            updated_at = NOW()
+            "#,
+            i64::from(l1_batch_number.0),
+            class.to_db(),
+        )
+        .execute(self.storage.conn())
+        .await
+        .expect("failed to set batch priority");
+    }
+
+    pub async fn get_batch_priority(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> BatchPriorityClass {
+        sqlx::query!(
+            r#"
+            SELECT
+                priority_class
+            FROM
+                batch_priorities_fri
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0),
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .expect("failed to get batch priority")
+        .map_or(BatchPriorityClass::Normal, |row| {
+            BatchPriorityClass::from_db(row.priority_class)
+        })
+    }
+
+    /// Returns all batches with a non-default priority class, ordered by the batch number.
+    pub async fn get_prioritized_batches(&mut self) -> Vec<(L1BatchNumber, BatchPriorityClass)> {
+        sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                priority_class
+            FROM
+                batch_priorities_fri
+            ORDER BY
+                l1_batch_number
+            "#
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .expect("failed to get prioritized batches")
+        .into_iter()
+        .map(|row| {
+            (
+                L1BatchNumber(row.l1_batch_number as u32),
+                BatchPriorityClass::from_db(row.priority_class),
+            )
+        })
+        .collect()
+    }
+
+    /// Returns the aging interval. Jobs for batches sealed longer than this interval ago are picked as if the batch
+    /// was urgent, so that batches with the normal or low priority class still progress if urgent batches keep coming.
+    /// Aging is applied when jobs are created or their class changes, and by [`Self::age_priority_classes()`].
+    pub async fn get_aging_interval(&mut self) -> Duration {
+        let secs = sqlx::query_scalar!(
+            r#"
+            SELECT
+                aging_interval_secs
+            FROM
+                batch_priority_settings_fri
+            "#
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .expect("failed to get batch priority aging interval");
+        Duration::from_secs(secs as u64)
+    }
+
+    /// Sets the aging interval (see [`Self::get_aging_interval()`]). The interval is shared by all components
+    /// picking jobs, so that they agree on the job ordering.
+    pub async fn set_aging_interval(&mut self, interval: Duration) {
+        sqlx::query!(
+            r#"
+            UPDATE batch_priority_settings_fri
+            SET
+                aging_interval_secs = $1,
+                updated_at = NOW()
+            "#,
+            interval.as_secs() as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .expect("failed to set batch priority aging interval");
+    }
+
+    /// Raises the priority class of queued jobs for batches sealed longer than the aging interval ago to urgent.
+    /// Returns the number of updated jobs.
+    pub async fn age_priority_classes(&mut self) -> u64 {
+        let aged_jobs = sqlx::query_scalar!(
+            r#"
+            SELECT
+                age_batch_priority_classes() AS "aged_jobs!"
+            "#
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .expect("failed to age batch priority classes");
+        aged_jobs as u64
+    }
+
+    pub async fn delete_batch_data(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<sqlx::postgres::PgQueryResult> {
+        sqlx::query!(
+            r#"
+            DELETE FROM batch_priorities_fri
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .execute(self.storage.conn())
+        .await
+    }
+
+    pub async fn delete(&mut self) -> sqlx::Result<sqlx::postgres::PgQueryResult> {
+        sqlx::query!(
+            r#"
+            DELETE FROM batch_priorities_fri
+            "#
+        )
+        .execute(self.storage.conn())
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::types::chrono::{self, Utc};
+    use zksync_basic_types::protocol_version::{L1VerifierConfig, ProtocolSemanticVersion};
+    use zksync_db_connection::connection_pool::ConnectionPool;
+
+    use super::*;
+    use crate::ProverDal;
+
+    #[tokio::test]
+    async fn managing_batch_priorities() {
+        let pool = ConnectionPool::<Prover>::prover_test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.fri_batch_priorities_dal();
+
+        assert_eq!(
+            dal.get_batch_priority(L1BatchNumber(1)).await,
+            BatchPriorityClass::Normal
+        );
+        dal.set_batch_priority(L1BatchNumber(1), BatchPriorityClass::Urgent)
+            .await;
+        dal.set_batch_priority(L1BatchNumber(2), BatchPriorityClass::Low)
+            .await;
+        assert_eq!(
+            dal.get_batch_priority(L1BatchNumber(1)).await,
+            BatchPriorityClass::Urgent
+        );
+        assert_eq!(
+            dal.get_prioritized_batches().await,
+            [
+                (L1BatchNumber(1), BatchPriorityClass::Urgent),
+                (L1BatchNumber(2), BatchPriorityClass::Low),
+            ]
+        );
+
+        dal.set_batch_priority(L1BatchNumber(1), BatchPriorityClass::Normal)
+            .await;
+        assert_eq!(
+            dal.get_batch_priority(L1BatchNumber(1)).await,
+            BatchPriorityClass::Normal
+        );
+        assert_eq!(
+            dal.get_prioritized_batches().await,
+            [(L1BatchNumber(2), BatchPriorityClass::Low)]
+        );
+    }
+
+    #[tokio::test]
+    async fn picking_jobs_respects_batch_priorities() {
+        let pool = ConnectionPool::<Prover>::prover_test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let protocol_version = ProtocolSemanticVersion::default();
+        conn.fri_protocol_versions_dal()
+            .save_prover_protocol_version(protocol_version, L1VerifierConfig::default())
+            .await;
+        assert_eq!(
+            conn.fri_batch_priorities_dal().get_aging_interval().await,
+            Duration::from_secs(2 * 60 * 60)
+        );
+        // The class for batch #4 is set before its job is created; it must be copied to the job.
+        conn.fri_batch_priorities_dal()
+            .set_batch_priority(L1BatchNumber(4), BatchPriorityClass::Urgent)
+            .await;
+
+        let now = Utc::now();
+        let batches = [
+            (L1BatchNumber(1), now - chrono::Duration::minutes(10)),
+            (L1BatchNumber(2), now),
+            // Sealed long ago; must not be starved by the urgent batches.
+            (L1BatchNumber(3), now - chrono::Duration::hours(3)),
+            (L1BatchNumber(4), now - chrono::Duration::minutes(1)),
+        ];
+        for (number, sealed_at) in batches {
+            conn.fri_basic_witness_generator_dal()
+                .save_witness_inputs(number, "", protocol_version, sealed_at)
+                .await;
+        }
+        // Classes for batches #2 and #3 are set after their jobs are created; they must be propagated to the jobs.
+        conn.fri_batch_priorities_dal()
+            .set_batch_priority(L1BatchNumber(2), BatchPriorityClass::Urgent)
+            .await;
+        conn.fri_batch_priorities_dal()
+            .set_batch_priority(L1BatchNumber(3), BatchPriorityClass::Low)
+            .await;
+
+        // Pick jobs in a transaction, so that they can be picked again after changing the aging interval.
+        let mut transaction = conn.start_transaction().await.unwrap();
+        let picked_batches = pick_all_basic_jobs(&mut transaction, protocol_version).await;
+        assert_eq!(
+            picked_batches,
+            [
+                L1BatchNumber(3),
+                L1BatchNumber(4),
+                L1BatchNumber(2),
+                L1BatchNumber(1)
+            ]
+        );
+        transaction.rollback().await.unwrap();
+
+        // With a shorter aging interval, batch #1 is treated as urgent as well once classes are aged.
+        conn.fri_batch_priorities_dal()
+            .set_aging_interval(Duration::from_secs(5 * 60))
+            .await;
+        assert_eq!(
+            conn.fri_batch_priorities_dal().get_aging_interval().await,
+            Duration::from_secs(5 * 60)
+        );
+        let aged_jobs = conn.fri_batch_priorities_dal().age_priority_classes().await;
+        assert_eq!(aged_jobs, 1);
+        // Aged jobs are not updated again.
+        let aged_jobs = conn.fri_batch_priorities_dal().age_priority_classes().await;
+        assert_eq!(aged_jobs, 0);
+        let picked_batches = pick_all_basic_jobs(&mut conn, protocol_version).await;
+        assert_eq!(
+            picked_batches,
+            [
+                L1BatchNumber(3),
+                L1BatchNumber(1),
+                L1BatchNumber(4),
+                L1BatchNumber(2)
+            ]
+        );
+    }
+
+    async fn pick_all_basic_jobs(
+        conn: &mut Connection<'_, Prover>,
+        protocol_version: ProtocolSemanticVersion,
+    ) -> Vec<L1BatchNumber> {
+        let mut picked_batches = vec![];
+        while let Some(number) = conn
+            .fri_basic_witness_generator_dal()
+            .get_next_basic_circuit_witness_job(protocol_version, "test")
+            .await
+        {
+            picked_batches.push(number);
+        }
+        picked_batches
+    }
+}
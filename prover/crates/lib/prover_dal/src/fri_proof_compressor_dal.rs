@@ -5,7 +5,8 @@ use sqlx::types::chrono::{DateTime, Utc};
 use zksync_basic_types::{
     protocol_version::{ProtocolSemanticVersion, ProtocolVersionId, VersionPatch},
     prover_dal::{
-        JobCountStatistics, ProofCompressionJobInfo, ProofCompressionJobStatus, StuckJobs,
+        ExtendedJobCountStatistics, JobCountStatistics, ProofCompressionJobInfo,
+        ProofCompressionJobStatus, StuckJobs,
     },
     L1BatchNumber,
 };
@@ -80,6 +81,7 @@ impl FriProofCompressorDal<'_, '_> {
                         AND protocol_version = $4
                         AND protocol_version_patch = $5
                     ORDER BY
+                        priority_class DESC,
                         priority DESC,
                         batch_sealed_at ASC
                     LIMIT
@@ -94,7 +96,7 @@ impl FriProofCompressorDal<'_, '_> {
             ProofCompressionJobStatus::Queued.to_string(),
             picked_by,
             protocol_version.minor as i32,
-            protocol_version.patch.0 as i32
+            protocol_version.patch.0 as i32,
         )
         .fetch_optional(self.storage.conn())
         .await
@@ -284,6 +286,47 @@ impl FriProofCompressorDal<'_, '_> {
         .collect()
     }
 
+    /// Returns compression job counts. Unlike other counts, `successful` only includes jobs that have completed
+    /// within the `window` preceding the current database time; jobs with proofs already sent to the server are counted
+    /// as successful.
+    pub async fn get_job_counts(&mut self, window: Duration) -> ExtendedJobCountStatistics {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) FILTER (
+                    WHERE
+                    status = 'queued'
+                ) AS "queued!",
+                COUNT(*) FILTER (
+                    WHERE
+                    status = 'in_progress'
+                ) AS "in_progress!",
+                COUNT(*) FILTER (
+                    WHERE
+                    status = 'failed'
+                ) AS "failed!",
+                COUNT(*) FILTER (
+                    WHERE
+                    status IN ('successful', 'sent_to_server')
+                    AND updated_at > NOW() - $1::INTERVAL
+                ) AS "successful!"
+            FROM
+                proof_compression_jobs_fri
+            "#,
+            &pg_interval_from_duration(window),
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .unwrap();
+
+        ExtendedJobCountStatistics {
+            queued: row.queued as usize,
+            in_progress: row.in_progress as usize,
+            failed: row.failed as usize,
+            successful: row.successful as usize,
+        }
+    }
+
     pub async fn get_oldest_not_compressed_batch(&mut self) -> Option<L1BatchNumber> {
         let result: Option<L1BatchNumber> = sqlx::query!(
             r#"
@@ -16,7 +16,8 @@ use zksync_basic_types::{
     },
     protocol_version::{ProtocolSemanticVersion, ProtocolVersionId, VersionPatch},
     prover_dal::{
-        FriProverJobMetadata, JobCountStatistics, ProverJobFriInfo, ProverJobStatus, StuckJobs,
+        ExtendedJobCountStatistics, FriProverJobMetadata, JobCountStatistics, ProverJobFriInfo,
+        ProverJobStatus, StuckJobs,
     },
     L1BatchNumber,
 };
@@ -145,6 +146,8 @@ impl FriProverDal<'_, '_> {
     /// Prover jobs must be thought of as ordered.
     /// Prover must prioritize proving such jobs that will make the chain move forward the fastest.
     /// Current ordering:
+    /// - pick the batch with the highest priority class (batches waiting for longer than the aging interval
+    ///   are raised to the urgent class by [`FriBatchPrioritiesDal::age_priority_classes()`])
     /// - pick the lowest batch
     /// - within the lowest batch, look at the lowest aggregation level (move up the proof tree)
     /// - pick the same type of circuit for as long as possible, this maximizes GPU cache reuse
@@ -153,6 +156,8 @@ impl FriProverDal<'_, '_> {
     /// The 2 differ in the type of jobs they will load. Some Basic jobs are heavy in resource utilization.
     ///
     /// NOTE: This function retrieves only HEAVY_BASIC_CIRCUIT_IDS jobs.
+    ///
+    /// [`FriBatchPrioritiesDal::age_priority_classes()`]: crate::fri_batch_priorities_dal::FriBatchPrioritiesDal::age_priority_classes
     pub async fn get_heavy_job(
         &mut self,
         protocol_version: ProtocolSemanticVersion,
@@ -180,6 +185,7 @@ impl FriProverDal<'_, '_> {
                         AND aggregation_round = $4
                         AND circuit_id = ANY($5)
                     ORDER BY
+                        priority_class DESC,
                         priority DESC,
                         batch_sealed_at ASC,
                         circuit_id ASC,
@@ -227,6 +233,8 @@ impl FriProverDal<'_, '_> {
     /// Prover jobs must be thought of as ordered.
     /// Prover must prioritize proving such jobs that will make the chain move forward the fastest.
     /// Current ordering:
+    /// - pick the batch with the highest priority class (batches waiting for longer than the aging interval
+    ///   are raised to the urgent class by [`FriBatchPrioritiesDal::age_priority_classes()`])
     /// - pick the lowest batch
     /// - within the lowest batch, look at the lowest aggregation level (move up the proof tree)
     /// - pick the same type of circuit for as long as possible, this maximizes GPU cache reuse
@@ -234,6 +242,8 @@ impl FriProverDal<'_, '_> {
     /// Most of this function is similar to `get_heavy_job()`.
     ///
     /// NOTE: This function retrieves all job but HEAVY_BASIC_CIRCUIT_IDS.
+    ///
+    /// [`FriBatchPrioritiesDal::age_priority_classes()`]: crate::fri_batch_priorities_dal::FriBatchPrioritiesDal::age_priority_classes
     pub async fn get_light_job(
         &mut self,
         protocol_version: ProtocolSemanticVersion,
@@ -260,6 +270,7 @@ impl FriProverDal<'_, '_> {
                         AND protocol_version_patch = $2
                         AND NOT (aggregation_round = $4 AND circuit_id = ANY($5))
                     ORDER BY
+                        priority_class DESC,
                         priority DESC,
                         batch_sealed_at ASC,
                         aggregation_round ASC,
@@ -538,6 +549,72 @@ impl FriProverDal<'_, '_> {
         }
     }
 
+    /// Returns job counts grouped by the aggregation round and circuit ID, ordered by these fields.
+    /// Unlike other counts, `successful` only includes jobs that have completed within the `window` preceding
+    /// the current database time.
+    pub async fn get_circuit_job_counts(
+        &mut self,
+        window: Duration,
+    ) -> Vec<(AggregationRound, u8, ExtendedJobCountStatistics)> {
+        sqlx::query!(
+            r#"
+            SELECT
+                aggregation_round,
+                circuit_id,
+                COUNT(*) FILTER (
+                    WHERE
+                    status = 'queued'
+                ) AS "queued!",
+                COUNT(*) FILTER (
+                    WHERE
+                    status = 'in_progress'
+                ) AS "in_progress!",
+                COUNT(*) FILTER (
+                    WHERE
+                    status = 'failed'
+                ) AS "failed!",
+                COUNT(*) FILTER (
+                    WHERE
+                    status = 'successful'
+                    AND updated_at > NOW() - $1::INTERVAL
+                ) AS "successful!"
+            FROM
+                prover_jobs_fri
+            WHERE
+                status IN ('queued', 'in_progress', 'failed')
+                OR (
+                    status = 'successful'
+                    AND updated_at > NOW() - $1::INTERVAL
+                )
+            GROUP BY
+                aggregation_round,
+                circuit_id
+            ORDER BY
+                aggregation_round,
+                circuit_id
+            "#,
+            &pg_interval_from_duration(window),
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| {
+            let counts = ExtendedJobCountStatistics {
+                queued: row.queued as usize,
+                in_progress: row.in_progress as usize,
+                failed: row.failed as usize,
+                successful: row.successful as usize,
+            };
+            (
+                AggregationRound::try_from(i32::from(row.aggregation_round)).unwrap(),
+                row.circuit_id as u8,
+                counts,
+            )
+        })
+        .collect()
+    }
+
     pub async fn get_generic_prover_jobs_stats(
         &mut self,
     ) -> HashMap<ProtocolSemanticVersion, JobCountStatistics> {
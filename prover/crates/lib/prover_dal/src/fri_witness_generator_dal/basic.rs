@@ -105,6 +105,7 @@ impl FriBasicWitnessGeneratorDal<'_, '_> {
                         AND protocol_version = $1
                         AND protocol_version_patch = $3
                     ORDER BY
+                        priority_class DESC,
                         priority DESC,
                         batch_sealed_at ASC
                     LIMIT
@@ -66,6 +66,7 @@ impl FriLeafWitnessGeneratorDal<'_, '_> {
                         AND protocol_version = $1
                         AND protocol_version_patch = $2
                     ORDER BY
+                        priority_class DESC,
                         priority DESC,
                         batch_sealed_at ASC
                     LIMIT
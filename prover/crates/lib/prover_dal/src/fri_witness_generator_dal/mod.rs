@@ -6,16 +6,22 @@ pub mod node;
 pub mod recursion_tip;
 pub mod scheduler;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use sqlx::{types::chrono::NaiveDateTime, Row};
 use zksync_basic_types::{
     basic_fri_types::AggregationRound,
     protocol_version::{ProtocolSemanticVersion, ProtocolVersionId, VersionPatch},
-    prover_dal::{JobCountStatistics, ProofGenerationTime, StuckJobs},
+    prover_dal::{
+        BatchStageTimings, ExtendedJobCountStatistics, JobCountStatistics, ProofGenerationTime,
+        StuckJobs,
+    },
     L1BatchNumber,
 };
-use zksync_db_connection::{connection::Connection, utils::naive_time_from_pg_interval};
+use zksync_db_connection::{
+    connection::Connection,
+    utils::{naive_time_from_pg_interval, pg_interval_from_duration},
+};
 
 use crate::Prover;
 
@@ -159,6 +165,38 @@ impl FriWitnessGeneratorDal<'_, '_> {
             .collect()
     }
 
+    /// Returns job counts for the specified aggregation round. Unlike other counts, `successful` only includes jobs
+    /// that have completed within the `window` preceding the current database time.
+    pub async fn get_witness_job_counts(
+        &mut self,
+        aggregation_round: AggregationRound,
+        window: Duration,
+    ) -> ExtendedJobCountStatistics {
+        let sql = format!(
+            r#"
+                SELECT
+                    COUNT(*) FILTER (WHERE status = 'queued') as queued,
+                    COUNT(*) FILTER (WHERE status = 'in_progress') as in_progress,
+                    COUNT(*) FILTER (WHERE status = 'failed') as failed,
+                    COUNT(*) FILTER (WHERE status = 'successful' AND updated_at > NOW() - $1::INTERVAL) as successful
+                FROM
+                    {}
+                "#,
+            table_for_round(aggregation_round),
+        );
+        let row = sqlx::query(&sql)
+            .bind(pg_interval_from_duration(window))
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap();
+        ExtendedJobCountStatistics {
+            queued: row.get::<i64, &str>("queued") as usize,
+            in_progress: row.get::<i64, &str>("in_progress") as usize,
+            failed: row.get::<i64, &str>("failed") as usize,
+            successful: row.get::<i64, &str>("successful") as usize,
+        }
+    }
+
     pub async fn delete_witness_generator_data_for_batch(
         &mut self,
         block_number: L1BatchNumber,
@@ -341,4 +379,145 @@ impl FriWitnessGeneratorDal<'_, '_> {
         .collect();
         Ok(proof_generation_times)
     }
+
+    /// Returns progress of batches through the proving pipeline. Returned batches are ones with witness inputs saved
+    /// within the `window` preceding the current database time, and all batches that are not fully proven yet.
+    /// At most `limit` latest batches are returned, ordered by the batch number.
+    pub async fn get_batch_stage_timings(
+        &mut self,
+        window: Duration,
+        limit: usize,
+    ) -> Vec<BatchStageTimings> {
+        let mut timings: Vec<_> = sqlx::query!(
+            r#"
+            WITH
+            stages AS (
+                SELECT
+                    wit.l1_batch_number,
+                    wit.created_at,
+                    CASE
+                        WHEN wit.status = 'successful' THEN wit.updated_at
+                    END AS basic_completed_at,
+                    leaf.completed_at AS leaf_completed_at,
+                    node.completed_at AS node_completed_at,
+                    CASE
+                        WHEN rt.status = 'successful' THEN rt.updated_at
+                    END AS recursion_tip_completed_at,
+                    CASE
+                        WHEN sch.status = 'successful' THEN sch.updated_at
+                    END AS scheduler_completed_at,
+                    CASE
+                        WHEN comp.status IN ('successful', 'sent_to_server') THEN comp.updated_at
+                    END AS compression_completed_at
+                FROM
+                    witness_inputs_fri AS wit
+                LEFT JOIN (
+                    SELECT
+                        l1_batch_number,
+                        MAX(updated_at) AS completed_at
+                    FROM
+                        leaf_aggregation_witness_jobs_fri
+                    GROUP BY
+                        l1_batch_number
+                    HAVING
+                        BOOL_AND(status = 'successful')
+                ) AS leaf ON wit.l1_batch_number = leaf.l1_batch_number
+                LEFT JOIN (
+                    SELECT
+                        l1_batch_number,
+                        MAX(updated_at) AS completed_at
+                    FROM
+                        node_aggregation_witness_jobs_fri
+                    GROUP BY
+                        l1_batch_number
+                    HAVING
+                        BOOL_AND(status = 'successful')
+                ) AS node ON wit.l1_batch_number = node.l1_batch_number
+                LEFT JOIN recursion_tip_witness_jobs_fri AS rt
+                    ON wit.l1_batch_number = rt.l1_batch_number
+                LEFT JOIN scheduler_witness_jobs_fri AS sch
+                    ON wit.l1_batch_number = sch.l1_batch_number
+                LEFT JOIN proof_compression_jobs_fri AS comp
+                    ON wit.l1_batch_number = comp.l1_batch_number
+                WHERE
+                    wit.created_at > NOW() - $1::INTERVAL
+                    OR (
+                        wit.status != 'skipped'
+                        AND (
+                            comp.status IS NULL
+                            OR comp.status NOT IN ('successful', 'sent_to_server', 'skipped')
+                        )
+                    )
+            )
+            
+            SELECT
+                l1_batch_number,
+                EXTRACT(
+                    EPOCH
+                    FROM
+                    NOW() - created_at
+                )::DOUBLE PRECISION AS "age!",
+                EXTRACT(
+                    EPOCH
+                    FROM
+                    basic_completed_at - created_at
+                )::DOUBLE PRECISION AS "basic?",
+                EXTRACT(
+                    EPOCH
+                    FROM
+                    leaf_completed_at - created_at
+                )::DOUBLE PRECISION AS "leaf?",
+                EXTRACT(
+                    EPOCH
+                    FROM
+                    node_completed_at - created_at
+                )::DOUBLE PRECISION AS "node?",
+                EXTRACT(
+                    EPOCH
+                    FROM
+                    recursion_tip_completed_at - created_at
+                )::DOUBLE PRECISION AS "recursion_tip?",
+                EXTRACT(
+                    EPOCH
+                    FROM
+                    scheduler_completed_at - created_at
+                )::DOUBLE PRECISION AS "scheduler?",
+                EXTRACT(
+                    EPOCH
+                    FROM
+                    compression_completed_at - created_at
+                )::DOUBLE PRECISION AS "compression?"
+            FROM
+                stages
+            ORDER BY
+                l1_batch_number DESC
+            LIMIT
+                $2
+            "#,
+            &pg_interval_from_duration(window),
+            limit as i64,
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| {
+            let to_duration = |secs: f64| Duration::from_secs_f64(secs.max(0.0));
+            BatchStageTimings {
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                age: to_duration(row.age),
+                witness_generation: [
+                    row.basic.map(to_duration),
+                    row.leaf.map(to_duration),
+                    row.node.map(to_duration),
+                    row.recursion_tip.map(to_duration),
+                    row.scheduler.map(to_duration),
+                ],
+                compression: row.compression.map(to_duration),
+            }
+        })
+        .collect();
+        timings.reverse();
+        timings
+    }
 }
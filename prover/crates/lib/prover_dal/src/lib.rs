@@ -7,6 +7,8 @@ pub use zksync_db_connection::{
 
 use crate::{
     cli_test_dal::CliTestDal,
+    fri_artifact_hashes_dal::FriArtifactHashesDal,
+    fri_batch_priorities_dal::FriBatchPrioritiesDal,
     fri_proof_compressor_dal::FriProofCompressorDal,
     fri_protocol_versions_dal::FriProtocolVersionsDal,
     fri_prover_dal::FriProverDal,
@@ -18,6 +20,8 @@ use crate::{
 };
 
 pub mod cli_test_dal;
+pub mod fri_artifact_hashes_dal;
+pub mod fri_batch_priorities_dal;
 pub mod fri_proof_compressor_dal;
 pub mod fri_protocol_versions_dal;
 pub mod fri_prover_dal;
@@ -55,6 +59,10 @@ where
     fn fri_protocol_versions_dal(&mut self) -> FriProtocolVersionsDal<'_, 'a>;
 
     fn fri_proof_compressor_dal(&mut self) -> FriProofCompressorDal<'_, 'a>;
+
+    fn fri_batch_priorities_dal(&mut self) -> FriBatchPrioritiesDal<'_, 'a>;
+
+    fn fri_artifact_hashes_dal(&mut self) -> FriArtifactHashesDal<'_, 'a>;
 }
 
 #[derive(Clone, Debug)]
@@ -106,4 +114,12 @@ impl<'a> ProverDal<'a> for Connection<'a, Prover> {
     fn fri_proof_compressor_dal(&mut self) -> FriProofCompressorDal<'_, 'a> {
         FriProofCompressorDal { storage: self }
     }
+
+    fn fri_batch_priorities_dal(&mut self) -> FriBatchPrioritiesDal<'_, 'a> {
+        FriBatchPrioritiesDal { storage: self }
+    }
+
+    fn fri_artifact_hashes_dal(&mut self) -> FriArtifactHashesDal<'_, 'a> {
+        FriArtifactHashesDal { storage: self }
+    }
 }
@@ -0,0 +1,189 @@
+//! Integrity checks for artifacts passed between prover components via the object store.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError};
+use zksync_prover_dal::{Connection, ConnectionPool, Prover, ProverDal};
+use zksync_types::{prover_dal::ProverArtifactKind, web3::keccak256};
+
+use crate::metrics::ARTIFACT_INTEGRITY_METRICS;
+
+/// Computes the content hash of a serialized artifact.
+pub fn content_hash(bytes: &[u8]) -> [u8; 32] {
+    keccak256(bytes)
+}
+
+/// Returns the object store bucket the artifact of the specified kind is stored in.
+pub fn artifact_bucket(kind: ProverArtifactKind) -> Bucket {
+    match kind {
+        ProverArtifactKind::WitnessInputs => Bucket::WitnessInput,
+        ProverArtifactKind::ClosedFormInputs => Bucket::LeafAggregationWitnessJobsFri,
+        ProverArtifactKind::Aggregations => Bucket::NodeAggregationWitnessJobsFri,
+        ProverArtifactKind::SchedulerPartialInput => Bucket::SchedulerWitnessJobsFri,
+        ProverArtifactKind::Circuit => Bucket::ProverJobsFri,
+        ProverArtifactKind::Proof | ProverArtifactKind::FinalProof => Bucket::ProofsFri,
+    }
+}
+
+/// Object store recording content hashes of written objects in the prover DB and checking them on reads.
+///
+/// Objects without a recorded hash (e.g., written before hashes were introduced) are returned as is.
+/// If the hash of a read object doesn't match the recorded one, a non-retriable error is returned.
+#[derive(Debug)]
+pub struct IntegrityCheckedObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    pool: ConnectionPool<Prover>,
+}
+
+impl IntegrityCheckedObjectStore {
+    pub fn new(inner: Arc<dyn ObjectStore>, pool: ConnectionPool<Prover>) -> Self {
+        Self { inner, pool }
+    }
+
+    async fn connection(&self) -> Result<Connection<'static, Prover>, ObjectStoreError> {
+        self.pool
+            .connection()
+            .await
+            .map_err(|err| ObjectStoreError::Other {
+                source: anyhow::Error::from(err).into(),
+                is_retriable: true,
+            })
+    }
+}
+
+#[async_trait]
+impl ObjectStore for IntegrityCheckedObjectStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let value = self.inner.get_raw(bucket, key).await?;
+        let expected_hash = self
+            .connection()
+            .await?
+            .fri_artifact_hashes_dal()
+            .get_artifact_hash(&bucket.to_string(), key)
+            .await;
+        let Some(expected_hash) = expected_hash else {
+            tracing::debug!("No content hash recorded for {bucket}/{key}, skipping the check");
+            return Ok(value);
+        };
+
+        let actual_hash = content_hash(&value);
+        if expected_hash != actual_hash {
+            ARTIFACT_INTEGRITY_METRICS.hash_mismatches[&bucket.to_string()].inc();
+            let message = format!(
+                "content hash mismatch for {bucket}/{key}: expected 0x{}, got 0x{}",
+                hex::encode(expected_hash),
+                hex::encode(actual_hash)
+            );
+            tracing::error!("{message}");
+            return Err(ObjectStoreError::Other {
+                source: message.into(),
+                is_retriable: false,
+            });
+        }
+        Ok(value)
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let hash = content_hash(&value);
+        self.inner.put_raw(bucket, key, value).await?;
+        // The hash is recorded only after the object is stored, so that a failed write doesn't leave a hash
+        // not matching the previously stored object.
+        self.connection()
+            .await?
+            .fri_artifact_hashes_dal()
+            .insert_artifact_hash(&bucket.to_string(), key, &hash)
+            .await;
+        Ok(())
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.inner.remove_raw(bucket, key).await?;
+        self.connection()
+            .await?
+            .fri_artifact_hashes_dal()
+            .delete_artifact_hash(&bucket.to_string(), key)
+            .await;
+        Ok(())
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_object_store::MockObjectStore;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn integrity_checked_store_detects_corrupted_objects() {
+        let pool = ConnectionPool::<Prover>::prover_test_pool().await;
+        let inner = MockObjectStore::arc();
+        let store = IntegrityCheckedObjectStore::new(inner.clone(), pool.clone());
+
+        store
+            .put_raw(Bucket::ProofsFri, "proof_1.bin", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let hash = pool
+            .connection()
+            .await
+            .unwrap()
+            .fri_artifact_hashes_dal()
+            .get_artifact_hash("proofs_fri", "proof_1.bin")
+            .await;
+        assert_eq!(hash, Some(content_hash(&[1, 2, 3]).to_vec()));
+        assert_eq!(
+            store
+                .get_raw(Bucket::ProofsFri, "proof_1.bin")
+                .await
+                .unwrap(),
+            [1, 2, 3]
+        );
+
+        // Objects without recorded hashes are not checked.
+        inner
+            .put_raw(Bucket::ProofsFri, "proof_2.bin", vec![4])
+            .await
+            .unwrap();
+        assert_eq!(
+            store
+                .get_raw(Bucket::ProofsFri, "proof_2.bin")
+                .await
+                .unwrap(),
+            [4]
+        );
+
+        inner
+            .put_raw(Bucket::ProofsFri, "proof_1.bin", vec![1, 2, 4])
+            .await
+            .unwrap();
+        let err = store
+            .get_raw(Bucket::ProofsFri, "proof_1.bin")
+            .await
+            .unwrap_err();
+        assert!(!err.is_retriable());
+        assert!(err.to_string().contains("content hash mismatch"), "{err}");
+
+        store
+            .remove_raw(Bucket::ProofsFri, "proof_1.bin")
+            .await
+            .unwrap();
+        let hash = pool
+            .connection()
+            .await
+            .unwrap()
+            .fri_artifact_hashes_dal()
+            .get_artifact_hash("proofs_fri", "proof_1.bin")
+            .await;
+        assert_eq!(hash, None);
+    }
+}
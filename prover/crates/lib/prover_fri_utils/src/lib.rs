@@ -3,6 +3,7 @@ use zksync_prover_fri_types::circuit_definitions::{
     zkevm_circuits::scheduler::aux::BaseLayerCircuitType,
 };
 
+pub mod artifact_integrity;
 pub mod metrics;
 
 // TODO: This should be moved into harness, once done the entire module can be removed.
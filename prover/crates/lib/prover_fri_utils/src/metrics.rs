@@ -1,4 +1,4 @@
-use vise::{EncodeLabelSet, EncodeLabelValue};
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, LabeledFamily, Metrics};
 use zksync_types::basic_fri_types::AggregationRound;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
@@ -16,3 +16,15 @@ impl std::fmt::Display for StageLabel {
         self.0.fmt(formatter)
     }
 }
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "prover_artifact_integrity")]
+pub(crate) struct ArtifactIntegrityMetrics {
+    /// Number of read artifacts with a content hash not matching the recorded one.
+    #[metrics(labels = ["bucket"])]
+    pub hash_mismatches: LabeledFamily<String, Counter>,
+}
+
+#[vise::register]
+pub(crate) static ARTIFACT_INTEGRITY_METRICS: vise::Global<ArtifactIntegrityMetrics> =
+    vise::Global::new();